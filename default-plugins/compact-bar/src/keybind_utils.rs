@@ -716,6 +716,14 @@ impl KeybindProcessor {
                             }
                         )
                     },
+                    |action: &Action| {
+                        matches!(
+                            action,
+                            Action::SearchToggleOption {
+                                option: actions::SearchOption::Regex
+                            }
+                        )
+                    },
                 ];
                 Self::find_predetermined_actions(mode_info, mode, ordered_predicates)
             },
@@ -733,7 +741,28 @@ impl KeybindProcessor {
             | InputMode::RenameTab
             | InputMode::RenamePane
             | InputMode::Prompt
-            | InputMode::Tmux => Vec::new(),
+            | InputMode::Tmux
+            | InputMode::Copy => Vec::new(),
+            InputMode::Custom(_) => {
+                // custom modes have no predetermined actions, so we show all of their bindings
+                let mut keybinds = mode_info.get_keybinds_for_mode(mode);
+                keybinds.sort_by_key(|(key, _actions)| format!("{}", key));
+                let mut action_types: Vec<ActionType> = vec![];
+                for (_key, actions) in &keybinds {
+                    if let Some(action_type) = actions.first().map(ActionType::from_action) {
+                        if !action_types.contains(&action_type) {
+                            action_types.push(action_type);
+                        }
+                    }
+                }
+                let ordered_predicates = action_types
+                    .into_iter()
+                    .map(|action_type| {
+                        move |action: &Action| ActionType::from_action(action) == action_type
+                    })
+                    .collect();
+                Self::find_predetermined_actions(mode_info, mode, ordered_predicates)
+            },
         }
     }
 }
@@ -104,6 +104,7 @@ keybinds clear-defaults=true {{
         bind "c" {{ SearchToggleOption "CaseSensitivity"; }}
         bind "w" {{ SearchToggleOption "Wrap"; }}
         bind "o" {{ SearchToggleOption "WholeWord"; }}
+        bind "r" {{ SearchToggleOption "Regex"; }}
     }}
     entersearch {{
         bind "Ctrl c" "Esc" {{ SwitchToMode "Scroll"; }}
@@ -326,6 +327,7 @@ keybinds clear-defaults=true {{
         bind "c" {{ SearchToggleOption "CaseSensitivity"; }}
         bind "w" {{ SearchToggleOption "Wrap"; }}
         bind "o" {{ SearchToggleOption "WholeWord"; }}
+        bind "r" {{ SearchToggleOption "Regex"; }}
     }}
     entersearch {{
         bind "Ctrl c" "Esc" {{ SwitchToMode "Scroll"; }}
@@ -551,6 +553,7 @@ keybinds clear-defaults=true {{
         bind "c" {{ SearchToggleOption "CaseSensitivity"; }}
         bind "w" {{ SearchToggleOption "Wrap"; }}
         bind "o" {{ SearchToggleOption "WholeWord"; }}
+        bind "r" {{ SearchToggleOption "Regex"; }}
     }}
     entersearch {{
         bind "Ctrl c" "Esc" {{ SwitchToMode "Scroll"; }}
@@ -753,6 +756,7 @@ keybinds clear-defaults=true {{
         bind "c" {{ SearchToggleOption "CaseSensitivity"; }}
         bind "w" {{ SearchToggleOption "Wrap"; }}
         bind "o" {{ SearchToggleOption "WholeWord"; }}
+        bind "r" {{ SearchToggleOption "Regex"; }}
     }}
     entersearch {{
         bind "Ctrl c" "Esc" {{ SwitchToMode "Scroll"; }}
@@ -959,6 +963,7 @@ keybinds clear-defaults=true {{
         bind "c" {{ SearchToggleOption "CaseSensitivity"; }}
         bind "w" {{ SearchToggleOption "Wrap"; }}
         bind "o" {{ SearchToggleOption "WholeWord"; }}
+        bind "r" {{ SearchToggleOption "Regex"; }}
     }}
     entersearch {{
         bind "Ctrl c" "Esc" {{ SwitchToMode "Scroll"; }}
@@ -1156,6 +1161,7 @@ keybinds clear-defaults=true {{
         bind "c" {{ SearchToggleOption "CaseSensitivity"; }}
         bind "w" {{ SearchToggleOption "Wrap"; }}
         bind "o" {{ SearchToggleOption "WholeWord"; }}
+        bind "r" {{ SearchToggleOption "Regex"; }}
     }}
     entersearch {{
         bind "Ctrl c" "Esc" {{ SwitchToMode "Scroll"; }}
@@ -5,6 +5,8 @@ use crate::ui_components::{back_to_presets, info_line};
 
 use crate::{POSSIBLE_MODIFIERS, WIDTH_BREAKPOINTS};
 
+const MAX_MAIN_LEADER_KEYS: usize = 4;
+
 #[derive(Debug)]
 pub struct RebindLeadersScreen {
     selected_primary_key_index: usize,
@@ -13,7 +15,8 @@ pub struct RebindLeadersScreen {
     rebinding_main_leader: bool,
     browsing_primary_modifier: bool,
     browsing_secondary_modifier: bool,
-    main_leader: Option<KeyWithModifier>,
+    main_leader: Vec<KeyWithModifier>, // more than one key is a multi-key sequence
+    recorded_main_leader: Vec<KeyWithModifier>,
     primary_modifier: BTreeSet<KeyModifier>,
     secondary_modifier: BTreeSet<KeyModifier>,
     latest_mode_info: Option<ModeInfo>,
@@ -35,7 +38,8 @@ impl Default for RebindLeadersScreen {
             rebinding_main_leader: false,
             browsing_primary_modifier: false,
             browsing_secondary_modifier: false,
-            main_leader: None,
+            main_leader: vec![],
+            recorded_main_leader: vec![],
             primary_modifier,
             secondary_modifier,
             latest_mode_info: None,
@@ -74,29 +78,14 @@ impl RebindLeadersScreen {
         )
     }
     fn set_main_leader_from_keybindings(&mut self) {
-        self.main_leader = self.latest_mode_info.as_ref().and_then(|mode_info| {
-            mode_info
-                .keybinds
-                .iter()
-                .find_map(|m| {
-                    if m.0 == InputMode::Locked {
-                        Some(m.1.clone())
-                    } else {
-                        None
-                    }
-                })
-                .and_then(|k| {
-                    k.into_iter().find_map(|(k, a)| {
-                        if a == &[actions::Action::SwitchToMode {
-                            input_mode: InputMode::Normal,
-                        }] {
-                            Some(k)
-                        } else {
-                            None
-                        }
-                    })
-                })
-        });
+        let unlock_action = [actions::Action::SwitchToMode {
+            input_mode: InputMode::Normal,
+        }];
+        self.main_leader = self
+            .get_current_keybind(InputMode::Locked, &unlock_action)
+            .map(|k| vec![k])
+            .or_else(|| self.get_current_key_sequence(InputMode::Locked, &unlock_action))
+            .unwrap_or_default();
     }
     fn set_primary_and_secondary_modifiers_from_keybindings(&mut self) {
         let mut primary_modifier = self.latest_mode_info.as_ref().and_then(|mode_info| {
@@ -181,6 +170,7 @@ impl RebindLeadersScreen {
     }
     pub fn set_rebinding_unlock_toggle(&mut self) {
         self.rebinding_main_leader = true;
+        self.recorded_main_leader.clear();
         self.browsing_secondary_modifier = false;
         self.browsing_primary_modifier = false;
         self.main_leader_selected = false;
@@ -453,7 +443,11 @@ impl RebindLeadersScreen {
         let base_y = rows.saturating_sub(10) / 2;
         if let Some(main_leader_key_text) = self.main_leader_text() {
             let main_leader_key_text = if self.rebinding_main_leader {
-                "...".to_owned()
+                self.recorded_main_leader
+                    .iter()
+                    .map(|k| format!("{} ", k))
+                    .chain(std::iter::once("...".to_owned()))
+                    .collect()
             } else {
                 main_leader_key_text
             };
@@ -473,11 +467,13 @@ impl RebindLeadersScreen {
                 let first_bulletin = "[Enter new key] eg.";
                 let second_bulletin = "\"Ctrl g\", \"Alt g\",";
                 let third_bulletin = "\"Alt ESC\", \"Ctrl SPACE\"";
+                let fourth_bulletin = "[More keys, then <ENTER>]";
                 print_nested_list_with_coordinates(
                     vec![
                         NestedListItem::new(first_bulletin).color_range(3, ..=14),
                         NestedListItem::new(second_bulletin),
                         NestedListItem::new(third_bulletin),
+                        NestedListItem::new(fourth_bulletin).color_range(3, 17..=23),
                     ],
                     base_x,
                     base_y + 6,
@@ -488,7 +484,17 @@ impl RebindLeadersScreen {
         }
     }
     fn main_leader_text(&self) -> Option<String> {
-        self.main_leader.as_ref().map(|m| format!("{}", m))
+        if self.main_leader.is_empty() {
+            None
+        } else {
+            Some(
+                self.main_leader
+                    .iter()
+                    .map(|m| format!("{}", m))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        }
     }
     fn render_secondary_modifier_selector(&mut self, rows: usize, cols: usize) {
         let screen_width = if cols >= WIDTH_BREAKPOINTS.0 {
@@ -742,7 +748,7 @@ impl RebindLeadersScreen {
             self.move_selection_for_default_preset(&key);
         } else if self.rebinding_main_leader {
             self.soft_reset_ui_state();
-            self.main_leader = Some(key.clone());
+            self.main_leader = vec![key.clone()];
             self.ui_is_dirty = true;
         }
         should_render
@@ -770,9 +776,16 @@ impl RebindLeadersScreen {
     fn rebind_keys(&mut self, write_to_disk: bool) {
         let mut keys_to_unbind = vec![];
         let mut keys_to_bind = vec![];
+        let mut key_sequences_to_unbind = vec![];
+        let mut key_sequences_to_bind = vec![];
         if self.currently_in_unlock_first() {
-            if let Some(unlock_key) = &self.main_leader {
-                self.bind_unlock_key(&mut keys_to_unbind, &mut keys_to_bind, unlock_key);
+            if !self.main_leader.is_empty() {
+                self.bind_unlock_key(
+                    &mut keys_to_unbind,
+                    &mut keys_to_bind,
+                    &mut key_sequences_to_unbind,
+                    &mut key_sequences_to_bind,
+                );
             }
             self.bind_all_secondary_actions(&mut keys_to_unbind, &mut keys_to_bind);
         } else {
@@ -784,7 +797,13 @@ impl RebindLeadersScreen {
         } else {
             self.notification = Some("Configuration applied to current session.".to_owned());
         }
-        rebind_keys(keys_to_unbind, keys_to_bind, write_to_disk);
+        rebind_keys_and_key_sequences(
+            keys_to_unbind,
+            keys_to_bind,
+            key_sequences_to_unbind,
+            key_sequences_to_bind,
+            write_to_disk,
+        );
     }
     fn bind_all_primary_actions(
         &self,
@@ -1144,87 +1163,53 @@ impl RebindLeadersScreen {
         &self,
         keys_to_unbind: &mut Vec<(InputMode, KeyWithModifier)>,
         keys_to_bind: &mut Vec<(InputMode, KeyWithModifier, Vec<actions::Action>)>,
-        unlock_key: &KeyWithModifier,
+        key_sequences_to_unbind: &mut Vec<(InputMode, Vec<KeyWithModifier>)>,
+        key_sequences_to_bind: &mut Vec<(InputMode, Vec<KeyWithModifier>, Vec<actions::Action>)>,
     ) {
-        if let Some(previous_unlock_key) = self.get_current_keybind(
+        let modes_with_unlock_key = [
             InputMode::Locked,
-            &[actions::Action::SwitchToMode {
-                input_mode: InputMode::Normal,
-            }],
-        ) {
-            keys_to_unbind.push((InputMode::Locked, previous_unlock_key.clone()));
-            keys_to_unbind.push((InputMode::Normal, previous_unlock_key.clone()));
-            keys_to_unbind.push((InputMode::Pane, previous_unlock_key.clone()));
-            keys_to_unbind.push((InputMode::Tab, previous_unlock_key.clone()));
-            keys_to_unbind.push((InputMode::Resize, previous_unlock_key.clone()));
-            keys_to_unbind.push((InputMode::Move, previous_unlock_key.clone()));
-            keys_to_unbind.push((InputMode::Search, previous_unlock_key.clone()));
-            keys_to_unbind.push((InputMode::Scroll, previous_unlock_key.clone()));
-            keys_to_unbind.push((InputMode::Session, previous_unlock_key.clone()));
-        }
-        keys_to_bind.push((
-            InputMode::Locked,
-            unlock_key.clone(),
-            vec![actions::Action::SwitchToMode {
-                input_mode: InputMode::Normal,
-            }],
-        ));
-        keys_to_bind.push((
             InputMode::Normal,
-            unlock_key.clone(),
-            vec![actions::Action::SwitchToMode {
-                input_mode: InputMode::Locked,
-            }],
-        ));
-        keys_to_bind.push((
             InputMode::Pane,
-            unlock_key.clone(),
-            vec![actions::Action::SwitchToMode {
-                input_mode: InputMode::Locked,
-            }],
-        ));
-        keys_to_bind.push((
             InputMode::Tab,
-            unlock_key.clone(),
-            vec![actions::Action::SwitchToMode {
-                input_mode: InputMode::Locked,
-            }],
-        ));
-        keys_to_bind.push((
             InputMode::Resize,
-            unlock_key.clone(),
-            vec![actions::Action::SwitchToMode {
-                input_mode: InputMode::Locked,
-            }],
-        ));
-        keys_to_bind.push((
             InputMode::Move,
-            unlock_key.clone(),
-            vec![actions::Action::SwitchToMode {
-                input_mode: InputMode::Locked,
-            }],
-        ));
-        keys_to_bind.push((
             InputMode::Search,
-            unlock_key.clone(),
-            vec![actions::Action::SwitchToMode {
-                input_mode: InputMode::Locked,
-            }],
-        ));
-        keys_to_bind.push((
             InputMode::Scroll,
-            unlock_key.clone(),
-            vec![actions::Action::SwitchToMode {
-                input_mode: InputMode::Locked,
-            }],
-        ));
-        keys_to_bind.push((
             InputMode::Session,
-            unlock_key.clone(),
-            vec![actions::Action::SwitchToMode {
-                input_mode: InputMode::Locked,
-            }],
-        ));
+        ];
+        let unlock_action = [actions::Action::SwitchToMode {
+            input_mode: InputMode::Normal,
+        }];
+        if let Some(previous_unlock_key) =
+            self.get_current_keybind(InputMode::Locked, &unlock_action)
+        {
+            for mode in modes_with_unlock_key {
+                keys_to_unbind.push((mode, previous_unlock_key.clone()));
+            }
+        }
+        if let Some(previous_unlock_sequence) =
+            self.get_current_key_sequence(InputMode::Locked, &unlock_action)
+        {
+            for mode in modes_with_unlock_key {
+                key_sequences_to_unbind.push((mode, previous_unlock_sequence.clone()));
+            }
+        }
+        for mode in modes_with_unlock_key {
+            let target_mode = if mode == InputMode::Locked {
+                InputMode::Normal
+            } else {
+                InputMode::Locked
+            };
+            let actions = vec![actions::Action::SwitchToMode {
+                input_mode: target_mode,
+            }];
+            match self.main_leader.as_slice() {
+                [unlock_key] => keys_to_bind.push((mode, unlock_key.clone(), actions)),
+                unlock_sequence => {
+                    key_sequences_to_bind.push((mode, unlock_sequence.to_vec(), actions))
+                },
+            }
+        }
     }
     fn get_current_keybind(
         &self,
@@ -1244,6 +1229,17 @@ impl RebindLeadersScreen {
             })
             .cloned()
     }
+    fn get_current_key_sequence(
+        &self,
+        in_mode: InputMode,
+        actions: &[actions::Action],
+    ) -> Option<Vec<KeyWithModifier>> {
+        self.latest_mode_info.as_ref().and_then(|m_i| {
+            m_i.get_key_sequences_for_mode(in_mode)
+                .into_iter()
+                .find_map(|(k, a)| if a == actions { Some(k) } else { None })
+        })
+    }
     fn soft_reset_ui_state(&mut self) {
         let mut latest_mode_info = self.latest_mode_info.take();
         let notification = self.notification.take();
@@ -1282,6 +1278,16 @@ impl RebindLeadersScreen {
             let write_to_disk = true;
             self.rebind_keys(write_to_disk);
             self.hard_reset_ui_state();
+        } else if key.bare_key == BareKey::Enter
+            && key.has_no_modifiers()
+            && self.rebinding_main_leader
+        {
+            let recorded_main_leader = self.recorded_main_leader.drain(..).collect::<Vec<_>>();
+            self.soft_reset_ui_state();
+            if !recorded_main_leader.is_empty() {
+                self.main_leader = recorded_main_leader;
+                self.ui_is_dirty = true;
+            }
         } else if key.bare_key == BareKey::Enter && key.has_no_modifiers() {
             let write_to_disk = false;
             self.rebind_keys(write_to_disk);
@@ -1309,9 +1315,14 @@ impl RebindLeadersScreen {
         {
             self.move_selection_for_unlock_first(&key);
         } else if self.rebinding_main_leader {
-            self.soft_reset_ui_state();
-            self.main_leader = Some(key.clone());
-            self.ui_is_dirty = true;
+            // keys are recorded until <ENTER> so that the toggle can be a multi-key sequence
+            self.recorded_main_leader.push(key.clone());
+            if self.recorded_main_leader.len() >= MAX_MAIN_LEADER_KEYS {
+                let recorded_main_leader = self.recorded_main_leader.drain(..).collect();
+                self.soft_reset_ui_state();
+                self.main_leader = recorded_main_leader;
+                self.ui_is_dirty = true;
+            }
         }
         true
     }
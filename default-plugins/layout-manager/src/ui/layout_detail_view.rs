@@ -42,13 +42,13 @@ impl<'a> LayoutDetail<'a> {
     fn calculate_content_lines(&self, metadata: &LayoutMetadata, max_cols: usize) -> usize {
         if !self.should_show_tabs_section(metadata) {
             // Panes only
-            let panes_lines = self.prepare_panes_content(metadata, max_cols);
+            let panes_lines = self.prepare_panes_and_imports_content(metadata, max_cols);
             panes_lines.len()
         } else {
             // Tabs and panes side-by-side
             let (left_width, right_width, _) = self.calculate_column_layout(max_cols);
             let tabs_lines = self.prepare_tabs_content(metadata, left_width);
-            let panes_lines = self.prepare_panes_content(metadata, right_width);
+            let panes_lines = self.prepare_panes_and_imports_content(metadata, right_width);
             tabs_lines.len().max(panes_lines.len())
         }
     }
@@ -151,7 +151,7 @@ impl<'a> LayoutDetail<'a> {
         let right_x = x + left_width + padding;
 
         let tabs_lines = self.prepare_tabs_content(metadata, left_width);
-        let panes_lines = self.prepare_panes_content(metadata, right_width);
+        let panes_lines = self.prepare_panes_and_imports_content(metadata, right_width);
 
         let (tabs_truncated, panes_truncated) =
             self.render_side_by_side_columns(&panes_lines, &tabs_lines, x, right_x, y, max_rows);
@@ -191,11 +191,11 @@ impl<'a> LayoutDetail<'a> {
             let current_y = start_y + line_idx;
 
             if let Some(line) = left_lines.get(line_idx) {
-                self.render_colored_line(line, left_x, current_y, line_idx == 0, 2);
+                self.render_colored_line(line, left_x, current_y, self.is_title_line(line), 2);
             }
 
             if let Some(line) = right_lines.get(line_idx) {
-                self.render_colored_line(line, right_x, current_y, line_idx == 0, 2);
+                self.render_colored_line(line, right_x, current_y, self.is_title_line(line), 2);
             }
         }
 
@@ -265,6 +265,46 @@ impl<'a> LayoutDetail<'a> {
         lines
     }
 
+    fn prepare_panes_and_imports_content(
+        &self,
+        metadata: &LayoutMetadata,
+        max_width: usize,
+    ) -> Vec<String> {
+        let mut lines = self.prepare_panes_content(metadata, max_width);
+        let imports_lines = self.prepare_imports_content(metadata, max_width);
+        if !lines.is_empty() && !imports_lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.extend(imports_lines);
+        lines
+    }
+
+    fn prepare_imports_content(&self, metadata: &LayoutMetadata, max_width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        if metadata.imports.is_empty() {
+            return lines;
+        }
+
+        // Add title
+        let title = "Imports:";
+        lines.push(truncate_with_ellipsis(title, max_width));
+
+        // Add imported files (only the file name, the full path is rarely interesting)
+        for import in &metadata.imports {
+            let file_name = import.split('/').last().unwrap_or(import);
+            let available_width = max_width.saturating_sub(4);
+            let truncated_name = truncate_with_ellipsis(file_name, available_width);
+            lines.push(format!("  - {}", truncated_name));
+        }
+
+        lines
+    }
+
+    fn is_title_line(&self, line: &str) -> bool {
+        // content lines are indented, titles are not
+        !line.is_empty() && !line.starts_with(' ')
+    }
+
     fn calculate_column_layout(&self, max_cols: usize) -> (usize, usize, usize) {
         let left_width = (max_cols - 1) / 2;
         let padding = 1;
@@ -301,7 +341,7 @@ impl<'a> LayoutDetail<'a> {
         max_rows: usize,
         max_cols: usize,
     ) {
-        let panes_lines = self.prepare_panes_content(metadata, max_cols);
+        let panes_lines = self.prepare_panes_and_imports_content(metadata, max_cols);
 
         let mut was_truncated = false;
         for (line_idx, pane_line) in panes_lines.iter().enumerate() {
@@ -311,7 +351,7 @@ impl<'a> LayoutDetail<'a> {
             }
 
             let current_y = y + line_idx;
-            self.render_colored_line(pane_line, x, current_y, line_idx == 0, 2);
+            self.render_colored_line(pane_line, x, current_y, self.is_title_line(pane_line), 2);
         }
 
         if was_truncated {
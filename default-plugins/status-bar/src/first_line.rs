@@ -589,13 +589,17 @@ fn get_key_shortcut_for_mode<'a>(
     mode: &InputMode,
 ) -> Option<&'a mut KeyShortcut> {
     let key_action = match mode {
-        InputMode::Normal | InputMode::Prompt | InputMode::Tmux => return None,
+        InputMode::Normal | InputMode::Prompt | InputMode::Tmux | InputMode::Custom(_) => {
+            return None
+        },
         InputMode::Locked => KeyAction::Lock,
         InputMode::Pane | InputMode::RenamePane => KeyAction::Pane,
         InputMode::Tab | InputMode::RenameTab => KeyAction::Tab,
         InputMode::Resize => KeyAction::Resize,
         InputMode::Move => KeyAction::Move,
-        InputMode::Scroll | InputMode::Search | InputMode::EnterSearch => KeyAction::Search,
+        InputMode::Scroll | InputMode::Search | InputMode::EnterSearch | InputMode::Copy => {
+            KeyAction::Search
+        },
         InputMode::Session => KeyAction::Session,
     };
     for shortcut in shortcuts.iter_mut() {
@@ -19,8 +19,8 @@ use first_line::first_line;
 use one_line_ui::one_line_ui;
 use second_line::{
     floating_panes_are_visible, fullscreen_panes_to_hide, keybinds,
-    locked_floating_panes_are_visible, locked_fullscreen_panes_to_hide, system_clipboard_error,
-    text_copied_hint,
+    locked_floating_panes_are_visible, locked_fullscreen_panes_to_hide,
+    pending_key_sequence_indicator, recording_indicator, system_clipboard_error, text_copied_hint,
 };
 use tip::utils::get_cached_tip_name;
 
@@ -42,6 +42,7 @@ struct State {
     classic_ui: bool,
     base_mode_is_locked: bool,
     cached_keybinds: KeybindsVec,
+    recorded_terminal_pane_ids: Vec<u32>,
 }
 
 register_plugin!(State);
@@ -209,6 +210,7 @@ impl ZellijPlugin for State {
             EventType::InputReceived,
             EventType::SystemClipboardFailure,
             EventType::InitialKeybinds,
+            EventType::RecordingStateChanged,
         ]);
     }
 
@@ -257,6 +259,12 @@ impl ZellijPlugin for State {
                 should_render = true;
                 self.display_system_clipboard_failure = true;
             },
+            Event::RecordingStateChanged(recorded_terminal_pane_ids) => {
+                if self.recorded_terminal_pane_ids != recorded_terminal_pane_ids {
+                    should_render = true;
+                }
+                self.recorded_terminal_pane_ids = recorded_terminal_pane_ids;
+            },
             Event::InputReceived => {
                 if self.text_copy_destination.is_some()
                     || self.display_system_clipboard_failure == true
@@ -280,6 +288,22 @@ impl ZellijPlugin for State {
         };
 
         let background = self.mode_info.style.colors.text_unselected.background;
+        let mut indicators = if self.recorded_terminal_pane_ids.is_empty() {
+            LinePart::default()
+        } else {
+            recording_indicator(&self.mode_info.style.colors)
+        };
+        if !self.mode_info.pending_key_sequence.is_empty() {
+            let pending_key_sequence_indicator = pending_key_sequence_indicator(
+                &self.mode_info.style.colors,
+                &self.mode_info.pending_key_sequence,
+            );
+            indicators = LinePart {
+                part: format!("{}{}", indicators, pending_key_sequence_indicator),
+                len: indicators.len + pending_key_sequence_indicator.len,
+            };
+        }
+        let available_cols = cols.saturating_sub(indicators.len);
 
         if rows == 1 && !self.classic_ui {
             let fill_bg = match background {
@@ -288,11 +312,12 @@ impl ZellijPlugin for State {
             };
             let active_tab = self.tabs.iter().find(|t| t.active);
             print!(
-                "{}{}",
+                "{}{}{}",
+                indicators,
                 one_line_ui(
                     &self.mode_info,
                     active_tab,
-                    cols,
+                    available_cols,
                     separator,
                     self.base_mode_is_locked,
                     self.text_copy_destination,
@@ -305,7 +330,13 @@ impl ZellijPlugin for State {
 
         //TODO: Switch to UI components here
         let active_tab = self.tabs.iter().find(|t| t.active);
-        let first_line = first_line(&self.mode_info, active_tab, cols, separator);
+        let mut first_line = first_line(&self.mode_info, active_tab, available_cols, separator);
+        if indicators.len > 0 {
+            first_line = LinePart {
+                part: format!("{}{}", indicators, first_line),
+                len: indicators.len + first_line.len,
+            };
+        }
         let second_line = self.second_line(cols);
 
         // [48;5;238m is white background, [0K is so that it fills the rest of the line
@@ -1272,6 +1272,7 @@ fn add_keygroup_separator(help: &ModeInfo, max_len: usize) -> Option<LinePart> {
         InputMode::RenameTab => Some("RENAMING TAB"),
         InputMode::EnterSearch => Some("ENTERING SEARCH TERM"),
         InputMode::Search => Some("SEARCHING"),
+        InputMode::Copy => Some("COPYING"),
         _ => None,
     };
     if let Some(mode_help_text) = mode_help_text {
@@ -1340,6 +1341,8 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<KeyWithModifier
     use Direction as Dir;
     use actions::SearchDirection as SDir;
     use actions::SearchOption as SOpt;
+    use actions::CopyModeMotion as CMotion;
+    use actions::CopySelectionKind as CKind;
 
     let mut old_keymap = mi.get_mode_keybinds();
     let s = |string: &str| string.to_string();
@@ -1486,6 +1489,23 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<KeyWithModifier
             action_key(&km, &[A::SearchToggleOption{option: SOpt::Wrap}])),
         (s("Whole words"), s("Whole"),
             action_key(&km, &[A::SearchToggleOption{option: SOpt::WholeWord}])),
+        (s("Regex"), s("Regex"),
+            action_key(&km, &[A::SearchToggleOption{option: SOpt::Regex}])),
+    ]} else if mi.mode == IM::Copy { vec![
+        (s("Move"), s("Move"), action_key_group(&km, &[
+            &[A::CopyModeMove{motion: CMotion::Left}], &[A::CopyModeMove{motion: CMotion::Down}],
+            &[A::CopyModeMove{motion: CMotion::Up}], &[A::CopyModeMove{motion: CMotion::Right}]])),
+        (s("Word"), s("Word"), action_key_group(&km, &[
+            &[A::CopyModeMove{motion: CMotion::WordForward}], &[A::CopyModeMove{motion: CMotion::WordBackward}]])),
+        (s("Select"), s("Select"), action_key(&km, &[A::CopyModeSelect{kind: CKind::Character}])),
+        (s("Select lines"), s("Lines"), action_key(&km, &[A::CopyModeSelect{kind: CKind::Line}])),
+        (s("Select block"), s("Block"), action_key(&km, &[A::CopyModeSelect{kind: CKind::Block}])),
+        (s("Copy selection"), s("Copy"), action_key(&km, &[A::Copy, TO_NORMAL])),
+        (s("Enter search term"), s("Search"),
+            action_key(&km, &[A::SwitchToMode{input_mode: IM::EnterSearch}, A::SearchInput{input: vec![0]}])),
+        (s("Search down"), s("Down"), action_key(&km, &[A::Search{direction: SDir::Down}])),
+        (s("Search up"), s("Up"), action_key(&km, &[A::Search{direction: SDir::Up}])),
+        (s("Select pane"), s("Select"), to_basemode_key),
     ]} else if mi.mode == IM::Session { vec![
         (s("Detach"), s("Detach"), action_key(&km, &[Action::Detach])),
         (s("Session Manager"), s("Manager"), session_manager_key(&km)),
@@ -1520,7 +1540,16 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<KeyWithModifier
         (s("Select pane"), s("Select"), to_basemode_key),
     ]} else if matches!(mi.mode, IM::RenamePane | IM::RenameTab) { vec![
         (s("When done"), s("Done"), to_basemode_key),
-    ]} else { vec![] }
+    ]} else if mi.mode.is_custom() {
+        // user defined modes have no known hints, so we name the first action of each binding
+        km.iter().filter_map(|(key, acvec)| {
+            let hint = match acvec.first()? {
+                A::SwitchToMode { input_mode } => format!("{:?}", input_mode),
+                action => action.to_string(),
+            };
+            Some((hint.clone(), hint, vec![key.clone()]))
+        }).collect()
+    } else { vec![] }
 }
 
 fn shortened_shortcut_list_nonstandard_mode(help: &ModeInfo) -> LinePart {
@@ -111,6 +111,8 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<KeyWithModifier
     use Direction as Dir;
     use actions::SearchDirection as SDir;
     use actions::SearchOption as SOpt;
+    use actions::CopyModeMotion as CMotion;
+    use actions::CopySelectionKind as CKind;
 
     let mut old_keymap = mi.get_mode_keybinds();
     let s = |string: &str| string.to_string();
@@ -252,6 +254,23 @@ fn get_keys_and_hints(mi: &ModeInfo) -> Vec<(String, String, Vec<KeyWithModifier
             action_key(&km, &[A::SearchToggleOption{option: SOpt::Wrap}])),
         (s("Whole words"), s("Whole"),
             action_key(&km, &[A::SearchToggleOption{option: SOpt::WholeWord}])),
+        (s("Regex"), s("Regex"),
+            action_key(&km, &[A::SearchToggleOption{option: SOpt::Regex}])),
+    ]} else if mi.mode == IM::Copy { vec![
+        (s("Move"), s("Move"), action_key_group(&km, &[
+            &[A::CopyModeMove{motion: CMotion::Left}], &[A::CopyModeMove{motion: CMotion::Down}],
+            &[A::CopyModeMove{motion: CMotion::Up}], &[A::CopyModeMove{motion: CMotion::Right}]])),
+        (s("Word"), s("Word"), action_key_group(&km, &[
+            &[A::CopyModeMove{motion: CMotion::WordForward}], &[A::CopyModeMove{motion: CMotion::WordBackward}]])),
+        (s("Select"), s("Select"), action_key(&km, &[A::CopyModeSelect{kind: CKind::Character}])),
+        (s("Select lines"), s("Lines"), action_key(&km, &[A::CopyModeSelect{kind: CKind::Line}])),
+        (s("Select block"), s("Block"), action_key(&km, &[A::CopyModeSelect{kind: CKind::Block}])),
+        (s("Copy selection"), s("Copy"), action_key(&km, &[A::Copy, TO_NORMAL])),
+        (s("Enter search term"), s("Search"),
+            action_key(&km, &[A::SwitchToMode{input_mode: IM::EnterSearch}, A::SearchInput{input: vec![0]}])),
+        (s("Search down"), s("Down"), action_key(&km, &[A::Search{direction: SDir::Down}])),
+        (s("Search up"), s("Up"), action_key(&km, &[A::Search{direction: SDir::Up}])),
+        (s("Select pane"), s("Select"), to_normal_key),
     ]} else if mi.mode == IM::Session { vec![
         (s("Detach"), s("Detach"), action_key(&km, &[Action::Detach])),
         (s("Session Manager"), s("Manager"), action_key(&km, &[A::LaunchOrFocusPlugin{plugin: Default::default(), should_float: true, move_to_focused_tab: true, should_open_in_place: false, close_replaced_pane: false, skip_cache: false, tab_id: None}, TO_NORMAL])), // not entirely accurate
@@ -392,6 +411,40 @@ pub fn system_clipboard_error(palette: &Styling) -> LinePart {
     }
 }
 
+pub fn recording_indicator(palette: &Styling) -> LinePart {
+    let indicator = " ● REC ";
+    let red_color = palette_match!(palette.text_unselected.emphasis_3);
+    LinePart {
+        part: Style::new()
+            .fg(red_color)
+            .bold()
+            .paint(indicator)
+            .to_string(),
+        len: indicator.chars().count(),
+    }
+}
+
+pub fn pending_key_sequence_indicator(
+    palette: &Styling,
+    pending_key_sequence: &[KeyWithModifier],
+) -> LinePart {
+    let keys = pending_key_sequence
+        .iter()
+        .map(|k| k.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let indicator = format!(" {} … ", keys);
+    let orange_color = palette_match!(palette.text_unselected.emphasis_0);
+    LinePart {
+        part: Style::new()
+            .fg(orange_color)
+            .bold()
+            .paint(&indicator)
+            .to_string(),
+        len: indicator.chars().count(),
+    }
+}
+
 pub fn fullscreen_panes_to_hide(palette: &Styling, panes_to_hide: usize) -> LinePart {
     let text_color = palette_match!(palette.text_unselected.base);
     let green_color = palette_match!(palette.text_unselected.emphasis_2);
@@ -31,19 +31,20 @@ use zellij_utils::web_server_commands::shutdown_all_webserver_instances;
 
 #[cfg(feature = "web_server_capability")]
 use zellij_utils::web_authentication_tokens::{
-    create_token, list_tokens, revoke_all_tokens, revoke_token,
+    create_scoped_token, list_tokens, revoke_all_tokens, revoke_token,
 };
 
 use miette::{Report, Result};
 use zellij_server::{os_input_output::get_server_os_input, start_server as start_server_impl};
 use zellij_utils::{
-    cli::{CliArgs, Command, SessionCommand, Sessions},
+    cli::{CliArgs, Command, PluginPermissionsCommand, SessionCommand, Sessions},
     data::ConnectToSession,
     envs,
     input::{
         actions::Action,
         config::{Config, ConfigError},
         options::Options,
+        permission::PermissionCache,
     },
     setup::Setup,
 };
@@ -254,18 +255,44 @@ pub(crate) fn stop_web_server() -> Result<(), String> {
 }
 
 #[cfg(feature = "web_server_capability")]
-pub(crate) fn create_auth_token(name: Option<String>, read_only: bool) -> Result<String, String> {
+pub(crate) fn create_auth_token(
+    name: Option<String>,
+    read_only: bool,
+    session_scope: Vec<String>,
+    expires_in: Option<std::time::Duration>,
+) -> Result<String, String> {
     // returns the token and it's name
-    create_token(name, read_only)
+    let scope_description = describe_session_scope(&session_scope);
+    create_scoped_token(name, read_only, session_scope, expires_in)
         .map(|(token, token_name)| {
             let access_type = if read_only { " (read-only)" } else { "" };
-            format!("{}: {}{}", token_name, token, access_type)
+            let expiry = expires_in
+                .map(|e| format!(", expires in {}", humantime::format_duration(e)))
+                .unwrap_or_default();
+            format!(
+                "{}: {}{} ({}{})",
+                token_name, token, access_type, scope_description, expiry
+            )
         })
         .map_err(|e| e.to_string())
 }
 
+#[cfg(feature = "web_server_capability")]
+fn describe_session_scope(session_scope: &[String]) -> String {
+    if session_scope.is_empty() {
+        "all sessions".to_owned()
+    } else {
+        format!("sessions: {}", session_scope.join(", "))
+    }
+}
+
 #[cfg(not(feature = "web_server_capability"))]
-pub(crate) fn create_auth_token(_name: Option<String>, _read_only: bool) -> Result<String, String> {
+pub(crate) fn create_auth_token(
+    _name: Option<String>,
+    _read_only: bool,
+    _session_scope: Vec<String>,
+    _expires_in: Option<std::time::Duration>,
+) -> Result<String, String> {
     log::error!(
         "This version of Zellij was compiled without web server support, cannot create auth token!"
     );
@@ -316,9 +343,17 @@ pub(crate) fn list_auth_tokens() -> Result<Vec<String>, String> {
             let mut res = vec![];
             for t in tokens {
                 let access_type = if t.read_only { " [READ-ONLY]" } else { "" };
+                let expiry = t
+                    .expires_at
+                    .map(|e| format!(", expires at {}", e))
+                    .unwrap_or_default();
                 res.push(format!(
-                    "{}: created at {}{}",
-                    t.name, t.created_at, access_type
+                    "{}: created at {}{} ({}{})",
+                    t.name,
+                    t.created_at,
+                    access_type,
+                    describe_session_scope(&t.session_scope),
+                    expiry
                 ))
             }
             res
@@ -1005,6 +1040,63 @@ pub(crate) fn list_aliases(opts: CliArgs) {
     process::exit(0);
 }
 
+pub(crate) fn manage_plugin_permissions(permissions_command: PluginPermissionsCommand) {
+    let cwd = std::env::current_dir().ok();
+    let plugin_name_from_url =
+        |url: &str| match PermissionCache::plugin_name_from_url(url, cwd.clone()) {
+            Ok(plugin_name) => plugin_name,
+            Err(e) => {
+                eprintln!("Invalid plugin url '{}': {}", url, e);
+                process::exit(1);
+            },
+        };
+    let write_to_file = |permission_cache: &PermissionCache| {
+        if let Err(e) = permission_cache.write_to_file() {
+            eprintln!("Failed to write plugin permissions: {}", e);
+            process::exit(1);
+        }
+    };
+    let mut permission_cache = PermissionCache::from_path_or_default(None);
+    match permissions_command {
+        PluginPermissionsCommand::List { url } => {
+            let plugin_name = url.map(|url| plugin_name_from_url(&url));
+            let mut granted: Vec<_> = permission_cache
+                .granted()
+                .iter()
+                .filter(|(name, _)| plugin_name.as_ref().map(|p| p == *name).unwrap_or(true))
+                .collect();
+            granted.sort_by(|(a, _), (b, _)| a.cmp(b));
+            if granted.is_empty() {
+                println!("No plugin permissions granted.");
+            }
+            for (plugin_name, permissions) in granted {
+                println!("{}", plugin_name);
+                if permissions.is_empty() {
+                    println!("    (all permissions denied)");
+                }
+                for permission in permissions {
+                    println!("    {}", permission);
+                }
+            }
+        },
+        PluginPermissionsCommand::Grant { url, permissions } => {
+            let plugin_name = plugin_name_from_url(&url);
+            permission_cache.grant(plugin_name.clone(), permissions);
+            write_to_file(&permission_cache);
+            println!("Updated permissions for {}", plugin_name);
+        },
+        PluginPermissionsCommand::Revoke { url, permissions } => {
+            let plugin_name = plugin_name_from_url(&url);
+            if !permission_cache.revoke(&plugin_name, &permissions) {
+                eprintln!("No matching permissions granted to {}", plugin_name);
+                process::exit(1);
+            }
+            write_to_file(&permission_cache);
+            println!("Updated permissions for {}", plugin_name);
+        },
+    }
+}
+
 pub(crate) fn watch_session(session_name: Option<String>, opts: CliArgs) {
     let (config, _, config_options, _, _) = match Setup::from_cli_args(&opts) {
         Ok(results) => results,
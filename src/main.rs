@@ -4,7 +4,7 @@ mod tests;
 
 use clap::Parser;
 use zellij_utils::{
-    cli::{CliAction, CliArgs, Command, Sessions},
+    cli::{CliAction, CliArgs, Command, PluginSubcommand, Sessions},
     consts::{create_config_and_cache_folders, VERSION},
     data::UnblockCondition,
     envs,
@@ -33,6 +33,9 @@ fn main() {
             command,
             direction,
             cwd,
+            env,
+            restart,
+            max_restarts,
             floating,
             in_place,
             close_replaced_pane,
@@ -95,12 +98,24 @@ fn main() {
                 near_current_pane,
                 borderless,
                 tab_id,
+                env,
+                restart,
+                max_restarts,
             };
             commands::send_action_to_session(command_cli_action, opts.session, config);
             std::process::exit(0);
         }
+        if let Some(Command::Sessions(Sessions::Plugin {
+            command: Some(PluginSubcommand::Permissions(permissions_command)),
+            ..
+        })) = opts.command
+        {
+            commands::manage_plugin_permissions(permissions_command);
+            std::process::exit(0);
+        }
         if let Some(Command::Sessions(Sessions::Plugin {
             url,
+            command: _,
             floating,
             in_place,
             close_replaced_pane,
@@ -121,7 +136,7 @@ fn main() {
             let unblock_condition = None;
             let command_cli_action = CliAction::NewPane {
                 command: vec![],
-                plugin: Some(url),
+                plugin: url,
                 direction: None,
                 cwd,
                 floating,
@@ -146,6 +161,9 @@ fn main() {
                 near_current_pane: false,
                 borderless,
                 tab_id,
+                env: vec![],
+                restart: None,
+                max_restarts: None,
             };
             commands::send_action_to_session(command_cli_action, opts.session, config);
             std::process::exit(0);
@@ -275,6 +293,7 @@ fn main() {
                 layout: opts.layout.clone(),
                 layout_string: opts.layout_string.clone(),
                 layout_dir: options.as_ref().and_then(|o| o.layout_dir.clone()),
+                layout_args: opts.layout_args.clone(),
                 name: None,
                 cwd: options.as_ref().and_then(|o| o.default_cwd.clone()),
                 initial_command: vec![],
@@ -349,7 +368,12 @@ fn main() {
             }
         } else if web_opts.create_token {
             let read_only = false;
-            match commands::create_auth_token(web_opts.token_name.clone(), read_only) {
+            match commands::create_auth_token(
+                web_opts.token_name.clone(),
+                read_only,
+                web_opts.token_session_scope.clone(),
+                web_opts.token_expires_in,
+            ) {
                 Ok(token_and_name) => {
                     println!("Created token successfully");
                     println!("");
@@ -362,7 +386,12 @@ fn main() {
             }
         } else if web_opts.create_read_only_token {
             let read_only = true;
-            match commands::create_auth_token(web_opts.token_name.clone(), read_only) {
+            match commands::create_auth_token(
+                web_opts.token_name.clone(),
+                read_only,
+                web_opts.token_session_scope.clone(),
+                web_opts.token_expires_in,
+            ) {
                 Ok(token_and_name) => {
                     println!("Created token successfully");
                     println!("");
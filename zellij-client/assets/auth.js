@@ -66,17 +66,24 @@ export async function getClientId(token, rememberMe, hasAuthenticationCookie) {
         }
     }
 
+    const sessionName = location.pathname.split("/").pop();
     let data = await fetch(`${baseUrl}/session`, {
         method: "POST",
         headers: {
             "Content-Type": "application/json",
         },
-        body: JSON.stringify({}),
+        body: JSON.stringify({ session_name: decodeURIComponent(sessionName) }),
     });
 
     if (data.status === 401) {
         await showErrorModal("Error", "Unauthorized or revoked login token.");
         return null;
+    } else if (data.status === 403) {
+        await showErrorModal(
+            "Error",
+            "This login token cannot access this session."
+        );
+        return null;
     } else if (!data.ok) {
         await showErrorModal(
             "Error",
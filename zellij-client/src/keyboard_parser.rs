@@ -17,8 +17,11 @@ enum KittyKeysParsingState {
 /// byte stream (reset and let a fallback parser handle it).
 #[derive(Debug)]
 pub enum KittyParseOutcome {
-    /// Complete sequence parsed; parser resets to Ground.
-    Complete(KeyWithModifier),
+    /// One or more complete sequences parsed, each with its own bytes (a
+    /// chunk can hold several, eg. a key press immediately followed by its
+    /// release). The parser keeps the state of a trailing partial sequence,
+    /// if any.
+    Complete(Vec<(KeyWithModifier, Vec<u8>)>),
     /// Bytes are a valid prefix; parser keeps state. Caller should let
     /// termwiz also see them this round and call `feed()` again on the
     /// next chunk.
@@ -32,6 +35,7 @@ pub struct KittyKeyboardParser {
     state: KittyKeysParsingState,
     number_bytes: Vec<u8>,
     modifier_bytes: Vec<u8>,
+    sequence_bytes: Vec<u8>,
 }
 
 /// CSI final-byte range (0x40..=0x7E), minus `u` and `~` which trigger
@@ -49,6 +53,7 @@ impl KittyKeyboardParser {
             state: KittyKeysParsingState::Ground,
             number_bytes: vec![],
             modifier_bytes: vec![],
+            sequence_bytes: vec![],
         }
     }
 
@@ -56,88 +61,94 @@ impl KittyKeyboardParser {
         self.state = KittyKeysParsingState::Ground;
         self.number_bytes.clear();
         self.modifier_bytes.clear();
+        self.sequence_bytes.clear();
     }
 
     /// Stateful, cross-chunk-aware entry point. Drives the same
     /// state machine as `parse()` but:
-    /// * resets to Ground after producing `Complete`/`NoMatch`, so the
-    ///   parser can be reused for the next sequence;
+    /// * resets to Ground after each complete sequence, so the parser can
+    ///   go on with the next one in the same chunk;
     /// * preserves state on `Incomplete`, so a sequence split across
     ///   chunks still resolves on a follow-up call.
     ///
     /// The existing `parse()` wrapper is retained for the unit tests in
     /// this file, which construct `::new()` per assertion.
     pub fn feed(&mut self, bytes: &[u8]) -> KittyParseOutcome {
+        let mut keys = vec![];
         for byte in bytes {
             if !self.advance(*byte) {
                 self.reset();
                 return KittyParseOutcome::NoMatch;
             }
+            self.sequence_bytes.push(*byte);
+            if self.is_done() {
+                let sequence_bytes = self.sequence_bytes.clone();
+                match self.finish() {
+                    Some(key) => keys.push((key, sequence_bytes)),
+                    None => return KittyParseOutcome::NoMatch,
+                }
+            }
+        }
+        if !keys.is_empty() {
+            KittyParseOutcome::Complete(keys)
+        } else {
+            match self.state {
+                KittyKeysParsingState::Ground => KittyParseOutcome::NoMatch,
+                _ => KittyParseOutcome::Incomplete,
+            }
         }
+    }
+
+    fn is_done(&self) -> bool {
         match self.state {
+            KittyKeysParsingState::DoneParsingWithU
+            | KittyKeysParsingState::DoneParsingWithTilde => true,
+            // ParsingNumber holds either a digit run waiting for a
+            // terminator or a single letter that is itself the
+            // terminator — `\x1b[A` etc.
+            KittyKeysParsingState::ParsingNumber => self
+                .number_bytes
+                .last()
+                .map(|last| is_csi_final_letter(*last))
+                .unwrap_or(false),
+            // ParsingModifiers holds either modifier digits waiting
+            // for the terminator letter or modifier digits + a trailing
+            // letter terminator — `\x1b[1;2A` etc.
+            KittyKeysParsingState::ParsingModifiers => self
+                .modifier_bytes
+                .last()
+                .map(|last| is_csi_final_letter(*last))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    // resets the parser, returns None if the sequence does not describe a key
+    fn finish(&mut self) -> Option<KeyWithModifier> {
+        let result = match self.state {
             KittyKeysParsingState::DoneParsingWithU => {
-                let result =
-                    KeyWithModifier::from_bytes_with_u(&self.number_bytes, &self.modifier_bytes);
-                self.reset();
-                match result {
-                    Some(k) => KittyParseOutcome::Complete(k),
-                    None => KittyParseOutcome::NoMatch,
-                }
+                KeyWithModifier::from_bytes_with_u(&self.number_bytes, &self.modifier_bytes)
             },
             KittyKeysParsingState::DoneParsingWithTilde => {
-                let result = KeyWithModifier::from_bytes_with_tilde(
-                    &self.number_bytes,
-                    &self.modifier_bytes,
-                );
-                self.reset();
-                match result {
-                    Some(k) => KittyParseOutcome::Complete(k),
-                    None => KittyParseOutcome::NoMatch,
-                }
+                KeyWithModifier::from_bytes_with_tilde(&self.number_bytes, &self.modifier_bytes)
             },
             KittyKeysParsingState::ParsingNumber => {
-                // ParsingNumber holds either a digit run waiting for a
-                // terminator (Incomplete) or a single letter that is
-                // itself the terminator — `\x1b[A` etc. (Complete).
-                match self.number_bytes.last().copied() {
-                    Some(last) if is_csi_final_letter(last) => {
-                        let result = KeyWithModifier::from_bytes_with_no_ending_byte(
-                            &self.number_bytes,
-                            &self.modifier_bytes,
-                        );
-                        self.reset();
-                        match result {
-                            Some(k) => KittyParseOutcome::Complete(k),
-                            None => KittyParseOutcome::NoMatch,
-                        }
-                    },
-                    _ => KittyParseOutcome::Incomplete,
-                }
+                KeyWithModifier::from_bytes_with_no_ending_byte(
+                    &self.number_bytes,
+                    &self.modifier_bytes,
+                )
             },
             KittyKeysParsingState::ParsingModifiers => {
-                // ParsingModifiers holds either modifier digits waiting
-                // for the terminator letter (Incomplete) or modifier
-                // digits + a trailing letter terminator
-                // — `\x1b[1;2A` etc. (Complete).
-                match self.modifier_bytes.last().copied() {
-                    Some(last) if is_csi_final_letter(last) => {
-                        let last_modifier = self.modifier_bytes.pop().unwrap();
-                        let result = KeyWithModifier::from_bytes_with_no_ending_byte(
-                            &[last_modifier],
-                            &self.modifier_bytes,
-                        );
-                        self.reset();
-                        match result {
-                            Some(k) => KittyParseOutcome::Complete(k),
-                            None => KittyParseOutcome::NoMatch,
-                        }
-                    },
-                    _ => KittyParseOutcome::Incomplete,
-                }
+                let last_modifier = self.modifier_bytes.pop().unwrap();
+                KeyWithModifier::from_bytes_with_no_ending_byte(
+                    &[last_modifier],
+                    &self.modifier_bytes,
+                )
             },
-            KittyKeysParsingState::ReceivedEscapeCharacter => KittyParseOutcome::Incomplete,
-            KittyKeysParsingState::Ground => KittyParseOutcome::NoMatch,
-        }
+            _ => None,
+        };
+        self.reset();
+        result
     }
 
     pub fn advance(&mut self, byte: u8) -> bool {
@@ -193,8 +204,8 @@ impl KittyKeyboardParser {
 #[cfg(test)]
 fn parse_for_test(bytes: &[u8]) -> Option<KeyWithModifier> {
     match KittyKeyboardParser::new().feed(bytes) {
-        KittyParseOutcome::Complete(k) => Some(k),
-        KittyParseOutcome::Incomplete | KittyParseOutcome::NoMatch => None,
+        KittyParseOutcome::Complete(mut keys) if keys.len() == 1 => keys.pop().map(|(k, _)| k),
+        _ => None,
     }
 }
 
@@ -2014,9 +2025,9 @@ fn fragmented_kitty_csi_u_emits_one_event() {
     let r1 = p.feed(b"\x1b[97;");
     assert!(matches!(r1, KittyParseOutcome::Incomplete));
     match p.feed(b"2u") {
-        KittyParseOutcome::Complete(k) => {
+        KittyParseOutcome::Complete(keys) => {
             assert_eq!(
-                k,
+                keys[0].0,
                 KeyWithModifier::new(BareKey::Char('a')).with_shift_modifier()
             );
         },
@@ -2037,9 +2048,9 @@ fn fragmented_kitty_byte_by_byte() {
         );
     }
     match p.feed(&[full[full.len() - 1]]) {
-        KittyParseOutcome::Complete(k) => {
+        KittyParseOutcome::Complete(keys) => {
             assert_eq!(
-                k,
+                keys[0].0,
                 KeyWithModifier::new(BareKey::Char('a')).with_ctrl_modifier()
             );
         },
@@ -2055,3 +2066,79 @@ fn non_kitty_bytes_yield_nomatch_and_reset() {
     let mut p = KittyKeyboardParser::new();
     assert!(matches!(p.feed(b"hello"), KittyParseOutcome::NoMatch));
 }
+
+#[test]
+fn can_parse_keys_with_progressive_enhancements() {
+    use zellij_utils::data::BareKey;
+    assert_eq!(
+        parse_for_test(b"\x1b[97;;97u"),
+        Some(KeyWithModifier::new(BareKey::Char('a'))),
+        "Can parse a key with associated text"
+    );
+    assert_eq!(
+        parse_for_test(b"\x1b[49:33;2u"),
+        Some(KeyWithModifier::new(BareKey::Char('!'))),
+        "Shift with a shifted key is the text it produces"
+    );
+    assert_eq!(
+        parse_for_test(b"\x1b[97;65;65u"),
+        Some(KeyWithModifier::new(BareKey::Char('A'))),
+        "Caps lock is taken into account through the associated text"
+    );
+    assert_eq!(
+        parse_for_test(b"\x1b[49:33;6u"),
+        Some(
+            KeyWithModifier::new(BareKey::Char('1'))
+                .with_ctrl_modifier()
+                .with_shift_modifier()
+        ),
+        "Shifted keys are ignored with other modifiers"
+    );
+    assert_eq!(
+        parse_for_test(b"\x1b[97;5:3u"),
+        Some(KeyWithModifier::new(BareKey::Char('a')).with_ctrl_modifier()),
+        "Can parse a key release"
+    );
+    assert_eq!(
+        parse_for_test(b"\x1b[1;5:2A"),
+        Some(KeyWithModifier::new(BareKey::Up).with_ctrl_modifier()),
+        "Can parse a repeated arrow key"
+    );
+    assert_eq!(
+        parse_for_test(b"\x1b[5;1:3~"),
+        Some(KeyWithModifier::new(BareKey::PageUp)),
+        "Can parse a tilde key release"
+    );
+}
+
+#[test]
+fn several_sequences_in_one_chunk_emit_one_event_each() {
+    use zellij_utils::data::BareKey;
+    let mut p = KittyKeyboardParser::new();
+    match p.feed(b"\x1b[97;5u\x1b[97;5:3u\x1b[1;5") {
+        KittyParseOutcome::Complete(keys) => {
+            let ctrl_a = KeyWithModifier::new(BareKey::Char('a')).with_ctrl_modifier();
+            assert_eq!(
+                keys,
+                vec![
+                    (ctrl_a.clone(), b"\x1b[97;5u".to_vec()),
+                    (ctrl_a, b"\x1b[97;5:3u".to_vec()),
+                ]
+            );
+        },
+        other => panic!("expected Complete, got {:?}", other),
+    }
+    match p.feed(b"A") {
+        KittyParseOutcome::Complete(keys) => {
+            assert_eq!(
+                keys,
+                vec![(
+                    KeyWithModifier::new(BareKey::Up).with_ctrl_modifier(),
+                    b"\x1b[1;5A".to_vec()
+                )],
+                "the trailing partial sequence completes on the next chunk"
+            );
+        },
+        other => panic!("expected Complete, got {:?}", other),
+    }
+}
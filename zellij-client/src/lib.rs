@@ -22,6 +22,7 @@ mod stdin_handler_windows;
 pub mod web_client;
 
 use log::info;
+use std::collections::BTreeMap;
 use std::env::current_exe;
 use std::io::{self, Write};
 use std::net::{IpAddr, Ipv4Addr};
@@ -51,7 +52,9 @@ const EXIT_ALTERNATE_SCREEN: &str = "\u{1b}[?1049l";
 const ENABLE_BRACKETED_PASTE: &str = "\u{1b}[?2004h";
 const RESET_STYLE: &str = "\u{1b}[m";
 const SHOW_CURSOR: &str = "\u{1b}[?25h";
-const ENTER_KITTY_KEYBOARD_MODE: &str = "\u{1b}[>1u";
+// we ask for all the progressive enhancements, and encode the keys for each pane according to the
+// ones it asked for
+const ENTER_KITTY_KEYBOARD_MODE: &str = "\u{1b}[>31u";
 const EXIT_KITTY_KEYBOARD_MODE: &str = "\u{1b}[<1u";
 const CLEAR_CLIENT_TERMINAL_ATTRIBUTES: &str = "\u{1b}[?1l\u{1b}=\u{1b}[r\u{1b}[?1000l\u{1b}[?1002l\u{1b}[?1003l\u{1b}[?1005l\u{1b}[?1006l\u{1b}[?12l";
 /// Subscribe to host color-palette theme notifications (CSI 2031). Hosts
@@ -441,10 +444,26 @@ pub(crate) enum InputInstruction {
     Exit,
 }
 
+#[cfg(feature = "web_server_capability")]
+const REMOTE_KEEPALIVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+#[cfg(feature = "web_server_capability")]
+const REMOTE_KEEPALIVE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+#[cfg(feature = "web_server_capability")]
+const CTRL_C: u8 = 3;
+
+#[cfg(feature = "web_server_capability")]
+enum RemoteConnectionEnded {
+    // the user quit or the server closed the session
+    Exit,
+    // the connection was lost, we can try to re-establish it
+    Dropped,
+}
+
 #[cfg(feature = "web_server_capability")]
 pub async fn run_remote_client_terminal_loop(
     os_input: Box<dyn ClientOsApi>,
     mut connections: remote_attach::WebSocketConnections,
+    reconnect: Option<remote_attach::RemoteReconnect>,
 ) -> Result<Option<ConnectToSession>, RemoteClientError> {
     use crate::os_input_output::{AsyncSignals, AsyncStdin};
 
@@ -458,10 +477,146 @@ pub async fn run_remote_client_terminal_loop(
         .get_async_signal_listener()
         .map_err(|e| RemoteClientError::IoError(e))?;
 
+    loop {
+        let connection_ended = run_remote_connection(
+            &*os_input,
+            &mut connections,
+            &mut async_stdin,
+            &mut async_signals,
+            synchronised_output,
+        )
+        .await;
+        match (connection_ended, reconnect.as_ref()) {
+            (RemoteConnectionEnded::Dropped, Some(reconnect)) => {
+                match reconnect_to_remote_session(
+                    &*os_input,
+                    reconnect,
+                    &mut async_stdin,
+                    &mut async_signals,
+                )
+                .await?
+                {
+                    Some(new_connections) => {
+                        log::info!(
+                            "Reconnected to remote session as {}",
+                            new_connections.web_client_id
+                        );
+                        connections = new_connections;
+                        // the server renders the whole screen for newly attached clients, so
+                        // we start from a clean slate rather than on top of the stale one
+                        let mut stdout = os_input.get_stdout_writer();
+                        stdout
+                            .write_all(b"\x1b[2J\x1b[H")
+                            .expect("cannot write to stdout");
+                        stdout.flush().expect("could not flush");
+                    },
+                    None => break,
+                }
+            },
+            _ => break,
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(feature = "web_server_capability")]
+async fn reconnect_to_remote_session(
+    os_input: &dyn ClientOsApi,
+    reconnect: &remote_attach::RemoteReconnect,
+    async_stdin: &mut Box<dyn crate::os_input_output::AsyncStdin>,
+    async_signals: &mut Box<dyn crate::os_input_output::AsyncSignals>,
+) -> Result<Option<remote_attach::WebSocketConnections>, RemoteClientError> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let delay = remote_attach::reconnect_delay(attempt);
+        let overlay = remote_attach::reconnecting_overlay(
+            reconnect.remote_session_url(),
+            attempt,
+            delay,
+            os_input.get_terminal_size().cols,
+        );
+        {
+            let mut stdout = os_input.get_stdout_writer();
+            stdout
+                .write_all(overlay.as_bytes())
+                .expect("cannot write to stdout");
+            stdout.flush().expect("could not flush");
+        }
+
+        let backoff = tokio::time::sleep(delay);
+        tokio::pin!(backoff);
+        loop {
+            tokio::select! {
+                _ = &mut backoff => break,
+                result = async_stdin.read() => {
+                    match result {
+                        // there's nowhere to send input while we're disconnected, only
+                        // Ctrl-c (to give up) means anything
+                        Ok(buf) if !buf.is_empty() && !is_ctrl_c(&buf) => {},
+                        _ => return Ok(None),
+                    }
+                }
+                Some(signal) = async_signals.recv() => {
+                    match signal {
+                        crate::os_input_output::SignalEvent::Resize => {},
+                        crate::os_input_output::SignalEvent::Quit => return Ok(None),
+                    }
+                }
+            }
+        }
+
+        match reconnect.reconnect().await {
+            Ok(connections) => return Ok(Some(connections)),
+            Err(e @ RemoteClientError::SessionTokenExpired)
+            | Err(e @ RemoteClientError::Unauthorized)
+            | Err(e @ RemoteClientError::UrlParseError(_)) => {
+                log::error!("Cannot reconnect to remote session: {}", e);
+                return Err(e);
+            },
+            Err(e) => {
+                log::error!("Failed to reconnect to remote session: {}", e);
+            },
+        }
+    }
+}
+
+// the terminal is still in kitty keyboard mode while we reconnect, where Ctrl-c arrives as a
+// CSI-u sequence (eg. ESC[99;5u) rather than as a raw byte
+#[cfg(feature = "web_server_capability")]
+fn is_ctrl_c(buf: &[u8]) -> bool {
+    use crate::keyboard_parser::{KittyKeyboardParser, KittyParseOutcome};
+    use zellij_utils::data::BareKey;
+
+    if buf.contains(&CTRL_C) {
+        return true;
+    }
+    buf.split(|byte| *byte == 0x1b).skip(1).any(|sequence| {
+        let mut sequence_bytes = vec![0x1b];
+        sequence_bytes.extend_from_slice(sequence);
+        match KittyKeyboardParser::new().feed(&sequence_bytes) {
+            KittyParseOutcome::Complete(keys) => keys
+                .iter()
+                .any(|(key, _)| key.is_key_with_ctrl_modifier(BareKey::Char('c'))),
+            _ => false,
+        }
+    })
+}
+
+#[cfg(feature = "web_server_capability")]
+async fn run_remote_connection(
+    os_input: &dyn ClientOsApi,
+    connections: &mut remote_attach::WebSocketConnections,
+    async_stdin: &mut Box<dyn crate::os_input_output::AsyncStdin>,
+    async_signals: &mut Box<dyn crate::os_input_output::AsyncSignals>,
+    synchronised_output: Option<SyncOutput>,
+) -> RemoteConnectionEnded {
+    let web_client_id = connections.web_client_id.clone();
     let create_resize_message = |size: Size| {
         Message::Text(
             serde_json::to_string(&WebClientToWebServerControlMessage {
-                web_client_id: connections.web_client_id.clone(),
+                web_client_id: web_client_id.clone(),
                 payload: WebClientToWebServerControlMessagePayload::TerminalResize(size),
             })
             .unwrap(),
@@ -478,6 +633,15 @@ pub async fn run_remote_client_terminal_loop(
         log::error!("Failed to send resize message: {}", e);
     }
 
+    // a connection that silently went away (eg. the laptop moved to another network) does not
+    // error until the OS gives up on it, so we ping the server and consider the connection
+    // dropped if we don't hear anything back in time
+    let mut last_heard_from_server = tokio::time::Instant::now();
+    let mut keepalive = tokio::time::interval_at(
+        tokio::time::Instant::now() + REMOTE_KEEPALIVE_INTERVAL,
+        REMOTE_KEEPALIVE_INTERVAL,
+    );
+
     loop {
         tokio::select! {
             // Handle stdin input
@@ -486,16 +650,16 @@ pub async fn run_remote_client_terminal_loop(
                     Ok(buf) if !buf.is_empty() => {
                         if let Err(e) = connections.terminal_ws.send(Message::Binary(buf)).await {
                             log::error!("Failed to send stdin to terminal WebSocket: {}", e);
-                            break;
+                            return RemoteConnectionEnded::Dropped;
                         }
                     }
                     Ok(_) => {
                         // Empty buffer means EOF
-                        break;
+                        return RemoteConnectionEnded::Exit;
                     }
                     Err(e) => {
                         log::error!("Error reading from stdin: {}", e);
-                        break;
+                        return RemoteConnectionEnded::Exit;
                     }
                 }
             }
@@ -507,17 +671,29 @@ pub async fn run_remote_client_terminal_loop(
                         let new_size = os_input.get_terminal_size();
                         if let Err(e) = connections.control_ws.send(create_resize_message(new_size)).await {
                             log::error!("Failed to send resize message: {}", e);
-                            break;
+                            return RemoteConnectionEnded::Dropped;
                         }
                     }
                     crate::os_input_output::SignalEvent::Quit => {
-                        break;
+                        return RemoteConnectionEnded::Exit;
                     }
                 }
             }
 
+            _ = keepalive.tick() => {
+                if last_heard_from_server.elapsed() > REMOTE_KEEPALIVE_TIMEOUT {
+                    log::error!("No response from web server, connection lost");
+                    return RemoteConnectionEnded::Dropped;
+                }
+                if let Err(e) = connections.control_ws.send(Message::Ping(vec![])).await {
+                    log::error!("Failed to ping web server: {}", e);
+                    return RemoteConnectionEnded::Dropped;
+                }
+            }
+
             // Handle terminal messages
             terminal_msg = connections.terminal_ws.next() => {
+                last_heard_from_server = tokio::time::Instant::now();
                 match terminal_msg {
                     Some(Ok(Message::Text(text))) => {
                         let mut stdout = os_input.get_stdout_writer();
@@ -554,21 +730,22 @@ pub async fn run_remote_client_terminal_loop(
                         stdout.flush().expect("could not flush");
                     }
                     Some(Ok(Message::Close(_))) => {
-                        break;
+                        return RemoteConnectionEnded::Exit;
                     }
                     Some(Err(e)) => {
                         log::error!("Error: {}", e);
-                        break;
+                        return RemoteConnectionEnded::Dropped;
                     }
                     None => {
                         log::error!("Received empty message from web server");
-                        break;
+                        return RemoteConnectionEnded::Dropped;
                     }
                     _ => {}
                 }
             }
 
             control_msg = connections.control_ws.next() => {
+                last_heard_from_server = tokio::time::Instant::now();
                 match control_msg {
                     Some(Ok(Message::Text(msg))) => {
                         let deserialized_msg: Result<WebServerToWebClientControlMessage, _> =
@@ -603,21 +780,19 @@ pub async fn run_remote_client_terminal_loop(
 
                     }
                     Some(Ok(Message::Close(_))) => {
-                        break;
+                        return RemoteConnectionEnded::Exit;
                     }
                     Some(Err(e)) => {
                         log::error!("{}", e);
-                        break;
+                        return RemoteConnectionEnded::Dropped;
                     }
-                    None => break,
+                    None => return RemoteConnectionEnded::Dropped,
                     _ => {}
                 }
             }
 
         }
     }
-
-    Ok(None)
 }
 
 #[cfg(feature = "web_server_capability")]
@@ -645,6 +820,12 @@ pub fn start_remote_client(
         ca_cert.as_deref(),
         insecure,
     )?;
+    let reconnect = remote_attach::RemoteReconnect::new(
+        remote_session_url,
+        ca_cert.as_deref(),
+        insecure,
+        &connections,
+    )?;
 
     let reconnect_to_session = None;
     os_input.unset_raw_mode().unwrap();
@@ -697,10 +878,16 @@ pub fn start_remote_client(
         std::process::exit(exit_status);
     };
 
-    runtime.block_on(run_remote_client_terminal_loop(
+    if let Err(e) = runtime.block_on(run_remote_client_terminal_loop(
         os_input.clone(),
         connections,
-    ))?;
+        Some(reconnect),
+    )) {
+        reset_controlling_terminal_state(
+            format!("Lost connection to {}: {}", remote_session_url, e),
+            1,
+        );
+    }
 
     let exit_msg = String::from("Bye from Zellij!");
 
@@ -825,6 +1012,7 @@ pub fn start_client(
                 max_panes: cli_args.max_panes,
                 force_run_layout_commands: false,
                 cwd: None,
+                layout_args: cli_args.layout_args(),
             };
             (
                 ClientToServerMsg::AttachClient {
@@ -870,6 +1058,7 @@ pub fn start_client(
                 max_panes: cli_args.max_panes,
                 force_run_layout_commands: force_run_commands,
                 cwd,
+                layout_args: BTreeMap::new(),
             };
 
             os_input.update_session_name(name);
@@ -924,6 +1113,7 @@ pub fn start_client(
                 max_panes: cli_args.max_panes,
                 force_run_layout_commands: false,
                 cwd: layout_cwd,
+                layout_args: cli_args.layout_args(),
             };
 
             os_input.update_session_name(name);
@@ -1322,6 +1512,7 @@ pub fn start_server_detached(
                 max_panes: cli_args.max_panes,
                 force_run_layout_commands: force_run_commands,
                 cwd,
+                layout_args: BTreeMap::new(),
             };
 
             os_input.update_session_name(name);
@@ -1377,6 +1568,7 @@ pub fn start_server_detached(
                 max_panes: cli_args.max_panes,
                 force_run_layout_commands: false,
                 cwd: layout_cwd,
+                layout_args: cli_args.layout_args(),
             };
 
             os_input.update_session_name(name);
@@ -10,6 +10,11 @@ struct LoginRequest {
     remember_me: bool,
 }
 
+#[derive(Serialize)]
+struct SessionRequest<'a> {
+    session_name: &'a str,
+}
+
 #[derive(Deserialize)]
 pub struct SessionResponse {
     pub web_client_id: String,
@@ -17,6 +22,7 @@ pub struct SessionResponse {
 
 pub async fn authenticate(
     server_base_url: &str,
+    session_name: &str,
     auth_token: &str,
     remember_me: bool,
     ca_cert: Option<&std::path::Path>,
@@ -69,7 +75,10 @@ pub async fn authenticate(
                 .header("Content-Type", "application/json")
                 .header("User-Agent", "http-terminal-client/1.0")
                 .header("Accept", "application/json")
-                .body("{}".as_bytes().to_vec())
+                .body(
+                    serde_json::to_vec(&SessionRequest { session_name })
+                        .map_err(|e| RemoteClientError::Other(Box::new(e)))?,
+                )
                 .map_err(|e| RemoteClientError::Other(Box::new(e)))?,
         )
         .await
@@ -77,7 +86,7 @@ pub async fn authenticate(
 
     // Handle session response
     match session_response.status().as_u16() {
-        401 => return Err(RemoteClientError::Unauthorized),
+        401 | 403 => return Err(RemoteClientError::Unauthorized),
         status if !session_response.status().is_success() => {
             return Err(RemoteClientError::ConnectionFailed(format!(
                 "Server returned status {}",
@@ -106,6 +115,7 @@ pub async fn authenticate(
 
 pub async fn validate_session_token(
     server_base_url: &str,
+    session_name: &str,
     session_token: &str,
     ca_cert: Option<&std::path::Path>,
     insecure: bool,
@@ -125,7 +135,10 @@ pub async fn validate_session_token(
                 .header("Content-Type", "application/json")
                 .header("User-Agent", "http-terminal-client/1.0")
                 .header("Accept", "application/json")
-                .body("{}".as_bytes().to_vec())
+                .body(
+                    serde_json::to_vec(&SessionRequest { session_name })
+                        .map_err(|e| RemoteClientError::Other(Box::new(e)))?,
+                )
                 .map_err(|e| RemoteClientError::Other(Box::new(e)))?,
         )
         .await
@@ -133,6 +146,8 @@ pub async fn validate_session_token(
 
     match session_response.status().as_u16() {
         401 => Err(RemoteClientError::SessionTokenExpired),
+        // the token is valid but not scoped to this session
+        403 => Err(RemoteClientError::Unauthorized),
         status if !session_response.status().is_success() => Err(
             RemoteClientError::ConnectionFailed(format!("Server returned status {}", status)),
         ),
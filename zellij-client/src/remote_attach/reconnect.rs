@@ -0,0 +1,91 @@
+use super::websockets::WebSocketConnections;
+use super::{extract_server_url, remote_attach_with_session_token};
+use crate::RemoteClientError;
+use std::path::PathBuf;
+use std::time::Duration;
+use zellij_utils::remote_session_tokens;
+
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Everything needed to re-establish the websocket connections to a remote session after they
+/// dropped (eg. because the network went away), without prompting the user again
+#[derive(Debug, Clone)]
+pub struct RemoteReconnect {
+    remote_session_url: String,
+    server_url: String,
+    ca_cert: Option<PathBuf>,
+    insecure: bool,
+    session_token: Option<String>,
+}
+
+impl RemoteReconnect {
+    pub fn new(
+        remote_session_url: &str,
+        ca_cert: Option<&std::path::Path>,
+        insecure: bool,
+        connections: &WebSocketConnections,
+    ) -> Result<Self, RemoteClientError> {
+        Ok(RemoteReconnect {
+            remote_session_url: remote_session_url.to_owned(),
+            server_url: extract_server_url(remote_session_url)?,
+            ca_cert: ca_cert.map(|p| p.to_path_buf()),
+            insecure,
+            session_token: connections.session_token.clone(),
+        })
+    }
+
+    pub fn remote_session_url(&self) -> &str {
+        &self.remote_session_url
+    }
+
+    /// Make a single attempt at re-establishing the connections, preferring the session token
+    /// saved in `remote_session_tokens` (if the user asked us to remember it) and falling back to
+    /// the one we were authenticated with in this run
+    pub async fn reconnect(&self) -> Result<WebSocketConnections, RemoteClientError> {
+        let saved_session_token = remote_session_tokens::get_session_token(&self.server_url)
+            .ok()
+            .flatten();
+        let session_token = saved_session_token
+            .or_else(|| self.session_token.clone())
+            .ok_or(RemoteClientError::SessionTokenExpired)?;
+        remote_attach_with_session_token(
+            &self.remote_session_url,
+            &session_token,
+            self.ca_cert.as_deref(),
+            self.insecure,
+        )
+        .await
+    }
+}
+
+/// Exponential backoff between reconnection attempts (attempts start at 1)
+pub fn reconnect_delay(attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    INITIAL_RECONNECT_DELAY
+        .saturating_mul(1 << exponent)
+        .min(MAX_RECONNECT_DELAY)
+}
+
+/// A single reverse-video line drawn over the top row of the (stale) screen while we are
+/// disconnected - the whole screen is redrawn by the server once we reconnect
+pub fn reconnecting_overlay(
+    remote_session_url: &str,
+    attempt: u32,
+    delay: Duration,
+    cols: usize,
+) -> String {
+    let text = format!(
+        " Connection lost, reconnecting to {} (attempt {}, next try in {}s). Ctrl-c to exit. ",
+        remote_session_url,
+        attempt,
+        delay.as_secs_f32().ceil() as u64,
+    );
+    let text: String = text.chars().take(cols).collect();
+    let padding = cols.saturating_sub(text.chars().count());
+    format!(
+        "\u{1b}7\u{1b}[1;1H\u{1b}[0;7m{}{}\u{1b}[0m\u{1b}8",
+        text,
+        " ".repeat(padding)
+    )
+}
@@ -930,6 +930,88 @@ mod tests {
 
         tls_mock_server::shutdown_server(handle, server_task).await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_reconnect_without_remembered_token_reuses_session_cookie() {
+        let server_state = MockRemoteServerState::new();
+        let auth_token = "test-reconnect-token";
+        server_state.add_valid_token(auth_token);
+
+        let (port, server_handle) = start_mock_server(server_state.clone()).await;
+        let server_url = format!("http://127.0.0.1:{}/session-name", port);
+        let base_url = format!("http://127.0.0.1:{}", port);
+
+        setup_test_db(&base_url);
+
+        let connections = call_attach_to_remote_session(
+            server_url.clone(),
+            Some(auth_token.to_string()),
+            false,
+            false,
+        )
+        .await
+        .expect("Connection should succeed");
+        assert!(connections.session_token.is_some());
+
+        let reconnect = RemoteReconnect::new(&server_url, None, true, &connections).unwrap();
+        drop(connections);
+        let reconnected = reconnect.reconnect().await;
+        assert!(
+            reconnected.is_ok(),
+            "Should reconnect with the session cookie: {:?}",
+            reconnected.err()
+        );
+
+        let endpoints = server_state.get_endpoints_called();
+        assert_eq!(
+            endpoints.iter().filter(|e| *e == "/command/login").count(),
+            1,
+            "Reconnecting should not log in again"
+        );
+        assert_eq!(
+            endpoints.iter().filter(|e| *e == "/ws/terminal").count(),
+            2,
+            "Terminal WebSocket should be re-established"
+        );
+
+        server_handle.abort();
+        cleanup_test_db(&base_url);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_reconnect_with_expired_session_token_fails() {
+        let server_state = MockRemoteServerState::new();
+        let auth_token = "test-reconnect-expired-token";
+        server_state.add_valid_token(auth_token);
+
+        let (port, server_handle) = start_mock_server(server_state.clone()).await;
+        let server_url = format!("http://127.0.0.1:{}/session-name", port);
+        let base_url = format!("http://127.0.0.1:{}", port);
+
+        setup_test_db(&base_url);
+
+        let connections = call_attach_to_remote_session(
+            server_url.clone(),
+            Some(auth_token.to_string()),
+            true,
+            false,
+        )
+        .await
+        .expect("Connection should succeed");
+        let reconnect = RemoteReconnect::new(&server_url, None, true, &connections).unwrap();
+
+        server_state.session_tokens.lock().unwrap().clear();
+        let reconnected = reconnect.reconnect().await;
+        assert!(
+            matches!(reconnected, Err(RemoteClientError::SessionTokenExpired)),
+            "Should fail with expired session token"
+        );
+
+        server_handle.abort();
+        cleanup_test_db(&base_url);
+    }
 }
 
 // Tests that don't require the web_server_capability feature
@@ -949,3 +1031,40 @@ mod tests {
         assert_eq!(result.unwrap(), "https://example.com:8080");
     }
 }
+
+#[test]
+fn test_reconnect_delay_backs_off_exponentially_up_to_a_cap() {
+    assert_eq!(reconnect_delay(1), Duration::from_millis(500));
+    assert_eq!(reconnect_delay(2), Duration::from_secs(1));
+    assert_eq!(reconnect_delay(3), Duration::from_secs(2));
+    assert_eq!(reconnect_delay(7), Duration::from_secs(30));
+    assert_eq!(reconnect_delay(u32::MAX), Duration::from_secs(30));
+}
+
+#[test]
+fn test_reconnecting_overlay_fills_exactly_one_line() {
+    let overlay = reconnecting_overlay(
+        "https://example.com/my-session",
+        3,
+        Duration::from_secs(2),
+        200,
+    );
+    assert!(overlay.contains("reconnecting to https://example.com/my-session (attempt 3"));
+    assert!(overlay.contains("next try in 2s"));
+    assert!(
+        overlay.starts_with("\u{1b}7\u{1b}[1;1H"),
+        "Should save the cursor"
+    );
+    assert!(overlay.ends_with("\u{1b}8"), "Should restore the cursor");
+
+    let narrow_overlay = reconnecting_overlay(
+        "https://example.com/my-session",
+        3,
+        Duration::from_secs(2),
+        20,
+    );
+    assert_eq!(
+        narrow_overlay,
+        "\u{1b}7\u{1b}[1;1H\u{1b}[0;7m Connection lost, re\u{1b}[0m\u{1b}8"
+    );
+}
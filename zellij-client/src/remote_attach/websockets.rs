@@ -174,6 +174,9 @@ pub struct WebSocketConnections {
     pub terminal_ws: WebSocketStream<MaybeTls>,
     pub control_ws: WebSocketStream<MaybeTls>,
     pub web_client_id: String,
+    /// The session cookie the connections were authenticated with, kept so that dropped
+    /// connections can be re-established without prompting for a token again
+    pub session_token: Option<String>,
 }
 
 impl std::fmt::Debug for WebSocketConnections {
@@ -274,5 +277,6 @@ pub async fn establish_websocket_connections(
         terminal_ws,
         control_ws,
         web_client_id: web_client_id.to_owned(),
+        session_token: http_client.get_cookie("session_token"),
     })
 }
@@ -2,7 +2,8 @@
 //! stdin.
 //!
 //! This parser routes stdin bytes through a private `termwiz::InputParser`,
-//! classifies OSC / CSI-report events into `HostReply` variants, and lets
+//! classifies OSC / CSI-report events (and kitty graphics APC replies, which
+//! the termwiz parser does not know about) into `HostReply` variants, and lets
 //! all other bytes (keyboard input) pass through as a residue byte sequence
 //! that the caller feeds to the normal keyboard parser.
 
@@ -16,6 +17,9 @@ use zellij_utils::{
     vendored::termwiz::input::{InputEvent, InputParser},
 };
 
+/// The image id of the kitty graphics query sent at startup, the host echoes it in its reply
+pub const KITTY_GRAPHICS_QUERY_ID: u32 = 31;
+
 /// Describe the terminal implementation of synchronised output
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum SyncOutput {
@@ -61,6 +65,8 @@ pub enum HostReply {
     /// DSR 997 reply / unsolicited notification reporting the host
     /// terminal's color-palette theme mode (CSI 2031).
     HostTerminalThemeChanged(HostTerminalThemeMode),
+    /// The host answered our kitty graphics protocol query with `OK`.
+    KittyGraphicsSupported,
 }
 
 /// Retained alias for the pre-refactor type name used by other modules in
@@ -154,6 +160,22 @@ impl HostReply {
         }
         None
     }
+
+    /// Classify an APC payload (the bytes between the `ESC _` prefix and
+    /// the ST terminator) into a `HostReply`, if it is the reply to our
+    /// kitty graphics query (`G i=31 ; OK`).
+    pub fn from_apc_payload(payload: &[u8]) -> Option<HostReply> {
+        let s = std::str::from_utf8(payload).ok()?;
+        let (control_data, message) = s.strip_prefix('G')?.split_once(';')?;
+        let answers_our_query = control_data
+            .split(',')
+            .any(|key_value| key_value == format!("i={}", KITTY_GRAPHICS_QUERY_ID));
+        if answers_our_query && message == "OK" {
+            Some(HostReply::KittyGraphicsSupported)
+        } else {
+            None
+        }
+    }
 }
 
 /// The "slot" tracking state for a single forwarded query currently in
@@ -232,6 +254,8 @@ pub struct StdinAnsiParser {
     partial_osc: Vec<u8>,
     /// Same for CSI device-control reports.
     partial_csi: Vec<u8>,
+    /// Same for APC sequences (kitty graphics replies).
+    partial_apc: Vec<u8>,
 }
 
 impl std::fmt::Debug for StdinAnsiParser {
@@ -251,6 +275,7 @@ impl StdinAnsiParser {
             active_forward: None,
             partial_osc: Vec::new(),
             partial_csi: Vec::new(),
+            partial_apc: Vec::new(),
         }
     }
 
@@ -388,13 +413,16 @@ impl StdinAnsiParser {
             }
         }
         // Produce the residue: replay the input through a scratch parser
-        // that strips out OSC payloads and whitelisted CSI reports. All
-        // other bytes pass through unchanged. The walk is stateful so
-        // an OSC/CSI sequence split across `feed()` calls is buffered
-        // rather than leaking into residue.
-        residue.extend(self.strip_replies(bytes));
+        // that strips out OSC payloads, whitelisted CSI reports and APC
+        // sequences. All other bytes pass through unchanged. The walk is
+        // stateful so an OSC/CSI/APC sequence split across `feed()` calls is
+        // buffered rather than leaking into residue. APC replies are
+        // classified here, since the termwiz parser does not report them.
+        residue.extend(self.strip_replies(bytes, &mut out.replies));
         out.residue = residue;
-        out.has_partial_state = !self.partial_osc.is_empty() || !self.partial_csi.is_empty();
+        out.has_partial_state = !self.partial_osc.is_empty()
+            || !self.partial_csi.is_empty()
+            || !self.partial_apc.is_empty();
         out
     }
 
@@ -405,31 +433,37 @@ impl StdinAnsiParser {
     /// keyboard parser instead of being stuck forever waiting for a
     /// disambiguating byte that is never coming.
     pub fn finalize(&mut self) -> Vec<u8> {
-        let mut out = Vec::with_capacity(self.partial_osc.len() + self.partial_csi.len());
+        let mut out = Vec::with_capacity(
+            self.partial_osc.len() + self.partial_csi.len() + self.partial_apc.len(),
+        );
         out.append(&mut self.partial_osc);
         out.append(&mut self.partial_csi);
+        out.append(&mut self.partial_apc);
         out
     }
 
     /// Walk `bytes` (with any pending partial buffer prepended) and drop
-    /// any OSC/whitelisted-CSI sequences, returning the remaining bytes
+    /// any OSC/whitelisted-CSI/APC sequences, returning the remaining bytes
     /// verbatim (keyboard residue). This is a byte-level scrubber — it
-    /// does not produce events, only bytes.
+    /// only produces events for the APC replies it drops, which it pushes
+    /// onto `replies`.
     ///
     /// If the chunk ends mid-sequence, the unterminated tail is held in
-    /// `self.partial_osc` or `self.partial_csi` and prepended to the
-    /// next call's input — so the corresponding bytes never reach
-    /// residue (and never appear as spurious keypresses) while waiting
-    /// for the rest of the sequence.
-    fn strip_replies(&mut self, bytes: &[u8]) -> Vec<u8> {
+    /// `self.partial_osc`, `self.partial_csi` or `self.partial_apc` and
+    /// prepended to the next call's input — so the corresponding bytes
+    /// never reach residue (and never appear as spurious keypresses) while
+    /// waiting for the rest of the sequence.
+    fn strip_replies(&mut self, bytes: &[u8], replies: &mut Vec<HostReply>) -> Vec<u8> {
         // Prepend any pending partial. At most one of (partial_osc,
-        // partial_csi) is non-empty at any time — the previous walk
-        // either completed all sequences or stopped at exactly one
+        // partial_csi, partial_apc) is non-empty at any time — the previous
+        // walk either completed all sequences or stopped at exactly one
         // unterminated tail.
-        let mut working: Vec<u8> =
-            Vec::with_capacity(self.partial_osc.len() + self.partial_csi.len() + bytes.len());
+        let mut working: Vec<u8> = Vec::with_capacity(
+            self.partial_osc.len() + self.partial_csi.len() + self.partial_apc.len() + bytes.len(),
+        );
         working.append(&mut self.partial_osc);
         working.append(&mut self.partial_csi);
+        working.append(&mut self.partial_apc);
         working.extend_from_slice(bytes);
 
         let mut out = Vec::with_capacity(working.len());
@@ -486,8 +520,34 @@ impl StdinAnsiParser {
                     },
                 }
             }
+            // APC: ESC _ ... ESC \
+            if rest.len() >= 2 && rest[0] == 0x1b && rest[1] == b'_' {
+                match apc_status(rest) {
+                    SeqStatus::Complete(len) => {
+                        if let Some(reply) = HostReply::from_apc_payload(&rest[2..len - 2]) {
+                            replies.push(reply);
+                        }
+                        i += len;
+                        continue;
+                    },
+                    SeqStatus::NeedMore => {
+                        let tail = rest.to_vec();
+                        if tail.len() > PARTIAL_BUFFER_CAP_BYTES {
+                            out.extend_from_slice(&tail);
+                        } else {
+                            self.partial_apc = tail;
+                        }
+                        return out;
+                    },
+                    SeqStatus::Malformed => {
+                        out.push(working[i]);
+                        i += 1;
+                        continue;
+                    },
+                }
+            }
             // Lone trailing ESC at the tail — could be the start of
-            // either OSC or CSI; the next byte will disambiguate. Buffer
+            // either OSC, CSI or APC; the next byte will disambiguate. Buffer
             // it under partial_osc by convention; the next call's
             // walker re-routes based on the actual second byte.
             if rest.len() == 1 && rest[0] == 0x1b {
@@ -526,6 +586,26 @@ fn osc_status(buf: &[u8]) -> SeqStatus {
     SeqStatus::NeedMore
 }
 
+/// Walk an APC sequence starting at the head of `buf`. Unlike OSC, APC is
+/// only terminated by ST (ESC \).
+fn apc_status(buf: &[u8]) -> SeqStatus {
+    if buf.get(0) != Some(&0x1b) || buf.get(1) != Some(&b'_') {
+        return SeqStatus::Malformed;
+    }
+    let mut i = 2;
+    while i < buf.len() {
+        match buf[i] {
+            0x1b => match buf.get(i + 1) {
+                Some(&b'\\') => return SeqStatus::Complete(i + 2),
+                Some(_) => return SeqStatus::Malformed,
+                None => return SeqStatus::NeedMore,
+            },
+            _ => i += 1,
+        }
+    }
+    SeqStatus::NeedMore
+}
+
 /// Walk a whitelisted CSI report starting at the head of `buf`.
 fn csi_status(buf: &[u8]) -> SeqStatus {
     if buf.get(0) != Some(&0x1b) || buf.get(1) != Some(&b'[') {
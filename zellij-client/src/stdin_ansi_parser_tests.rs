@@ -135,6 +135,33 @@ fn unterminated_osc_within_single_chunk_is_buffered() {
     );
 }
 
+#[test]
+fn kitty_graphics_query_reply() {
+    let mut parser = StdinAnsiParser::new();
+    let (replies, residue) = feed_once(&mut parser, b"a\x1b_Gi=31;OK\x1b\\b");
+    assert_eq!(residue, b"ab", "kitty graphics reply should be consumed");
+    assert_eq!(replies.len(), 1);
+    assert!(matches!(replies[0], HostReply::KittyGraphicsSupported));
+}
+
+#[test]
+fn kitty_graphics_error_reply_and_split_reply() {
+    let mut parser = StdinAnsiParser::new();
+    let (replies, residue) = feed_once(&mut parser, b"\x1b_Gi=31;ENOTSUPPORTED:no\x1b\\");
+    assert!(residue.is_empty());
+    assert!(
+        replies.is_empty(),
+        "an error reply does not advertise support"
+    );
+
+    let (replies, residue) = feed_once(&mut parser, b"\x1b_Gi=31;O");
+    assert!(residue.is_empty(), "unterminated APC must be buffered");
+    assert!(replies.is_empty());
+    let (replies, residue) = feed_once(&mut parser, b"K\x1b\\");
+    assert!(residue.is_empty());
+    assert!(matches!(replies[..], [HostReply::KittyGraphicsSupported]));
+}
+
 #[test]
 fn forwarding_window_accumulates_and_barrier_closes() {
     let mut parser = StdinAnsiParser::new();
@@ -1,6 +1,6 @@
 use crate::keyboard_parser::{KittyKeyboardParser, KittyParseOutcome};
 use crate::os_input_output::ClientOsApi;
-use crate::stdin_ansi_parser::StdinAnsiParser;
+use crate::stdin_ansi_parser::{StdinAnsiParser, KITTY_GRAPHICS_QUERY_ID};
 #[cfg(windows)]
 use crate::stdin_handler_windows::enable_vt_input;
 use crate::InputInstruction;
@@ -160,14 +160,17 @@ pub(crate) fn stdin_loop(
                             // parser keeps its state so the next chunk's
                             // continuation completes the sequence.
                             match kitty_parser.feed(&residue) {
-                                KittyParseOutcome::Complete(key_with_modifier) => {
-                                    send_input_instructions
-                                        .send(InputInstruction::KeyWithModifierEvent(
-                                            key_with_modifier,
-                                            current_buffer.drain(..).collect(),
-                                            true,
-                                        ))
-                                        .unwrap();
+                                KittyParseOutcome::Complete(keys) => {
+                                    current_buffer.clear();
+                                    for (key_with_modifier, raw_bytes) in keys {
+                                        send_input_instructions
+                                            .send(InputInstruction::KeyWithModifierEvent(
+                                                key_with_modifier,
+                                                raw_bytes,
+                                                true,
+                                            ))
+                                            .unwrap();
+                                    }
                                     continue;
                                 },
                                 KittyParseOutcome::Incomplete | KittyParseOutcome::NoMatch => {},
@@ -280,6 +283,12 @@ fn build_startup_query_string() -> String {
     let mut query_string = String::from(
         "\u{1b}[14t\u{1b}[16t\u{1b}]11;?\u{1b}\u{5c}\u{1b}]10;?\u{1b}\u{5c}\u{1b}[?2026$p",
     );
+    // <ESC>_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA<ESC>\ => query kitty graphics support with a 1x1
+    // image, hosts that support it reply with OK
+    query_string.push_str(&format!(
+        "\u{1b}_Gi={},s=1,v=1,a=q,t=d,f=24;AAAA\u{1b}\u{5c}",
+        KITTY_GRAPHICS_QUERY_ID
+    ));
     // query colors
     // eg. <ESC>]4;5;?<ESC>\ => query color register number 5
     for i in 0..256 {
@@ -1,6 +1,6 @@
 use crate::os_input_output::{AsyncSignals, AsyncStdin, ClientOsApi, SignalEvent};
 use crate::remote_attach::websockets::MaybeTls;
-use crate::remote_attach::WebSocketConnections;
+use crate::remote_attach::{RemoteReconnect, WebSocketConnections};
 use crate::run_remote_client_terminal_loop;
 use crate::web_client::control_message::{
     WebClientToWebServerControlMessage, WebClientToWebServerControlMessagePayload,
@@ -351,6 +351,7 @@ async fn test_stdin_forwarded_to_terminal_websocket() {
         terminal_ws,
         control_ws,
         web_client_id: "test-stdin".to_string(),
+        session_token: None,
     };
 
     // Create mock OS API with controllable stdin
@@ -361,7 +362,9 @@ async fn test_stdin_forwarded_to_terminal_websocket() {
 
     // Spawn the async loop
     let loop_handle =
-        tokio::spawn(async move { run_remote_client_terminal_loop(os_input, connections).await });
+        tokio::spawn(
+            async move { run_remote_client_terminal_loop(os_input, connections, None).await },
+        );
 
     // Send stdin data
     let test_data = b"hello from stdin\n".to_vec();
@@ -423,6 +426,7 @@ async fn test_terminal_output_written_to_stdout() {
         terminal_ws,
         control_ws,
         web_client_id: "test-stdout".to_string(),
+        session_token: None,
     };
 
     let (_stdin_tx, stdin_rx) = mpsc::unbounded_channel();
@@ -433,7 +437,9 @@ async fn test_terminal_output_written_to_stdout() {
     let os_input = Box::new(os_input);
 
     let loop_handle =
-        tokio::spawn(async move { run_remote_client_terminal_loop(os_input, connections).await });
+        tokio::spawn(
+            async move { run_remote_client_terminal_loop(os_input, connections, None).await },
+        );
 
     // Send terminal output from server
     let test_output = "Hello from terminal";
@@ -498,6 +504,7 @@ async fn test_resize_signal_sends_control_message() {
         terminal_ws,
         control_ws,
         web_client_id: "test-resize".to_string(),
+        session_token: None,
     };
 
     let (_stdin_tx, stdin_rx) = mpsc::unbounded_channel();
@@ -506,7 +513,9 @@ async fn test_resize_signal_sends_control_message() {
     let os_input = Box::new(TestClientOsApi::new(stdin_rx, signal_rx));
 
     let loop_handle =
-        tokio::spawn(async move { run_remote_client_terminal_loop(os_input, connections).await });
+        tokio::spawn(
+            async move { run_remote_client_terminal_loop(os_input, connections, None).await },
+        );
 
     // Wait for initial resize message to be sent on startup
     tokio::time::sleep(Duration::from_millis(200)).await;
@@ -589,6 +598,7 @@ async fn test_quit_signal_exits_loop() {
         terminal_ws,
         control_ws,
         web_client_id: "test-quit".to_string(),
+        session_token: None,
     };
 
     let (_stdin_tx, stdin_rx) = mpsc::unbounded_channel();
@@ -597,7 +607,9 @@ async fn test_quit_signal_exits_loop() {
     let os_input = Box::new(TestClientOsApi::new(stdin_rx, signal_rx));
 
     let loop_handle =
-        tokio::spawn(async move { run_remote_client_terminal_loop(os_input, connections).await });
+        tokio::spawn(
+            async move { run_remote_client_terminal_loop(os_input, connections, None).await },
+        );
 
     // Send quit signal
     signal_tx.send(SignalEvent::Quit).unwrap();
@@ -644,6 +656,7 @@ async fn test_websocket_close_exits_loop() {
         terminal_ws,
         control_ws,
         web_client_id: "test-close".to_string(),
+        session_token: None,
     };
 
     let (_stdin_tx, stdin_rx) = mpsc::unbounded_channel();
@@ -652,7 +665,9 @@ async fn test_websocket_close_exits_loop() {
     let os_input = Box::new(TestClientOsApi::new(stdin_rx, signal_rx));
 
     let loop_handle =
-        tokio::spawn(async move { run_remote_client_terminal_loop(os_input, connections).await });
+        tokio::spawn(
+            async move { run_remote_client_terminal_loop(os_input, connections, None).await },
+        );
 
     // Send close message
     server
@@ -702,6 +717,7 @@ async fn test_control_message_handling() {
         terminal_ws,
         control_ws,
         web_client_id: "test-control".to_string(),
+        session_token: None,
     };
 
     let (_stdin_tx, stdin_rx) = mpsc::unbounded_channel();
@@ -712,7 +728,9 @@ async fn test_control_message_handling() {
     let os_input = Box::new(os_input);
 
     let loop_handle =
-        tokio::spawn(async move { run_remote_client_terminal_loop(os_input, connections).await });
+        tokio::spawn(
+            async move { run_remote_client_terminal_loop(os_input, connections, None).await },
+        );
 
     // Wait for initial resize message to be sent on startup
     tokio::time::sleep(Duration::from_millis(200)).await;
@@ -776,3 +794,100 @@ async fn test_control_message_handling() {
         .expect("Loop didn't exit")
         .unwrap();
 }
+
+async fn assert_reconnecting_loop_ignores_input_until(ignored_input: &[u8], ctrl_c: &[u8]) {
+    // a server that accepts both websockets and then goes away without closing them
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (drop_connections_tx, drop_connections_rx) = tokio::sync::oneshot::channel::<()>();
+    let server_handle = tokio::spawn(async move {
+        let mut server_sockets = vec![];
+        for _ in 0..2 {
+            let (tcp, _) = listener.accept().await.unwrap();
+            server_sockets.push(tokio_tungstenite::accept_async(tcp).await.unwrap());
+        }
+        let _ = drop_connections_rx.await;
+        drop(server_sockets);
+    });
+
+    let terminal_tcp = TcpStream::connect(format!("127.0.0.1:{}", port))
+        .await
+        .unwrap();
+    let (terminal_ws, _) = tokio_tungstenite::client_async_with_config(
+        format!("ws://127.0.0.1:{}/ws/terminal", port),
+        MaybeTls::Plain(terminal_tcp),
+        None,
+    )
+    .await
+    .unwrap();
+    let control_tcp = TcpStream::connect(format!("127.0.0.1:{}", port))
+        .await
+        .unwrap();
+    let (control_ws, _) = tokio_tungstenite::client_async_with_config(
+        format!("ws://127.0.0.1:{}/ws/control", port),
+        MaybeTls::Plain(control_tcp),
+        None,
+    )
+    .await
+    .unwrap();
+
+    let connections = WebSocketConnections {
+        terminal_ws,
+        control_ws,
+        web_client_id: "test-reconnect".to_string(),
+        session_token: Some("test-session-token".to_string()),
+    };
+    let remote_session_url = format!("http://127.0.0.1:{}/my-session", port);
+    let reconnect = RemoteReconnect::new(&remote_session_url, None, false, &connections).unwrap();
+
+    let (stdin_tx, stdin_rx) = mpsc::unbounded_channel();
+    let (_signal_tx, signal_rx) = mpsc::unbounded_channel();
+
+    let os_input = TestClientOsApi::new(stdin_rx, signal_rx);
+    let stdout_buffer = os_input.stdout_buffer.clone();
+    let os_input = Box::new(os_input);
+
+    let loop_handle = tokio::spawn(async move {
+        run_remote_client_terminal_loop(os_input, connections, Some(reconnect)).await
+    });
+
+    drop_connections_tx.send(()).unwrap();
+    server_handle.await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let stdout = stdout_buffer.lock().unwrap().clone();
+    let stdout_str = String::from_utf8_lossy(&stdout);
+    assert!(
+        stdout_str.contains(&format!(
+            "reconnecting to {} (attempt 1",
+            remote_session_url
+        )),
+        "Expected reconnecting overlay, got: '{}'",
+        stdout_str
+    );
+
+    // input other than Ctrl-c is ignored while reconnecting
+    stdin_tx.send(ignored_input.to_vec()).unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!loop_handle.is_finished(), "Loop should keep reconnecting");
+
+    stdin_tx.send(ctrl_c.to_vec()).unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(2), loop_handle)
+        .await
+        .expect("Loop didn't exit within timeout")
+        .expect("Loop panicked");
+    assert!(result.is_ok(), "Expected Ok result, got: {:?}", result);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_dropped_connection_shows_reconnecting_overlay() {
+    assert_reconnecting_loop_ignores_input_until(b"a", &[3]).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_kitty_keyboard_ctrl_c_exits_reconnecting_loop() {
+    // the terminal is in kitty keyboard mode, so keys arrive as CSI-u sequences
+    assert_reconnecting_loop_ignores_input_until(b"\x1b[97u", b"\x1b[99;5u").await;
+}
@@ -4,7 +4,7 @@ use axum::http::header::SET_COOKIE;
 use axum::{extract::Request, http::StatusCode, middleware::Next, response::Response};
 use axum_extra::extract::cookie::{Cookie, SameSite};
 use zellij_utils::web_authentication_tokens::{
-    hash_token, is_session_token_read_only, validate_session_token,
+    get_session_token_scope, hash_token, is_session_token_read_only, validate_session_token,
 };
 
 #[derive(Clone)]
@@ -13,6 +13,10 @@ pub struct SessionTokenHash(pub String);
 #[derive(Clone, Copy)]
 pub struct IsReadOnly(pub bool);
 
+/// Session names or glob patterns the token behind this request may attach to (empty for any)
+#[derive(Clone)]
+pub struct SessionScope(pub Vec<String>);
+
 pub async fn auth_middleware(request: Request, next: Next) -> Result<Response, StatusCode> {
     let cookies = parse_cookies(&request);
 
@@ -25,6 +29,8 @@ pub async fn auth_middleware(request: Request, next: Next) -> Result<Response, S
         Ok(true) => {
             // Check if this is a read-only token
             let is_read_only = is_session_token_read_only(&session_token).unwrap_or(true);
+            let session_scope = get_session_token_scope(&session_token)
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
             // Compute session token hash for client ownership verification
             let session_token_hash = hash_token(&session_token);
@@ -32,6 +38,7 @@ pub async fn auth_middleware(request: Request, next: Next) -> Result<Response, S
             // Store in request extensions for downstream handlers
             let mut request = request;
             request.extensions_mut().insert(IsReadOnly(is_read_only));
+            request.extensions_mut().insert(SessionScope(session_scope));
             request
                 .extensions_mut()
                 .insert(SessionTokenHash(session_token_hash));
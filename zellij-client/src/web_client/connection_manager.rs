@@ -5,6 +5,7 @@ use axum::extract::ws::{CloseFrame, Message};
 use std::sync::{atomic::AtomicBool, Arc};
 use tokio::sync::mpsc::UnboundedSender;
 use tokio_util::sync::CancellationToken;
+use zellij_utils::web_authentication_tokens::session_scope_allows;
 
 impl ConnectionTable {
     pub fn add_new_client(
@@ -13,13 +14,22 @@ impl ConnectionTable {
         client_os_api: Box<dyn ClientOsApi>,
         is_read_only: bool,
         session_token_hash: String,
+        session_scope: Vec<String>,
     ) {
         self.client_id_to_channels
             .insert(client_id.clone(), ClientChannels::new(client_os_api));
         self.client_read_only_status
             .insert(client_id.clone(), is_read_only);
         self.client_session_token_hash
-            .insert(client_id, session_token_hash);
+            .insert(client_id.clone(), session_token_hash);
+        self.client_session_scope.insert(client_id, session_scope);
+    }
+
+    pub fn is_session_in_client_scope(&self, client_id: &str, session_name: &str) -> bool {
+        self.client_session_scope
+            .get(client_id)
+            .map(|session_scope| session_scope_allows(session_scope, session_name))
+            .unwrap_or(false)
     }
 
     pub fn verify_client_ownership(&self, client_id: &str, session_token_hash: &str) -> bool {
@@ -88,6 +98,7 @@ impl ConnectionTable {
         }
         self.client_read_only_status.remove(client_id);
         self.client_session_token_hash.remove(client_id);
+        self.client_session_scope.remove(client_id);
     }
 
     pub fn get_should_not_reconnect_flag(&self, client_id: &str) -> Option<Arc<AtomicBool>> {
@@ -1,5 +1,7 @@
-use crate::web_client::authentication::{IsReadOnly, SessionTokenHash};
-use crate::web_client::types::{AppState, CreateClientIdResponse, LoginRequest, LoginResponse};
+use crate::web_client::authentication::{IsReadOnly, SessionScope, SessionTokenHash};
+use crate::web_client::types::{
+    AppState, CreateClientIdRequest, CreateClientIdResponse, LoginRequest, LoginResponse,
+};
 use crate::web_client::utils::{get_mime_type, parse_cookies};
 use axum::{
     extract::{Path as AxumPath, Request, State},
@@ -10,7 +12,10 @@ use axum::{
 use axum_extra::extract::cookie::{Cookie, SameSite};
 use include_dir;
 use uuid::Uuid;
-use zellij_utils::{consts::VERSION, web_authentication_tokens::create_session_token};
+use zellij_utils::{
+    consts::VERSION,
+    web_authentication_tokens::{create_session_token, session_scope_allows},
+};
 
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
@@ -26,6 +31,8 @@ const WEB_CLIENT_PAGE: &str = include_str!(concat!(
     "assets/index.html"
 ));
 
+const MAX_CREATE_CLIENT_REQUEST_SIZE: usize = 4096;
+
 const ASSETS_DIR: include_dir::Dir<'_> = include_dir::include_dir!("$CARGO_MANIFEST_DIR/assets");
 
 pub async fn serve_html(State(state): State<AppState>, request: Request) -> Html<String> {
@@ -122,6 +129,32 @@ pub async fn create_new_client(
             StatusCode::INTERNAL_SERVER_ERROR,
             Json("Missing session info".to_string()),
         ))?;
+    let session_scope = request.extensions().get::<SessionScope>().cloned().ok_or((
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json("Missing session info".to_string()),
+    ))?;
+
+    // the session this client is about to attach to, if it told us (the scope is enforced again
+    // whenever the client actually attaches to a session)
+    let body = axum::body::to_bytes(request.into_body(), MAX_CREATE_CLIENT_REQUEST_SIZE)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(e.to_string())))?;
+    let create_client_request: CreateClientIdRequest =
+        serde_json::from_slice(&body).unwrap_or_default();
+    if let Some(session_name) = create_client_request
+        .session_name
+        .filter(|session_name| !session_name.is_empty())
+    {
+        if !session_scope_allows(&session_scope.0, &session_name) {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(format!(
+                    "This token cannot access session \"{}\"",
+                    session_name
+                )),
+            ));
+        }
+    }
 
     let web_client_id = String::from(Uuid::new_v4());
     let os_input = state
@@ -134,6 +167,7 @@ pub async fn create_new_client(
         os_input,
         is_read_only,
         session_token_hash.0,
+        session_scope.0,
     );
 
     Ok(Json(CreateClientIdResponse {
@@ -214,12 +214,14 @@ pub fn parse_stdin(
 ) {
     if !session.explicitly_disable_kitty_keyboard_protocol {
         match session.kitty_parser.feed(buf) {
-            KittyParseOutcome::Complete(key_with_modifier) => {
-                os_input.send_to_server(ClientToServerMsg::Key {
-                    key: key_with_modifier.clone(),
-                    raw_bytes: buf.to_vec(),
-                    is_kitty_keyboard_protocol: true,
-                });
+            KittyParseOutcome::Complete(keys) => {
+                for (key_with_modifier, raw_bytes) in keys {
+                    os_input.send_to_server(ClientToServerMsg::Key {
+                        key: key_with_modifier,
+                        raw_bytes,
+                        is_kitty_keyboard_protocol: true,
+                    });
+                }
                 return;
             },
             KittyParseOutcome::Incomplete | KittyParseOutcome::NoMatch => {},
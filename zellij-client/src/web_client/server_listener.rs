@@ -102,6 +102,24 @@ pub fn zellij_server_listener(
 
                     let session_exists = session_manager.session_exists(&session_name).unwrap_or(false);
 
+                    let is_in_scope = connection_table
+                        .lock()
+                        .unwrap()
+                        .is_session_in_client_scope(&web_client_id, &session_name);
+                    if !is_in_scope {
+                        log::error!("Token is not allowed to access session {}.", session_name);
+                        client_connection_bus.send_control(
+                            WebServerToWebClientControlMessage::LogError {
+                                lines: vec![format!(
+                                    "This token cannot access session \"{}\"",
+                                    session_name
+                                )],
+                            },
+                        );
+                        client_connection_bus.close_connection();
+                        return;
+                    }
+
                     if is_read_only && !session_exists {
                         log::error!("Read only tokens cannot create new sessions.");
                         client_connection_bus.close_connection();
@@ -1,6 +1,7 @@
 use crate::os_input_output::ClientOsApi;
 use crate::spawn_server;
 
+use std::collections::BTreeMap;
 use std::{fs, path::PathBuf};
 use zellij_utils::{
     consts::session_layout_cache_file_name,
@@ -102,6 +103,7 @@ pub fn create_first_message(
             max_panes: None,
             force_run_layout_commands: false,
             cwd: None,
+            layout_args: BTreeMap::new(),
         };
 
         ClientToServerMsg::FirstClientConnected {
@@ -121,6 +123,7 @@ pub fn create_first_message(
             max_panes: None,
             force_run_layout_commands: false,
             cwd: None,
+            layout_args: BTreeMap::new(),
         };
         let is_web_client = true;
 
@@ -83,6 +83,7 @@ pub struct ConnectionTable {
     pub client_id_to_channels: HashMap<String, ClientChannels>,
     pub client_read_only_status: HashMap<String, bool>,
     pub client_session_token_hash: HashMap<String, String>,
+    pub client_session_scope: HashMap<String, Vec<String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -167,6 +168,11 @@ pub struct AppState {
     pub is_https: bool,
 }
 
+#[derive(Deserialize, Default)]
+pub struct CreateClientIdRequest {
+    pub session_name: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct CreateClientIdResponse {
     pub web_client_id: String,
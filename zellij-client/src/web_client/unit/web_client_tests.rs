@@ -22,7 +22,7 @@ use zellij_utils::{
     errors::ErrorContext,
     ipc::{ClientToServerMsg, ServerToClientMsg},
     pane_size::Size,
-    web_authentication_tokens::{create_token, delete_db, revoke_token},
+    web_authentication_tokens::{create_scoped_token, create_token, delete_db, revoke_token},
 };
 
 use serial_test::serial;
@@ -1857,6 +1857,212 @@ mod web_client_tests {
     }
 
     // Helper function to login and get session token
+    async fn start_test_server(
+        session_manager: Arc<MockSessionManager>,
+    ) -> (u16, tokio::task::JoinHandle<()>) {
+        let mock_os_api_factory = Arc::new(MockClientOsApiFactory::new());
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let port = addr.port();
+        let temp_config_path = std::env::temp_dir().join("test_config.kdl");
+        let server_handle = tokio::spawn(async move {
+            serve_web_client(
+                Config::default(),
+                Options::default(),
+                Some(temp_config_path),
+                listener,
+                None,
+                Some(session_manager),
+                Some(mock_os_api_factory),
+                addr.ip(),
+                port,
+            )
+            .await;
+        });
+        wait_for_server(port, Duration::from_secs(5))
+            .await
+            .expect("Server should start");
+        (port, server_handle)
+    }
+
+    async fn create_client_session_status(
+        port: u16,
+        session_token: &str,
+        body: serde_json::Value,
+    ) -> u16 {
+        let session_url = format!("http://127.0.0.1:{}/session", port);
+        let session_token = session_token.to_string();
+        let response = timeout(
+            Duration::from_secs(5),
+            tokio::task::spawn_blocking(move || {
+                isahc::Request::post(&session_url)
+                    .header("Cookie", format!("session_token={}", session_token))
+                    .header("Content-Type", "application/json")
+                    .body(body.to_string())
+                    .unwrap()
+                    .send()
+            }),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+        response.status().as_u16()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_scoped_token_cannot_create_client_for_out_of_scope_session() {
+        let _ = delete_db();
+
+        let (scoped_token, _) = create_scoped_token(
+            Some("scoped".to_string()),
+            false,
+            vec!["proj-*".to_string()],
+            None,
+        )
+        .unwrap();
+
+        let (port, server_handle) =
+            start_test_server(Arc::new(MockSessionManager::with_all_sessions_existing())).await;
+
+        let session_token = login_and_get_session_token(port, &scoped_token).await;
+
+        assert_eq!(
+            create_client_session_status(
+                port,
+                &session_token,
+                serde_json::json!({ "session_name": "other-session" })
+            )
+            .await,
+            403,
+            "Out of scope session should be refused"
+        );
+        assert_eq!(
+            create_client_session_status(
+                port,
+                &session_token,
+                serde_json::json!({ "session_name": "proj-frontend" })
+            )
+            .await,
+            200,
+            "In scope session should be allowed"
+        );
+        assert_eq!(
+            create_client_session_status(port, &session_token, serde_json::json!({})).await,
+            200,
+            "Clients that do not name a session up front are checked when they attach"
+        );
+
+        server_handle.abort();
+        let _ = delete_db();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_scoped_token_cannot_attach_to_out_of_scope_session() {
+        let _ = delete_db();
+
+        let (scoped_token, _) = create_scoped_token(
+            Some("scoped".to_string()),
+            false,
+            vec!["proj-*".to_string()],
+            None,
+        )
+        .unwrap();
+
+        let mock_session_manager = Arc::new(MockSessionManager::new());
+        let session_manager_for_verification = mock_session_manager.clone();
+        let (port, server_handle) = start_test_server(mock_session_manager).await;
+
+        let session_token = login_and_get_session_token(port, &scoped_token).await;
+
+        let mut sinks = vec![];
+        for session_name in ["other-session", "proj-backend"] {
+            let web_client_id = create_client_session(port, &session_token).await;
+            let terminal_ws_url = format!(
+                "ws://127.0.0.1:{}/ws/terminal/{}?web_client_id={}",
+                port, session_name, web_client_id
+            );
+            let (terminal_ws, _) = timeout(
+                Duration::from_secs(5),
+                connect_async_with_cookie(&terminal_ws_url, &session_token),
+            )
+            .await
+            .expect("Terminal WebSocket connection timed out")
+            .expect("Failed to connect to terminal WebSocket");
+            let (terminal_sink, _terminal_stream) = terminal_ws.split();
+            sinks.push(terminal_sink);
+        }
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        assert!(
+            !session_manager_for_verification.was_session_created("other-session"),
+            "Out of scope session should not be created"
+        );
+        assert!(
+            session_manager_for_verification
+                .get_first_message_for_session("other-session")
+                .is_none(),
+            "Should not attach to out of scope session"
+        );
+        assert!(
+            session_manager_for_verification.was_session_created("proj-backend"),
+            "In scope session should be created"
+        );
+
+        for mut sink in sinks {
+            let _ = sink.close().await;
+        }
+        server_handle.abort();
+        let _ = delete_db();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_expired_token_cannot_login() {
+        let _ = delete_db();
+
+        let (expired_token, _) = create_scoped_token(
+            Some("expired".to_string()),
+            false,
+            vec![],
+            Some(Duration::ZERO),
+        )
+        .unwrap();
+
+        let (port, server_handle) = start_test_server(Arc::new(MockSessionManager::new())).await;
+
+        let login_url = format!("http://127.0.0.1:{}/command/login", port);
+        let login_payload = serde_json::json!({
+            "auth_token": expired_token,
+            "remember_me": true
+        });
+        let response = timeout(
+            Duration::from_secs(5),
+            tokio::task::spawn_blocking(move || {
+                isahc::Request::post(&login_url)
+                    .header("Content-Type", "application/json")
+                    .body(login_payload.to_string())
+                    .unwrap()
+                    .send()
+            }),
+        )
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(response.status(), 401, "Expired token should not log in");
+
+        server_handle.abort();
+        let _ = delete_db();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
     async fn login_and_get_session_token(port: u16, auth_token: &str) -> String {
         let login_url = format!("http://127.0.0.1:{}/command/login", port);
         let login_payload = serde_json::json!({
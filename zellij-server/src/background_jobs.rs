@@ -6,6 +6,7 @@ use zellij_utils::consts::{
 #[allow(unused_imports)]
 use zellij_utils::data::{Event, HttpVerb, LayoutInfo, SessionInfo, WebServerStatus};
 use zellij_utils::errors::{prelude::*, BackgroundJobContext, ContextType};
+use zellij_utils::input::command::RestartPolicy;
 use zellij_utils::input::layout::RunPlugin;
 #[allow(unused_imports)]
 use zellij_utils::shared::parse_base_url;
@@ -20,7 +21,7 @@ use isahc::prelude::*;
 use isahc::AsyncReadResponseExt;
 use isahc::{config::RedirectPolicy, HttpClient, Request};
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -31,6 +32,7 @@ use std::sync::{
 use std::time::{Duration, Instant};
 use zellij_utils::consts::is_ipc_socket;
 
+use crate::panes::scrollback_store::{write_scrollback_files, ScrollbackRecord};
 use crate::panes::PaneId;
 use crate::plugins::{PluginId, PluginInstruction};
 use crate::pty::PtyInstruction;
@@ -46,6 +48,11 @@ pub enum BackgroundJob {
     ReportSessionInfo(String, SessionInfo),               // String - session name
     ReportPluginList(BTreeMap<PluginId, RunPlugin>),      // String - session name
     ReportLayoutInfo((String, BTreeMap<String, String>)), // BTreeMap<file_name, pane_contents>
+    PersistPaneScrollbacks(
+        String,                          // session name
+        Vec<(String, ScrollbackRecord)>, // file name, record to append
+        BTreeSet<String>,                // scrollback files still in use
+    ),
     RunCommand(
         PluginId,
         ClientId,
@@ -72,8 +79,9 @@ pub enum BackgroundJob {
     },
     FlashPaneBell(Vec<PaneId>),
     StopFlashPaneBell(Vec<PaneId>),
-    FlashTabBell(usize),     // usize = tab_id
-    StopFlashTabBell(usize), // usize = tab_id
+    FlashTabBell(usize),            // usize = tab_id
+    StopFlashTabBell(usize),        // usize = tab_id
+    RestartCommandPane(u32, usize), // u32 - terminal pane id, usize - restart attempt
     Exit,
 }
 
@@ -87,6 +95,9 @@ impl From<&BackgroundJob> for BackgroundJobContext {
             },
             BackgroundJob::ReportSessionInfo(..) => BackgroundJobContext::ReportSessionInfo,
             BackgroundJob::ReportLayoutInfo(..) => BackgroundJobContext::ReportLayoutInfo,
+            BackgroundJob::PersistPaneScrollbacks(..) => {
+                BackgroundJobContext::PersistPaneScrollbacks
+            },
             BackgroundJob::RunCommand(..) => BackgroundJobContext::RunCommand,
             BackgroundJob::WebRequest(..) => BackgroundJobContext::WebRequest,
             BackgroundJob::ReportPluginList(..) => BackgroundJobContext::ReportPluginList,
@@ -102,6 +113,7 @@ impl From<&BackgroundJob> for BackgroundJobContext {
             BackgroundJob::StopFlashPaneBell(..) => BackgroundJobContext::StopFlashPaneBell,
             BackgroundJob::FlashTabBell(..) => BackgroundJobContext::FlashTabBell,
             BackgroundJob::StopFlashTabBell(..) => BackgroundJobContext::StopFlashTabBell,
+            BackgroundJob::RestartCommandPane(..) => BackgroundJobContext::RestartCommandPane,
             BackgroundJob::Exit => BackgroundJobContext::Exit,
         }
     }
@@ -291,6 +303,12 @@ pub(crate) fn background_jobs_main(
                     .senders
                     .send_to_plugin(PluginInstruction::UpdateSessionSaveTime(timestamp_millis));
             },
+            BackgroundJob::PersistPaneScrollbacks(session_name, records, files_in_use) => {
+                let folder = session_info_folder_for_session(&session_name);
+                if let Err(e) = write_scrollback_files(&folder, records, &files_in_use) {
+                    log::error!("Failed to write pane scrollback files: {}", e);
+                }
+            },
             BackgroundJob::RunCommand(
                 plugin_id,
                 client_id,
@@ -621,6 +639,18 @@ pub(crate) fn background_jobs_main(
                     .senders
                     .send_to_screen(ScreenInstruction::SetTabBellFlash(tab_id, false));
             },
+            BackgroundJob::RestartCommandPane(terminal_pane_id, attempt) => {
+                runtime.spawn({
+                    let senders = bus.senders.clone();
+                    async move {
+                        tokio::time::sleep(RestartPolicy::restart_delay(attempt)).await;
+                        let _ = senders.send_to_screen(ScreenInstruction::RestartCommandPane(
+                            terminal_pane_id,
+                            attempt,
+                        ));
+                    }
+                });
+            },
             BackgroundJob::Exit => {
                 for loading_plugin in loading_plugins.values() {
                     loading_plugin.store(false, Ordering::SeqCst);
@@ -18,6 +18,8 @@ mod pane_groups;
 mod plugins;
 mod pty;
 mod pty_writer;
+mod recently_closed;
+mod recording;
 mod route;
 mod screen;
 mod session_layout_metadata;
@@ -34,6 +36,7 @@ use std::{
     path::PathBuf,
     sync::{Arc, RwLock},
     thread,
+    time::Duration,
 };
 use zellij_utils::envs;
 use zellij_utils::pane_size::Size;
@@ -53,7 +56,8 @@ use route::{route_thread_main, NotificationEnd};
 use zellij_utils::{
     channels::{self, ChannelWithContext, SenderWithContext},
     consts::{
-        DEFAULT_SCROLL_BUFFER_SIZE, SCROLL_BUFFER_SIZE, ZELLIJ_SEEN_RELEASE_NOTES_CACHE_FILE,
+        DEFAULT_KEY_SEQUENCE_TIMEOUT_MS, DEFAULT_SCROLL_BUFFER_SIZE, DISK_SCROLL_BUFFER_SIZE,
+        SCROLL_BUFFER_SIZE, ZELLIJ_SEEN_RELEASE_NOTES_CACHE_FILE,
     },
     data::{
         ConnectToSession, InputMode, KeyWithModifier, LayoutInfo, LayoutWithError, Style,
@@ -68,6 +72,7 @@ use zellij_utils::{
         keybinds::Keybinds,
         layout::{FloatingPaneLayout, Layout, PluginAlias, Run, RunPluginOrAlias},
         options::Options,
+        permission::PermissionCache,
         plugins::PluginAliases,
     },
     ipc::{ClientAttributes, ExitReason, ServerToClientMsg},
@@ -123,6 +128,8 @@ pub enum ServerInstruction {
         client_id: ClientId,
         keys_to_rebind: Vec<(InputMode, KeyWithModifier, Vec<Action>)>,
         keys_to_unbind: Vec<(InputMode, KeyWithModifier)>,
+        key_sequences_to_rebind: Vec<(InputMode, Vec<KeyWithModifier>, Vec<Action>)>,
+        key_sequences_to_unbind: Vec<(InputMode, Vec<KeyWithModifier>)>,
         write_config_to_disk: bool,
     },
     StartWebServer(ClientId),
@@ -236,6 +243,15 @@ impl SessionConfiguration {
             .and_then(|c| c.options.default_mode.clone())
             .unwrap_or_default()
     }
+    pub fn get_client_key_sequence_timeout(&self, client_id: &ClientId) -> Duration {
+        let timeout_ms = self
+            .runtime_config
+            .get(client_id)
+            .or(Some(&self.saved_config))
+            .and_then(|c| c.options.key_sequence_timeout)
+            .unwrap_or(DEFAULT_KEY_SEQUENCE_TIMEOUT_MS);
+        Duration::from_millis(timeout_ms)
+    }
     pub fn get_client_configuration(&self, client_id: &ClientId) -> Config {
         self.runtime_config
             .get(client_id)
@@ -272,6 +288,8 @@ impl SessionConfiguration {
         client_id: &ClientId,
         keys_to_rebind: Vec<(InputMode, KeyWithModifier, Vec<Action>)>,
         keys_to_unbind: Vec<(InputMode, KeyWithModifier)>,
+        key_sequences_to_rebind: Vec<(InputMode, Vec<KeyWithModifier>, Vec<Action>)>,
+        key_sequences_to_unbind: Vec<(InputMode, Vec<KeyWithModifier>)>,
     ) -> (Option<Config>, bool) {
         let mut full_reconfigured_config = None;
         let mut config_changed = false;
@@ -304,6 +322,23 @@ impl SessionConfiguration {
                         keys_in_mode.insert(key_with_modifier, actions);
                     }
                 }
+                for (input_mode, keys) in key_sequences_to_unbind {
+                    let key_sequences_in_mode = config
+                        .keybinds
+                        .get_input_mode_key_sequences_mut(&input_mode);
+                    if key_sequences_in_mode.remove(&keys).is_some() {
+                        config_changed = true;
+                    }
+                }
+                for (input_mode, keys, actions) in key_sequences_to_rebind {
+                    let key_sequences_in_mode = config
+                        .keybinds
+                        .get_input_mode_key_sequences_mut(&input_mode);
+                    if key_sequences_in_mode.get(&keys) != Some(&actions) {
+                        config_changed = true;
+                        key_sequences_in_mode.insert(keys, actions);
+                    }
+                }
                 if config_changed {
                     full_reconfigured_config = Some(config.clone());
                 }
@@ -1657,6 +1692,8 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 client_id,
                 keys_to_rebind,
                 keys_to_unbind,
+                key_sequences_to_rebind,
+                key_sequences_to_unbind,
                 write_config_to_disk,
             } => {
                 let (new_config, runtime_config_changed) = session_data
@@ -1665,7 +1702,13 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .as_mut()
                     .unwrap()
                     .session_configuration
-                    .rebind_keys(&client_id, keys_to_rebind, keys_to_unbind);
+                    .rebind_keys(
+                        &client_id,
+                        keys_to_rebind,
+                        keys_to_unbind,
+                        key_sequences_to_rebind,
+                        key_sequences_to_unbind,
+                    );
 
                 update_new_saved_config(
                     new_config,
@@ -1868,6 +1911,7 @@ fn init_session(
             .scroll_buffer_size
             .unwrap_or(DEFAULT_SCROLL_BUFFER_SIZE),
     );
+    let _ = DISK_SCROLL_BUFFER_SIZE.set(config_options.disk_scroll_buffer_size.unwrap_or(0));
 
     let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> = channels::unbounded();
     let to_screen = SenderWithContext::new(to_screen);
@@ -1892,6 +1936,8 @@ fn init_session(
     // Determine and initialize the data directory
     let data_dir = cli_assets.data_dir.unwrap_or_else(get_default_data_dir);
 
+    seed_plugin_permissions(&config);
+
     let serialization_interval = config_options.serialization_interval;
     let disable_session_metadata = config_options.disable_session_metadata.unwrap_or(false);
     let web_server_ip = config_options
@@ -2329,9 +2375,25 @@ fn update_new_saved_config(
     }
 }
 
+fn seed_plugin_permissions(config: &Config) {
+    if config.plugin_permissions.is_empty() {
+        return;
+    }
+    let mut permission_cache = PermissionCache::from_path_or_default(None);
+    if permission_cache.seed(&config.plugin_permissions) {
+        if let Err(e) = permission_cache.write_to_file() {
+            log::error!("Failed to write seeded plugin permissions: {}", e);
+        }
+    }
+}
+
 pub fn get_engine() -> Engine {
     log::info!("Loading plugins using Wasmi interpreter");
-    Engine::default()
+    let mut config = wasmi::Config::default();
+    // fuel lets us suspend plugins that spin forever in a callback rather than have them stall
+    // the plugin executor
+    config.consume_fuel(true);
+    Engine::new(&config)
 }
 
 // TODO: move elsewhere
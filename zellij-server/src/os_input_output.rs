@@ -1,4 +1,4 @@
-use crate::{panes::PaneId, ClientId};
+use crate::{panes::PaneId, recording::Recordings, ClientId};
 
 use interprocess::local_socket::Stream as LocalSocketStream;
 
@@ -267,6 +267,7 @@ pub struct ServerOsInputOutput {
     pty_backend: PtyBackendImpl,
     client_senders: Arc<Mutex<HashMap<ClientId, ClientSender>>>,
     cached_resizes: Arc<Mutex<Option<BTreeMap<u32, (u16, u16, Option<u16>, Option<u16>)>>>>,
+    recordings: Recordings,
 }
 
 /// A null `AsyncReader` for held panes (produces EOF immediately).
@@ -360,6 +361,10 @@ pub trait ServerOsApi: Send + Sync {
     fn clear_terminal_id(&self, terminal_id: u32) -> Result<()>;
     fn cache_resizes(&mut self) {}
     fn apply_cached_resizes(&mut self) {}
+    /// The asciicast recordings of terminal panes in progress, if recording is supported
+    fn recordings(&self) -> Option<Recordings> {
+        None
+    }
 }
 
 impl ServerOsApi for ServerOsInputOutput {
@@ -375,6 +380,7 @@ impl ServerOsApi for ServerOsInputOutput {
             cached_resizes.insert(id, (cols, rows, width_in_pixels, height_in_pixels));
             return Ok(());
         }
+        self.recordings.record_resize(id, cols, rows);
         self.pty_backend
             .set_terminal_size(id, cols, rows, width_in_pixels, height_in_pixels)
     }
@@ -682,6 +688,9 @@ impl ServerOsApi for ServerOsInputOutput {
             }
         }
     }
+    fn recordings(&self) -> Option<Recordings> {
+        Some(self.recordings.clone())
+    }
 }
 
 impl Clone for Box<dyn ServerOsApi> {
@@ -695,6 +704,7 @@ pub fn get_server_os_input() -> Result<ServerOsInputOutput, std::io::Error> {
         pty_backend: PtyBackendImpl::new()?,
         client_senders: Arc::new(Mutex::new(HashMap::new())),
         cached_resizes: Arc::new(Mutex::new(None)),
+        recordings: Recordings::default(),
     })
 }
 
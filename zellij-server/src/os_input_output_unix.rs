@@ -221,6 +221,7 @@ fn handle_openpty(
         }
         command
             .args(&cmd.args)
+            .envs(&cmd.env)
             .env("ZELLIJ_PANE_ID", &format!("{}", terminal_id))
             .pre_exec(move || -> io::Result<()> {
                 if libc::login_tty(pid_secondary) != 0 {
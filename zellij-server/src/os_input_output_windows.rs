@@ -169,11 +169,12 @@ fn build_command_line(cmd: &RunCommand) -> Vec<u16> {
 }
 
 /// Build a UTF-16 environment block (each entry `KEY=VALUE\0`, terminated by
-/// an extra `\0`) from the current process environment, adding
-/// `ZELLIJ_PANE_ID`.
-fn build_environment_block(terminal_id: u32) -> Vec<u16> {
+/// an extra `\0`) from the current process environment, overridden by the
+/// pane's own `env` and adding `ZELLIJ_PANE_ID`.
+fn build_environment_block(terminal_id: u32, env: &BTreeMap<String, String>) -> Vec<u16> {
     let mut block: Vec<u16> = Vec::new();
-    for (key, value) in std::env::vars() {
+    let inherited = std::env::vars().filter(|(key, _)| !env.contains_key(key));
+    for (key, value) in inherited.chain(env.clone()) {
         if key == "ZELLIJ_PANE_ID" {
             continue;
         }
@@ -307,7 +308,7 @@ fn spawn_child_process(
 
     // --- command line & environment ---
     let mut cmd_line = build_command_line(cmd);
-    let env_block = build_environment_block(terminal_id);
+    let env_block = build_environment_block(terminal_id, &cmd.env);
 
     let cwd: Option<Vec<u16>> = cmd.cwd.as_ref().and_then(|p| {
         if p.exists() && p.is_dir() {
@@ -4,6 +4,7 @@ use crate::panes::Row;
 
 use crate::panes::Selection;
 use crate::{
+    panes::kitty_graphics::{KittyGraphicsHost, KittyGraphicsHosts},
     panes::sixel::SixelImageStore,
     panes::terminal_character::{AnsiCode, CharacterStyles},
     panes::{LinkHandler, PaneId, TerminalCharacter, DEFAULT_STYLES, EMPTY_TERMINAL_CHARACTER},
@@ -57,10 +58,14 @@ fn adjust_styles_for_possible_selection(
     character_styles: CharacterStyles,
     chunk_y: usize,
     chunk_width: usize,
+    character_width: usize,
 ) -> CharacterStyles {
     chunk_selection_and_colors
         .iter()
-        .find(|hs| hs.selection.contains(chunk_y, chunk_width))
+        .find(|hs| {
+            hs.selection
+                .contains_character(chunk_y, chunk_width, character_width)
+        })
         .map(|hs| {
             let mut styles = character_styles;
             if let Some(bg) = hs.bg {
@@ -175,6 +180,7 @@ fn serialize_chunks_with_newlines(
                     *t_character.styles,
                     character_chunk.y,
                     chunk_width,
+                    t_character.width(),
                 ),
                 pane_default_fg,
                 pane_default_bg,
@@ -199,6 +205,7 @@ fn serialize_chunks(
     sixel_chunks: Option<&Vec<SixelImageChunk>>,
     link_handler: Option<&mut Rc<RefCell<LinkHandler>>>,
     sixel_image_store: Option<&mut SixelImageStore>,
+    kitty_graphics_host: Option<(&mut KittyGraphicsHost, SizeInPixels)>,
     styled_underlines: bool,
     osc8_hyperlinks: bool,
     max_size: Option<Size>,
@@ -207,6 +214,7 @@ fn serialize_chunks(
 
     let mut vte_output = String::new();
     let mut sixel_vte: Option<String> = None;
+    let mut kitty_graphics_host = kitty_graphics_host;
     let link_handler = link_handler.map(|l_h| l_h.borrow());
     for character_chunk in character_chunks {
         // Skip chunks that are completely outside the size bounds
@@ -240,6 +248,7 @@ fn serialize_chunks(
                     *t_character.styles,
                     character_chunk.y,
                     chunk_width,
+                    t_character.width(),
                 ),
                 pane_default_fg,
                 pane_default_bg,
@@ -256,6 +265,15 @@ fn serialize_chunks(
             chunk_width += t_character.width();
             vte_output.push(t_character.character);
         }
+        if let Some((kitty_graphics_host, _)) = kitty_graphics_host.as_mut() {
+            let sixel_vte = sixel_vte.get_or_insert_with(String::new);
+            kitty_graphics_host.delete_placements_under_text(
+                character_chunk.y,
+                character_chunk.x,
+                chunk_width,
+                sixel_vte,
+            );
+        }
     }
     if let Some(sixel_image_store) = sixel_image_store {
         if let Some(sixel_chunks) = sixel_chunks {
@@ -270,6 +288,22 @@ fn serialize_chunks(
                     }
                 }
 
+                if let Some((kitty_graphics_host, character_cell_size)) =
+                    kitty_graphics_host.as_mut()
+                {
+                    if sixel_image_store.is_kitty_image(sixel_chunk.sixel_image_id) {
+                        let sixel_vte = sixel_vte.get_or_insert_with(String::new);
+                        serialize_kitty_image_chunk(
+                            sixel_chunk,
+                            sixel_image_store,
+                            kitty_graphics_host,
+                            *character_cell_size,
+                            sixel_vte,
+                        )
+                        .with_context(err_context)?;
+                        continue;
+                    }
+                }
                 let serialized_sixel_image = sixel_image_store.serialize_image(
                     sixel_chunk.sixel_image_id,
                     sixel_chunk.sixel_image_pixel_x,
@@ -299,6 +333,51 @@ fn serialize_chunks(
     Ok(vte_output)
 }
 
+/// Places a chunk of a kitty graphics image on a host that supports them, one cell row at a time
+fn serialize_kitty_image_chunk(
+    sixel_chunk: &SixelImageChunk,
+    sixel_image_store: &mut SixelImageStore,
+    kitty_graphics_host: &mut KittyGraphicsHost,
+    character_cell_size: SizeInPixels,
+    vte_output: &mut String,
+) -> Result<()> {
+    let row_pixel_height = std::cmp::max(character_cell_size.height, 1);
+    let width_in_cells = sixel_chunk
+        .sixel_image_pixel_width
+        .div_ceil(std::cmp::max(character_cell_size.width, 1));
+    for (row, row_pixel_y) in (0..sixel_chunk.sixel_image_pixel_height)
+        .step_by(row_pixel_height)
+        .enumerate()
+    {
+        let serialized_row = sixel_image_store.serialize_kitty_image(
+            sixel_chunk.sixel_image_id,
+            sixel_chunk.sixel_image_pixel_x,
+            sixel_chunk.sixel_image_pixel_y + row_pixel_y,
+            sixel_chunk.sixel_image_pixel_width,
+            std::cmp::min(
+                row_pixel_height,
+                sixel_chunk.sixel_image_pixel_height - row_pixel_y,
+            ),
+        );
+        if let Some((pixel_size, rgba)) = serialized_row {
+            if pixel_size.width == 0 || pixel_size.height == 0 {
+                continue;
+            }
+            let cell_y = sixel_chunk.cell_y + row;
+            vte_goto_instruction(sixel_chunk.cell_x, cell_y, vte_output)?;
+            kitty_graphics_host.place_image_row(
+                sixel_chunk.cell_x,
+                cell_y,
+                width_in_cells,
+                pixel_size,
+                &rgba,
+                vte_output,
+            );
+        }
+    }
+    Ok(())
+}
+
 type AbsoluteMiddleStart = usize;
 type AbsoluteMiddleEnd = usize;
 type PadLeftEndBy = usize;
@@ -364,6 +443,7 @@ pub struct Output {
     osc8_hyperlinks: bool,
     pane_render_report: PaneRenderReport,
     pub collect_ansi_pane_contents: bool,
+    pub kitty_graphics_hosts: Rc<RefCell<KittyGraphicsHosts>>,
     cursor_coordinates: Option<(usize, usize)>,
 }
 
@@ -535,12 +615,17 @@ impl Output {
             }
 
             // append the actual vte
+            let mut kitty_graphics_hosts = self.kitty_graphics_hosts.borrow_mut();
+            let kitty_graphics_host = kitty_graphics_hosts
+                .get_mut(&client_id)
+                .zip(*self.character_cell_size.borrow());
             client_serialized_render_instructions.push_str(
                 &serialize_chunks(
                     client_character_chunks,
                     self.sixel_chunks.get(&client_id),
                     self.link_handler.as_mut(),
                     Some(&mut self.sixel_image_store.borrow_mut()),
+                    kitty_graphics_host,
                     self.styled_underlines,
                     self.osc8_hyperlinks,
                     None, // No size constraints for regular rendering
@@ -609,6 +694,7 @@ impl Output {
                     self.sixel_chunks.get(&client_id),
                     self.link_handler.as_mut(),
                     Some(&mut self.sixel_image_store.borrow_mut()),
+                    None,
                     self.styled_underlines,
                     self.osc8_hyperlinks,
                     max_size,
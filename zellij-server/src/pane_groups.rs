@@ -8,6 +8,8 @@ use crate::{panes::PaneId, pty::PtyInstruction, thread_bus::ThreadSenders, Clien
 
 pub struct PaneGroups {
     panes_in_group: HashMap<ClientId, Vec<PaneId>>,
+    // panes (across all tabs) that receive the input typed into any one of them
+    sync_group: Vec<PaneId>,
     senders: ThreadSenders,
 }
 
@@ -15,6 +17,7 @@ impl std::fmt::Debug for PaneGroups {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PaneGroups")
             .field("panes_in_group", &self.panes_in_group)
+            .field("sync_group", &self.sync_group)
             .finish_non_exhaustive()
     }
 }
@@ -23,6 +26,7 @@ impl PaneGroups {
     pub fn new(senders: ThreadSenders) -> Self {
         PaneGroups {
             panes_in_group: HashMap::new(),
+            sync_group: vec![],
             senders,
         }
     }
@@ -126,6 +130,36 @@ impl PaneGroups {
     pub fn override_groups_with(&mut self, new_pane_groups: HashMap<ClientId, Vec<PaneId>>) {
         self.panes_in_group = new_pane_groups;
     }
+    pub fn sync_group(&self) -> &[PaneId] {
+        &self.sync_group
+    }
+    pub fn is_in_sync_group(&self, pane_id: &PaneId) -> bool {
+        self.sync_group.contains(pane_id)
+    }
+    /// An empty sync group turns syncing off
+    pub fn set_sync_group(&mut self, mut pane_ids: Vec<PaneId>) {
+        let mut seen = HashSet::new();
+        pane_ids.retain(|p| seen.insert(*p));
+        self.sync_group = pane_ids;
+    }
+    /// Turns syncing off if it's on, otherwise makes the client's marked panes the sync group
+    /// and unmarks them. Returns whether syncing is now on.
+    pub fn toggle_sync_group(&mut self, client_id: &ClientId) -> bool {
+        if self.sync_group.is_empty() {
+            let marked_panes = self
+                .panes_in_group
+                .get_mut(client_id)
+                .map(std::mem::take)
+                .unwrap_or_default();
+            self.set_sync_group(marked_panes);
+        } else {
+            self.sync_group.clear();
+        }
+        !self.sync_group.is_empty()
+    }
+    pub fn retain_in_sync_group(&mut self, f: impl FnMut(&PaneId) -> bool) {
+        self.sync_group.retain(f);
+    }
     fn should_launch_plugin(
         &self,
         previous_groups: &HashMap<ClientId, Vec<PaneId>>,
@@ -498,4 +532,48 @@ mod tests {
 
         assert!(!pane_groups.should_launch_plugin(&previous_groups, &client_id));
     }
+
+    #[test]
+    fn set_sync_group_deduplicates_panes() {
+        let mut pane_groups = create_test_pane_groups();
+        pane_groups.set_sync_group(vec![
+            PaneId::Terminal(1),
+            PaneId::Terminal(2),
+            PaneId::Terminal(1),
+        ]);
+        assert_eq!(
+            pane_groups.sync_group(),
+            &[PaneId::Terminal(1), PaneId::Terminal(2)]
+        );
+        assert!(pane_groups.is_in_sync_group(&PaneId::Terminal(2)));
+        assert!(!pane_groups.is_in_sync_group(&PaneId::Terminal(3)));
+    }
+
+    #[test]
+    fn toggle_sync_group_moves_marked_panes_to_sync_group() {
+        let mut pane_groups = create_test_pane_groups();
+        let client_id: ClientId = 1;
+        let screen_size = create_test_screen_size();
+
+        pane_groups.add_pane_id_to_group(PaneId::Terminal(1), screen_size, &client_id);
+        pane_groups.add_pane_id_to_group(PaneId::Terminal(2), screen_size, &client_id);
+
+        assert!(pane_groups.toggle_sync_group(&client_id));
+        assert_eq!(
+            pane_groups.sync_group(),
+            &[PaneId::Terminal(1), PaneId::Terminal(2)]
+        );
+        assert!(pane_groups.get_client_pane_group(&client_id).is_empty());
+
+        assert!(!pane_groups.toggle_sync_group(&client_id));
+        assert!(pane_groups.sync_group().is_empty());
+    }
+
+    #[test]
+    fn toggle_sync_group_without_marked_panes_stays_off() {
+        let mut pane_groups = create_test_pane_groups();
+        let client_id: ClientId = 1;
+        assert!(!pane_groups.toggle_sync_group(&client_id));
+        assert!(pane_groups.sync_group().is_empty());
+    }
 }
@@ -0,0 +1,401 @@
+//! Copy mode: a cursor moved through a pane and its scrollback with vi-like motions, selecting
+//! text on the way so that it can be copied without a mouse.
+
+use crate::panes::{Grid, Row, Selection};
+use zellij_utils::input::actions::{CopyModeMotion, CopySelectionKind};
+use zellij_utils::position::Position;
+
+// how many lines above and below the cursor word motions look at
+const WORD_MOTION_LINES: isize = 500;
+
+/// The copy mode cursor of a grid and where its selection started. Positions use the same
+/// coordinates as `Selection` (negative lines are in the scrollback)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyModeCursor {
+    pub position: Position,
+    // the column to go back to when moving up and down through shorter lines
+    preferred_column: usize,
+    selection_start: Option<(Position, CopySelectionKind)>,
+}
+
+impl CopyModeCursor {
+    pub fn new(position: Position) -> Self {
+        CopyModeCursor {
+            position,
+            preferred_column: position.column(),
+            selection_start: None,
+        }
+    }
+    pub fn move_up(&mut self, lines: usize) {
+        self.position.line.0 -= lines as isize;
+        if let Some((selection_start, _)) = self.selection_start.as_mut() {
+            selection_start.line.0 -= lines as isize;
+        }
+    }
+    pub fn move_down(&mut self, lines: usize) {
+        self.position.line.0 += lines as isize;
+        if let Some((selection_start, _)) = self.selection_start.as_mut() {
+            selection_start.line.0 += lines as isize;
+        }
+    }
+    /// Starts selecting from the cursor, stops selecting if already selecting with this kind or
+    /// changes the kind of the current selection otherwise (like vim's visual modes)
+    pub fn toggle_selection(&mut self, kind: CopySelectionKind) {
+        self.selection_start = match self.selection_start {
+            Some((_, current_kind)) if current_kind == kind => None,
+            Some((selection_start, _)) => Some((selection_start, kind)),
+            None => Some((self.position, kind)),
+        };
+    }
+    pub fn clear_selection(&mut self) {
+        self.selection_start = None;
+    }
+    /// The text between the cursor and where the selection started, both included
+    pub fn selection(&self, width: usize) -> Option<Selection> {
+        let (selection_start, kind) = self.selection_start?;
+        let (first, last) = if selection_start <= self.position {
+            (selection_start, self.position)
+        } else {
+            (self.position, selection_start)
+        };
+        let mut selection = Selection::default();
+        match kind {
+            CopySelectionKind::Character => selection.select(
+                first,
+                Position::new(last.line() as i32, last.column() as u16 + 1),
+                false,
+            ),
+            CopySelectionKind::Line => selection.select(
+                Position::new(first.line() as i32, 0),
+                Position::new(last.line() as i32, width as u16),
+                false,
+            ),
+            CopySelectionKind::Block => {
+                let start_column = std::cmp::min(first.column(), last.column());
+                let end_column = std::cmp::max(first.column(), last.column()) + 1;
+                selection.select(
+                    Position::new(first.line() as i32, start_column as u16),
+                    Position::new(last.line() as i32, end_column as u16),
+                    true,
+                )
+            },
+        }
+        Some(selection)
+    }
+    pub fn coordinates_in_viewport(&self, width: usize, height: usize) -> Option<(usize, usize)> {
+        let (line, column) = (self.position.line(), self.position.column());
+        if line < 0 || line as usize >= height || column >= width {
+            None
+        } else {
+            Some((column, line as usize))
+        }
+    }
+}
+
+// vi's word classes: whitespace, keyword characters and everything else
+fn character_class(character: char) -> u8 {
+    if character.is_whitespace() {
+        0
+    } else if character.is_alphanumeric() || character == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+// the starting column of every character in the row, an empty row has a single empty cell so
+// that the cursor has a place on it
+fn row_cells(row: Option<&Row>) -> Vec<(usize, char)> {
+    let mut cells = vec![];
+    let mut column = 0;
+    for terminal_character in row.iter().flat_map(|row| row.columns.iter()) {
+        cells.push((column, terminal_character.character));
+        column += terminal_character.width();
+    }
+    if cells.is_empty() {
+        cells.push((0, ' '));
+    }
+    cells
+}
+
+impl Grid {
+    pub fn is_in_copy_mode(&self) -> bool {
+        self.copy_mode_cursor.is_some()
+    }
+    /// Places the copy mode cursor on the active search result if there is one, and otherwise
+    /// (when not already in copy mode) on the terminal cursor
+    pub fn enter_copy_mode(&mut self) {
+        let active_search_result = self.search_results.active.map(|active| active.start);
+        match (self.copy_mode_cursor.as_mut(), active_search_result) {
+            (Some(copy_mode_cursor), Some(position)) => {
+                copy_mode_cursor.position = position;
+                copy_mode_cursor.preferred_column = position.column();
+            },
+            (Some(_), None) => {},
+            (None, position) => {
+                let position = position.unwrap_or_else(|| match self.cursor_coordinates() {
+                    Some((x, y, _)) => Position::new(y as i32, x as u16),
+                    None => Position::new(self.viewport.len().saturating_sub(1) as i32, 0),
+                });
+                self.copy_mode_cursor = Some(CopyModeCursor::new(position));
+            },
+        }
+        self.update_copy_mode_selection();
+    }
+    pub fn exit_copy_mode(&mut self) {
+        if self.copy_mode_cursor.take().is_some() {
+            self.reset_selection();
+            self.output_buffer.update_all_lines();
+        }
+    }
+    pub fn copy_mode_move(&mut self, motion: CopyModeMotion) {
+        if self.copy_mode_cursor.is_none() {
+            self.enter_copy_mode();
+        }
+        self.scroll_copy_mode_cursor_into_view();
+        let Some(mut copy_mode_cursor) = self.copy_mode_cursor else {
+            return;
+        };
+        let Position { line, column } = copy_mode_cursor.position;
+        let (line, column) = (line.0, column.0);
+        match motion {
+            CopyModeMotion::Up | CopyModeMotion::Down => {
+                let line = match motion {
+                    CopyModeMotion::Up if line == 0 && self.copy_mode_scroll_up() => 0,
+                    CopyModeMotion::Up => line.saturating_sub(1).max(0),
+                    _ if line as usize + 1 >= self.viewport.len().max(1)
+                        && self.copy_mode_scroll_down() =>
+                    {
+                        line
+                    },
+                    _ => std::cmp::min(line + 1, self.viewport.len().max(1) as isize - 1),
+                };
+                let column = self.copy_mode_column_in_line(line, copy_mode_cursor.preferred_column);
+                copy_mode_cursor.position = Position::new(line as i32, column as u16);
+            },
+            CopyModeMotion::Left | CopyModeMotion::Right => {
+                let cells = row_cells(self.copy_mode_row(line));
+                let index = cells
+                    .iter()
+                    .rposition(|(cell_column, _)| *cell_column <= column)
+                    .unwrap_or(0);
+                let index = match motion {
+                    CopyModeMotion::Left => index.saturating_sub(1),
+                    _ => std::cmp::min(index + 1, cells.len() - 1),
+                };
+                copy_mode_cursor.position = Position::new(line as i32, cells[index].0 as u16);
+                copy_mode_cursor.preferred_column = cells[index].0;
+            },
+            CopyModeMotion::LineStart => {
+                copy_mode_cursor.position = Position::new(line as i32, 0);
+                copy_mode_cursor.preferred_column = 0;
+            },
+            CopyModeMotion::LineEnd => {
+                let cells = row_cells(self.copy_mode_row(line));
+                let last_character_column = cells
+                    .iter()
+                    .rev()
+                    .find(|(_, character)| !character.is_whitespace())
+                    .or_else(|| cells.first())
+                    .map(|(cell_column, _)| *cell_column)
+                    .unwrap_or(0);
+                copy_mode_cursor.position =
+                    Position::new(line as i32, last_character_column as u16);
+                copy_mode_cursor.preferred_column = last_character_column;
+            },
+            CopyModeMotion::WordForward
+            | CopyModeMotion::WordBackward
+            | CopyModeMotion::WordEnd => {
+                let position = self.copy_mode_word_motion(copy_mode_cursor.position, motion);
+                copy_mode_cursor.position = position;
+                copy_mode_cursor.preferred_column = position.column();
+            },
+            CopyModeMotion::Top => {
+                self.scroll_to_top();
+                let column = self.copy_mode_column_in_line(0, copy_mode_cursor.preferred_column);
+                copy_mode_cursor.position = Position::new(0, column as u16);
+            },
+            CopyModeMotion::Bottom => {
+                self.reset_viewport();
+                let line = self.viewport.len().saturating_sub(1);
+                let column =
+                    self.copy_mode_column_in_line(line as isize, copy_mode_cursor.preferred_column);
+                copy_mode_cursor.position = Position::new(line as i32, column as u16);
+            },
+        }
+        // scrolling moved the previous cursor along with the content, the new one was placed
+        // relative to the viewport after scrolling - so we only keep its selection start
+        if let Some(current_cursor) = self.copy_mode_cursor.as_mut() {
+            current_cursor.position = copy_mode_cursor.position;
+            current_cursor.preferred_column = copy_mode_cursor.preferred_column;
+        }
+        self.scroll_copy_mode_cursor_into_view();
+        self.update_copy_mode_selection();
+    }
+    pub fn copy_mode_select(&mut self, kind: CopySelectionKind) {
+        if self.copy_mode_cursor.is_none() {
+            self.enter_copy_mode();
+        }
+        if let Some(copy_mode_cursor) = self.copy_mode_cursor.as_mut() {
+            copy_mode_cursor.toggle_selection(kind);
+        }
+        self.update_copy_mode_selection();
+    }
+    /// Moves the copy mode cursor to the start of the active search result, if any
+    pub fn move_copy_mode_cursor_to_active_search_result(&mut self) {
+        if let (Some(copy_mode_cursor), Some(active)) =
+            (self.copy_mode_cursor.as_mut(), self.search_results.active)
+        {
+            copy_mode_cursor.position = active.start;
+            copy_mode_cursor.preferred_column = active.start.column();
+            self.update_copy_mode_selection();
+        }
+    }
+    fn update_copy_mode_selection(&mut self) {
+        let Some(copy_mode_cursor) = self.copy_mode_cursor else {
+            return;
+        };
+        let old_selection = self.selection;
+        match copy_mode_cursor.selection(self.width) {
+            Some(selection) => self.selection = selection,
+            None => self.selection.reset(),
+        }
+        self.update_selected_lines(&old_selection, &self.selection.clone());
+        self.mark_for_rerender();
+    }
+    fn scroll_copy_mode_cursor_into_view(&mut self) {
+        // scrolling moves the cursor along with the content, see `move_up`/`move_down`
+        while self
+            .copy_mode_cursor
+            .map(|copy_mode_cursor| copy_mode_cursor.position.line() < 0)
+            .unwrap_or(false)
+        {
+            if !self.copy_mode_scroll_up() {
+                break;
+            }
+        }
+        while self
+            .copy_mode_cursor
+            .map(|copy_mode_cursor| copy_mode_cursor.position.line() >= self.height as isize)
+            .unwrap_or(false)
+        {
+            if !self.copy_mode_scroll_down() {
+                break;
+            }
+        }
+        // the cursor could still be out of bounds if the content it was on is gone (eg. it was
+        // dropped from the scrollback)
+        let last_line = self.viewport.len().max(1) as isize - 1;
+        if let Some(copy_mode_cursor) = self.copy_mode_cursor.as_mut() {
+            let line = copy_mode_cursor.position.line();
+            if line < 0 || line > last_line {
+                copy_mode_cursor.position.line.0 = line.clamp(0, last_line);
+            }
+        }
+    }
+    // returns false if there was nothing to scroll to
+    fn copy_mode_scroll_up(&mut self) -> bool {
+        let lines_below_count = self.lines_below.len();
+        self.scroll_up_one_line();
+        self.lines_below.len() != lines_below_count
+    }
+    fn copy_mode_scroll_down(&mut self) -> bool {
+        let lines_below_count = self.lines_below.len();
+        self.scroll_down_one_line();
+        self.lines_below.len() != lines_below_count
+    }
+    fn copy_mode_row(&self, line: isize) -> Option<&Row> {
+        if line < 0 {
+            let index = self.lines_above.len().checked_sub(line.unsigned_abs())?;
+            self.lines_above.get(index)
+        } else if (line as usize) < self.viewport.len() {
+            self.viewport.get(line as usize)
+        } else {
+            self.lines_below.get(line as usize - self.viewport.len())
+        }
+    }
+    // the start of the character at this column, or of the last one if the line is shorter
+    fn copy_mode_column_in_line(&self, line: isize, column: usize) -> usize {
+        row_cells(self.copy_mode_row(line))
+            .iter()
+            .rev()
+            .find(|(cell_column, _)| *cell_column <= column)
+            .map(|(cell_column, _)| *cell_column)
+            .unwrap_or(0)
+    }
+    fn copy_mode_word_motion(&self, position: Position, motion: CopyModeMotion) -> Position {
+        let first_line = std::cmp::max(
+            position.line() - WORD_MOTION_LINES,
+            -(self.lines_above.len() as isize),
+        );
+        let last_line = std::cmp::min(
+            position.line() + WORD_MOTION_LINES,
+            (self.viewport.len() + self.lines_below.len()) as isize - 1,
+        );
+        // the characters around the cursor as a single stream, lines are separated by a
+        // whitespace unless they wrap into the next one
+        let mut cells: Vec<(Option<Position>, char)> = vec![];
+        for line in first_line..=last_line {
+            let row = self.copy_mode_row(line);
+            let next_row_continues_this_one = self
+                .copy_mode_row(line + 1)
+                .map(|next_row| !next_row.is_canonical)
+                .unwrap_or(false);
+            for (column, character) in row_cells(row) {
+                cells.push((Some(Position::new(line as i32, column as u16)), character));
+            }
+            if !next_row_continues_this_one {
+                cells.push((None, '\n'));
+            }
+        }
+        let Some(mut index) = cells.iter().rposition(|(cell_position, _)| {
+            cell_position
+                .map(|cell_position| cell_position <= position)
+                .unwrap_or(false)
+        }) else {
+            return position;
+        };
+        let class_at = |index: usize| character_class(cells[index].1);
+        let last_index = cells.len() - 1;
+        match motion {
+            CopyModeMotion::WordForward => {
+                let class = class_at(index);
+                while index < last_index && class != 0 && class_at(index) == class {
+                    index += 1;
+                }
+                while index < last_index && class_at(index) == 0 {
+                    index += 1;
+                }
+            },
+            CopyModeMotion::WordEnd => {
+                if index < last_index {
+                    index += 1;
+                }
+                while index < last_index && class_at(index) == 0 {
+                    index += 1;
+                }
+                let class = class_at(index);
+                while index < last_index && class_at(index + 1) == class {
+                    index += 1;
+                }
+            },
+            _ => {
+                index = index.saturating_sub(1);
+                while index > 0 && class_at(index) == 0 {
+                    index -= 1;
+                }
+                let class = class_at(index);
+                while index > 0 && class_at(index - 1) == class {
+                    index -= 1;
+                }
+            },
+        }
+        // we might have stopped on a line separator at either end of the stream
+        cells[..=index]
+            .iter()
+            .rev()
+            .chain(cells[index..].iter())
+            .find_map(|(cell_position, _)| *cell_position)
+            .unwrap_or(position)
+    }
+}
@@ -5,7 +5,7 @@ use zellij_utils::{
 };
 
 use crate::resize_pty;
-use crate::tab::{pane_info_for_pane, Pane};
+use crate::tab::{pane_info_for_pane, Pane, PaneHints};
 use floating_pane_grid::FloatingPaneGrid;
 
 use crate::{
@@ -376,11 +376,14 @@ impl FloatingPanes {
         }
         Ok(())
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &mut self,
         output: &mut Output,
         mouse_hover_pane_id: &HashMap<ClientId, PaneId>,
         current_pane_group: HashMap<ClientId, Vec<PaneId>>,
+        sync_group: &[PaneId],
+        pane_hints: &HashMap<ClientId, PaneHints>,
         client_id_override: Option<ClientId>,
         help_text_visible: &HashMap<ClientId, bool>,
     ) -> Result<()> {
@@ -471,6 +474,8 @@ impl FloatingPanes {
                 true,
                 mouse_hover_pane_id,
                 current_pane_group.clone(),
+                sync_group,
+                pane_hints,
                 show_help_text,
             );
             for client_id in &connected_clients {
@@ -1167,6 +1172,9 @@ impl FloatingPanes {
     pub fn get_panes(&self) -> impl Iterator<Item = (&PaneId, &Box<dyn Pane>)> {
         self.panes.iter()
     }
+    pub fn get_panes_mut(&mut self) -> impl Iterator<Item = (&PaneId, &mut Box<dyn Pane>)> {
+        self.panes.iter_mut()
+    }
     pub fn visible_panes_count(&self) -> usize {
         self.panes.len()
     }
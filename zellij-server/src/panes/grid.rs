@@ -1,3 +1,8 @@
+use super::kitty_graphics::{
+    KittyGraphicsAction, KittyGraphicsCommand, KittyGraphicsParser, KittyGrid, KittyImage,
+};
+use super::kitty_keyboard::KittyKeyboardFlagsStack;
+use super::shell_integration::{PromptMark, PromptMarkKind, VisiblePromptJump};
 use super::sixel::{PixelRect, SixelGrid, SixelImageStore};
 use std::borrow::Cow;
 use std::cell::RefCell;
@@ -19,11 +24,12 @@ use std::{
 
 use vte;
 use zellij_utils::{
-    consts::{DEFAULT_SCROLL_BUFFER_SIZE, SCROLL_BUFFER_SIZE},
+    consts::{DEFAULT_SCROLL_BUFFER_SIZE, DISK_SCROLL_BUFFER_SIZE, SCROLL_BUFFER_SIZE},
     data::{Palette, PaletteColor, Styling},
     input::mouse::{MouseEvent, MouseEventType},
     pane_size::SizeInPixels,
     position::Position,
+    vendored::termwiz::input::KittyKeyboardFlags,
 };
 
 const TABSTOP_WIDTH: usize = 8; // TODO: is this always right?
@@ -113,11 +119,16 @@ use zellij_utils::{consts::VERSION, shared::version_number};
 
 use crate::output::{CharacterChunk, HighlightSelection, OutputBuffer, SixelImageChunk};
 use crate::panes::alacritty_functions::{parse_number, xparse_color};
+use crate::panes::copy_mode::CopyModeCursor;
 use crate::panes::hyperlink_tracker::HyperlinkTracker;
 use crate::panes::link_handler::LinkHandler;
+use crate::panes::scrollback_archive::ScrollbackArchive;
+use crate::panes::scrollback_store::{
+    encode_record, PersistedScrollback, ScrollbackPersistence, ScrollbackRecord,
+};
 use crate::panes::search::SearchResult;
 use crate::panes::terminal_character::{
-    AnsiCode, CharsetIndex, Cursor, CursorShape, RcCharacterStyles, StandardCharset,
+    AnsiCode, CharsetIndex, Cursor, CursorShape, LinkAnchor, RcCharacterStyles, StandardCharset,
     TerminalCharacter, EMPTY_TERMINAL_CHARACTER,
 };
 use crate::panes::Selection;
@@ -204,6 +215,7 @@ fn transfer_rows_from_lines_above_to_viewport(
     lines_above: &mut VecDeque<Row>,
     viewport: &mut VecDeque<Row>,
     sixel_grid: &mut SixelGrid,
+    scrollback_archive: &mut ScrollbackArchive,
     count: usize,
     max_viewport_width: usize,
 ) -> usize {
@@ -235,7 +247,7 @@ fn transfer_rows_from_lines_above_to_viewport(
     }
     if !next_lines.is_empty() {
         let excess_row = Row::from_rows(next_lines);
-        bounded_push(lines_above, sixel_grid, excess_row);
+        bounded_push(lines_above, sixel_grid, scrollback_archive, excess_row);
     }
     match usize::try_from(lines_added_to_viewport) {
         Ok(n) => n,
@@ -247,6 +259,7 @@ fn transfer_rows_from_viewport_to_lines_above(
     viewport: &mut VecDeque<Row>,
     lines_above: &mut VecDeque<Row>,
     sixel_grid: &mut SixelGrid,
+    scrollback_archive: &mut ScrollbackArchive,
     count: usize,
     max_viewport_width: usize,
 ) -> isize {
@@ -262,7 +275,12 @@ fn transfer_rows_from_viewport_to_lines_above(
             next_lines.append(&mut bottom_canonical_row_and_wraps_in_dst);
         }
         next_lines.push(next_line);
-        let dropped_line_width = bounded_push(lines_above, sixel_grid, Row::from_rows(next_lines));
+        let dropped_line_width = bounded_push(
+            lines_above,
+            sixel_grid,
+            scrollback_archive,
+            Row::from_rows(next_lines),
+        );
         if let Some(width) = dropped_line_width {
             transferred_rows_count -=
                 calculate_row_display_height(width, max_viewport_width) as isize;
@@ -311,13 +329,19 @@ fn transfer_rows_from_lines_below_to_viewport(
     }
 }
 
-fn bounded_push(vec: &mut VecDeque<Row>, sixel_grid: &mut SixelGrid, value: Row) -> Option<usize> {
+fn bounded_push(
+    vec: &mut VecDeque<Row>,
+    sixel_grid: &mut SixelGrid,
+    scrollback_archive: &mut ScrollbackArchive,
+    value: Row,
+) -> Option<usize> {
     let mut dropped_line_width = None;
     if vec.len() >= *SCROLL_BUFFER_SIZE.get().unwrap() {
         let line = vec.pop_front();
         if let Some(line) = line {
             sixel_grid.offset_grid_top();
             dropped_line_width = Some(line.width());
+            scrollback_archive.push(line);
         }
     }
     vec.push_back(value);
@@ -337,6 +361,10 @@ pub fn create_horizontal_tabstops(columns: usize) -> BTreeSet<usize> {
     horizontal_tabstops
 }
 
+fn new_scrollback_archive() -> ScrollbackArchive {
+    ScrollbackArchive::new(DISK_SCROLL_BUFFER_SIZE.get().copied().unwrap_or(0))
+}
+
 fn calculate_row_display_height(row_width: usize, viewport_width: usize) -> usize {
     if row_width <= viewport_width {
         return 1;
@@ -382,9 +410,9 @@ pub fn parse_osc7_path(raw: &[u8]) -> Option<std::path::PathBuf> {
 macro_rules! dump_screen {
     ($lines:expr) => {{
         let mut is_first = true;
-        let mut buf = String::with_capacity($lines.iter().map(|l| l.len()).sum());
+        let mut buf = String::new();
 
-        for line in &$lines {
+        for line in $lines {
             if line.is_canonical && !is_first {
                 buf.push_str("\n");
             }
@@ -405,7 +433,7 @@ macro_rules! dump_screen_with_ansi {
         let mut buf = String::new();
         let mut last_styles: Option<RcCharacterStyles> = None;
 
-        for line in &$lines {
+        for line in $lines {
             if line.is_canonical && !is_first {
                 buf.push_str("\n");
                 last_styles = None;
@@ -595,9 +623,14 @@ pub struct Grid {
     pub(crate) lines_above: VecDeque<Row>,
     pub(crate) viewport: VecDeque<Row>,
     pub(crate) lines_below: VecDeque<Row>,
+    scrollback_archive: ScrollbackArchive,
+    // rows scrolled down past the in-memory buffer while scrolled into the archive, the most
+    // recently pushed being the one right below `lines_below`
+    lines_below_archive: ScrollbackArchive,
+    scrollback_persistence: ScrollbackPersistence,
     horizontal_tabstops: BTreeSet<usize>,
     alternate_screen_state: Option<AlternateScreenState>,
-    cursor: Cursor,
+    pub(crate) cursor: Cursor,
     cursor_is_hidden: bool,
     saved_cursor_position: Option<Cursor>,
     scroll_region: (usize, usize),
@@ -611,6 +644,8 @@ pub struct Grid {
     title_stack: Vec<String>,
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
     sixel_grid: SixelGrid,
+    kitty_grid: KittyGrid,
+    kitty_graphics_parser: KittyGraphicsParser,
     pub changed_colors: Option<[Option<AnsiCode>; 256]>,
     pub should_render: bool,
     pub lock_renders: bool,
@@ -641,11 +676,16 @@ pub struct Grid {
     /// `pending_messages_to_pty` for this pane.
     pub color_palette_notification_enabled: bool,
     pub search_results: SearchResult,
+    pub(crate) copy_mode_cursor: Option<CopyModeCursor>,
     pub pending_clipboard_update: Option<String>,
     pub pending_osc7_cwd: Option<std::path::PathBuf>,
     /// Pending desktop notifications: (payload, terminator)
     /// Payload is the semicolon-joined params after "99", terminator is "\x07" or "\x1b\\"
     pub pending_desktop_notifications: Vec<(String, String)>,
+    /// Exit codes of commands the shell reported as finished through OSC 133;D
+    pub pending_finished_commands: Vec<Option<i32>>,
+    /// The on-screen prompt that `scroll_to_previous_prompt` last stopped at, if any
+    pub(crate) visible_prompt_jump: Option<VisiblePromptJump>,
     /// Whitelisted host-terminal queries intercepted from the app running
     /// in this pane (CSI 14t / 16t pixel-dim queries, OSC 10;? / 11;? /
     /// 4;N;? color queries). Each entry is the raw byte sequence that
@@ -658,7 +698,8 @@ pub struct Grid {
     arrow_fonts: bool,
     styled_underlines: bool,
     osc8_hyperlinks: bool,
-    pub supports_kitty_keyboard_protocol: bool, // has the app requested kitty keyboard support?
+    kitty_keyboard_flags: KittyKeyboardFlagsStack, // pushed by the app, empty if it did not request
+    // kitty keyboard support
     explicitly_disable_kitty_keyboard_protocol: bool, // has kitty keyboard support been explicitly
     // disabled by user config?
     click: Click,
@@ -929,6 +970,9 @@ impl Grid {
             lines_above: VecDeque::new(),
             viewport: VecDeque::from(vec![Row::new().canonical()]),
             lines_below: VecDeque::new(),
+            lines_below_archive: ScrollbackArchive::new(usize::MAX),
+            scrollback_archive: new_scrollback_archive(),
+            scrollback_persistence: ScrollbackPersistence::default(),
             horizontal_tabstops: create_horizontal_tabstops(columns),
             cursor: Cursor::new(0, 0, styled_underlines),
             cursor_is_hidden: false,
@@ -966,10 +1010,15 @@ impl Grid {
             color_palette_notification_enabled: false,
             character_cell_size,
             search_results: Default::default(),
+            copy_mode_cursor: None,
             sixel_grid,
+            kitty_grid: KittyGrid::default(),
+            kitty_graphics_parser: KittyGraphicsParser::default(),
             pending_clipboard_update: None,
             pending_osc7_cwd: None,
             pending_desktop_notifications: Vec::new(),
+            pending_finished_commands: Vec::new(),
+            visible_prompt_jump: None,
             pending_forwarded_queries: Vec::new(),
             ui_component_bytes: None,
             style,
@@ -978,7 +1027,7 @@ impl Grid {
             styled_underlines,
             osc8_hyperlinks,
             lock_renders: false,
-            supports_kitty_keyboard_protocol: false,
+            kitty_keyboard_flags: KittyKeyboardFlagsStack::default(),
             explicitly_disable_kitty_keyboard_protocol,
             click: Click::default(),
             hyperlink_tracker: HyperlinkTracker::new(),
@@ -1035,9 +1084,10 @@ impl Grid {
     }
     pub fn scrollback_position_and_length(&self) -> (usize, usize) {
         // (position, length)
+        let lines_below_count = self.lines_below.len() + self.lines_below_archive.len();
         (
-            self.lines_below.len(),
-            (self.scrollback_buffer_lines + self.lines_below.len()),
+            lines_below_count,
+            (self.scrollback_buffer_lines + self.archived_line_count() + lines_below_count),
         )
     }
 
@@ -1145,15 +1195,19 @@ impl Grid {
 
     pub fn scroll_up_one_line(&mut self) -> bool {
         let mut found_something = false;
+        if self.lines_above.is_empty() {
+            self.restore_archived_line();
+        }
         if !self.lines_above.is_empty() && self.viewport.len() == self.height {
             self.is_scrolled = true;
             let line_to_push_down = self.viewport.pop_back().unwrap();
-            self.lines_below.push_front(line_to_push_down);
+            self.push_line_below(line_to_push_down);
 
             let transferred_rows_height = transfer_rows_from_lines_above_to_viewport(
                 &mut self.lines_above,
                 &mut self.viewport,
                 &mut self.sixel_grid,
+                &mut self.scrollback_archive,
                 1,
                 self.width,
             );
@@ -1161,7 +1215,7 @@ impl Grid {
                 .scrollback_buffer_lines
                 .saturating_sub(transferred_rows_height);
 
-            self.selection.move_down(1);
+            self.move_selection_down(1);
             // Move all search-selections down one line as well
             found_something = self
                 .search_results
@@ -1172,11 +1226,12 @@ impl Grid {
     }
     pub fn scroll_down_one_line(&mut self) -> bool {
         let mut found_something = false;
+        self.restore_lines_below();
         if !self.lines_below.is_empty()
             && self.viewport.len() == self.height
             && !self.viewport.is_empty()
         {
-            let mut line_to_push_up = self.viewport.pop_front().unwrap();
+            let line_to_push_up = self.viewport.pop_front().unwrap();
 
             self.scrollback_buffer_lines +=
                 calculate_row_display_height(line_to_push_up.width(), self.width);
@@ -1186,7 +1241,7 @@ impl Grid {
             } else {
                 match self.lines_above.pop_back() {
                     Some(mut last_line_above) => {
-                        last_line_above.append(&mut line_to_push_up.columns);
+                        last_line_above.append_row(line_to_push_up);
                         last_line_above
                     },
                     None => {
@@ -1197,8 +1252,12 @@ impl Grid {
                 }
             };
 
-            let dropped_line_width =
-                bounded_push(&mut self.lines_above, &mut self.sixel_grid, line_to_push_up);
+            let dropped_line_width = bounded_push(
+                &mut self.lines_above,
+                &mut self.sixel_grid,
+                &mut self.scrollback_archive,
+                line_to_push_up,
+            );
             if let Some(width) = dropped_line_width {
                 let dropped_line_height = calculate_row_display_height(width, self.width);
 
@@ -1214,7 +1273,7 @@ impl Grid {
                 self.width,
             );
 
-            self.selection.move_up(1);
+            self.move_selection_up(1);
             // Move all search-selections up one line as well
             found_something =
                 self.search_results
@@ -1259,6 +1318,10 @@ impl Grid {
             return;
         }
         self.selection.reset();
+        if let Some(copy_mode_cursor) = self.copy_mode_cursor.as_mut() {
+            // lines are rewrapped, so the selection no longer points at the same text
+            copy_mode_cursor.clear_selection();
+        }
         self.sixel_grid.character_cell_size_possibly_changed();
         let cursors = if new_columns != self.width {
             self.horizontal_tabstops = create_horizontal_tabstops(new_columns);
@@ -1266,13 +1329,13 @@ impl Grid {
             let cursor_index_in_canonical_line = self.cursor_index_in_canonical_line();
             let saved_cursor_index_in_canonical_line = self.saved_cursor_index_in_canonical_line();
             let mut viewport_canonical_lines = vec![];
-            for mut row in self.viewport.drain(..) {
+            for row in self.viewport.drain(..) {
                 if !row.is_canonical
                     && viewport_canonical_lines.is_empty()
                     && !self.lines_above.is_empty()
                 {
                     let mut first_line_above = self.lines_above.pop_back().unwrap();
-                    first_line_above.append(&mut row.columns);
+                    first_line_above.append_row(row);
                     viewport_canonical_lines.push(first_line_above);
                     cursor_canonical_line_index += 1;
                 } else if row.is_canonical {
@@ -1280,7 +1343,7 @@ impl Grid {
                 } else {
                     match viewport_canonical_lines.last_mut() {
                         Some(last_line) => {
-                            last_line.append(&mut row.columns);
+                            last_line.append_row(row);
                         },
                         None => {
                             // the state is corrupted somehow
@@ -1412,6 +1475,7 @@ impl Grid {
                         &mut self.lines_above,
                         &mut self.viewport,
                         &mut self.sixel_grid,
+                        &mut self.scrollback_archive,
                         row_count_to_transfer,
                         new_columns,
                     );
@@ -1439,6 +1503,7 @@ impl Grid {
                         &mut self.viewport,
                         &mut self.lines_above,
                         &mut self.sixel_grid,
+                        &mut self.scrollback_archive,
                         row_count_to_transfer,
                         new_columns,
                     );
@@ -1665,6 +1730,11 @@ impl Grid {
     /// bounds) so that the host terminal can position the cursor for IME even
     /// when the app has hidden it. The bool is true when the cursor is visible.
     pub fn cursor_coordinates(&self) -> Option<(usize, usize, bool)> {
+        if let Some(copy_mode_cursor) = self.copy_mode_cursor {
+            return copy_mode_cursor
+                .coordinates_in_viewport(self.width, self.height)
+                .map(|(x, y)| (x, y, true));
+        }
         if self.cursor.x >= self.width || self.cursor.y >= self.height {
             None
         } else {
@@ -1685,11 +1755,11 @@ impl Grid {
     }
     /// Dumps all lines above terminal vieport and the viewport itself to a string
     pub fn dump_screen(&self, full: bool) -> String {
-        let viewport: String = dump_screen!(self.viewport);
+        let viewport: String = dump_screen!(&self.viewport);
         if !full {
             return viewport;
         }
-        let mut scrollback: String = dump_screen!(self.lines_above);
+        let mut scrollback: String = dump_screen!(self.lines_above_including_archived(0));
         if !scrollback.is_empty() {
             scrollback.push('\n');
         }
@@ -1698,17 +1768,173 @@ impl Grid {
     }
     /// Dumps all lines (with ansi) above terminal viewport and the viewport itself to a string
     pub fn dump_screen_with_ansi(&self, full: bool) -> String {
-        let viewport: String = dump_screen_with_ansi!(self.viewport);
+        let viewport: String = dump_screen_with_ansi!(&self.viewport);
         if !full {
             return viewport;
         }
-        let mut scrollback: String = dump_screen_with_ansi!(self.lines_above);
+        let mut scrollback: String = dump_screen_with_ansi!(self.lines_above_including_archived(0));
         if !scrollback.is_empty() {
             scrollback.push('\n');
         }
         scrollback.push_str(&viewport);
         scrollback
     }
+    /// Lines above the viewport, starting with those archived on disk (if any), skipping the
+    /// first `skip` of them
+    fn lines_above_including_archived(&self, skip: usize) -> impl Iterator<Item = Cow<'_, Row>> {
+        let archived_line_count = self.archived_line_count();
+        self.scrollback_archive
+            .rows_from(skip)
+            .map(Cow::Owned)
+            .chain(
+                self.lines_above
+                    .iter()
+                    .skip(skip.saturating_sub(archived_line_count))
+                    .map(Cow::Borrowed),
+            )
+    }
+    fn archived_line_count(&self) -> usize {
+        self.scrollback_archive.len()
+    }
+    pub(crate) fn has_archived_lines(&self) -> bool {
+        self.archived_line_count() > 0
+    }
+    /// Moves the lines right below `lines_below` back from disk, once we scrolled down close
+    /// enough to them
+    fn restore_lines_below(&mut self) {
+        while self.lines_below.len() <= self.height {
+            match self.lines_below_archive.pop_back() {
+                Some(mut line) => self
+                    .lines_below
+                    .extend(line.split_to_rows_of_length(self.width)),
+                None => break,
+            }
+        }
+    }
+    /// Moves the line to the top of `lines_below`, archiving the bottom one on disk if there
+    /// are too many of them
+    fn push_line_below(&mut self, line: Row) {
+        self.lines_below.push_front(line);
+        if self.lines_below.len() > *SCROLL_BUFFER_SIZE.get().unwrap() {
+            self.archive_last_line_below();
+        }
+    }
+    /// Archives the bottom line of `lines_below` on disk, the lines in both archives are
+    /// unwrapped (like `lines_above`) so that their chunks can be moved from one to the other
+    fn archive_last_line_below(&mut self) {
+        let mut rows = vec![];
+        while let Some(row) = self.lines_below.pop_back() {
+            let is_canonical = row.is_canonical;
+            rows.push(row);
+            if is_canonical {
+                break;
+            }
+        }
+        if !rows.is_empty() {
+            rows.reverse();
+            self.lines_below_archive
+                .push(Row::from_rows(rows).canonical());
+        }
+    }
+    /// Lines below the viewport, including those archived on disk (if any)
+    fn lines_below_including_archived(&self) -> impl Iterator<Item = Cow<'_, Row>> {
+        let mut archived_lines_below: Vec<Row> = self.lines_below_archive.rows_from(0).collect();
+        archived_lines_below.reverse();
+        let width = self.width;
+        self.lines_below.iter().map(Cow::Borrowed).chain(
+            archived_lines_below
+                .into_iter()
+                .flat_map(move |mut line| line.split_to_rows_of_length(width))
+                .map(Cow::Owned),
+        )
+    }
+    /// The number of rows the archived lines take up on screen
+    fn archived_display_height(&self, scrollback_archive: &ScrollbackArchive) -> usize {
+        scrollback_archive
+            .row_widths()
+            .map(|row_width| calculate_row_display_height(row_width, self.width))
+            .sum()
+    }
+    /// Moves the most recent line archived on disk (if any) back to the in-memory scrollback
+    fn restore_archived_line(&mut self) {
+        if let Some(line) = self.scrollback_archive.pop_back() {
+            self.scrollback_buffer_lines += calculate_row_display_height(line.width(), self.width);
+            self.lines_above.push_front(line);
+        }
+    }
+    /// The rows added to the scrollback since the last call, as a record to append to the
+    /// pane's scrollback file. The last row above the viewport is left out because it can
+    /// still be rewrapped along with the viewport.
+    pub fn scrollback_record(&mut self) -> Option<ScrollbackRecord> {
+        if self.alternate_screen_state.is_some() || !self.lines_below.is_empty() {
+            // the scrollback is not all above the viewport right now, we'll get it next time
+            return None;
+        }
+        let history_start = self.scrollback_archive.dropped_row_count();
+        let history_len = self.archived_line_count() + self.lines_above.len().saturating_sub(1);
+        let history_end = history_start + history_len as u64;
+        let (truncate, first_row) = self
+            .scrollback_persistence
+            .update(history_start, history_end)?;
+        let rows = self
+            .lines_above_including_archived((first_row - history_start) as usize)
+            .take((history_end - first_row) as usize);
+        let record = encode_record(history_start, first_row, rows, &self.link_handler.borrow());
+        match record {
+            Ok(bytes) => Some(ScrollbackRecord { truncate, bytes }),
+            Err(e) => {
+                log::error!("Failed to serialize scrollback: {}", e);
+                self.scrollback_persistence.reset();
+                None
+            },
+        }
+    }
+    pub fn has_persisted_scrollback(&self) -> bool {
+        self.scrollback_persistence.is_persisted()
+    }
+    /// Places the rows of a resurrected pane's scrollback file above the viewport
+    pub fn restore_scrollback(&mut self, persisted_scrollback: PersistedScrollback) {
+        let link_indices: HashMap<u16, u16> = {
+            let mut link_handler = self.link_handler.borrow_mut();
+            persisted_scrollback
+                .links
+                .into_iter()
+                .map(|(link_index, link)| (link_index, link_handler.add_link(link)))
+                .collect()
+        };
+        // consecutive characters usually share their styles, so we only remap them once
+        let mut last_remapped_styles: Option<(RcCharacterStyles, RcCharacterStyles)> = None;
+        for mut row in persisted_scrollback.rows {
+            for character in row.columns.iter_mut() {
+                let Some(LinkAnchor::Start(link_index)) = character.styles.link_anchor else {
+                    continue;
+                };
+                match &last_remapped_styles {
+                    Some((styles, remapped_styles)) if *styles == character.styles => {
+                        character.styles = remapped_styles.clone();
+                    },
+                    _ => {
+                        let styles = character.styles.clone();
+                        character.styles.update(|styles| {
+                            styles.link_anchor = link_indices
+                                .get(&link_index)
+                                .copied()
+                                .map(LinkAnchor::Start)
+                        });
+                        last_remapped_styles = Some((styles, character.styles.clone()));
+                    },
+                }
+            }
+            bounded_push(
+                &mut self.lines_above,
+                &mut self.sixel_grid,
+                &mut self.scrollback_archive,
+                row,
+            );
+        }
+        self.scrollback_buffer_lines = self.recalculate_scrollback_buffer_count();
+        self.output_buffer.update_all_lines();
+    }
     pub fn move_viewport_up(&mut self, count: usize) {
         for _ in 0..count {
             self.scroll_up_one_line();
@@ -1721,16 +1947,126 @@ impl Grid {
         }
         self.output_buffer.update_all_lines();
     }
+    /// Scrolls up to the first line of the scrollback (including the lines archived on disk)
+    /// at once, rather than line by line. The archived lines are not read back (other than
+    /// those that come into view), their chunks are moved as they are to the lines below.
+    pub fn scroll_to_top(&mut self) {
+        if self.viewport.len() != self.height
+            || (self.lines_above.is_empty() && !self.has_archived_lines())
+        {
+            return;
+        }
+        while let Some(line) = self.viewport.pop_back() {
+            self.push_line_below(line);
+        }
+        let mut scrolled_rows = 0;
+        while let Some(mut line) = self.lines_above.pop_back() {
+            // lines above are unwrapped, lines below are wrapped to the viewport width
+            let rows = line.split_to_rows_of_length(self.width);
+            scrolled_rows += rows.len();
+            for row in rows.into_iter().rev() {
+                self.push_line_below(row);
+            }
+        }
+        if self.has_archived_lines() {
+            // the archived lines go right below the viewport, so the lines that are in memory
+            // are archived below them first
+            while !self.lines_below.is_empty() {
+                self.archive_last_line_below();
+            }
+            scrolled_rows += self.archived_display_height(&self.scrollback_archive);
+            self.lines_below_archive
+                .append_reversed(&mut self.scrollback_archive);
+            self.restore_lines_below();
+        }
+        transfer_rows_from_lines_below_to_viewport(
+            &mut self.lines_below,
+            &mut self.viewport,
+            self.height,
+            self.width,
+        );
+        self.is_scrolled = !self.lines_below.is_empty();
+        self.scrollback_buffer_lines = 0;
+        self.move_selection_down(scrolled_rows);
+        self.search_results
+            .move_down(scrolled_rows, &self.viewport, self.height);
+        self.output_buffer.update_all_lines();
+    }
+    /// Scrolls down to the bottom at once, rather than line by line. Like `scroll_to_top`, the
+    /// lines archived below the viewport are moved back to the scrollback archive as they are,
+    /// only those that the in-memory scrollback is made of again are read back.
     pub fn reset_viewport(&mut self) {
-        let max_lines_to_scroll = *SCROLL_BUFFER_SIZE.get().unwrap() * 2; // while not very elegant, this can prevent minor bugs from becoming showstoppers by sticking the whole app display in an endless loop
-        let mut lines_scrolled = 0;
-        let should_clear_output_buffer = self.is_scrolled;
-        while self.is_scrolled && lines_scrolled < max_lines_to_scroll {
-            self.scroll_down_one_line();
-            lines_scrolled += 1;
+        if !self.is_scrolled {
+            return;
         }
-        if should_clear_output_buffer {
-            self.output_buffer.update_all_lines();
+        let scroll_buffer_size = *SCROLL_BUFFER_SIZE.get().unwrap();
+        let mut scrolled_rows = self.lines_below.len();
+        // the lines of the viewport and below it, unwrapped like the lines above
+        let mut lines_to_push_up: Vec<Row> = vec![];
+        if self
+            .viewport
+            .front()
+            .map(|row| !row.is_canonical)
+            .unwrap_or(false)
+        {
+            // the top row continues the last line above
+            lines_to_push_up.extend(self.lines_above.pop_back());
+        }
+        for row in self.viewport.drain(..).chain(self.lines_below.drain(..)) {
+            match lines_to_push_up.last_mut() {
+                Some(line) if !row.is_canonical => line.append_row(row),
+                _ => lines_to_push_up.push(row.canonical()),
+            }
+        }
+        if self.lines_below_archive.len() > scroll_buffer_size {
+            // there are enough lines archived below to make up the whole in-memory scrollback,
+            // so everything above them is archived
+            scrolled_rows += self.archived_display_height(&self.lines_below_archive);
+            for line in self.lines_above.drain(..).chain(lines_to_push_up.drain(..)) {
+                self.scrollback_archive.push(line);
+            }
+            self.scrollback_archive
+                .append_reversed(&mut self.lines_below_archive);
+        } else {
+            while let Some(line) = self.lines_below_archive.pop_back() {
+                scrolled_rows += calculate_row_display_height(line.width(), self.width);
+                lines_to_push_up.push(line);
+            }
+        }
+        for line in lines_to_push_up {
+            bounded_push(
+                &mut self.lines_above,
+                &mut self.sixel_grid,
+                &mut self.scrollback_archive,
+                line,
+            );
+        }
+        self.restore_lines_above(scroll_buffer_size);
+        transfer_rows_from_lines_above_to_viewport(
+            &mut self.lines_above,
+            &mut self.viewport,
+            &mut self.sixel_grid,
+            &mut self.scrollback_archive,
+            self.height,
+            self.width,
+        );
+        self.restore_lines_above(scroll_buffer_size);
+        self.scrollback_archive.trim();
+        self.scrollback_buffer_lines = self.recalculate_scrollback_buffer_count();
+        self.is_scrolled = false;
+        self.move_selection_up(scrolled_rows);
+        self.search_results.move_up(
+            scrolled_rows,
+            &self.viewport,
+            &self.lines_below,
+            self.height,
+        );
+        self.output_buffer.update_all_lines();
+    }
+    /// Moves the most recent archived lines back to memory, until there are `count` of them
+    fn restore_lines_above(&mut self, count: usize) {
+        while self.lines_above.len() < count && self.has_archived_lines() {
+            self.restore_archived_line();
         }
     }
     pub fn rotate_scroll_region_up(&mut self, count: usize) {
@@ -1761,7 +2097,7 @@ impl Grid {
                 && !self.viewport.is_empty()
             {
                 self.transfer_rows_to_lines_above(1);
-                self.selection.move_up(1);
+                self.move_selection_up(1);
             } else if scroll_region_top < self.viewport.len() {
                 self.viewport.remove(scroll_region_top);
             }
@@ -1815,7 +2151,7 @@ impl Grid {
 
                 self.viewport
                     .push_back(Row::new().canonical().with_bg_color(scroll_bg));
-                self.selection.move_up(1);
+                self.move_selection_up(1);
             } else {
                 if scroll_region_top == 0
                     && self.alternate_screen_state.is_none()
@@ -1824,7 +2160,7 @@ impl Grid {
                     // Partial scroll region starting at top: preserve
                     // scrolled-off lines in scrollback
                     self.transfer_rows_to_lines_above(1);
-                    self.selection.move_up(1);
+                    self.move_selection_up(1);
                 } else if scroll_region_top < self.viewport.len() {
                     self.viewport.remove(scroll_region_top);
                 }
@@ -2002,7 +2338,7 @@ impl Grid {
             }
             let wrapped_row = Row::new();
             self.viewport.push_back(wrapped_row);
-            self.selection.move_up(1);
+            self.move_selection_up(1);
             self.output_buffer.update_all_lines();
         } else {
             self.cursor.y += 1;
@@ -2017,6 +2353,8 @@ impl Grid {
     }
     fn clear_lines_above(&mut self) {
         self.lines_above.clear();
+        self.scrollback_archive.clear();
+        self.scrollback_persistence.reset();
         self.scrollback_buffer_lines = self.recalculate_scrollback_buffer_count();
     }
 
@@ -2050,6 +2388,24 @@ impl Grid {
             self.output_buffer.update_line(self.viewport.len() - 1);
         }
     }
+    fn add_prompt_mark(&mut self, kind: PromptMarkKind) {
+        if let PromptMarkKind::CommandEnd(exit_code) = kind {
+            self.pending_finished_commands.push(exit_code);
+        }
+        if self.alternate_screen_state.is_some() {
+            // full screen apps have no prompts, and their screen is discarded when they exit
+            return;
+        }
+        if self.viewport.get(self.cursor.y).is_none() {
+            self.pad_lines_until(self.cursor.y, EMPTY_TERMINAL_CHARACTER);
+        }
+        if let Some(current_row) = self.viewport.get_mut(self.cursor.y) {
+            current_row.add_prompt_mark(PromptMark {
+                kind,
+                column: self.cursor.x,
+            });
+        }
+    }
     pub fn move_cursor_to(&mut self, x: usize, y: usize, pad_character: TerminalCharacter) {
         let (scroll_region_top, scroll_region_bottom) = self.scroll_region;
         self.cursor.x = std::cmp::min(self.width - 1, x);
@@ -2158,7 +2514,7 @@ impl Grid {
                     && !self.viewport.is_empty()
                 {
                     self.transfer_rows_to_lines_above(1);
-                    self.selection.move_up(1);
+                    self.move_selection_up(1);
                 } else if current_line_index < self.viewport.len() {
                     self.viewport.remove(current_line_index);
                 }
@@ -2254,7 +2610,10 @@ impl Grid {
     }
     pub fn reset_terminal_state(&mut self) {
         self.lines_above = VecDeque::new();
+        self.scrollback_archive = new_scrollback_archive();
+        self.scrollback_persistence.reset();
         self.lines_below = VecDeque::new();
+        self.lines_below_archive.clear();
         self.is_scrolled = false;
         self.viewport = VecDeque::from(vec![Row::new().canonical()]);
         self.alternate_screen_state = None;
@@ -2276,7 +2635,7 @@ impl Grid {
         self.mouse_tracking = MouseTracking::Off;
         self.focus_event_tracking = false;
         self.cursor_is_hidden = false;
-        self.supports_kitty_keyboard_protocol = false;
+        self.kitty_keyboard_flags.clear();
         self.set_scroll_region_to_viewport_size();
         self.pane_default_fg = None;
         self.pane_default_bg = None;
@@ -2648,6 +3007,14 @@ impl Grid {
         self.update_selected_lines(&old_selection, &self.selection.clone());
         self.mark_for_rerender();
     }
+    pub fn start_block_selection(&mut self, start: &Position) {
+        let old_selection = self.selection;
+        // a block selection is never a word or line selection, whatever clicks came before it
+        self.click.reset();
+        self.selection.start_block(*start);
+        self.update_selected_lines(&old_selection, &self.selection.clone());
+        self.mark_for_rerender();
+    }
     pub fn update_selection(&mut self, to: &Position) {
         let old_selection = self.selection;
         if &old_selection.end != to {
@@ -2694,6 +3061,19 @@ impl Grid {
         self.mark_for_rerender();
     }
 
+    // the copy mode cursor moves along with the selection as content scrolls
+    fn move_selection_up(&mut self, lines: usize) {
+        self.selection.move_up(lines);
+        if let Some(copy_mode_cursor) = self.copy_mode_cursor.as_mut() {
+            copy_mode_cursor.move_up(lines);
+        }
+    }
+    fn move_selection_down(&mut self, lines: usize) {
+        self.selection.move_down(lines);
+        if let Some(copy_mode_cursor) = self.copy_mode_cursor.as_mut() {
+            copy_mode_cursor.move_down(lines);
+        }
+    }
     pub fn reset_selection(&mut self) {
         let old_selection = self.selection;
         self.selection.reset();
@@ -2714,10 +3094,19 @@ impl Grid {
 
             // on the first line of the selection, use the selection start column
             // otherwise, start at the beginning of the line
-            let start_column = if l == start.line.0 { start.column.0 } else { 0 };
+            // (block selections use the same columns on all lines)
+            let start_column = if sorted_selection.is_block() {
+                sorted_selection.block_columns().start
+            } else if l == start.line.0 {
+                start.column.0
+            } else {
+                0
+            };
 
             // same thing on the last line, but with the selection end column
-            let end_column = if l == end.line.0 {
+            let end_column = if sorted_selection.is_block() {
+                sorted_selection.block_columns().end
+            } else if l == end.line.0 {
                 end.column.0
             } else {
                 self.width
@@ -2731,7 +3120,7 @@ impl Grid {
                 Row::from_columns(VecDeque::from(vec![EMPTY_TERMINAL_CHARACTER; self.width]));
 
             // get the row from lines_above, viewport, or lines below depending on index
-            let row = if l < 0 && self.lines_above.len() > l.abs() as usize {
+            let row = if l < 0 && self.lines_above.len() >= l.unsigned_abs() {
                 let offset_from_end = l.abs();
                 &self.lines_above[self
                     .lines_above
@@ -2752,14 +3141,22 @@ impl Grid {
 
             let mut terminal_col = 0;
             for terminal_character in &row.columns {
-                if (start_column..end_column).contains(&terminal_col) {
+                let is_selected = if sorted_selection.is_block() {
+                    // wide characters partly inside the block are copied as a whole, as they are
+                    // highlighted
+                    terminal_col < end_column
+                        && terminal_col + terminal_character.width() > start_column
+                } else {
+                    (start_column..end_column).contains(&terminal_col)
+                };
+                if is_selected {
                     line_selection.push(terminal_character.character);
                 }
 
                 terminal_col += terminal_character.width();
             }
 
-            if row.is_canonical {
+            if row.is_canonical || sorted_selection.is_block() {
                 selection.push(line_selection);
             } else {
                 // rejoin wrapped lines if possible
@@ -2872,7 +3269,11 @@ impl Grid {
         Some((position_start, position_end))
     }
 
-    fn update_selected_lines(&mut self, old_selection: &Selection, new_selection: &Selection) {
+    pub(crate) fn update_selected_lines(
+        &mut self,
+        old_selection: &Selection,
+        new_selection: &Selection,
+    ) {
         for l in old_selection.diff(new_selection, self.height) {
             self.output_buffer.update_line(l as usize);
         }
@@ -2898,6 +3299,7 @@ impl Grid {
             &mut self.viewport,
             &mut self.lines_above,
             &mut self.sixel_grid,
+            &mut self.scrollback_archive,
             count,
             self.width,
         );
@@ -2956,6 +3358,121 @@ impl Grid {
             }
         }
     }
+    /// The vte parser ignores APC sequences, so we look for kitty graphics commands ourselves
+    pub fn advance_kitty_graphics_parser(&mut self, byte: u8) {
+        if let Some(command) = self.kitty_graphics_parser.advance(byte) {
+            self.handle_kitty_graphics_command(command);
+        }
+    }
+    fn handle_kitty_graphics_command(&mut self, mut command: KittyGraphicsCommand) {
+        let character_cell_size = *self.character_cell_size.borrow();
+        let result = match command.action {
+            // we only display images if we know the pixel size of each character cell, so we
+            // don't report supporting them otherwise
+            KittyGraphicsAction::Query => match character_cell_size {
+                Some(_) => self.kitty_grid.transmit(&mut command).map(|_| ()),
+                None => Err("EINVAL:unknown character cell size".to_owned()),
+            },
+            KittyGraphicsAction::Transmit => self.kitty_grid.transmit(&mut command).map(|_| ()),
+            KittyGraphicsAction::TransmitAndDisplay => self
+                .kitty_grid
+                .transmit(&mut command)
+                .and_then(|image| self.place_kitty_image(&command, &image)),
+            KittyGraphicsAction::Display => self
+                .kitty_grid
+                .stored_image(&command)
+                .and_then(|image| self.place_kitty_image(&command, &image)),
+            KittyGraphicsAction::Delete => {
+                self.delete_kitty_placements(&command);
+                Ok(())
+            },
+            KittyGraphicsAction::Unsupported => Err("EINVAL:unsupported action".to_owned()),
+        };
+        if let Some(response) = command.response(&result) {
+            self.pending_messages_to_pty.push(response);
+        }
+    }
+    fn place_kitty_image(
+        &mut self,
+        command: &KittyGraphicsCommand,
+        image: &KittyImage,
+    ) -> Result<(), String> {
+        let character_cell_size = (*self.character_cell_size.borrow())
+            .ok_or_else(|| "EINVAL:unknown character cell size".to_owned())?;
+        let (x_pixel_coordinates, y_pixel_coordinates) = self
+            .current_cursor_pixel_coordinates()
+            .ok_or_else(|| "EINVAL:unknown character cell size".to_owned())?;
+        let placed_image = image.for_placement(command, character_cell_size)?;
+        let sixel_image = placed_image
+            .to_sixel_image()
+            .ok_or_else(|| "EINVAL:failed to render image".to_owned())?;
+        let (image_pixel_height, image_pixel_width) = placed_image.pixel_size();
+        let new_image_id = self.sixel_grid.next_image_id();
+        self.sixel_grid
+            .new_kitty_image(new_image_id, sixel_image, placed_image);
+        self.sixel_grid.place_image(
+            new_image_id,
+            PixelRect::new(
+                x_pixel_coordinates + command.cell_x_offset,
+                y_pixel_coordinates + command.cell_y_offset,
+                image_pixel_height,
+                image_pixel_width,
+            ),
+        );
+        if let Some(replaced_image_id) =
+            self.kitty_grid
+                .add_placement(new_image_id, command, &self.sixel_grid)
+        {
+            self.sixel_grid.remove_image(replaced_image_id);
+        }
+        if command.move_cursor {
+            // like kitty, we move to the cell after the image, on its last row
+            let columns =
+                (command.cell_x_offset + image_pixel_width).div_ceil(character_cell_size.width);
+            let rows =
+                (command.cell_y_offset + image_pixel_height).div_ceil(character_cell_size.height);
+            for _ in 1..rows {
+                self.add_canonical_line();
+            }
+            self.cursor.x += columns;
+            if self.cursor.x >= self.width {
+                self.cursor.x = 0;
+                self.add_canonical_line();
+            }
+        }
+        self.render_full_viewport();
+        Ok(())
+    }
+    fn delete_kitty_placements(&mut self, command: &KittyGraphicsCommand) {
+        let character_cell_size = match *self.character_cell_size.borrow() {
+            Some(character_cell_size) => character_cell_size,
+            None => return,
+        };
+        let scrollback_pixel_height = self.lines_above.len() * character_cell_size.height;
+        let cursor_rect = PixelRect::new(
+            self.cursor.x * character_cell_size.width,
+            scrollback_pixel_height + self.cursor.y * character_cell_size.height,
+            character_cell_size.height,
+            character_cell_size.width,
+        );
+        let viewport_rect = PixelRect::new(
+            0,
+            scrollback_pixel_height,
+            self.height * character_cell_size.height,
+            self.width * character_cell_size.width,
+        );
+        let image_ids_to_remove = self.kitty_grid.delete_placements(
+            command,
+            &self.sixel_grid,
+            character_cell_size,
+            cursor_rect,
+            viewport_rect,
+        );
+        for image_id in image_ids_to_remove {
+            self.sixel_grid.remove_image(image_id);
+        }
+        self.render_full_viewport();
+    }
     fn mouse_buttons_value_x10(&self, event: &MouseEvent) -> u8 {
         let mut value = 35; // Default to no buttons down.
         if event.event_type == MouseEventType::Release {
@@ -3261,6 +3778,9 @@ impl Grid {
             },
         }
     }
+    pub fn kitty_keyboard_flags(&self) -> KittyKeyboardFlags {
+        self.kitty_keyboard_flags.current()
+    }
     pub fn is_alternate_mode_active(&self) -> bool {
         self.alternate_screen_state.is_some()
     }
@@ -3280,8 +3800,11 @@ impl Grid {
     }
     pub fn delete_viewport_and_scroll(&mut self) {
         self.lines_above.clear();
+        self.scrollback_archive.clear();
+        self.scrollback_persistence.reset();
         self.viewport.clear();
         self.lines_below.clear();
+        self.lines_below_archive.clear();
     }
     pub fn reset_cursor_position(&mut self) {
         self.cursor = Cursor::new(0, 0, self.styled_underlines);
@@ -3315,6 +3838,14 @@ impl Grid {
     pub fn has_selection(&self) -> bool {
         !self.selection.is_empty()
     }
+    // Only the last N lines are returned if max specified (Some(0) means "all" — no truncation)
+    fn scrollback_lines_to_skip(&self, max_scrollback_lines: Option<usize>) -> usize {
+        let line_count = self.archived_line_count() + self.lines_above.len();
+        match max_scrollback_lines {
+            Some(max) if max > 0 => line_count.saturating_sub(max),
+            _ => 0,
+        }
+    }
     pub fn pane_contents(
         &self,
         get_full_scrollback: bool,
@@ -3327,19 +3858,14 @@ impl Grid {
         }
         if get_full_scrollback {
             let mut lines_above_viewport: Vec<String> = Vec::with_capacity(self.lines_above.len());
-            for row in &self.lines_above {
+            for row in self
+                .lines_above_including_archived(self.scrollback_lines_to_skip(max_scrollback_lines))
+            {
                 let s: String = (&row.columns).into_iter().map(|x| x.character).collect();
                 lines_above_viewport.push(s);
             }
-            // Truncate to last N lines if max specified (Some(0) means "all" — no truncation)
-            if let Some(max) = max_scrollback_lines {
-                if max > 0 && lines_above_viewport.len() > max {
-                    let start = lines_above_viewport.len() - max;
-                    lines_above_viewport = lines_above_viewport.split_off(start);
-                }
-            }
             let mut lines_below_viewport: Vec<String> = Vec::with_capacity(self.lines_below.len());
-            for row in &self.lines_below {
+            for row in self.lines_below_including_archived() {
                 let s: String = (&row.columns).into_iter().map(|x| x.character).collect();
                 lines_below_viewport.push(s);
             }
@@ -3396,18 +3922,14 @@ impl Grid {
 
         if get_full_scrollback {
             let mut lines_above_viewport: Vec<String> = Vec::with_capacity(self.lines_above.len());
-            for row in &self.lines_above {
-                lines_above_viewport.push(extract_row_with_ansi(row));
-            }
-            if let Some(max) = max_scrollback_lines {
-                if max > 0 && lines_above_viewport.len() > max {
-                    let start = lines_above_viewport.len() - max;
-                    lines_above_viewport = lines_above_viewport.split_off(start);
-                }
+            for row in self
+                .lines_above_including_archived(self.scrollback_lines_to_skip(max_scrollback_lines))
+            {
+                lines_above_viewport.push(extract_row_with_ansi(&row));
             }
             let mut lines_below_viewport: Vec<String> = Vec::with_capacity(self.lines_below.len());
-            for row in &self.lines_below {
-                lines_below_viewport.push(extract_row_with_ansi(row));
+            for row in self.lines_below_including_archived() {
+                lines_below_viewport.push(extract_row_with_ansi(&row));
             }
             PaneContents::new_with_scrollback(
                 viewport,
@@ -3776,6 +4298,15 @@ impl Perform for Grid {
                 }
             },
 
+            // Shell integration prompt marks (FinalTerm)
+            b"133" => {
+                if let Some(kind) =
+                    PromptMarkKind::from_osc_params(params.get(1..).unwrap_or_default())
+                {
+                    self.add_prompt_mark(kind);
+                }
+            },
+
             _ => {
                 if self.debug {
                     log::warn!("Unhandled osc: {:?}", params);
@@ -3891,10 +4422,12 @@ impl Perform for Grid {
                                 }
                                 alternate_screen_state.apply_contents_to(
                                     &mut self.lines_above,
+                                    &mut self.scrollback_archive,
                                     &mut self.viewport,
                                     &mut self.cursor,
                                     &mut self.sixel_grid,
-                                    &mut self.supports_kitty_keyboard_protocol,
+                                    &mut self.kitty_grid,
+                                    &mut self.kitty_keyboard_flags,
                                 );
                             }
                             self.alternate_screen_state = None;
@@ -3985,6 +4518,8 @@ impl Perform for Grid {
                             // enter alternate buffer
                             let current_lines_above =
                                 std::mem::replace(&mut self.lines_above, VecDeque::new());
+                            let current_scrollback_archive =
+                                std::mem::take(&mut self.scrollback_archive);
                             let current_viewport = std::mem::replace(
                                 &mut self.viewport,
                                 VecDeque::from(vec![Row::new().canonical()]),
@@ -3993,21 +4528,22 @@ impl Perform for Grid {
                                 &mut self.cursor,
                                 Cursor::new(0, 0, self.styled_underlines),
                             );
-                            let current_supports_kitty_keyboard_protocol = std::mem::replace(
-                                &mut self.supports_kitty_keyboard_protocol,
-                                false,
-                            );
+                            let current_kitty_keyboard_flags =
+                                std::mem::take(&mut self.kitty_keyboard_flags);
                             let sixel_image_store = self.sixel_grid.sixel_image_store.clone();
                             let alternate_sixelgrid = std::mem::replace(
                                 &mut self.sixel_grid,
                                 SixelGrid::new(self.character_cell_size.clone(), sixel_image_store),
                             );
+                            let alternate_kitty_grid = std::mem::take(&mut self.kitty_grid);
                             self.alternate_screen_state = Some(AlternateScreenState::new(
                                 current_lines_above,
+                                current_scrollback_archive,
                                 current_viewport,
                                 current_cursor,
                                 alternate_sixelgrid,
-                                current_supports_kitty_keyboard_protocol,
+                                alternate_kitty_grid,
+                                current_kitty_keyboard_flags,
                             ));
                             self.clear_viewport_before_rendering = true;
                             self.scrollback_buffer_lines =
@@ -4204,42 +4740,24 @@ impl Perform for Grid {
         } else if c == 's' {
             self.save_cursor_position();
         } else if c == 'u' && intermediates == &[b'>'] {
-            // Zellij only supports the first "progressive enhancement" layer of the kitty keyboard
-            // protocol
-            // 0 disables, everything else enables.
-            let count = next_param_or(0);
+            // push kitty keyboard protocol flags
+            let flags = next_param_or(0);
             if !self.explicitly_disable_kitty_keyboard_protocol {
-                if count > 0 {
-                    self.supports_kitty_keyboard_protocol = true;
-                } else {
-                    self.supports_kitty_keyboard_protocol = false;
-                }
+                self.kitty_keyboard_flags.push(flags as u16);
             }
         } else if c == 'u' && intermediates == &[b'<'] {
-            // Zellij only supports the first "progressive enhancement" layer of the kitty keyboard
-            // protocol
-            if !self.explicitly_disable_kitty_keyboard_protocol {
-                self.supports_kitty_keyboard_protocol = false;
-            }
+            // pop kitty keyboard protocol flags
+            let count = next_param_or(1);
+            self.kitty_keyboard_flags.pop(count);
         } else if c == 'u' && intermediates == &[b'?'] {
-            // Zellij only supports the first "progressive enhancement" layer of the kitty keyboard
-            // protocol
-            let reply = if self.supports_kitty_keyboard_protocol {
-                "\u{1b}[?1u"
-            } else {
-                "\u{1b}[?0u"
-            };
-            self.pending_messages_to_pty.push(reply.as_bytes().to_vec());
+            let reply = format!("\u{1b}[?{}u", self.kitty_keyboard_flags.current().bits());
+            self.pending_messages_to_pty.push(reply.into_bytes());
         } else if c == 'u' && intermediates == &[b'='] {
-            // kitty keyboard protocol without the stack, just setting.
-            // 0 disables, everything else enables.
-            let count = next_param_or(0);
+            // set the current kitty keyboard protocol flags without pushing
+            let flags = next_param_or(0);
+            let mode = next_param_or(1);
             if !self.explicitly_disable_kitty_keyboard_protocol {
-                if count > 0 {
-                    self.supports_kitty_keyboard_protocol = true;
-                } else {
-                    self.supports_kitty_keyboard_protocol = false;
-                }
+                self.kitty_keyboard_flags.set(flags as u16, mode);
             }
         } else if c == 'u' {
             self.restore_cursor_position();
@@ -4511,43 +5029,51 @@ impl Perform for Grid {
 #[derive(Clone)]
 pub struct AlternateScreenState {
     lines_above: VecDeque<Row>,
+    scrollback_archive: ScrollbackArchive,
     viewport: VecDeque<Row>,
     cursor: Cursor,
     sixel_grid: SixelGrid,
-    supports_kitty_keyboard_protocol: bool,
+    kitty_grid: KittyGrid,
+    kitty_keyboard_flags: KittyKeyboardFlagsStack,
 }
 impl AlternateScreenState {
     pub fn new(
         lines_above: VecDeque<Row>,
+        scrollback_archive: ScrollbackArchive,
         viewport: VecDeque<Row>,
         cursor: Cursor,
         sixel_grid: SixelGrid,
-        supports_kitty_keyboard_protocol: bool,
+        kitty_grid: KittyGrid,
+        kitty_keyboard_flags: KittyKeyboardFlagsStack,
     ) -> Self {
         AlternateScreenState {
             lines_above,
+            scrollback_archive,
             viewport,
             cursor,
             sixel_grid,
-            supports_kitty_keyboard_protocol,
+            kitty_grid,
+            kitty_keyboard_flags,
         }
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn apply_contents_to(
         &mut self,
         lines_above: &mut VecDeque<Row>,
+        scrollback_archive: &mut ScrollbackArchive,
         viewport: &mut VecDeque<Row>,
         cursor: &mut Cursor,
         sixel_grid: &mut SixelGrid,
-        supports_kitty_keyboard_protocol: &mut bool,
+        kitty_grid: &mut KittyGrid,
+        kitty_keyboard_flags: &mut KittyKeyboardFlagsStack,
     ) {
         std::mem::swap(&mut self.lines_above, lines_above);
+        std::mem::swap(&mut self.scrollback_archive, scrollback_archive);
         std::mem::swap(&mut self.viewport, viewport);
         std::mem::swap(&mut self.cursor, cursor);
         std::mem::swap(&mut self.sixel_grid, sixel_grid);
-        std::mem::swap(
-            &mut self.supports_kitty_keyboard_protocol,
-            supports_kitty_keyboard_protocol,
-        );
+        std::mem::swap(&mut self.kitty_grid, kitty_grid);
+        std::mem::swap(&mut self.kitty_keyboard_flags, kitty_keyboard_flags);
     }
 }
 
@@ -4557,6 +5083,7 @@ pub struct Row {
     pub is_canonical: bool,
     width: Option<usize>,
     pub bg_color: Option<AnsiCode>,
+    pub prompt_marks: Vec<PromptMark>,
 }
 
 impl Debug for Row {
@@ -4575,6 +5102,7 @@ impl Row {
             is_canonical: false,
             width: None,
             bg_color: None,
+            prompt_marks: vec![],
         }
     }
     pub fn from_columns(columns: VecDeque<TerminalCharacter>) -> Self {
@@ -4583,6 +5111,7 @@ impl Row {
             is_canonical: false,
             width: None,
             bg_color: None,
+            prompt_marks: vec![],
         }
     }
     pub fn from_rows(mut rows: Vec<Row>) -> Self {
@@ -4590,8 +5119,8 @@ impl Row {
             Row::new()
         } else {
             let mut first_row = rows.remove(0);
-            for row in &mut rows {
-                first_row.append(&mut row.columns);
+            for row in rows {
+                first_row.append_row(row);
             }
             first_row
         }
@@ -4818,6 +5347,27 @@ impl Row {
         self.columns.append(to_append);
         self.width = None;
     }
+    /// Like `append`, but also keeps the prompt marks of the appended row
+    pub fn append_row(&mut self, mut row: Row) {
+        let offset = self.width();
+        for mut prompt_mark in row.prompt_marks.drain(..) {
+            prompt_mark.column += offset;
+            self.prompt_marks.push(prompt_mark);
+        }
+        self.append(&mut row.columns);
+    }
+    pub fn add_prompt_mark(&mut self, prompt_mark: PromptMark) {
+        // shells redraw their prompts in place, so only keep the latest mark of each kind
+        self.prompt_marks.retain(|existing_mark| {
+            std::mem::discriminant(&existing_mark.kind) != std::mem::discriminant(&prompt_mark.kind)
+        });
+        self.prompt_marks.push(prompt_mark);
+    }
+    pub fn has_prompt_start(&self) -> bool {
+        self.prompt_marks
+            .iter()
+            .any(|prompt_mark| prompt_mark.kind == PromptMarkKind::PromptStart)
+    }
     pub fn drain_until(&mut self, x: usize) -> VecDeque<TerminalCharacter> {
         let mut drained_part_len = 0;
         let mut split_pos = 0;
@@ -4894,6 +5444,25 @@ impl Row {
         }
         if parts.is_empty() {
             parts.push(self.clone());
+        } else {
+            // each prompt mark goes to the part containing its column, marks past the end of the
+            // line (eg. the cursor position on an empty prompt) go to the last part
+            let mut part_start = 0;
+            let part_count = parts.len();
+            for (i, part) in parts.iter_mut().enumerate() {
+                let part_end = part_start + part.width();
+                let is_last_part = i == part_count - 1;
+                for prompt_mark in &self.prompt_marks {
+                    if prompt_mark.column >= part_start
+                        && (prompt_mark.column < part_end || is_last_part)
+                    {
+                        let mut prompt_mark = *prompt_mark;
+                        prompt_mark.column -= part_start;
+                        part.prompt_marks.push(prompt_mark);
+                    }
+                }
+                part_start = part_end;
+            }
         }
         self.width = None;
         parts
@@ -0,0 +1,901 @@
+//! The kitty graphics protocol (<https://sw.kovidgoyal.net/kitty/graphics-protocol/>).
+//!
+//! Applications send graphics commands as APC sequences (`ESC _ G <control data> ; <payload> ESC \`),
+//! which the vte parser ignores, so we pick them out of the pty output ourselves. Transmitted
+//! images are kept in a `KittyImageStore`. Each placement is placed in the `SixelGrid`, so that it
+//! is clipped, scrolled and re-rendered just like a sixel image. Clients whose host terminal
+//! supports the kitty graphics protocol get it as a kitty image (see `KittyGraphicsHost`), the
+//! others get it converted to sixel.
+
+use crate::panes::sixel::{PixelRect, SixelGrid};
+use crate::ClientId;
+use sixel_image::SixelImage;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::rc::Rc;
+use zellij_utils::pane_size::SizeInPixels;
+
+const MAX_APC_LENGTH: usize = 64 * 1024 * 1024;
+const MAX_APC_PAYLOAD_CHUNK: usize = 4096; // the chunk size kitty asks for
+const IMAGE_STORE_QUOTA: usize = 320 * 1024 * 1024; // bytes of decoded image data, same as kitty
+const MAX_IMAGE_DIMENSION: usize = 10_000; // in pixels, same as kitty
+const MAX_SIXEL_COLOR_REGISTERS: usize = 256;
+
+/// The number of bytes an image of this size takes, if it is small enough for us to hold
+fn image_byte_size(width: usize, height: usize, bytes_per_pixel: usize) -> Result<usize, String> {
+    if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+        return Err("EFBIG:image is too large".to_owned());
+    }
+    width
+        .checked_mul(height)
+        .and_then(|pixel_count| pixel_count.checked_mul(bytes_per_pixel))
+        .filter(|byte_size| *byte_size <= IMAGE_STORE_QUOTA)
+        .ok_or_else(|| "EFBIG:image is too large".to_owned())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ApcScannerState {
+    #[default]
+    Ground,
+    Escape,
+    Apc,
+    ApcEscape,
+}
+
+/// Picks complete kitty graphics APC sequences out of a byte stream
+#[derive(Debug, Clone, Default)]
+struct ApcScanner {
+    state: ApcScannerState,
+    buffer: Vec<u8>,
+    discarding: bool, // not a graphics command, or too long
+}
+
+impl ApcScanner {
+    pub fn advance(&mut self, byte: u8) -> Option<Vec<u8>> {
+        match self.state {
+            ApcScannerState::Ground => {
+                if byte == 0x1b {
+                    self.state = ApcScannerState::Escape;
+                }
+            },
+            ApcScannerState::Escape => self.advance_escape(byte),
+            ApcScannerState::Apc => match byte {
+                0x1b => self.state = ApcScannerState::ApcEscape,
+                0x18 | 0x1a => self.state = ApcScannerState::Ground, // CAN and SUB abort the sequence
+                _ => {
+                    if self.buffer.is_empty() && byte != b'G' {
+                        self.discarding = true;
+                    }
+                    if self.buffer.len() >= MAX_APC_LENGTH {
+                        self.discarding = true;
+                        self.buffer = vec![];
+                    }
+                    if !self.discarding {
+                        self.buffer.push(byte);
+                    }
+                },
+            },
+            ApcScannerState::ApcEscape => {
+                if byte == b'\\' {
+                    self.state = ApcScannerState::Ground;
+                    let apc = std::mem::take(&mut self.buffer);
+                    if !self.discarding {
+                        return Some(apc);
+                    }
+                } else {
+                    // an escape that is not ST aborts the sequence and starts a new one
+                    self.advance_escape(byte);
+                }
+            },
+        }
+        None
+    }
+    fn advance_escape(&mut self, byte: u8) {
+        self.state = match byte {
+            b'_' => {
+                self.buffer.clear();
+                self.discarding = false;
+                ApcScannerState::Apc
+            },
+            0x1b => ApcScannerState::Escape,
+            _ => ApcScannerState::Ground,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KittyGraphicsAction {
+    #[default]
+    Transmit,
+    TransmitAndDisplay,
+    Display,
+    Delete,
+    Query,
+    Unsupported, // animation frames, unicode placeholders and such
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KittyGraphicsCommand {
+    pub action: KittyGraphicsAction, // a
+    quiet: u32,                      // q - 1 suppresses OK responses, 2 suppresses errors too
+    format: u32,                     // f - 24 (RGB), 32 (RGBA) or 100 (PNG)
+    medium: u8,                      // t - d (direct), f (file), t (temporary file), s (shm)
+    compression: Option<u8>,         // o - only z (zlib) is defined
+    data_width: usize,               // s
+    data_height: usize,              // v
+    data_size: Option<usize>,        // S - when reading from a file
+    data_offset: usize,              // O - when reading from a file
+    pub image_id: u32,               // i
+    pub image_number: u32,           // I
+    pub placement_id: u32,           // p
+    more_chunks: bool,               // m
+    pub x: usize, // x - source rect left edge in pixels, or a 1-based column when deleting
+    pub y: usize, // y - source rect top edge in pixels, or a 1-based row when deleting
+    source_width: usize, // w
+    source_height: usize, // h
+    columns: usize, // c - number of cells to display the image over
+    rows: usize,  // r
+    pub cell_x_offset: usize, // X - in pixels, inside the first cell
+    pub cell_y_offset: usize, // Y
+    pub move_cursor: bool, // C=1 means the cursor should not move after placement
+    pub delete: u8, // d - what to delete, uppercase also frees the image data
+    payload: Vec<u8>, // base64 encoded
+}
+
+impl KittyGraphicsCommand {
+    pub fn parse(apc: &[u8]) -> Option<Self> {
+        let apc = apc.strip_prefix(b"G")?;
+        let (control_data, payload) = match apc.iter().position(|b| *b == b';') {
+            Some(separator) => (&apc[..separator], &apc[separator + 1..]),
+            None => (apc, &[][..]),
+        };
+        let mut command = KittyGraphicsCommand {
+            format: 32,
+            medium: b'd',
+            move_cursor: true,
+            delete: b'a',
+            payload: payload.to_vec(),
+            ..Default::default()
+        };
+        for key_value in control_data.split(|b| *b == b',') {
+            let (key, value) = match key_value {
+                [key, b'=', value @ ..] if !value.is_empty() => (*key, value),
+                _ => continue,
+            };
+            let number = || {
+                std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|value| value.parse::<usize>().ok())
+            };
+            match key {
+                b'a' => {
+                    command.action = match value[0] {
+                        b't' => KittyGraphicsAction::Transmit,
+                        b'T' => KittyGraphicsAction::TransmitAndDisplay,
+                        b'p' => KittyGraphicsAction::Display,
+                        b'd' => KittyGraphicsAction::Delete,
+                        b'q' => KittyGraphicsAction::Query,
+                        _ => KittyGraphicsAction::Unsupported,
+                    }
+                },
+                b'q' => command.quiet = number()? as u32,
+                b'f' => command.format = number()? as u32,
+                b't' => command.medium = value[0],
+                b'o' => command.compression = Some(value[0]),
+                b's' => command.data_width = number()?,
+                b'v' => command.data_height = number()?,
+                b'S' => command.data_size = Some(number()?),
+                b'O' => command.data_offset = number()?,
+                b'i' => command.image_id = number()? as u32,
+                b'I' => command.image_number = number()? as u32,
+                b'p' => command.placement_id = number()? as u32,
+                b'm' => command.more_chunks = number()? == 1,
+                b'x' => command.x = number()?,
+                b'y' => command.y = number()?,
+                b'w' => command.source_width = number()?,
+                b'h' => command.source_height = number()?,
+                b'c' => command.columns = number()?,
+                b'r' => command.rows = number()?,
+                b'X' => command.cell_x_offset = number()?,
+                b'Y' => command.cell_y_offset = number()?,
+                b'C' => command.move_cursor = number()? != 1,
+                b'd' => command.delete = value[0],
+                b'U' => command.action = KittyGraphicsAction::Unsupported,
+                _ => {}, // eg. z-index, which we cannot honor when rendering through sixel
+            }
+        }
+        Some(command)
+    }
+    /// The reply to send back to the application, if it asked for one
+    pub fn response(&self, result: &Result<(), String>) -> Option<Vec<u8>> {
+        if self.image_id == 0 && self.image_number == 0 {
+            return None;
+        }
+        let message = match result {
+            Ok(()) if self.quiet == 0 => "OK",
+            Err(error) if self.quiet < 2 => error.as_str(),
+            _ => return None,
+        };
+        let mut response = String::from("\u{1b}_G");
+        if self.image_id != 0 {
+            let _ = write!(response, "i={}", self.image_id);
+        }
+        if self.image_number != 0 {
+            if self.image_id != 0 {
+                response.push(',');
+            }
+            let _ = write!(response, "I={}", self.image_number);
+        }
+        if self.placement_id != 0 {
+            let _ = write!(response, ",p={}", self.placement_id);
+        }
+        let _ = write!(response, ";{}\u{1b}\\", message);
+        Some(response.into_bytes())
+    }
+    fn load_image(&self) -> Result<KittyImage, String> {
+        let payload = base64::decode(&self.payload)
+            .map_err(|_| "EINVAL:payload is not valid base64".to_owned())?;
+        let data = match self.medium {
+            b'd' => payload,
+            b'f' | b't' => self.read_image_file(&payload)?,
+            _ => return Err("EINVAL:unsupported transmission medium".to_owned()),
+        };
+        let data = match self.compression {
+            None => data,
+            Some(b'z') => {
+                // an image can never be larger than the store, so neither can its data
+                let mut decompressed = vec![];
+                flate2::read::ZlibDecoder::new(&data[..])
+                    .take(IMAGE_STORE_QUOTA as u64 + 1)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|_| "EINVAL:failed to decompress image data".to_owned())?;
+                if decompressed.len() > IMAGE_STORE_QUOTA {
+                    return Err("EFBIG:image data is too large".to_owned());
+                }
+                decompressed
+            },
+            Some(_) => return Err("EINVAL:unsupported compression".to_owned()),
+        };
+        KittyImage::decode(self.format, self.data_width, self.data_height, data)
+    }
+    fn read_image_file(&self, path: &[u8]) -> Result<Vec<u8>, String> {
+        let path = std::str::from_utf8(path)
+            .map(std::path::PathBuf::from)
+            .map_err(|_| "EINVAL:file name is not valid UTF-8".to_owned())?;
+        let failed_to_read =
+            |e: std::io::Error| format!("EBADF:failed to read {}: {}", path.display(), e);
+        let mut open_options = std::fs::OpenOptions::new();
+        open_options.read(true);
+        // opening without blocking lets us turn down FIFOs rather than wait for a writer
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            open_options.custom_flags(libc::O_NONBLOCK);
+        }
+        let mut file = open_options.open(&path).map_err(failed_to_read)?;
+        let metadata = file.metadata().map_err(failed_to_read)?;
+        if !metadata.is_file() {
+            return Err(format!("EBADF:{} is not a regular file", path.display()));
+        }
+        let file_size = metadata.len() as usize;
+        let start = std::cmp::min(self.data_offset, file_size);
+        let size = match self.data_size {
+            Some(data_size) => std::cmp::min(data_size, file_size - start),
+            None => file_size - start,
+        };
+        if size > IMAGE_STORE_QUOTA {
+            return Err("EFBIG:image file is too large".to_owned());
+        }
+        let mut data = Vec::with_capacity(size);
+        file.seek(SeekFrom::Start(start as u64))
+            .and_then(|_| file.take(size as u64).read_to_end(&mut data))
+            .map_err(failed_to_read)?;
+        if self.medium == b't' && is_temporary_image_file(&path) {
+            let _ = std::fs::remove_file(&path);
+        }
+        Ok(data)
+    }
+}
+
+/// Temporary files are deleted once read, as long as they are named as the protocol requires
+/// and are in the temporary directory, so that we do not delete arbitrary files
+fn is_temporary_image_file(path: &Path) -> bool {
+    let named_as_required = path
+        .file_name()
+        .map(|file_name| {
+            file_name
+                .to_string_lossy()
+                .contains("tty-graphics-protocol")
+        })
+        .unwrap_or(false);
+    let in_temp_dir = match (path.canonicalize(), std::env::temp_dir().canonicalize()) {
+        (Ok(path), Ok(temp_dir)) => path.starts_with(temp_dir),
+        _ => false,
+    };
+    named_as_required && in_temp_dir
+}
+
+/// A decoded image, as 8 bit RGBA pixels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KittyImage {
+    width: usize,
+    height: usize,
+    rgba: Vec<u8>,
+}
+
+impl KittyImage {
+    pub fn decode(format: u32, width: usize, height: usize, data: Vec<u8>) -> Result<Self, String> {
+        match format {
+            24 | 32 => {
+                let bytes_per_pixel = if format == 24 { 3 } else { 4 };
+                if width == 0 || height == 0 {
+                    return Err("EINVAL:image width and height are required".to_owned());
+                }
+                let byte_size = image_byte_size(width, height, bytes_per_pixel)?;
+                if data.len() < byte_size {
+                    return Err("ENODATA:insufficient image data".to_owned());
+                }
+                let rgba = if format == 24 {
+                    data[..byte_size]
+                        .chunks_exact(3)
+                        .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                        .collect()
+                } else {
+                    data[..byte_size].to_vec()
+                };
+                Ok(KittyImage {
+                    width,
+                    height,
+                    rgba,
+                })
+            },
+            100 => KittyImage::decode_png(&data),
+            _ => Err("EINVAL:unsupported image format".to_owned()),
+        }
+    }
+    fn decode_png(data: &[u8]) -> Result<Self, String> {
+        let bad_png = |_| "EBADPNG:failed to decode PNG data".to_owned();
+        let mut decoder = png::Decoder::new(data);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(bad_png)?;
+        let info = reader.info();
+        image_byte_size(info.width as usize, info.height as usize, 4)?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut buffer).map_err(bad_png)?;
+        let pixels = &buffer[..frame.buffer_size()];
+        let rgba = match frame.color_type {
+            png::ColorType::Rgba => pixels.to_vec(),
+            png::ColorType::Rgb => pixels
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => pixels
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            png::ColorType::Grayscale => pixels.iter().flat_map(|g| [*g, *g, *g, 255]).collect(),
+            png::ColorType::Indexed => {
+                return Err("EBADPNG:unexpected indexed PNG output".to_owned());
+            },
+        };
+        Ok(KittyImage {
+            width: frame.width as usize,
+            height: frame.height as usize,
+            rgba,
+        })
+    }
+    pub fn pixel_size(&self) -> (usize, usize) {
+        // (height, width), like SixelImage
+        (self.height, self.width)
+    }
+    fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let index = (y * self.width + x) * 4;
+        [
+            self.rgba[index],
+            self.rgba[index + 1],
+            self.rgba[index + 2],
+            self.rgba[index + 3],
+        ]
+    }
+    /// Crops and scales the image as the placement command asks
+    pub fn for_placement(
+        &self,
+        command: &KittyGraphicsCommand,
+        character_cell_size: SizeInPixels,
+    ) -> Result<KittyImage, String> {
+        let source_x = std::cmp::min(command.x, self.width);
+        let source_y = std::cmp::min(command.y, self.height);
+        let source_width = match command.source_width {
+            0 => self.width - source_x,
+            width => std::cmp::min(width, self.width - source_x),
+        };
+        let source_height = match command.source_height {
+            0 => self.height - source_y,
+            height => std::cmp::min(height, self.height - source_y),
+        };
+        if source_width == 0 || source_height == 0 {
+            return Err("EINVAL:source rectangle is empty".to_owned());
+        }
+        // cell counts come from the application, anything too large is turned down below
+        let (width, height) = match (command.columns, command.rows) {
+            (0, 0) => (source_width, source_height),
+            (columns, 0) => {
+                let width = columns.saturating_mul(character_cell_size.width);
+                (
+                    width,
+                    std::cmp::max(1, source_height.saturating_mul(width) / source_width),
+                )
+            },
+            (0, rows) => {
+                let height = rows.saturating_mul(character_cell_size.height);
+                (
+                    std::cmp::max(1, source_width.saturating_mul(height) / source_height),
+                    height,
+                )
+            },
+            (columns, rows) => (
+                columns.saturating_mul(character_cell_size.width),
+                rows.saturating_mul(character_cell_size.height),
+            ),
+        };
+        let mut rgba = Vec::with_capacity(image_byte_size(width, height, 4)?);
+        for y in 0..height {
+            let source_pixel_y = source_y + y * source_height / height;
+            for x in 0..width {
+                let source_pixel_x = source_x + x * source_width / width;
+                rgba.extend_from_slice(&self.pixel(source_pixel_x, source_pixel_y));
+            }
+        }
+        Ok(KittyImage {
+            width,
+            height,
+            rgba,
+        })
+    }
+    /// The size of the part of the rect inside the image, and its pixels as base64 encoded RGBA,
+    /// the payload of a transmit command
+    pub fn serialize_range(
+        &self,
+        pixel_x: usize,
+        pixel_y: usize,
+        pixel_width: usize,
+        pixel_height: usize,
+    ) -> (SizeInPixels, String) {
+        let pixel_x = std::cmp::min(pixel_x, self.width);
+        let pixel_y = std::cmp::min(pixel_y, self.height);
+        let right_edge = std::cmp::min(pixel_x + pixel_width, self.width);
+        let bottom_edge = std::cmp::min(pixel_y + pixel_height, self.height);
+        let mut rgba = vec![];
+        for y in pixel_y..bottom_edge {
+            let row_start = y * self.width * 4;
+            rgba.extend_from_slice(&self.rgba[row_start + pixel_x * 4..row_start + right_edge * 4]);
+        }
+        let size = SizeInPixels {
+            height: bottom_edge - pixel_y,
+            width: right_edge - pixel_x,
+        };
+        (size, base64::encode(rgba))
+    }
+    /// Makes the pixels in the rect transparent, like `SixelImage::cut_out`
+    pub fn cut_out(
+        &mut self,
+        pixel_x: usize,
+        pixel_y: usize,
+        pixel_width: usize,
+        pixel_height: usize,
+    ) {
+        let right_edge = std::cmp::min(pixel_x + pixel_width, self.width);
+        let bottom_edge = std::cmp::min(pixel_y + pixel_height, self.height);
+        for y in pixel_y..bottom_edge {
+            for x in pixel_x..right_edge {
+                self.rgba[(y * self.width + x) * 4 + 3] = 0;
+            }
+        }
+    }
+    pub fn to_sixel_image(&self) -> Option<SixelImage> {
+        let opaque = |pixel: [u8; 4]| pixel[3] >= 128;
+        let mut colors: BTreeMap<[u8; 3], u16> = BTreeMap::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pixel = self.pixel(x, y);
+                if opaque(pixel) && colors.len() <= MAX_SIXEL_COLOR_REGISTERS {
+                    let next_register = colors.len() as u16;
+                    colors
+                        .entry([pixel[0], pixel[1], pixel[2]])
+                        .or_insert(next_register);
+                }
+            }
+        }
+        let quantize = colors.len() > MAX_SIXEL_COLOR_REGISTERS;
+        let color_register = |pixel: [u8; 4]| -> u16 {
+            if quantize {
+                // a 6x7x6 color cube
+                let r = pixel[0] as u16 * 5 / 255;
+                let g = pixel[1] as u16 * 6 / 255;
+                let b = pixel[2] as u16 * 5 / 255;
+                r * 42 + g * 6 + b
+            } else {
+                *colors.get(&[pixel[0], pixel[1], pixel[2]]).unwrap_or(&0)
+            }
+        };
+
+        let mut serialized = String::from("\u{1b}P0;1q");
+        let _ = write!(serialized, "\"1;1;{};{}", self.width, self.height);
+        if quantize {
+            for r in 0..6 {
+                for g in 0..7 {
+                    for b in 0..6 {
+                        let _ = write!(
+                            serialized,
+                            "#{};2;{};{};{}",
+                            r * 42 + g * 6 + b,
+                            r * 100 / 5,
+                            g * 100 / 6,
+                            b * 100 / 5
+                        );
+                    }
+                }
+            }
+        } else {
+            for (color, register) in &colors {
+                let _ = write!(
+                    serialized,
+                    "#{};2;{};{};{}",
+                    register,
+                    color[0] as usize * 100 / 255,
+                    color[1] as usize * 100 / 255,
+                    color[2] as usize * 100 / 255
+                );
+            }
+        }
+        for band_top in (0..self.height).step_by(6) {
+            let mut band: BTreeMap<u16, Vec<u8>> = BTreeMap::new();
+            for y in band_top..std::cmp::min(band_top + 6, self.height) {
+                for x in 0..self.width {
+                    let pixel = self.pixel(x, y);
+                    if opaque(pixel) {
+                        band.entry(color_register(pixel))
+                            .or_insert_with(|| vec![0; self.width])[x] |= 1 << (y - band_top);
+                    }
+                }
+            }
+            for (register, sixels) in band {
+                let _ = write!(serialized, "#{}", register);
+                let mut sixels = sixels.into_iter().peekable();
+                while let Some(sixel) = sixels.next() {
+                    let mut repeat_count = 1;
+                    while sixels.peek() == Some(&sixel) {
+                        sixels.next();
+                        repeat_count += 1;
+                    }
+                    let sixel_character = (sixel + 63) as char;
+                    if repeat_count > 3 {
+                        let _ = write!(serialized, "!{}{}", repeat_count, sixel_character);
+                    } else {
+                        for _ in 0..repeat_count {
+                            serialized.push(sixel_character);
+                        }
+                    }
+                }
+                serialized.push('$');
+            }
+            serialized.push('-');
+        }
+        serialized.push_str("\u{1b}\\");
+        SixelImage::new(serialized.as_bytes()).ok()
+    }
+    fn byte_count(&self) -> usize {
+        self.rgba.len()
+    }
+}
+
+/// Images transmitted by the application, to be placed later by id or number
+#[derive(Debug, Clone, Default)]
+pub struct KittyImageStore {
+    images: HashMap<u32, Rc<KittyImage>>, // key is the image id
+    image_numbers: HashMap<u32, u32>,     // image number -> image id
+    insertion_order: Vec<u32>,
+}
+
+impl KittyImageStore {
+    pub fn insert(&mut self, image_id: u32, image: Rc<KittyImage>) {
+        self.remove(image_id);
+        self.images.insert(image_id, image);
+        self.insertion_order.push(image_id);
+        // evict the oldest images until we are back within the quota
+        while self.byte_count() > IMAGE_STORE_QUOTA && self.insertion_order.len() > 1 {
+            let oldest_image_id = self.insertion_order[0];
+            self.remove(oldest_image_id);
+        }
+    }
+    pub fn get(&self, image_id: u32) -> Option<Rc<KittyImage>> {
+        self.images.get(&image_id).cloned()
+    }
+    pub fn remove(&mut self, image_id: u32) {
+        self.images.remove(&image_id);
+        self.insertion_order.retain(|id| *id != image_id);
+        self.image_numbers.retain(|_, id| *id != image_id);
+    }
+    pub fn image_count(&self) -> usize {
+        self.images.len()
+    }
+    /// The image id for the command, assigning a new one if the command uses an image number
+    fn image_id_for(&mut self, command: &KittyGraphicsCommand) -> u32 {
+        if command.image_id != 0 || command.image_number == 0 {
+            return command.image_id;
+        }
+        let image_id = self.images.keys().max().map(|id| id + 1).unwrap_or(1);
+        self.image_numbers.insert(command.image_number, image_id);
+        image_id
+    }
+    fn resolve_image_id(&self, command: &KittyGraphicsCommand) -> u32 {
+        if command.image_id == 0 && command.image_number != 0 {
+            self.image_numbers
+                .get(&command.image_number)
+                .copied()
+                .unwrap_or(0)
+        } else {
+            command.image_id
+        }
+    }
+    fn byte_count(&self) -> usize {
+        self.images.values().map(|image| image.byte_count()).sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KittyPlacement {
+    image_id: u32,
+    placement_id: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KittyGraphicsParser {
+    apc_scanner: ApcScanner,
+    pending_transmission: Option<KittyGraphicsCommand>, // chunked transmission in progress
+}
+
+impl KittyGraphicsParser {
+    /// Returns a graphics command once one (including all its chunks) is complete
+    pub fn advance(&mut self, byte: u8) -> Option<KittyGraphicsCommand> {
+        let command = KittyGraphicsCommand::parse(&self.apc_scanner.advance(byte)?)?;
+        match self.pending_transmission.take() {
+            Some(mut pending_transmission) => {
+                // subsequent chunks only carry the payload and the m key
+                pending_transmission.payload.extend(command.payload);
+                if command.more_chunks {
+                    self.pending_transmission = Some(pending_transmission);
+                    None
+                } else {
+                    Some(pending_transmission)
+                }
+            },
+            None if command.more_chunks => {
+                self.pending_transmission = Some(command);
+                None
+            },
+            None => Some(command),
+        }
+    }
+}
+
+/// The images and placements of one screen (like kitty, the alternate screen has its own)
+#[derive(Debug, Clone, Default)]
+pub struct KittyGrid {
+    pub image_store: KittyImageStore,
+    placements: HashMap<usize, KittyPlacement>, // key is the id of the image in the SixelGrid
+}
+
+impl KittyGrid {
+    /// Decodes the image of a transmit or query command, storing it if it has an id or number
+    pub fn transmit(
+        &mut self,
+        command: &mut KittyGraphicsCommand,
+    ) -> Result<Rc<KittyImage>, String> {
+        let image = Rc::new(command.load_image()?);
+        if command.action != KittyGraphicsAction::Query {
+            command.image_id = self.image_store.image_id_for(command);
+            if command.image_id != 0 {
+                self.image_store.insert(command.image_id, image.clone());
+            }
+        }
+        Ok(image)
+    }
+    pub fn stored_image(&self, command: &KittyGraphicsCommand) -> Result<Rc<KittyImage>, String> {
+        self.image_store
+            .get(self.image_store.resolve_image_id(command))
+            .ok_or_else(|| "ENOENT:no such image".to_owned())
+    }
+    /// Returns the id of the placement this one replaces, if any
+    pub fn add_placement(
+        &mut self,
+        sixel_image_id: usize,
+        command: &KittyGraphicsCommand,
+        sixel_grid: &SixelGrid,
+    ) -> Option<usize> {
+        self.remove_stale_placements(sixel_grid);
+        let image_id = self.image_store.resolve_image_id(command);
+        let replaced_placement = if command.placement_id != 0 {
+            self.placements
+                .iter()
+                .find(|(_, placement)| {
+                    placement.image_id == image_id && placement.placement_id == command.placement_id
+                })
+                .map(|(sixel_image_id, _)| *sixel_image_id)
+        } else {
+            None
+        };
+        if let Some(replaced_placement) = replaced_placement {
+            self.placements.remove(&replaced_placement);
+        }
+        self.placements.insert(
+            sixel_image_id,
+            KittyPlacement {
+                image_id,
+                placement_id: command.placement_id,
+            },
+        );
+        replaced_placement
+    }
+    /// Removes the placements the delete command selects, returning their ids in the SixelGrid.
+    /// `cursor_rect` and `viewport_rect` are in the same absolute pixel coordinates as the
+    /// image locations
+    pub fn delete_placements(
+        &mut self,
+        command: &KittyGraphicsCommand,
+        sixel_grid: &SixelGrid,
+        character_cell_size: SizeInPixels,
+        cursor_rect: PixelRect,
+        viewport_rect: PixelRect,
+    ) -> Vec<usize> {
+        self.remove_stale_placements(sixel_grid);
+        let image_id = self.image_store.resolve_image_id(command);
+        let cell_rect = |column: usize, row: usize| PixelRect {
+            x: column.saturating_sub(1) * character_cell_size.width,
+            y: viewport_rect.y + (row.saturating_sub(1) * character_cell_size.height) as isize,
+            width: character_cell_size.width,
+            height: character_cell_size.height,
+        };
+        let rect_to_delete = match command.delete.to_ascii_lowercase() {
+            b'c' => Some(cursor_rect),
+            b'p' => Some(cell_rect(command.x, command.y)),
+            b'x' => Some(PixelRect {
+                height: viewport_rect.height,
+                ..cell_rect(command.x, 1)
+            }),
+            b'y' => Some(PixelRect {
+                x: 0,
+                width: viewport_rect.width,
+                ..cell_rect(1, command.y)
+            }),
+            _ => None,
+        };
+        let image_locations: HashMap<usize, PixelRect> = sixel_grid
+            .image_coordinates()
+            .map(|(id, pixel_rect)| (id, *pixel_rect))
+            .collect();
+        let placements_to_delete: Vec<usize> = self
+            .placements
+            .iter()
+            .filter(
+                |(sixel_image_id, placement)| match command.delete.to_ascii_lowercase() {
+                    b'a' => true,
+                    b'i' | b'n' => {
+                        placement.image_id == image_id
+                            && (command.placement_id == 0
+                                || placement.placement_id == command.placement_id)
+                    },
+                    _ => match (rect_to_delete, image_locations.get(sixel_image_id)) {
+                        (Some(rect_to_delete), Some(image_location)) => {
+                            image_location.intersecting_rect(&rect_to_delete).is_some()
+                        },
+                        _ => false,
+                    },
+                },
+            )
+            .map(|(sixel_image_id, _)| *sixel_image_id)
+            .collect();
+        let free_image_data = command.delete.is_ascii_uppercase();
+        for sixel_image_id in &placements_to_delete {
+            if let Some(placement) = self.placements.remove(sixel_image_id) {
+                if free_image_data {
+                    self.image_store.remove(placement.image_id);
+                }
+            }
+        }
+        if free_image_data && matches!(command.delete, b'I' | b'N') {
+            // also when the image has no placements
+            self.image_store.remove(image_id);
+        }
+        placements_to_delete
+    }
+    fn remove_stale_placements(&mut self, sixel_grid: &SixelGrid) {
+        // placements scrolled out of the scrollback or covered by other images are reaped by the
+        // SixelGrid
+        let image_ids: Vec<usize> = sixel_grid.image_coordinates().map(|(id, _)| id).collect();
+        self.placements
+            .retain(|sixel_image_id, _| image_ids.contains(sixel_image_id));
+    }
+}
+
+/// The image id we give the image placed at this cell of the host terminal. Each cell holds at
+/// most one of our placements, so a new one there replaces the old one, as it would with sixel.
+fn host_image_id(cell_x: usize, cell_y: usize) -> u32 {
+    ((cell_y as u32) << 16 | (cell_x as u32 & 0xffff)) + 1
+}
+
+/// The hosts of the clients that reported supporting the kitty graphics protocol
+pub type KittyGraphicsHosts = HashMap<ClientId, KittyGraphicsHost>;
+
+/// The kitty graphics placements we made on the terminal of one client.
+///
+/// Unlike sixel pixels, kitty images are not erased by the text written over them, so we keep
+/// track of them and delete them ourselves. Images are placed one cell row at a time, so that
+/// text written over some of the rows of an image deletes just those rows, which are then
+/// re-rendered along with the text.
+#[derive(Debug, Clone, Default)]
+pub struct KittyGraphicsHost {
+    placements: BTreeMap<(usize, usize), usize>, // (cell_y, cell_x) -> width in cells
+}
+
+impl KittyGraphicsHost {
+    /// Deletes the placements that the text written on row `y` from column `x` to `x_end` covers
+    pub fn delete_placements_under_text(
+        &mut self,
+        y: usize,
+        x: usize,
+        x_end: usize,
+        vte_output: &mut String,
+    ) {
+        let covered_placements: Vec<(usize, usize)> = self
+            .placements
+            .range((y, 0)..(y + 1, 0))
+            .filter(|((_, cell_x), width)| *cell_x < x_end && cell_x + **width > x)
+            .map(|(cell, _)| *cell)
+            .collect();
+        for (cell_y, cell_x) in covered_placements {
+            self.placements.remove(&(cell_y, cell_x));
+            let _ = write!(
+                vte_output,
+                "\u{1b}_Ga=d,d=I,i={},q=2\u{1b}\\",
+                host_image_id(cell_x, cell_y)
+            );
+        }
+    }
+    /// Transmits and displays one row of an image at the cursor, which is at `cell_x`/`cell_y`,
+    /// without moving it. `rgba` is the base64 encoded payload from `KittyImage::serialize_range`
+    pub fn place_image_row(
+        &mut self,
+        cell_x: usize,
+        cell_y: usize,
+        width_in_cells: usize,
+        pixel_size: SizeInPixels,
+        rgba: &str,
+        vte_output: &mut String,
+    ) {
+        self.placements.insert((cell_y, cell_x), width_in_cells);
+        // base64 is ascii, so the payload can be chunked anywhere
+        let mut chunks = rgba.as_bytes().chunks(MAX_APC_PAYLOAD_CHUNK).peekable();
+        let mut is_first_chunk = true;
+        while let Some(chunk) = chunks.next() {
+            let more_chunks = if chunks.peek().is_some() { 1 } else { 0 };
+            if is_first_chunk {
+                let _ = write!(
+                    vte_output,
+                    "\u{1b}_Ga=T,f=32,s={},v={},i={},C=1,q=2,m={};",
+                    pixel_size.width,
+                    pixel_size.height,
+                    host_image_id(cell_x, cell_y),
+                    more_chunks
+                );
+                is_first_chunk = false;
+            } else {
+                let _ = write!(vte_output, "\u{1b}_Gm={};", more_chunks);
+            }
+            vte_output.push_str(&String::from_utf8_lossy(chunk));
+            vte_output.push_str("\u{1b}\\");
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! The progressive enhancements of the kitty keyboard protocol
+//! (https://sw.kovidgoyal.net/kitty/keyboard-protocol/#progressive-enhancement).
+//!
+//! We ask the host terminal for all of them, so that the key events we get from it carry
+//! everything (event types, alternate keys, associated text...). Each pane then gets these events
+//! re-encoded according to the flags its application pushed.
+
+use zellij_utils::data::{BareKey, KeyModifier, KeyWithModifier};
+use zellij_utils::vendored::termwiz::input::KittyKeyboardFlags;
+
+// the spec leaves the size of the stack to the implementation, the oldest entries are evicted when
+// it is full
+const MAX_FLAGS_STACK_SIZE: usize = 16;
+
+const SHIFT: u32 = 0b0000_0001;
+const ALT: u32 = 0b0000_0010;
+const CTRL: u32 = 0b0000_0100;
+const SUPER: u32 = 0b0000_1000;
+const CAPS_LOCK: u32 = 0b0100_0000;
+const NUM_LOCK: u32 = 0b1000_0000;
+const LOCK_MODIFIERS: u32 = CAPS_LOCK | NUM_LOCK;
+
+const ENTER: u32 = 13;
+const TAB: u32 = 9;
+const BACKSPACE: u32 = 127;
+const ESC: u32 = 27;
+
+// the functional keys (eg. the keypad or media keys) are mapped to the unicode private use area
+const FIRST_FUNCTIONAL_KEY: u32 = 57344;
+const LAST_FUNCTIONAL_KEY: u32 = 63743;
+const FIRST_MODIFIER_KEY: u32 = 57441; // left shift
+const LAST_MODIFIER_KEY: u32 = 57452; // iso level 5 shift
+
+/// The stack of flags pushed by the application, each screen (main and alternate) has its own
+#[derive(Debug, Clone, Default)]
+pub struct KittyKeyboardFlagsStack {
+    stack: Vec<KittyKeyboardFlags>,
+}
+
+impl KittyKeyboardFlagsStack {
+    pub fn current(&self) -> KittyKeyboardFlags {
+        self.stack
+            .last()
+            .copied()
+            .unwrap_or(KittyKeyboardFlags::NONE)
+    }
+    pub fn push(&mut self, flags: u16) {
+        if self.stack.len() == MAX_FLAGS_STACK_SIZE {
+            self.stack.remove(0);
+        }
+        self.stack
+            .push(KittyKeyboardFlags::from_bits_truncate(flags));
+    }
+    pub fn pop(&mut self, count: usize) {
+        let remaining = self.stack.len().saturating_sub(count);
+        self.stack.truncate(remaining);
+    }
+    /// `mode` is 1 to replace the current flags, 2 to add to them and 3 to remove from them
+    pub fn set(&mut self, flags: u16, mode: usize) {
+        let flags = KittyKeyboardFlags::from_bits_truncate(flags);
+        let current = self.current();
+        let new_flags = match mode {
+            1 => flags,
+            2 => current | flags,
+            3 => current - flags,
+            _ => return,
+        };
+        match self.stack.last_mut() {
+            Some(top) => *top = new_flags,
+            None => self.stack.push(new_flags),
+        }
+    }
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEventType {
+    Press,
+    Repeat,
+    Release,
+}
+
+/// A key event as reported by a host terminal in kitty keyboard mode:
+/// `CSI key-code[:shifted-key[:base-layout-key]] ; modifiers[:event-type] ; text u`
+/// (or `~` and the legacy letter terminators for some of the functional keys)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KittyKeyEvent {
+    key_code: u32,
+    shifted_key: Option<u32>,
+    base_layout_key: Option<u32>,
+    modifiers: u32, // without the +1 of the encoding
+    event_type: KeyEventType,
+    text: Vec<u32>, // codepoints
+    terminator: u8,
+}
+
+impl KittyKeyEvent {
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let params = bytes.strip_prefix(b"\x1b[")?;
+        let (&terminator, params) = params.split_last()?;
+        if !(terminator == b'u' || terminator == b'~' || terminator.is_ascii_alphabetic()) {
+            return None;
+        }
+        let params = std::str::from_utf8(params).ok()?;
+        let mut fields = params.split(';');
+        let mut key_codes = fields.next().unwrap_or("").split(':');
+        let key_code = match key_codes.next() {
+            Some("") | None if terminator != b'u' => 1,
+            Some(key_code) => key_code.parse().ok()?,
+            None => return None,
+        };
+        let shifted_key = key_codes.next().and_then(|k| k.parse().ok());
+        let base_layout_key = key_codes.next().and_then(|k| k.parse().ok());
+        let mut modifiers_and_event_type = fields.next().unwrap_or("").split(':');
+        let modifiers = modifiers_and_event_type
+            .next()
+            .and_then(|m| m.parse::<u32>().ok())
+            .map(|m| m.saturating_sub(1))
+            .unwrap_or(0);
+        let event_type = match modifiers_and_event_type.next() {
+            Some("2") => KeyEventType::Repeat,
+            Some("3") => KeyEventType::Release,
+            _ => KeyEventType::Press,
+        };
+        let text = fields
+            .next()
+            .map(|text| text.split(':').filter_map(|c| c.parse().ok()).collect())
+            .unwrap_or_default();
+        Some(KittyKeyEvent {
+            key_code,
+            shifted_key,
+            base_layout_key,
+            modifiers,
+            event_type,
+            text,
+            terminator,
+        })
+    }
+    /// For keys we did not get from a host terminal in kitty keyboard mode
+    pub fn from_key(key: &KeyWithModifier) -> Option<Self> {
+        let mut modifiers = 0;
+        for key_modifier in &key.key_modifiers {
+            modifiers |= match key_modifier {
+                KeyModifier::Shift => SHIFT,
+                KeyModifier::Alt => ALT,
+                KeyModifier::Ctrl => CTRL,
+                KeyModifier::Super => SUPER,
+            };
+        }
+        let mut text = vec![];
+        let (key_code, terminator) = match key.bare_key {
+            BareKey::Char(character) => {
+                let lowercase = character.to_lowercase().next().unwrap_or(character);
+                if lowercase != character {
+                    modifiers |= SHIFT;
+                }
+                if modifiers & !SHIFT == 0 {
+                    text.push(character as u32);
+                }
+                (lowercase as u32, b'u')
+            },
+            BareKey::Enter => (ENTER, b'u'),
+            BareKey::Tab => (TAB, b'u'),
+            BareKey::Backspace => (BACKSPACE, b'u'),
+            BareKey::Esc => (ESC, b'u'),
+            BareKey::Left => (1, b'D'),
+            BareKey::Right => (1, b'C'),
+            BareKey::Up => (1, b'A'),
+            BareKey::Down => (1, b'B'),
+            BareKey::Home => (1, b'H'),
+            BareKey::End => (1, b'F'),
+            BareKey::Insert => (2, b'~'),
+            BareKey::Delete => (3, b'~'),
+            BareKey::PageUp => (5, b'~'),
+            BareKey::PageDown => (6, b'~'),
+            BareKey::F(1) => (1, b'P'),
+            BareKey::F(2) => (1, b'Q'),
+            BareKey::F(3) => (13, b'~'),
+            BareKey::F(4) => (1, b'S'),
+            BareKey::F(5) => (15, b'~'),
+            BareKey::F(6) => (17, b'~'),
+            BareKey::F(7) => (18, b'~'),
+            BareKey::F(8) => (19, b'~'),
+            BareKey::F(9) => (20, b'~'),
+            BareKey::F(10) => (21, b'~'),
+            BareKey::F(11) => (23, b'~'),
+            BareKey::F(12) => (24, b'~'),
+            BareKey::F(_) => return None,
+            BareKey::CapsLock => (57358, b'u'),
+            BareKey::ScrollLock => (57359, b'u'),
+            BareKey::NumLock => (57360, b'u'),
+            BareKey::PrintScreen => (57361, b'u'),
+            BareKey::Pause => (57362, b'u'),
+            BareKey::Menu => (57363, b'u'),
+        };
+        Some(KittyKeyEvent {
+            key_code,
+            shifted_key: None,
+            base_layout_key: None,
+            modifiers,
+            event_type: KeyEventType::Press,
+            text,
+            terminator,
+        })
+    }
+    /// A press (or repeat) of a key other than a lone modifier, ie. an event a terminal without
+    /// the kitty keyboard protocol would also report. Only these go through the keybindings.
+    pub fn is_key_press(&self) -> bool {
+        self.event_type != KeyEventType::Release && !self.is_modifier_key()
+    }
+    fn is_modifier_key(&self) -> bool {
+        self.terminator == b'u' && (FIRST_MODIFIER_KEY..=LAST_MODIFIER_KEY).contains(&self.key_code)
+    }
+    /// Encodes this event for an application that pushed `flags`, returns None if the event
+    /// should not be reported to it at all
+    pub fn encode(&self, flags: KittyKeyboardFlags) -> Option<Vec<u8>> {
+        let report_all_keys = flags.contains(KittyKeyboardFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES);
+        let report_event_types = flags.contains(KittyKeyboardFlags::REPORT_EVENT_TYPES);
+        if self.event_type == KeyEventType::Release && !report_event_types {
+            return None;
+        }
+        if self.is_modifier_key() && !report_all_keys {
+            return None;
+        }
+        if !report_all_keys {
+            if let Some(legacy_bytes) = self.legacy_bytes() {
+                // release events are never reported for keys sent as text
+                return match self.event_type {
+                    KeyEventType::Release => None,
+                    _ => Some(legacy_bytes),
+                };
+            }
+        }
+        let modifiers = if report_all_keys {
+            self.modifiers
+        } else {
+            self.modifiers & !LOCK_MODIFIERS
+        };
+        let mut key_field = self.key_code.to_string();
+        if flags.contains(KittyKeyboardFlags::REPORT_ALTERNATE_KEYS) && self.terminator == b'u' {
+            let shifted_key = self.shifted_key.filter(|_| modifiers & SHIFT != 0);
+            match (shifted_key, self.base_layout_key) {
+                (Some(shifted_key), Some(base_layout_key)) => {
+                    key_field.push_str(&format!(":{}:{}", shifted_key, base_layout_key))
+                },
+                (Some(shifted_key), None) => key_field.push_str(&format!(":{}", shifted_key)),
+                (None, Some(base_layout_key)) => {
+                    key_field.push_str(&format!("::{}", base_layout_key))
+                },
+                (None, None) => {},
+            }
+        }
+        let mut modifiers_field = if modifiers != 0 {
+            (modifiers + 1).to_string()
+        } else {
+            String::new()
+        };
+        if report_event_types && self.event_type != KeyEventType::Press {
+            if modifiers_field.is_empty() {
+                modifiers_field.push('1');
+            }
+            match self.event_type {
+                KeyEventType::Repeat => modifiers_field.push_str(":2"),
+                _ => modifiers_field.push_str(":3"),
+            }
+        }
+        let text_field = if flags.contains(KittyKeyboardFlags::REPORT_ASSOCIATED_TEXT)
+            && report_all_keys
+            && self.event_type != KeyEventType::Release
+        {
+            self.text
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(":")
+        } else {
+            String::new()
+        };
+        let mut encoded = String::from("\u{1b}[");
+        if !text_field.is_empty() {
+            encoded.push_str(&format!("{};{};{}", key_field, modifiers_field, text_field));
+        } else if !modifiers_field.is_empty() {
+            encoded.push_str(&format!("{};{}", key_field, modifiers_field));
+        } else if self.terminator == b'u' || self.terminator == b'~' {
+            encoded.push_str(&key_field);
+        }
+        // the keys with letter terminators are "CSI A" unless they have modifiers
+        encoded.push(self.terminator as char);
+        Some(encoded.into_bytes())
+    }
+    /// The keys that are still sent as they are in legacy mode unless all keys are reported as
+    /// escape codes: those producing text, and Enter, Tab and Backspace without modifiers
+    fn legacy_bytes(&self) -> Option<Vec<u8>> {
+        if self.terminator != b'u' {
+            return None;
+        }
+        let modifiers = self.modifiers & !LOCK_MODIFIERS;
+        match self.key_code {
+            ENTER if modifiers == 0 => Some(b"\r".to_vec()),
+            TAB if modifiers == 0 => Some(b"\t".to_vec()),
+            BACKSPACE if modifiers == 0 => Some(b"\x7f".to_vec()),
+            ENTER | TAB | BACKSPACE | ESC => None,
+            key_code if modifiers & !SHIFT == 0 && produces_text(key_code) => {
+                let text: String = if !self.text.is_empty() {
+                    self.text
+                        .iter()
+                        .filter_map(|c| char::from_u32(*c))
+                        .collect()
+                } else if modifiers & SHIFT != 0 {
+                    let shifted_key = self.shifted_key.and_then(char::from_u32).or_else(|| {
+                        char::from_u32(key_code).and_then(|c| c.to_uppercase().next())
+                    })?;
+                    shifted_key.to_string()
+                } else {
+                    char::from_u32(key_code)?.to_string()
+                };
+                Some(text.into_bytes())
+            },
+            _ => None,
+        }
+    }
+}
+
+fn produces_text(key_code: u32) -> bool {
+    key_code >= 32
+        && key_code != BACKSPACE
+        && !(FIRST_FUNCTIONAL_KEY..=LAST_FUNCTIONAL_KEY).contains(&key_code)
+        && char::from_u32(key_code).is_some()
+}
+
+#[cfg(test)]
+#[path = "./unit/kitty_keyboard_tests.rs"]
+mod kitty_keyboard_tests;
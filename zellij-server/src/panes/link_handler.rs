@@ -62,6 +62,17 @@ impl LinkHandler {
         anchor
     }
 
+    pub fn link(&self, index: u16) -> Option<&Link> {
+        self.links.get(&index)
+    }
+
+    pub fn add_link(&mut self, link: Link) -> u16 {
+        let index = self.link_index;
+        self.links.insert(index, link);
+        self.link_index += 1;
+        index
+    }
+
     pub fn output_osc8(&self, link_anchor: Option<LinkAnchor>) -> Option<String> {
         link_anchor.and_then(|link| match link {
             LinkAnchor::Start(index) => {
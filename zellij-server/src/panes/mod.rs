@@ -1,8 +1,14 @@
 pub mod alacritty_functions;
+pub mod copy_mode;
 pub mod grid;
 pub mod hyperlink_tracker;
+pub mod kitty_graphics;
+pub mod kitty_keyboard;
 pub mod link_handler;
+pub mod scrollback_archive;
+pub mod scrollback_store;
 pub mod selection;
+pub mod shell_integration;
 pub mod sixel;
 pub mod terminal_character;
 
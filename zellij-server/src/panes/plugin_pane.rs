@@ -4,6 +4,7 @@ use std::time::Instant;
 use crate::output::{CharacterChunk, SixelImageChunk};
 use crate::panes::{
     grid::Grid,
+    kitty_keyboard::KittyKeyEvent,
     sixel::SixelImageStore,
     terminal_pane::{BRACKETED_PASTE_BEGIN, BRACKETED_PASTE_END},
     LinkHandler, PaneId,
@@ -276,10 +277,17 @@ impl Pane for PluginPane {
         &mut self,
         key_with_modifier: &Option<KeyWithModifier>,
         mut raw_input_bytes: Vec<u8>,
-        _raw_input_bytes_are_kitty: bool,
+        raw_input_bytes_are_kitty: bool,
         client_id: Option<ClientId>,
     ) -> Option<AdjustedInput> {
-        if client_id
+        if raw_input_bytes_are_kitty
+            && KittyKeyEvent::parse(&raw_input_bytes)
+                .map(|key_event| !key_event.is_key_press())
+                .unwrap_or(false)
+        {
+            // plugins only get key presses
+            None
+        } else if client_id
             .and_then(|c| self.grids.get(&c))
             .map(|g| g.has_selection())
             .unwrap_or(false)
@@ -0,0 +1,752 @@
+//! Disk-backed overflow for the scrollback buffer.
+//!
+//! Rows that fall off the top of a pane's in-memory scrollback (`scroll_buffer_size`) are
+//! collected here instead of being dropped. Every `ROWS_PER_CHUNK` rows are encoded as styled
+//! runs and handed to a background thread, which deflates them and writes them to a spill file
+//! under the cache dir. Until then, a chunk is read from memory. There is one spill file per
+//! session (server process): it is unlinked as soon as it is created, so the OS reclaims it
+//! when the session ends, even if it crashes. Space freed by trimmed chunks is reused by
+//! later ones.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use crossbeam::channel::{unbounded, Sender};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use unicode_width::UnicodeWidthChar;
+use zellij_utils::consts::ZELLIJ_CACHE_DIR;
+
+use crate::panes::grid::Row;
+use crate::panes::shell_integration::{PromptMark, PromptMarkKind};
+use crate::panes::terminal_character::{
+    AnsiCode, AnsiStyledUnderline, LinkAnchor, NamedColor, RcCharacterStyles, TerminalCharacter,
+    RESET_STYLES,
+};
+
+const ROWS_PER_CHUNK: usize = 1000;
+
+static SPILL_FILE_WRITER: OnceLock<Option<SpillFileWriter>> = OnceLock::new();
+
+/// Scrollback rows older than the in-memory buffer, oldest first.
+///
+/// An archive with a `max_rows` of 0 (the default) stores nothing, but still counts the rows
+/// pushed through it so the absolute position of the remaining scrollback is known.
+///
+/// Cloning is cheap: packed chunks are shared and their space in the spill file is released
+/// when the last clone referencing them is dropped.
+#[derive(Clone, Default)]
+pub struct ScrollbackArchive {
+    chunks: VecDeque<ArchiveEntry>,
+    packed_row_count: usize,
+    unpacked_rows: Vec<Row>,
+    max_rows: usize,
+    dropped_row_count: u64,
+}
+
+impl ScrollbackArchive {
+    pub fn new(max_rows: usize) -> Self {
+        ScrollbackArchive {
+            chunks: VecDeque::new(),
+            packed_row_count: 0,
+            unpacked_rows: vec![],
+            max_rows,
+            dropped_row_count: 0,
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.packed_row_count + self.unpacked_rows.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Number of rows that were dropped off the top since the archive was created or cleared
+    pub fn dropped_row_count(&self) -> u64 {
+        self.dropped_row_count
+    }
+    /// Adds a row below all previously archived rows, dropping the oldest chunks once
+    /// `max_rows` is exceeded
+    pub fn push(&mut self, row: Row) {
+        self.unpacked_rows.push(row);
+        if self.unpacked_rows.len() >= ROWS_PER_CHUNK {
+            self.pack_unpacked_rows();
+        }
+        self.trim();
+    }
+    /// Removes and returns the most recently archived row, loading its chunk back into memory
+    /// if needed
+    pub fn pop_back(&mut self) -> Option<Row> {
+        if self.unpacked_rows.is_empty() {
+            let entry = self.chunks.pop_back()?;
+            self.packed_row_count -= entry.chunk.row_count;
+            match entry.load() {
+                Ok(rows) => self.unpacked_rows = rows,
+                Err(e) => log::error!("Failed to load archived scrollback: {}", e),
+            }
+        }
+        self.unpacked_rows.pop()
+    }
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.packed_row_count = 0;
+        self.unpacked_rows.clear();
+        self.dropped_row_count = 0;
+    }
+    /// Moves all the rows of `other` after the rows of this archive, in reverse order (eg. from
+    /// the rows above the viewport, oldest first, to those below it, nearest first). Packed
+    /// chunks are moved as they are, without being read back. The archive is not trimmed, so
+    /// that the caller can take back the rows it wants to keep in memory first.
+    pub fn append_reversed(&mut self, other: &mut ScrollbackArchive) {
+        for row in other.unpacked_rows.drain(..).rev() {
+            self.unpacked_rows.push(row);
+            if self.unpacked_rows.len() >= ROWS_PER_CHUNK {
+                self.pack_unpacked_rows();
+            }
+        }
+        if !self.unpacked_rows.is_empty() {
+            self.pack_unpacked_rows();
+        }
+        while let Some(mut entry) = other.chunks.pop_back() {
+            other.packed_row_count -= entry.chunk.row_count;
+            self.packed_row_count += entry.chunk.row_count;
+            entry.reversed = !entry.reversed;
+            self.chunks.push_back(entry);
+        }
+    }
+    /// Drops the oldest rows exceeding `max_rows`
+    pub fn trim(&mut self) {
+        while self.len() > self.max_rows {
+            match self.chunks.pop_front() {
+                Some(entry) => {
+                    self.packed_row_count -= entry.chunk.row_count;
+                    self.dropped_row_count += entry.chunk.row_count as u64;
+                },
+                None => {
+                    let excess = self.len() - self.max_rows;
+                    self.unpacked_rows.drain(..excess);
+                    self.dropped_row_count += excess as u64;
+                },
+            }
+        }
+    }
+    /// The width of each archived row, in order, without reading them back
+    pub fn row_widths(&self) -> impl Iterator<Item = usize> + '_ {
+        self.chunks
+            .iter()
+            .flat_map(|entry| entry.row_widths())
+            .chain(self.unpacked_rows.iter().map(|row| row.width()))
+    }
+    /// Iterates over the archived rows starting at `start`, decompressing one chunk at a time.
+    /// Chunks that end before `start` are skipped without being read.
+    pub fn rows_from(&self, start: usize) -> impl Iterator<Item = Row> + '_ {
+        let mut rows_to_skip = start;
+        let chunks_to_skip = self
+            .chunks
+            .iter()
+            .take_while(|entry| {
+                if rows_to_skip >= entry.chunk.row_count {
+                    rows_to_skip -= entry.chunk.row_count;
+                    true
+                } else {
+                    false
+                }
+            })
+            .count();
+        self.chunks
+            .iter()
+            .skip(chunks_to_skip)
+            .flat_map(|entry| {
+                entry.load().unwrap_or_else(|e| {
+                    log::error!("Failed to load archived scrollback: {}", e);
+                    vec![]
+                })
+            })
+            .chain(self.unpacked_rows.iter().cloned())
+            .skip(rows_to_skip)
+    }
+    fn pack_unpacked_rows(&mut self) {
+        let rows = std::mem::take(&mut self.unpacked_rows);
+        match ArchivedChunk::store(&rows) {
+            Ok(chunk) => {
+                self.packed_row_count += chunk.row_count;
+                self.chunks.push_back(ArchiveEntry {
+                    chunk: Rc::new(chunk),
+                    reversed: false,
+                });
+            },
+            Err(e) => {
+                log::error!("Failed to write scrollback to disk, dropping it: {}", e);
+                self.dropped_row_count += rows.len() as u64;
+            },
+        }
+    }
+}
+
+/// A chunk as part of an archive. A chunk moved to an archive that holds its rows in the
+/// opposite order (see `ScrollbackArchive::append_reversed`) is read back reversed.
+#[derive(Clone)]
+struct ArchiveEntry {
+    chunk: Rc<ArchivedChunk>,
+    reversed: bool,
+}
+
+impl ArchiveEntry {
+    fn load(&self) -> io::Result<Vec<Row>> {
+        let mut rows = self.chunk.load()?;
+        if self.reversed {
+            rows.reverse();
+        }
+        Ok(rows)
+    }
+    fn row_widths(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        let row_widths = self.chunk.row_widths.iter().map(|width| *width as usize);
+        if self.reversed {
+            Box::new(row_widths.rev())
+        } else {
+            Box::new(row_widths)
+        }
+    }
+}
+
+struct ArchivedChunk {
+    state: Arc<Mutex<ChunkState>>,
+    row_count: usize,
+    // kept in memory so that the height of the rows can be known without reading them back
+    row_widths: Vec<u32>,
+}
+
+enum ChunkState {
+    /// Encoded but not yet compressed and written to the spill file
+    Pending(Arc<Vec<u8>>),
+    Stored(Extent),
+    /// Dropped before it was written, its extent is released as soon as it is
+    Dropped,
+}
+
+impl ArchivedChunk {
+    fn store(rows: &[Row]) -> io::Result<Self> {
+        let mut bytes = vec![];
+        encode_rows(rows, &mut bytes)?;
+        let bytes = Arc::new(bytes);
+        let state = Arc::new(Mutex::new(ChunkState::Pending(bytes.clone())));
+        spill_file_writer()?.send(SpillFileInstruction::Store(state.clone(), bytes))?;
+        Ok(ArchivedChunk {
+            state,
+            row_count: rows.len(),
+            row_widths: rows.iter().map(|row| row.width() as u32).collect(),
+        })
+    }
+    fn load(&self) -> io::Result<Vec<Row>> {
+        let extent = match &*lock_chunk_state(&self.state) {
+            ChunkState::Pending(bytes) => return decode_rows(&mut &bytes[..]),
+            ChunkState::Stored(extent) => *extent,
+            ChunkState::Dropped => return Err(invalid_data("chunk was dropped")),
+        };
+        let bytes = read_extent(&spill_file_writer()?.file, extent)?;
+        decode_rows(&mut DeflateDecoder::new(&bytes[..]))
+    }
+}
+
+impl Drop for ArchivedChunk {
+    fn drop(&mut self) {
+        let mut state = lock_chunk_state(&self.state);
+        match *state {
+            ChunkState::Stored(extent) => {
+                if let Ok(writer) = spill_file_writer() {
+                    let _ = writer.send(SpillFileInstruction::Release(extent));
+                }
+            },
+            ChunkState::Pending(_) | ChunkState::Dropped => *state = ChunkState::Dropped,
+        }
+    }
+}
+
+fn lock_chunk_state(state: &Mutex<ChunkState>) -> std::sync::MutexGuard<'_, ChunkState> {
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Extent {
+    offset: u64,
+    len: u64,
+}
+
+impl Extent {
+    fn end(&self) -> u64 {
+        self.offset + self.len
+    }
+}
+
+enum SpillFileInstruction {
+    Store(Arc<Mutex<ChunkState>>, Arc<Vec<u8>>),
+    Release(Extent),
+}
+
+/// The spill file is written (and its space managed) on its own thread, so that the screen
+/// thread never waits for deflate or disk writes. Stored chunks are read directly from the file.
+struct SpillFileWriter {
+    file: Arc<File>,
+    sender: Sender<SpillFileInstruction>,
+}
+
+impl SpillFileWriter {
+    fn start() -> io::Result<Self> {
+        let mut spill_file = SpillFile::create()?;
+        let file = spill_file.file.clone();
+        let (sender, receiver) = unbounded();
+        thread::Builder::new()
+            .name("scrollback_archive".to_string())
+            .spawn(move || {
+                for instruction in receiver {
+                    match instruction {
+                        SpillFileInstruction::Store(state, bytes) => {
+                            spill_file.store_chunk(&state, &bytes)
+                        },
+                        SpillFileInstruction::Release(extent) => {
+                            if let Err(e) = spill_file.release(extent) {
+                                log::error!("Failed to release archived scrollback: {}", e);
+                            }
+                        },
+                    }
+                }
+            })?;
+        Ok(SpillFileWriter { file, sender })
+    }
+    fn send(&self, instruction: SpillFileInstruction) -> io::Result<()> {
+        self.sender
+            .send(instruction)
+            .map_err(|_| io::Error::other("scrollback spill file thread is gone"))
+    }
+}
+
+fn spill_file_writer() -> io::Result<&'static SpillFileWriter> {
+    SPILL_FILE_WRITER
+        .get_or_init(|| match SpillFileWriter::start() {
+            Ok(writer) => Some(writer),
+            Err(e) => {
+                log::error!("Failed to create scrollback spill file: {}", e);
+                None
+            },
+        })
+        .as_ref()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no scrollback spill file"))
+}
+
+fn read_extent(file: &File, extent: Extent) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0; extent.len as usize];
+    read_exact_at(file, &mut bytes, extent.offset)?;
+    Ok(bytes)
+}
+
+// the spill file is read and written at explicit offsets from both the screen thread and the
+// writer thread, so neither of them may rely on the file cursor
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+#[cfg(unix)]
+fn write_all_at(file: &File, buf: &[u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::write_all_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset) {
+            Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+            Ok(n) => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            },
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn write_all_at(file: &File, mut buf: &[u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_write(buf, offset) {
+            Ok(0) => return Err(io::Error::from(io::ErrorKind::WriteZero)),
+            Ok(n) => {
+                buf = &buf[n..];
+                offset += n as u64;
+            },
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+struct SpillFile {
+    file: Arc<File>,
+    len: u64,
+    free_extents: Vec<Extent>, // sorted by offset, never adjacent
+}
+
+impl SpillFile {
+    fn create() -> io::Result<Self> {
+        let dir = ZELLIJ_CACHE_DIR.join("scrollback");
+        std::fs::create_dir_all(&dir)?;
+        Ok(SpillFile {
+            file: Arc::new(tempfile::tempfile_in(dir)?),
+            len: 0,
+            free_extents: vec![],
+        })
+    }
+    fn store_chunk(&mut self, state: &Mutex<ChunkState>, bytes: &[u8]) {
+        let stored = compress(bytes).and_then(|compressed| self.store(&compressed));
+        let mut state = lock_chunk_state(state);
+        match (stored, &*state) {
+            (Ok(extent), ChunkState::Dropped) => {
+                if let Err(e) = self.release(extent) {
+                    log::error!("Failed to release archived scrollback: {}", e);
+                }
+            },
+            (Ok(extent), _) => *state = ChunkState::Stored(extent),
+            (Err(e), _) => {
+                // the chunk stays pending, so it can still be read from memory
+                log::error!(
+                    "Failed to write scrollback to disk, keeping it in memory: {}",
+                    e
+                );
+            },
+        }
+    }
+    fn store(&mut self, bytes: &[u8]) -> io::Result<Extent> {
+        let len = bytes.len() as u64;
+        let extent = match self.free_extents.iter().position(|free| free.len >= len) {
+            Some(i) => {
+                let free = &mut self.free_extents[i];
+                let extent = Extent {
+                    offset: free.offset,
+                    len,
+                };
+                free.offset += len;
+                free.len -= len;
+                if free.len == 0 {
+                    self.free_extents.remove(i);
+                }
+                extent
+            },
+            None => {
+                let extent = Extent {
+                    offset: self.len,
+                    len,
+                };
+                self.len += len;
+                extent
+            },
+        };
+        if let Err(e) = write_all_at(&self.file, bytes, extent.offset) {
+            self.release(extent)?;
+            return Err(e);
+        }
+        Ok(extent)
+    }
+    fn release(&mut self, extent: Extent) -> io::Result<()> {
+        let i = self
+            .free_extents
+            .partition_point(|free| free.offset < extent.offset);
+        self.free_extents.insert(i, extent);
+        if i + 1 < self.free_extents.len()
+            && self.free_extents[i].end() == self.free_extents[i + 1].offset
+        {
+            self.free_extents[i].len += self.free_extents.remove(i + 1).len;
+        }
+        if i > 0 && self.free_extents[i - 1].end() == self.free_extents[i].offset {
+            self.free_extents[i - 1].len += self.free_extents.remove(i).len;
+        }
+        if let Some(last) = self.free_extents.last().copied() {
+            if last.end() == self.len {
+                self.free_extents.pop();
+                self.len = last.offset;
+                self.file.set_len(self.len)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn compress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(vec![], Compression::fast());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+// Chunk encoding (little endian, before compression):
+//
+// row count (u32), then for each row: is_canonical (u8), bg_color, prompt marks, and the
+// row's characters as runs sharing the same styles: run count (u32), then for each run the
+// styles followed by character count (u32) and each character (u32) with its width (u8)
+
+fn encode_rows(rows: &[Row], w: &mut impl Write) -> io::Result<()> {
+    w.write_u32::<LittleEndian>(rows.len() as u32)?;
+    for row in rows {
+        encode_row(row, w)?;
+    }
+    Ok(())
+}
+
+fn decode_rows(r: &mut impl Read) -> io::Result<Vec<Row>> {
+    let row_count = r.read_u32::<LittleEndian>()? as usize;
+    if row_count > ROWS_PER_CHUNK {
+        return Err(invalid_data("too many rows in chunk"));
+    }
+    let mut rows = Vec::with_capacity(row_count);
+    for _ in 0..row_count {
+        rows.push(decode_row(r)?);
+    }
+    Ok(rows)
+}
+
+pub(crate) fn encode_row(row: &Row, w: &mut impl Write) -> io::Result<()> {
+    w.write_u8(row.is_canonical as u8)?;
+    encode_ansi_code(row.bg_color, w)?;
+    w.write_u32::<LittleEndian>(row.prompt_marks.len() as u32)?;
+    for prompt_mark in &row.prompt_marks {
+        encode_prompt_mark(prompt_mark, w)?;
+    }
+    let mut runs: Vec<(&RcCharacterStyles, Vec<&TerminalCharacter>)> = vec![];
+    for character in &row.columns {
+        match runs.last_mut() {
+            Some((styles, characters)) if *styles == &character.styles => {
+                characters.push(character)
+            },
+            _ => runs.push((&character.styles, vec![character])),
+        }
+    }
+    w.write_u32::<LittleEndian>(runs.len() as u32)?;
+    for (styles, characters) in runs {
+        encode_styles(styles, w)?;
+        w.write_u32::<LittleEndian>(characters.len() as u32)?;
+        for character in characters {
+            w.write_u32::<LittleEndian>(character.character as u32)?;
+            w.write_u8(character.width() as u8)?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn decode_row(r: &mut impl Read) -> io::Result<Row> {
+    let is_canonical = r.read_u8()? != 0;
+    let bg_color = decode_ansi_code(r)?;
+    let prompt_mark_count = r.read_u32::<LittleEndian>()?;
+    let mut prompt_marks = vec![];
+    for _ in 0..prompt_mark_count {
+        prompt_marks.push(decode_prompt_mark(r)?);
+    }
+    let mut columns = VecDeque::new();
+    let run_count = r.read_u32::<LittleEndian>()?;
+    for _ in 0..run_count {
+        let styles = decode_styles(r)?;
+        let character_count = r.read_u32::<LittleEndian>()?;
+        for _ in 0..character_count {
+            let character = char::from_u32(r.read_u32::<LittleEndian>()?)
+                .ok_or_else(|| invalid_data("invalid character"))?;
+            let width = r.read_u8()?;
+            let terminal_character = if width == 1 && character.width().unwrap_or(0) != 1 {
+                TerminalCharacter::new_singlewidth_styled(character, styles.clone())
+            } else {
+                TerminalCharacter::new_styled(character, styles.clone())
+            };
+            columns.push_back(terminal_character);
+        }
+    }
+    let mut row = Row::from_columns(columns).with_bg_color(bg_color);
+    row.is_canonical = is_canonical;
+    row.prompt_marks = prompt_marks;
+    Ok(row)
+}
+
+fn encode_prompt_mark(prompt_mark: &PromptMark, w: &mut impl Write) -> io::Result<()> {
+    match prompt_mark.kind {
+        PromptMarkKind::PromptStart => w.write_u8(0)?,
+        PromptMarkKind::CommandStart => w.write_u8(1)?,
+        PromptMarkKind::OutputStart => w.write_u8(2)?,
+        PromptMarkKind::CommandEnd(None) => w.write_u8(3)?,
+        PromptMarkKind::CommandEnd(Some(exit_code)) => {
+            w.write_u8(4)?;
+            w.write_i32::<LittleEndian>(exit_code)?;
+        },
+    }
+    w.write_u32::<LittleEndian>(prompt_mark.column as u32)
+}
+
+fn decode_prompt_mark(r: &mut impl Read) -> io::Result<PromptMark> {
+    let kind = match r.read_u8()? {
+        0 => PromptMarkKind::PromptStart,
+        1 => PromptMarkKind::CommandStart,
+        2 => PromptMarkKind::OutputStart,
+        3 => PromptMarkKind::CommandEnd(None),
+        4 => PromptMarkKind::CommandEnd(Some(r.read_i32::<LittleEndian>()?)),
+        _ => return Err(invalid_data("invalid prompt mark")),
+    };
+    let column = r.read_u32::<LittleEndian>()? as usize;
+    Ok(PromptMark { kind, column })
+}
+
+fn encode_styles(styles: &RcCharacterStyles, w: &mut impl Write) -> io::Result<()> {
+    let styles = match styles {
+        RcCharacterStyles::Reset => return w.write_u8(0),
+        RcCharacterStyles::Rc(styles) => styles,
+    };
+    w.write_u8(1)?;
+    for ansi_code in [
+        styles.foreground,
+        styles.background,
+        styles.underline_color,
+        styles.strike,
+        styles.hidden,
+        styles.reverse,
+        styles.slow_blink,
+        styles.fast_blink,
+        styles.underline,
+        styles.bold,
+        styles.dim,
+        styles.italic,
+    ] {
+        encode_ansi_code(ansi_code, w)?;
+    }
+    match styles.link_anchor {
+        None => w.write_u8(0)?,
+        Some(LinkAnchor::Start(link_id)) => {
+            w.write_u8(1)?;
+            w.write_u16::<LittleEndian>(link_id)?;
+        },
+        Some(LinkAnchor::End) => w.write_u8(2)?,
+    }
+    w.write_u8(styles.styled_underlines_enabled as u8)
+}
+
+fn decode_styles(r: &mut impl Read) -> io::Result<RcCharacterStyles> {
+    if r.read_u8()? == 0 {
+        return Ok(RcCharacterStyles::Reset);
+    }
+    let mut styles = RESET_STYLES;
+    for ansi_code in [
+        &mut styles.foreground,
+        &mut styles.background,
+        &mut styles.underline_color,
+        &mut styles.strike,
+        &mut styles.hidden,
+        &mut styles.reverse,
+        &mut styles.slow_blink,
+        &mut styles.fast_blink,
+        &mut styles.underline,
+        &mut styles.bold,
+        &mut styles.dim,
+        &mut styles.italic,
+    ] {
+        *ansi_code = decode_ansi_code(r)?;
+    }
+    styles.link_anchor = match r.read_u8()? {
+        0 => None,
+        1 => Some(LinkAnchor::Start(r.read_u16::<LittleEndian>()?)),
+        2 => Some(LinkAnchor::End),
+        _ => return Err(invalid_data("invalid link anchor")),
+    };
+    styles.styled_underlines_enabled = r.read_u8()? != 0;
+    Ok(RcCharacterStyles::Rc(Rc::new(styles)))
+}
+
+const NAMED_COLORS: [NamedColor; 16] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+];
+
+const STYLED_UNDERLINES: [AnsiStyledUnderline; 4] = [
+    AnsiStyledUnderline::Double,
+    AnsiStyledUnderline::Undercurl,
+    AnsiStyledUnderline::Underdotted,
+    AnsiStyledUnderline::Underdashed,
+];
+
+fn encode_ansi_code(ansi_code: Option<AnsiCode>, w: &mut impl Write) -> io::Result<()> {
+    match ansi_code {
+        None => w.write_u8(0),
+        Some(AnsiCode::On) => w.write_u8(1),
+        Some(AnsiCode::Reset) => w.write_u8(2),
+        Some(AnsiCode::NamedColor(named_color)) => {
+            let index = NAMED_COLORS
+                .iter()
+                .position(|c| *c == named_color)
+                .unwrap_or(0);
+            w.write_all(&[3, index as u8])
+        },
+        Some(AnsiCode::RgbCode((r, g, b))) => w.write_all(&[4, r, g, b]),
+        Some(AnsiCode::ColorIndex(index)) => w.write_all(&[5, index]),
+        Some(AnsiCode::Underline(None)) => w.write_u8(6),
+        Some(AnsiCode::Underline(Some(styled_underline))) => {
+            let index = STYLED_UNDERLINES
+                .iter()
+                .position(|u| *u == styled_underline)
+                .unwrap_or(0);
+            w.write_all(&[7, index as u8])
+        },
+    }
+}
+
+fn decode_ansi_code(r: &mut impl Read) -> io::Result<Option<AnsiCode>> {
+    let ansi_code = match r.read_u8()? {
+        0 => None,
+        1 => Some(AnsiCode::On),
+        2 => Some(AnsiCode::Reset),
+        3 => {
+            let named_color = NAMED_COLORS
+                .get(r.read_u8()? as usize)
+                .ok_or_else(|| invalid_data("invalid named color"))?;
+            Some(AnsiCode::NamedColor(*named_color))
+        },
+        4 => Some(AnsiCode::RgbCode((
+            r.read_u8()?,
+            r.read_u8()?,
+            r.read_u8()?,
+        ))),
+        5 => Some(AnsiCode::ColorIndex(r.read_u8()?)),
+        6 => Some(AnsiCode::Underline(None)),
+        7 => {
+            let styled_underline = STYLED_UNDERLINES
+                .get(r.read_u8()? as usize)
+                .ok_or_else(|| invalid_data("invalid underline style"))?;
+            Some(AnsiCode::Underline(Some(*styled_underline)))
+        },
+        _ => return Err(invalid_data("invalid ansi code")),
+    };
+    Ok(ansi_code)
+}
+
+pub(crate) fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+#[path = "./unit/scrollback_archive_tests.rs"]
+mod scrollback_archive_tests;
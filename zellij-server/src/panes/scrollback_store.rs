@@ -0,0 +1,240 @@
+//! Scrollback files for session resurrection.
+//!
+//! With `serialize_full_scrollback`, the history of every terminal pane (everything above its
+//! viewport, including rows archived on disk) is written to a file next to the session's
+//! serialized layout, which refers to it as the pane's `scrollback_file`. On every
+//! `serialization_interval` only the rows added since the previous one are appended to it.
+//!
+//! Rows are numbered from the first row the pane had since its scrollback was last cleared.
+//! Each record replaces all rows from its first row onwards and drops those before its
+//! history start, so rows that changed after being written (eg. pulled back into a growing
+//! viewport) are corrected by the next record. Once most of the file is made of replaced or
+//! dropped rows, it is rewritten from scratch.
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+
+use crate::panes::grid::Row;
+use crate::panes::link_handler::{Link, LinkHandler};
+use crate::panes::scrollback_archive::{decode_row, encode_row, invalid_data};
+use crate::panes::terminal_character::LinkAnchor;
+
+const SCROLLBACK_FILE_PREFIX: &str = "scrollback_";
+
+// rows that may be replaced or dropped before the file is rewritten, on top of the history
+// itself
+const COMPACTION_SLACK: u64 = 10_000;
+
+// the only strings are hyperlink uris and ids, a length above this means the file is corrupted
+const MAX_STRING_LEN: usize = 1024 * 1024;
+
+pub fn scrollback_file_name(terminal_id: u32) -> String {
+    format!("{}{}", SCROLLBACK_FILE_PREFIX, terminal_id)
+}
+
+/// A record to append to a pane's scrollback file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScrollbackRecord {
+    /// the file should be emptied before appending this record
+    pub truncate: bool,
+    pub bytes: Vec<u8>,
+}
+
+/// Tracks which part of a pane's history was already written to its scrollback file
+#[derive(Debug, Default, Clone)]
+pub struct ScrollbackPersistence {
+    persisted_until: Option<u64>,
+    history_start: u64,
+    rows_in_file: u64,
+}
+
+impl ScrollbackPersistence {
+    /// Starts over with a new file, eg. after the scrollback was cleared
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+    pub fn is_persisted(&self) -> bool {
+        self.persisted_until.is_some()
+    }
+    /// Records that the history now spans `history_start..history_end` and returns whether
+    /// the file should be truncated and the first row that needs to be written, or None if
+    /// the file is up to date
+    pub fn update(&mut self, history_start: u64, history_end: u64) -> Option<(bool, u64)> {
+        let history_len = history_end - history_start;
+        let (truncate, first_row) = match self.persisted_until {
+            Some(persisted_until) if self.rows_in_file <= history_len * 2 + COMPACTION_SLACK => {
+                if persisted_until == history_end && history_start == self.history_start {
+                    return None;
+                }
+                (false, persisted_until.clamp(history_start, history_end))
+            },
+            _ => (true, history_start),
+        };
+        let written_rows = history_end - first_row;
+        self.rows_in_file = if truncate {
+            written_rows
+        } else {
+            self.rows_in_file + written_rows
+        };
+        self.persisted_until = Some(history_end);
+        self.history_start = history_start;
+        Some((truncate, first_row))
+    }
+}
+
+// Record encoding (little endian): record length (u32) followed by the deflated record:
+//
+// history start (u64), first row (u64), then each row prefixed by a 1 (u8) and encoded as
+// in the scrollback archive, then a 0 (u8), then the hyperlinks used by these rows: link
+// count (u32) and for each its index (u16), uri and optional id as length (u32) prefixed
+// strings, the id prefixed by whether it's present (u8)
+
+pub fn encode_record<'a>(
+    history_start: u64,
+    first_row: u64,
+    rows: impl Iterator<Item = Cow<'a, Row>>,
+    link_handler: &LinkHandler,
+) -> io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(vec![], Compression::fast());
+    encoder.write_u64::<LittleEndian>(history_start)?;
+    encoder.write_u64::<LittleEndian>(first_row)?;
+    let mut link_indices = BTreeSet::new();
+    for row in rows {
+        for character in &row.columns {
+            if let Some(LinkAnchor::Start(link_index)) = character.styles.link_anchor {
+                link_indices.insert(link_index);
+            }
+        }
+        encoder.write_u8(1)?;
+        encode_row(&row, &mut encoder)?;
+    }
+    encoder.write_u8(0)?;
+    let links: Vec<(u16, &Link)> = link_indices
+        .into_iter()
+        .filter_map(|link_index| link_handler.link(link_index).map(|link| (link_index, link)))
+        .collect();
+    encoder.write_u32::<LittleEndian>(links.len() as u32)?;
+    for (link_index, link) in links {
+        encoder.write_u16::<LittleEndian>(link_index)?;
+        write_string(&link.uri, &mut encoder)?;
+        match &link.id {
+            Some(id) => {
+                encoder.write_u8(1)?;
+                write_string(id, &mut encoder)?;
+            },
+            None => encoder.write_u8(0)?,
+        }
+    }
+    let compressed = encoder.finish()?;
+    let mut bytes = Vec::with_capacity(compressed.len() + 4);
+    bytes.write_u32::<LittleEndian>(compressed.len() as u32)?;
+    bytes.extend_from_slice(&compressed);
+    Ok(bytes)
+}
+
+/// The contents of a scrollback file, with hyperlinks indexed as they were in the pane
+/// that wrote it
+#[derive(Default)]
+pub struct PersistedScrollback {
+    pub rows: VecDeque<Row>,
+    pub links: HashMap<u16, Link>,
+}
+
+pub fn read_scrollback_file(path: &Path) -> io::Result<PersistedScrollback> {
+    let mut bytes = vec![];
+    File::open(path)?.read_to_end(&mut bytes)?;
+    let mut persisted_scrollback = PersistedScrollback::default();
+    let mut first_row_index = 0;
+    let mut remaining = &bytes[..];
+    while remaining.len() >= 4 {
+        let record_len = (&remaining[..4]).read_u32::<LittleEndian>()? as usize;
+        let Some(record) = remaining.get(4..4 + record_len) else {
+            // the last record was only partially written, eg. if we crashed while writing it
+            log::warn!("Ignoring truncated record in {}", path.display());
+            break;
+        };
+        remaining = &remaining[4 + record_len..];
+        let mut decoder = DeflateDecoder::new(record);
+        let history_start = decoder.read_u64::<LittleEndian>()?;
+        let first_row = decoder.read_u64::<LittleEndian>()?;
+        let rows = &mut persisted_scrollback.rows;
+        if first_row < first_row_index || first_row > first_row_index + rows.len() as u64 {
+            rows.clear();
+            first_row_index = first_row;
+        }
+        rows.truncate((first_row - first_row_index) as usize);
+        while decoder.read_u8()? != 0 {
+            rows.push_back(decode_row(&mut decoder)?);
+        }
+        while first_row_index < history_start && rows.pop_front().is_some() {
+            first_row_index += 1;
+        }
+        let link_count = decoder.read_u32::<LittleEndian>()?;
+        for _ in 0..link_count {
+            let link_index = decoder.read_u16::<LittleEndian>()?;
+            let uri = read_string(&mut decoder)?;
+            let id = match decoder.read_u8()? {
+                0 => None,
+                _ => Some(read_string(&mut decoder)?),
+            };
+            persisted_scrollback
+                .links
+                .insert(link_index, Link { id, uri });
+        }
+    }
+    Ok(persisted_scrollback)
+}
+
+/// Appends the records to the scrollback files in `folder` and deletes scrollback files that
+/// are no longer in use
+pub fn write_scrollback_files(
+    folder: &Path,
+    records: Vec<(String, ScrollbackRecord)>,
+    files_in_use: &BTreeSet<String>,
+) -> io::Result<()> {
+    std::fs::create_dir_all(folder)?;
+    for (file_name, record) in records {
+        let mut file = if record.truncate {
+            File::create(folder.join(&file_name))?
+        } else {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(folder.join(&file_name))?
+        };
+        file.write_all(&record.bytes)?;
+    }
+    for entry in std::fs::read_dir(folder)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name.starts_with(SCROLLBACK_FILE_PREFIX) && !files_in_use.contains(&file_name) {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+fn write_string(string: &str, w: &mut impl Write) -> io::Result<()> {
+    w.write_u32::<LittleEndian>(string.len() as u32)?;
+    w.write_all(string.as_bytes())
+}
+
+fn read_string(r: &mut impl Read) -> io::Result<String> {
+    let len = r.read_u32::<LittleEndian>()? as usize;
+    if len > MAX_STRING_LEN {
+        return Err(invalid_data("string is too long"));
+    }
+    let mut bytes = vec![0; len];
+    r.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|_| invalid_data("invalid string"))
+}
+
+#[cfg(test)]
+#[path = "./unit/scrollback_store_tests.rs"]
+mod scrollback_store_tests;
@@ -1,6 +1,7 @@
 use super::Selection;
 use crate::panes::terminal_character::TerminalCharacter;
 use crate::panes::{Grid, Row};
+use regex::{Regex, RegexBuilder};
 use std::borrow::Cow;
 use std::collections::VecDeque;
 use std::fmt::Debug;
@@ -99,6 +100,10 @@ pub struct SearchResult {
     pub whole_word_only: bool, // TODO
     // Jump from the bottom to the top (or vice versa), if we run out of lines to search
     pub wrap_search: bool,
+    // Interpret the needle as a regular expression
+    pub regex_search: bool,
+    // The needle compiled with the current search-options (None if it is not a valid regex)
+    pub compiled_regex: Option<Regex>,
 }
 
 impl SearchResult {
@@ -142,7 +147,30 @@ impl SearchResult {
     }
 
     pub fn has_modifiers_set(&self) -> bool {
-        self.wrap_search || self.whole_word_only || self.case_insensitive
+        self.wrap_search || self.whole_word_only || self.case_insensitive || self.regex_search
+    }
+
+    pub(crate) fn set_needle(&mut self, needle: &str) {
+        self.needle = needle.to_string();
+        self.compile_regex();
+    }
+
+    /// Needs to be called whenever the needle or one of the options affecting the
+    /// regex (case sensitivity, whole-word) changes
+    pub(crate) fn compile_regex(&mut self) {
+        self.compiled_regex = if self.regex_search && !self.needle.is_empty() {
+            let pattern = if self.whole_word_only {
+                format!(r"\b(?:{})\b", self.needle)
+            } else {
+                self.needle.clone()
+            };
+            RegexBuilder::new(&pattern)
+                .case_insensitive(self.case_insensitive)
+                .build()
+                .ok()
+        } else {
+            None
+        };
     }
 
     fn check_if_haystack_char_matches_needle(
@@ -182,6 +210,9 @@ impl SearchResult {
         if self.needle.is_empty() || row.columns.is_empty() {
             return res;
         }
+        if self.regex_search {
+            return self.search_row_with_regex(ridx, row, tail);
+        }
 
         let mut tailit = tail.iter();
         let mut source = SearchSource::Main(row); // Where we currently get the haystack-characters from
@@ -280,6 +311,80 @@ impl SearchResult {
         res
     }
 
+    /// Search a row and its tail with the compiled regex.
+    /// The row and its tail are joined into one haystack, so that matches can span wrapped lines.
+    /// Only matches starting in `row` itself are returned, the others will be found when
+    /// searching the rows of the tail. If the regex has capture groups, only the captured parts
+    /// of each match are returned.
+    fn search_row_with_regex(&self, ridx: usize, row: &Row, tail: &[&Row]) -> Vec<Selection> {
+        let mut res = Vec::new();
+        let regex = match &self.compiled_regex {
+            Some(regex) => regex,
+            None => return res,
+        };
+
+        // Byte offset of every character in the haystack and where it lives in the grid
+        let mut haystack = String::new();
+        let mut positions: Vec<(usize, Position)> = Vec::new();
+        for (line_offset, haystack_row) in
+            std::iter::once(row).chain(tail.iter().copied()).enumerate()
+        {
+            for (cidx, terminal_character) in haystack_row.columns.iter().enumerate() {
+                positions.push((
+                    haystack.len(),
+                    Position::new((ridx + line_offset) as i32, cidx as u16),
+                ));
+                haystack.push(terminal_character.character);
+            }
+        }
+        let main_row_end = positions
+            .get(row.columns.len())
+            .map(|(byte_offset, _)| *byte_offset)
+            .unwrap_or(haystack.len());
+        let position_of_byte = |byte_offset: usize| {
+            positions.partition_point(|(character_offset, _)| *character_offset < byte_offset)
+        };
+
+        for captures in regex.captures_iter(&haystack) {
+            let whole_match = match captures.get(0) {
+                Some(whole_match) => whole_match,
+                None => continue,
+            };
+            if whole_match.start() >= main_row_end {
+                break;
+            }
+            let highlighted: Vec<_> = if captures.len() > 1 {
+                captures.iter().skip(1).flatten().collect()
+            } else {
+                vec![whole_match]
+            };
+            for highlight in highlighted {
+                if highlight.start() == highlight.end() {
+                    // Empty matches (eg. "a*") can't be highlighted
+                    continue;
+                }
+                let start = positions[position_of_byte(highlight.start())].1;
+                let last = positions[position_of_byte(highlight.end()) - 1].1;
+                let mut selection = Selection::default();
+                selection.start(start);
+                selection.end(Position::new(
+                    last.line() as i32,
+                    (last.column() + 1) as u16,
+                ));
+                res.push(selection);
+            }
+        }
+
+        // Same as with the plain search, the tail may not have been wrapped yet
+        for s in res.iter_mut() {
+            while s.end.column() > row.width() {
+                s.end.column.0 -= row.width();
+                s.end.line.0 += 1;
+            }
+        }
+        res
+    }
+
     pub(crate) fn move_active_selection_to_next(&mut self) {
         if let Some(active_idx) = self.active {
             self.active = self
@@ -396,10 +501,12 @@ impl SearchResult {
 impl Grid {
     pub fn search_down(&mut self) {
         self.search_scrollbuffer(SearchDirection::Down);
+        self.move_copy_mode_cursor_to_active_search_result();
     }
 
     pub fn search_up(&mut self) {
         self.search_scrollbuffer(SearchDirection::Up);
+        self.move_copy_mode_cursor_to_active_search_result();
     }
 
     pub fn clear_search(&mut self) {
@@ -412,7 +519,7 @@ impl Grid {
     }
 
     pub fn set_search_string(&mut self, needle: &str) {
-        self.search_results.needle = needle.to_string();
+        self.search_results.set_needle(needle);
         self.search_viewport();
         // If the current viewport does not contain any hits,
         // we jump around until we find something. Starting
@@ -455,6 +562,7 @@ impl Grid {
 
     pub fn toggle_search_case_sensitivity(&mut self) {
         self.search_results.case_insensitive = !self.search_results.case_insensitive;
+        self.search_results.compile_regex();
         for line in self.search_results.selections.drain(..) {
             self.output_buffer
                 .update_lines(line.start.line() as usize, line.end.line() as usize);
@@ -470,6 +578,20 @@ impl Grid {
 
     pub fn toggle_search_whole_words(&mut self) {
         self.search_results.whole_word_only = !self.search_results.whole_word_only;
+        self.search_results.compile_regex();
+        for line in self.search_results.selections.drain(..) {
+            self.output_buffer
+                .update_lines(line.start.line() as usize, line.end.line() as usize);
+        }
+        self.search_results.active = None;
+        self.search_viewport();
+        // Maybe the selection we had is now gone
+        self.search_results.unset_active_selection_if_nonexistent();
+    }
+
+    pub fn toggle_search_regex(&mut self) {
+        self.search_results.regex_search = !self.search_results.regex_search;
+        self.search_results.compile_regex();
         for line in self.search_results.selections.drain(..) {
             self.output_buffer
                 .update_lines(line.start.line() as usize, line.end.line() as usize);
@@ -540,7 +662,7 @@ impl Grid {
 
     fn search_reached_opposite_end(&mut self, dir: SearchDirection) -> bool {
         match dir {
-            SearchDirection::Up => self.lines_above.is_empty(),
+            SearchDirection::Up => self.lines_above.is_empty() && !self.has_archived_lines(),
             SearchDirection::Down => self.lines_below.is_empty(),
         }
     }
@@ -653,7 +775,7 @@ impl Grid {
             },
             SearchDirection::Down => {
                 // Go to the top
-                while !self.lines_above.is_empty() {
+                while !self.lines_above.is_empty() || self.has_archived_lines() {
                     rows += 1;
                     self.scroll_up_one_line();
                 }
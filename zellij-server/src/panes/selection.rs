@@ -11,6 +11,7 @@ pub struct Selection {
     active: bool, // used to handle moving the selection up and down
     last_added_word_position: Option<(Position, Position)>, // (start / end)
     last_added_line_index: Option<isize>,
+    is_block: bool, // the rectangle between start and end rather than the text stream
 }
 
 impl Default for Selection {
@@ -21,6 +22,7 @@ impl Default for Selection {
             active: false,
             last_added_word_position: None,
             last_added_line_index: None,
+            is_block: false,
         }
     }
 }
@@ -28,10 +30,17 @@ impl Default for Selection {
 impl Selection {
     pub fn start(&mut self, start: Position) {
         self.active = true;
+        self.is_block = false;
         self.start = start;
         self.end = start;
     }
 
+    /// Starts a rectangular selection, which keeps following the mouse like a regular one
+    pub fn start_block(&mut self, start: Position) {
+        self.start(start);
+        self.is_block = true;
+    }
+
     pub fn to(&mut self, to: Position) {
         self.end = to
     }
@@ -52,6 +61,24 @@ impl Selection {
         self.last_added_word_position = Some((start, end));
         self.last_added_line_index = Some(start.line.0);
     }
+    /// Selects a finished (inactive) region, as opposed to one that follows the mouse
+    pub fn select(&mut self, start: Position, end: Position, is_block: bool) {
+        self.active = false;
+        self.is_block = is_block;
+        self.start = start;
+        self.end = end;
+        self.last_added_word_position = None;
+        self.last_added_line_index = None;
+    }
+    pub fn is_block(&self) -> bool {
+        self.is_block
+    }
+    /// The columns (end excluded) of a block selection, which are the same on all of its lines
+    pub fn block_columns(&self) -> Range<usize> {
+        let start_column = std::cmp::min(self.start.column.0, self.end.column.0);
+        let end_column = std::cmp::max(self.start.column.0, self.end.column.0);
+        start_column..end_column
+    }
     pub fn add_word_to_position(&mut self, word_start: Position, word_end: Position) {
         // here we assume word_start is smaller or equal to word_end
         let already_added = self
@@ -170,6 +197,9 @@ impl Selection {
             (self.end, self.start)
         };
 
+        if self.is_block {
+            return start.line.0 <= row && row <= end.line.0 && self.block_columns().contains(&col);
+        }
         if (start.line.0) < row && row < end.line.0 {
             return true;
         }
@@ -182,6 +212,19 @@ impl Selection {
         end.line.0 == row && col < end.column.0
     }
 
+    /// Like `contains`, but a wide character partly inside a block selection is selected as a whole
+    pub fn contains_character(&self, row: usize, col: usize, width: usize) -> bool {
+        if self.is_block && width > 1 {
+            let block_columns = self.block_columns();
+            self.contains_row(row)
+                && col < block_columns.end
+                && col + width > block_columns.start
+                && !block_columns.is_empty()
+        } else {
+            self.contains(row, col)
+        }
+    }
+
     pub fn contains_row(&self, row: usize) -> bool {
         let row = row as isize;
         let (start, end) = if self.start <= self.end {
@@ -199,6 +242,7 @@ impl Selection {
     pub fn reset(&mut self) {
         self.start = Position::new(0, 0);
         self.end = self.start;
+        self.is_block = false;
     }
 
     pub fn sorted(&self) -> Self {
@@ -213,6 +257,7 @@ impl Selection {
             active: self.active,
             last_added_word_position: self.last_added_word_position,
             last_added_line_index: self.last_added_line_index,
+            is_block: self.is_block,
         }
     }
 
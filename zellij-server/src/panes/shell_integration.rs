@@ -0,0 +1,262 @@
+//! Semantic prompt marks reported by shells through OSC 133 (the FinalTerm shell integration
+//! protocol): `A` marks the start of a prompt, `B` the start of the command line the user types,
+//! `C` the start of the command output and `D[;exit_code]` the end of the command.
+
+use crate::panes::{Grid, Row};
+use std::str;
+use zellij_utils::position::Position;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptMarkKind {
+    PromptStart,
+    CommandStart,
+    OutputStart,
+    CommandEnd(Option<i32>), // exit code
+}
+
+impl PromptMarkKind {
+    /// Parses the parameters following the `133` of an OSC 133 sequence
+    pub fn from_osc_params(params: &[&[u8]]) -> Option<Self> {
+        match *params.first()? {
+            b"A" => Some(PromptMarkKind::PromptStart),
+            b"B" => Some(PromptMarkKind::CommandStart),
+            b"C" => Some(PromptMarkKind::OutputStart),
+            b"D" => {
+                let exit_code = params
+                    .get(1)
+                    .and_then(|exit_code| str::from_utf8(exit_code).ok())
+                    .and_then(|exit_code| exit_code.parse::<i32>().ok());
+                Some(PromptMarkKind::CommandEnd(exit_code))
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptMark {
+    pub kind: PromptMarkKind,
+    pub column: usize, // in width units, same as the cursor
+}
+
+/// The output of a finished command, from its `C` mark up to (not including) its `D` mark.
+/// Positions use the same coordinates as `Selection` (negative lines are in the scrollback)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub start: Position,
+    pub end: Position,
+    pub exit_code: Option<i32>,
+}
+
+/// A prompt that was jumped to while it was already on screen above the cursor. The viewport
+/// cannot be scrolled to bring it to the top, so we select its line instead and remember it to
+/// continue above it next time. It no longer applies once the pane's contents change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisiblePromptJump {
+    row: usize,
+    cursor: (usize, usize),
+    lines_above_count: usize,
+}
+
+impl Grid {
+    /// Jumps to the closest prompt above the cursor (or above the top of the viewport if it is
+    /// scrolled). Prompts that are already on screen are stepped through by selecting their line,
+    /// others are scrolled to the top of the viewport. Returns false if there is no such prompt
+    pub fn scroll_to_previous_prompt(&mut self) -> bool {
+        let had_visible_prompt_jump = self.visible_prompt_jump.is_some();
+        let visible_prompt_row = self.previous_visible_prompt_row();
+        self.visible_prompt_jump = visible_prompt_row.map(|row| VisiblePromptJump {
+            row,
+            cursor: (self.cursor.x, self.cursor.y),
+            lines_above_count: self.lines_above.len(),
+        });
+        if let Some(visible_prompt_row) = visible_prompt_row {
+            self.select_prompt_line(visible_prompt_row);
+            return true;
+        }
+        if !self.lines_above.iter().any(|row| row.has_prompt_start()) {
+            return false;
+        }
+        if had_visible_prompt_jump {
+            self.selection.reset();
+        }
+        while !self.lines_above.is_empty() && self.viewport.len() == self.height {
+            self.scroll_up_one_line();
+            if self.top_row_has_prompt_start() {
+                break;
+            }
+        }
+        self.output_buffer.update_all_lines();
+        true
+    }
+    /// Scrolls down until the closest prompt below the top of the viewport is at its top (or
+    /// until we reach the bottom), returns false if there is no such prompt
+    pub fn scroll_to_next_prompt(&mut self) -> bool {
+        if self.visible_prompt_jump.take().is_some() {
+            self.selection.reset();
+            self.output_buffer.update_all_lines();
+        }
+        let has_next_prompt = self
+            .viewport
+            .iter()
+            .skip(1)
+            .chain(self.lines_below.iter())
+            .any(|row| row.has_prompt_start());
+        if !has_next_prompt || self.lines_below.is_empty() {
+            return false;
+        }
+        while !self.lines_below.is_empty() && self.viewport.len() == self.height {
+            self.scroll_down_one_line();
+            if self.top_row_has_prompt_start() {
+                break;
+            }
+        }
+        self.output_buffer.update_all_lines();
+        true
+    }
+    /// All finished commands in the scrollback, oldest first
+    pub fn command_outputs(&self) -> Vec<CommandOutput> {
+        let mut command_outputs = vec![];
+        let mut output_start = None;
+        for (line, row) in self.rows_with_selection_line_indices() {
+            for prompt_mark in &row.prompt_marks {
+                let position = Position::new(line as i32, prompt_mark.column as u16);
+                match prompt_mark.kind {
+                    PromptMarkKind::PromptStart => {
+                        // a new prompt without a command end means the previous command did not
+                        // report finishing (eg. it was interrupted)
+                        output_start = None;
+                    },
+                    PromptMarkKind::CommandStart => {},
+                    PromptMarkKind::OutputStart => {
+                        output_start = Some(position);
+                    },
+                    PromptMarkKind::CommandEnd(exit_code) => {
+                        if let Some(start) = output_start.take() {
+                            command_outputs.push(CommandOutput {
+                                start,
+                                end: position,
+                                exit_code,
+                            });
+                        }
+                    },
+                }
+            }
+        }
+        command_outputs
+    }
+    /// `index` counts back from the last finished command (0)
+    pub fn command_output_text(&self, index: usize) -> Option<String> {
+        let command_output = self.command_outputs().into_iter().rev().nth(index)?;
+        let (start, end) = (command_output.start, command_output.end);
+        let mut lines: Vec<String> = vec![];
+        for (line, row) in self.rows_with_selection_line_indices() {
+            if line < start.line() {
+                continue;
+            }
+            if line > end.line() || (line == end.line() && end.column() == 0) {
+                break;
+            }
+            let start_column = if line == start.line() {
+                start.column()
+            } else {
+                0
+            };
+            let end_column = if line == end.line() {
+                end.column()
+            } else {
+                usize::MAX
+            };
+            let mut line_text = String::new();
+            let mut terminal_col = 0;
+            for terminal_character in &row.columns {
+                if (start_column..end_column).contains(&terminal_col) {
+                    line_text.push(terminal_character.character);
+                }
+                terminal_col += terminal_character.width();
+            }
+            match lines.last_mut() {
+                Some(previous_line) if !row.is_canonical => previous_line.push_str(&line_text),
+                _ => lines.push(line_text),
+            }
+        }
+        let lines: Vec<&str> = lines.iter().map(|line| line.trim_end()).collect();
+        Some(lines.join("\n"))
+    }
+    pub fn select_last_command_output(&mut self) -> bool {
+        match self.command_outputs().last() {
+            Some(command_output) => {
+                self.selection
+                    .set_start_and_end_positions(command_output.start, command_output.end);
+                self.output_buffer.update_all_lines();
+                self.mark_for_rerender();
+                true
+            },
+            None => false,
+        }
+    }
+    /// The closest prompt on screen above the cursor, or above the on-screen prompt we last
+    /// jumped to
+    fn previous_visible_prompt_row(&self) -> Option<usize> {
+        if self.is_scrolled {
+            return None;
+        }
+        let last_jump = self.visible_prompt_jump.filter(|last_jump| {
+            last_jump.cursor == (self.cursor.x, self.cursor.y)
+                && last_jump.lines_above_count == self.lines_above.len()
+        });
+        let search_above = match last_jump {
+            Some(last_jump) => last_jump.row,
+            None => self.cursor.y,
+        };
+        (0..search_above.min(self.viewport.len()))
+            .rev()
+            .find(|row| self.viewport[*row].has_prompt_start())
+    }
+    // from the start of the prompt to the end of its line
+    fn select_prompt_line(&mut self, viewport_row: usize) {
+        let Some(row) = self.viewport.get(viewport_row) else {
+            return;
+        };
+        let prompt_column = row
+            .prompt_marks
+            .iter()
+            .find(|prompt_mark| prompt_mark.kind == PromptMarkKind::PromptStart)
+            .map(|prompt_mark| prompt_mark.column)
+            .unwrap_or(0);
+        let line = viewport_row as i32;
+        self.selection.select(
+            Position::new(line, prompt_column as u16),
+            Position::new(line, row.width() as u16),
+            false,
+        );
+        self.output_buffer.update_all_lines();
+        self.mark_for_rerender();
+    }
+    fn top_row_has_prompt_start(&self) -> bool {
+        self.viewport
+            .front()
+            .map(|row| row.has_prompt_start())
+            .unwrap_or(false)
+    }
+    fn rows_with_selection_line_indices(&self) -> impl Iterator<Item = (isize, &Row)> {
+        let lines_above_count = self.lines_above.len() as isize;
+        let viewport_count = self.viewport.len() as isize;
+        self.lines_above
+            .iter()
+            .enumerate()
+            .map(move |(i, row)| (i as isize - lines_above_count, row))
+            .chain(
+                self.viewport
+                    .iter()
+                    .enumerate()
+                    .map(|(i, row)| (i as isize, row)),
+            )
+            .chain(
+                self.lines_below
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, row)| (viewport_count + i as isize, row)),
+            )
+    }
+}
@@ -1,4 +1,5 @@
 use crate::output::SixelImageChunk;
+use crate::panes::kitty_graphics::KittyImage;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
@@ -160,28 +161,7 @@ impl SixelGrid {
                     image_pixel_size.1,
                 );
 
-                // here we remove images which this image covers completely to save on system
-                // resources - TODO: also do this with partial covers, eg. if several images
-                // together cover one image
-                for (image_id, pixel_rect) in &self.sixel_image_locations {
-                    if let Some(intersecting_rect) =
-                        pixel_rect.intersecting_rect(&image_size_and_coordinates)
-                    {
-                        if intersecting_rect.x == pixel_rect.x
-                            && intersecting_rect.y == pixel_rect.y
-                            && intersecting_rect.height == pixel_rect.height
-                            && intersecting_rect.width == pixel_rect.width
-                        {
-                            self.image_ids_to_reap.push(*image_id);
-                        }
-                    }
-                }
-                for image_id in &self.image_ids_to_reap {
-                    self.sixel_image_locations.remove(image_id);
-                }
-
-                self.sixel_image_locations
-                    .insert(new_image_id, image_size_and_coordinates);
+                self.place_image(new_image_id, image_size_and_coordinates);
                 self.currently_parsing = None;
                 Some(sixel_image)
             } else {
@@ -191,6 +171,35 @@ impl SixelGrid {
             None
         }
     }
+    pub fn place_image(&mut self, new_image_id: usize, image_size_and_coordinates: PixelRect) {
+        // here we remove images which this image covers completely to save on system
+        // resources - TODO: also do this with partial covers, eg. if several images
+        // together cover one image
+        for (image_id, pixel_rect) in &self.sixel_image_locations {
+            if let Some(intersecting_rect) =
+                pixel_rect.intersecting_rect(&image_size_and_coordinates)
+            {
+                if intersecting_rect.x == pixel_rect.x
+                    && intersecting_rect.y == pixel_rect.y
+                    && intersecting_rect.height == pixel_rect.height
+                    && intersecting_rect.width == pixel_rect.width
+                {
+                    self.image_ids_to_reap.push(*image_id);
+                }
+            }
+        }
+        for image_id in &self.image_ids_to_reap {
+            self.sixel_image_locations.remove(image_id);
+        }
+
+        self.sixel_image_locations
+            .insert(new_image_id, image_size_and_coordinates);
+    }
+    pub fn remove_image(&mut self, image_id: usize) {
+        if self.sixel_image_locations.remove(&image_id).is_some() {
+            self.image_ids_to_reap.push(image_id);
+        }
+    }
     pub fn image_coordinates(&self) -> impl Iterator<Item = (usize, &PixelRect)> {
         self.sixel_image_locations
             .iter()
@@ -262,7 +271,14 @@ impl SixelGrid {
         }
     }
     pub fn next_image_id(&self) -> usize {
-        self.sixel_image_store.borrow().sixel_images.keys().len()
+        // images are reaped out of order, so we can't rely on the image count here
+        self.sixel_image_store
+            .borrow()
+            .sixel_images
+            .keys()
+            .max()
+            .map(|image_id| image_id + 1)
+            .unwrap_or(0)
     }
     pub fn new_sixel_image(&mut self, sixel_image_id: usize, sixel_image: SixelImage) {
         self.sixel_image_store
@@ -270,6 +286,20 @@ impl SixelGrid {
             .sixel_images
             .insert(sixel_image_id, (sixel_image, HashMap::new()));
     }
+    /// A kitty graphics image, along with its sixel conversion for hosts that do not support the
+    /// kitty graphics protocol
+    pub fn new_kitty_image(
+        &mut self,
+        image_id: usize,
+        sixel_image: SixelImage,
+        kitty_image: KittyImage,
+    ) {
+        self.new_sixel_image(image_id, sixel_image);
+        self.sixel_image_store
+            .borrow_mut()
+            .kitty_images
+            .insert(image_id, (kitty_image, HashMap::new()));
+    }
     pub fn remove_pixels_from_image(&mut self, image_id: usize, pixel_rect: PixelRect) {
         if let Some((sixel_image, sixel_image_cache)) = self
             .sixel_image_store
@@ -285,10 +315,26 @@ impl SixelGrid {
             );
             sixel_image_cache.clear(); // TODO: more intelligent cache clearing
         }
+        if let Some((kitty_image, kitty_image_cache)) = self
+            .sixel_image_store
+            .borrow_mut()
+            .kitty_images
+            .get_mut(&image_id)
+        {
+            kitty_image.cut_out(
+                pixel_rect.x,
+                pixel_rect.y as usize,
+                pixel_rect.width,
+                pixel_rect.height,
+            );
+            kitty_image_cache.clear();
+        }
     }
     pub fn reap_images(&mut self, ids_to_reap: Vec<usize>) {
+        let mut sixel_image_store = self.sixel_image_store.borrow_mut();
         for id in ids_to_reap {
-            drop(self.sixel_image_store.borrow_mut().sixel_images.remove(&id));
+            drop(sixel_image_store.sixel_images.remove(&id));
+            drop(sixel_image_store.kitty_images.remove(&id));
         }
     }
     pub fn image_cell_coordinates_in_viewport(
@@ -425,9 +471,11 @@ impl SixelGrid {
 }
 
 type SixelImageCache = HashMap<PixelRect, String>;
+type KittyImageCache = HashMap<PixelRect, (SizeInPixels, String)>;
 #[derive(Debug, Clone, Default)]
 pub struct SixelImageStore {
     sixel_images: HashMap<usize, (SixelImage, SixelImageCache)>,
+    kitty_images: HashMap<usize, (KittyImage, KittyImageCache)>, // same ids as their sixel images
 }
 
 impl SixelImageStore {
@@ -460,6 +508,29 @@ impl SixelImageStore {
                 }
             })
     }
+    pub fn is_kitty_image(&self, image_id: usize) -> bool {
+        self.kitty_images.contains_key(&image_id)
+    }
+    /// The size and base64 encoded pixels of part of a kitty graphics image
+    pub fn serialize_kitty_image(
+        &mut self,
+        image_id: usize,
+        pixel_x: usize,
+        pixel_y: usize,
+        pixel_width: usize,
+        pixel_height: usize,
+    ) -> Option<(SizeInPixels, String)> {
+        self.kitty_images
+            .get_mut(&image_id)
+            .map(|(kitty_image, kitty_image_cache)| {
+                kitty_image_cache
+                    .entry(PixelRect::new(pixel_x, pixel_y, pixel_height, pixel_width))
+                    .or_insert_with(|| {
+                        kitty_image.serialize_range(pixel_x, pixel_y, pixel_width, pixel_height)
+                    })
+                    .clone()
+            })
+    }
     pub fn image_count(&self) -> usize {
         self.sixel_images.len()
     }
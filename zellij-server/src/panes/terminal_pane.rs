@@ -1,4 +1,6 @@
 use crate::output::{CharacterChunk, SixelImageChunk};
+use crate::panes::kitty_keyboard::KittyKeyEvent;
+use crate::panes::scrollback_store::{PersistedScrollback, ScrollbackRecord};
 use crate::panes::sixel::SixelImageStore;
 use crate::panes::LinkHandler;
 use crate::panes::{
@@ -16,7 +18,8 @@ use std::rc::Rc;
 use std::time::{self, Instant};
 use vte;
 use zellij_utils::data::PaneContents;
-use zellij_utils::input::command::RunCommand;
+use zellij_utils::input::actions::{CopyModeMotion, CopySelectionKind};
+use zellij_utils::input::command::{RestartPolicy, RunCommand};
 use zellij_utils::input::mouse::{MouseEvent, MouseEventType};
 use zellij_utils::pane_size::Offset;
 use zellij_utils::{
@@ -145,7 +148,11 @@ pub struct TerminalPane {
     search_term: String,
     is_held: Option<(Option<i32>, IsFirstRun, RunCommand)>, // a "held" pane means that its command has either exited and the pane is waiting for a
     // possible user instruction to be re-run, or that the command has not yet been run
-    banner: Option<String>, // a banner to be rendered inside this TerminalPane, used for panes
+    restart_count: usize, // how many times in a row the command was restarted by its restart policy
+    last_exit_status: Option<i32>, // the exit status of the command before it was last restarted
+    pending_restart: Option<usize>, // the next restart attempt, if the command is held waiting for it
+    command_started_at: Instant, // when the command last started running, to tell if it was stable
+    banner: Option<String>,      // a banner to be rendered inside this TerminalPane, used for panes
     // held on startup and can possibly be used to display some errors
     pane_frame_color_override: Option<(PaletteColor, Option<String>)>,
     has_bell_notification: bool,
@@ -225,6 +232,7 @@ impl Pane for TerminalPane {
         let mut iter = bytes.into_iter();
         while let Some(byte) = iter.next() {
             self.vte_parser.advance(&mut self.grid, byte);
+            self.grid.advance_kitty_graphics_parser(byte);
             if !self.grid.pending_forwarded_queries.is_empty() {
                 // Grid produced a forward. Stop feeding; queue the
                 // un-fed remainder so Tab can replay it after the
@@ -302,7 +310,7 @@ impl Pane for TerminalPane {
                 }
             }
         } else {
-            if self.grid.supports_kitty_keyboard_protocol {
+            if !self.grid.kitty_keyboard_flags().is_empty() {
                 self.adjust_input_to_terminal_with_kitty_keyboard_protocol(
                     key_with_modifier,
                     raw_input_bytes,
@@ -408,9 +416,17 @@ impl Pane for TerminalPane {
                 if self.grid.search_results.wrap_search {
                     modifiers.push("w")
                 }
+                if self.grid.search_results.regex_search {
+                    modifiers.push("r")
+                }
                 modifier_text.push_str(&modifiers.join(", "));
                 modifier_text.push(']');
             }
+            if self.grid.search_results.regex_search
+                && self.grid.search_results.compiled_regex.is_none()
+            {
+                modifier_text.push_str(" (invalid regex)");
+            }
             format!("SEARCHING: {}{}", self.search_term, modifier_text)
         } else if self.pane_name.is_empty() {
             self.grid
@@ -448,6 +464,9 @@ impl Pane for TerminalPane {
                 frame.add_exit_status(exit_status.as_ref().copied());
             }
         }
+        if self.restart_count > 0 {
+            frame.add_restart_info(self.restart_count, self.last_exit_status);
+        }
         if let Some((frame_color_override, _text)) = self.pane_frame_color_override.as_ref() {
             frame.override_color(*frame_color_override);
         }
@@ -651,11 +670,42 @@ impl Pane for TerminalPane {
         self.grid.pending_osc7_cwd.take()
     }
 
+    fn drain_finished_commands(&mut self) -> Vec<Option<i32>> {
+        self.grid.pending_finished_commands.drain(..).collect()
+    }
+
+    fn scroll_to_previous_prompt(&mut self) {
+        if self.grid.scroll_to_previous_prompt() {
+            self.set_should_render(true);
+        }
+    }
+
+    fn scroll_to_next_prompt(&mut self) {
+        if self.grid.scroll_to_next_prompt() {
+            self.set_should_render(true);
+        }
+    }
+
+    fn select_last_command_output(&mut self) {
+        if self.grid.select_last_command_output() {
+            self.set_should_render(true);
+        }
+    }
+
+    fn command_output(&self, index: usize) -> Option<String> {
+        self.grid.command_output_text(index)
+    }
+
     fn start_selection(&mut self, start: &Position, _client_id: ClientId) {
         self.grid.start_selection(start);
         self.set_should_render(true);
     }
 
+    fn start_block_selection(&mut self, start: &Position, _client_id: ClientId) {
+        self.grid.start_block_selection(start);
+        self.set_should_render(true);
+    }
+
     fn update_selection(&mut self, to: &Position, _client_id: ClientId) {
         let should_scroll = self.selection_scrolled_at.elapsed()
             >= time::Duration::from_millis(SELECTION_SCROLL_INTERVAL_MS);
@@ -818,15 +868,45 @@ impl Pane for TerminalPane {
     fn toggle_search_wrap(&mut self) {
         self.grid.toggle_search_wrap();
     }
+    fn toggle_search_regex(&mut self) {
+        self.grid.toggle_search_regex();
+        self.set_should_render(true);
+    }
     fn clear_search(&mut self) {
         self.grid.clear_search();
         self.search_term.clear();
     }
+    fn enter_copy_mode(&mut self) {
+        self.grid.enter_copy_mode();
+        self.set_should_render(true);
+    }
+    fn exit_copy_mode(&mut self) {
+        self.grid.exit_copy_mode();
+        self.set_should_render(true);
+    }
+    fn copy_mode_move(&mut self, motion: CopyModeMotion) {
+        self.grid.copy_mode_move(motion);
+        self.set_should_render(true);
+    }
+    fn copy_mode_select(&mut self, kind: CopySelectionKind) {
+        self.grid.copy_mode_select(kind);
+        self.set_should_render(true);
+    }
     fn is_alternate_mode_active(&self) -> bool {
         self.grid.is_alternate_mode_active()
     }
     fn hold(&mut self, exit_status: Option<i32>, is_first_run: bool, run_command: RunCommand) {
         self.invoked_with = Some(Run::Command(run_command.clone()));
+        if !is_first_run
+            && RestartPolicy::resets_restart_count(exit_status, self.command_started_at.elapsed())
+        {
+            self.restart_count = 0;
+        }
+        self.pending_restart = if is_first_run {
+            None
+        } else {
+            run_command.next_restart(exit_status, self.restart_count)
+        };
         self.is_held = Some((exit_status, is_first_run, run_command));
         if let Some(notification_end) = self.notification_end.as_mut() {
             if let Some(exit_status) = exit_status {
@@ -923,18 +1003,49 @@ impl Pane for TerminalPane {
     fn serialize(&self, scrollback_lines_to_serialize: Option<usize>) -> Option<String> {
         self.grid.serialize(scrollback_lines_to_serialize)
     }
+    fn scrollback_record(&mut self) -> Option<ScrollbackRecord> {
+        self.grid.scrollback_record()
+    }
+    fn has_persisted_scrollback(&self) -> bool {
+        self.grid.has_persisted_scrollback()
+    }
+    fn restore_scrollback(&mut self, persisted_scrollback: PersistedScrollback) {
+        self.grid.restore_scrollback(persisted_scrollback);
+        self.set_should_render(true);
+    }
     fn rerun(&mut self) -> Option<RunCommand> {
         // if this is a command pane that has exited or is waiting to be rerun, will return its
         // RunCommand, otherwise it is safe to assume this is not the right sort of pane or that it
         // is not in the right sort of state
         self.is_held.take().map(|(_, _, run_command)| {
             self.is_held = None;
+            self.reset_restarts();
             self.grid.reset_terminal_state();
             self.set_should_render(true);
             self.remove_banner();
             run_command.clone()
         })
     }
+    fn pending_restart(&self) -> Option<usize> {
+        self.pending_restart
+    }
+    fn restart(&mut self, attempt: usize) -> Option<RunCommand> {
+        // only restart if this is still the restart we were waiting for (eg. the user did not
+        // re-run the command manually in the meantime)
+        if self.pending_restart != Some(attempt) {
+            return None;
+        }
+        self.pending_restart = None;
+        self.is_held.take().map(|(exit_status, _, run_command)| {
+            self.restart_count = attempt;
+            self.last_exit_status = exit_status;
+            self.command_started_at = Instant::now();
+            self.grid.reset_terminal_state();
+            self.set_should_render(true);
+            self.remove_banner();
+            run_command
+        })
+    }
     fn update_theme(&mut self, theme: Styling) {
         self.style.colors = theme.clone();
         self.grid.update_theme(theme);
@@ -1132,6 +1243,10 @@ impl TerminalPane {
             fake_cursor_locations: HashSet::new(),
             search_term: String::new(),
             is_held: None,
+            restart_count: 0,
+            last_exit_status: None,
+            pending_restart: None,
+            command_started_at: Instant::now(),
             banner: None,
             pane_frame_color_override: None,
             has_bell_notification: false,
@@ -1199,6 +1314,12 @@ impl TerminalPane {
         self.banner = Some(banner.clone());
         self.handle_pty_bytes(banner.as_bytes().to_vec());
     }
+    fn reset_restarts(&mut self) {
+        self.restart_count = 0;
+        self.last_exit_status = None;
+        self.pending_restart = None;
+        self.command_started_at = Instant::now();
+    }
     fn remove_banner(&mut self) {
         if self.banner.is_some() {
             self.grid.reset_terminal_state();
@@ -1212,15 +1333,20 @@ impl TerminalPane {
         raw_input_bytes: Vec<u8>,
         raw_input_bytes_are_kitty: bool,
     ) -> Option<AdjustedInput> {
-        if raw_input_bytes_are_kitty || key.is_none() {
-            Some(AdjustedInput::WriteBytesToTerminal(raw_input_bytes))
+        // the host terminal reports keys with all the kitty keyboard protocol enhancements (if
+        // it supports them), so we re-encode them with the ones this terminal pane asked for.
+        // If the host terminal is operating in non "kitty keys" mode, we serialize the
+        // "non kitty" key to a "kitty key" instead
+        let key_event = if raw_input_bytes_are_kitty {
+            KittyKeyEvent::parse(&raw_input_bytes)
         } else {
-            // here what happens is that the host terminal is operating in non "kitty keys" mode, but
-            // this terminal pane *is* operating in "kitty keys" mode - so we need to serialize the "non kitty"
-            // key to a "kitty key"
-            key.as_ref()
-                .and_then(|k| k.serialize_kitty())
-                .map(|s| AdjustedInput::WriteBytesToTerminal(s.as_bytes().to_vec()))
+            key.as_ref().and_then(KittyKeyEvent::from_key)
+        };
+        match key_event {
+            Some(key_event) => key_event
+                .encode(self.grid.kitty_keyboard_flags())
+                .map(AdjustedInput::WriteBytesToTerminal),
+            None => Some(AdjustedInput::WriteBytesToTerminal(raw_input_bytes)),
         }
     }
     fn adjust_input_to_terminal_without_kitty_keyboard_protocol(
@@ -1229,6 +1355,14 @@ impl TerminalPane {
         raw_input_bytes: Vec<u8>,
         raw_input_bytes_are_kitty: bool,
     ) -> Option<AdjustedInput> {
+        if raw_input_bytes_are_kitty
+            && KittyKeyEvent::parse(&raw_input_bytes)
+                .map(|key_event| !key_event.is_key_press())
+                .unwrap_or(false)
+        {
+            // key releases and lone modifiers only exist in the kitty keyboard protocol
+            return None;
+        }
         if self.grid.new_line_mode {
             let key_is_enter = raw_input_bytes.as_slice() == &[13]
                 || key
@@ -1314,6 +1448,7 @@ impl TerminalPane {
     fn handle_held_run(&mut self) -> Option<AdjustedInput> {
         self.is_held.take().map(|(_, _, run_command)| {
             self.is_held = None;
+            self.reset_restarts();
             self.grid.reset_terminal_state();
             self.set_should_render(true);
             self.remove_banner();
@@ -1325,6 +1460,7 @@ impl TerminalPane {
             // Drop to shell in the same working directory as the command was run
             let working_dir = run_command.cwd.clone();
             self.is_held = None;
+            self.reset_restarts();
             self.grid.reset_terminal_state();
             self.set_should_render(true);
             self.remove_banner();
@@ -10,7 +10,7 @@ use crate::{
     output::Output,
     panes::{ActivePanes, PaneId},
     plugins::PluginInstruction,
-    tab::{pane_info_for_pane, Pane, MIN_TERMINAL_HEIGHT, MIN_TERMINAL_WIDTH},
+    tab::{pane_info_for_pane, Pane, PaneHints, MIN_TERMINAL_HEIGHT, MIN_TERMINAL_WIDTH},
     thread_bus::ThreadSenders,
     ui::boundaries::Boundaries,
     ui::pane_contents_and_ui::PaneContentsAndUi,
@@ -1005,12 +1005,15 @@ impl TiledPanes {
     pub fn has_panes(&self) -> bool {
         !self.panes.is_empty()
     }
+    #[allow(clippy::too_many_arguments)]
     pub fn render(
         &mut self,
         output: &mut Output,
         floating_panes_are_visible: bool,
         mouse_hover_pane_id: &HashMap<ClientId, PaneId>,
         current_pane_group: HashMap<ClientId, Vec<PaneId>>,
+        sync_group: &[PaneId],
+        pane_hints: &HashMap<ClientId, PaneHints>,
         client_id_override: Option<ClientId>,
         help_text_visible: &HashMap<ClientId, bool>,
     ) -> Result<()> {
@@ -1112,6 +1115,8 @@ impl TiledPanes {
                     should_draw_pane_frames,
                     &mouse_hover_pane_id,
                     current_pane_group.clone(),
+                    sync_group,
+                    pane_hints,
                     show_help_text,
                 );
                 for client_id in &connected_clients {
@@ -1198,6 +1203,14 @@ impl TiledPanes {
                             .with_context(err_context)?;
                     }
                 }
+                if !self.draw_pane_frames && !pane_is_stacked {
+                    // stacked panes show it on their title line
+                    for client_id in &connected_clients {
+                        pane_contents_and_ui
+                            .render_pane_hint_label_over_contents(*client_id)
+                            .with_context(err_context)?;
+                    }
+                }
             }
         }
         // render boundaries if needed
@@ -1219,6 +1232,9 @@ impl TiledPanes {
     pub fn get_panes(&self) -> impl Iterator<Item = (&PaneId, &Box<dyn Pane>)> {
         self.panes.iter()
     }
+    pub fn get_panes_mut(&mut self) -> impl Iterator<Item = (&PaneId, &mut Box<dyn Pane>)> {
+        self.panes.iter_mut()
+    }
     pub fn set_geom_for_pane_with_run(
         &mut self,
         run: Option<Run>,
@@ -2484,6 +2500,44 @@ impl TiledPanes {
             closed_pane
         }
     }
+    /// Move a pane back to the geom it had before it was closed, if the panes that took over
+    /// its space have not changed since. Otherwise the pane is left where it is.
+    pub fn restore_pane_geom(&mut self, pane_id: PaneId, geom: PaneGeom) {
+        let mut pane_grid = TiledPaneGrid::new(
+            &mut self.panes,
+            &self.panes_to_hide,
+            *self.display_area.borrow(),
+            *self.viewport.borrow(),
+        );
+        if !pane_grid.fill_space_over_pane(pane_id) {
+            return;
+        }
+        let Some(mut pane) = self.panes.remove(&pane_id) else {
+            return;
+        };
+        let mut pane_grid = TiledPaneGrid::new(
+            &mut self.panes,
+            &self.panes_to_hide,
+            *self.display_area.borrow(),
+            *self.viewport.borrow(),
+        );
+        match pane_grid.make_room_for_pane_at(&geom) {
+            Some(direction) => {
+                pane.set_geom(PaneGeom {
+                    logical_position: None,
+                    ..geom
+                });
+                self.add_pane_with_existing_geom(pane_id, pane);
+                self.relayout(direction);
+            },
+            None => {
+                let should_relayout = true;
+                self.add_pane(pane_id, pane, should_relayout, None);
+            },
+        }
+        self.reset_boundaries();
+        self.set_pane_frames(self.draw_pane_frames);
+    }
     pub fn hold_pane(
         &mut self,
         pane_id: PaneId,
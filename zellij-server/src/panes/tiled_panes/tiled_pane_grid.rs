@@ -1355,6 +1355,100 @@ impl<'a> TiledPaneGrid<'a> {
         }
         false
     }
+    /// Shrink the panes that grew over the space of a closed pane back out of it, so that a pane
+    /// can be placed at its previous geom. Returns the direction in which the tab should be
+    /// relaid out, or None (without changing anything) if the panes around it changed since
+    pub fn make_room_for_pane_at(&mut self, geom: &PaneGeom) -> Option<SplitDirection> {
+        let (freed_width, freed_height) = (geom.cols.as_percent()?, geom.rows.as_percent()?);
+        let overlapping: Vec<(PaneId, PaneGeom)> = {
+            let panes = self.panes.borrow();
+            let mut overlapping = vec![];
+            for (pane_id, pane) in panes.iter() {
+                let pane_geom = pane.position_and_size();
+                let overlaps = pane_geom.x < geom.x + geom.cols.as_usize()
+                    && geom.x < pane_geom.x + pane_geom.cols.as_usize()
+                    && pane_geom.y < geom.y + geom.rows.as_usize()
+                    && geom.y < pane_geom.y + pane_geom.rows.as_usize();
+                if overlaps {
+                    if pane_geom.is_stacked() || !pane.selectable() {
+                        return None;
+                    }
+                    overlapping.push((*pane_id, pane_geom));
+                }
+            }
+            overlapping
+        };
+        if overlapping.is_empty() {
+            return None;
+        }
+        let (geom_right, geom_bottom) =
+            (geom.x + geom.cols.as_usize(), geom.y + geom.rows.as_usize());
+        let grew_sideways = overlapping.iter().all(|(_, p)| {
+            p.y >= geom.y
+                && p.y + p.rows.as_usize() <= geom_bottom
+                && p.x <= geom.x
+                && p.x + p.cols.as_usize() >= geom_right
+                && p.cols
+                    .as_percent()
+                    .map(|c| c > freed_width)
+                    .unwrap_or(false)
+        });
+        let grew_vertically = overlapping.iter().all(|(_, p)| {
+            p.x >= geom.x
+                && p.x + p.cols.as_usize() <= geom_right
+                && p.y <= geom.y
+                && p.y + p.rows.as_usize() >= geom_bottom
+                && p.rows
+                    .as_percent()
+                    .map(|r| r > freed_height)
+                    .unwrap_or(false)
+        });
+        if grew_sideways {
+            let grew_from_the_left = overlapping
+                .iter()
+                .all(|(_, p)| p.x + p.cols.as_usize() == geom_right);
+            let grew_from_the_right = overlapping.iter().all(|(_, p)| p.x == geom.x);
+            if !grew_from_the_left && !grew_from_the_right {
+                return None;
+            }
+            let mut panes = self.panes.borrow_mut();
+            for (pane_id, _) in &overlapping {
+                if let Some(pane) = panes.get_mut(pane_id) {
+                    pane.reduce_width(freed_width);
+                    if !grew_from_the_left {
+                        // the relayout orders panes by their position, so this one needs to
+                        // move out of the way
+                        let mut pane_geom = pane.position_and_size();
+                        pane_geom.x = geom_right;
+                        pane.set_geom(pane_geom);
+                    }
+                }
+            }
+            Some(SplitDirection::Horizontal)
+        } else if grew_vertically {
+            let grew_from_above = overlapping
+                .iter()
+                .all(|(_, p)| p.y + p.rows.as_usize() == geom_bottom);
+            let grew_from_below = overlapping.iter().all(|(_, p)| p.y == geom.y);
+            if !grew_from_above && !grew_from_below {
+                return None;
+            }
+            let mut panes = self.panes.borrow_mut();
+            for (pane_id, _) in &overlapping {
+                if let Some(pane) = panes.get_mut(pane_id) {
+                    pane.reduce_height(freed_height);
+                    if !grew_from_above {
+                        let mut pane_geom = pane.position_and_size();
+                        pane_geom.y = geom_bottom;
+                        pane.set_geom(pane_geom);
+                    }
+                }
+            }
+            Some(SplitDirection::Vertical)
+        } else {
+            None
+        }
+    }
     pub fn find_room_for_new_pane(
         &self,
         cursor_height_width_ratio: Option<usize>,
@@ -1,6 +1,9 @@
 use super::super::Grid;
 use crate::panes::grid::SixelImageStore;
 use crate::panes::link_handler::LinkHandler;
+use crate::panes::scrollback_archive::ScrollbackArchive;
+use crate::panes::scrollback_store::{read_scrollback_file, scrollback_file_name};
+use crate::panes::terminal_character::LinkAnchor;
 use insta::assert_snapshot;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -8,6 +11,7 @@ use std::rc::Rc;
 use vte;
 use zellij_utils::{
     data::{Palette, Style},
+    input::actions::{CopyModeMotion, CopySelectionKind},
     pane_size::SizeInPixels,
     position::Position,
 };
@@ -6102,3 +6106,636 @@ fn csi_5n_status_query_still_handled_locally() {
         "DSR 5 must still produce its local 'all good' reply"
     );
 }
+
+fn shell_command_with_osc_133_marks(command: &str, output: &[&str], exit_code: i32) -> String {
+    let mut bytes = format!(
+        "\u{1b}]133;A\u{7}$ \u{1b}]133;B\u{7}{}\r\n\u{1b}]133;C\u{7}",
+        command
+    );
+    for line in output {
+        bytes.push_str(line);
+        bytes.push_str("\r\n");
+    }
+    bytes.push_str(&format!("\u{1b}]133;D;{}\u{7}", exit_code));
+    bytes
+}
+
+#[test]
+fn osc_133_command_end_reports_exit_codes_and_output() {
+    let mut parser = vte::Parser::new();
+    let mut grid = new_grid_for_forwarding_test();
+    let bytes = format!(
+        "{}{}\u{1b}]133;A\u{7}$ ",
+        shell_command_with_osc_133_marks("ls", &["file1", "file2"], 0),
+        shell_command_with_osc_133_marks("cat nope", &["cat: nope: No such file"], 1),
+    );
+    for byte in bytes.as_bytes() {
+        parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(
+        grid.pending_finished_commands,
+        vec![Some(0), Some(1)],
+        "both finished commands reported with their exit codes"
+    );
+    assert_eq!(
+        grid.command_output_text(0),
+        Some("cat: nope: No such file".to_owned())
+    );
+    assert_eq!(grid.command_output_text(1), Some("file1\nfile2".to_owned()));
+    assert_eq!(grid.command_output_text(2), None, "no such command output");
+}
+
+#[test]
+fn osc_133_command_output_survives_reflow() {
+    let mut parser = vte::Parser::new();
+    let mut grid = new_grid_for_forwarding_test();
+    let bytes = format!(
+        "{}\u{1b}]133;A\u{7}$ ",
+        shell_command_with_osc_133_marks("cat", &["a line longer than the pane width", "short"], 0),
+    );
+    for byte in bytes.as_bytes() {
+        parser.advance(&mut grid, *byte);
+    }
+    grid.change_size(10, 7);
+    assert_eq!(
+        grid.command_output_text(0),
+        Some("a line longer than the pane width\nshort".to_owned()),
+        "command output extracted after shrinking the pane"
+    );
+    grid.change_size(10, 40);
+    assert_eq!(
+        grid.command_output_text(0),
+        Some("a line longer than the pane width\nshort".to_owned()),
+        "command output extracted after growing the pane back"
+    );
+}
+
+#[test]
+fn osc_133_marks_are_ignored_in_alternate_screen() {
+    let mut parser = vte::Parser::new();
+    let mut grid = new_grid_for_forwarding_test();
+    let bytes = format!(
+        "\u{1b}[?1049h{}",
+        shell_command_with_osc_133_marks("ls", &["file1"], 0),
+    );
+    for byte in bytes.as_bytes() {
+        parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.pending_finished_commands, vec![Some(0)]);
+    assert_eq!(grid.command_output_text(0), None);
+}
+
+#[test]
+fn scroll_to_previous_and_next_prompt() {
+    let mut parser = vte::Parser::new();
+    let mut grid = new_grid_for_forwarding_test();
+    let output = ["line 1", "line 2", "line 3", "line 4", "line 5"];
+    let mut bytes = String::new();
+    for i in 0..5 {
+        bytes.push_str(&shell_command_with_osc_133_marks(
+            &format!("command {}", i),
+            &output,
+            0,
+        ));
+    }
+    bytes.push_str("\u{1b}]133;A\u{7}$ ");
+    for byte in bytes.as_bytes() {
+        parser.advance(&mut grid, *byte);
+    }
+    assert!(!grid.is_scrolled);
+    assert!(grid.scroll_to_previous_prompt());
+    assert!(!grid.is_scrolled, "the previous prompt is on screen");
+    assert!(grid.scroll_to_previous_prompt());
+    assert!(grid.viewport[0].has_prompt_start());
+    let lines_above_at_first_prompt = grid.lines_above.len();
+    assert!(grid.scroll_to_previous_prompt());
+    assert!(grid.viewport[0].has_prompt_start());
+    assert_eq!(grid.lines_above.len(), lines_above_at_first_prompt - 6);
+    assert!(grid.scroll_to_next_prompt());
+    assert!(grid.viewport[0].has_prompt_start());
+    assert_eq!(grid.lines_above.len(), lines_above_at_first_prompt);
+    while grid.scroll_to_previous_prompt() {}
+    assert_eq!(grid.lines_above.len(), 0, "scrolled to the first prompt");
+    assert!(grid.viewport[0].has_prompt_start());
+}
+
+#[test]
+fn scroll_to_previous_prompt_steps_through_prompts_on_screen() {
+    let mut parser = vte::Parser::new();
+    let mut grid = new_grid_for_forwarding_test();
+    let mut bytes = String::from("motd line 1\r\nmotd line 2\r\nmotd line 3\r\n");
+    for i in 0..2 {
+        bytes.push_str(&shell_command_with_osc_133_marks(
+            &format!("command {}", i),
+            &["line 1", "line 2"],
+            0,
+        ));
+    }
+    bytes.push_str("\u{1b}]133;A\u{7}$ ");
+    for byte in bytes.as_bytes() {
+        parser.advance(&mut grid, *byte);
+    }
+    assert!(!grid.is_scrolled);
+    assert!(
+        !grid.lines_above.iter().any(|row| row.has_prompt_start()),
+        "all prompts are on screen"
+    );
+    let viewport_before = format!("{:?}", grid);
+    let (command_0_prompt_row, command_1_prompt_row) = (3, 6);
+    let selected_rows = |grid: &Grid| -> Vec<usize> {
+        (0..grid.height)
+            .filter(|row| grid.selection.contains_row(*row))
+            .collect()
+    };
+
+    assert!(grid.scroll_to_previous_prompt(), "prompt of command 1");
+    assert_eq!(
+        selected_rows(&grid),
+        vec![command_1_prompt_row],
+        "prompt of command 1 is selected"
+    );
+    assert_eq!(
+        grid.get_selected_text()
+            .map(|text| text.trim_end().to_owned()),
+        Some("$ command 1".to_owned())
+    );
+    assert!(grid.scroll_to_previous_prompt(), "prompt of command 0");
+    assert_eq!(
+        selected_rows(&grid),
+        vec![command_0_prompt_row],
+        "prompt of command 0 is selected"
+    );
+    assert!(
+        !grid.scroll_to_previous_prompt(),
+        "no prompt above command 0"
+    );
+    assert!(!grid.is_scrolled, "prompts on screen are not scrolled to");
+    assert_eq!(format!("{:?}", grid), viewport_before);
+
+    parser.advance(&mut grid, b'x');
+    assert!(
+        grid.scroll_to_previous_prompt(),
+        "new output starts over from the cursor"
+    );
+}
+
+#[test]
+fn kitty_keyboard_flags_stack_per_screen() {
+    let mut parser = vte::Parser::new();
+    let mut grid = new_grid_for_forwarding_test();
+    let mut write = |grid: &mut Grid, bytes: &str| {
+        for byte in bytes.as_bytes() {
+            parser.advance(grid, *byte);
+        }
+    };
+    write(&mut grid, "\u{1b}[>1u\u{1b}[>11u\u{1b}[?u");
+    assert_eq!(grid.kitty_keyboard_flags().bits(), 11);
+    write(&mut grid, "\u{1b}[=4;2u");
+    assert_eq!(grid.kitty_keyboard_flags().bits(), 15);
+    // the alternate screen has a stack of its own
+    write(&mut grid, "\u{1b}[?1049h\u{1b}[?u");
+    assert!(grid.kitty_keyboard_flags().is_empty());
+    write(&mut grid, "\u{1b}[>31u\u{1b}[?1049l");
+    assert_eq!(grid.kitty_keyboard_flags().bits(), 15);
+    write(&mut grid, "\u{1b}[<u");
+    assert_eq!(grid.kitty_keyboard_flags().bits(), 1);
+    write(&mut grid, "\u{1b}[<5u");
+    assert!(grid.kitty_keyboard_flags().is_empty());
+    assert_eq!(
+        grid.pending_messages_to_pty,
+        vec![b"\x1b[?11u".to_vec(), b"\x1b[?0u".to_vec()]
+    );
+}
+
+fn create_grid_with_archived_scrollback(line_count: usize, disk_scroll_buffer_size: usize) -> Grid {
+    let mut grid = create_grid_with_size_and_raw(10, 80, b"");
+    grid.scrollback_archive = ScrollbackArchive::new(disk_scroll_buffer_size);
+    let mut content = String::new();
+    for i in 0..line_count {
+        write!(content, "\u{1b}[3{}mline {}\u{1b}[m\r\n", i % 8, i).unwrap();
+    }
+    feed_bytes(&mut grid, content.as_bytes());
+    grid
+}
+
+#[test]
+fn lines_dropped_from_scroll_buffer_are_archived() {
+    let grid = create_grid_with_archived_scrollback(12_500, 100_000);
+    assert_eq!(grid.lines_above.len(), 10_000);
+    assert_eq!(grid.archived_line_count(), 2_491);
+    let dump = grid.dump_screen(true);
+    let lines: Vec<&str> = dump.lines().collect();
+    assert_eq!(lines[0], "line 0");
+    assert_eq!(lines[12_499], "line 12499");
+    // archived lines keep their styles
+    let dump_with_ansi = grid.dump_screen_with_ansi(true);
+    let lines_with_ansi: Vec<&str> = dump_with_ansi.lines().collect();
+    assert_eq!(
+        lines_with_ansi[0].replace("line 0", ""),
+        lines_with_ansi[10_000].replace("line 10000", "")
+    );
+    assert_ne!(lines_with_ansi[0], "line 0");
+}
+
+#[test]
+fn archived_lines_are_trimmed_to_disk_scroll_buffer_size() {
+    let grid = create_grid_with_archived_scrollback(14_500, 2_500);
+    assert_eq!(grid.lines_above.len(), 10_000);
+    assert!(grid.archived_line_count() <= 2_500);
+    let dump = grid.dump_screen(true);
+    let first_line = dump.lines().next().unwrap().to_owned();
+    assert_eq!(
+        first_line,
+        format!("line {}", 14_491 - 10_000 - grid.archived_line_count())
+    );
+}
+
+#[test]
+fn pane_contents_include_archived_lines() {
+    let grid = create_grid_with_archived_scrollback(12_500, 100_000);
+    let full = grid.pane_contents(true, None);
+    assert_eq!(full.lines_above_viewport.len(), 12_491);
+    assert_eq!(full.lines_above_viewport[0].trim_end(), "line 0");
+    let last_lines = grid.pane_contents(true, Some(10_005));
+    assert_eq!(last_lines.lines_above_viewport.len(), 10_005);
+    assert_eq!(last_lines.lines_above_viewport[0].trim_end(), "line 2486");
+}
+
+#[test]
+fn scrolling_up_restores_archived_lines() {
+    let mut grid = create_grid_with_archived_scrollback(12_500, 100_000);
+    for _ in 0..12_491 {
+        grid.scroll_up_one_line();
+    }
+    assert!(!grid.has_archived_lines());
+    assert_eq!(grid.dump_screen(false).lines().next(), Some("line 0"));
+    assert_eq!(grid.scrollback_position_and_length(), (12_491, 12_491));
+    // the lines we scrolled past are archived again rather than all being kept in memory
+    assert_eq!(grid.lines_below.len(), 10_000);
+    assert_eq!(grid.lines_below_archive.len(), 2_491);
+    let full = grid.pane_contents(true, None);
+    assert_eq!(full.lines_below_viewport.len(), 12_491);
+    assert_eq!(full.lines_below_viewport[0].trim_end(), "line 10");
+    assert_eq!(full.lines_below_viewport[12_489].trim_end(), "line 12499");
+    for _ in 0..12_491 {
+        grid.scroll_down_one_line();
+    }
+    assert!(grid.lines_below.is_empty());
+    assert!(grid.lines_below_archive.is_empty());
+    assert_eq!(grid.lines_above.len(), 10_000);
+    assert_eq!(grid.archived_line_count(), 2_491);
+    assert_eq!(grid.dump_screen(true).lines().next(), Some("line 0"));
+    assert_eq!(grid.dump_screen(false).lines().next(), Some("line 12491"));
+}
+
+#[test]
+fn search_finds_archived_lines() {
+    let mut grid = create_grid_with_size_and_raw(10, 80, b"");
+    grid.scrollback_archive = ScrollbackArchive::new(100_000);
+    let mut content = String::from("needle\r\n");
+    for i in 0..12_000 {
+        write!(content, "line {}\r\n", i).unwrap();
+    }
+    feed_bytes(&mut grid, content.as_bytes());
+    assert!(grid.has_archived_lines());
+    grid.set_search_string("needle");
+    grid.search_up();
+    assert!(grid.search_results.active.is_some());
+    assert_eq!(grid.dump_screen(false).lines().next(), Some("needle"));
+}
+
+#[test]
+fn restored_scrollback_keeps_rows_and_links() {
+    let mut grid = create_grid_with_size_and_raw(10, 80, b"");
+    let mut content =
+        String::from("\u{1b}]8;;https://zellij.dev\u{1b}\\link\u{1b}]8;;\u{1b}\\\r\n");
+    for i in 0..30 {
+        write!(content, "\u{1b}[3{}mline {}\u{1b}[m\r\n", i % 8, i).unwrap();
+    }
+    feed_bytes(&mut grid, content.as_bytes());
+    let record = grid.scrollback_record().unwrap();
+    assert!(record.truncate);
+    assert!(grid.has_persisted_scrollback());
+    assert!(grid.scrollback_record().is_none());
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(scrollback_file_name(1));
+    std::fs::write(&path, &record.bytes).unwrap();
+    let mut restored_grid = create_grid_with_size_and_raw(10, 80, b"");
+    // the restored pane already has a link of its own
+    feed_bytes(
+        &mut restored_grid,
+        b"\x1b]8;;https://example.com\x1b\\x\x1b]8;;\x1b\\\x1b[H",
+    );
+    restored_grid.restore_scrollback(read_scrollback_file(&path).unwrap());
+    // the last line above the viewport is part of the serialized pane contents instead
+    assert_eq!(restored_grid.lines_above.len(), grid.lines_above.len() - 1);
+    let original_lines = grid.dump_screen_with_ansi(true);
+    let restored_lines = restored_grid.dump_screen_with_ansi(true);
+    assert_eq!(
+        original_lines.lines().take(20).collect::<Vec<_>>(),
+        restored_lines.lines().take(20).collect::<Vec<_>>()
+    );
+    let link_anchor = restored_grid.lines_above[0].columns[0].styles.link_anchor;
+    let Some(LinkAnchor::Start(link_index)) = link_anchor else {
+        panic!("link was not restored");
+    };
+    let link_handler = restored_grid.link_handler.borrow();
+    assert_eq!(
+        link_handler.link(link_index).unwrap().uri,
+        "https://zellij.dev"
+    );
+}
+
+#[test]
+fn scrollback_records_are_incremental() {
+    let mut grid = create_grid_with_size_and_raw(10, 80, b"");
+    let mut content = String::new();
+    for i in 0..30 {
+        write!(content, "line {}\r\n", i).unwrap();
+    }
+    feed_bytes(&mut grid, content.as_bytes());
+    let first_record = grid.scrollback_record().unwrap();
+    feed_bytes(&mut grid, b"line 30\r\nline 31\r\n");
+    let second_record = grid.scrollback_record().unwrap();
+    assert!(!second_record.truncate);
+    assert!(second_record.bytes.len() < first_record.bytes.len());
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(scrollback_file_name(1));
+    std::fs::write(&path, [first_record.bytes, second_record.bytes].concat()).unwrap();
+    let persisted_scrollback = read_scrollback_file(&path).unwrap();
+    assert_eq!(persisted_scrollback.rows.len(), grid.lines_above.len() - 1);
+
+    // clearing the scrollback starts a new file
+    feed_bytes(&mut grid, b"\x1b[3J");
+    assert!(!grid.has_persisted_scrollback());
+}
+
+fn copy_mode_cursor_position(grid: &Grid) -> (isize, usize) {
+    let position = grid.copy_mode_cursor.unwrap().position;
+    (position.line(), position.column())
+}
+
+#[test]
+fn copy_mode_vi_motions() {
+    let mut grid = create_grid_with_size_and_raw(5, 20, b"foo bar-baz qux\r\nsecond line\r\n");
+    grid.enter_copy_mode();
+    assert_eq!(copy_mode_cursor_position(&grid), (2, 0));
+    grid.copy_mode_move(CopyModeMotion::Up);
+    grid.copy_mode_move(CopyModeMotion::Up);
+    grid.copy_mode_move(CopyModeMotion::Up);
+    assert_eq!(copy_mode_cursor_position(&grid), (0, 0));
+    grid.copy_mode_move(CopyModeMotion::WordForward);
+    assert_eq!(copy_mode_cursor_position(&grid), (0, 4));
+    grid.copy_mode_move(CopyModeMotion::WordForward);
+    assert_eq!(copy_mode_cursor_position(&grid), (0, 7));
+    grid.copy_mode_move(CopyModeMotion::WordForward);
+    assert_eq!(copy_mode_cursor_position(&grid), (0, 8));
+    grid.copy_mode_move(CopyModeMotion::WordEnd);
+    assert_eq!(copy_mode_cursor_position(&grid), (0, 10));
+    grid.copy_mode_move(CopyModeMotion::WordBackward);
+    assert_eq!(copy_mode_cursor_position(&grid), (0, 8));
+    grid.copy_mode_move(CopyModeMotion::LineEnd);
+    assert_eq!(copy_mode_cursor_position(&grid), (0, 14));
+    grid.copy_mode_move(CopyModeMotion::WordForward);
+    assert_eq!(
+        copy_mode_cursor_position(&grid),
+        (1, 0),
+        "word motions cross lines"
+    );
+    grid.copy_mode_move(CopyModeMotion::Up);
+    grid.copy_mode_move(CopyModeMotion::LineEnd);
+    grid.copy_mode_move(CopyModeMotion::Down);
+    assert_eq!(
+        copy_mode_cursor_position(&grid),
+        (1, 10),
+        "cursor is clamped to the end of shorter lines"
+    );
+    grid.copy_mode_move(CopyModeMotion::LineStart);
+    grid.copy_mode_move(CopyModeMotion::Left);
+    assert_eq!(copy_mode_cursor_position(&grid), (1, 0));
+    grid.copy_mode_move(CopyModeMotion::Right);
+    assert_eq!(copy_mode_cursor_position(&grid), (1, 1));
+    assert_eq!(grid.cursor_coordinates(), Some((1, 1, true)));
+    grid.exit_copy_mode();
+    assert_eq!(grid.cursor_coordinates(), Some((0, 2, true)));
+}
+
+#[test]
+fn copy_mode_selects_characters_and_lines() {
+    let mut grid = create_grid_with_size_and_raw(5, 20, b"foo bar-baz qux\r\nsecond line\r\n");
+    grid.enter_copy_mode();
+    grid.copy_mode_move(CopyModeMotion::Top);
+    grid.copy_mode_move(CopyModeMotion::WordForward);
+    grid.copy_mode_select(CopySelectionKind::Character);
+    grid.copy_mode_move(CopyModeMotion::WordEnd);
+    assert_eq!(grid.get_selected_text(), Some("bar".to_owned()));
+    grid.copy_mode_move(CopyModeMotion::Down);
+    assert_eq!(
+        grid.get_selected_text(),
+        Some("bar-baz qux\nsecond".to_owned())
+    );
+    grid.copy_mode_select(CopySelectionKind::Line);
+    assert_eq!(
+        grid.get_selected_text(),
+        Some("foo bar-baz qux\nsecond line".to_owned())
+    );
+    grid.copy_mode_select(CopySelectionKind::Line);
+    assert_eq!(grid.get_selected_text(), None, "selecting again toggles");
+}
+
+#[test]
+fn copy_mode_block_selection() {
+    let mut grid = create_grid_with_size_and_raw(5, 20, b"abcdef\r\nghi\r\nmnopqr\r\n");
+    grid.enter_copy_mode();
+    grid.copy_mode_move(CopyModeMotion::Top);
+    grid.copy_mode_move(CopyModeMotion::Right);
+    grid.copy_mode_select(CopySelectionKind::Block);
+    grid.copy_mode_move(CopyModeMotion::Down);
+    grid.copy_mode_move(CopyModeMotion::Down);
+    grid.copy_mode_move(CopyModeMotion::Right);
+    grid.copy_mode_move(CopyModeMotion::Right);
+    assert_eq!(copy_mode_cursor_position(&grid), (2, 3));
+    assert!(grid.selection.is_block());
+    assert_eq!(grid.get_selected_text(), Some("bcd\nhi\nnop".to_owned()));
+}
+
+#[test]
+fn scroll_to_top_matches_scrolling_up_line_by_line() {
+    let mut content = String::new();
+    for i in 0..30 {
+        write!(content, "line {} {}\r\n", i, "wrapped ".repeat(i % 4)).unwrap();
+    }
+    let mut scrolled_at_once = create_grid_with_size_and_raw(5, 20, content.as_bytes());
+    let mut scrolled_line_by_line = create_grid_with_size_and_raw(5, 20, content.as_bytes());
+    scrolled_at_once.scroll_to_top();
+    scrolled_line_by_line.move_viewport_up(1000);
+    assert_eq!(
+        viewport_texts(&scrolled_at_once),
+        viewport_texts(&scrolled_line_by_line)
+    );
+    assert_eq!(
+        scrolled_at_once.scrollback_position_and_length(),
+        scrolled_line_by_line.scrollback_position_and_length()
+    );
+    assert!(scrolled_at_once.is_scrolled);
+    scrolled_at_once.reset_viewport();
+    scrolled_line_by_line.reset_viewport();
+    assert_eq!(
+        viewport_texts(&scrolled_at_once),
+        viewport_texts(&scrolled_line_by_line)
+    );
+    assert!(!scrolled_at_once.is_scrolled);
+}
+
+#[test]
+fn scroll_to_top_and_bottom_through_archived_lines_match_scrolling_line_by_line() {
+    let mut scrolled_at_once = create_grid_with_archived_scrollback(12_500, 100_000);
+    let mut scrolled_line_by_line = create_grid_with_archived_scrollback(12_500, 100_000);
+    for grid in [&mut scrolled_at_once, &mut scrolled_line_by_line] {
+        grid.selection
+            .select(Position::new(9, 0), Position::new(9, 4), false);
+    }
+
+    scrolled_at_once.scroll_to_top();
+    scrolled_line_by_line.move_viewport_up(20_000);
+    assert!(!scrolled_at_once.has_archived_lines());
+    assert_eq!(
+        viewport_texts(&scrolled_at_once),
+        viewport_texts(&scrolled_line_by_line)
+    );
+    assert_eq!(
+        scrolled_at_once.scrollback_position_and_length(),
+        scrolled_line_by_line.scrollback_position_and_length()
+    );
+    assert_eq!(scrolled_at_once.selection, scrolled_line_by_line.selection);
+    assert!(
+        scrolled_at_once.lines_below.len() < 1_000,
+        "only the archived lines that come into view are read back"
+    );
+    assert_eq!(
+        scrolled_at_once
+            .pane_contents(true, None)
+            .lines_below_viewport,
+        scrolled_line_by_line
+            .pane_contents(true, None)
+            .lines_below_viewport
+    );
+
+    scrolled_at_once.reset_viewport();
+    scrolled_line_by_line.move_viewport_down(20_000);
+    assert!(!scrolled_at_once.is_scrolled);
+    assert_eq!(
+        viewport_texts(&scrolled_at_once),
+        viewport_texts(&scrolled_line_by_line)
+    );
+    assert_eq!(
+        scrolled_at_once.dump_screen(true),
+        scrolled_line_by_line.dump_screen(true)
+    );
+    assert_eq!(
+        scrolled_at_once.scrollback_position_and_length(),
+        scrolled_line_by_line.scrollback_position_and_length()
+    );
+    assert_eq!(scrolled_at_once.lines_above.len(), 10_000);
+    assert_eq!(scrolled_at_once.archived_line_count(), 2_491);
+    assert_eq!(scrolled_at_once.selection, scrolled_line_by_line.selection);
+}
+
+#[test]
+fn scroll_to_top_and_bottom_through_archived_wrapped_lines() {
+    let mut content = String::new();
+    for i in 0..12_000 {
+        write!(content, "line {} {}\r\n", i, "wrapped ".repeat(i % 4)).unwrap();
+    }
+    let create_grid = || {
+        let mut grid = create_grid_with_size_and_raw(5, 20, b"");
+        grid.scrollback_archive = ScrollbackArchive::new(100_000);
+        feed_bytes(&mut grid, content.as_bytes());
+        grid
+    };
+    let mut scrolled_at_once = create_grid();
+    let mut scrolled_line_by_line = create_grid();
+    assert!(scrolled_at_once.has_archived_lines());
+
+    scrolled_at_once.scroll_to_top();
+    scrolled_line_by_line.move_viewport_up(30_000);
+    assert_eq!(
+        viewport_texts(&scrolled_at_once),
+        viewport_texts(&scrolled_line_by_line)
+    );
+    assert_eq!(
+        scrolled_at_once
+            .pane_contents(true, None)
+            .lines_below_viewport,
+        scrolled_line_by_line
+            .pane_contents(true, None)
+            .lines_below_viewport
+    );
+    scrolled_at_once.move_viewport_down(3);
+    scrolled_line_by_line.move_viewport_down(3);
+    assert_eq!(
+        viewport_texts(&scrolled_at_once),
+        viewport_texts(&scrolled_line_by_line)
+    );
+
+    scrolled_at_once.reset_viewport();
+    scrolled_line_by_line.move_viewport_down(30_000);
+    assert_eq!(
+        viewport_texts(&scrolled_at_once),
+        viewport_texts(&scrolled_line_by_line)
+    );
+    assert_eq!(
+        scrolled_at_once.dump_screen(true),
+        scrolled_line_by_line.dump_screen(true)
+    );
+}
+
+#[test]
+fn copy_mode_moves_through_scrollback() {
+    let mut content = String::new();
+    for i in 0..30 {
+        write!(content, "line {}\r\n", i).unwrap();
+    }
+    let mut grid = create_grid_with_size_and_raw(5, 20, content.as_bytes());
+    grid.enter_copy_mode();
+    grid.copy_mode_move(CopyModeMotion::Up);
+    assert_eq!(copy_mode_cursor_position(&grid), (3, 0));
+    grid.copy_mode_move(CopyModeMotion::Top);
+    assert_eq!(copy_mode_cursor_position(&grid), (0, 0));
+    assert!(grid.is_scrolled);
+    grid.copy_mode_select(CopySelectionKind::Line);
+    grid.copy_mode_move(CopyModeMotion::Down);
+    assert_eq!(grid.get_selected_text(), Some("line 0\nline 1".to_owned()));
+    grid.copy_mode_move(CopyModeMotion::Bottom);
+    assert_eq!(copy_mode_cursor_position(&grid), (4, 0));
+    assert!(!grid.is_scrolled);
+    assert_eq!(
+        grid.get_selected_text().map(|text| text.lines().count()),
+        Some(30),
+        "the selection stays anchored to the text it started on"
+    );
+
+    // output moves the cursor along with its text
+    grid.copy_mode_select(CopySelectionKind::Line);
+    grid.copy_mode_move(CopyModeMotion::Up);
+    feed_bytes(&mut grid, b"line 30\r\n");
+    assert_eq!(copy_mode_cursor_position(&grid), (2, 0));
+}
+
+#[test]
+fn block_selection_with_wide_characters_and_trailing_whitespace() {
+    let mut grid = create_grid_with_size_and_raw(
+        5,
+        20,
+        "PID  CMD     \r\n1    漢字bash\r\n22   vim\r\n".as_bytes(),
+    );
+    grid.start_block_selection(&Position::new(0, 6));
+    grid.update_selection(&Position::new(2, 8));
+    grid.end_selection(&Position::new(2, 8));
+    assert!(grid.selection.is_block());
+    // the wide character starting before the block and the one ending after it are copied
+    // whole, and the selection is not padded with the whitespace of shorter lines
+    assert_eq!(grid.get_selected_text(), Some("MD\n漢字\nim".to_owned()));
+}
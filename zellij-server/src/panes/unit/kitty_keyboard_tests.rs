@@ -0,0 +1,171 @@
+use super::*;
+
+fn all_flags() -> KittyKeyboardFlags {
+    KittyKeyboardFlags::all()
+}
+
+fn encode(event: &str, flags: KittyKeyboardFlags) -> Option<String> {
+    KittyKeyEvent::parse(event.as_bytes())
+        .and_then(|event| event.encode(flags))
+        .map(|bytes| String::from_utf8(bytes).unwrap())
+}
+
+#[test]
+fn parse_key_event_with_all_fields() {
+    let event = KittyKeyEvent::parse(b"\x1b[97:65:1092;2:2;65u").unwrap();
+    assert_eq!(
+        event,
+        KittyKeyEvent {
+            key_code: 97,
+            shifted_key: Some(65),
+            base_layout_key: Some(1092),
+            modifiers: SHIFT,
+            event_type: KeyEventType::Repeat,
+            text: vec![65],
+            terminator: b'u',
+        }
+    );
+}
+
+#[test]
+fn parse_functional_key_events() {
+    let up_released = KittyKeyEvent::parse(b"\x1b[1;1:3A").unwrap();
+    assert_eq!(up_released.key_code, 1);
+    assert_eq!(up_released.event_type, KeyEventType::Release);
+    assert_eq!(up_released.terminator, b'A');
+    let up = KittyKeyEvent::parse(b"\x1b[A").unwrap();
+    assert_eq!(up.key_code, 1);
+    assert!(up.is_key_press());
+    let page_up = KittyKeyEvent::parse(b"\x1b[5;5~").unwrap();
+    assert_eq!(page_up.key_code, 5);
+    assert_eq!(page_up.modifiers, CTRL);
+    assert!(KittyKeyEvent::parse(b"abc").is_none());
+}
+
+#[test]
+fn releases_and_lone_modifiers_are_not_key_presses() {
+    assert!(KittyKeyEvent::parse(b"\x1b[97u").unwrap().is_key_press());
+    assert!(KittyKeyEvent::parse(b"\x1b[97;1:2u")
+        .unwrap()
+        .is_key_press());
+    assert!(!KittyKeyEvent::parse(b"\x1b[97;1:3u")
+        .unwrap()
+        .is_key_press());
+    assert!(!KittyKeyEvent::parse(b"\x1b[57441;2u")
+        .unwrap()
+        .is_key_press());
+}
+
+#[test]
+fn disambiguate_only_sends_text_keys_as_text() {
+    let flags = KittyKeyboardFlags::DISAMBIGUATE_ESCAPE_CODES;
+    assert_eq!(encode("\x1b[97;;97u", flags), Some("a".to_owned()));
+    assert_eq!(encode("\x1b[97:65;2;65u", flags), Some("A".to_owned()));
+    assert_eq!(encode("\x1b[49:33;2u", flags), Some("!".to_owned()));
+    assert_eq!(encode("\x1b[13u", flags), Some("\r".to_owned()));
+    assert_eq!(encode("\x1b[27u", flags), Some("\u{1b}[27u".to_owned()));
+    assert_eq!(encode("\x1b[97;5u", flags), Some("\u{1b}[97;5u".to_owned()));
+    assert_eq!(encode("\x1b[97;1:3u", flags), None, "no release events");
+    assert_eq!(encode("\x1b[57441;2u", flags), None, "no lone modifiers");
+    assert_eq!(encode("\x1b[1;1:2A", flags), Some("\u{1b}[A".to_owned()));
+    assert_eq!(encode("\x1b[1;3A", flags), Some("\u{1b}[1;3A".to_owned()));
+}
+
+#[test]
+fn report_event_types() {
+    let flags =
+        KittyKeyboardFlags::DISAMBIGUATE_ESCAPE_CODES | KittyKeyboardFlags::REPORT_EVENT_TYPES;
+    assert_eq!(
+        encode("\x1b[97;5:2u", flags),
+        Some("\u{1b}[97;5:2u".to_owned())
+    );
+    assert_eq!(
+        encode("\x1b[97;5:3u", flags),
+        Some("\u{1b}[97;5:3u".to_owned())
+    );
+    assert_eq!(
+        encode("\x1b[27;1:3u", flags),
+        Some("\u{1b}[27;1:3u".to_owned())
+    );
+    assert_eq!(
+        encode("\x1b[1;1:3A", flags),
+        Some("\u{1b}[1;1:3A".to_owned())
+    );
+    assert_eq!(
+        encode("\x1b[5;1:3~", flags),
+        Some("\u{1b}[5;1:3~".to_owned())
+    );
+    assert_eq!(
+        encode("\x1b[97;1:3u", flags),
+        None,
+        "text keys are still sent as text, without release events"
+    );
+}
+
+#[test]
+fn report_all_keys_with_alternates_and_text() {
+    assert_eq!(
+        encode("\x1b[97:65:1092;2;65u", all_flags()),
+        Some("\u{1b}[97:65:1092;2;65u".to_owned())
+    );
+    assert_eq!(
+        encode("\x1b[97::1092;;97u", all_flags()),
+        Some("\u{1b}[97::1092;;97u".to_owned())
+    );
+    assert_eq!(
+        encode("\x1b[97;1:3u", all_flags()),
+        Some("\u{1b}[97;1:3u".to_owned())
+    );
+    assert_eq!(
+        encode("\x1b[57441;2u", all_flags()),
+        Some("\u{1b}[57441;2u".to_owned())
+    );
+    assert_eq!(
+        encode("\x1b[13u", all_flags()),
+        Some("\u{1b}[13u".to_owned())
+    );
+    let without_alternates_and_text = KittyKeyboardFlags::DISAMBIGUATE_ESCAPE_CODES
+        | KittyKeyboardFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES;
+    assert_eq!(
+        encode("\x1b[97:65:1092;2;65u", without_alternates_and_text),
+        Some("\u{1b}[97;2u".to_owned())
+    );
+}
+
+#[test]
+fn keys_from_non_kitty_host_terminals() {
+    let shift_a = KittyKeyEvent::from_key(&KeyWithModifier::new(BareKey::Char('A'))).unwrap();
+    assert_eq!(shift_a.encode(all_flags()), Some(b"\x1b[97;2;65u".to_vec()));
+    assert_eq!(
+        shift_a.encode(KittyKeyboardFlags::DISAMBIGUATE_ESCAPE_CODES),
+        Some(b"A".to_vec())
+    );
+    let ctrl_left =
+        KittyKeyEvent::from_key(&KeyWithModifier::new(BareKey::Left).with_ctrl_modifier()).unwrap();
+    assert_eq!(
+        ctrl_left.encode(KittyKeyboardFlags::DISAMBIGUATE_ESCAPE_CODES),
+        Some(b"\x1b[1;5D".to_vec())
+    );
+}
+
+#[test]
+fn flags_stack() {
+    let mut stack = KittyKeyboardFlagsStack::default();
+    assert_eq!(stack.current(), KittyKeyboardFlags::NONE);
+    stack.push(1);
+    stack.push(3);
+    assert_eq!(stack.current().bits(), 3);
+    stack.set(8, 2);
+    assert_eq!(stack.current().bits(), 11);
+    stack.set(2, 3);
+    assert_eq!(stack.current().bits(), 9);
+    stack.pop(1);
+    assert_eq!(stack.current().bits(), 1);
+    stack.pop(5);
+    assert_eq!(stack.current(), KittyKeyboardFlags::NONE);
+    for flags in 0..(MAX_FLAGS_STACK_SIZE as u16 + 4) {
+        stack.push(flags % 32);
+    }
+    stack.pop(MAX_FLAGS_STACK_SIZE - 1);
+    assert_eq!(stack.current().bits(), 4, "the oldest entries are evicted");
+}
@@ -0,0 +1,161 @@
+use super::*;
+
+fn styled_row(i: usize) -> Row {
+    let styles: RcCharacterStyles = RESET_STYLES
+        .foreground(Some(AnsiCode::NamedColor(NAMED_COLORS[i % 16])))
+        .background(Some(AnsiCode::RgbCode((i as u8, 2, 3))))
+        .underline(Some(AnsiCode::Underline(Some(
+            AnsiStyledUnderline::Undercurl,
+        ))))
+        .link_anchor(Some(LinkAnchor::Start(i as u16)))
+        .into();
+    let mut columns: VecDeque<TerminalCharacter> = format!("row {} ", i)
+        .chars()
+        .map(|c| TerminalCharacter::new_styled(c, styles.clone()))
+        .collect();
+    columns.push_back(TerminalCharacter::new('文'));
+    columns.push_back(TerminalCharacter::new_singlewidth('\u{200b}'));
+    let mut row = Row::from_columns(columns).with_bg_color(Some(AnsiCode::ColorIndex(4)));
+    row.is_canonical = i % 3 != 0;
+    row.prompt_marks = vec![PromptMark {
+        kind: PromptMarkKind::CommandEnd(Some(i as i32)),
+        column: i,
+    }];
+    row
+}
+
+fn assert_rows_eq(left: &Row, right: &Row) {
+    assert_eq!(left.is_canonical, right.is_canonical);
+    assert_eq!(left.bg_color, right.bg_color);
+    assert_eq!(left.prompt_marks, right.prompt_marks);
+    assert_eq!(left.columns.len(), right.columns.len());
+    for (left, right) in left.columns.iter().zip(right.columns.iter()) {
+        assert_eq!(left.character, right.character);
+        assert_eq!(left.styles, right.styles);
+        assert_eq!(left.width(), right.width());
+    }
+}
+
+#[test]
+fn archived_rows_keep_their_contents_and_styles() {
+    let mut archive = ScrollbackArchive::new(10_000);
+    for i in 0..2_500 {
+        archive.push(styled_row(i));
+    }
+    assert_eq!(archive.len(), 2_500);
+    assert_eq!(archive.chunks.len(), 2);
+    let rows: Vec<Row> = archive.rows_from(0).collect();
+    assert_eq!(rows.len(), 2_500);
+    for (i, row) in rows.iter().enumerate() {
+        assert_rows_eq(row, &styled_row(i));
+    }
+}
+
+#[test]
+fn rows_from_skips_rows() {
+    let mut archive = ScrollbackArchive::new(10_000);
+    for i in 0..2_500 {
+        archive.push(styled_row(i));
+    }
+    let rows: Vec<Row> = archive.rows_from(1_999).collect();
+    assert_eq!(rows.len(), 501);
+    assert_rows_eq(&rows[0], &styled_row(1_999));
+    assert_eq!(archive.rows_from(3_000).count(), 0);
+}
+
+#[test]
+fn pop_back_returns_rows_newest_first() {
+    let mut archive = ScrollbackArchive::new(10_000);
+    for i in 0..1_500 {
+        archive.push(styled_row(i));
+    }
+    for i in (0..1_500).rev() {
+        assert_rows_eq(&archive.pop_back().unwrap(), &styled_row(i));
+    }
+    assert!(archive.pop_back().is_none());
+    assert!(archive.is_empty());
+}
+
+#[test]
+fn oldest_chunks_are_dropped_when_exceeding_max_rows() {
+    let mut archive = ScrollbackArchive::new(2_000);
+    for i in 0..3_500 {
+        archive.push(styled_row(i));
+    }
+    assert_eq!(archive.len(), 1_500);
+    assert_rows_eq(&archive.rows_from(0).next().unwrap(), &styled_row(2_000));
+}
+
+#[test]
+fn appended_archive_is_moved_in_reverse_order_without_unpacking_its_chunks() {
+    let mut archive = ScrollbackArchive::new(usize::MAX);
+    for i in (2_500..3_100).rev() {
+        archive.push(styled_row(i));
+    }
+    let mut other = ScrollbackArchive::new(10_000);
+    for i in 0..2_500 {
+        other.push(styled_row(i));
+    }
+    let other_chunk = other.chunks[0].chunk.clone();
+    archive.append_reversed(&mut other);
+    assert!(other.is_empty());
+    assert_eq!(archive.len(), 3_100);
+    assert!(
+        archive
+            .chunks
+            .iter()
+            .any(|entry| Rc::ptr_eq(&entry.chunk, &other_chunk)),
+        "packed chunks are moved as they are"
+    );
+    let expected_widths: Vec<usize> = (0..3_100).rev().map(|i| styled_row(i).width()).collect();
+    assert_eq!(archive.row_widths().collect::<Vec<_>>(), expected_widths);
+    let rows: Vec<Row> = archive.rows_from(0).collect();
+    assert_eq!(rows.len(), 3_100);
+    for (row, i) in rows.iter().zip((0..3_100).rev()) {
+        assert_rows_eq(row, &styled_row(i));
+    }
+    for i in 0..3_100 {
+        assert_rows_eq(&archive.pop_back().unwrap(), &styled_row(i));
+    }
+}
+
+#[test]
+fn spill_file_space_is_reused() {
+    let mut spill_file = SpillFile::create().unwrap();
+    let first = spill_file.store(&[1; 10]).unwrap();
+    let second = spill_file.store(&[2; 10]).unwrap();
+    let third = spill_file.store(&[3; 10]).unwrap();
+    assert_eq!(spill_file.len, 30);
+    spill_file.release(first).unwrap();
+    spill_file.release(second).unwrap();
+    assert_eq!(spill_file.free_extents.len(), 1);
+    let fourth = spill_file.store(&[4; 15]).unwrap();
+    assert_eq!(fourth.offset, 0);
+    assert_eq!(read_extent(&spill_file.file, third).unwrap(), vec![3; 10]);
+    assert_eq!(read_extent(&spill_file.file, fourth).unwrap(), vec![4; 15]);
+    spill_file.release(third).unwrap();
+    assert_eq!(spill_file.len, 15);
+    assert_eq!(spill_file.free_extents.len(), 0);
+}
+
+#[test]
+fn chunks_are_written_to_the_spill_file_in_the_background() {
+    let mut archive = ScrollbackArchive::new(10_000);
+    for i in 0..1_000 {
+        archive.push(styled_row(i));
+    }
+    let chunk = archive.chunks[0].chunk.clone();
+    let mut waited = 0;
+    while matches!(*lock_chunk_state(&chunk.state), ChunkState::Pending(_)) {
+        assert!(waited < 500, "chunk was not written to the spill file");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        waited += 1;
+    }
+    assert!(matches!(
+        *lock_chunk_state(&chunk.state),
+        ChunkState::Stored(_)
+    ));
+    let rows = chunk.load().unwrap();
+    assert_eq!(rows.len(), 1_000);
+    assert_rows_eq(&rows[999], &styled_row(999));
+}
@@ -0,0 +1,202 @@
+use super::*;
+use crate::panes::terminal_character::{RcCharacterStyles, TerminalCharacter, RESET_STYLES};
+use tempfile::tempdir;
+
+fn row(text: &str) -> Row {
+    Row::from_columns(text.chars().map(TerminalCharacter::new).collect())
+}
+
+fn linked_row(text: &str, link_index: u16) -> Row {
+    let styles: RcCharacterStyles = RESET_STYLES
+        .link_anchor(Some(LinkAnchor::Start(link_index)))
+        .into();
+    Row::from_columns(
+        text.chars()
+            .map(|c| TerminalCharacter::new_styled(c, styles.clone()))
+            .collect(),
+    )
+}
+
+fn row_text(row: &Row) -> String {
+    row.columns.iter().map(|c| c.character).collect()
+}
+
+fn record(history_start: u64, first_row: u64, rows: &[Row], link_handler: &LinkHandler) -> Vec<u8> {
+    encode_record(
+        history_start,
+        first_row,
+        rows.iter().map(Cow::Borrowed),
+        link_handler,
+    )
+    .unwrap()
+}
+
+fn write_file(path: &Path, records: &[Vec<u8>]) {
+    let mut file = File::create(path).unwrap();
+    for record in records {
+        file.write_all(record).unwrap();
+    }
+}
+
+#[test]
+fn persistence_only_writes_new_rows() {
+    let mut persistence = ScrollbackPersistence::default();
+    assert!(!persistence.is_persisted());
+    assert_eq!(persistence.update(0, 100), Some((true, 0)));
+    assert!(persistence.is_persisted());
+    assert_eq!(persistence.update(0, 100), None);
+    assert_eq!(persistence.update(0, 150), Some((false, 100)));
+    // rows pulled back into the viewport are rewritten once they're scrolled out again
+    assert_eq!(persistence.update(0, 140), Some((false, 140)));
+    assert_eq!(persistence.update(0, 145), Some((false, 140)));
+    // only the history start moved
+    assert_eq!(persistence.update(10, 145), Some((false, 145)));
+    persistence.reset();
+    assert!(!persistence.is_persisted());
+    assert_eq!(persistence.update(0, 5), Some((true, 0)));
+}
+
+#[test]
+fn persistence_rewrites_file_once_mostly_stale() {
+    let mut persistence = ScrollbackPersistence::default();
+    assert_eq!(persistence.update(0, 1_000), Some((true, 0)));
+    let mut history_start = 0;
+    let mut truncated_at = None;
+    for i in 1..100 {
+        history_start += 1_000;
+        match persistence.update(history_start, history_start + 1_000) {
+            Some((true, first_row)) => {
+                assert_eq!(first_row, history_start);
+                truncated_at = Some(i);
+                break;
+            },
+            Some((false, first_row)) => assert_eq!(first_row, history_start),
+            None => panic!("history changed"),
+        }
+    }
+    // 1_000 rows of history and 10_000 of slack
+    assert_eq!(truncated_at, Some(13));
+}
+
+#[test]
+fn scrollback_file_roundtrip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(scrollback_file_name(1));
+    let mut link_handler = LinkHandler::new();
+    let link_index = link_handler.add_link(Link {
+        id: Some("an-id".to_owned()),
+        uri: "https://zellij.dev".to_owned(),
+    });
+    let rows = vec![row("first"), linked_row("link", link_index), row("third")];
+    write_file(&path, &[record(0, 0, &rows, &link_handler)]);
+    let persisted_scrollback = read_scrollback_file(&path).unwrap();
+    let texts: Vec<String> = persisted_scrollback.rows.iter().map(row_text).collect();
+    assert_eq!(texts, vec!["first", "link", "third"]);
+    assert_eq!(
+        persisted_scrollback.rows[1].columns[0].styles.link_anchor,
+        Some(LinkAnchor::Start(link_index))
+    );
+    assert_eq!(persisted_scrollback.links.len(), 1);
+    let link = &persisted_scrollback.links[&link_index];
+    assert_eq!(link.uri, "https://zellij.dev");
+    assert_eq!(link.id.as_deref(), Some("an-id"));
+}
+
+#[test]
+fn later_records_replace_rows_and_drop_history() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(scrollback_file_name(1));
+    let link_handler = LinkHandler::new();
+    write_file(
+        &path,
+        &[
+            record(0, 0, &[row("0"), row("1"), row("2")], &link_handler),
+            // row 2 changed after being written
+            record(0, 2, &[row("2 changed"), row("3")], &link_handler),
+            record(2, 4, &[row("4")], &link_handler),
+        ],
+    );
+    let persisted_scrollback = read_scrollback_file(&path).unwrap();
+    let texts: Vec<String> = persisted_scrollback.rows.iter().map(row_text).collect();
+    assert_eq!(texts, vec!["2 changed", "3", "4"]);
+}
+
+#[test]
+fn truncated_last_record_is_ignored() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(scrollback_file_name(1));
+    let link_handler = LinkHandler::new();
+    let mut last_record = record(0, 1, &[row("1")], &link_handler);
+    last_record.truncate(last_record.len() - 2);
+    write_file(
+        &path,
+        &[record(0, 0, &[row("0")], &link_handler), last_record],
+    );
+    let persisted_scrollback = read_scrollback_file(&path).unwrap();
+    let texts: Vec<String> = persisted_scrollback.rows.iter().map(row_text).collect();
+    assert_eq!(texts, vec!["0"]);
+}
+
+#[test]
+fn corrupted_string_length_is_an_error() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(scrollback_file_name(1));
+    let mut encoder = DeflateEncoder::new(vec![], Compression::fast());
+    encoder.write_u64::<LittleEndian>(0).unwrap();
+    encoder.write_u64::<LittleEndian>(0).unwrap();
+    encoder.write_u8(0).unwrap();
+    encoder.write_u32::<LittleEndian>(1).unwrap();
+    encoder.write_u16::<LittleEndian>(0).unwrap();
+    encoder.write_u32::<LittleEndian>(u32::MAX).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let mut corrupted_record = vec![];
+    corrupted_record
+        .write_u32::<LittleEndian>(compressed.len() as u32)
+        .unwrap();
+    corrupted_record.extend_from_slice(&compressed);
+    write_file(&path, &[corrupted_record]);
+    let error = read_scrollback_file(&path).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn write_scrollback_files_appends_truncates_and_deletes_unused_files() {
+    let dir = tempdir().unwrap();
+    let link_handler = LinkHandler::new();
+    let record_for = |truncate, first_row, text| ScrollbackRecord {
+        truncate,
+        bytes: record(0, first_row, &[row(text)], &link_handler),
+    };
+    let files_in_use: BTreeSet<String> = [scrollback_file_name(1), scrollback_file_name(2)].into();
+    write_scrollback_files(
+        dir.path(),
+        vec![
+            (scrollback_file_name(1), record_for(true, 0, "a")),
+            (scrollback_file_name(2), record_for(true, 0, "b")),
+        ],
+        &files_in_use,
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("session-layout.kdl"), "").unwrap();
+    write_scrollback_files(
+        dir.path(),
+        vec![
+            (scrollback_file_name(1), record_for(false, 1, "a2")),
+            (scrollback_file_name(3), record_for(true, 0, "c")),
+        ],
+        &[scrollback_file_name(1), scrollback_file_name(3)].into(),
+    )
+    .unwrap();
+    let read_texts = |terminal_id| -> Vec<String> {
+        read_scrollback_file(&dir.path().join(scrollback_file_name(terminal_id)))
+            .unwrap()
+            .rows
+            .iter()
+            .map(row_text)
+            .collect()
+    };
+    assert_eq!(read_texts(1), vec!["a", "a2"]);
+    assert_eq!(read_texts(3), vec!["c"]);
+    assert!(!dir.path().join(scrollback_file_name(2)).exists());
+    assert!(dir.path().join("session-layout.kdl").exists());
+}
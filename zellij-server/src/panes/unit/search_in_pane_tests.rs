@@ -424,3 +424,80 @@ pub fn searching_whole_word_case_insensitive() {
         format!("{:?}", terminal_pane.grid)
     );
 }
+
+#[test]
+pub fn searching_with_regex() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("qu[a-z]m");
+    // Not a regex yet, so nothing is found
+    assert!(terminal_pane.grid.search_results.selections.is_empty());
+
+    terminal_pane.toggle_search_regex();
+    assert_snapshot!(
+        "grid_copy_quam_highlighted",
+        format!("{:?}", terminal_pane.grid)
+    );
+
+    terminal_pane.toggle_search_case_sensitivity();
+    assert_snapshot!(
+        "grid_copy_quam_insensitive_highlighted",
+        format!("{:?}", terminal_pane.grid)
+    );
+
+    terminal_pane.toggle_search_case_sensitivity();
+    terminal_pane.toggle_search_regex();
+    assert!(terminal_pane.grid.search_results.selections.is_empty());
+}
+
+#[test]
+pub fn searching_with_regex_whole_word() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("qu.m");
+    terminal_pane.toggle_search_regex();
+    terminal_pane.toggle_search_whole_words();
+    assert_snapshot!(
+        "grid_copy_quam_whole_word_only",
+        format!("{:?}", terminal_pane.grid)
+    );
+}
+
+#[test]
+pub fn searching_with_regex_across_line_wrap() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term(r"aliquam\s+sem\s+fring\w+");
+    terminal_pane.toggle_search_regex();
+    // Spread across two lines
+    terminal_pane.grid.change_size(30, 60);
+    assert_snapshot!(
+        "grid_copy_multiline_highlighted",
+        format!("{:?}", terminal_pane.grid)
+    );
+
+    // Spread across 4 lines
+    terminal_pane.grid.change_size(40, 4);
+    assert_snapshot!(
+        "grid_copy_multiline_highlighted_narrow",
+        format!("{:?}", terminal_pane.grid)
+    );
+}
+
+#[test]
+pub fn searching_with_regex_capture_groups() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("(tor)tor");
+    terminal_pane.toggle_search_regex();
+    // Only the captured part of each match gets highlighted
+    assert_snapshot!(format!("{:?}", terminal_pane.grid));
+    terminal_pane.search_up();
+    let active = terminal_pane.grid.search_results.active.unwrap();
+    assert_eq!(active.end.column() - active.start.column(), 3);
+}
+
+#[test]
+pub fn searching_with_invalid_regex() {
+    let mut terminal_pane = create_pane();
+    terminal_pane.update_search_term("qu[am");
+    terminal_pane.toggle_search_regex();
+    assert!(terminal_pane.grid.search_results.compiled_regex.is_none());
+    assert!(terminal_pane.grid.search_results.selections.is_empty());
+}
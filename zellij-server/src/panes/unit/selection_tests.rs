@@ -45,6 +45,7 @@ fn contains() {
         active: false,
         last_added_word_position: None,
         last_added_line_index: None,
+        is_block: false,
     };
 
     let test_cases = vec![
@@ -97,6 +98,7 @@ fn sorted() {
         active: false,
         last_added_word_position: None,
         last_added_line_index: None,
+        is_block: false,
     };
     let sorted_selection = selection.sorted();
     assert_eq!(selection.start, sorted_selection.start);
@@ -108,6 +110,7 @@ fn sorted() {
         active: false,
         last_added_word_position: None,
         last_added_line_index: None,
+        is_block: false,
     };
     let sorted_selection = selection.sorted();
     assert_eq!(selection.end, sorted_selection.start);
@@ -122,6 +125,7 @@ fn line_indices() {
         active: false,
         last_added_word_position: None,
         last_added_line_index: None,
+        is_block: false,
     };
 
     assert_eq!(selection.line_indices(), (1..=10))
@@ -137,6 +141,7 @@ fn move_up_inactive() {
         active: false,
         last_added_word_position: None,
         last_added_line_index: None,
+        is_block: false,
     };
 
     inactive_selection.move_up(2);
@@ -157,6 +162,7 @@ fn move_up_active() {
         active: true,
         last_added_word_position: None,
         last_added_line_index: None,
+        is_block: false,
     };
 
     inactive_selection.move_up(2);
@@ -174,6 +180,7 @@ fn move_down_inactive() {
         active: false,
         last_added_word_position: None,
         last_added_line_index: None,
+        is_block: false,
     };
 
     inactive_selection.move_down(2);
@@ -194,6 +201,7 @@ fn move_down_active() {
         active: true,
         last_added_word_position: None,
         last_added_line_index: None,
+        is_block: false,
     };
 
     inactive_selection.move_down(2);
@@ -213,6 +221,7 @@ fn add_word_to_position_extend_line_above() {
         active: true,
         last_added_word_position: Some((last_word_start, last_word_end)),
         last_added_line_index: None,
+        is_block: false,
     };
     let word_start = Position::new(9, 5);
     let word_end = Position::new(9, 6);
@@ -234,6 +243,7 @@ fn add_word_to_position_extend_line_below() {
         active: true,
         last_added_word_position: Some((last_word_start, last_word_end)),
         last_added_line_index: None,
+        is_block: false,
     };
     let word_start = Position::new(21, 5);
     let word_end = Position::new(21, 6);
@@ -255,6 +265,7 @@ fn add_word_to_position_reduce_from_above() {
         active: true,
         last_added_word_position: Some((last_word_start, last_word_end)),
         last_added_line_index: None,
+        is_block: false,
     };
     let word_start = Position::new(11, 5);
     let word_end = Position::new(11, 6);
@@ -276,6 +287,7 @@ fn add_word_to_position_reduce_from_below() {
         active: true,
         last_added_word_position: Some((last_word_start, last_word_end)),
         last_added_line_index: None,
+        is_block: false,
     };
     let word_start = Position::new(19, 5);
     let word_end = Position::new(19, 6);
@@ -297,6 +309,7 @@ fn add_word_to_position_extend_right() {
         active: true,
         last_added_word_position: Some((last_word_start, last_word_end)),
         last_added_line_index: None,
+        is_block: false,
     };
     let word_start = Position::new(20, 21);
     let word_end = Position::new(20, 23);
@@ -318,6 +331,7 @@ fn add_word_to_position_extend_left() {
         active: true,
         last_added_word_position: Some((last_word_start, last_word_end)),
         last_added_line_index: None,
+        is_block: false,
     };
     let word_start = Position::new(10, 5);
     let word_end = Position::new(10, 9);
@@ -339,6 +353,7 @@ fn add_word_to_position_reduce_from_left() {
         active: true,
         last_added_word_position: Some((last_word_start, last_word_end)),
         last_added_line_index: None,
+        is_block: false,
     };
     let word_start = Position::new(10, 20);
     let word_end = Position::new(10, 30);
@@ -360,6 +375,7 @@ fn add_word_to_position_reduce_from_right() {
         active: true,
         last_added_word_position: Some((last_word_start, last_word_end)),
         last_added_line_index: None,
+        is_block: false,
     };
     let word_start = Position::new(20, 5);
     let word_end = Position::new(20, 10);
@@ -380,6 +396,7 @@ fn add_line_to_position_extend_upwards() {
         active: true,
         last_added_word_position: None,
         last_added_line_index: Some(last_added_line_index),
+        is_block: false,
     };
     let line_index_to_add = 9;
     let last_index_in_line = 21;
@@ -400,6 +417,7 @@ fn add_line_to_position_extend_downwards() {
         active: true,
         last_added_word_position: None,
         last_added_line_index: Some(last_added_line_index),
+        is_block: false,
     };
     let line_index_to_add = 21;
     let last_index_in_line = 21;
@@ -423,6 +441,7 @@ fn add_line_to_position_reduce_from_below() {
         active: true,
         last_added_word_position: None,
         last_added_line_index: Some(last_added_line_index),
+        is_block: false,
     };
     let line_index_to_add = 19;
     let last_index_in_line = 21;
@@ -446,6 +465,7 @@ fn add_line_to_position_reduce_from_above() {
         active: true,
         last_added_word_position: None,
         last_added_line_index: Some(last_added_line_index),
+        is_block: false,
     };
     let line_index_to_add = 9;
     let last_index_in_line = 21;
@@ -475,6 +495,7 @@ fn finalize_preserves_positions() {
         active: true,
         last_added_word_position: Some((start, end)),
         last_added_line_index: None,
+        is_block: false,
     };
 
     selection.finalize();
@@ -531,3 +552,34 @@ fn set_start_and_end_without_finalize_only_moves_start() {
     assert_eq!(selection.start, Position::new(13, 5));
     assert_eq!(selection.end, word_end);
 }
+
+#[test]
+fn block_selection_contains_only_its_columns() {
+    let mut selection = Selection::default();
+    selection.select(Position::new(1, 5), Position::new(3, 2), true);
+
+    assert!(selection.is_block());
+    assert_eq!(selection.block_columns(), 2..5);
+    assert!(selection.contains(2, 2));
+    assert!(selection.contains(3, 4));
+    assert!(!selection.contains(2, 5));
+    assert!(!selection.contains(2, 1));
+    assert!(!selection.contains(4, 3));
+
+    selection.start(Position::new(1, 1));
+    assert!(!selection.is_block());
+}
+
+#[test]
+fn block_selection_contains_wide_characters_it_overlaps() {
+    let mut selection = Selection::default();
+    selection.start_block(Position::new(1, 3));
+    selection.to(Position::new(2, 6));
+
+    assert!(selection.is_block());
+    assert!(selection.contains_character(1, 2, 2));
+    assert!(selection.contains_character(2, 5, 2));
+    assert!(!selection.contains_character(2, 1, 2));
+    assert!(!selection.contains_character(2, 6, 2));
+    assert!(!selection.contains_character(1, 2, 1));
+}
@@ -1,13 +1,16 @@
 use super::super::TerminalPane;
+use crate::output::Output;
+use crate::panes::kitty_graphics::KittyGraphicsHost;
 use crate::panes::sixel::SixelImageStore;
 use crate::panes::LinkHandler;
 use crate::tab::Pane;
 use insta::assert_snapshot;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use zellij_utils::{
     data::{Palette, Style},
+    input::command::{RestartPolicy, RunCommand, RESTART_STABILITY_THRESHOLD},
     pane_size::{Offset, PaneGeom, SizeInPixels},
     position::Position,
 };
@@ -873,3 +876,364 @@ pub fn frameless_pane_position_is_on_frame() {
     assert!(!terminal_pane.position_is_on_frame(&Position::new(30, 130)));
     assert!(!terminal_pane.position_is_on_frame(&Position::new(30, 131)));
 }
+
+fn make_terminal_pane_for_kitty_graphics(
+    sixel_image_store: Rc<RefCell<SixelImageStore>>,
+) -> TerminalPane {
+    let mut fake_win_size = PaneGeom::default();
+    fake_win_size.cols.set_inner(121);
+    fake_win_size.rows.set_inner(20);
+    let terminal_emulator_colors = Rc::new(RefCell::new(Palette::default()));
+    let terminal_emulator_color_codes = Rc::new(RefCell::new(HashMap::new()));
+    let character_cell_size = Rc::new(RefCell::new(Some(SizeInPixels {
+        width: 8,
+        height: 21,
+    })));
+    TerminalPane::new(
+        1,
+        fake_win_size,
+        Style::default(),
+        0,
+        String::new(),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        character_cell_size,
+        sixel_image_store,
+        terminal_emulator_colors,
+        terminal_emulator_color_codes,
+        None,
+        None,
+        false,
+        true,
+        true,
+        true,
+        false,
+        None,
+    )
+}
+
+fn kitty_graphics_command(control_data: &str, payload: &[u8]) -> Vec<u8> {
+    format!(
+        "\u{1b}_G{};{}\u{1b}\\",
+        control_data,
+        base64::encode(payload)
+    )
+    .into_bytes()
+}
+
+fn red_rgba_pixels(width: usize, height: usize) -> Vec<u8> {
+    [255, 0, 0, 255].repeat(width * height)
+}
+
+#[test]
+pub fn kitty_graphics_image_inside_terminal_pane() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store.clone());
+    terminal_pane.handle_pty_bytes(kitty_graphics_command(
+        "a=T,f=32,s=16,v=42",
+        &red_rgba_pixels(16, 42),
+    ));
+    terminal_pane.handle_pty_bytes(Vec::from("text after image".as_bytes()));
+    assert_eq!(sixel_image_store.borrow().image_count(), 1);
+    // the image has no id, so we should not respond to it
+    assert!(terminal_pane.grid.pending_messages_to_pty.is_empty());
+    assert_snapshot!(format!("{:?}", terminal_pane.grid));
+}
+
+#[test]
+pub fn kitty_graphics_chunked_transmission() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store.clone());
+    let pixels = red_rgba_pixels(16, 42);
+    let (first_chunk, second_chunk) = pixels.split_at(pixels.len() / 2);
+    terminal_pane.handle_pty_bytes(kitty_graphics_command(
+        "a=T,f=32,s=16,v=42,m=1",
+        first_chunk,
+    ));
+    assert_eq!(
+        sixel_image_store.borrow().image_count(),
+        0,
+        "image not displayed before its last chunk"
+    );
+    terminal_pane.handle_pty_bytes(kitty_graphics_command("m=0", second_chunk));
+    assert_eq!(
+        sixel_image_store.borrow().image_count(),
+        1,
+        "image displayed after its last chunk"
+    );
+}
+
+#[test]
+pub fn kitty_graphics_display_and_delete_stored_image() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store.clone());
+    terminal_pane.handle_pty_bytes(kitty_graphics_command(
+        "a=t,f=32,s=16,v=42,i=7",
+        &red_rgba_pixels(16, 42),
+    ));
+    assert_eq!(
+        terminal_pane
+            .grid
+            .pending_messages_to_pty
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![Vec::from("\u{1b}_Gi=7;OK\u{1b}\\".as_bytes())],
+        "transmission acknowledged"
+    );
+    assert_eq!(
+        sixel_image_store.borrow().image_count(),
+        0,
+        "transmitted image is not displayed"
+    );
+    terminal_pane.handle_pty_bytes(kitty_graphics_command("a=p,i=7,q=1", &[]));
+    terminal_pane.handle_pty_bytes(kitty_graphics_command("a=p,i=7,q=1", &[]));
+    assert_eq!(
+        sixel_image_store.borrow().image_count(),
+        2,
+        "stored image displayed twice"
+    );
+    assert!(terminal_pane.grid.pending_messages_to_pty.is_empty());
+    terminal_pane.handle_pty_bytes(kitty_graphics_command("a=d,d=i,i=7", &[]));
+    terminal_pane.render(None).unwrap(); // images are reaped when rendering
+    assert_eq!(
+        sixel_image_store.borrow().image_count(),
+        0,
+        "all placements deleted"
+    );
+    terminal_pane.handle_pty_bytes(kitty_graphics_command("a=p,i=7", &[]));
+    assert_eq!(
+        sixel_image_store.borrow().image_count(),
+        1,
+        "lowercase delete keeps the image data"
+    );
+    terminal_pane.handle_pty_bytes(kitty_graphics_command("a=d,d=I,i=7,q=2", &[]));
+    terminal_pane.handle_pty_bytes(kitty_graphics_command("a=p,i=7", &[]));
+    assert_eq!(
+        terminal_pane.grid.pending_messages_to_pty.last(),
+        Some(&Vec::from(
+            "\u{1b}_Gi=7;ENOENT:no such image\u{1b}\\".as_bytes()
+        )),
+        "uppercase delete frees the image data"
+    );
+}
+
+#[test]
+pub fn kitty_graphics_query() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store.clone());
+    terminal_pane.handle_pty_bytes(kitty_graphics_command("a=q,f=24,s=1,v=1,i=31", &[0, 0, 0]));
+    assert_eq!(
+        terminal_pane.grid.pending_messages_to_pty,
+        vec![Vec::from("\u{1b}_Gi=31;OK\u{1b}\\".as_bytes())]
+    );
+    assert_eq!(sixel_image_store.borrow().image_count(), 0);
+    terminal_pane.handle_pty_bytes(kitty_graphics_command("a=p,i=31", &[]));
+    assert_eq!(
+        terminal_pane.grid.pending_messages_to_pty.last(),
+        Some(&Vec::from(
+            "\u{1b}_Gi=31;ENOENT:no such image\u{1b}\\".as_bytes()
+        )),
+        "queried images are not stored"
+    );
+}
+
+#[test]
+pub fn kitty_graphics_oversized_image_is_rejected() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store.clone());
+    terminal_pane.handle_pty_bytes(kitty_graphics_command(
+        "a=T,f=32,s=100000,v=100000,i=9",
+        &red_rgba_pixels(1, 1),
+    ));
+    assert_eq!(
+        terminal_pane.grid.pending_messages_to_pty,
+        vec![Vec::from(
+            "\u{1b}_Gi=9;EFBIG:image is too large\u{1b}\\".as_bytes()
+        )]
+    );
+    assert_eq!(sixel_image_store.borrow().image_count(), 0);
+}
+
+#[test]
+pub fn kitty_graphics_oversized_placement_is_rejected() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store.clone());
+    terminal_pane.handle_pty_bytes(kitty_graphics_command(
+        "a=t,f=32,s=16,v=42,i=10,q=1",
+        &red_rgba_pixels(16, 42),
+    ));
+    terminal_pane.handle_pty_bytes(kitty_graphics_command(
+        "a=p,i=10,c=4000000000,r=4000000000",
+        &[],
+    ));
+    assert_eq!(
+        terminal_pane.grid.pending_messages_to_pty,
+        vec![Vec::from(
+            "\u{1b}_Gi=10;EFBIG:image is too large\u{1b}\\".as_bytes()
+        )]
+    );
+    assert_eq!(sixel_image_store.borrow().image_count(), 0);
+}
+
+fn render_to_clients_with_and_without_kitty_graphics(
+    terminal_pane: &mut TerminalPane,
+    sixel_image_store: Rc<RefCell<SixelImageStore>>,
+    kitty_graphics_host: &mut KittyGraphicsHost,
+) -> (String, String) {
+    let (kitty_client, sixel_client) = (1, 2);
+    let character_cell_size = Rc::new(RefCell::new(Some(SizeInPixels {
+        width: 8,
+        height: 21,
+    })));
+    let mut output = Output::new(sixel_image_store, character_cell_size, true, true);
+    output
+        .kitty_graphics_hosts
+        .borrow_mut()
+        .insert(kitty_client, std::mem::take(kitty_graphics_host));
+    output.add_clients(
+        &HashSet::from([kitty_client, sixel_client]),
+        Rc::new(RefCell::new(LinkHandler::new())),
+        None,
+    );
+    let (character_chunks, _, sixel_chunks) = terminal_pane.render(None).unwrap().unwrap();
+    for client_id in [kitty_client, sixel_client] {
+        output
+            .add_character_chunks_to_client(client_id, character_chunks.clone(), None)
+            .unwrap();
+        output.add_sixel_image_chunks_to_client(client_id, sixel_chunks.clone(), None);
+    }
+    let mut serialized = output.serialize().unwrap();
+    *kitty_graphics_host = output
+        .kitty_graphics_hosts
+        .borrow_mut()
+        .remove(&kitty_client)
+        .unwrap();
+    (
+        serialized.remove(&kitty_client).unwrap(),
+        serialized.remove(&sixel_client).unwrap(),
+    )
+}
+
+#[test]
+pub fn kitty_graphics_are_sent_as_they_are_to_hosts_that_support_them() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store.clone());
+    let mut kitty_graphics_host = KittyGraphicsHost::default();
+    // two cells wide and two rows tall
+    terminal_pane.handle_pty_bytes(kitty_graphics_command(
+        "a=T,f=32,s=16,v=42",
+        &red_rgba_pixels(16, 42),
+    ));
+    let (kitty_output, sixel_output) = render_to_clients_with_and_without_kitty_graphics(
+        &mut terminal_pane,
+        sixel_image_store.clone(),
+        &mut kitty_graphics_host,
+    );
+    let first_row = format!(
+        "\u{1b}[1;1H\u{1b}[m\u{1b}_Ga=T,f=32,s=16,v=21,i=1,C=1,q=2,m=0;{}\u{1b}\\",
+        base64::encode(red_rgba_pixels(16, 21))
+    );
+    let second_row = format!(
+        "\u{1b}[2;1H\u{1b}[m\u{1b}_Ga=T,f=32,s=16,v=21,i=65537,C=1,q=2,m=0;{}\u{1b}\\",
+        base64::encode(red_rgba_pixels(16, 21))
+    );
+    assert!(kitty_output.contains(&first_row), "{:?}", kitty_output);
+    assert!(kitty_output.contains(&second_row), "{:?}", kitty_output);
+    assert!(
+        !kitty_output.contains("\u{1b}P"),
+        "no sixel for kitty hosts"
+    );
+    assert!(sixel_output.contains("\u{1b}P"), "sixel for other hosts");
+    assert!(!sixel_output.contains("\u{1b}_G"));
+
+    // text written over the first row of the image erases it, like it does with sixel
+    terminal_pane.handle_pty_bytes(Vec::from("\u{1b}[Hab".as_bytes()));
+    let (kitty_output, _) = render_to_clients_with_and_without_kitty_graphics(
+        &mut terminal_pane,
+        sixel_image_store.clone(),
+        &mut kitty_graphics_host,
+    );
+    assert!(
+        kitty_output.contains("\u{1b}_Ga=d,d=I,i=1,q=2\u{1b}\\"),
+        "{:?}",
+        kitty_output
+    );
+    assert!(
+        !kitty_output.contains("i=65537"),
+        "the second row is untouched"
+    );
+    assert!(
+        kitty_output.contains("\u{1b}_Ga=T,f=32,s=16,v=21,i=1,"),
+        "the rest of the first row is placed again"
+    );
+}
+
+#[test]
+pub fn kitty_graphics_file_transmission_honours_size_and_offset() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store.clone());
+    let image_file = tempfile::NamedTempFile::new().unwrap();
+    let mut file_contents = vec![0; 10];
+    file_contents.extend(red_rgba_pixels(16, 42));
+    file_contents.extend(vec![0; 10]);
+    std::fs::write(image_file.path(), &file_contents).unwrap();
+    terminal_pane.handle_pty_bytes(kitty_graphics_command(
+        &format!("a=T,t=f,f=32,s=16,v=42,i=11,O=10,S={}", 16 * 42 * 4),
+        image_file.path().to_string_lossy().as_bytes(),
+    ));
+    assert_eq!(
+        terminal_pane.grid.pending_messages_to_pty,
+        vec![Vec::from("\u{1b}_Gi=11;OK\u{1b}\\".as_bytes())]
+    );
+    assert_eq!(sixel_image_store.borrow().image_count(), 1);
+}
+
+#[test]
+pub fn kitty_graphics_file_transmission_requires_regular_file() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store.clone());
+    terminal_pane.handle_pty_bytes(kitty_graphics_command(
+        "a=T,t=f,f=32,s=16,v=42,i=12",
+        b"/dev/zero",
+    ));
+    assert_eq!(
+        terminal_pane.grid.pending_messages_to_pty,
+        vec![Vec::from(
+            "\u{1b}_Gi=12;EBADF:/dev/zero is not a regular file\u{1b}\\".as_bytes()
+        )]
+    );
+    assert_eq!(sixel_image_store.borrow().image_count(), 0);
+}
+
+#[test]
+pub fn restart_count_is_reset_after_command_ran_stably() {
+    let sixel_image_store = Rc::new(RefCell::new(SixelImageStore::default()));
+    let mut terminal_pane = make_terminal_pane_for_kitty_graphics(sixel_image_store);
+    let run_command = RunCommand {
+        command: std::path::PathBuf::from("my-server"),
+        restart_policy: RestartPolicy::OnFailure,
+        max_restarts: Some(1),
+        ..Default::default()
+    };
+    terminal_pane.hold(Some(1), false, run_command.clone());
+    assert!(terminal_pane.restart(1).is_some());
+    terminal_pane.hold(Some(1), false, run_command.clone());
+    assert_eq!(
+        terminal_pane.pending_restart(),
+        None,
+        "a command that keeps failing quickly is not restarted more than max_restarts times"
+    );
+
+    terminal_pane.rerun();
+    assert_eq!(terminal_pane.restart_count, 0);
+    terminal_pane.hold(Some(1), false, run_command.clone());
+    assert!(terminal_pane.restart(1).is_some());
+    terminal_pane.command_started_at = std::time::Instant::now()
+        .checked_sub(RESTART_STABILITY_THRESHOLD)
+        .unwrap();
+    terminal_pane.hold(Some(1), false, run_command);
+    assert_eq!(
+        terminal_pane.pending_restart(),
+        Some(1),
+        "a command that failed after running stably starts a fresh series of restarts"
+    );
+}
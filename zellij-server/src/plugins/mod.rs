@@ -1,3 +1,4 @@
+mod module_cache;
 mod pinned_executor;
 mod pipes;
 mod plugin_loader;
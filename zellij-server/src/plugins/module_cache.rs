@@ -0,0 +1,180 @@
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use wasmi::{Engine, Module};
+use zellij_utils::errors::prelude::*;
+
+// wasmi cannot serialize its translated modules, so what we cache across sessions is the
+// validation of a module: a marker file named by the sha256 of the exact wasm bytes that were
+// validated, along with the wasmi version and engine configuration they were validated with.
+// On a hit we skip validation, which is the bulk of the work wasmi does up-front since
+// function bodies are translated lazily (eg. ~20ms down to ~3ms for the built-in status-bar).
+// The cache dir is versioned, so upgrading Zellij starts with an empty cache and removes the
+// caches of other versions.
+
+// the major.minor of the wasmi dependency in Cargo.toml, validation rules can change with it
+const WASMI_VERSION: &str = "0.51";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleCacheStatus {
+    Hit,
+    Miss,
+}
+
+pub fn load_module_with_cache(
+    engine: &Engine,
+    wasm_bytes: &[u8],
+    cache_dir: &Path,
+) -> Result<(Module, ModuleCacheStatus)> {
+    let marker = marker_path(engine, cache_dir, wasm_bytes);
+    if marker.exists() {
+        // Safety: the marker is only ever written after Module::new (which validates) has
+        // succeeded for these exact bytes with this wasmi version and engine configuration
+        let module = unsafe { Module::new_unchecked(engine, wasm_bytes) }?;
+        return Ok((module, ModuleCacheStatus::Hit));
+    }
+    let module = Module::new(engine, wasm_bytes)?;
+    if let Err(e) = write_marker(cache_dir, &marker) {
+        log::warn!(
+            "Failed to write plugin module cache entry {}: {:?}",
+            marker.display(),
+            e
+        );
+    }
+    Ok((module, ModuleCacheStatus::Miss))
+}
+
+fn marker_path(engine: &Engine, cache_dir: &Path, wasm_bytes: &[u8]) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(WASMI_VERSION.as_bytes());
+    hasher.update(format!("{:?}", engine.config()).as_bytes());
+    hasher.update(wasm_bytes);
+    let hash: String = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    cache_dir.join(hash)
+}
+
+fn write_marker(cache_dir: &Path, marker: &Path) -> std::io::Result<()> {
+    if !cache_dir.exists() {
+        remove_caches_of_other_versions(cache_dir);
+    }
+    std::fs::create_dir_all(cache_dir)?;
+    std::fs::write(marker, [])
+}
+
+// the cache of each version is in the same place under its own versioned folder
+fn remove_caches_of_other_versions(cache_dir: &Path) {
+    let (Some(cache_dir_name), Some(versions_dir)) = (
+        cache_dir.file_name(),
+        cache_dir
+            .parent()
+            .and_then(|version_dir| version_dir.parent()),
+    ) else {
+        return;
+    };
+    let Ok(entries) = std::fs::read_dir(versions_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let other_cache_dir = entry.path().join(cache_dir_name);
+        if other_cache_dir != cache_dir && other_cache_dir.is_dir() {
+            if let Err(e) = std::fs::remove_dir_all(&other_cache_dir) {
+                log::warn!(
+                    "Failed to remove stale plugin module cache {}: {:?}",
+                    other_cache_dir.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    // (module (func (export "noop")))
+    const VALID_MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00, 0x03,
+        0x02, 0x01, 0x00, 0x07, 0x08, 0x01, 0x04, b'n', b'o', b'o', b'p', 0x00, 0x00, 0x0a, 0x04,
+        0x01, 0x02, 0x00, 0x0b,
+    ];
+
+    #[test]
+    fn first_load_is_a_miss_and_second_load_is_a_hit() {
+        let engine = Engine::default();
+        let cache_dir = tempdir().unwrap();
+        let (_module, status) =
+            load_module_with_cache(&engine, VALID_MODULE, cache_dir.path()).unwrap();
+        assert_eq!(status, ModuleCacheStatus::Miss);
+        let (module, status) =
+            load_module_with_cache(&engine, VALID_MODULE, cache_dir.path()).unwrap();
+        assert_eq!(status, ModuleCacheStatus::Hit);
+        assert!(module.get_export("noop").is_some());
+    }
+
+    #[test]
+    fn different_bytes_do_not_share_a_cache_entry() {
+        let engine = Engine::default();
+        let cache_dir = tempdir().unwrap();
+        load_module_with_cache(&engine, VALID_MODULE, cache_dir.path()).unwrap();
+        let mut other_module = VALID_MODULE.to_vec();
+        other_module[24] = b'p'; // export "nopp" instead of "noop"
+        let (module, status) =
+            load_module_with_cache(&engine, &other_module, cache_dir.path()).unwrap();
+        assert_eq!(status, ModuleCacheStatus::Miss);
+        assert!(module.get_export("nopp").is_some());
+    }
+
+    #[test]
+    fn different_engine_configurations_do_not_share_a_cache_entry() {
+        let cache_dir = tempdir().unwrap();
+        load_module_with_cache(&Engine::default(), VALID_MODULE, cache_dir.path()).unwrap();
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        let (_module, status) =
+            load_module_with_cache(&Engine::new(&config), VALID_MODULE, cache_dir.path()).unwrap();
+        assert_eq!(status, ModuleCacheStatus::Miss);
+    }
+
+    #[test]
+    fn invalid_module_is_not_cached() {
+        let engine = Engine::default();
+        let cache_dir = tempdir().unwrap();
+        let invalid_module = &VALID_MODULE[..VALID_MODULE.len() - 1];
+        assert!(load_module_with_cache(&engine, invalid_module, cache_dir.path()).is_err());
+        assert!(load_module_with_cache(&engine, invalid_module, cache_dir.path()).is_err());
+        assert_eq!(std::fs::read_dir(cache_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn caches_of_other_versions_are_removed() {
+        let engine = Engine::default();
+        let cache_root = tempdir().unwrap();
+        let old_cache_dir = cache_root.path().join("0.1.0").join("plugin_modules");
+        let new_cache_dir = cache_root.path().join("0.2.0").join("plugin_modules");
+        load_module_with_cache(&engine, VALID_MODULE, &old_cache_dir).unwrap();
+        let (_module, status) =
+            load_module_with_cache(&engine, VALID_MODULE, &new_cache_dir).unwrap();
+        assert_eq!(status, ModuleCacheStatus::Miss);
+        assert!(!old_cache_dir.exists());
+        assert!(cache_root.path().join("0.1.0").exists());
+    }
+
+    #[test]
+    fn wasmi_version_matches_the_dependency() {
+        let manifest = include_str!("../../Cargo.toml");
+        let dependency = manifest
+            .split("[dependencies.wasmi]")
+            .nth(1)
+            .and_then(|section| section.lines().find(|line| line.starts_with("version")))
+            .unwrap();
+        assert!(
+            dependency.contains(&format!("\"{}.", WASMI_VERSION)),
+            "WASMI_VERSION needs to be updated along with the wasmi dependency"
+        );
+    }
+}
@@ -145,7 +145,7 @@ pub fn apply_pipe_message_to_plugin(
     plugin_render_assets: &mut Vec<PluginRenderAsset>,
     senders: &ThreadSenders,
 ) -> Result<()> {
-    let instance = &running_plugin.instance;
+    let instance = running_plugin.instance;
     let rows = running_plugin.rows;
     let columns = running_plugin.columns;
 
@@ -154,6 +154,25 @@ pub fn apply_pipe_message_to_plugin(
         .clone()
         .try_into()
         .map_err(|e| anyhow!("Failed to convert to protobuf: {:?}", e))?;
+    let unblock_pipes_without_rendering = |running_plugin: &mut RunningPlugin| {
+        let pipes_to_block_or_unblock =
+            pipes_to_block_or_unblock(running_plugin, Some(&pipe_message.source));
+        let plugin_render_asset = PluginRenderAsset::new(
+            plugin_id,
+            client_id,
+            vec![], // nothing to render
+        )
+        .with_pipes(pipes_to_block_or_unblock);
+        let _ = senders
+            .send_to_plugin(PluginInstruction::UnblockCliPipes(vec![
+                plugin_render_asset,
+            ]))
+            .context("failed to unblock input pipe");
+    };
+    if running_plugin.is_suspended() {
+        unblock_pipes_without_rendering(running_plugin);
+        return Ok(());
+    }
     match instance.get_typed_func::<(), i32>(&mut running_plugin.store, "pipe") {
         Ok(pipe) => {
             wasi_write_object(
@@ -161,21 +180,28 @@ pub fn apply_pipe_message_to_plugin(
                 &protobuf_pipe_message.encode_to_vec(),
             )
             .with_context(err_context)?;
-            let should_render = pipe
-                .call(&mut running_plugin.store, ())
-                .with_context(err_context)?;
+            let Some(should_render) = running_plugin
+                .call_with_fuel(&pipe, ())
+                .with_context(err_context)?
+            else {
+                unblock_pipes_without_rendering(running_plugin);
+                return Ok(());
+            };
             let should_render = should_render == 1;
             if rows > 0 && columns > 0 && should_render {
-                let rendered_bytes = instance
+                let render = instance
                     .get_typed_func::<(i32, i32), ()>(&mut running_plugin.store, "render")
-                    .and_then(|render| {
-                        render.call(&mut running_plugin.store, (rows as i32, columns as i32))
-                    })
-                    .map_err(|e| anyhow!(e))
-                    .and_then(|_| {
-                        wasi_read_string(running_plugin.store.data()).map_err(|e| anyhow!(e))
-                    })
                     .with_context(err_context)?;
+                if running_plugin
+                    .call_with_fuel(&render, (rows as i32, columns as i32))
+                    .with_context(err_context)?
+                    .is_none()
+                {
+                    unblock_pipes_without_rendering(running_plugin);
+                    return Ok(());
+                }
+                let rendered_bytes =
+                    wasi_read_string(running_plugin.store.data()).with_context(err_context)?;
                 let pipes_to_block_or_unblock =
                     pipes_to_block_or_unblock(running_plugin, Some(&pipe_message.source));
                 let plugin_render_asset = PluginRenderAsset::new(
@@ -186,33 +212,13 @@ pub fn apply_pipe_message_to_plugin(
                 .with_pipes(pipes_to_block_or_unblock);
                 plugin_render_assets.push(plugin_render_asset);
             } else {
-                let pipes_to_block_or_unblock =
-                    pipes_to_block_or_unblock(running_plugin, Some(&pipe_message.source));
-                let plugin_render_asset = PluginRenderAsset::new(plugin_id, client_id, vec![])
-                    .with_pipes(pipes_to_block_or_unblock);
-                let _ = senders
-                    .send_to_plugin(PluginInstruction::UnblockCliPipes(vec![
-                        plugin_render_asset,
-                    ]))
-                    .context("failed to unblock input pipe");
+                unblock_pipes_without_rendering(running_plugin);
             }
         },
         Err(_e) => {
             // no-op, this is probably an old plugin that does not have this interface
             // we don't log this error because if we do the logs will be super crowded
-            let pipes_to_block_or_unblock =
-                pipes_to_block_or_unblock(running_plugin, Some(&pipe_message.source));
-            let plugin_render_asset = PluginRenderAsset::new(
-                plugin_id,
-                client_id,
-                vec![], // nothing to render
-            )
-            .with_pipes(pipes_to_block_or_unblock);
-            let _ = senders
-                .send_to_plugin(PluginInstruction::UnblockCliPipes(vec![
-                    plugin_render_asset,
-                ]))
-                .context("failed to unblock input pipe");
+            unblock_pipes_without_rendering(running_plugin);
         },
     }
     Ok(())
@@ -1,5 +1,7 @@
+use crate::plugins::module_cache::{load_module_with_cache, ModuleCacheStatus};
 use crate::plugins::plugin_map::{
-    PluginEnv, PluginMap, RunningPlugin, VecDequeInputStream, WriteOutputStream,
+    fuel_budget_for_plugin, refuel, PluginEnv, PluginMap, RunningPlugin, VecDequeInputStream,
+    WriteOutputStream,
 };
 use crate::plugins::plugin_worker::{plugin_worker, RunningWorker};
 use crate::plugins::wasm_bridge::{LoadingContext, PluginCache};
@@ -25,8 +27,13 @@ use crate::{
 
 use zellij_utils::plugin_api::action::ProtobufPluginConfiguration;
 use zellij_utils::{
-    consts::ZELLIJ_TMP_DIR, data::InputMode, errors::prelude::*, input::command::TerminalAction,
-    input::keybinds::Keybinds, input::plugins::PluginConfig, pane_size::Size,
+    consts::{ZELLIJ_PLUGIN_MODULE_CACHE_DIR, ZELLIJ_TMP_DIR},
+    data::InputMode,
+    errors::prelude::*,
+    input::command::TerminalAction,
+    input::keybinds::Keybinds,
+    input::plugins::PluginConfig,
+    pane_size::Size,
 };
 
 /// Open a directory as a `File` handle for WASI pre-opening.
@@ -147,11 +154,25 @@ impl<'a> PluginLoader<'a> {
         self.loading_indication.override_previous_error();
         let wasm_bytes = self.plugin_config.resolve_wasm_bytes(&self.plugin_dir)?;
         let timer = std::time::Instant::now();
-        let module = Module::new(&self.engine, &wasm_bytes)?;
+        if self.skip_cache {
+            let module = Module::new(&self.engine, &wasm_bytes)?;
+            log::info!(
+                "Loaded plugin '{}' in {:?}",
+                self.plugin_config.path.display(),
+                timer.elapsed()
+            );
+            return Ok(module);
+        }
+        let (module, cache_status) =
+            load_module_with_cache(&self.engine, &wasm_bytes, &ZELLIJ_PLUGIN_MODULE_CACHE_DIR)?;
         log::info!(
-            "Loaded plugin '{}' in {:?}",
+            "Loaded plugin '{}' in {:?} (module cache {})",
             self.plugin_config.path.display(),
-            timer.elapsed()
+            timer.elapsed(),
+            match cache_status {
+                ModuleCacheStatus::Hit => "hit",
+                ModuleCacheStatus::Miss => "miss",
+            }
         );
         Ok(module)
     }
@@ -214,6 +235,7 @@ impl<'a> PluginLoader<'a> {
             workers,
         );
 
+        plugin.lock().unwrap().refuel();
         start_function
             .call(&mut plugin.lock().unwrap().store, ())
             .with_context(err_context)?;
@@ -227,6 +249,7 @@ impl<'a> PluginLoader<'a> {
         let protobuf_bytes = protobuf_plugin_configuration.encode_to_vec();
         wasi_write_object(plugin.lock().unwrap().store.data(), &protobuf_bytes)
             .with_context(err_context)?;
+        plugin.lock().unwrap().refuel();
         load_function
             .call(&mut plugin.lock().unwrap().store, ())
             .with_context(err_context)?;
@@ -286,6 +309,7 @@ impl<'a> PluginLoader<'a> {
 
         // Apply optimized resource limits for memory efficiency
         store.limiter(|plugin_env| &mut plugin_env.store_limits);
+        refuel(&mut store, fuel_budget_for_plugin(&self.plugin_config));
 
         let mut linker = Linker::new(&self.engine);
         wasmi_wasi::add_to_linker(&mut linker, |plugin_env: &mut PluginEnv| {
@@ -398,6 +422,9 @@ impl<'a> PluginLoader<'a> {
 
         // Apply optimized resource limits for memory efficiency
         store.limiter(|plugin_env| &mut plugin_env.store_limits);
+        // workers run on their own threads and so cannot stall the plugin executor, they are not
+        // subject to the fuel budget
+        refuel(&mut store, u64::MAX);
 
         let mut linker = Linker::new(&self.engine);
         wasmi_wasi::add_to_linker(&mut linker, |plugin_env: &mut PluginEnv| {
@@ -1,4 +1,5 @@
 use crate::plugins::plugin_worker::MessageToWorker;
+use crate::plugins::wasm_bridge::handle_plugin_unresponsive;
 use crate::plugins::PluginId;
 use std::io::Write;
 use std::{
@@ -6,7 +7,7 @@ use std::{
     path::PathBuf,
     sync::{Arc, Mutex},
 };
-use wasmi::{Instance, Store, StoreLimits};
+use wasmi::{Instance, Store, StoreLimits, TrapCode, TypedFunc, WasmParams, WasmResults};
 use wasmi_wasi::WasiCtx;
 
 use crate::{thread_bus::ThreadSenders, ClientId};
@@ -22,6 +23,47 @@ use zellij_utils::{
 };
 use zellij_utils::{data::PermissionType, errors::prelude::*};
 
+// roughly the number of wasm instructions a plugin may execute in a single callback (eg. `update`,
+// `render` or `pipe`) before we consider it unresponsive and suspend it, this can be overridden
+// per plugin with the `fuel_budget` configuration key
+// the default plugins use up to ~35_000_000 in their busiest callbacks (debug builds, which are
+// the heaviest), so this leaves them more than ten times that while still suspending a spinning
+// plugin within a second or so in release builds
+pub const DEFAULT_FUEL_BUDGET: u64 = 500_000_000;
+const FUEL_BUDGET_CONFIGURATION_KEY: &str = "fuel_budget";
+
+pub fn fuel_budget_for_plugin(plugin_config: &PluginConfig) -> u64 {
+    match plugin_config
+        .initial_userspace_configuration
+        .inner()
+        .get(FUEL_BUDGET_CONFIGURATION_KEY)
+    {
+        Some(fuel_budget) => match fuel_budget.parse::<u64>() {
+            Ok(fuel_budget) if fuel_budget > 0 => fuel_budget,
+            _ => {
+                log::error!(
+                    "Invalid {} '{}' for plugin {}, using the default",
+                    FUEL_BUDGET_CONFIGURATION_KEY,
+                    fuel_budget,
+                    plugin_config.location
+                );
+                DEFAULT_FUEL_BUDGET
+            },
+        },
+        None => DEFAULT_FUEL_BUDGET,
+    }
+}
+
+pub fn refuel<T>(store: &mut Store<T>, fuel: u64) {
+    // this fails only if fuel metering is disabled in the engine, in which case there is nothing
+    // to refuel
+    let _ = store.set_fuel(fuel);
+}
+
+pub fn is_out_of_fuel(error: &wasmi::Error) -> bool {
+    error.as_trap_code() == Some(TrapCode::OutOfFuel)
+}
+
 // the idea here is to provide atomicity when adding/removing plugins from the map (eg. when a new
 // client connects) but to also allow updates/renders not to block each other
 // so when adding/removing from the map - everything is halted, that's life
@@ -359,10 +401,13 @@ pub struct RunningPlugin {
     pub columns: usize,
     next_event_ids: HashMap<AtomicEvent, usize>,
     last_applied_event_ids: HashMap<AtomicEvent, usize>,
+    fuel_budget: u64,
+    suspended: bool,
 }
 
 impl RunningPlugin {
     pub fn new(store: Store<PluginEnv>, instance: Instance, rows: usize, columns: usize) -> Self {
+        let fuel_budget = fuel_budget_for_plugin(&store.data().plugin);
         RunningPlugin {
             store,
             instance,
@@ -370,6 +415,46 @@ impl RunningPlugin {
             columns,
             next_event_ids: HashMap::new(),
             last_applied_event_ids: HashMap::new(),
+            fuel_budget,
+            suspended: false,
+        }
+    }
+    pub fn refuel(&mut self) {
+        refuel(&mut self.store, self.fuel_budget);
+    }
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+    // gives a suspended plugin another full fuel budget, if it spins again it will be suspended
+    // again
+    pub fn resume(&mut self) {
+        self.suspended = false;
+    }
+    // calls a plugin function with a full fuel budget, returns Ok(None) if the plugin is (or has
+    // now become) suspended for running out of fuel, in which case it will not be called again
+    // until it is resumed or reloaded
+    pub fn call_with_fuel<Params: WasmParams, Results: WasmResults>(
+        &mut self,
+        function: &TypedFunc<Params, Results>,
+        params: Params,
+    ) -> Result<Option<Results>, wasmi::Error> {
+        if self.suspended {
+            return Ok(None);
+        }
+        self.refuel();
+        match function.call(&mut self.store, params) {
+            Ok(results) => Ok(Some(results)),
+            Err(e) if is_out_of_fuel(&e) => {
+                self.suspended = true;
+                let plugin_env = self.store.data();
+                handle_plugin_unresponsive(
+                    plugin_env.plugin_id,
+                    self.fuel_budget,
+                    plugin_env.senders.clone(),
+                );
+                Ok(None)
+            },
+            Err(e) => Err(e),
         }
     }
     pub fn next_event_id(&mut self, atomic_event: AtomicEvent) -> usize {
@@ -410,3 +495,252 @@ impl RunningPlugin {
         self.store.data().intercepting_key_presses
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::screen::ScreenInstruction;
+    use wasmi::{Config, Engine, Linker, Module, StoreLimitsBuilder};
+    use wasmi_wasi::sync::WasiCtxBuilder;
+    use zellij_utils::channels::{self, ChannelWithContext, SenderWithContext};
+
+    // (module (func (export "spin") (loop br 0)) (func (export "noop")))
+    const SPINNING_MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic and version
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section: () -> ()
+        0x03, 0x03, 0x02, 0x00, 0x00, // function section: two functions of type 0
+        0x07, 0x0f, 0x02, 0x04, b's', b'p', b'i', b'n', 0x00, 0x00, 0x04, b'n', b'o', b'o', b'p',
+        0x00, 0x01, // export section
+        0x0a, 0x0c, 0x02, 0x07, 0x00, 0x03, 0x40, 0x0c, 0x00, 0x0b, 0x0b, 0x02, 0x00,
+        0x0b, // code section
+    ];
+
+    // (module (func (export "update") (result i32) i32.const 0) (func (export "render") (param i32 i32)))
+    const IDLE_PLUGIN_MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic and version
+        0x01, 0x0a, 0x02, 0x60, 0x00, 0x01, 0x7f, 0x60, 0x02, 0x7f, 0x7f,
+        0x00, // type section: () -> i32, (i32, i32) -> ()
+        0x03, 0x03, 0x02, 0x00, 0x01, // function section
+        0x07, 0x13, 0x02, 0x06, b'u', b'p', b'd', b'a', b't', b'e', 0x00, 0x00, 0x06, b'r', b'e',
+        b'n', b'd', b'e', b'r', 0x00, 0x01, // export section
+        0x0a, 0x09, 0x02, 0x04, 0x00, 0x41, 0x00, 0x0b, 0x02, 0x00, 0x0b, // code section
+    ];
+
+    fn plugin_config_with_configuration(configuration: &[(&str, &str)]) -> PluginConfig {
+        PluginConfig {
+            path: PathBuf::from("/path/to/plugin.wasm"),
+            _allow_exec_host_cmd: false,
+            location: RunPluginLocation::File(PathBuf::from("/path/to/plugin.wasm")),
+            initial_userspace_configuration: PluginUserConfiguration::new(
+                configuration
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            initial_cwd: None,
+        }
+    }
+
+    fn instantiate_spinning_module() -> (Store<()>, Instance) {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, SPINNING_MODULE).unwrap();
+        let mut store = Store::new(&engine, ());
+        refuel(&mut store, DEFAULT_FUEL_BUDGET);
+        let instance = Linker::new(&engine)
+            .instantiate_and_start(&mut store, &module)
+            .unwrap();
+        (store, instance)
+    }
+
+    fn running_plugin_with_module(
+        module_bytes: &[u8],
+        fuel_budget: u64,
+        senders: ThreadSenders,
+    ) -> RunningPlugin {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, module_bytes).unwrap();
+        let plugin_env = PluginEnv {
+            plugin_id: 1,
+            plugin: plugin_config_with_configuration(&[("fuel_budget", &fuel_budget.to_string())]),
+            permissions: Arc::new(Mutex::new(None)),
+            senders,
+            wasi_ctx: WasiCtxBuilder::new().build(),
+            tab_index: None,
+            client_id: 1,
+            plugin_own_data_dir: PathBuf::new(),
+            plugin_own_cache_dir: PathBuf::new(),
+            path_to_default_shell: PathBuf::new(),
+            default_shell: None,
+            layout_dir: None,
+            plugin_cwd: PathBuf::new(),
+            session_env_vars: BTreeMap::new(),
+            input_pipes_to_unblock: Arc::new(Mutex::new(HashSet::new())),
+            input_pipes_to_block: Arc::new(Mutex::new(HashSet::new())),
+            default_mode: InputMode::Normal,
+            subscriptions: Arc::new(Mutex::new(HashSet::new())),
+            stdin_pipe: Arc::new(Mutex::new(VecDeque::new())),
+            stdout_pipe: Arc::new(Mutex::new(VecDeque::new())),
+            keybinds: Keybinds::default(),
+            intercepting_key_presses: false,
+            store_limits: StoreLimitsBuilder::new().build(),
+        };
+        let mut store = Store::new(&engine, plugin_env);
+        let instance = Linker::new(&engine)
+            .instantiate_and_start(&mut store, &module)
+            .unwrap();
+        RunningPlugin::new(store, instance, 10, 10)
+    }
+
+    #[test]
+    fn fuel_budget_defaults_when_not_configured() {
+        let plugin_config = plugin_config_with_configuration(&[]);
+        assert_eq!(fuel_budget_for_plugin(&plugin_config), DEFAULT_FUEL_BUDGET);
+    }
+
+    #[test]
+    fn fuel_budget_from_plugin_configuration() {
+        let plugin_config = plugin_config_with_configuration(&[("fuel_budget", "5000")]);
+        assert_eq!(fuel_budget_for_plugin(&plugin_config), 5000);
+    }
+
+    #[test]
+    fn invalid_fuel_budget_falls_back_to_default() {
+        for invalid_fuel_budget in ["0", "-1", "lots"] {
+            let plugin_config =
+                plugin_config_with_configuration(&[("fuel_budget", invalid_fuel_budget)]);
+            assert_eq!(fuel_budget_for_plugin(&plugin_config), DEFAULT_FUEL_BUDGET);
+        }
+    }
+
+    #[test]
+    fn spinning_function_runs_out_of_fuel() {
+        let (mut store, instance) = instantiate_spinning_module();
+        let spin = instance
+            .get_typed_func::<(), ()>(&mut store, "spin")
+            .unwrap();
+        refuel(&mut store, 10_000);
+        let error = spin.call(&mut store, ()).unwrap_err();
+        assert!(is_out_of_fuel(&error));
+    }
+
+    #[test]
+    fn refueling_allows_calls_after_running_out_of_fuel() {
+        let (mut store, instance) = instantiate_spinning_module();
+        let spin = instance
+            .get_typed_func::<(), ()>(&mut store, "spin")
+            .unwrap();
+        let noop = instance
+            .get_typed_func::<(), ()>(&mut store, "noop")
+            .unwrap();
+        refuel(&mut store, 10_000);
+        assert!(spin.call(&mut store, ()).is_err());
+        assert_eq!(store.get_fuel().unwrap(), 0);
+        refuel(&mut store, 10_000);
+        assert!(noop.call(&mut store, ()).is_ok());
+    }
+
+    #[test]
+    fn plugin_out_of_fuel_is_suspended_until_resumed() {
+        let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> =
+            channels::unbounded();
+        let senders = ThreadSenders {
+            to_screen: Some(SenderWithContext::new(to_screen)),
+            ..Default::default()
+        };
+        let mut running_plugin = running_plugin_with_module(SPINNING_MODULE, 10_000, senders);
+        let spin = running_plugin
+            .instance
+            .get_typed_func::<(), ()>(&mut running_plugin.store, "spin")
+            .unwrap();
+        let noop = running_plugin
+            .instance
+            .get_typed_func::<(), ()>(&mut running_plugin.store, "noop")
+            .unwrap();
+
+        assert_eq!(running_plugin.call_with_fuel(&spin, ()).unwrap(), None);
+        assert!(running_plugin.is_suspended(), "plugin suspended");
+        match screen_receiver.try_recv() {
+            Ok((ScreenInstruction::UpdatePluginLoadingStage(1, loading_indication), _)) => {
+                assert!(loading_indication.is_error(), "plugin marked unresponsive")
+            },
+            _ => panic!("screen not told the plugin is unresponsive"),
+        }
+        assert_eq!(
+            running_plugin.call_with_fuel(&noop, ()).unwrap(),
+            None,
+            "suspended plugin is not called"
+        );
+
+        running_plugin.resume();
+        assert!(!running_plugin.is_suspended());
+        assert_eq!(
+            running_plugin.call_with_fuel(&noop, ()).unwrap(),
+            Some(()),
+            "resumed plugin is called with a fresh fuel budget"
+        );
+    }
+
+    #[test]
+    fn key_that_resumes_a_suspended_plugin_is_not_delivered_to_it() {
+        use crate::plugins::wasm_bridge::apply_event_to_plugin;
+        use zellij_utils::data::{BareKey, Event, KeyWithModifier};
+
+        let mut running_plugin =
+            running_plugin_with_module(IDLE_PLUGIN_MODULE, 10_000, ThreadSenders::default());
+        running_plugin.suspended = true;
+        let key = Event::Key(KeyWithModifier::new(BareKey::Char('a')));
+        let mut plugin_render_assets = vec![];
+
+        apply_event_to_plugin(
+            1,
+            1,
+            &mut running_plugin,
+            &key,
+            &mut plugin_render_assets,
+            ThreadSenders::default(),
+            &HashSet::new(),
+        )
+        .unwrap();
+        assert!(!running_plugin.is_suspended(), "plugin resumed");
+        assert!(
+            running_plugin
+                .store
+                .data()
+                .stdin_pipe
+                .lock()
+                .unwrap()
+                .is_empty(),
+            "waking key not delivered to the plugin"
+        );
+        assert_eq!(
+            plugin_render_assets.len(),
+            1,
+            "plugin rendered in place of the unresponsive message"
+        );
+
+        apply_event_to_plugin(
+            1,
+            1,
+            &mut running_plugin,
+            &key,
+            &mut plugin_render_assets,
+            ThreadSenders::default(),
+            &HashSet::new(),
+        )
+        .unwrap();
+        assert!(
+            !running_plugin
+                .store
+                .data()
+                .stdin_pipe
+                .lock()
+                .unwrap()
+                .is_empty(),
+            "next key delivered to the plugin"
+        );
+    }
+}
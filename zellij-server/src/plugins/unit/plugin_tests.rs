@@ -337,6 +337,7 @@ fn create_plugin_thread(
     let mut config = wasmi::Config::default();
     config.set_max_stack_height(1024 * 1024);
     config.set_max_recursion_depth(1000);
+    config.consume_fuel(true);
     let engine = Engine::new(&config);
     let data_dir = PathBuf::from(tempdir().unwrap().path());
     let layout_dir = PathBuf::from(tempdir().unwrap().path());
@@ -436,6 +437,7 @@ fn create_plugin_thread_with_server_receiver(
     let mut config = wasmi::Config::default();
     config.set_max_stack_height(1024 * 1024);
     config.set_max_recursion_depth(1000);
+    config.consume_fuel(true);
     let engine = Engine::new(&config);
     let data_dir = PathBuf::from(tempdir().unwrap().path());
     let default_shell = PathBuf::from(".");
@@ -531,6 +533,7 @@ fn create_plugin_thread_with_pty_receiver(
     let mut config = wasmi::Config::default();
     config.set_max_stack_height(1024 * 1024);
     config.set_max_recursion_depth(1000);
+    config.consume_fuel(true);
     let engine = Engine::new(&config);
     let data_dir = PathBuf::from(tempdir().unwrap().path());
     let layout_dir = layout_dir.unwrap_or_else(|| PathBuf::from(tempdir().unwrap().path()));
@@ -621,6 +624,7 @@ fn create_plugin_thread_with_background_jobs_receiver(
     let mut config = wasmi::Config::default();
     config.set_max_stack_height(1024 * 1024);
     config.set_max_recursion_depth(1000);
+    config.consume_fuel(true);
     let engine = Engine::new(&config);
     let data_dir = PathBuf::from(tempdir().unwrap().path());
     let default_shell = PathBuf::from(".");
@@ -12695,7 +12699,7 @@ pub fn mode_update_payload_is_lightweight_for_opted_in_plugins() {
     let mut mode_map = std::collections::HashMap::new();
     mode_map.insert(KeyWithModifier::new(BareKey::Char('q')), vec![Action::Quit]);
     keybind_map.insert(InputMode::Normal, mode_map);
-    let test_keybinds = Keybinds(keybind_map);
+    let test_keybinds = Keybinds(keybind_map, Default::default(), Default::default());
 
     let _ = plugin_thread_sender.send(PluginInstruction::Reconfigure {
         client_id,
@@ -12839,7 +12843,7 @@ pub fn reconfiguration_resends_keybinds_to_opted_in_plugins() {
     let mut mode_map = std::collections::HashMap::new();
     mode_map.insert(KeyWithModifier::new(BareKey::Char('x')), vec![Action::Quit]);
     keybind_map.insert(InputMode::Normal, mode_map);
-    let new_keybinds = Keybinds(keybind_map);
+    let new_keybinds = Keybinds(keybind_map, Default::default(), Default::default());
 
     // Send Reconfigure
     let _ = plugin_thread_sender.send(PluginInstruction::Reconfigure {
@@ -844,19 +844,22 @@ impl WasmBridge {
                                         "render",
                                     )
                                     .and_then(|render| {
-                                        render.call(
-                                            &mut running_plugin.store,
+                                        running_plugin.call_with_fuel(
+                                            &render,
                                             (new_rows as i32, new_columns as i32),
                                         )
                                     })
                                     .map_err(|e| anyhow!(e))
-                                    .and_then(|_| {
-                                        wasi_read_string(running_plugin.store.data())
-                                            .map_err(|e| anyhow!(e))
+                                    .and_then(|rendered| match rendered {
+                                        Some(()) => wasi_read_string(running_plugin.store.data())
+                                            .map(Some)
+                                            .map_err(|e| anyhow!(e)),
+                                        None => Ok(None),
                                     })
                                     .with_context(err_context);
                                 match rendered_bytes {
-                                    Ok(rendered_bytes) => {
+                                    Ok(None) => {}, // plugin is suspended
+                                    Ok(Some(rendered_bytes)) => {
                                         let plugin_render_asset = PluginRenderAsset::new(
                                             plugin_id,
                                             client_id,
@@ -2097,6 +2100,8 @@ fn check_event_permission(
         | Event::CommandPaneReRun(..)
         | Event::CwdChanged(..)
         | Event::CommandChanged(..)
+        | Event::CommandFinished(..)
+        | Event::RecordingStateChanged(..)
         | Event::AvailableLayoutInfo(..)
         | Event::PluginConfigurationChanged(..)
         | Event::HighlightClicked { .. }
@@ -2127,11 +2132,39 @@ pub fn apply_event_to_plugin(
     senders: ThreadSenders,
     plugin_subscriptions: &HashSet<EventType>,
 ) -> Result<()> {
-    let instance = &running_plugin.instance;
+    let instance = running_plugin.instance;
     let rows = running_plugin.rows;
     let columns = running_plugin.columns;
 
     let err_context = || format!("Failed to apply event to plugin {plugin_id}");
+    if running_plugin.is_suspended() {
+        // key presses are only sent to the focused plugin, so this is the user asking for it
+        // to be resumed - the key itself is meant for us rather than for the plugin, so we only
+        // render the plugin again in place of the unresponsive message
+        if !matches!(event, Event::Key(..)) {
+            return Ok(());
+        }
+        running_plugin.resume();
+        if rows > 0 && columns > 0 {
+            let render = instance
+                .get_typed_func::<(i32, i32), ()>(&mut running_plugin.store, "render")
+                .with_context(err_context)?;
+            if running_plugin
+                .call_with_fuel(&render, (rows as i32, columns as i32))
+                .with_context(err_context)?
+                .is_some()
+            {
+                let rendered_bytes =
+                    wasi_read_string(running_plugin.store.data()).with_context(err_context)?;
+                plugin_render_assets.push(PluginRenderAsset::new(
+                    plugin_id,
+                    client_id,
+                    rendered_bytes.as_bytes().to_vec(),
+                ));
+            }
+        }
+        return Ok(());
+    }
     match check_event_permission(running_plugin.store.data(), event) {
         (PermissionStatus::Granted, _) => {
             let mut event = event.clone();
@@ -2144,6 +2177,9 @@ pub fn apply_event_to_plugin(
                     // Legacy plugin — send full keybindings as before
                     mode_info.keybinds = running_plugin.store.data().keybinds.to_keybinds_vec();
                 }
+                mode_info.key_sequences =
+                    running_plugin.store.data().keybinds.to_key_sequences_vec();
+                mode_info.custom_modes = running_plugin.store.data().keybinds.custom_modes();
             }
             let protobuf_event: Result<ProtobufEvent, _> = event.clone().try_into();
             match protobuf_event {
@@ -2153,9 +2189,12 @@ pub fn apply_event_to_plugin(
                         .with_context(err_context)?;
                     wasi_write_object(running_plugin.store.data(), &protobuf_event.encode_to_vec())
                         .with_context(err_context)?;
-                    let should_render = update
-                        .call(&mut running_plugin.store, ())
-                        .with_context(err_context)?;
+                    let Some(should_render) = running_plugin
+                        .call_with_fuel(&update, ())
+                        .with_context(err_context)?
+                    else {
+                        return Ok(());
+                    };
                     let mut should_render = should_render == 1;
                     if let Event::PermissionRequestResult(..) = event {
                         // we always render in this case, otherwise the request permission screen stays on
@@ -2163,17 +2202,17 @@ pub fn apply_event_to_plugin(
                         should_render = true;
                     }
                     if rows > 0 && columns > 0 && should_render {
-                        let rendered_bytes = instance
+                        let render = instance
                             .get_typed_func::<(i32, i32), ()>(&mut running_plugin.store, "render")
-                            .and_then(|render| {
-                                render
-                                    .call(&mut running_plugin.store, (rows as i32, columns as i32))
-                            })
-                            .map_err(|e| anyhow!(e))
-                            .and_then(|_| {
-                                wasi_read_string(running_plugin.store.data())
-                                    .map_err(|e| anyhow!(e))
-                            })
+                            .with_context(err_context)?;
+                        if running_plugin
+                            .call_with_fuel(&render, (rows as i32, columns as i32))
+                            .with_context(err_context)?
+                            .is_none()
+                        {
+                            return Ok(());
+                        }
+                        let rendered_bytes = wasi_read_string(running_plugin.store.data())
                             .with_context(err_context)?;
                         let pipes_to_block_or_unblock =
                             pipes_to_block_or_unblock(running_plugin, None);
@@ -2227,13 +2266,30 @@ pub fn handle_plugin_crash(plugin_id: PluginId, message: String, senders: Thread
     ));
 }
 
+pub fn handle_plugin_unresponsive(plugin_id: PluginId, fuel_budget: u64, senders: ThreadSenders) {
+    log::error!(
+        "Plugin {} exceeded its fuel budget of {} and was suspended",
+        plugin_id,
+        fuel_budget
+    );
+    let mut loading_indication = LoadingIndication::new("Unresponsive".to_owned());
+    loading_indication.indicate_loading_error(format!(
+        "Plugin is unresponsive: it exceeded its fuel budget ({}) in a single call and was suspended.\nPress a key in this pane to resume it, reload it from the plugin-manager to restart it, or raise its fuel_budget.",
+        fuel_budget
+    ));
+    let _ = senders.send_to_screen(ScreenInstruction::UpdatePluginLoadingStage(
+        plugin_id,
+        loading_indication,
+    ));
+}
+
 pub fn apply_before_close_event_to_plugin(
     plugin_id: PluginId,
     client_id: ClientId,
     running_plugin: &mut RunningPlugin,
     senders: ThreadSenders,
 ) -> Result<()> {
-    let instance = &running_plugin.instance;
+    let instance = running_plugin.instance;
 
     let err_context = || format!("Failed to apply event to plugin {plugin_id}");
     let event = Event::BeforeClose;
@@ -2246,8 +2302,8 @@ pub fn apply_before_close_event_to_plugin(
         .with_context(err_context)?;
     wasi_write_object(running_plugin.store.data(), &protobuf_event.encode_to_vec())
         .with_context(err_context)?;
-    let _should_render = update
-        .call(&mut running_plugin.store, ())
+    let _should_render = running_plugin
+        .call_with_fuel(&update, ())
         .with_context(err_context)?;
     let pipes_to_block_or_unblock = pipes_to_block_or_unblock(running_plugin, None);
     let plugin_render_asset =
@@ -86,7 +86,8 @@ use zellij_utils::{
             ProtobufGetPanePidResponse, ProtobufGetPaneRunningCommandResponse,
             ProtobufGetSessionEnvironmentVariablesResponse, ProtobufGetSessionListResponse,
             ProtobufGetTabInfoResponse, ProtobufHideFloatingPanesResponse,
-            ProtobufKillSessionsResponse, ProtobufNewTabResponse, ProtobufNewTabsResponse,
+            ProtobufKillSessionsResponse, ProtobufListRecentlyClosedResponse,
+            ProtobufNewTabResponse, ProtobufNewTabsResponse,
             ProtobufOpenCommandPaneBackgroundResponse,
             ProtobufOpenCommandPaneFloatingNearPluginResponse,
             ProtobufOpenCommandPaneFloatingResponse,
@@ -264,8 +265,8 @@ fn host_run_plugin_command(mut caller: Caller<'_, PluginEnv>) {
                     PluginCommand::NewTabsWithLayout(raw_layout) => {
                         new_tabs_with_layout(env, &raw_layout)?
                     },
-                    PluginCommand::NewTabsWithLayoutInfo(layout_info) => {
-                        new_tabs_with_layout_info(env, layout_info)?
+                    PluginCommand::NewTabsWithLayoutInfo(layout_info, layout_args) => {
+                        new_tabs_with_layout_info(env, layout_info, layout_args)?
                     },
                     PluginCommand::OverrideLayout(
                         layout_info,
@@ -580,8 +581,17 @@ fn host_run_plugin_command(mut caller: Caller<'_, PluginEnv>) {
                     PluginCommand::RebindKeys {
                         keys_to_rebind,
                         keys_to_unbind,
+                        key_sequences_to_rebind,
+                        key_sequences_to_unbind,
                         write_config_to_disk,
-                    } => rebind_keys(env, keys_to_rebind, keys_to_unbind, write_config_to_disk)?,
+                    } => rebind_keys(
+                        env,
+                        keys_to_rebind,
+                        keys_to_unbind,
+                        key_sequences_to_rebind,
+                        key_sequences_to_unbind,
+                        write_config_to_disk,
+                    )?,
                     PluginCommand::ListClients => list_clients(env),
                     PluginCommand::ChangeHostFolder(new_host_folder) => {
                         change_host_folder(env, new_host_folder)
@@ -592,6 +602,11 @@ fn host_run_plugin_command(mut caller: Caller<'_, PluginEnv>) {
                     PluginCommand::StackPanes(pane_ids) => {
                         stack_panes(env, pane_ids.into_iter().map(|p_id| p_id.into()).collect())
                     },
+                    PluginCommand::SetSyncGroup(pane_ids) => {
+                        set_sync_group(env, pane_ids.into_iter().map(|p_id| p_id.into()).collect())
+                    },
+                    PluginCommand::ListRecentlyClosed => list_recently_closed(env),
+                    PluginCommand::ReopenClosed(id) => reopen_closed(env, id),
                     PluginCommand::ChangeFloatingPanesCoordinates(pane_ids_and_coordinates) => {
                         change_floating_panes_coordinates(
                             env,
@@ -772,14 +787,26 @@ fn host_run_plugin_command(mut caller: Caller<'_, PluginEnv>) {
                     },
                 },
                 (PermissionStatus::Denied, permission) => {
+                    let permission = permission
+                        .map(|p| p.to_string())
+                        .unwrap_or("UNKNOWN".to_owned());
+                    let command_type =
+                        CommandType::from_str(&command.to_string()).with_context(err_context)?;
                     log::error!(
                         "Plugin '{}' permission '{}' denied - Command '{:?}' denied",
                         env.name(),
-                        permission
-                            .map(|p| p.to_string())
-                            .unwrap_or("UNKNOWN".to_owned()),
-                        CommandType::from_str(&command.to_string()).with_context(err_context)?
+                        permission,
+                        command_type,
                     );
+                    let _ = env
+                        .senders
+                        .send_to_background_jobs(BackgroundJob::DisplayPaneError(
+                            vec![PaneId::Plugin(env.plugin_id)],
+                            format!(
+                                "DENIED: {:?} (missing {} permission)",
+                                command_type, permission
+                            ),
+                        ));
                 },
             };
             Ok(())
@@ -1056,6 +1083,9 @@ fn open_command_pane_in_new_tab(
             context,
         )),
         use_terminal_title: false,
+        env: BTreeMap::new(),
+        restart_policy: Default::default(),
+        max_restarts: None,
     };
     let initial_panes = Some(vec![CommandOrPlugin::Command(run_command_action)]);
     let action = Action::NewTab {
@@ -1286,15 +1316,14 @@ fn parse_layout(env: &PluginEnv, layout_string: String) {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     // Parse the KDL layout - same parameters as list_available_layouts in layout.rs:1260
-    let parse_result = Layout::from_kdl(
+    let parse_result = Layout::from_kdl_with_imports(
         &layout_string,
         Some("parse_layout_api".to_string()), // file_name for error reporting
-        None,                                 // no swap layouts
-        None,                                 // no cwd
+        env.layout_dir.clone(),               // imports are resolved relative to the layout dir
     );
 
     let response = match parse_result {
-        Ok(layout) => {
+        Ok((layout, imported_files)) => {
             // Extract tabs from layout
             // This logic matches LayoutMetadata::from in data.rs:1762-1772
             let layout_tabs = layout.tabs();
@@ -1325,6 +1354,10 @@ fn parse_layout(env: &PluginEnv, layout_string: String) {
                 tabs,
                 creation_time: current_time.clone(),
                 update_time: current_time,
+                imports: imported_files
+                    .iter()
+                    .map(|f| f.display().to_string())
+                    .collect(),
             };
 
             // Convert LayoutMetadata to protobuf
@@ -1946,6 +1979,9 @@ fn open_command_pane_in_place_of_plugin(
             context,
         )),
         use_terminal_title,
+        env: BTreeMap::new(),
+        restart_policy: Default::default(),
+        max_restarts: None,
     };
     let run_cmd = TerminalAction::RunCommand(run_command_action.into());
 
@@ -2045,6 +2081,9 @@ fn open_command_pane_in_place_of_pane_id(
             context,
         )),
         use_terminal_title,
+        env: BTreeMap::new(),
+        restart_policy: Default::default(),
+        max_restarts: None,
     };
     let run_cmd = TerminalAction::RunCommand(run_command_action.into());
 
@@ -2140,6 +2179,9 @@ fn open_command_pane(
             context,
         )),
         use_terminal_title,
+        env: BTreeMap::new(),
+        restart_policy: Default::default(),
+        max_restarts: None,
     };
     let action = Action::NewTiledPane {
         direction,
@@ -2190,6 +2232,9 @@ fn open_command_pane_near_plugin(
             context,
         )),
         use_terminal_title,
+        env: BTreeMap::new(),
+        restart_policy: Default::default(),
+        max_restarts: None,
     };
     let run_cmd = TerminalAction::RunCommand(run_command_action.into());
 
@@ -2249,6 +2294,9 @@ fn open_command_pane_floating(
             context,
         )),
         use_terminal_title,
+        env: BTreeMap::new(),
+        restart_policy: Default::default(),
+        max_restarts: None,
     };
     let action = Action::NewFloatingPane {
         command: Some(run_command_action),
@@ -2299,6 +2347,9 @@ fn open_command_pane_floating_near_plugin(
             context,
         )),
         use_terminal_title,
+        env: BTreeMap::new(),
+        restart_policy: Default::default(),
+        max_restarts: None,
     };
     let run_cmd = TerminalAction::RunCommand(run_command_action.into());
 
@@ -2359,6 +2410,9 @@ fn open_command_pane_in_place(
             context,
         )),
         use_terminal_title,
+        env: BTreeMap::new(),
+        restart_policy: Default::default(),
+        max_restarts: None,
     };
     let action = Action::NewInPlacePane {
         command: Some(run_command_action),
@@ -2411,6 +2465,9 @@ fn open_command_pane_background(
             context,
         )),
         use_terminal_title,
+        env: BTreeMap::new(),
+        restart_policy: Default::default(),
+        max_restarts: None,
     };
     let run_cmd = TerminalAction::RunCommand(run_command_action.into());
 
@@ -2678,6 +2735,8 @@ fn rebind_keys(
     env: &PluginEnv,
     keys_to_rebind: Vec<(InputMode, KeyWithModifier, Vec<Action>)>,
     keys_to_unbind: Vec<(InputMode, KeyWithModifier)>,
+    key_sequences_to_rebind: Vec<(InputMode, Vec<KeyWithModifier>, Vec<Action>)>,
+    key_sequences_to_unbind: Vec<(InputMode, Vec<KeyWithModifier>)>,
     write_config_to_disk: bool,
 ) -> Result<()> {
     let err_context = || "Failed to rebind_keys";
@@ -2687,6 +2746,8 @@ fn rebind_keys(
             client_id,
             keys_to_rebind,
             keys_to_unbind,
+            key_sequences_to_rebind,
+            key_sequences_to_unbind,
             write_config_to_disk,
         })
         .with_context(err_context)?;
@@ -2712,9 +2773,13 @@ fn new_tabs_with_layout(env: &PluginEnv, raw_layout: &str) -> Result<()> {
     Ok(())
 }
 
-fn new_tabs_with_layout_info(env: &PluginEnv, layout_info: LayoutInfo) -> Result<()> {
+fn new_tabs_with_layout_info(
+    env: &PluginEnv,
+    layout_info: LayoutInfo,
+    layout_args: BTreeMap<String, String>,
+) -> Result<()> {
     // TODO: cwd
-    let layout = Layout::from_layout_info(&env.layout_dir, layout_info)
+    let layout = Layout::from_layout_info(&env.layout_dir, layout_info, &layout_args)
         .map_err(|e| anyhow!("Failed to parse layout: {:?}", e))?;
     apply_layout(env, layout);
     Ok(())
@@ -3794,6 +3859,53 @@ fn stack_panes(env: &PluginEnv, pane_ids: Vec<PaneId>) {
             .send_to_screen(ScreenInstruction::StackPanes(pane_ids, env.client_id, None));
 }
 
+fn set_sync_group(env: &PluginEnv, pane_ids: Vec<PaneId>) {
+    let _ = env
+        .senders
+        .send_to_screen(ScreenInstruction::SetSyncGroup(pane_ids, None));
+}
+
+fn list_recently_closed(env: &PluginEnv) {
+    use crossbeam::channel::RecvTimeoutError;
+    use std::time::Duration;
+
+    let err_context = || format!("failed to list recently closed for plugin {}", env.name());
+
+    let (response_sender, response_receiver) = crossbeam::channel::bounded(1);
+    env.senders
+        .send_to_screen(ScreenInstruction::ListRecentlyClosed {
+            response_channel: response_sender,
+        })
+        .with_context(err_context)
+        .non_fatal();
+
+    let recently_closed = match response_receiver.recv_timeout(Duration::from_millis(100)) {
+        Ok(recently_closed) => recently_closed,
+        Err(RecvTimeoutError::Timeout) => {
+            log::error!("ListRecentlyClosed timed out for plugin {}", env.plugin_id);
+            vec![]
+        },
+        Err(RecvTimeoutError::Disconnected) => {
+            log::error!(
+                "ListRecentlyClosed channel disconnected for plugin {}",
+                env.plugin_id
+            );
+            vec![]
+        },
+    };
+
+    let response = ProtobufListRecentlyClosedResponse {
+        recently_closed: recently_closed.into_iter().map(|r| r.into()).collect(),
+    };
+    let _ = wasi_write_object(env, &response.encode_to_vec());
+}
+
+fn reopen_closed(env: &PluginEnv, id: u32) {
+    let _ = env
+        .senders
+        .send_to_screen(ScreenInstruction::ReopenClosed(id, env.client_id));
+}
+
 fn change_floating_panes_coordinates(
     env: &PluginEnv,
     pane_ids_and_coordinates: Vec<(PaneId, FloatingPaneCoordinates)>,
@@ -5188,7 +5300,7 @@ fn override_layout(
     apply_only_to_active_tab: bool,
     context: BTreeMap<String, String>,
 ) -> Result<()> {
-    let layout = Layout::from_layout_info(&env.layout_dir, layout_info)
+    let layout = Layout::from_layout_info(&env.layout_dir, layout_info, &BTreeMap::new())
         .map_err(|e| anyhow!("Failed to parse layout: {:?}", e))?;
 
     // Convert all tabs to Vec<TabLayoutInfo>
@@ -5404,6 +5516,8 @@ fn check_command_permission(
         | PluginCommand::LoadNewPlugin { .. }
         | PluginCommand::SetFloatingPanePinned(..)
         | PluginCommand::StackPanes(..)
+        | PluginCommand::SetSyncGroup(..)
+        | PluginCommand::ReopenClosed(..)
         | PluginCommand::ChangeFloatingPanesCoordinates(..)
         | PluginCommand::TogglePaneBorderless(..)
         | PluginCommand::SetPaneBorderless(..)
@@ -5447,6 +5561,7 @@ fn check_command_permission(
         | PluginCommand::CurrentSessionLastSavedTime
         | PluginCommand::GetPaneInfo(..)
         | PluginCommand::GetTabInfo(..)
+        | PluginCommand::ListRecentlyClosed
         | PluginCommand::GetSessionList => PermissionType::ReadApplicationState,
         PluginCommand::RebindKeys { .. } | PluginCommand::Reconfigure(..) => {
             PermissionType::Reconfigure
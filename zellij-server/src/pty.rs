@@ -7,6 +7,7 @@ use crate::terminal_bytes::TerminalBytes;
 use crate::{
     panes::PaneId,
     plugins::{DumpSessionLayoutResponse, PluginId, PluginInstruction},
+    recently_closed::{ClosedItem, ClosedPane, ClosedTab},
     screen::{ScreenInstruction, TabOverrideResult},
     session_layout_metadata::SessionLayoutMetadata,
     thread_bus::{Bus, ThreadSenders},
@@ -155,6 +156,17 @@ pub enum PtyInstruction {
     },
     UpdateAndReportCwds,
     NotifyCwdFromOsc7(u32, PathBuf),
+    RememberClosedPane(SessionLayoutMetadata, PaneId, usize), // usize is the tab id
+    RememberClosedTab(SessionLayoutMetadata, usize),          // usize is the tab position
+    ReopenClosedPane(ClosedPane, ClientId, Option<NotificationEnd>),
+    SpawnScratchpad(
+        String, // scratchpad name
+        Option<TerminalAction>,
+        Option<FloatingPaneCoordinates>,
+        usize, // tab id
+        ClientId,
+        Option<NotificationEnd>,
+    ),
     Exit,
 }
 
@@ -188,6 +200,10 @@ impl From<&PtyInstruction> for PtyContext {
             PtyInstruction::GetPaneCwd { .. } => PtyContext::GetPaneCwd,
             PtyInstruction::UpdateAndReportCwds => PtyContext::UpdateAndReportCwds,
             PtyInstruction::NotifyCwdFromOsc7(..) => PtyContext::NotifyCwdFromOsc7,
+            PtyInstruction::RememberClosedPane(..) => PtyContext::RememberClosedPane,
+            PtyInstruction::RememberClosedTab(..) => PtyContext::RememberClosedTab,
+            PtyInstruction::ReopenClosedPane(..) => PtyContext::ReopenClosedPane,
+            PtyInstruction::SpawnScratchpad(..) => PtyContext::SpawnScratchpad,
             PtyInstruction::Exit => PtyContext::Exit,
         }
     }
@@ -603,7 +619,7 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                     Ok(..) => {},
                     Err(err) => match err.downcast_ref::<ZellijError>() {
                         Some(ZellijError::CommandNotFound { terminal_id, .. }) => {
-                            if run_command.hold_on_close {
+                            if run_command.holds_pane_on_exit() {
                                 pty.bus
                                     .senders
                                     .send_to_screen(ScreenInstruction::PtyBytes(
@@ -898,6 +914,183 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
             PtyInstruction::NotifyCwdFromOsc7(terminal_id, path) => {
                 pty.notify_cwd_from_osc7(terminal_id, path);
             },
+            PtyInstruction::RememberClosedPane(mut session_layout_metadata, pane_id, tab_id) => {
+                let err_context = || format!("failed to remember closed pane {:?}", pane_id);
+                pty.populate_session_layout_metadata(&mut session_layout_metadata);
+                if let Some(closed_pane) = session_layout_metadata.into_closed_pane(pane_id, tab_id)
+                {
+                    pty.bus
+                        .senders
+                        .send_to_screen(ScreenInstruction::AddRecentlyClosed(ClosedItem::Pane(
+                            closed_pane,
+                        )))
+                        .with_context(err_context)
+                        .non_fatal();
+                }
+            },
+            PtyInstruction::RememberClosedTab(mut session_layout_metadata, position) => {
+                let err_context = || String::from("failed to remember closed tab");
+                pty.populate_session_layout_metadata(&mut session_layout_metadata);
+                // we go through the same serialization as session resurrection so that the tab
+                // comes back the way it would after a restart
+                let layout =
+                    session_serialization::serialize_session_layout(session_layout_metadata.into())
+                        .map_err(|e| anyhow!(e))
+                        .and_then(|(kdl_layout, _pane_contents)| {
+                            Layout::from_str(&kdl_layout, String::new(), None, None)
+                                .map_err(|e| anyhow!("{:?}", e))
+                        });
+                match layout {
+                    Ok(mut layout) => {
+                        layout.recursively_add_start_suspended(Some(false));
+                        if let Some((name, tiled_panes, floating_panes)) =
+                            layout.tabs().into_iter().next()
+                        {
+                            pty.bus
+                                .senders
+                                .send_to_screen(ScreenInstruction::AddRecentlyClosed(
+                                    ClosedItem::Tab(ClosedTab {
+                                        name: name.unwrap_or_default(),
+                                        position,
+                                        tiled_panes,
+                                        floating_panes,
+                                    }),
+                                ))
+                                .with_context(err_context)
+                                .non_fatal();
+                        }
+                    },
+                    Err(e) => Err::<(), _>(e).with_context(err_context).non_fatal(),
+                }
+            },
+            PtyInstruction::ReopenClosedPane(closed_pane, client_id, completion_tx) => {
+                let err_context = || String::from("failed to reopen closed pane");
+                let terminal_action = match closed_pane.run.clone() {
+                    Some(Run::Command(run_command)) => TerminalAction::RunCommand(run_command),
+                    Some(Run::EditFile(path, line_number, cwd)) => {
+                        TerminalAction::OpenFile(OpenFilePayload::new(path, line_number, cwd))
+                    },
+                    Some(Run::Cwd(cwd)) => pty.get_default_terminal(Some(cwd), None),
+                    None => pty.get_default_terminal(None, None),
+                    Some(Run::Plugin(_)) => {
+                        // closed plugin panes are reopened through FillPluginCwd by the screen
+                        log::error!("Cannot reopen a closed plugin pane as a terminal");
+                        continue;
+                    },
+                };
+                let run_command = match &terminal_action {
+                    TerminalAction::RunCommand(run_command) => Some(run_command.clone()),
+                    _ => None,
+                };
+                match pty.spawn_terminal(
+                    Some(terminal_action),
+                    ClientTabIndexOrPaneId::TabIndex(closed_pane.tab_id),
+                ) {
+                    Ok((pid, starts_held)) => {
+                        let hold_for_command = if starts_held { run_command } else { None };
+                        pty.bus
+                            .senders
+                            .send_to_screen(ScreenInstruction::RestoreClosedPane(
+                                PaneId::Terminal(pid),
+                                hold_for_command,
+                                closed_pane,
+                                client_id,
+                                completion_tx,
+                            ))
+                            .with_context(err_context)?;
+                    },
+                    Err(err) => match err.downcast_ref::<ZellijError>() {
+                        Some(ZellijError::CommandNotFound { terminal_id, .. }) => {
+                            log::error!("Failed to reopen closed pane: {:?}", err);
+                            pty.close_pane(PaneId::Terminal(*terminal_id))
+                                .with_context(err_context)?;
+                        },
+                        _ => Err::<(), _>(err).with_context(err_context).non_fatal(),
+                    },
+                }
+            },
+            PtyInstruction::SpawnScratchpad(
+                name,
+                terminal_action,
+                coordinates,
+                tab_id,
+                client_id,
+                completion_tx,
+            ) => {
+                let err_context = || format!("failed to spawn scratchpad {}", name);
+                let run_command = match &terminal_action {
+                    Some(TerminalAction::RunCommand(run_command)) => Some(run_command.clone()),
+                    _ => None,
+                };
+                let invoked_with = run_command.clone().map(Run::Command);
+                match pty.spawn_terminal(terminal_action, ClientTabIndexOrPaneId::TabIndex(tab_id))
+                {
+                    Ok((pid, starts_held)) => {
+                        let hold_for_command = if starts_held { run_command } else { None };
+                        pty.bus
+                            .senders
+                            .send_to_screen(ScreenInstruction::AddScratchpad(
+                                name.clone(),
+                                PaneId::Terminal(pid),
+                                hold_for_command,
+                                invoked_with,
+                                coordinates,
+                                tab_id,
+                                client_id,
+                                completion_tx,
+                            ))
+                            .with_context(err_context)?;
+                    },
+                    Err(err) => match err.downcast_ref::<ZellijError>() {
+                        Some(ZellijError::CommandNotFound { terminal_id, .. }) => {
+                            match run_command {
+                                Some(run_command) if run_command.hold_on_close => {
+                                    let hold_for_command = None; // we do not hold an "error" pane
+                                    pty.bus
+                                        .senders
+                                        .send_to_screen(ScreenInstruction::AddScratchpad(
+                                            name.clone(),
+                                            PaneId::Terminal(*terminal_id),
+                                            hold_for_command,
+                                            invoked_with,
+                                            coordinates,
+                                            tab_id,
+                                            client_id,
+                                            completion_tx,
+                                        ))
+                                        .with_context(err_context)?;
+                                    send_command_not_found_to_screen(
+                                        pty.bus.senders.clone(),
+                                        *terminal_id,
+                                        run_command,
+                                    )
+                                    .with_context(err_context)?;
+                                },
+                                _ => {
+                                    log::error!("Failed to spawn scratchpad: {:?}", err);
+                                    pty.close_pane(PaneId::Terminal(*terminal_id))
+                                        .with_context(err_context)?;
+                                    pty.bus
+                                        .senders
+                                        .send_to_screen(ScreenInstruction::ScratchpadNotSpawned(
+                                            name.clone(),
+                                        ))
+                                        .with_context(err_context)?;
+                                },
+                            }
+                        },
+                        _ => {
+                            Err::<(), _>(err).with_context(err_context).non_fatal();
+                            pty.bus
+                                .senders
+                                .send_to_screen(ScreenInstruction::ScratchpadNotSpawned(
+                                    name.clone(),
+                                ))
+                                .with_context(err_context)?;
+                        },
+                    },
+                }
+            },
             PtyInstruction::Exit => break,
         }
     }
@@ -1045,7 +1238,7 @@ impl Pty {
             match &terminal_action {
                 TerminalAction::RunCommand(run_command) => (
                     run_command.hold_on_start,
-                    run_command.hold_on_close,
+                    run_command.holds_pane_on_exit(),
                     run_command.originating_plugin.clone(),
                     None,
                 ),
@@ -1136,12 +1329,20 @@ impl Pty {
             let senders = self.bus.senders.clone();
             let debug_to_file = self.debug_to_file;
             let activity_flag = activity_flag.clone();
+            let recordings = self.bus.os_input.as_ref().and_then(|o| o.recordings());
             async move {
-                TerminalBytes::new(terminal_id, reader, senders, debug_to_file, activity_flag)
-                    .listen()
-                    .await
-                    .with_context(|| err_context(terminal_id))
-                    .fatal();
+                TerminalBytes::new(
+                    terminal_id,
+                    reader,
+                    senders,
+                    debug_to_file,
+                    activity_flag,
+                    recordings,
+                )
+                .listen()
+                .await
+                .with_context(|| err_context(terminal_id))
+                .fatal();
             }
         });
 
@@ -1338,6 +1539,7 @@ impl Pty {
                         let senders = self.bus.senders.clone();
                         let debug_to_file = self.debug_to_file;
                         let activity_flag = activity_flag.clone();
+                        let recordings = self.bus.os_input.as_ref().and_then(|o| o.recordings());
                         async move {
                             TerminalBytes::new(
                                 terminal_id,
@@ -1345,6 +1547,7 @@ impl Pty {
                                 senders,
                                 debug_to_file,
                                 activity_flag,
+                                recordings,
                             )
                             .listen()
                             .await
@@ -1516,6 +1719,7 @@ impl Pty {
                         let senders = self.bus.senders.clone();
                         let debug_to_file = self.debug_to_file;
                         let activity_flag = activity_flag.clone();
+                        let recordings = self.bus.os_input.as_ref().and_then(|o| o.recordings());
                         async move {
                             TerminalBytes::new(
                                 terminal_id,
@@ -1523,6 +1727,7 @@ impl Pty {
                                 senders,
                                 debug_to_file,
                                 activity_flag,
+                                recordings,
                             )
                             .listen()
                             .await
@@ -1610,7 +1815,7 @@ impl Pty {
         match run_instruction {
             Some(Run::Command(mut command)) => {
                 let starts_held = command.hold_on_start;
-                let hold_on_close = command.hold_on_close;
+                let hold_on_close = command.holds_pane_on_exit();
                 let quit_cb = Box::new({
                     let senders = self.bus.senders.clone();
                     move |pane_id, exit_status, command| {
@@ -1817,6 +2022,18 @@ impl Pty {
                 self.terminal_cwds.remove(&id);
                 self.terminal_cmds.remove(&id);
                 self.terminal_foreground_cmds.remove(&id);
+                if let Some(recordings) = self.bus.os_input.as_ref().and_then(|o| o.recordings()) {
+                    if recordings.stop(id) {
+                        let _ = self
+                            .bus
+                            .senders
+                            .send_to_plugin(PluginInstruction::Update(vec![(
+                                None,
+                                None,
+                                Event::RecordingStateChanged(recordings.recorded_terminal_ids()),
+                            )]));
+                    }
+                }
                 self.bus
                     .os_input
                     .as_ref()
@@ -1859,7 +2076,7 @@ impl Pty {
                 let _ = self.task_handles.remove(&id); // if all is well, this shouldn't be here
                 let _ = self.id_to_child_pid.remove(&id); // if all is wlel, this shouldn't be here
 
-                let hold_on_close = run_command.hold_on_close;
+                let hold_on_close = run_command.holds_pane_on_exit();
                 let originating_plugin = Arc::new(run_command.originating_plugin.clone());
                 let quit_cb = Box::new({
                     let senders = self.bus.senders.clone();
@@ -1910,12 +2127,20 @@ impl Pty {
                     let senders = self.bus.senders.clone();
                     let debug_to_file = self.debug_to_file;
                     let activity_flag = activity_flag.clone();
+                    let recordings = self.bus.os_input.as_ref().and_then(|o| o.recordings());
                     async move {
-                        TerminalBytes::new(id, reader, senders, debug_to_file, activity_flag)
-                            .listen()
-                            .await
-                            .with_context(|| err_context(pane_id))
-                            .fatal();
+                        TerminalBytes::new(
+                            id,
+                            reader,
+                            senders,
+                            debug_to_file,
+                            activity_flag,
+                            recordings,
+                        )
+                        .listen()
+                        .await
+                        .with_context(|| err_context(pane_id))
+                        .fatal();
                     }
                 });
 
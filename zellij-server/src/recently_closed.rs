@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+use zellij_utils::data::{FloatingPaneCoordinates, NewPanePlacement, RecentlyClosedInfo};
+use zellij_utils::input::layout::{FloatingPaneLayout, Run, TiledPaneLayout};
+use zellij_utils::pane_size::PaneGeom;
+
+// older entries are dropped so that a long running session does not accumulate these forever
+const MAX_RECENTLY_CLOSED: usize = 20;
+
+/// What we need in order to bring back a pane that was closed by the user
+#[derive(Debug, Clone)]
+pub struct ClosedPane {
+    pub run: Option<Run>, // None is the default shell
+    pub title: Option<String>,
+    pub geom: PaneGeom,
+    pub is_floating: bool,
+    pub is_borderless: bool,
+    pub tab_id: usize,
+}
+
+impl ClosedPane {
+    pub fn name(&self) -> String {
+        self.title
+            .clone()
+            .or_else(|| {
+                self.run.as_ref().and_then(|run| match run {
+                    Run::Command(run_command) => Some(run_command.to_string()),
+                    Run::EditFile(path, _, _) => path
+                        .file_name()
+                        .map(|file_name| file_name.to_string_lossy().to_string()),
+                    Run::Plugin(plugin) => Some(plugin.location_string()),
+                    Run::Cwd(_) => None,
+                })
+            })
+            .unwrap_or_else(|| String::from("Pane"))
+    }
+    pub fn is_plugin(&self) -> bool {
+        matches!(self.run, Some(Run::Plugin(_)))
+    }
+    pub fn floating_pane_coordinates(&self) -> Option<FloatingPaneCoordinates> {
+        if self.is_floating {
+            Some(self.geom.into())
+        } else {
+            None
+        }
+    }
+    pub fn new_pane_placement(&self) -> NewPanePlacement {
+        match self.floating_pane_coordinates() {
+            Some(floating_pane_coordinates) => {
+                NewPanePlacement::Floating(Some(floating_pane_coordinates))
+            },
+            None => NewPanePlacement::Tiled {
+                direction: None,
+                borderless: Some(self.is_borderless),
+            },
+        }
+    }
+}
+
+/// A closed tab, as the layout it would be serialized to
+#[derive(Debug, Clone)]
+pub struct ClosedTab {
+    pub name: String,
+    pub position: usize,
+    pub tiled_panes: TiledPaneLayout,
+    pub floating_panes: Vec<FloatingPaneLayout>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ClosedItem {
+    Pane(ClosedPane),
+    Tab(ClosedTab),
+}
+
+impl ClosedItem {
+    fn info(&self, id: u32) -> RecentlyClosedInfo {
+        match self {
+            ClosedItem::Pane(closed_pane) => RecentlyClosedInfo {
+                id,
+                is_tab: false,
+                name: closed_pane.name(),
+            },
+            ClosedItem::Tab(closed_tab) => RecentlyClosedInfo {
+                id,
+                is_tab: true,
+                name: closed_tab.name.clone(),
+            },
+        }
+    }
+}
+
+/// The panes and tabs closed in this session, most recent last
+#[derive(Debug, Default)]
+pub struct RecentlyClosed {
+    items: VecDeque<(u32, ClosedItem)>,
+    next_id: u32,
+}
+
+impl RecentlyClosed {
+    pub fn push(&mut self, closed_item: ClosedItem) {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.items.push_back((id, closed_item));
+        if self.items.len() > MAX_RECENTLY_CLOSED {
+            self.items.pop_front();
+        }
+    }
+    pub fn take_last_pane(&mut self) -> Option<ClosedPane> {
+        let index = self
+            .items
+            .iter()
+            .rposition(|(_, item)| matches!(item, ClosedItem::Pane(_)))?;
+        match self.items.remove(index) {
+            Some((_, ClosedItem::Pane(closed_pane))) => Some(closed_pane),
+            _ => None,
+        }
+    }
+    pub fn take_last_tab(&mut self) -> Option<ClosedTab> {
+        let index = self
+            .items
+            .iter()
+            .rposition(|(_, item)| matches!(item, ClosedItem::Tab(_)))?;
+        match self.items.remove(index) {
+            Some((_, ClosedItem::Tab(closed_tab))) => Some(closed_tab),
+            _ => None,
+        }
+    }
+    pub fn take(&mut self, id: u32) -> Option<ClosedItem> {
+        let index = self.items.iter().position(|(item_id, _)| *item_id == id)?;
+        self.items.remove(index).map(|(_, closed_item)| closed_item)
+    }
+    /// Most recently closed first
+    pub fn list(&self) -> Vec<RecentlyClosedInfo> {
+        self.items
+            .iter()
+            .rev()
+            .map(|(id, closed_item)| closed_item.info(*id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/recently_closed_tests.rs"]
+mod recently_closed_tests;
@@ -0,0 +1,326 @@
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// Recordings are written in the asciicast v2 format (https://docs.asciinema.org/manual/asciicast/v2/):
+// a JSON header line followed by one JSON array per event - `[time, "o", data]` for output and
+// `[time, "r", "COLSxROWS"]` for resizes. The output is captured straight from the pty reader,
+// so the recording can be replayed (eg. with `asciinema play`) independently of Zellij.
+
+// events are buffered, but the file should still follow the recording closely
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct AsciicastRecorder {
+    file: BufWriter<File>,
+    started_at: Instant,
+    last_flushed_at: Instant,
+    // bytes at the end of the last read that are the start of a multi-byte UTF-8 character -
+    // asciicast events are JSON strings, so these wait for the next read to complete them
+    incomplete_utf8: Vec<u8>,
+}
+
+impl AsciicastRecorder {
+    pub fn new(
+        output: &Path,
+        cols: usize,
+        rows: usize,
+        title: Option<&str>,
+        initial_output: &str,
+    ) -> io::Result<Self> {
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = BufWriter::new(File::create(output)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let mut header = json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": timestamp,
+        });
+        if let Some(title) = title {
+            header["title"] = json!(title);
+        }
+        writeln!(file, "{}", header)?;
+        let mut recorder = AsciicastRecorder {
+            file,
+            started_at: Instant::now(),
+            last_flushed_at: Instant::now(),
+            incomplete_utf8: vec![],
+        };
+        if !initial_output.is_empty() {
+            recorder.write_event("o", initial_output)?;
+        }
+        Ok(recorder)
+    }
+    pub fn record_output(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let mut bytes_to_record = std::mem::take(&mut self.incomplete_utf8);
+        bytes_to_record.extend_from_slice(bytes);
+        let complete_len = match std::str::from_utf8(&bytes_to_record) {
+            Ok(_) => bytes_to_record.len(),
+            // error_len() is None when the bytes end in the middle of a character
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => bytes_to_record.len(),
+        };
+        self.incomplete_utf8 = bytes_to_record.split_off(complete_len);
+        if bytes_to_record.is_empty() {
+            return Ok(());
+        }
+        let output = String::from_utf8_lossy(&bytes_to_record);
+        self.write_event("o", &output)
+    }
+    pub fn record_resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.write_event("r", &format!("{}x{}", cols, rows))
+    }
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.last_flushed_at = Instant::now();
+        self.file.flush()
+    }
+    fn write_event(&mut self, event_type: &str, data: &str) -> io::Result<()> {
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+        writeln!(self.file, "{}", json!([elapsed, event_type, data]))?;
+        if self.last_flushed_at.elapsed() >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// When recording a whole tab, each terminal pane gets its own file next to the requested one,
+/// eg. `demo.cast` becomes `demo-terminal_3.cast`
+pub fn recording_path_for_pane(output: &Path, terminal_id: u32) -> PathBuf {
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match output.extension() {
+        Some(extension) => format!(
+            "{}-terminal_{}.{}",
+            stem,
+            terminal_id,
+            extension.to_string_lossy()
+        ),
+        None => format!("{}-terminal_{}", stem, terminal_id),
+    };
+    output.with_file_name(file_name)
+}
+
+/// The panes that started recording and those that failed to, when starting several at once
+#[derive(Debug, Default)]
+pub struct StartedRecordings {
+    pub started: Vec<(u32, PathBuf)>, // terminal id and the file it is recorded to
+    pub failed: Vec<String>,
+}
+
+/// The recordings currently in progress, keyed by terminal pane id. Shared between the pty
+/// readers (output), the os input (resizes) and screen (starting/stopping recordings).
+/// Each recording has its own lock, so that the pty readers of different panes do not wait
+/// for each other to write.
+#[derive(Clone, Default)]
+pub struct Recordings {
+    recorders: Arc<Mutex<BTreeMap<u32, Arc<Mutex<AsciicastRecorder>>>>>,
+}
+
+impl Recordings {
+    pub fn start(
+        &self,
+        terminal_id: u32,
+        output: &Path,
+        cols: usize,
+        rows: usize,
+        title: Option<&str>,
+        initial_output: &str,
+    ) -> io::Result<()> {
+        let mut recorders = self.recorders.lock().unwrap();
+        if recorders.contains_key(&terminal_id) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("terminal_{} is already being recorded", terminal_id),
+            ));
+        }
+        let recorder = AsciicastRecorder::new(output, cols, rows, title, initial_output)?;
+        recorders.insert(terminal_id, Arc::new(Mutex::new(recorder)));
+        Ok(())
+    }
+    /// Returns true if the pane was being recorded
+    pub fn stop(&self, terminal_id: u32) -> bool {
+        let recorder = self.recorders.lock().unwrap().remove(&terminal_id);
+        match recorder {
+            Some(recorder) => {
+                if let Err(e) = recorder.lock().unwrap().flush() {
+                    log::error!(
+                        "Failed to write the end of the recording of terminal_{}: {}",
+                        terminal_id,
+                        e
+                    );
+                }
+                true
+            },
+            None => false,
+        }
+    }
+    pub fn is_recording(&self, terminal_id: u32) -> bool {
+        self.recorders.lock().unwrap().contains_key(&terminal_id)
+    }
+    pub fn recorded_terminal_ids(&self) -> Vec<u32> {
+        self.recorders.lock().unwrap().keys().copied().collect()
+    }
+    pub fn record_output(&self, terminal_id: u32, bytes: &[u8]) {
+        self.record(terminal_id, |recorder| recorder.record_output(bytes));
+    }
+    pub fn record_resize(&self, terminal_id: u32, cols: u16, rows: u16) {
+        self.record(terminal_id, |recorder| recorder.record_resize(cols, rows));
+    }
+    fn record(
+        &self,
+        terminal_id: u32,
+        write: impl FnOnce(&mut AsciicastRecorder) -> io::Result<()>,
+    ) {
+        // only hold the lock of all the recordings long enough to find this one
+        let recorder = self.recorders.lock().unwrap().get(&terminal_id).cloned();
+        if let Some(recorder) = recorder {
+            let result = write(&mut recorder.lock().unwrap());
+            if let Err(e) = result {
+                log::error!(
+                    "Failed to write recording of terminal_{}, stopping it: {}",
+                    terminal_id,
+                    e
+                );
+                let mut recorders = self.recorders.lock().unwrap();
+                // unless it was stopped (and maybe started again) in the meantime
+                if recorders
+                    .get(&terminal_id)
+                    .map_or(false, |current| Arc::ptr_eq(current, &recorder))
+                {
+                    recorders.remove(&terminal_id);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use tempfile::tempdir;
+
+    fn read_recording(path: &Path) -> Vec<Value> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn recording_has_asciicast_header_and_events() {
+        let dir = tempdir().unwrap();
+        let output = dir.path().join("nested").join("demo.cast");
+        let recordings = Recordings::default();
+        recordings
+            .start(3, &output, 80, 24, Some("demo"), "$ ")
+            .unwrap();
+        recordings.record_output(3, b"ls\r\n");
+        recordings.record_resize(3, 100, 30);
+        recordings.record_output(4, b"not recorded");
+        assert_eq!(recordings.recorded_terminal_ids(), vec![3]);
+        assert!(recordings.stop(3));
+        assert!(!recordings.stop(3));
+        recordings.record_output(3, b"after stop");
+
+        let lines = read_recording(&output);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 80);
+        assert_eq!(lines[0]["height"], 24);
+        assert_eq!(lines[0]["title"], "demo");
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "$ ");
+        assert_eq!(lines[2][1], "o");
+        assert_eq!(lines[2][2], "ls\r\n");
+        assert_eq!(lines[3][1], "r");
+        assert_eq!(lines[3][2], "100x30");
+        assert!(lines[3][0].as_f64().unwrap() >= lines[2][0].as_f64().unwrap());
+    }
+
+    #[test]
+    fn multi_byte_characters_split_across_reads_are_kept_whole() {
+        let dir = tempdir().unwrap();
+        let output = dir.path().join("demo.cast");
+        let recordings = Recordings::default();
+        recordings.start(1, &output, 80, 24, None, "").unwrap();
+        let bytes = "héllo".as_bytes();
+        recordings.record_output(1, &bytes[..2]);
+        recordings.record_output(1, &bytes[2..]);
+        recordings.stop(1);
+
+        let lines = read_recording(&output);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].get("title").is_none());
+        assert_eq!(lines[1][2], "h");
+        assert_eq!(lines[2][2], "éllo");
+    }
+
+    #[test]
+    fn panes_are_recorded_without_waiting_for_each_other() {
+        let dir = tempdir().unwrap();
+        let recordings = Recordings::default();
+        recordings
+            .start(1, &dir.path().join("first.cast"), 80, 24, None, "")
+            .unwrap();
+        recordings
+            .start(2, &dir.path().join("second.cast"), 80, 24, None, "")
+            .unwrap();
+        let first_recorder = recordings.recorders.lock().unwrap()[&1].clone();
+        let writing_first_recording = first_recorder.lock().unwrap();
+        // would wait forever if all the recordings shared one lock
+        recordings.record_output(2, b"ls\r\n");
+        drop(writing_first_recording);
+        recordings.record_output(1, b"pwd\r\n");
+        recordings.stop(1);
+        recordings.stop(2);
+
+        assert_eq!(
+            read_recording(&dir.path().join("first.cast"))[1][2],
+            "pwd\r\n"
+        );
+        assert_eq!(
+            read_recording(&dir.path().join("second.cast"))[1][2],
+            "ls\r\n"
+        );
+    }
+
+    #[test]
+    fn whole_tab_recordings_get_one_file_per_pane() {
+        assert_eq!(
+            recording_path_for_pane(Path::new("/tmp/demo.cast"), 3),
+            PathBuf::from("/tmp/demo-terminal_3.cast")
+        );
+        assert_eq!(
+            recording_path_for_pane(Path::new("/tmp/demo"), 1),
+            PathBuf::from("/tmp/demo-terminal_1")
+        );
+    }
+
+    #[test]
+    fn cannot_record_the_same_pane_twice() {
+        let dir = tempdir().unwrap();
+        let recordings = Recordings::default();
+        recordings
+            .start(1, &dir.path().join("first.cast"), 80, 24, None, "")
+            .unwrap();
+        assert!(recordings
+            .start(1, &dir.path().join("second.cast"), 80, 24, None, "")
+            .is_err());
+        assert!(recordings.is_recording(1));
+        assert!(!dir.path().join("second.cast").exists());
+    }
+}
@@ -6,27 +6,28 @@ use crate::global_async_runtime::get_tokio_runtime;
 use crate::thread_bus::ThreadSenders;
 use crate::{
     os_input_output::ServerOsApi,
-    panes::PaneId,
+    panes::{kitty_keyboard::KittyKeyEvent, PaneId},
     plugins::PluginInstruction,
     pty::{ClientTabIndexOrPaneId, PtyInstruction},
     screen::ScreenInstruction,
     ServerInstruction, SessionMetaData, SessionState,
 };
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 use zellij_utils::{
     channels::SenderWithContext,
     data::{
-        BareKey, ConnectToSession, Direction, Event, InputMode, KeyModifier, ListPanesResponse,
-        ListTabsResponse, NewPanePlacement, PaneListEntry, ResizeStrategy, TabInfo,
-        UnblockCondition,
+        BareKey, ConnectToSession, Direction, Event, InputMode, KeyModifier, KeyWithModifier,
+        ListPanesResponse, ListTabsResponse, NewPanePlacement, PaneListEntry, ResizeStrategy,
+        TabInfo, UnblockCondition,
     },
     envs,
     errors::prelude::*,
     input::{
         actions::{Action, SearchDirection, SearchOption},
         command::TerminalAction,
+        keybinds::PendingKey,
     },
     ipc::{ClientToServerMsg, ExitReason, IpcReceiverWithContext, ServerToClientMsg},
 };
@@ -580,6 +581,81 @@ pub(crate) fn route_action(
                 ))
                 .with_context(err_context)?;
         },
+        Action::ScrollToPreviousPrompt => {
+            senders
+                .send_to_screen(ScreenInstruction::ScrollToPreviousPrompt(
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
+        Action::ScrollToNextPrompt => {
+            senders
+                .send_to_screen(ScreenInstruction::ScrollToNextPrompt(
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
+        Action::SelectLastCommandOutput => {
+            senders
+                .send_to_screen(ScreenInstruction::SelectLastCommandOutput(
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
+        Action::CopyLastCommandOutput => {
+            senders
+                .send_to_screen(ScreenInstruction::CopyLastCommandOutput(
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
+        Action::DumpCommandOutput {
+            file_path,
+            pane_id,
+            index,
+        } => {
+            senders
+                .send_to_screen(ScreenInstruction::DumpCommandOutput(
+                    file_path,
+                    client_id,
+                    pane_id.map(|p| p.into()),
+                    index,
+                    Some(NotificationEnd::new(completion_tx)),
+                    cli_client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::StartRecording {
+            output,
+            pane_id,
+            whole_tab,
+        } => {
+            senders
+                .send_to_screen(ScreenInstruction::StartRecording(
+                    output,
+                    client_id,
+                    pane_id.map(|p| p.into()),
+                    whole_tab,
+                    Some(NotificationEnd::new(completion_tx)),
+                    cli_client_id,
+                ))
+                .with_context(err_context)?;
+        },
+        Action::StopRecording { pane_id, whole_tab } => {
+            senders
+                .send_to_screen(ScreenInstruction::StopRecording(
+                    client_id,
+                    pane_id.map(|p| p.into()),
+                    whole_tab,
+                    Some(NotificationEnd::new(completion_tx)),
+                    cli_client_id,
+                ))
+                .with_context(err_context)?;
+        },
         Action::ToggleFocusFullscreen => {
             senders
                 .send_to_screen(ScreenInstruction::ToggleActiveTerminalFullscreen(
@@ -1028,6 +1104,40 @@ pub(crate) fn route_action(
                 ))
                 .with_context(err_context)?;
         },
+        Action::ReopenClosedPane => {
+            senders
+                .send_to_screen(ScreenInstruction::ReopenClosedPane(
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
+        Action::ReopenClosedTab => {
+            senders
+                .send_to_screen(ScreenInstruction::ReopenClosedTab(
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
+        Action::ToggleScratchpad {
+            name,
+            command,
+            coordinates,
+        } => {
+            let run_cmd = command
+                .map(|cmd| TerminalAction::RunCommand(cmd.into()))
+                .or_else(|| default_shell.clone());
+            senders
+                .send_to_screen(ScreenInstruction::ToggleScratchpad(
+                    name,
+                    run_cmd,
+                    coordinates,
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
         Action::GoToTab { index } => {
             senders
                 .send_to_screen(ScreenInstruction::GoToTab(
@@ -1246,11 +1356,32 @@ pub(crate) fn route_action(
                 SearchOption::Wrap => {
                     ScreenInstruction::SearchToggleWrap(client_id, notification_end)
                 },
+                SearchOption::Regex => {
+                    ScreenInstruction::SearchToggleRegex(client_id, notification_end)
+                },
             };
             senders
                 .send_to_screen(instruction)
                 .with_context(err_context)?;
         },
+        Action::CopyModeMove { motion } => {
+            senders
+                .send_to_screen(ScreenInstruction::CopyModeMove(
+                    motion,
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
+        Action::CopyModeSelect { kind } => {
+            senders
+                .send_to_screen(ScreenInstruction::CopyModeSelect(
+                    kind,
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
         Action::ToggleMouseMode => {}, // Handled client side
         Action::PreviousSwapLayout => {
             senders
@@ -1422,6 +1553,11 @@ pub(crate) fn route_action(
         Action::CloseTerminalPane {
             pane_id: terminal_pane_id,
         } => {
+            senders
+                .send_to_screen(ScreenInstruction::RememberClosedPane(PaneId::Terminal(
+                    terminal_pane_id,
+                )))
+                .with_context(err_context)?;
             senders
                 .send_to_screen(ScreenInstruction::ClosePane(
                     PaneId::Terminal(terminal_pane_id),
@@ -1435,6 +1571,11 @@ pub(crate) fn route_action(
         Action::ClosePluginPane {
             pane_id: plugin_pane_id,
         } => {
+            senders
+                .send_to_screen(ScreenInstruction::RememberClosedPane(PaneId::Plugin(
+                    plugin_pane_id,
+                )))
+                .with_context(err_context)?;
             senders
                 .send_to_screen(ScreenInstruction::ClosePane(
                     PaneId::Plugin(plugin_pane_id),
@@ -1812,6 +1953,30 @@ pub(crate) fn route_action(
                 ))
                 .with_context(err_context)?;
         },
+        Action::ToggleSyncGroup => {
+            senders
+                .send_to_screen(ScreenInstruction::ToggleSyncGroup(
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
+        Action::ShowPaneHints => {
+            senders
+                .send_to_screen(ScreenInstruction::ShowPaneHints(
+                    client_id,
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
+        Action::SetSyncGroup { pane_ids } => {
+            senders
+                .send_to_screen(ScreenInstruction::SetSyncGroup(
+                    pane_ids.iter().map(|p| PaneId::from(*p)).collect(),
+                    Some(NotificationEnd::new(completion_tx)),
+                ))
+                .with_context(err_context)?;
+        },
         Action::ShowFloatingPanes { tab_id } => {
             senders
                 .send_to_screen(ScreenInstruction::ShowFloatingPanes {
@@ -2141,6 +2306,98 @@ macro_rules! send_to_screen_or_retry_queue {
     }};
 }
 
+/// The keys of a multi-key sequence a client pressed so far
+#[derive(Debug, Default)]
+struct PendingKeySequence {
+    keys: Vec<PendingKey>,
+    // the keys are flushed at this time unless the client presses another key before it
+    flush_at: Option<Instant>,
+}
+
+impl PendingKeySequence {
+    pub fn keys_with_modifier(&self) -> Vec<KeyWithModifier> {
+        self.keys
+            .iter()
+            .map(|k| k.key_with_modifier.clone())
+            .collect()
+    }
+}
+
+// returns true if the route thread should break
+fn route_key_actions(
+    actions: Vec<Action>,
+    client_id: ClientId,
+    senders: ThreadSenders,
+    default_shell: Option<TerminalAction>,
+    mut seen_cli_pipes: Option<&mut HashSet<String>>,
+    client_input_mode: InputMode,
+    os_input: Box<dyn ServerOsApi>,
+) -> bool {
+    let mut should_break = false;
+    for action in actions {
+        // Send user input to plugin thread for logging
+        let _ = senders.send_to_plugin(PluginInstruction::UserInput {
+            client_id,
+            action: action.clone(),
+            terminal_id: None,
+            cli_client_id: None,
+        });
+
+        match route_action(
+            action,
+            client_id,
+            None,
+            None,
+            senders.clone(),
+            default_shell.clone(),
+            seen_cli_pipes.as_deref_mut(),
+            client_input_mode,
+            Some(os_input.clone()),
+        ) {
+            Ok(route_action_should_break) => {
+                if route_action_should_break.0 {
+                    should_break = true;
+                }
+            },
+            Err(e) => {
+                log::error!("{}", e);
+            },
+        }
+    }
+    should_break
+}
+
+// the client did not press another key within the timeout, so the keys of the unfinished
+// sequence are handled as if they were pressed on their own
+fn flush_pending_key_sequence(
+    pending_key_sequence: &mut PendingKeySequence,
+    session_data: &Arc<RwLock<Option<SessionMetaData>>>,
+    os_input: Box<dyn ServerOsApi>,
+    client_id: ClientId,
+) {
+    pending_key_sequence.flush_at = None;
+    let dispatch_inputs = session_data.read().unwrap().as_ref().and_then(|s| {
+        let (kb, im, dim) = s.get_client_keybinds_and_mode(&client_id)?;
+        let actions = kb.flush_pending_keys(im, &mut pending_key_sequence.keys, dim);
+        Some((s.senders.clone(), s.default_shell.clone(), dim, actions))
+    });
+    if let Some((senders, default_shell, client_input_mode, actions)) = dispatch_inputs {
+        let _ = senders.send_to_screen(ScreenInstruction::UpdatePendingKeySequence(
+            vec![],
+            client_id,
+        ));
+        route_key_actions(
+            actions,
+            client_id,
+            senders,
+            default_shell,
+            None,
+            client_input_mode,
+            os_input,
+        );
+    }
+}
+
 pub(crate) fn route_thread_main(
     session_data: Arc<RwLock<Option<SessionMetaData>>>,
     session_state: Arc<RwLock<SessionState>>,
@@ -2153,8 +2410,42 @@ pub(crate) fn route_thread_main(
     let err_context = || format!("failed to handle instruction for client {client_id}");
     let mut seen_cli_pipes = HashSet::new();
     let mut consecutive_unknown_messages_received = 0;
+    let mut pending_key_sequence = PendingKeySequence::default();
+    // messages are read on their own thread so that we can stop waiting for them to flush an
+    // unfinished key sequence when its timeout expires
+    let (client_messages_sender, client_messages) = crossbeam::channel::unbounded();
+    thread::Builder::new()
+        .name("client_message_reader".to_string())
+        .spawn(move || loop {
+            if client_messages_sender
+                .send(receiver.recv_client_msg())
+                .is_err()
+            {
+                break;
+            }
+        })
+        .with_context(err_context)?;
     'route_loop: loop {
-        match receiver.recv_client_msg() {
+        let client_message = match pending_key_sequence.flush_at {
+            Some(flush_at) => match client_messages.recv_deadline(flush_at) {
+                Ok(client_message) => client_message,
+                Err(crossbeam::channel::RecvTimeoutError::Timeout) => {
+                    flush_pending_key_sequence(
+                        &mut pending_key_sequence,
+                        &session_data,
+                        os_input.clone(),
+                        client_id,
+                    );
+                    continue;
+                },
+                Err(crossbeam::channel::RecvTimeoutError::Disconnected) => break 'route_loop,
+            },
+            None => match client_messages.recv() {
+                Ok(client_message) => client_message,
+                Err(_) => break 'route_loop,
+            },
+        };
+        match client_message {
             Some((instruction, err_ctx)) => {
                 consecutive_unknown_messages_received = 0;
                 err_ctx.update_thread_ctx();
@@ -2233,54 +2524,90 @@ pub(crate) fn route_thread_main(
                                 session_data.read().unwrap().as_ref().and_then(|s| {
                                     let (kb, im, dim) =
                                         s.get_client_keybinds_and_mode(&client_id)?;
-                                    let actions: Vec<Action> = kb
-                                        .get_actions_for_key_in_mode_or_default_action(
+                                    let is_key_press = !is_kitty_keyboard_protocol
+                                        || KittyKeyEvent::parse(&raw_bytes)
+                                            .map(|key_event| key_event.is_key_press())
+                                            .unwrap_or(true);
+                                    let had_pending_keys = !pending_key_sequence.keys.is_empty();
+                                    let actions: Vec<Action> = if is_key_press {
+                                        kb.get_actions_for_key_sequence_in_mode_or_default_action(
                                             im,
+                                            &mut pending_key_sequence.keys,
                                             &key,
                                             raw_bytes,
                                             dim,
                                             is_kitty_keyboard_protocol,
-                                        );
+                                        )
+                                    } else {
+                                        // key releases and lone modifiers don't trigger
+                                        // keybindings, they are only written to panes that
+                                        // asked for them
+                                        match kb.default_action_for_mode(
+                                            im,
+                                            Some(&key),
+                                            raw_bytes,
+                                            dim,
+                                            is_kitty_keyboard_protocol,
+                                        ) {
+                                            action @ Action::Write { .. } => vec![action],
+                                            _ => vec![],
+                                        }
+                                    };
+                                    let pending_keys = if had_pending_keys
+                                        || !pending_key_sequence.keys.is_empty()
+                                    {
+                                        Some(pending_key_sequence.keys_with_modifier())
+                                    } else {
+                                        None
+                                    };
                                     Some((
                                         s.senders.clone(),
                                         s.default_shell.clone(),
                                         s.session_configuration
                                             .get_client_default_input_mode(&client_id),
                                         actions,
+                                        is_key_press,
+                                        pending_keys,
+                                        s.session_configuration
+                                            .get_client_key_sequence_timeout(&client_id),
                                     ))
                                 });
-                            if let Some((senders, default_shell, client_input_mode, actions)) =
-                                dispatch_inputs
+                            if let Some((
+                                senders,
+                                default_shell,
+                                client_input_mode,
+                                actions,
+                                is_key_press,
+                                pending_keys,
+                                key_sequence_timeout,
+                            )) = dispatch_inputs
                             {
-                                for action in actions {
-                                    // Send user input to plugin thread for logging
-                                    let _ = senders.send_to_plugin(PluginInstruction::UserInput {
-                                        client_id,
-                                        action: action.clone(),
-                                        terminal_id: None,
-                                        cli_client_id: None,
-                                    });
-
-                                    match route_action(
-                                        action,
-                                        client_id,
-                                        None,
-                                        None,
-                                        senders.clone(),
-                                        default_shell.clone(),
-                                        Some(&mut seen_cli_pipes),
-                                        client_input_mode,
-                                        Some(os_input.clone()),
-                                    ) {
-                                        Ok(route_action_should_break) => {
-                                            if route_action_should_break.0 {
-                                                should_break = true;
-                                            }
-                                        },
-                                        Err(e) => {
-                                            log::error!("{}", e);
-                                        },
+                                if let Some(pending_keys) = pending_keys {
+                                    // every key press restarts the timeout, cancelling the
+                                    // previous one
+                                    if pending_keys.is_empty() {
+                                        pending_key_sequence.flush_at = None;
+                                    } else if is_key_press {
+                                        pending_key_sequence.flush_at =
+                                            Some(Instant::now() + key_sequence_timeout);
                                     }
+                                    let _ = senders.send_to_screen(
+                                        ScreenInstruction::UpdatePendingKeySequence(
+                                            pending_keys,
+                                            client_id,
+                                        ),
+                                    );
+                                }
+                                if route_key_actions(
+                                    actions,
+                                    client_id,
+                                    senders,
+                                    default_shell,
+                                    Some(&mut seen_cli_pipes),
+                                    client_input_mode,
+                                    os_input.clone(),
+                                ) {
+                                    should_break = true;
                                 }
                             }
                         },
@@ -2589,6 +2916,15 @@ pub(crate) fn route_thread_main(
                                 retry_queue
                             );
                         },
+                        ClientToServerMsg::KittyGraphicsSupported => {
+                            send_to_screen_or_retry_queue!(
+                                senders,
+                                ScreenInstruction::KittyGraphicsSupported(client_id),
+                                instruction,
+                                retry_queue
+                            )
+                            .with_context(err_context)?;
+                        },
                         ClientToServerMsg::SubscribeToPaneRenders {
                             ref pane_ids,
                             ref scrollback,
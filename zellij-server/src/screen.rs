@@ -29,7 +29,7 @@
 //! - `tab_history: BTreeMap<ClientId, Vec<usize>>`: History of tab IDs per client
 
 use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::net::{IpAddr, Ipv4Addr};
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -40,13 +40,15 @@ use crate::route::NotificationEnd;
 
 use log::{debug, warn};
 use zellij_utils::data::{
-    CommandOrPlugin, Direction, EventType, FloatingPaneCoordinates, GetFocusedPaneInfoResponse,
-    HostTerminalThemeMode, KeyWithModifier, LayoutInfo, LayoutWithError, ListPanesResponse,
-    ListTabsResponse, NewPanePlacement, PaneContents, PaneInfo, PaneListEntry, PaneManifest,
-    PaneRenderReport, PaneScrollbackResponse, PluginPermission, RegexHighlight, Resize,
-    ResizeStrategy, SessionInfo, Styling, TabInfo, WebSharing,
+    BareKey, CommandOrPlugin, Direction, EventType, FloatingPaneCoordinates,
+    GetFocusedPaneInfoResponse, HostTerminalThemeMode, KeyWithModifier, LayoutInfo,
+    LayoutWithError, ListPanesResponse, ListTabsResponse, NewPanePlacement, PaneContents, PaneInfo,
+    PaneListEntry, PaneManifest, PaneRenderReport, PaneScrollbackResponse, PluginPermission,
+    RecentlyClosedInfo, RegexHighlight, Resize, ResizeStrategy, SessionInfo, Styling, TabInfo,
+    WebSharing,
 };
 use zellij_utils::errors::prelude::*;
+use zellij_utils::input::actions::{CopyModeMotion, CopySelectionKind};
 use zellij_utils::input::command::RunCommand;
 use zellij_utils::input::config::Config;
 use zellij_utils::input::keybinds::Keybinds;
@@ -72,16 +74,19 @@ use crate::pane_groups::PaneGroups;
 use crate::panes::alacritty_functions::xparse_color;
 use crate::panes::terminal_character::AnsiCode;
 use crate::panes::terminal_pane::{BRACKETED_PASTE_BEGIN, BRACKETED_PASTE_END};
+use crate::recently_closed::{ClosedItem, ClosedPane, ClosedTab, RecentlyClosed};
 use crate::session_layout_metadata::{PaneLayoutMetadata, SessionLayoutMetadata};
 
 use crate::{
     output::Output,
+    panes::kitty_graphics::KittyGraphicsHosts,
+    panes::scrollback_store::scrollback_file_name,
     panes::sixel::SixelImageStore,
     panes::PaneId,
     plugins::{DumpSessionLayoutResponse, PluginId, PluginInstruction, PluginRenderAsset},
     pty::{get_default_shell, ClientTabIndexOrPaneId, PtyInstruction, VteBytes},
     pty_writer::PtyWriteInstruction,
-    tab::{SuppressedPanes, Tab},
+    tab::{Pane, SuppressedPanes, Tab},
     thread_bus::Bus,
     ui::loading_indication::LoadingIndication,
     ClientId, ServerInstruction,
@@ -423,6 +428,33 @@ pub enum ScreenInstruction {
     PageScrollDown(ClientId, Option<NotificationEnd>),
     HalfPageScrollUp(ClientId, Option<NotificationEnd>),
     HalfPageScrollDown(ClientId, Option<NotificationEnd>),
+    ScrollToPreviousPrompt(ClientId, Option<NotificationEnd>),
+    ScrollToNextPrompt(ClientId, Option<NotificationEnd>),
+    SelectLastCommandOutput(ClientId, Option<NotificationEnd>),
+    CopyLastCommandOutput(ClientId, Option<NotificationEnd>),
+    DumpCommandOutput(
+        Option<String>,
+        ClientId,
+        Option<PaneId>,
+        usize, // index of the command output, counting back from the last one
+        Option<NotificationEnd>,
+        Option<ClientId>, // cli_client_id - used to send output to the CLI client's STDOUT
+    ),
+    StartRecording(
+        String, // output file
+        ClientId,
+        Option<PaneId>,
+        bool, // whole tab
+        Option<NotificationEnd>,
+        Option<ClientId>, // cli_client_id
+    ),
+    StopRecording(
+        ClientId,
+        Option<PaneId>,
+        bool, // whole tab
+        Option<NotificationEnd>,
+        Option<ClientId>, // cli_client_id
+    ),
     ClearScroll(ClientId),
     CloseFocusedPane(ClientId, Option<NotificationEnd>),
     ToggleActiveTerminalFullscreen(ClientId, Option<NotificationEnd>),
@@ -507,6 +539,8 @@ pub enum ScreenInstruction {
     TerminalBackgroundColor(String),
     TerminalForegroundColor(String),
     TerminalColorRegisters(Vec<(usize, String)>),
+    /// The host terminal of this client supports the kitty graphics protocol
+    KittyGraphicsSupported(ClientId),
     /// A pane's Grid intercepted an app-in-pane whitelisted query; Screen
     /// assigns a token, queues the forward, and dispatches to the client.
     /// `query` carries the classified form so Screen can match on it
@@ -557,6 +591,7 @@ pub enum ScreenInstruction {
         Option<NotificationEnd>,
     ),
     ChangeModeForAllClients(InputMode, Option<InputMode>, Option<NotificationEnd>),
+    UpdatePendingKeySequence(Vec<KeyWithModifier>, ClientId),
     MouseEvent(MouseEvent, ClientId, Option<NotificationEnd>),
     Copy(ClientId, Option<NotificationEnd>),
     AddClient(
@@ -573,6 +608,9 @@ pub enum ScreenInstruction {
     SearchToggleCaseSensitivity(ClientId, Option<NotificationEnd>),
     SearchToggleWholeWord(ClientId, Option<NotificationEnd>),
     SearchToggleWrap(ClientId, Option<NotificationEnd>),
+    SearchToggleRegex(ClientId, Option<NotificationEnd>),
+    CopyModeMove(CopyModeMotion, ClientId, Option<NotificationEnd>),
+    CopyModeSelect(CopySelectionKind, ClientId, Option<NotificationEnd>),
     AddRedPaneFrameColorOverride(Vec<PaneId>, Option<String>), // Option<String> => optional error text
     ClearPaneFrameColorOverride(Vec<PaneId>),
     SetTabBellFlash(usize, bool), // tab_id, is_flashing
@@ -747,6 +785,7 @@ pub enum ScreenInstruction {
         mouse_click_through: bool,
     },
     RerunCommandPane(u32, Option<NotificationEnd>), // u32 - terminal pane id
+    RestartCommandPane(u32, usize), // u32 - terminal pane id, usize - restart attempt
     ResizePaneWithId(ResizeStrategy, PaneId),
     EditScrollbackForPaneWithId(PaneId, Option<NotificationEnd>),
     WriteToPaneId(Vec<u8>, PaneId, Option<NotificationEnd>),
@@ -810,6 +849,42 @@ pub enum ScreenInstruction {
     EmbedMultiplePanes(Vec<PaneId>, ClientId),
     TogglePaneInGroup(ClientId, Option<NotificationEnd>),
     ToggleGroupMarking(ClientId, Option<NotificationEnd>),
+    ToggleSyncGroup(ClientId, Option<NotificationEnd>),
+    SetSyncGroup(Vec<PaneId>, Option<NotificationEnd>),
+    ShowPaneHints(ClientId, Option<NotificationEnd>),
+    ReopenClosedPane(ClientId, Option<NotificationEnd>),
+    ReopenClosedTab(ClientId, Option<NotificationEnd>),
+    ReopenClosed(u32, ClientId), // u32 is the id of the recently closed pane or tab
+    RememberClosedPane(PaneId),
+    AddRecentlyClosed(ClosedItem),
+    RestoreClosedPane(
+        PaneId,
+        Option<RunCommand>, // hold for command
+        ClosedPane,
+        ClientId,
+        Option<NotificationEnd>,
+    ),
+    ListRecentlyClosed {
+        response_channel: crossbeam::channel::Sender<Vec<RecentlyClosedInfo>>,
+    },
+    ToggleScratchpad(
+        String, // scratchpad name
+        Option<TerminalAction>,
+        Option<FloatingPaneCoordinates>,
+        ClientId,
+        Option<NotificationEnd>,
+    ),
+    AddScratchpad(
+        String, // scratchpad name
+        PaneId,
+        Option<RunCommand>, // hold for command
+        Option<Run>,        // invoked with
+        Option<FloatingPaneCoordinates>,
+        usize, // tab id
+        ClientId,
+        Option<NotificationEnd>,
+    ),
+    ScratchpadNotSpawned(String), // scratchpad name
     SessionSharingStatusChange(bool),
     SetMouseSelectionSupport(PaneId, bool),
     InterceptKeyPresses(PluginId, ClientId),
@@ -954,6 +1029,15 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::PageScrollDown(..) => ScreenContext::PageScrollDown,
             ScreenInstruction::HalfPageScrollUp(..) => ScreenContext::HalfPageScrollUp,
             ScreenInstruction::HalfPageScrollDown(..) => ScreenContext::HalfPageScrollDown,
+            ScreenInstruction::ScrollToPreviousPrompt(..) => ScreenContext::ScrollToPreviousPrompt,
+            ScreenInstruction::ScrollToNextPrompt(..) => ScreenContext::ScrollToNextPrompt,
+            ScreenInstruction::SelectLastCommandOutput(..) => {
+                ScreenContext::SelectLastCommandOutput
+            },
+            ScreenInstruction::CopyLastCommandOutput(..) => ScreenContext::CopyLastCommandOutput,
+            ScreenInstruction::DumpCommandOutput(..) => ScreenContext::DumpCommandOutput,
+            ScreenInstruction::StartRecording(..) => ScreenContext::StartRecording,
+            ScreenInstruction::StopRecording(..) => ScreenContext::StopRecording,
             ScreenInstruction::ClearScroll(..) => ScreenContext::ClearScroll,
             ScreenInstruction::CloseFocusedPane(..) => ScreenContext::CloseFocusedPane,
             ScreenInstruction::ToggleActiveTerminalFullscreen(..) => {
@@ -992,6 +1076,7 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::TerminalForegroundColor(..) => {
                 ScreenContext::TerminalForegroundColor
             },
+            ScreenInstruction::KittyGraphicsSupported(..) => ScreenContext::KittyGraphicsSupported,
             ScreenInstruction::TerminalColorRegisters(..) => ScreenContext::TerminalColorRegisters,
             ScreenInstruction::ForwardHostQuery { .. } => ScreenContext::ForwardHostQuery,
             ScreenInstruction::ForwardedReplyFromHost { .. } => {
@@ -1007,6 +1092,9 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::SetLightTheme(..) => ScreenContext::SetLightTheme,
             ScreenInstruction::ToggleTheme(..) => ScreenContext::ToggleTheme,
             ScreenInstruction::ChangeMode(..) => ScreenContext::ChangeMode,
+            ScreenInstruction::UpdatePendingKeySequence(..) => {
+                ScreenContext::UpdatePendingKeySequence
+            },
             ScreenInstruction::ChangeModeForAllClients(..) => {
                 ScreenContext::ChangeModeForAllClients
             },
@@ -1026,6 +1114,9 @@ impl From<&ScreenInstruction> for ScreenContext {
             },
             ScreenInstruction::SearchToggleWholeWord(..) => ScreenContext::SearchToggleWholeWord,
             ScreenInstruction::SearchToggleWrap(..) => ScreenContext::SearchToggleWrap,
+            ScreenInstruction::SearchToggleRegex(..) => ScreenContext::SearchToggleRegex,
+            ScreenInstruction::CopyModeMove(..) => ScreenContext::CopyModeMove,
+            ScreenInstruction::CopyModeSelect(..) => ScreenContext::CopyModeSelect,
             ScreenInstruction::AddRedPaneFrameColorOverride(..) => {
                 ScreenContext::AddRedPaneFrameColorOverride
             },
@@ -1084,6 +1175,7 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::GetCurrentTabInfo { .. } => ScreenContext::GetCurrentTabInfo,
             ScreenInstruction::Reconfigure { .. } => ScreenContext::Reconfigure,
             ScreenInstruction::RerunCommandPane { .. } => ScreenContext::RerunCommandPane,
+            ScreenInstruction::RestartCommandPane(..) => ScreenContext::RestartCommandPane,
             ScreenInstruction::ResizePaneWithId(..) => ScreenContext::ResizePaneWithId,
             ScreenInstruction::EditScrollbackForPaneWithId(..) => {
                 ScreenContext::EditScrollbackForPaneWithId
@@ -1133,6 +1225,19 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::EmbedMultiplePanes(..) => ScreenContext::EmbedMultiplePanes,
             ScreenInstruction::TogglePaneInGroup(..) => ScreenContext::TogglePaneInGroup,
             ScreenInstruction::ToggleGroupMarking(..) => ScreenContext::ToggleGroupMarking,
+            ScreenInstruction::ToggleSyncGroup(..) => ScreenContext::ToggleSyncGroup,
+            ScreenInstruction::SetSyncGroup(..) => ScreenContext::SetSyncGroup,
+            ScreenInstruction::ShowPaneHints(..) => ScreenContext::ShowPaneHints,
+            ScreenInstruction::ReopenClosedPane(..) => ScreenContext::ReopenClosedPane,
+            ScreenInstruction::ReopenClosedTab(..) => ScreenContext::ReopenClosedTab,
+            ScreenInstruction::ReopenClosed(..) => ScreenContext::ReopenClosed,
+            ScreenInstruction::RememberClosedPane(..) => ScreenContext::RememberClosedPane,
+            ScreenInstruction::AddRecentlyClosed(..) => ScreenContext::AddRecentlyClosed,
+            ScreenInstruction::RestoreClosedPane(..) => ScreenContext::RestoreClosedPane,
+            ScreenInstruction::ListRecentlyClosed { .. } => ScreenContext::ListRecentlyClosed,
+            ScreenInstruction::ToggleScratchpad(..) => ScreenContext::ToggleScratchpad,
+            ScreenInstruction::AddScratchpad(..) => ScreenContext::AddScratchpad,
+            ScreenInstruction::ScratchpadNotSpawned(..) => ScreenContext::ScratchpadNotSpawned,
             ScreenInstruction::SessionSharingStatusChange(..) => {
                 ScreenContext::SessionSharingStatusChange
             },
@@ -1371,6 +1476,7 @@ pub(crate) struct Screen {
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
     stacked_resize: Rc<RefCell<bool>>,
     sixel_image_store: Rc<RefCell<SixelImageStore>>,
+    kitty_graphics_hosts: Rc<RefCell<KittyGraphicsHosts>>,
     terminal_emulator_colors: Rc<RefCell<Palette>>,
     terminal_emulator_color_codes: Rc<RefCell<HashMap<usize, String>>>,
     connected_clients: Rc<RefCell<HashMap<ClientId, bool>>>, // bool -> is_web_client
@@ -1389,6 +1495,7 @@ pub(crate) struct Screen {
     session_serialization: bool,
     serialize_pane_viewport: bool,
     scrollback_lines_to_serialize: Option<usize>,
+    serialize_full_scrollback: bool,
     session_is_mirrored: bool,
     copy_options: CopyOptions,
     debug: bool,
@@ -1411,6 +1518,8 @@ pub(crate) struct Screen {
     web_clients_allowed: bool,
     web_sharing: WebSharing,
     current_pane_group: Rc<RefCell<PaneGroups>>,
+    recently_closed: RecentlyClosed,
+    pending_scratchpads: HashSet<String>, // spawned but not yet added to a tab
     advanced_mouse_actions: bool,
     mouse_hover_effects: bool,
     visual_bell: bool,
@@ -1561,6 +1670,7 @@ impl Screen {
             character_cell_size: Rc::new(RefCell::new(None)),
             stacked_resize: Rc::new(RefCell::new(stacked_resize)),
             sixel_image_store: Rc::new(RefCell::new(SixelImageStore::default())),
+            kitty_graphics_hosts: Rc::new(RefCell::new(KittyGraphicsHosts::new())),
             style: client_attributes.style,
             connected_clients: Rc::new(RefCell::new(HashMap::new())),
             active_tab_ids: BTreeMap::new(),
@@ -1586,6 +1696,7 @@ impl Screen {
             session_serialization,
             serialize_pane_viewport,
             scrollback_lines_to_serialize,
+            serialize_full_scrollback: false,
             styled_underlines,
             osc8_hyperlinks,
             arrow_fonts,
@@ -1596,6 +1707,8 @@ impl Screen {
             web_clients_allowed,
             web_sharing,
             current_pane_group: Rc::new(RefCell::new(current_pane_group)),
+            recently_closed: RecentlyClosed::default(),
+            pending_scratchpads: HashSet::new(),
             currently_marking_pane_group: Rc::new(RefCell::new(HashMap::new())),
             advanced_mouse_actions,
             mouse_hover_effects,
@@ -2058,6 +2171,7 @@ impl Screen {
                     .active_tab_ids
                     .get(&client_id)
                     .with_context(err_context)?;
+                self.remember_closed_tab(active_tab_index);
                 self.close_tab_by_id(active_tab_index)
                     .with_context(err_context)
             },
@@ -2167,6 +2281,19 @@ impl Screen {
         }
     }
 
+    /// Kitty graphics images are sent to this client as they are from now on, rather than
+    /// converted to sixel
+    pub fn add_kitty_graphics_host(&mut self, client_id: ClientId) {
+        self.kitty_graphics_hosts
+            .borrow_mut()
+            .entry(client_id)
+            .or_default();
+        // images already on screen were sent as sixel
+        for tab in self.tabs.values_mut() {
+            tab.set_force_render();
+        }
+    }
+
     /// Enqueue a whitelisted host-terminal query from pane `pane_id`.
     /// Queries are serialized globally: at most one is in flight to the
     /// client at a time; the rest wait in `forward_queue`. Returns the
@@ -2510,6 +2637,7 @@ impl Screen {
                 self.styled_underlines,
                 self.osc8_hyperlinks,
             );
+            output.kitty_graphics_hosts = self.kitty_graphics_hosts.clone();
 
             let has_ansi_subscribers = self.pane_render_subscribers.values().any(|s| s.ansi);
             output.collect_ansi_pane_contents =
@@ -2704,6 +2832,19 @@ impl Screen {
     pub fn get_tabs(&self) -> &BTreeMap<usize, Tab> {
         &self.tabs
     }
+    /// Lets plugins know which terminal panes are being recorded (eg. for a REC indicator)
+    fn report_recording_state(&self) {
+        if let Some(recordings) = self.bus.os_input.as_ref().and_then(|o| o.recordings()) {
+            let _ = self
+                .bus
+                .senders
+                .send_to_plugin(PluginInstruction::Update(vec![(
+                    None,
+                    None,
+                    Event::RecordingStateChanged(recordings.recorded_terminal_ids()),
+                )]));
+        }
+    }
 
     /// Returns an immutable reference to this [`Screen`]'s active [`Tab`].
     pub fn get_active_tab(&self, client_id: ClientId) -> Result<&Tab> {
@@ -3166,6 +3307,7 @@ impl Screen {
                 tab.visible(false).with_context(err_context)?;
             }
         }
+        self.kitty_graphics_hosts.borrow_mut().remove(&client_id);
         let previously_active_tab_id = self.active_tab_ids.get(&client_id).copied();
         if let Some(prev_tab_id) = previously_active_tab_id {
             self.global_last_active_tab_id = prev_tab_id;
@@ -3500,8 +3642,9 @@ impl Screen {
     }
     fn dump_layout_to_hd(&mut self) -> Result<()> {
         let err_context = || format!("Failed to log and report session state");
-        let session_layout_metadata =
-            self.get_layout_metadata(Some(self.default_shell.clone()), None);
+        let session_layout_metadata = self
+            .get_layout_metadata_for_resurrection()
+            .with_context(err_context)?;
         self.bus
             .senders
             .send_to_plugin(PluginInstruction::LogLayoutToHd(session_layout_metadata))
@@ -3778,14 +3921,27 @@ impl Screen {
         };
 
         // If we leave the Search-related modes, we need to clear all previous searches
-        let search_related_modes = [InputMode::EnterSearch, InputMode::Search, InputMode::Scroll];
+        let search_related_modes = [
+            InputMode::EnterSearch,
+            InputMode::Search,
+            InputMode::Scroll,
+            InputMode::Copy,
+        ];
         if search_related_modes.contains(&previous_mode)
             && !search_related_modes.contains(&mode_info.mode)
         {
-            active_tab!(self, client_id, |tab: &mut Tab| tab.clear_search(client_id));
+            active_tab!(self, client_id, |tab: &mut Tab| {
+                tab.clear_search(client_id);
+                tab.exit_copy_mode(client_id);
+            });
         }
 
-        if previous_mode == InputMode::Scroll
+        if mode_info.mode == InputMode::Copy {
+            active_tab!(self, client_id, |tab: &mut Tab| tab
+                .enter_copy_mode(client_id));
+        }
+
+        if (previous_mode == InputMode::Scroll || previous_mode == InputMode::Copy)
             && (mode_info.mode == InputMode::Normal || mode_info.mode == InputMode::Locked)
         {
             if let Ok(active_tab) = self.get_active_tab_mut(client_id) {
@@ -3818,6 +3974,34 @@ impl Screen {
             tab.mark_active_pane_for_rerender(client_id);
             tab.update_input_modes()?;
         }
+        self.update_background_plugins_with_mode_info(mode_info, client_id)
+    }
+    pub fn update_pending_key_sequence(
+        &mut self,
+        pending_key_sequence: Vec<KeyWithModifier>,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let mut mode_info = self
+            .mode_info
+            .get(&client_id)
+            .cloned()
+            .unwrap_or_else(|| self.default_mode_info.clone());
+        if mode_info.pending_key_sequence == pending_key_sequence {
+            return Ok(());
+        }
+        mode_info.pending_key_sequence = pending_key_sequence;
+        self.mode_info.insert(client_id, mode_info.clone());
+        for tab in self.tabs.values_mut() {
+            tab.change_mode_info(mode_info.clone(), client_id);
+            tab.update_input_modes()?;
+        }
+        self.update_background_plugins_with_mode_info(mode_info, client_id)
+    }
+    fn update_background_plugins_with_mode_info(
+        &self,
+        mode_info: ModeInfo,
+        client_id: ClientId,
+    ) -> Result<()> {
         // Notify background plugins subscribed to ModeUpdate
         let mut bg_updates = vec![];
         for ((bg_pid, bg_cid), subs) in &self.background_plugin_subscriptions {
@@ -4122,6 +4306,16 @@ impl Screen {
             );
         }
     }
+    pub fn restart_command_pane_with_id(&mut self, terminal_pane_id: u32, attempt: usize) {
+        // the pane might have been closed or manually re-run while we were waiting to restart
+        // it, in which case there is nothing to do
+        for tab in self.tabs.values_mut() {
+            if tab.has_pane_with_pid(&PaneId::Terminal(terminal_pane_id)) {
+                tab.restart_terminal_pane_with_id(terminal_pane_id, attempt);
+                break;
+            }
+        }
+    }
     pub fn resize_pane_with_id(&mut self, resize: ResizeStrategy, pane_id: PaneId) {
         let mut found = false;
         for tab in self.tabs.values_mut() {
@@ -5014,195 +5208,648 @@ impl Screen {
         }
         Ok(())
     }
-    fn get_layout_metadata(
-        &self,
-        default_shell: Option<PathBuf>,
-        tab_index: Option<usize>,
-    ) -> SessionLayoutMetadata {
-        let mut session_layout_metadata = SessionLayoutMetadata::new(self.default_layout.clone());
-        if let Some(default_shell) = default_shell {
-            session_layout_metadata.update_default_shell(default_shell);
-        }
-        let first_client_id = self.get_first_client_id();
-        let active_tab_index =
-            first_client_id.and_then(|client_id| self.active_tab_ids.get(&client_id));
-
-        // Filter tabs based on optional tab_index parameter
-        let tabs_to_process: Vec<_> = self
-            .tabs
-            .iter()
-            .filter(|(idx, _)| tab_index.map_or(true, |target| **idx == target))
-            .collect();
-
-        for (tab_index, tab) in tabs_to_process {
-            let tab_is_focused = active_tab_index == Some(&tab_index);
-            let hide_floating_panes = !tab.are_floating_panes_visible();
-            let mut suppressed_panes = HashMap::new();
-            for (triggering_pane_id, p) in tab.get_suppressed_panes() {
-                suppressed_panes.insert(*triggering_pane_id, p);
-            }
-
-            let all_connected_clients: Vec<ClientId> = self
-                .connected_clients
-                .borrow()
-                .iter()
-                .map(|(c, _i)| *c)
-                .filter(|c| self.active_tab_ids.get(&c) == Some(&tab_index))
-                .collect();
-
-            let mut active_pane_ids: HashMap<ClientId, Option<PaneId>> = HashMap::new();
-            for connected_client_id in &all_connected_clients {
-                active_pane_ids.insert(
-                    *connected_client_id,
-                    tab.get_active_pane_id(*connected_client_id),
-                );
-            }
-
-            let tiled_panes: Vec<PaneLayoutMetadata> = tab
-                .get_tiled_panes()
-                .map(|(pane_id, p)| {
-                    // here we look to see if this pane triggers any suppressed pane,
-                    // and if so we take that suppressed pane - we do this because this
-                    // is currently only the case the scrollback editing panes, and
-                    // when dumping the layout we want the "real" pane and not the
-                    // editor pane
-                    match suppressed_panes.remove(pane_id) {
-                        Some((is_scrollback_editor, suppressed_pane)) if *is_scrollback_editor => {
-                            (suppressed_pane.pid(), suppressed_pane)
-                        },
-                        _ => (*pane_id, p),
-                    }
-                })
-                .map(|(pane_id, p)| {
-                    let focused_clients: Vec<ClientId> = active_pane_ids
-                        .iter()
-                        .filter_map(|(c_id, p_id)| {
-                            p_id.and_then(|p_id| if p_id == pane_id { Some(*c_id) } else { None })
-                        })
-                        .collect();
-                    let (default_fg, default_bg) = p.get_pane_default_colors();
-                    PaneLayoutMetadata::new(
-                        pane_id,
-                        p.position_and_size(),
-                        p.borderless(),
-                        p.invoked_with().clone(),
-                        p.custom_title(),
-                        !focused_clients.is_empty(),
-                        if self.serialize_pane_viewport {
-                            p.serialize(self.scrollback_lines_to_serialize)
-                        } else {
-                            None
-                        },
-                        focused_clients,
-                        default_fg,
-                        default_bg,
-                    )
-                })
-                .collect();
-            let floating_panes: Vec<PaneLayoutMetadata> = tab
-                .get_floating_panes()
-                .map(|(pane_id, p)| {
-                    // here we look to see if this pane triggers any suppressed pane,
-                    // and if so we take that suppressed pane - we do this because this
-                    // is currently only the case the scrollback editing panes, and
-                    // when dumping the layout we want the "real" pane and not the
-                    // editor pane
-                    match suppressed_panes.remove(pane_id) {
-                        Some((is_scrollback_editor, suppressed_pane)) if *is_scrollback_editor => {
-                            (suppressed_pane.pid(), suppressed_pane)
-                        },
-                        _ => (*pane_id, p),
-                    }
-                })
-                .map(|(pane_id, p)| {
-                    let focused_clients: Vec<ClientId> = active_pane_ids
-                        .iter()
-                        .filter_map(|(c_id, p_id)| {
-                            p_id.and_then(|p_id| if p_id == pane_id { Some(*c_id) } else { None })
-                        })
-                        .collect();
-                    let (default_fg, default_bg) = p.get_pane_default_colors();
-                    PaneLayoutMetadata::new(
-                        pane_id,
-                        p.position_and_size(),
-                        false, // floating panes are never borderless
-                        p.invoked_with().clone(),
-                        p.custom_title(),
-                        !focused_clients.is_empty(),
-                        if self.serialize_pane_viewport {
-                            p.serialize(self.scrollback_lines_to_serialize)
-                        } else {
-                            None
-                        },
-                        focused_clients,
-                        default_fg,
-                        default_bg,
-                    )
-                })
-                .collect();
-            session_layout_metadata.add_tab(
-                tab.name.clone(),
-                tab_is_focused,
-                hide_floating_panes,
-                tiled_panes,
-                floating_panes,
-            );
-        }
-        session_layout_metadata
-    }
-    fn update_plugin_loading_stage(
-        &mut self,
-        pid: u32,
-        loading_indication: LoadingIndication,
-    ) -> bool {
-        let all_tabs = self.get_tabs_mut();
-        let mut found_plugin = false;
-        for tab in all_tabs.values_mut() {
-            if tab.has_plugin(pid) {
-                found_plugin = true;
-                tab.update_plugin_loading_stage(pid, loading_indication);
-                break;
-            }
-        }
-        found_plugin
-    }
-    fn connected_clients_contains(&self, client_id: &ClientId) -> bool {
-        self.connected_clients.borrow().contains_key(client_id)
-    }
-    fn get_client_pane_group(&self, client_id: &ClientId) -> HashSet<PaneId> {
-        self.current_pane_group
-            .borrow()
-            .get_client_pane_group(client_id)
-    }
-    fn clear_pane_group(&mut self, client_id: &ClientId) {
-        self.current_pane_group
-            .borrow_mut()
-            .clear_pane_group(client_id);
-        self.currently_marking_pane_group
-            .borrow_mut()
-            .remove(client_id);
-    }
-    fn toggle_pane_id_in_group(&mut self, pane_id: PaneId, client_id: &ClientId) {
+    pub fn toggle_sync_group(&mut self, client_id: ClientId) {
         {
-            let mut pane_groups = self.current_pane_group.borrow_mut();
-            pane_groups.toggle_pane_id_in_group(pane_id, self.size, client_id);
+            // the panes the client marked become the sync group, which ends the marking
+            self.current_pane_group
+                .borrow_mut()
+                .toggle_sync_group(&client_id);
         }
         self.retain_only_existing_panes_in_pane_groups();
+        let _ = self.log_and_report_session_state();
     }
-    fn add_pane_id_to_group(&mut self, pane_id: PaneId, client_id: &ClientId) {
+    pub fn set_sync_group(&mut self, pane_ids: Vec<PaneId>) {
         {
-            let mut pane_groups = self.current_pane_group.borrow_mut();
-            pane_groups.add_pane_id_to_group(pane_id, self.size, client_id);
+            self.current_pane_group
+                .borrow_mut()
+                .set_sync_group(pane_ids);
         }
         self.retain_only_existing_panes_in_pane_groups();
+        let _ = self.log_and_report_session_state();
     }
-    fn add_active_pane_to_group_if_marking(&mut self, client_id: &ClientId) {
-        {
-            if self
-                .currently_marking_pane_group
-                .borrow()
-                .get(client_id)
-                .copied()
+    /// Captures what is needed to reopen this pane later. This must happen before the pane is
+    /// closed, so that the pty thread can still query its process
+    pub fn remember_closed_pane(&self, pane_id: PaneId) {
+        let Some(tab_id) = self
+            .tabs
+            .values()
+            .find(|tab| tab.has_pane_with_pid(&pane_id))
+            .map(|tab| tab.id)
+        else {
+            return;
+        };
+        let with_scrollback_files = false;
+        let mut session_layout_metadata = self.layout_metadata(
+            Some(self.default_shell.clone()),
+            Some(tab_id),
+            with_scrollback_files,
+        );
+        session_layout_metadata.retain_pane(pane_id);
+        self.bus
+            .senders
+            .send_to_pty(PtyInstruction::RememberClosedPane(
+                session_layout_metadata,
+                pane_id,
+                tab_id,
+            ))
+            .with_context(|| format!("failed to remember closed pane {:?}", pane_id))
+            .non_fatal();
+    }
+    fn remember_closed_tab(&self, tab_id: usize) {
+        let Some(position) = self.get_tab_position_by_id(tab_id) else {
+            return;
+        };
+        let with_scrollback_files = false;
+        let session_layout_metadata = self.layout_metadata(
+            Some(self.default_shell.clone()),
+            Some(tab_id),
+            with_scrollback_files,
+        );
+        self.bus
+            .senders
+            .send_to_pty(PtyInstruction::RememberClosedTab(
+                session_layout_metadata,
+                position,
+            ))
+            .with_context(|| format!("failed to remember closed tab {}", tab_id))
+            .non_fatal();
+    }
+    pub fn reopen_closed_pane(
+        &mut self,
+        mut closed_pane: ClosedPane,
+        client_id: ClientId,
+        completion_tx: Option<NotificationEnd>,
+    ) -> Result<()> {
+        let err_context = || String::from("failed to reopen closed pane");
+        let Some(client_id) = self.client_id(client_id) else {
+            return Ok(());
+        };
+        // the pane goes back to its tab if that is still around, otherwise to the current one
+        match self.get_tab_position_by_id(closed_pane.tab_id) {
+            Some(tab_position) => {
+                self.switch_active_tab(tab_position, None, true, client_id)
+                    .with_context(err_context)?;
+            },
+            None => {
+                closed_pane.tab_id = *self
+                    .active_tab_ids
+                    .get(&client_id)
+                    .with_context(err_context)?;
+            },
+        }
+        match closed_pane.run.clone() {
+            Some(Run::Plugin(run_plugin)) => {
+                let should_be_opened_in_place = false;
+                let skip_cache = false;
+                self.bus
+                    .senders
+                    .send_to_pty(PtyInstruction::FillPluginCwd(
+                        Some(closed_pane.is_floating),
+                        should_be_opened_in_place,
+                        false, // close_replaced_pane
+                        closed_pane.title.clone(),
+                        run_plugin,
+                        closed_pane.tab_id,
+                        None,
+                        client_id,
+                        Size::default(),
+                        skip_cache,
+                        None,
+                        Some(true),
+                        closed_pane.floating_pane_coordinates(),
+                        completion_tx,
+                    ))
+                    .with_context(err_context)
+            },
+            _ => self
+                .bus
+                .senders
+                .send_to_pty(PtyInstruction::ReopenClosedPane(
+                    closed_pane,
+                    client_id,
+                    completion_tx,
+                ))
+                .with_context(err_context),
+        }
+    }
+    pub fn restore_closed_pane(
+        &mut self,
+        pid: PaneId,
+        hold_for_command: Option<RunCommand>,
+        closed_pane: ClosedPane,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to restore closed pane {:?}", pid);
+        let client_id = self.client_id(client_id);
+        let Some(tab) = self.tabs.get_mut(&closed_pane.tab_id) else {
+            log::error!("Failed to find tab with ID: {}", closed_pane.tab_id);
+            return Ok(());
+        };
+        let invoked_with = match &closed_pane.run {
+            Some(Run::Command(_)) | Some(Run::EditFile(..)) => closed_pane.run.clone(),
+            _ => None,
+        };
+        let start_suppressed = false;
+        let should_focus_pane = true;
+        tab.new_pane(
+            pid,
+            closed_pane.title.clone(),
+            invoked_with,
+            start_suppressed,
+            should_focus_pane,
+            closed_pane.new_pane_placement(),
+            client_id,
+            None,
+        )
+        .with_context(err_context)?;
+        if !closed_pane.is_floating {
+            tab.restore_tiled_pane_geom(pid, closed_pane.geom);
+        }
+        if let Some(hold_for_command) = hold_for_command {
+            let is_first_run = true;
+            tab.hold_pane(pid, None, is_first_run, hold_for_command);
+        }
+        Ok(())
+    }
+    pub fn reopen_closed_tab(
+        &mut self,
+        closed_tab: ClosedTab,
+        client_id: ClientId,
+        completion_tx: Option<NotificationEnd>,
+    ) -> Result<()> {
+        let err_context = || format!("failed to reopen closed tab {}", closed_tab.name);
+        let Some(client_id) = self.client_id(client_id) else {
+            return Ok(());
+        };
+        let tab_index = self.get_new_tab_id();
+        let swap_layouts = (
+            self.default_layout.swap_tiled_layouts.clone(),
+            self.default_layout.swap_floating_layouts.clone(),
+        );
+        self.new_tab(
+            tab_index,
+            swap_layouts,
+            Some(closed_tab.name.clone()),
+            Some(client_id),
+        )
+        .with_context(err_context)?;
+        self.move_tab_to_position(tab_index, closed_tab.position);
+        let should_change_focus_to_new_tab = true;
+        let is_web_client = self
+            .connected_clients
+            .borrow()
+            .get(&client_id)
+            .copied()
+            .unwrap_or(false);
+        self.bus
+            .senders
+            .send_to_plugin(PluginInstruction::NewTab(
+                None,
+                None,
+                Some(closed_tab.tiled_panes),
+                closed_tab.floating_panes,
+                tab_index,
+                None,  // initial_panes
+                false, // block_on_first_terminal
+                should_change_focus_to_new_tab,
+                (client_id, is_web_client),
+                completion_tx,
+            ))
+            .with_context(err_context)
+    }
+    /// Shows or hides the scratchpad with this name, bringing it over to the current tab if it
+    /// was left in another one. If it does not exist yet, it is spawned with `terminal_action`
+    pub fn toggle_scratchpad(
+        &mut self,
+        name: String,
+        terminal_action: Option<TerminalAction>,
+        coordinates: Option<FloatingPaneCoordinates>,
+        client_id: ClientId,
+        mut completion_tx: Option<NotificationEnd>,
+    ) -> Result<()> {
+        let err_context = || format!("failed to toggle scratchpad {}", name);
+        let Some(client_id) = self.client_id(client_id) else {
+            return Ok(());
+        };
+        let active_tab_id = *self
+            .active_tab_ids
+            .get(&client_id)
+            .with_context(err_context)?;
+        if self.pending_scratchpads.contains(&name) {
+            // it is still being spawned, eg. the toggle was pressed twice in a row
+            return Ok(());
+        }
+        let scratchpad = self.tabs.iter().find_map(|(tab_id, tab)| {
+            tab.scratchpad_pane_id(&name)
+                .map(|pane_id| (*tab_id, pane_id))
+        });
+        match scratchpad {
+            Some((tab_id, pane_id)) if tab_id == active_tab_id => {
+                self.tabs
+                    .get_mut(&tab_id)
+                    .with_context(err_context)?
+                    .toggle_scratchpad(&name, client_id)
+                    .with_context(err_context)?;
+                if let Some(completion) = completion_tx.as_mut() {
+                    completion.set_affected_pane_id(pane_id);
+                }
+            },
+            Some((tab_id, pane_id)) => {
+                let pane = self
+                    .tabs
+                    .get_mut(&tab_id)
+                    .and_then(|tab| tab.extract_scratchpad(&name))
+                    .with_context(err_context)?;
+                self.tabs
+                    .get_mut(&active_tab_id)
+                    .with_context(err_context)?
+                    .show_scratchpad(name.clone(), pane)
+                    .with_context(err_context)?;
+                if let Some(completion) = completion_tx.as_mut() {
+                    completion.set_affected_pane_id(pane_id);
+                }
+            },
+            None => {
+                self.pending_scratchpads.insert(name.clone());
+                self.bus
+                    .senders
+                    .send_to_pty(PtyInstruction::SpawnScratchpad(
+                        name.clone(),
+                        terminal_action,
+                        coordinates,
+                        active_tab_id,
+                        client_id,
+                        completion_tx,
+                    ))
+                    .with_context(err_context)?;
+            },
+        }
+        Ok(())
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_scratchpad(
+        &mut self,
+        name: String,
+        pid: PaneId,
+        hold_for_command: Option<RunCommand>,
+        invoked_with: Option<Run>,
+        coordinates: Option<FloatingPaneCoordinates>,
+        tab_id: usize,
+        client_id: ClientId,
+    ) -> Result<()> {
+        let err_context = || format!("failed to add scratchpad {}", name);
+        self.pending_scratchpads.remove(&name);
+        let client_id = self.client_id(client_id);
+        let Some(tab) = self.tabs.get_mut(&tab_id) else {
+            log::error!("Failed to find tab with ID: {}", tab_id);
+            return Ok(());
+        };
+        let start_suppressed = false;
+        let should_focus_pane = true;
+        tab.new_pane(
+            pid,
+            Some(name.clone()),
+            invoked_with,
+            start_suppressed,
+            should_focus_pane,
+            NewPanePlacement::Floating(coordinates),
+            client_id,
+            None,
+        )
+        .with_context(err_context)?;
+        tab.add_scratchpad(name, pid);
+        if let Some(hold_for_command) = hold_for_command {
+            let is_first_run = true;
+            tab.hold_pane(pid, None, is_first_run, hold_for_command);
+        }
+        Ok(())
+    }
+    /// Moves a tab to the given position, shifting the tabs from there onwards to the right
+    fn move_tab_to_position(&mut self, tab_id: usize, position: usize) {
+        let Some(current_position) = self.get_tab_position_by_id(tab_id) else {
+            return;
+        };
+        let position = std::cmp::min(position, self.tabs.len().saturating_sub(1));
+        for tab in self.tabs.values_mut() {
+            if tab.id == tab_id {
+                tab.position = position;
+            } else if tab.position > current_position && tab.position <= position {
+                tab.position -= 1;
+            } else if tab.position < current_position && tab.position >= position {
+                tab.position += 1;
+            }
+        }
+    }
+    fn active_tab_has_pane_hints(&self, client_id: ClientId) -> bool {
+        self.get_active_tab(client_id)
+            .map(|tab| tab.has_pane_hints(client_id))
+            .unwrap_or(false)
+    }
+    fn active_pane_is_in_sync_group(&self, client_id: ClientId) -> bool {
+        // like active_tab_and_connected_client_id, fall back to the first connected client for
+        // clients that are not connected (eg. cli actions)
+        let client_id = if self.get_active_tab(client_id).is_ok() {
+            client_id
+        } else {
+            match self.get_first_client_id() {
+                Some(client_id) => client_id,
+                None => return false,
+            }
+        };
+        self.get_active_pane_id(&client_id)
+            .map(|pane_id| self.current_pane_group.borrow().is_in_sync_group(&pane_id))
+            .unwrap_or(false)
+    }
+    /// Writes the input to every pane of the sync group, in whichever tab they are. Returns
+    /// true if the UI should be updated (eg. when a command pane was closed with ctrl-c)
+    fn write_to_sync_group(
+        &mut self,
+        key_with_modifier: &Option<KeyWithModifier>,
+        raw_bytes: Vec<u8>,
+        is_kitty_keyboard_protocol: bool,
+        client_id: ClientId,
+    ) -> Result<bool> {
+        let sync_group = { self.current_pane_group.borrow().sync_group().to_vec() };
+        let mut should_update_ui = false;
+        for pane_id in sync_group {
+            let Some(tab) = self
+                .tabs
+                .values_mut()
+                .find(|tab| tab.has_pane_with_pid(&pane_id))
+            else {
+                continue;
+            };
+            let ui_change_triggered = tab
+                .write_to_pane_id(
+                    key_with_modifier,
+                    raw_bytes.clone(),
+                    is_kitty_keyboard_protocol,
+                    pane_id,
+                    Some(client_id),
+                    None,
+                )
+                .context("failed to write to sync group")?;
+            if ui_change_triggered {
+                should_update_ui = true;
+            }
+        }
+        Ok(should_update_ui)
+    }
+    fn get_layout_metadata(
+        &self,
+        default_shell: Option<PathBuf>,
+        tab_index: Option<usize>,
+    ) -> SessionLayoutMetadata {
+        self.layout_metadata(default_shell, tab_index, false)
+    }
+    /// Layout metadata to resurrect this session with, referring to the panes' scrollback files
+    /// if these are kept
+    fn get_layout_metadata_for_resurrection(&mut self) -> Result<SessionLayoutMetadata> {
+        self.persist_pane_scrollbacks()?;
+        Ok(self.layout_metadata(Some(self.default_shell.clone()), None, true))
+    }
+    /// Appends what was added to the panes' scrollback since the last time to their scrollback
+    /// files
+    fn persist_pane_scrollbacks(&mut self) -> Result<()> {
+        if !self.serialize_pane_viewport || !self.serialize_full_scrollback {
+            return Ok(());
+        }
+        let mut records = vec![];
+        let mut files_in_use = BTreeSet::new();
+        for tab in self.tabs.values_mut() {
+            for (terminal_id, record) in tab.update_scrollback_files() {
+                let file_name = scrollback_file_name(terminal_id);
+                if let Some(record) = record {
+                    records.push((file_name.clone(), record));
+                }
+                files_in_use.insert(file_name);
+            }
+        }
+        self.bus
+            .senders
+            .send_to_background_jobs(BackgroundJob::PersistPaneScrollbacks(
+                self.session_name.clone(),
+                records,
+                files_in_use,
+            ))
+            .with_context(|| "failed to persist pane scrollbacks")
+    }
+    /// The serialized contents of a pane for its layout, and the name of its scrollback file
+    /// if it has one and `with_scrollback_file` is true
+    fn serialize_pane_contents(
+        &self,
+        pane_id: PaneId,
+        pane: &dyn Pane,
+        with_scrollback_file: bool,
+    ) -> (Option<String>, Option<PathBuf>) {
+        if !self.serialize_pane_viewport {
+            return (None, None);
+        }
+        match pane_id {
+            PaneId::Terminal(terminal_id)
+                if with_scrollback_file && pane.has_persisted_scrollback() =>
+            {
+                // the scrollback file has everything but the last line above the viewport
+                (
+                    pane.serialize(Some(1)),
+                    Some(PathBuf::from(scrollback_file_name(terminal_id))),
+                )
+            },
+            _ => (pane.serialize(self.scrollback_lines_to_serialize), None),
+        }
+    }
+    fn layout_metadata(
+        &self,
+        default_shell: Option<PathBuf>,
+        tab_index: Option<usize>,
+        with_scrollback_files: bool,
+    ) -> SessionLayoutMetadata {
+        let mut session_layout_metadata = SessionLayoutMetadata::new(self.default_layout.clone());
+        if let Some(default_shell) = default_shell {
+            session_layout_metadata.update_default_shell(default_shell);
+        }
+        let first_client_id = self.get_first_client_id();
+        let active_tab_index =
+            first_client_id.and_then(|client_id| self.active_tab_ids.get(&client_id));
+
+        // Filter tabs based on optional tab_index parameter
+        let tabs_to_process: Vec<_> = self
+            .tabs
+            .iter()
+            .filter(|(idx, _)| tab_index.map_or(true, |target| **idx == target))
+            .collect();
+
+        for (tab_index, tab) in tabs_to_process {
+            let tab_is_focused = active_tab_index == Some(&tab_index);
+            let hide_floating_panes = !tab.are_floating_panes_visible();
+            let mut suppressed_panes = HashMap::new();
+            for (triggering_pane_id, p) in tab.get_suppressed_panes() {
+                suppressed_panes.insert(*triggering_pane_id, p);
+            }
+
+            let all_connected_clients: Vec<ClientId> = self
+                .connected_clients
+                .borrow()
+                .iter()
+                .map(|(c, _i)| *c)
+                .filter(|c| self.active_tab_ids.get(&c) == Some(&tab_index))
+                .collect();
+
+            let mut active_pane_ids: HashMap<ClientId, Option<PaneId>> = HashMap::new();
+            for connected_client_id in &all_connected_clients {
+                active_pane_ids.insert(
+                    *connected_client_id,
+                    tab.get_active_pane_id(*connected_client_id),
+                );
+            }
+
+            let tiled_panes: Vec<PaneLayoutMetadata> = tab
+                .get_tiled_panes()
+                .map(|(pane_id, p)| {
+                    // here we look to see if this pane triggers any suppressed pane,
+                    // and if so we take that suppressed pane - we do this because this
+                    // is currently only the case the scrollback editing panes, and
+                    // when dumping the layout we want the "real" pane and not the
+                    // editor pane
+                    match suppressed_panes.remove(pane_id) {
+                        Some((is_scrollback_editor, suppressed_pane)) if *is_scrollback_editor => {
+                            (suppressed_pane.pid(), suppressed_pane)
+                        },
+                        _ => (*pane_id, p),
+                    }
+                })
+                .map(|(pane_id, p)| {
+                    let focused_clients: Vec<ClientId> = active_pane_ids
+                        .iter()
+                        .filter_map(|(c_id, p_id)| {
+                            p_id.and_then(|p_id| if p_id == pane_id { Some(*c_id) } else { None })
+                        })
+                        .collect();
+                    let (default_fg, default_bg) = p.get_pane_default_colors();
+                    let (pane_contents, scrollback_file) =
+                        self.serialize_pane_contents(pane_id, &**p, with_scrollback_files);
+                    PaneLayoutMetadata::new(
+                        pane_id,
+                        p.position_and_size(),
+                        p.borderless(),
+                        p.invoked_with().clone(),
+                        p.custom_title(),
+                        !focused_clients.is_empty(),
+                        pane_contents,
+                        scrollback_file,
+                        focused_clients,
+                        default_fg,
+                        default_bg,
+                        None,
+                    )
+                })
+                .collect();
+            let floating_panes: Vec<PaneLayoutMetadata> = tab
+                .get_floating_panes()
+                .map(|(pane_id, p)| {
+                    // here we look to see if this pane triggers any suppressed pane,
+                    // and if so we take that suppressed pane - we do this because this
+                    // is currently only the case the scrollback editing panes, and
+                    // when dumping the layout we want the "real" pane and not the
+                    // editor pane
+                    match suppressed_panes.remove(pane_id) {
+                        Some((is_scrollback_editor, suppressed_pane)) if *is_scrollback_editor => {
+                            (suppressed_pane.pid(), suppressed_pane)
+                        },
+                        _ => (*pane_id, p),
+                    }
+                })
+                // hidden scratchpads are serialized with the floating panes they are shown as
+                .chain(tab.get_hidden_scratchpads())
+                .map(|(pane_id, p)| {
+                    let focused_clients: Vec<ClientId> = active_pane_ids
+                        .iter()
+                        .filter_map(|(c_id, p_id)| {
+                            p_id.and_then(|p_id| if p_id == pane_id { Some(*c_id) } else { None })
+                        })
+                        .collect();
+                    let (default_fg, default_bg) = p.get_pane_default_colors();
+                    let (pane_contents, scrollback_file) =
+                        self.serialize_pane_contents(pane_id, &**p, with_scrollback_files);
+                    PaneLayoutMetadata::new(
+                        pane_id,
+                        p.position_and_size(),
+                        false, // floating panes are never borderless
+                        p.invoked_with().clone(),
+                        p.custom_title(),
+                        !focused_clients.is_empty(),
+                        pane_contents,
+                        scrollback_file,
+                        focused_clients,
+                        default_fg,
+                        default_bg,
+                        tab.scratchpad_name(pane_id),
+                    )
+                })
+                .collect();
+            session_layout_metadata.add_tab(
+                tab.name.clone(),
+                tab_is_focused,
+                hide_floating_panes,
+                tiled_panes,
+                floating_panes,
+            );
+        }
+        session_layout_metadata
+    }
+    fn update_plugin_loading_stage(
+        &mut self,
+        pid: u32,
+        loading_indication: LoadingIndication,
+    ) -> bool {
+        let all_tabs = self.get_tabs_mut();
+        let mut found_plugin = false;
+        for tab in all_tabs.values_mut() {
+            if tab.has_plugin(pid) {
+                found_plugin = true;
+                tab.update_plugin_loading_stage(pid, loading_indication);
+                break;
+            }
+        }
+        found_plugin
+    }
+    fn connected_clients_contains(&self, client_id: &ClientId) -> bool {
+        self.connected_clients.borrow().contains_key(client_id)
+    }
+    fn get_client_pane_group(&self, client_id: &ClientId) -> HashSet<PaneId> {
+        self.current_pane_group
+            .borrow()
+            .get_client_pane_group(client_id)
+    }
+    fn clear_pane_group(&mut self, client_id: &ClientId) {
+        self.current_pane_group
+            .borrow_mut()
+            .clear_pane_group(client_id);
+        self.currently_marking_pane_group
+            .borrow_mut()
+            .remove(client_id);
+    }
+    fn toggle_pane_id_in_group(&mut self, pane_id: PaneId, client_id: &ClientId) {
+        {
+            let mut pane_groups = self.current_pane_group.borrow_mut();
+            pane_groups.toggle_pane_id_in_group(pane_id, self.size, client_id);
+        }
+        self.retain_only_existing_panes_in_pane_groups();
+    }
+    fn add_pane_id_to_group(&mut self, pane_id: PaneId, client_id: &ClientId) {
+        {
+            let mut pane_groups = self.current_pane_group.borrow_mut();
+            pane_groups.add_pane_id_to_group(pane_id, self.size, client_id);
+        }
+        self.retain_only_existing_panes_in_pane_groups();
+    }
+    fn add_active_pane_to_group_if_marking(&mut self, client_id: &ClientId) {
+        {
+            if self
+                .currently_marking_pane_group
+                .borrow()
+                .get(client_id)
+                .copied()
                 .unwrap_or(false)
             {
                 let active_pane_id = self.get_active_pane_id(&client_id);
@@ -5325,6 +5972,14 @@ impl Screen {
                 .override_groups_with(current_pane_group);
             clients_with_empty_group
         };
+        {
+            let all_tabs = self.get_tabs();
+            self.current_pane_group
+                .borrow_mut()
+                .retain_in_sync_group(|p_id| {
+                    all_tabs.values().any(|tab| tab.has_pane_with_pid(p_id))
+                });
+        }
         for client_id in &clients_with_empty_group {
             self.currently_marking_pane_group
                 .borrow_mut()
@@ -5773,6 +6428,7 @@ pub(crate) fn screen_thread_main(
     );
     screen.host_theme_dark_styling = host_theme_dark_styling;
     screen.host_theme_light_styling = host_theme_light_styling;
+    screen.serialize_full_scrollback = config_options.serialize_full_scrollback.unwrap_or(false);
 
     let mut pending_tab_ids: HashSet<usize> = HashSet::new();
     let mut pending_tab_switches: HashSet<(usize, ClientId)> = HashSet::new(); // usize is the
@@ -6073,6 +6729,20 @@ pub(crate) fn screen_thread_main(
                 let mut state_changed = false;
                 let client_input_mode = screen.get_client_input_mode(client_id);
                 match client_input_mode {
+                    _ if screen.active_tab_has_pane_hints(client_id) => {
+                        // while the pane hints are shown, keys are only used to pick a pane
+                        let input = match &key_with_modifier {
+                            Some(key) if key.has_no_modifiers() => match key.bare_key {
+                                BareKey::Char(character) => character.to_string(),
+                                _ => String::from_utf8_lossy(&raw_bytes).to_string(),
+                            },
+                            _ => String::from_utf8_lossy(&raw_bytes).to_string(),
+                        };
+                        active_tab!(screen, client_id, |tab: &mut Tab| tab
+                            .handle_pane_hint_input(&input, client_id)
+                            .non_fatal());
+                        state_changed = true;
+                    },
                     Some(InputMode::RenameTab) => {
                         if !(raw_bytes == BRACKETED_PASTE_BEGIN || raw_bytes == BRACKETED_PASTE_END)
                         {
@@ -6080,6 +6750,20 @@ pub(crate) fn screen_thread_main(
                             state_changed = true;
                         }
                     },
+                    _ if !matches!(
+                        client_input_mode,
+                        Some(InputMode::EnterSearch) | Some(InputMode::RenamePane)
+                    ) && screen.active_pane_is_in_sync_group(client_id) =>
+                    {
+                        if screen.write_to_sync_group(
+                            &key_with_modifier,
+                            raw_bytes,
+                            is_kitty_keyboard_protocol,
+                            client_id,
+                        )? {
+                            state_changed = true;
+                        }
+                    },
                     _ => {
                         active_tab_and_connected_client_id!(
                             screen,
@@ -6747,11 +7431,63 @@ pub(crate) fn screen_thread_main(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .handle_scrollwheel_down(&point, 3, client_id), ?
+                        .handle_scrollwheel_down(&point, 3, client_id), ?
+                );
+                screen.render(None)?;
+            },
+            ScreenInstruction::ScrollToBottom(
+                client_id,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .scroll_active_terminal_to_bottom(client_id), ?
+                );
+                screen.render(None)?;
+            },
+            ScreenInstruction::ScrollToTop(
+                client_id,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .scroll_active_terminal_to_top(client_id), ?
+                );
+                screen.render(None)?;
+            },
+            ScreenInstruction::PageScrollUp(
+                client_id,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .scroll_active_terminal_up_page(client_id)
+                );
+                screen.render(None)?;
+            },
+            ScreenInstruction::PageScrollDown(
+                client_id,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab
+                        .scroll_active_terminal_down_page(client_id), ?
                 );
                 screen.render(None)?;
             },
-            ScreenInstruction::ScrollToBottom(
+            ScreenInstruction::HalfPageScrollUp(
                 client_id,
                 _completion_tx, // the action ends here, dropping this will release anything
                                 // waiting for it
@@ -6760,11 +7496,11 @@ pub(crate) fn screen_thread_main(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_to_bottom(client_id), ?
+                        .scroll_active_terminal_up_half_page(client_id)
                 );
                 screen.render(None)?;
             },
-            ScreenInstruction::ScrollToTop(
+            ScreenInstruction::HalfPageScrollDown(
                 client_id,
                 _completion_tx, // the action ends here, dropping this will release anything
                                 // waiting for it
@@ -6773,11 +7509,11 @@ pub(crate) fn screen_thread_main(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_to_top(client_id), ?
+                        .scroll_active_terminal_down_half_page(client_id), ?
                 );
                 screen.render(None)?;
             },
-            ScreenInstruction::PageScrollUp(
+            ScreenInstruction::ScrollToPreviousPrompt(
                 client_id,
                 _completion_tx, // the action ends here, dropping this will release anything
                                 // waiting for it
@@ -6786,11 +7522,11 @@ pub(crate) fn screen_thread_main(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_up_page(client_id)
+                        .scroll_active_terminal_to_previous_prompt(client_id)
                 );
                 screen.render(None)?;
             },
-            ScreenInstruction::PageScrollDown(
+            ScreenInstruction::ScrollToNextPrompt(
                 client_id,
                 _completion_tx, // the action ends here, dropping this will release anything
                                 // waiting for it
@@ -6799,11 +7535,11 @@ pub(crate) fn screen_thread_main(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_down_page(client_id), ?
+                        .scroll_active_terminal_to_next_prompt(client_id), ?
                 );
                 screen.render(None)?;
             },
-            ScreenInstruction::HalfPageScrollUp(
+            ScreenInstruction::SelectLastCommandOutput(
                 client_id,
                 _completion_tx, // the action ends here, dropping this will release anything
                                 // waiting for it
@@ -6811,12 +7547,11 @@ pub(crate) fn screen_thread_main(
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_up_half_page(client_id)
+                    |tab: &mut Tab, client_id: ClientId| tab.select_last_command_output(client_id)
                 );
                 screen.render(None)?;
             },
-            ScreenInstruction::HalfPageScrollDown(
+            ScreenInstruction::CopyLastCommandOutput(
                 client_id,
                 _completion_tx, // the action ends here, dropping this will release anything
                                 // waiting for it
@@ -6825,10 +7560,225 @@ pub(crate) fn screen_thread_main(
                     screen,
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab
-                        .scroll_active_terminal_down_half_page(client_id), ?
+                        .copy_last_command_output(client_id), ?
                 );
                 screen.render(None)?;
             },
+            ScreenInstruction::DumpCommandOutput(
+                file,
+                client_id,
+                pane_id,
+                index,
+                completion_tx,
+                cli_client_id,
+            ) => {
+                let mut command_output = None;
+                match pane_id {
+                    Some(pane_id) => {
+                        for tab in screen.get_tabs_mut().values_mut() {
+                            if tab.has_pane_with_pid(&pane_id) {
+                                command_output = tab.dump_command_output(
+                                    file.clone(),
+                                    Some(pane_id),
+                                    client_id,
+                                    index,
+                                )?;
+                                break;
+                            }
+                        }
+                    },
+                    None => {
+                        active_tab_and_connected_client_id!(
+                            screen,
+                            client_id,
+                            |tab: &mut Tab, client_id: ClientId| {
+                                command_output = tab.dump_command_output(
+                                    file.clone(),
+                                    None,
+                                    client_id,
+                                    index,
+                                )?;
+                                Ok::<(), anyhow::Error>(())
+                            },
+                            ?
+                        );
+                    },
+                }
+                match (command_output, file) {
+                    (Some(_), Some(_)) => {
+                        // written to file, the action ends here
+                        drop(completion_tx);
+                    },
+                    (Some(command_output), None) => {
+                        screen.bus.senders.send_to_server(ServerInstruction::Log(
+                            vec![command_output],
+                            cli_client_id.unwrap_or(client_id),
+                            completion_tx,
+                        ))?;
+                    },
+                    (None, _) => {
+                        screen
+                            .bus
+                            .senders
+                            .send_to_server(ServerInstruction::LogError(
+                                vec![
+                                    "No finished command output found (this requires a shell with OSC 133 integration)"
+                                        .to_owned(),
+                                ],
+                                cli_client_id.unwrap_or(client_id),
+                                completion_tx,
+                            ))?;
+                    },
+                }
+            },
+            ScreenInstruction::StartRecording(
+                output,
+                client_id,
+                pane_id,
+                whole_tab,
+                completion_tx,
+                cli_client_id,
+            ) => {
+                let output = PathBuf::from(output);
+                let mut started_recordings = None;
+                match pane_id {
+                    Some(pane_id) => {
+                        if let Some(tab) = screen
+                            .get_tabs()
+                            .values()
+                            .find(|tab| tab.has_pane_with_pid(&pane_id))
+                        {
+                            started_recordings = Some(tab.start_recording(
+                                &output,
+                                Some(pane_id),
+                                false,
+                                Some(client_id),
+                            ));
+                        }
+                    },
+                    None => {
+                        active_tab_and_connected_client_id_with_first_tab_fallback!(
+                            screen,
+                            client_id,
+                            |tab: &mut Tab, client_id: Option<ClientId>| {
+                                started_recordings =
+                                    Some(tab.start_recording(&output, None, whole_tab, client_id));
+                            }
+                        );
+                    },
+                }
+                let started_recordings = started_recordings
+                    .unwrap_or_else(|| Err(anyhow!("no pane to record")))
+                    .and_then(|started_recordings| {
+                        if started_recordings.started.is_empty() {
+                            match started_recordings.failed.is_empty() {
+                                true => Err(anyhow!("no terminal panes to record")),
+                                false => Err(anyhow!(started_recordings.failed.join("\n"))),
+                            }
+                        } else {
+                            Ok(started_recordings)
+                        }
+                    });
+                match started_recordings {
+                    Ok(started_recordings) => {
+                        screen.report_recording_state();
+                        let lines: Vec<String> = started_recordings
+                            .started
+                            .iter()
+                            .map(|(terminal_id, path)| {
+                                format!("Recording terminal_{} to {}", terminal_id, path.display())
+                            })
+                            .collect();
+                        if !started_recordings.failed.is_empty() {
+                            // the panes that did start keep recording, so they are reported too
+                            let lines = lines
+                                .into_iter()
+                                .chain(started_recordings.failed.iter().map(|failure| {
+                                    format!("Failed to start recording: {}", failure)
+                                }))
+                                .collect();
+                            screen
+                                .bus
+                                .senders
+                                .send_to_server(ServerInstruction::LogError(
+                                    lines,
+                                    cli_client_id.unwrap_or(client_id),
+                                    completion_tx,
+                                ))?;
+                        } else {
+                            match cli_client_id {
+                                Some(cli_client_id) => {
+                                    screen.bus.senders.send_to_server(ServerInstruction::Log(
+                                        lines,
+                                        cli_client_id,
+                                        completion_tx,
+                                    ))?;
+                                },
+                                None => drop(completion_tx),
+                            }
+                        }
+                    },
+                    Err(e) => {
+                        screen
+                            .bus
+                            .senders
+                            .send_to_server(ServerInstruction::LogError(
+                                vec![format!("Failed to start recording: {:#}", e)],
+                                cli_client_id.unwrap_or(client_id),
+                                completion_tx,
+                            ))?;
+                    },
+                }
+            },
+            ScreenInstruction::StopRecording(
+                client_id,
+                pane_id,
+                whole_tab,
+                completion_tx,
+                cli_client_id,
+            ) => {
+                let recordings = screen.bus.os_input.as_ref().and_then(|o| o.recordings());
+                let terminal_ids: Vec<u32> = match (pane_id, whole_tab) {
+                    (Some(PaneId::Terminal(id)), _) => vec![id],
+                    (Some(PaneId::Plugin(_)), _) => vec![],
+                    (None, true) => screen
+                        .get_active_tab(client_id)
+                        .ok()
+                        .or_else(|| screen.get_tabs().values().next())
+                        .map(|tab| tab.get_all_pane_ids())
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter_map(|pane_id| match pane_id {
+                            PaneId::Terminal(id) => Some(id),
+                            PaneId::Plugin(_) => None,
+                        })
+                        .collect(),
+                    (None, false) => recordings
+                        .as_ref()
+                        .map(|r| r.recorded_terminal_ids())
+                        .unwrap_or_default(),
+                };
+                let stopped_recordings: Vec<u32> = match &recordings {
+                    Some(recordings) => terminal_ids
+                        .into_iter()
+                        .filter(|terminal_id| recordings.stop(*terminal_id))
+                        .collect(),
+                    None => vec![],
+                };
+                if stopped_recordings.is_empty() {
+                    screen
+                        .bus
+                        .senders
+                        .send_to_server(ServerInstruction::LogError(
+                            vec!["No recording in progress".to_owned()],
+                            cli_client_id.unwrap_or(client_id),
+                            completion_tx,
+                        ))?;
+                } else {
+                    screen.report_recording_state();
+                    drop(completion_tx);
+                }
+            },
             ScreenInstruction::ClearScroll(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -6839,6 +7789,18 @@ pub(crate) fn screen_thread_main(
                 screen.render(None)?;
             },
             ScreenInstruction::CloseFocusedPane(client_id, completion_tx) => {
+                if let Some(client_id) = screen.client_id(client_id) {
+                    if let Ok(tab) = screen.get_active_tab(client_id) {
+                        match tab.get_active_pane_id(client_id) {
+                            // closing the last pane closes the tab, which is what we remember
+                            Some(pane_id) if tab.closing_pane_closes_tab(pane_id) => {
+                                screen.remember_closed_tab(tab.id);
+                            },
+                            Some(pane_id) => screen.remember_closed_pane(pane_id),
+                            None => {},
+                        }
+                    }
+                }
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
@@ -7360,6 +8322,10 @@ pub(crate) fn screen_thread_main(
             ScreenInstruction::TerminalColorRegisters(color_registers) => {
                 screen.update_terminal_color_registers(color_registers);
             },
+            ScreenInstruction::KittyGraphicsSupported(client_id) => {
+                screen.add_kitty_graphics_host(client_id);
+                screen.render(None)?;
+            },
             ScreenInstruction::ForwardHostQuery { pane_id, query } => {
                 screen.forward_host_query(pane_id, query);
             },
@@ -7423,6 +8389,10 @@ pub(crate) fn screen_thread_main(
                 screen.change_mode_for_all_clients(input_mode, base_mode)?;
                 screen.render(None)?;
             },
+            ScreenInstruction::UpdatePendingKeySequence(pending_key_sequence, client_id) => {
+                screen.update_pending_key_sequence(pending_key_sequence, client_id)?;
+                screen.render(None)?;
+            },
             ScreenInstruction::ToggleActiveSyncTab(
                 client_id,
                 _completion_tx, // the action ends here, dropping this will release anything
@@ -7588,6 +8558,44 @@ pub(crate) fn screen_thread_main(
                 );
                 screen.render(None)?;
             },
+            ScreenInstruction::SearchToggleRegex(
+                client_id,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.toggle_search_regex(client_id)
+                );
+                screen.render(None)?;
+            },
+            ScreenInstruction::CopyModeMove(
+                motion,
+                client_id,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.copy_mode_move(motion, client_id)
+                );
+                screen.render(None)?;
+            },
+            ScreenInstruction::CopyModeSelect(
+                kind,
+                client_id,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.copy_mode_select(kind, client_id)
+                );
+                screen.render(None)?;
+            },
             ScreenInstruction::AddRedPaneFrameColorOverride(pane_ids, error_text) => {
                 let all_tabs = screen.get_tabs_mut();
                 for pane_id in pane_ids {
@@ -8542,6 +9550,7 @@ pub(crate) fn screen_thread_main(
             },
             ScreenInstruction::CloseTabWithId(tab_id, _completion_tx) => {
                 if screen.get_tab_by_id(tab_id).is_some() {
+                    screen.remember_closed_tab(tab_id);
                     screen.close_tab_by_id(tab_id).non_fatal();
                 } else {
                     log::error!("Failed to find tab with ID: {}", tab_id);
@@ -8706,7 +9715,9 @@ pub(crate) fn screen_thread_main(
                 };
 
                 let session_layout_metadata = if screen.session_serialization {
-                    screen.get_layout_metadata(Some(screen.default_shell.clone()), None)
+                    screen
+                        .get_layout_metadata_for_resurrection()
+                        .with_context(err_context)?
                 } else {
                     // Create empty metadata if serialization is disabled
                     SessionLayoutMetadata::new(screen.default_layout.clone())
@@ -8858,6 +9869,10 @@ pub(crate) fn screen_thread_main(
             ScreenInstruction::RerunCommandPane(terminal_pane_id, completion_tx) => {
                 screen.rerun_command_pane_with_id(terminal_pane_id, completion_tx)
             },
+            ScreenInstruction::RestartCommandPane(terminal_pane_id, attempt) => {
+                screen.restart_command_pane_with_id(terminal_pane_id, attempt);
+                screen.render(None)?;
+            },
             ScreenInstruction::ResizePaneWithId(resize, pane_id) => {
                 screen.resize_pane_with_id(resize, pane_id)
             },
@@ -9267,6 +10282,136 @@ pub(crate) fn screen_thread_main(
             ) => {
                 screen.toggle_group_marking(client_id).non_fatal();
             },
+            ScreenInstruction::ToggleSyncGroup(
+                client_id,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                screen.toggle_sync_group(client_id);
+                screen.render(None)?;
+            },
+            ScreenInstruction::SetSyncGroup(
+                pane_ids,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                screen.set_sync_group(pane_ids);
+                screen.render(None)?;
+            },
+            ScreenInstruction::ShowPaneHints(
+                client_id,
+                _completion_tx, // the action ends here, dropping this will release anything
+                                // waiting for it
+            ) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.show_pane_hints(client_id)
+                );
+                screen.render(None)?;
+            },
+            ScreenInstruction::ReopenClosedPane(client_id, completion_tx) => {
+                if let Some(closed_pane) = screen.recently_closed.take_last_pane() {
+                    screen.reopen_closed_pane(closed_pane, client_id, completion_tx)?;
+                }
+                screen.render(None)?;
+            },
+            ScreenInstruction::ReopenClosedTab(client_id, completion_tx) => {
+                if let Some(closed_tab) = screen.recently_closed.take_last_tab() {
+                    screen.reopen_closed_tab(closed_tab, client_id, completion_tx)?;
+                }
+                screen.render(None)?;
+            },
+            ScreenInstruction::ReopenClosed(id, client_id) => {
+                match screen.recently_closed.take(id) {
+                    Some(ClosedItem::Pane(closed_pane)) => {
+                        screen.reopen_closed_pane(closed_pane, client_id, None)?;
+                    },
+                    Some(ClosedItem::Tab(closed_tab)) => {
+                        screen.reopen_closed_tab(closed_tab, client_id, None)?;
+                    },
+                    None => log::error!("No recently closed pane or tab with id: {}", id),
+                }
+                screen.render(None)?;
+            },
+            ScreenInstruction::RememberClosedPane(pane_id) => {
+                screen.remember_closed_pane(pane_id);
+            },
+            ScreenInstruction::AddRecentlyClosed(closed_item) => {
+                screen.recently_closed.push(closed_item);
+            },
+            ScreenInstruction::RestoreClosedPane(
+                pid,
+                hold_for_command,
+                closed_pane,
+                client_id,
+                mut completion_tx,
+            ) => {
+                if let Some(c) = completion_tx.as_mut() {
+                    c.set_affected_pane_id(pid);
+                }
+                screen.restore_closed_pane(pid, hold_for_command, closed_pane, client_id)?;
+                if let Some(pending_events) = pending_events_waiting_for_pane.remove(&pid) {
+                    for event in pending_events {
+                        screen.bus.senders.send_to_screen(event).non_fatal();
+                    }
+                }
+                screen.log_and_report_session_state()?;
+                screen.render(None)?;
+            },
+            ScreenInstruction::ListRecentlyClosed { response_channel } => {
+                let _ = response_channel.send(screen.recently_closed.list());
+            },
+            ScreenInstruction::ToggleScratchpad(
+                name,
+                terminal_action,
+                coordinates,
+                client_id,
+                completion_tx,
+            ) => {
+                screen.toggle_scratchpad(
+                    name,
+                    terminal_action,
+                    coordinates,
+                    client_id,
+                    completion_tx,
+                )?;
+                screen.log_and_report_session_state()?;
+                screen.render(None)?;
+            },
+            ScreenInstruction::AddScratchpad(
+                name,
+                pid,
+                hold_for_command,
+                invoked_with,
+                coordinates,
+                tab_id,
+                client_id,
+                mut completion_tx,
+            ) => {
+                if let Some(completion) = completion_tx.as_mut() {
+                    completion.set_affected_pane_id(pid);
+                }
+                screen.add_scratchpad(
+                    name,
+                    pid,
+                    hold_for_command,
+                    invoked_with,
+                    coordinates,
+                    tab_id,
+                    client_id,
+                )?;
+                if let Some(pending_events) = pending_events_waiting_for_pane.remove(&pid) {
+                    for event in pending_events {
+                        screen.bus.senders.send_to_screen(event).non_fatal();
+                    }
+                }
+                screen.log_and_report_session_state()?;
+                screen.render(None)?;
+            },
+            ScreenInstruction::ScratchpadNotSpawned(name) => {
+                screen.pending_scratchpads.remove(&name);
+            },
             ScreenInstruction::SessionSharingStatusChange(web_sharing) => {
                 if web_sharing {
                     screen.web_sharing = WebSharing::On;
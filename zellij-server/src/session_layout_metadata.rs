@@ -1,4 +1,5 @@
 use crate::panes::PaneId;
+use crate::recently_closed::ClosedPane;
 use crate::ClientId;
 use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
@@ -251,6 +252,53 @@ impl SessionLayoutMetadata {
         }
         plugin_ids
     }
+    /// Drop all panes but this one, so that only its process is queried
+    pub fn retain_pane(&mut self, pane_id: PaneId) {
+        for tab in &mut self.tabs {
+            tab.tiled_panes.retain(|pane| pane.id == pane_id);
+            tab.floating_panes.retain(|pane| pane.id == pane_id);
+        }
+    }
+    pub fn into_closed_pane(self, pane_id: PaneId, tab_id: usize) -> Option<ClosedPane> {
+        let global_cwd = self.global_cwd;
+        self.tabs.into_iter().find_map(|tab| {
+            let tiled_panes = tab.tiled_panes.into_iter().map(|pane| (pane, false));
+            let floating_panes = tab.floating_panes.into_iter().map(|pane| (pane, true));
+            tiled_panes
+                .chain(floating_panes)
+                .find(|(pane, _)| pane.id == pane_id)
+                .map(|(pane, is_floating)| {
+                    let cwd = match (&global_cwd, pane.cwd) {
+                        (Some(global_cwd), Some(cwd)) => Some(global_cwd.join(cwd)),
+                        (None, Some(cwd)) => Some(cwd),
+                        (Some(global_cwd), None) => Some(global_cwd.clone()),
+                        (None, None) => None,
+                    };
+                    let run = match pane.run {
+                        Some(Run::Command(mut run_command)) => {
+                            if run_command.cwd.is_none() {
+                                run_command.cwd = cwd;
+                            }
+                            Some(Run::Command(run_command))
+                        },
+                        Some(Run::EditFile(path, line_number, edit_cwd)) => {
+                            Some(Run::EditFile(path, line_number, cwd.or(edit_cwd)))
+                        },
+                        Some(Run::Cwd(run_cwd)) => Some(Run::Cwd(cwd.unwrap_or(run_cwd))),
+                        Some(Run::Plugin(run_plugin)) => Some(Run::Plugin(run_plugin)),
+                        None => cwd.map(Run::Cwd),
+                    };
+                    ClosedPane {
+                        run,
+                        title: pane.title,
+                        geom: pane.geom,
+                        is_floating,
+                        is_borderless: pane.is_borderless,
+                        tab_id,
+                    }
+                })
+        })
+    }
     pub fn remove_plugin_from_layout(&mut self, plugin_id_to_remove: u32) {
         for tab in &mut self.tabs {
             // Filter tiled panes
@@ -288,6 +336,13 @@ impl SessionLayoutMetadata {
                         } else {
                             let mut run_command = RunCommand::new(PathBuf::from(command_name));
                             run_command.args = args;
+                            // the running command is read from the OS, but the env it was
+                            // started with is only known to us
+                            if let Some(Run::Command(previous_run_command)) =
+                                pane_layout_metadata.run.take()
+                            {
+                                run_command.env = previous_run_command.env;
+                            }
                             pane_layout_metadata.run = Some(Run::Command(run_command));
                         }
                     }
@@ -472,6 +527,7 @@ impl SessionLayoutMetadata {
             tabs,
             creation_time: current_time.clone(),
             update_time: current_time,
+            imports: vec![],
         }
     }
 }
@@ -533,8 +589,10 @@ impl Into<PaneLayoutManifest> for PaneLayoutMetadata {
             title: self.title,
             is_focused: self.is_focused,
             pane_contents: self.pane_contents,
+            scrollback_file: self.scrollback_file,
             default_fg: self.default_fg,
             default_bg: self.default_bg,
+            scratchpad: self.scratchpad,
         }
     }
 }
@@ -558,12 +616,15 @@ pub struct PaneLayoutMetadata {
     title: Option<String>,
     is_focused: bool,
     pane_contents: Option<String>,
+    scrollback_file: Option<PathBuf>,
     focused_clients: Vec<ClientId>,
     default_fg: Option<String>,
     default_bg: Option<String>,
+    scratchpad: Option<String>,
 }
 
 impl PaneLayoutMetadata {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: PaneId,
         geom: PaneGeom,
@@ -572,9 +633,11 @@ impl PaneLayoutMetadata {
         title: Option<String>,
         is_focused: bool,
         pane_contents: Option<String>,
+        scrollback_file: Option<PathBuf>,
         focused_clients: Vec<ClientId>,
         default_fg: Option<String>,
         default_bg: Option<String>,
+        scratchpad: Option<String>,
     ) -> Self {
         PaneLayoutMetadata {
             id,
@@ -585,9 +648,11 @@ impl PaneLayoutMetadata {
             title,
             is_focused,
             pane_contents,
+            scrollback_file,
             focused_clients,
             default_fg,
             default_bg,
+            scratchpad,
         }
     }
     fn to_pane_metadata(&self) -> PaneMetadata {
@@ -698,9 +763,11 @@ mod tests {
             None,
             false,
             None,
+            None,
             vec![],
             None,
             None,
+            None,
         )
     }
 
@@ -717,9 +784,11 @@ mod tests {
             None,
             false,
             None,
+            None,
             vec![],
             None,
             None,
+            None,
         )
     }
 
@@ -5,6 +5,7 @@ use crate::tab::{get_next_terminal_position, HoldForCommand, Pane};
 
 use crate::{
     os_input_output::ServerOsApi,
+    panes::scrollback_store::read_scrollback_file,
     panes::sixel::SixelImageStore,
     panes::{FloatingPanes, TiledPanes},
     panes::{LinkHandler, PaneId, PluginPane, TerminalPane},
@@ -15,6 +16,7 @@ use crate::{
 };
 use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 use std::rc::Rc;
 use zellij_utils::{
     data::{Palette, Style},
@@ -44,6 +46,7 @@ pub struct LayoutApplier<'a> {
     osc8_hyperlinks: bool,
     explicitly_disable_kitty_keyboard_protocol: bool,
     blocking_terminal: Option<(u32, NotificationEnd)>,
+    scratchpads: Vec<(String, PaneId)>, // the scratchpads opened by the layout
 }
 
 impl<'a> LayoutApplier<'a> {
@@ -103,8 +106,13 @@ impl<'a> LayoutApplier<'a> {
             osc8_hyperlinks,
             explicitly_disable_kitty_keyboard_protocol,
             blocking_terminal,
+            scratchpads: vec![],
         }
     }
+    /// The scratchpads opened by the layout, which the tab should hide until they are toggled
+    pub fn take_scratchpads(&mut self) -> Vec<(String, PaneId)> {
+        std::mem::take(&mut self.scratchpads)
+    }
     pub fn apply_layout(
         &mut self,
         layout: TiledPaneLayout,
@@ -644,6 +652,9 @@ impl<'a> LayoutApplier<'a> {
             self.explicitly_disable_kitty_keyboard_protocol,
             None,
         );
+        if let Some(scrollback_file) = &floating_pane_layout.pane_scrollback_file {
+            restore_scrollback(&mut new_pane, scrollback_file);
+        }
         if let Some(pane_initial_contents) = &floating_pane_layout.pane_initial_contents {
             new_pane.handle_pty_bytes(pane_initial_contents.as_bytes().into());
             new_pane.handle_pty_bytes("\n\r".as_bytes().into());
@@ -720,6 +731,9 @@ impl<'a> LayoutApplier<'a> {
             self.explicitly_disable_kitty_keyboard_protocol,
             notification_end,
         );
+        if let Some(scrollback_file) = &layout.pane_scrollback_file {
+            restore_scrollback(&mut new_pane, scrollback_file);
+        }
         if let Some(pane_initial_contents) = &layout.pane_initial_contents {
             new_pane.handle_pty_bytes(pane_initial_contents.as_bytes().into());
             new_pane.handle_pty_bytes("\n\r".as_bytes().into());
@@ -814,6 +828,10 @@ impl<'a> LayoutApplier<'a> {
                     &floating_pane_layout,
                 )?
             } else if let Some((pid, hold_for_command)) = new_floating_terminal_ids.next() {
+                if let Some(scratchpad) = &floating_pane_layout.scratchpad {
+                    self.scratchpads
+                        .push((scratchpad.clone(), PaneId::Terminal(*pid)));
+                }
                 self.new_floating_terminal_pane(
                     pid,
                     hold_for_command,
@@ -1405,3 +1423,14 @@ impl<'a> PaneApplier<'a> {
         }
     }
 }
+
+fn restore_scrollback(pane: &mut TerminalPane, scrollback_file: &Path) {
+    match read_scrollback_file(scrollback_file) {
+        Ok(persisted_scrollback) => pane.restore_scrollback(persisted_scrollback),
+        Err(e) => log::error!(
+            "Failed to restore scrollback from {}: {}",
+            scrollback_file.display(),
+            e
+        ),
+    }
+}
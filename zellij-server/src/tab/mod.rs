@@ -5,14 +5,16 @@ mod clipboard;
 mod copy_command;
 mod layout_applier;
 mod mouse_handler;
+mod pane_hints;
 mod swap_layouts;
 
 use crate::plugins::PluginId;
 use copy_command::CopyCommand;
 pub use mouse_handler::{MouseEffect, MouseHandler, PaneEdge, PaneResizeState};
+pub use pane_hints::{PaneHintInput, PaneHints};
 use std::env::temp_dir;
 use std::net::IpAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 use zellij_utils::data::PaneContents;
 use zellij_utils::data::{
@@ -20,6 +22,7 @@ use zellij_utils::data::{
     PluginPermission, RegexHighlight, ResizeStrategy, Style, WebSharing,
 };
 use zellij_utils::errors::prelude::*;
+use zellij_utils::input::actions::{CopyModeMotion, CopySelectionKind};
 use zellij_utils::input::command::RunCommand;
 use zellij_utils::input::mouse::MouseEvent;
 use zellij_utils::position::Position;
@@ -41,11 +44,13 @@ use crate::{
     output::{CharacterChunk, Output, SixelImageChunk},
     panes::floating_panes::floating_pane_grid::half_size_middle_geom,
     panes::grid::namespace_notification_id,
+    panes::scrollback_store::{PersistedScrollback, ScrollbackRecord},
     panes::sixel::SixelImageStore,
     panes::{FloatingPanes, TiledPanes},
     panes::{LinkHandler, PaneId, PluginPane, TerminalPane},
     plugins::PluginInstruction,
     pty::{ClientTabIndexOrPaneId, PtyInstruction, VteBytes},
+    recording::{recording_path_for_pane, StartedRecordings},
     thread_bus::ThreadSenders,
     ClientId, ServerInstruction,
 };
@@ -143,6 +148,8 @@ pub const MIN_TERMINAL_HEIGHT: usize = 5;
 pub const MIN_TERMINAL_WIDTH: usize = 5;
 
 const MAX_PENDING_VTE_EVENTS: usize = 7000;
+// how many of its last lines to log when a command pane runs out of restarts
+const EXITED_PANE_LOG_LINES: usize = 10;
 
 type HoldForCommand = Option<RunCommand>;
 pub type SuppressedPanes = HashMap<PaneId, (bool, Box<dyn Pane>)>; // bool => is scrollback editor
@@ -166,6 +173,7 @@ pub(crate) struct Tab {
     tiled_panes: TiledPanes,
     floating_panes: FloatingPanes,
     suppressed_panes: SuppressedPanes,
+    scratchpads: HashMap<String, PaneId>, // hidden scratchpads are kept in suppressed_panes
     max_panes: Option<usize>,
     viewport: Rc<RefCell<Viewport>>, // includes all non-UI panes
     display_area: Rc<RefCell<Size>>, // includes all panes (including eg. the status bar and tab bar in the default layout)
@@ -210,6 +218,7 @@ pub(crate) struct Tab {
     web_clients_allowed: bool,
     web_sharing: WebSharing,
     mouse_hover_pane_id: HashMap<ClientId, PaneId>,
+    pane_hints: HashMap<ClientId, PaneHints>,
     mouse_help_text_visible: HashMap<ClientId, bool>,
     last_mouse_activity_time: HashMap<ClientId, Instant>,
     current_pane_group: Rc<RefCell<PaneGroups>>,
@@ -325,6 +334,7 @@ pub trait Pane {
         }
     }
     fn start_selection(&mut self, _start: &Position, _client_id: ClientId) {}
+    fn start_block_selection(&mut self, _start: &Position, _client_id: ClientId) {}
     fn update_selection(&mut self, _position: &Position, _client_id: ClientId) {}
     fn end_selection(&mut self, _end: &Position, _client_id: ClientId) {}
     fn reset_selection(&mut self, _client_id: Option<ClientId>) {}
@@ -455,6 +465,22 @@ pub trait Pane {
     fn drain_osc7_cwd(&mut self) -> Option<std::path::PathBuf> {
         None
     }
+    fn drain_finished_commands(&mut self) -> Vec<Option<i32>> {
+        // exit codes of commands reported finished through OSC 133 shell integration
+        vec![]
+    }
+    fn scroll_to_previous_prompt(&mut self) {
+        // No-op by default (only terminal-panes have shell integration)
+    }
+    fn scroll_to_next_prompt(&mut self) {
+        // No-op by default (only terminal-panes have shell integration)
+    }
+    fn select_last_command_output(&mut self) {
+        // No-op by default (only terminal-panes have shell integration)
+    }
+    fn command_output(&self, _index: usize) -> Option<String> {
+        None
+    }
     fn render_full_viewport(&mut self) {}
     fn relative_position(&self, position_on_screen: &Position) -> Position {
         position_on_screen.relative_to(self.get_content_y(), self.get_content_x())
@@ -587,9 +613,24 @@ pub trait Pane {
     fn toggle_search_wrap(&mut self) {
         // No-op by default (only terminal-panes currently have search capability)
     }
+    fn toggle_search_regex(&mut self) {
+        // No-op by default (only terminal-panes currently have search capability)
+    }
     fn clear_search(&mut self) {
         // No-op by default (only terminal-panes currently have search capability)
     }
+    fn enter_copy_mode(&mut self) {
+        // No-op by default (only terminal-panes currently have copy mode)
+    }
+    fn exit_copy_mode(&mut self) {
+        // No-op by default (only terminal-panes currently have copy mode)
+    }
+    fn copy_mode_move(&mut self, _motion: CopyModeMotion) {
+        // No-op by default (only terminal-panes currently have copy mode)
+    }
+    fn copy_mode_select(&mut self, _kind: CopySelectionKind) {
+        // No-op by default (only terminal-panes currently have copy mode)
+    }
     fn is_alternate_mode_active(&self) -> bool {
         // False by default (only terminal-panes support alternate mode)
         false
@@ -634,9 +675,22 @@ pub trait Pane {
     fn serialize(&self, _scrollback_lines_to_serialize: Option<usize>) -> Option<String> {
         None
     }
+    fn scrollback_record(&mut self) -> Option<ScrollbackRecord> {
+        None
+    } // only relevant to terminal panes
+    fn has_persisted_scrollback(&self) -> bool {
+        false
+    } // only relevant to terminal panes
+    fn restore_scrollback(&mut self, _persisted_scrollback: PersistedScrollback) {} // only relevant to terminal panes
     fn rerun(&mut self) -> Option<RunCommand> {
         None
     } // only relevant to terminal panes
+    fn pending_restart(&self) -> Option<usize> {
+        None
+    } // only relevant to terminal panes
+    fn restart(&mut self, _attempt: usize) -> Option<RunCommand> {
+        None
+    } // only relevant to terminal panes
     fn update_theme(&mut self, _theme: Styling) {}
     fn update_arrow_fonts(&mut self, _should_support_arrow_fonts: bool) {}
     fn update_rounded_corners(&mut self, _rounded_corners: bool) {}
@@ -822,6 +876,7 @@ impl Tab {
             tiled_panes,
             floating_panes,
             suppressed_panes: HashMap::new(),
+            scratchpads: HashMap::new(),
             name: name.clone(),
             prev_name: name,
             size: initial_size,
@@ -864,6 +919,7 @@ impl Tab {
             web_clients_allowed,
             web_sharing,
             mouse_hover_pane_id: HashMap::new(),
+            pane_hints: HashMap::new(),
             mouse_help_text_visible: HashMap::new(),
             last_mouse_activity_time: HashMap::new(),
             current_pane_group,
@@ -894,7 +950,7 @@ impl Tab {
     ) -> Result<()> {
         self.swap_layouts
             .set_base_layout((layout.clone(), floating_panes_layout.clone()));
-        match LayoutApplier::new(
+        let mut layout_applier = LayoutApplier::new(
             &self.viewport,
             &self.senders,
             &self.sixel_image_store,
@@ -916,8 +972,8 @@ impl Tab {
             self.osc8_hyperlinks,
             self.explicitly_disable_kitty_keyboard_protocol,
             blocking_terminal,
-        )
-        .apply_layout(
+        );
+        match layout_applier.apply_layout(
             layout,
             floating_panes_layout,
             new_terminal_ids,
@@ -926,6 +982,7 @@ impl Tab {
             client_id,
         ) {
             Ok(should_show_floating_panes) => {
+                let scratchpads = layout_applier.take_scratchpads();
                 if should_show_floating_panes && !self.floating_panes.panes_are_visible() {
                     self.toggle_floating_panes(Some(client_id), None, None)
                         .non_fatal();
@@ -933,6 +990,12 @@ impl Tab {
                     self.toggle_floating_panes(Some(client_id), None, None)
                         .non_fatal();
                 }
+                // scratchpads start out hidden, this also hides the floating panes if only
+                // scratchpads were among them
+                for (name, pane_id) in scratchpads {
+                    self.scratchpads.insert(name, pane_id);
+                    self.suppress_pane(pane_id, Some(client_id));
+                }
                 self.tiled_panes.reapply_pane_frames();
                 self.is_pending = false;
                 self.apply_buffered_instructions().non_fatal();
@@ -1314,6 +1377,7 @@ impl Tab {
         self.connected_clients.borrow_mut().remove(&client_id);
         self.mouse_help_text_visible.remove(&client_id);
         self.last_mouse_activity_time.remove(&client_id);
+        self.pane_hints.remove(&client_id);
         self.set_force_render();
     }
     pub fn drain_connected_clients(
@@ -1791,6 +1855,14 @@ impl Tab {
             self.add_tiled_pane(new_pane, pid, false, client_id)
         }
     }
+    /// Put a reopened tiled pane back where it was before it was closed, if its neighbours still
+    /// allow it
+    pub fn restore_tiled_pane_geom(&mut self, pid: PaneId, geom: PaneGeom) {
+        if self.tiled_panes.panes_contain(&pid) {
+            self.tiled_panes.restore_pane_geom(pid, geom);
+            self.tiled_panes.set_force_render();
+        }
+    }
     pub fn new_floating_pane(
         &mut self,
         pid: PaneId,
@@ -2839,6 +2911,7 @@ impl Tab {
             let clipboard_update = terminal_output.drain_clipboard_update();
             let desktop_notifications = terminal_output.drain_desktop_notifications();
             let osc7_cwd = terminal_output.drain_osc7_cwd();
+            let finished_commands = terminal_output.drain_finished_commands();
             for message in messages_to_pty {
                 self.write_to_pane_id_without_preprocessing(message, PaneId::Terminal(pid))
                     .with_context(err_context)?;
@@ -2864,6 +2937,14 @@ impl Tab {
                     .senders
                     .send_to_pty(PtyInstruction::NotifyCwdFromOsc7(pid, path));
             }
+            if !finished_commands.is_empty() {
+                let _ = self.senders.send_to_plugin(PluginInstruction::Update(
+                    finished_commands
+                        .into_iter()
+                        .map(|exit_code| (None, None, Event::CommandFinished(pid, exit_code)))
+                        .collect(),
+                ));
+            }
         }
         Ok(())
     }
@@ -3319,12 +3400,15 @@ impl Tab {
 
         let current_pane_group: HashMap<ClientId, Vec<PaneId>> =
             { self.current_pane_group.borrow().clone_inner() };
+        let sync_group: Vec<PaneId> = { self.current_pane_group.borrow().sync_group().to_vec() };
         self.tiled_panes
             .render(
                 output,
                 self.floating_panes.panes_are_visible(),
                 &self.mouse_hover_pane_id,
                 current_pane_group.clone(),
+                &sync_group,
+                &self.pane_hints,
                 client_id_override,
                 &self.mouse_help_text_visible,
             )
@@ -3337,6 +3421,8 @@ impl Tab {
                     output,
                     &self.mouse_hover_pane_id,
                     current_pane_group,
+                    &sync_group,
+                    &self.pane_hints,
                     client_id_override,
                     &self.mouse_help_text_visible,
                 )
@@ -3453,6 +3539,27 @@ impl Tab {
     pub(crate) fn get_floating_panes(&self) -> impl Iterator<Item = (&PaneId, &Box<dyn Pane>)> {
         self.floating_panes.get_panes()
     }
+    /// The terminal panes (including suppressed ones) whose scrollback is kept in a file, along
+    /// with what to append to that file
+    pub(crate) fn update_scrollback_files(&mut self) -> Vec<(u32, Option<ScrollbackRecord>)> {
+        self.tiled_panes
+            .get_panes_mut()
+            .chain(self.floating_panes.get_panes_mut())
+            .map(|(_, pane)| pane)
+            .chain(self.suppressed_panes.values_mut().map(|(_, pane)| pane))
+            .filter_map(|pane| match pane.pid() {
+                PaneId::Terminal(terminal_id) => {
+                    let record = pane.scrollback_record();
+                    if pane.has_persisted_scrollback() {
+                        Some((terminal_id, record))
+                    } else {
+                        None
+                    }
+                },
+                PaneId::Plugin(_) => None,
+            })
+            .collect()
+    }
     pub(crate) fn get_suppressed_panes(
         &self,
     ) -> impl Iterator<Item = (&PaneId, &(bool, Box<dyn Pane>))> {
@@ -3497,6 +3604,14 @@ impl Tab {
             .count();
         tiled_panes_count + floating_panes_count + 1
     }
+    /// Whether closing this pane leaves no selectable tiled panes in the tab, which closes it
+    pub fn closing_pane_closes_tab(&self, pane_id: PaneId) -> bool {
+        self.tiled_panes.panes_contain(&pane_id)
+            && !self.suppressed_panes.contains_key(&pane_id)
+            && self
+                .get_selectable_tiled_panes()
+                .all(|(selectable_pane_id, _)| *selectable_pane_id == pane_id)
+    }
     pub fn has_selectable_panes(&self) -> bool {
         let selectable_tiled_panes = self.tiled_panes.get_panes().filter(|(_, p)| p.selectable());
         let selectable_floating_panes = self
@@ -3511,6 +3626,7 @@ impl Tab {
     }
     pub fn resize_whole_tab(&mut self, new_screen_size: Size) -> Result<()> {
         let err_context = || format!("failed to resize whole tab (id {})", self.id);
+        let previous_screen_size = self.size;
         self.size = new_screen_size;
         // If a tiled pane is fullscreen, exit fullscreen first so that *all*
         // tiled panes (including the currently hidden ones) participate in the
@@ -3528,14 +3644,21 @@ impl Tab {
             .resize_pty_all_panes(&mut self.os_api)
             .with_context(err_context)?;
         self.tiled_panes.resize(new_screen_size);
-        if self.auto_layout && !self.swap_layouts.is_floating_damaged() {
+        // swap layouts with size constraints (eg. min_columns) are re-applied when one of their
+        // constraints changes, even if the user has since changed the layout manually
+        let (tiled_size_constraints_changed, floating_size_constraints_changed) = self
+            .swap_layouts
+            .update_display_area(previous_screen_size, new_screen_size);
+        if self.auto_layout
+            && (!self.swap_layouts.is_floating_damaged() || floating_size_constraints_changed)
+        {
             // we do this only for floating panes, because the constraint system takes care of the
             // tiled panes
             self.swap_layouts.set_is_floating_damaged();
             let _ = self.relayout_floating_panes(false);
         }
         if self.auto_layout
-            && !self.swap_layouts.is_tiled_damaged()
+            && (!self.swap_layouts.is_tiled_damaged() || tiled_size_constraints_changed)
             && fullscreen_pane_to_restore.is_none()
         {
             self.swap_layouts.set_is_tiled_damaged();
@@ -4162,12 +4285,68 @@ impl Tab {
         }
         if self.floating_panes.panes_contain(&id) {
             self.floating_panes
-                .hold_pane(id, exit_status, is_first_run, run_command);
+                .hold_pane(id, exit_status, is_first_run, run_command.clone());
         } else if self.tiled_panes.panes_contain(&id) {
             self.tiled_panes
-                .hold_pane(id, exit_status, is_first_run, run_command);
+                .hold_pane(id, exit_status, is_first_run, run_command.clone());
         } else if let Some(pane) = self.suppressed_panes.values_mut().find(|p| p.1.pid() == id) {
-            pane.1.hold(exit_status, is_first_run, run_command);
+            pane.1.hold(exit_status, is_first_run, run_command.clone());
+        }
+        if !is_first_run {
+            self.restart_or_close_exited_pane(id, exit_status, &run_command);
+        }
+    }
+    fn restart_or_close_exited_pane(
+        &mut self,
+        id: PaneId,
+        exit_status: Option<i32>,
+        run_command: &RunCommand,
+    ) {
+        // panes are held on exit either to be re-run by the user or to be restarted by their
+        // restart policy, if neither is the case we close them ourselves - unless they ran out
+        // of restarts, in which case we keep them around so that their last output is not lost
+        let pane = self
+            .floating_panes
+            .get_pane(id)
+            .or_else(|| self.tiled_panes.get_pane(id))
+            .or_else(|| self.suppressed_panes.get(&id).map(|p| &p.1));
+        let pending_restart = pane.and_then(|pane| pane.pending_restart());
+        let ran_out_of_restarts =
+            pending_restart.is_none() && run_command.restart_policy.should_restart(exit_status);
+        if ran_out_of_restarts {
+            let last_lines = pane
+                .map(|pane| {
+                    let screen = pane.dump_screen(false, None);
+                    let lines: Vec<&str> = screen.trim_end().lines().collect();
+                    lines[lines.len().saturating_sub(EXITED_PANE_LOG_LINES)..].join("\n")
+                })
+                .unwrap_or_default();
+            log::warn!(
+                "Pane {:?} ({}) ran out of restarts, exit status: {:?}, last lines:\n{}",
+                id,
+                run_command,
+                exit_status,
+                last_lines
+            );
+        }
+        match (id, pending_restart) {
+            (PaneId::Terminal(terminal_pane_id), Some(attempt)) => {
+                let _ = self
+                    .senders
+                    .send_to_background_jobs(BackgroundJob::RestartCommandPane(
+                        terminal_pane_id,
+                        attempt,
+                    ));
+            },
+            _ if !run_command.hold_on_close && !ran_out_of_restarts => {
+                let _ = self.senders.send_to_screen(ScreenInstruction::ClosePane(
+                    id,
+                    None,
+                    None,
+                    exit_status,
+                ));
+            },
+            _ => {},
         }
     }
     pub fn replace_pane_with_suppressed_pane(
@@ -4352,6 +4531,102 @@ impl Tab {
             None
         }
     }
+    pub fn get_command_output(
+        &mut self,
+        pane_id: Option<PaneId>,
+        client_id: ClientId,
+        index: usize,
+    ) -> Option<String> {
+        match pane_id {
+            Some(pane_id) => self
+                .get_pane_with_id(pane_id)
+                .and_then(|pane| pane.command_output(index)),
+            None => self
+                .get_active_pane_or_floating_pane_mut(client_id)
+                .and_then(|pane| pane.command_output(index)),
+        }
+    }
+    pub fn dump_command_output(
+        &mut self,
+        file: Option<String>,
+        pane_id: Option<PaneId>,
+        client_id: ClientId,
+        index: usize,
+    ) -> Result<Option<String>> {
+        // writes the command output to the file if one was given, returns None if there is no
+        // such command output
+        let err_context = || format!("failed to dump command output for client {client_id}");
+        let command_output = self.get_command_output(pane_id, client_id, index);
+        if let (Some(command_output), Some(file)) = (&command_output, file) {
+            self.os_api
+                .write_to_file(command_output.clone(), Some(file))
+                .with_context(err_context)?;
+        }
+        Ok(command_output)
+    }
+    pub fn start_recording(
+        &self,
+        output: &Path,
+        pane_id: Option<PaneId>,
+        whole_tab: bool,
+        client_id: Option<ClientId>,
+    ) -> Result<StartedRecordings> {
+        // a pane that fails to start recording does not stop the others from starting, it is
+        // reported along with them
+        let recordings = self
+            .os_api
+            .recordings()
+            .context("recording panes is not supported")?;
+        let terminal_ids: Vec<u32> = if whole_tab {
+            self.get_all_pane_ids()
+                .into_iter()
+                .filter_map(|pane_id| match pane_id {
+                    PaneId::Terminal(id) if !recordings.is_recording(id) => Some(id),
+                    _ => None,
+                })
+                .collect()
+        } else {
+            match pane_id.or_else(|| client_id.and_then(|c| self.get_active_pane_id(c))) {
+                Some(PaneId::Terminal(id)) => vec![id],
+                Some(PaneId::Plugin(_)) => bail!("only terminal panes can be recorded"),
+                None => bail!("no pane to record"),
+            }
+        };
+        let mut started_recordings = StartedRecordings::default();
+        for terminal_id in terminal_ids {
+            let Some(pane) = self.get_pane_with_id(PaneId::Terminal(terminal_id)) else {
+                continue;
+            };
+            let path = if whole_tab {
+                recording_path_for_pane(output, terminal_id)
+            } else {
+                output.to_path_buf()
+            };
+            // start the recording from what is currently on screen rather than a blank terminal
+            let initial_output = format!(
+                "\u{1b}[H\u{1b}[2J{}",
+                pane.dump_screen_with_ansi(false, None)
+                    .replace('\n', "\r\n")
+            );
+            match recordings.start(
+                terminal_id,
+                &path,
+                pane.get_content_columns(),
+                pane.get_content_rows(),
+                Some(&pane.current_title()),
+                &initial_output,
+            ) {
+                Ok(()) => started_recordings.started.push((terminal_id, path)),
+                Err(e) => started_recordings.failed.push(format!(
+                    "failed to record terminal_{} to {}: {}",
+                    terminal_id,
+                    path.display(),
+                    e
+                )),
+            }
+        }
+        Ok(started_recordings)
+    }
     pub fn edit_scrollback(
         &mut self,
         client_id: ClientId,
@@ -4610,6 +4885,44 @@ impl Tab {
         Ok(())
     }
 
+    pub fn scroll_active_terminal_to_previous_prompt(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.scroll_to_previous_prompt();
+        }
+    }
+
+    pub fn scroll_active_terminal_to_next_prompt(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context =
+            || format!("failed to scroll to next prompt in active pane for client {client_id}");
+
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.scroll_to_next_prompt();
+            if !active_pane.is_scrolled() {
+                if let PaneId::Terminal(raw_fd) = active_pane.pid() {
+                    self.process_pending_vte_events(raw_fd)
+                        .with_context(err_context)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn select_last_command_output(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.select_last_command_output();
+        }
+    }
+
+    pub fn copy_last_command_output(&mut self, client_id: ClientId) -> Result<()> {
+        if let Some(command_output) = self.get_command_output(None, client_id, 0) {
+            self.copy_text_to_clipboard(&command_output)
+                .with_context(|| {
+                    format!("failed to copy last command output for client {client_id}")
+                })?;
+        }
+        Ok(())
+    }
+
     pub fn scroll_terminal_to_top(&mut self, terminal_pane_id: u32) {
         if let Some(terminal_pane) = self.get_pane_with_id_mut(PaneId::Terminal(terminal_pane_id)) {
             terminal_pane.clear_scroll();
@@ -5016,12 +5329,102 @@ impl Tab {
         }
     }
 
+    pub fn toggle_search_regex(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.toggle_search_regex();
+        }
+    }
+
     pub fn clear_search(&mut self, client_id: ClientId) {
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             active_pane.clear_search();
         }
     }
 
+    pub fn enter_copy_mode(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.enter_copy_mode();
+        }
+    }
+
+    pub fn exit_copy_mode(&mut self, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.exit_copy_mode();
+        }
+    }
+
+    pub fn copy_mode_move(&mut self, motion: CopyModeMotion, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.copy_mode_move(motion);
+        }
+    }
+
+    pub fn copy_mode_select(&mut self, kind: CopySelectionKind, client_id: ClientId) {
+        if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
+            active_pane.copy_mode_select(kind);
+        }
+    }
+
+    pub fn show_pane_hints(&mut self, client_id: ClientId) {
+        let mut tiled_panes: Vec<&Box<dyn Pane>> = self
+            .tiled_panes
+            .get_panes()
+            .filter(|(pane_id, pane)| {
+                pane.selectable() && !self.tiled_panes.panes_to_hide_contains(**pane_id)
+            })
+            .map(|(_, pane)| pane)
+            .collect();
+        tiled_panes.sort_by_key(|pane| (pane.y(), pane.x()));
+        let floating_panes_are_visible = self.floating_panes.panes_are_visible();
+        let mut floating_panes: Vec<&Box<dyn Pane>> = self
+            .floating_panes
+            .get_panes()
+            .filter(|(_, pane)| {
+                pane.selectable()
+                    && (floating_panes_are_visible || pane.position_and_size().is_pinned)
+            })
+            .map(|(_, pane)| pane)
+            .collect();
+        floating_panes.sort_by_key(|pane| (pane.y(), pane.x()));
+        let pane_ids: Vec<PaneId> = tiled_panes
+            .iter()
+            .chain(floating_panes.iter())
+            .map(|pane| pane.pid())
+            .collect();
+        let pane_hints = PaneHints::new(&pane_ids);
+        if !pane_hints.is_empty() {
+            self.pane_hints.insert(client_id, pane_hints);
+            self.set_force_render();
+        }
+    }
+
+    pub fn has_pane_hints(&self, client_id: ClientId) -> bool {
+        self.pane_hints.contains_key(&client_id)
+    }
+
+    pub fn handle_pane_hint_input(&mut self, input: &str, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to handle pane hint input for client {client_id}");
+        let Some(pane_hints) = self.pane_hints.get_mut(&client_id) else {
+            return Ok(());
+        };
+        match pane_hints.handle_input(input) {
+            PaneHintInput::Pending => {},
+            PaneHintInput::Selected(pane_id) => {
+                self.pane_hints.remove(&client_id);
+                // the pane might have been closed or hidden while the hints were shown
+                if self.has_non_suppressed_pane_with_pid(&pane_id) {
+                    self.focus_pane_with_id(pane_id, false, false, client_id)
+                        .with_context(err_context)?;
+                }
+            },
+            PaneHintInput::Cancelled => {
+                self.pane_hints.remove(&client_id);
+            },
+        }
+        self.set_force_render();
+        Ok(())
+    }
+
     pub fn is_pending(&self) -> bool {
         self.is_pending
     }
@@ -5378,6 +5781,65 @@ impl Tab {
             },
         }
     }
+    pub fn add_scratchpad(&mut self, name: String, pane_id: PaneId) {
+        self.scratchpads.insert(name, pane_id);
+    }
+    /// The pane of the scratchpad with this name, if it is still in this tab
+    pub fn scratchpad_pane_id(&self, name: &str) -> Option<PaneId> {
+        self.scratchpads
+            .get(name)
+            .copied()
+            .filter(|pane_id| self.has_pane_with_pid(pane_id))
+    }
+    pub fn scratchpad_name(&self, pane_id: PaneId) -> Option<String> {
+        self.scratchpads
+            .iter()
+            .find(|(_name, scratchpad_pane_id)| **scratchpad_pane_id == pane_id)
+            .map(|(name, _)| name.clone())
+    }
+    pub fn get_hidden_scratchpads(&self) -> impl Iterator<Item = (PaneId, &Box<dyn Pane>)> {
+        self.suppressed_panes
+            .values()
+            .map(|(_, pane)| (pane.pid(), pane))
+            .filter(|(pane_id, _)| self.scratchpads.values().any(|p| p == pane_id))
+    }
+    /// Hides the scratchpad with this name if it can be seen, otherwise shows and focuses it
+    pub fn toggle_scratchpad(&mut self, name: &str, client_id: ClientId) -> Result<()> {
+        let Some(pane_id) = self.scratchpad_pane_id(name) else {
+            return Ok(());
+        };
+        if self.tiled_panes.panes_contain(&pane_id)
+            || (self.floating_panes.panes_contain(&pane_id) && self.are_floating_panes_visible())
+        {
+            // suppressed under its own id, the pane keeps running until it is shown again
+            self.suppress_pane(pane_id, Some(client_id));
+            Ok(())
+        } else if self.floating_panes.panes_contain(&pane_id) {
+            self.show_floating_panes();
+            self.floating_panes.focus_pane_if_exists(pane_id, client_id)
+        } else {
+            match self.extract_scratchpad(name) {
+                Some(pane) => self.show_scratchpad(name.to_owned(), pane),
+                None => Ok(()),
+            }
+        }
+    }
+    /// Takes the scratchpad with this name out of the tab, eg. to show it in another tab
+    pub fn extract_scratchpad(&mut self, name: &str) -> Option<Box<dyn Pane>> {
+        let pane_id = self.scratchpads.remove(name)?;
+        let dont_swap_if_suppressed = true;
+        self.extract_pane(pane_id, dont_swap_if_suppressed)
+    }
+    /// Floats this scratchpad pane where it was last seen and focuses it
+    pub fn show_scratchpad(&mut self, name: String, pane: Box<dyn Pane>) -> Result<()> {
+        let pane_id = pane.pid();
+        let floating_pane_coordinates = pane.position_and_size().into();
+        self.show_floating_panes();
+        self.add_floating_pane(pane, pane_id, Some(floating_pane_coordinates), true)?;
+        self.scratchpads.insert(name, pane_id);
+        self.set_force_render();
+        Ok(())
+    }
     fn insert_suppressed_pane(
         &mut self,
         suppressing_pane_id: PaneId,
@@ -5619,6 +6081,24 @@ impl Tab {
             },
         }
     }
+    pub fn restart_terminal_pane_with_id(&mut self, terminal_pane_id: u32, attempt: usize) {
+        let pane_id = PaneId::Terminal(terminal_pane_id);
+        if let Some(pane_to_restart) = self
+            .floating_panes
+            .get_mut(&pane_id)
+            .or_else(|| self.tiled_panes.get_pane_mut(pane_id))
+            .or_else(|| self.suppressed_panes.get_mut(&pane_id).map(|p| &mut p.1))
+        {
+            if let Some(command_to_restart) = pane_to_restart.restart(attempt) {
+                self.pids_waiting_resize.insert(terminal_pane_id);
+                let _ = self.senders.send_to_pty(PtyInstruction::ReRunCommandInPane(
+                    pane_id,
+                    command_to_restart,
+                    None,
+                ));
+            }
+        }
+    }
     pub fn resize_pane_with_id(&mut self, strategy: ResizeStrategy, pane_id: PaneId) -> Result<()> {
         let err_context = || format!("unable to resize pane");
         if self.floating_panes.panes_contain(&pane_id) {
@@ -125,6 +125,10 @@ enum MouseAction {
         pane_id: PaneId,
         position: Position,
     },
+    StartBlockSelection {
+        pane_id: PaneId,
+        position: Position,
+    },
     UpdateSelection {
         position: Position,
     },
@@ -725,24 +729,10 @@ impl MouseHandler {
                 Ok(MouseEffect::state_changed())
             },
             MouseAction::StartSelection { pane_id, position } => {
-                let pane = tab
-                    .get_pane_with_id_mut(pane_id)
-                    .ok_or_else(|| anyhow!("Failed to find pane {pane_id:?}"))?;
-                let relative_position = pane.relative_position(&position);
-
-                let mut leave_clipboard_message = false;
-                pane.start_selection(&relative_position, client_id);
-                if pane.get_selected_text(client_id).is_some() {
-                    leave_clipboard_message = true;
-                }
-                if pane.supports_mouse_selection() {
-                    tab.selecting_with_mouse_in_pane = Some(pane_id);
-                }
-                if leave_clipboard_message {
-                    Ok(MouseEffect::leave_clipboard_message())
-                } else {
-                    Ok(MouseEffect::default())
-                }
+                Self::execute_start_selection(tab, pane_id, position, false, client_id)
+            },
+            MouseAction::StartBlockSelection { pane_id, position } => {
+                Self::execute_start_selection(tab, pane_id, position, true, client_id)
             },
             MouseAction::UpdateSelection { position } => {
                 if let Some(pane_id_with_selection) = tab.selecting_with_mouse_in_pane {
@@ -925,6 +915,37 @@ impl MouseHandler {
         Ok(MouseEffect::state_changed())
     }
 
+    fn execute_start_selection(
+        tab: &mut Tab,
+        pane_id: PaneId,
+        position: Position,
+        is_block: bool,
+        client_id: ClientId,
+    ) -> Result<MouseEffect> {
+        let pane = tab
+            .get_pane_with_id_mut(pane_id)
+            .ok_or_else(|| anyhow!("Failed to find pane {pane_id:?}"))?;
+        let relative_position = pane.relative_position(&position);
+
+        let mut leave_clipboard_message = false;
+        if is_block {
+            pane.start_block_selection(&relative_position, client_id);
+        } else {
+            pane.start_selection(&relative_position, client_id);
+        }
+        if pane.get_selected_text(client_id).is_some() {
+            leave_clipboard_message = true;
+        }
+        if pane.supports_mouse_selection() {
+            tab.selecting_with_mouse_in_pane = Some(pane_id);
+        }
+        if leave_clipboard_message {
+            Ok(MouseEffect::leave_clipboard_message())
+        } else {
+            Ok(MouseEffect::default())
+        }
+    }
+
     fn execute_end_selection(
         tab: &mut Tab,
         position: Position,
@@ -1290,6 +1311,13 @@ impl MouseHandler {
                         position: event.position,
                     });
                 }
+            } else if Some(details.pane_id) == ctx.active_pane_id {
+                // ctrl-dragging inside the active pane selects a rectangle, even if the terminal
+                // wants the mouse
+                return Ok(MouseAction::StartBlockSelection {
+                    pane_id: details.pane_id,
+                    position: event.position,
+                });
             }
             return Ok(MouseAction::NoAction);
         }
@@ -0,0 +1,89 @@
+use crate::panes::PaneId;
+
+// home row first so that the most common labels are the easiest to type
+const HINT_ALPHABET: &[char] = &[
+    'a', 's', 'd', 'f', 'g', 'h', 'j', 'k', 'l', 'q', 'w', 'e', 'r', 't', 'y', 'u', 'i', 'o', 'p',
+    'z', 'x', 'c', 'v', 'b', 'n', 'm',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneHintInput {
+    Pending,
+    Selected(PaneId),
+    Cancelled,
+}
+
+/// The labels shown on the frames of the visible panes of a tab while a client is choosing a
+/// pane to focus, along with what the client typed so far
+#[derive(Debug, Clone, Default)]
+pub struct PaneHints {
+    labels: Vec<(String, PaneId)>,
+    typed: String,
+}
+
+impl PaneHints {
+    pub fn new(pane_ids: &[PaneId]) -> Self {
+        let labels = hint_labels(pane_ids.len())
+            .into_iter()
+            .zip(pane_ids.iter().copied())
+            .collect();
+        PaneHints {
+            labels,
+            typed: String::new(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+    /// The label of this pane, if it can still be selected with what was typed so far
+    pub fn label_for(&self, pane_id: PaneId) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(label, id)| *id == pane_id && label.starts_with(&self.typed))
+            .map(|(label, _)| label.as_str())
+    }
+    pub fn handle_input(&mut self, input: &str) -> PaneHintInput {
+        for character in input.chars() {
+            self.typed.push(character.to_ascii_lowercase());
+            if let Some((_, pane_id)) = self.labels.iter().find(|(label, _)| *label == self.typed) {
+                return PaneHintInput::Selected(*pane_id);
+            }
+            if !self
+                .labels
+                .iter()
+                .any(|(label, _)| label.starts_with(&self.typed))
+            {
+                return PaneHintInput::Cancelled;
+            }
+        }
+        if self.typed.is_empty() {
+            PaneHintInput::Cancelled
+        } else {
+            PaneHintInput::Pending
+        }
+    }
+}
+
+fn hint_labels(count: usize) -> Vec<String> {
+    if count <= HINT_ALPHABET.len() {
+        HINT_ALPHABET
+            .iter()
+            .take(count)
+            .map(|c| c.to_string())
+            .collect()
+    } else {
+        HINT_ALPHABET
+            .iter()
+            .flat_map(|first| {
+                HINT_ALPHABET
+                    .iter()
+                    .map(move |second| format!("{first}{second}"))
+            })
+            .take(count)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/pane_hints_tests.rs"]
+mod pane_hints_tests;
@@ -183,36 +183,52 @@ impl SwapLayouts {
         constraint: &LayoutConstraint,
         tiled_panes: &TiledPanes,
     ) -> bool {
-        match constraint {
-            LayoutConstraint::MaxPanes(max_panes) => {
-                tiled_panes.visible_panes_count() <= *max_panes
-            },
-            LayoutConstraint::MinPanes(min_panes) => {
-                tiled_panes.visible_panes_count() >= *min_panes
-            },
-            LayoutConstraint::ExactPanes(pane_count) => {
-                tiled_panes.visible_panes_count() == *pane_count
-            },
-            LayoutConstraint::NoConstraint => true,
-        }
+        constraint.fits_pane_count(tiled_panes.visible_panes_count())
+            && constraint.fits_display_area(&self.display_area.borrow())
     }
     fn state_fits_floating_panes_constraint(
         &self,
         constraint: &LayoutConstraint,
         floating_panes: &FloatingPanes,
     ) -> bool {
-        match constraint {
-            LayoutConstraint::MaxPanes(max_panes) => {
-                floating_panes.visible_panes_count() <= *max_panes
-            },
-            LayoutConstraint::MinPanes(min_panes) => {
-                floating_panes.visible_panes_count() >= *min_panes
-            },
-            LayoutConstraint::ExactPanes(pane_count) => {
-                floating_panes.visible_panes_count() == *pane_count
-            },
-            LayoutConstraint::NoConstraint => true,
+        constraint.fits_pane_count(floating_panes.visible_panes_count())
+            && constraint.fits_display_area(&self.display_area.borrow())
+    }
+    /// To be called when the display area is resized, so that layouts with size constraints
+    /// (eg. min_columns) can be re-evaluated
+    ///
+    /// If a size constraint started or stopped fitting, the relevant layouts are marked as damaged
+    /// so that they will be searched again starting from the current one. If a constraint that did
+    /// not fit before fits now, we start the search from its layout instead.
+    /// Returns (tiled_layouts_changed, floating_layouts_changed)
+    pub fn update_display_area(
+        &mut self,
+        previous_display_area: Size,
+        new_display_area: Size,
+    ) -> (bool, bool) {
+        let (tiled_layouts_changed, newly_fitting_tiled_layout) = size_constraints_changed(
+            self.swap_tiled_layouts.iter().map(|l| &l.0),
+            &previous_display_area,
+            &new_display_area,
+        );
+        let (floating_layouts_changed, newly_fitting_floating_layout) = size_constraints_changed(
+            self.swap_floating_layouts.iter().map(|l| &l.0),
+            &previous_display_area,
+            &new_display_area,
+        );
+        if tiled_layouts_changed {
+            self.is_tiled_damaged = true;
+        }
+        if let Some(position) = newly_fitting_tiled_layout {
+            self.current_tiled_layout_position = position;
+        }
+        if floating_layouts_changed {
+            self.is_floating_damaged = true;
+        }
+        if let Some(position) = newly_fitting_floating_layout {
+            self.current_floating_layout_position = position;
         }
+        (tiled_layouts_changed, floating_layouts_changed)
     }
     pub fn swap_tiled_panes(
         &mut self,
@@ -289,3 +305,26 @@ impl SwapLayouts {
         None
     }
 }
+
+fn size_constraints_changed<'a, T: 'a>(
+    layouts: impl Iterator<Item = &'a BTreeMap<LayoutConstraint, T>>,
+    previous_display_area: &Size,
+    new_display_area: &Size,
+) -> (bool, Option<usize>) {
+    // (any_constraint_changed, position_of_first_layout_with_a_newly_fitting_constraint)
+    let mut any_constraint_changed = false;
+    let mut first_newly_fitting_layout = None;
+    for (position, layout) in layouts.enumerate() {
+        for constraint in layout.keys() {
+            let fitted = constraint.fits_display_area(previous_display_area);
+            let fits = constraint.fits_display_area(new_display_area);
+            if fitted != fits {
+                any_constraint_changed = true;
+            }
+            if fits && !fitted && first_newly_fitting_layout.is_none() {
+                first_newly_fitting_layout = Some(position);
+            }
+        }
+    }
+    (any_constraint_changed, first_newly_fitting_layout)
+}
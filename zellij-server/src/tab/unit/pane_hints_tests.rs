@@ -0,0 +1,60 @@
+use super::*;
+
+#[test]
+fn single_letter_labels_for_few_panes() {
+    let pane_hints = PaneHints::new(&[PaneId::Terminal(1), PaneId::Plugin(2), PaneId::Terminal(3)]);
+
+    assert_eq!(pane_hints.label_for(PaneId::Terminal(1)), Some("a"));
+    assert_eq!(pane_hints.label_for(PaneId::Plugin(2)), Some("s"));
+    assert_eq!(pane_hints.label_for(PaneId::Terminal(3)), Some("d"));
+    assert_eq!(pane_hints.label_for(PaneId::Terminal(2)), None);
+}
+
+#[test]
+fn typing_a_label_selects_its_pane() {
+    let mut pane_hints = PaneHints::new(&[PaneId::Terminal(1), PaneId::Terminal(2)]);
+
+    assert_eq!(
+        pane_hints.handle_input("s"),
+        PaneHintInput::Selected(PaneId::Terminal(2))
+    );
+}
+
+#[test]
+fn labels_are_case_insensitive() {
+    let mut pane_hints = PaneHints::new(&[PaneId::Terminal(1), PaneId::Terminal(2)]);
+
+    assert_eq!(
+        pane_hints.handle_input("A"),
+        PaneHintInput::Selected(PaneId::Terminal(1))
+    );
+}
+
+#[test]
+fn typing_something_else_cancels() {
+    let mut pane_hints = PaneHints::new(&[PaneId::Terminal(1), PaneId::Terminal(2)]);
+
+    assert_eq!(pane_hints.handle_input("x"), PaneHintInput::Cancelled);
+    let mut pane_hints = PaneHints::new(&[PaneId::Terminal(1), PaneId::Terminal(2)]);
+    assert_eq!(pane_hints.handle_input("\u{1b}"), PaneHintInput::Cancelled);
+}
+
+#[test]
+fn two_letter_labels_for_many_panes() {
+    let pane_ids: Vec<PaneId> = (0..30).map(PaneId::Terminal).collect();
+    let mut pane_hints = PaneHints::new(&pane_ids);
+
+    assert_eq!(pane_hints.label_for(PaneId::Terminal(0)), Some("aa"));
+    assert_eq!(pane_hints.label_for(PaneId::Terminal(26)), Some("sa"));
+    assert_eq!(pane_hints.handle_input("s"), PaneHintInput::Pending);
+    assert_eq!(
+        pane_hints.label_for(PaneId::Terminal(0)),
+        None,
+        "labels that can no longer be typed are hidden"
+    );
+    assert_eq!(pane_hints.label_for(PaneId::Terminal(26)), Some("sa"));
+    assert_eq!(
+        pane_hints.handle_input("s"),
+        PaneHintInput::Selected(PaneId::Terminal(27))
+    );
+}
@@ -5105,6 +5105,101 @@ fn swapping_layouts_after_resize_snaps_to_current_layout() {
     assert_snapshot!(snapshot);
 }
 
+#[test]
+fn swap_layouts_with_size_constraints_are_reapplied_on_resize() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let swap_layouts = r#"
+        layout {
+            swap_tiled_layout {
+                tab min_columns=200 max_panes=2 {
+                    pane split_direction="vertical" {
+                        pane
+                        pane
+                    }
+                }
+                tab max_panes=2 {
+                    pane
+                    pane
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(swap_layouts, Some("file_name.kdl".into()), None, None).unwrap();
+    let swap_tiled_layouts = layout.swap_tiled_layouts.clone();
+    let swap_floating_layouts = layout.swap_floating_layouts.clone();
+    let stacked_resize = true;
+    let mut tab = create_new_tab_with_swap_layouts(
+        size,
+        ModeInfo::default(),
+        (swap_tiled_layouts, swap_floating_layouts),
+        None,
+        true,
+        stacked_resize,
+    );
+    let new_pane_id_1 = PaneId::Terminal(2);
+
+    tab.new_pane(
+        new_pane_id_1,
+        None,
+        None,
+        false,
+        true,
+        NewPanePlacement::default(),
+        Some(client_id),
+        None,
+    )
+    .unwrap();
+    let second_pane_geom = |tab: &Tab| {
+        tab.tiled_panes
+            .panes
+            .get(&new_pane_id_1)
+            .unwrap()
+            .position_and_size()
+    };
+    assert_eq!(
+        second_pane_geom(&tab).x,
+        0,
+        "panes stacked on a narrow screen"
+    );
+    assert!(
+        second_pane_geom(&tab).y > 0,
+        "panes stacked on a narrow screen"
+    );
+
+    // manually resizing marks the layout as damaged, size constraints should still apply
+    tab.resize(client_id, ResizeStrategy::new(Resize::Increase, None))
+        .unwrap();
+    tab.resize_whole_tab(Size {
+        cols: 240,
+        rows: 20,
+    })
+    .unwrap();
+    assert!(
+        second_pane_geom(&tab).x > 0,
+        "panes side by side on a wide screen"
+    );
+    assert_eq!(
+        second_pane_geom(&tab).y,
+        0,
+        "panes side by side on a wide screen"
+    );
+
+    tab.resize_whole_tab(size).unwrap();
+    assert_eq!(
+        second_pane_geom(&tab).x,
+        0,
+        "panes stacked again on a narrow screen"
+    );
+    assert!(
+        second_pane_geom(&tab).y > 0,
+        "panes stacked again on a narrow screen"
+    );
+}
+
 #[test]
 fn swap_tiled_layout_with_stacked_children() {
     let size = Size {
@@ -10372,6 +10467,48 @@ fn test_left_release_after_selection_copies_to_clipboard() {
     assert!(release_effect.leave_clipboard_message);
 }
 
+#[test]
+fn test_ctrl_drag_selects_and_copies_a_block() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+
+    tab.handle_pty_bytes(
+        1,
+        Vec::from("USER   PID\r\nroot   1\r\nzellij 4242".as_bytes()),
+    )
+    .unwrap();
+
+    tab.handle_mouse_event(
+        &MouseEvent::new_left_press_with_ctrl_event(Position::new(1, 8)),
+        client_id,
+    )
+    .unwrap();
+    tab.handle_mouse_event(
+        &MouseEvent::new_left_motion_with_ctrl_event(Position::new(3, 12)),
+        client_id,
+    )
+    .unwrap();
+    let release_effect = tab
+        .handle_mouse_event(
+            &MouseEvent::new_left_release_with_ctrl_event(Position::new(3, 12)),
+            client_id,
+        )
+        .unwrap();
+
+    assert!(release_effect.leave_clipboard_message);
+    assert_eq!(
+        tab.get_active_pane(client_id)
+            .unwrap()
+            .get_selected_text(client_id),
+        Some("PID\n1\n4242".to_owned()),
+        "only the columns between the two points are selected"
+    );
+}
+
 #[test]
 fn test_ctrl_click_on_tiled_pane_edge_starts_resize() {
     let size = Size {
@@ -14845,3 +14982,138 @@ fn hidden_cursor_still_emits_cup_for_host_terminal_positioning() {
         "Show-cursor sequence must not be present when app has hidden the cursor"
     );
 }
+
+#[test]
+fn pane_hints_label_tiled_stacked_and_floating_panes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    let mut output = Output::default();
+    tab.vertical_split(PaneId::Terminal(2), None, client_id, None, None)
+        .unwrap();
+    tab.new_pane(
+        PaneId::Terminal(3),
+        None,
+        None,
+        false,
+        true,
+        NewPanePlacement::Stacked {
+            pane_id_to_stack_under: None,
+            borderless: None,
+        },
+        Some(client_id),
+        None,
+    )
+    .unwrap();
+    tab.toggle_floating_panes(Some(client_id), None, None)
+        .unwrap();
+    tab.new_pane(
+        PaneId::Terminal(4),
+        None,
+        None,
+        false,
+        true,
+        NewPanePlacement::default(),
+        Some(client_id),
+        None,
+    )
+    .unwrap();
+
+    tab.show_pane_hints(client_id);
+    tab.render(&mut output, None).unwrap();
+    let snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+    assert_snapshot!(snapshot);
+    assert!(
+        !tab.has_pane_hints(client_id + 1),
+        "pane hints are only shown to the client that asked for them"
+    );
+
+    tab.handle_pane_hint_input("a", client_id).unwrap();
+    assert_eq!(
+        tab.get_active_pane_id(client_id),
+        Some(PaneId::Terminal(1)),
+        "typing a label focuses its pane"
+    );
+    assert!(
+        !tab.are_floating_panes_visible(),
+        "focusing a tiled pane hides the floating panes"
+    );
+    assert!(!tab.has_pane_hints(client_id), "pane hints are dismissed");
+}
+
+#[test]
+fn pane_hints_are_dismissed_by_an_unknown_key() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    tab.vertical_split(PaneId::Terminal(2), None, client_id, None, None)
+        .unwrap();
+
+    tab.show_pane_hints(client_id);
+    assert!(tab.has_pane_hints(client_id));
+    tab.handle_pane_hint_input("\u{1b}", client_id).unwrap();
+    assert!(!tab.has_pane_hints(client_id));
+    assert_eq!(
+        tab.get_active_pane_id(client_id),
+        Some(PaneId::Terminal(2)),
+        "focus does not change"
+    );
+}
+
+#[test]
+fn pane_hints_label_panes_without_frames() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab_without_pane_frames(size, ModeInfo::default());
+    let mut output = Output::default();
+    tab.vertical_split(PaneId::Terminal(2), None, client_id, None, None)
+        .unwrap();
+    tab.handle_pty_bytes(1, Vec::from("pane content".as_bytes()))
+        .unwrap();
+
+    tab.show_pane_hints(client_id);
+    tab.render(&mut output, None).unwrap();
+    let snapshot = take_snapshot(
+        output.serialize().unwrap().get(&client_id).unwrap(),
+        size.rows,
+        size.cols,
+        Palette::default(),
+    );
+    assert_snapshot!(snapshot);
+}
+
+#[test]
+fn pane_hints_for_a_closed_pane_are_dismissed() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    tab.vertical_split(PaneId::Terminal(2), None, client_id, None, None)
+        .unwrap();
+
+    tab.show_pane_hints(client_id);
+    tab.close_pane(PaneId::Terminal(2), false, None);
+    tab.handle_pane_hint_input("s", client_id).unwrap();
+    assert!(!tab.has_pane_hints(client_id));
+    assert_eq!(
+        tab.get_active_pane_id(client_id),
+        Some(PaneId::Terminal(1)),
+        "focus does not change"
+    );
+}
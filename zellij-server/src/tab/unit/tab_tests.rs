@@ -16307,3 +16307,159 @@ pub fn cli_rename_active_pane_then_interactive_esc_restores() {
     let pane = tab.get_pane_with_id(pane_id).unwrap();
     assert_eq!(pane.current_title(), "spark");
 }
+
+fn tiled_pane_geoms(tab: &Tab) -> Vec<(PaneId, (usize, usize, usize, usize))> {
+    let mut geoms: Vec<_> = tab
+        .tiled_panes
+        .panes
+        .iter()
+        .map(|(pane_id, pane)| {
+            let geom = pane.position_and_size();
+            (
+                *pane_id,
+                (geom.x, geom.y, geom.cols.as_usize(), geom.rows.as_usize()),
+            )
+        })
+        .collect();
+    geoms.sort_by_key(|(_, geom)| *geom);
+    geoms
+}
+
+#[test]
+fn reopened_pane_is_restored_to_its_previous_geom() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let stacked_resize = false;
+    let mut tab = create_new_tab(size, stacked_resize);
+    for i in 2..5 {
+        tab.new_pane(
+            PaneId::Terminal(i),
+            None,
+            None,
+            false,
+            true,
+            NewPanePlacement::default(),
+            Some(1),
+            None,
+        )
+        .unwrap();
+    }
+    let geoms_before_close = tiled_pane_geoms(&tab);
+
+    for (closed_pane_id, reopened_pane_id) in [(1, 5), (3, 6)] {
+        let closed_pane_id = PaneId::Terminal(closed_pane_id);
+        let reopened_pane_id = PaneId::Terminal(reopened_pane_id);
+        let geom = tab
+            .tiled_panes
+            .panes
+            .get(&closed_pane_id)
+            .unwrap()
+            .position_and_size();
+        let expected_geoms: Vec<_> = tiled_pane_geoms(&tab)
+            .into_iter()
+            .map(|(pane_id, geom)| {
+                if pane_id == closed_pane_id {
+                    (reopened_pane_id, geom)
+                } else {
+                    (pane_id, geom)
+                }
+            })
+            .collect();
+        tab.close_pane(closed_pane_id, true, None);
+        assert_ne!(
+            tiled_pane_geoms(&tab).len(),
+            geoms_before_close.len(),
+            "pane was closed"
+        );
+
+        tab.new_pane(
+            reopened_pane_id,
+            None,
+            None,
+            false,
+            true,
+            NewPanePlacement::default(),
+            Some(1),
+            None,
+        )
+        .unwrap();
+        tab.restore_tiled_pane_geom(reopened_pane_id, geom);
+
+        assert_eq!(
+            tiled_pane_geoms(&tab),
+            expected_geoms,
+            "all panes are back where they were"
+        );
+    }
+}
+
+#[test]
+fn toggling_a_scratchpad_hides_it_and_shows_it_where_it_was() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let stacked_resize = false;
+    let mut tab = create_new_tab(size, stacked_resize);
+    let client_id = 1;
+    let scratchpad_pane_id = PaneId::Terminal(2);
+    tab.new_floating_pane(scratchpad_pane_id, None, None, false, true, None, None)
+        .unwrap();
+    tab.add_scratchpad("notes".to_owned(), scratchpad_pane_id);
+    let geom_before_toggle = tab
+        .floating_panes
+        .get_pane(scratchpad_pane_id)
+        .unwrap()
+        .position_and_size();
+
+    tab.toggle_scratchpad("notes", client_id).unwrap();
+    assert!(
+        !tab.floating_panes.panes_contain(&scratchpad_pane_id),
+        "scratchpad was hidden"
+    );
+    assert_eq!(
+        tab.scratchpad_pane_id("notes"),
+        Some(scratchpad_pane_id),
+        "hidden scratchpad is still running in the tab"
+    );
+    assert_eq!(tab.get_hidden_scratchpads().count(), 1);
+
+    tab.toggle_scratchpad("notes", client_id).unwrap();
+    assert!(tab.are_floating_panes_visible(), "floating panes are shown");
+    assert_eq!(
+        tab.floating_panes
+            .get_pane(scratchpad_pane_id)
+            .unwrap()
+            .position_and_size(),
+        geom_before_toggle,
+        "scratchpad is shown where it was"
+    );
+    assert_eq!(tab.get_hidden_scratchpads().count(), 0);
+}
+
+#[test]
+fn toggling_a_scratchpad_under_hidden_floating_panes_shows_it() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let stacked_resize = false;
+    let mut tab = create_new_tab(size, stacked_resize);
+    let client_id = 1;
+    let scratchpad_pane_id = PaneId::Terminal(2);
+    tab.new_floating_pane(scratchpad_pane_id, None, None, false, true, None, None)
+        .unwrap();
+    tab.add_scratchpad("notes".to_owned(), scratchpad_pane_id);
+    tab.toggle_floating_panes(Some(client_id), None, None)
+        .unwrap();
+    assert!(!tab.are_floating_panes_visible());
+
+    tab.toggle_scratchpad("notes", client_id).unwrap();
+    assert!(tab.are_floating_panes_visible(), "floating panes are shown");
+    assert!(
+        tab.floating_panes.panes_contain(&scratchpad_pane_id),
+        "scratchpad was not hidden"
+    );
+}
@@ -1,4 +1,7 @@
-use crate::{os_input_output::AsyncReader, screen::ScreenInstruction, thread_bus::ThreadSenders};
+use crate::{
+    os_input_output::AsyncReader, recording::Recordings, screen::ScreenInstruction,
+    thread_bus::ThreadSenders,
+};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
@@ -16,6 +19,7 @@ pub(crate) struct TerminalBytes {
     async_reader: Box<dyn AsyncReader>,
     debug: bool,
     activity_flag: Arc<AtomicBool>,
+    recordings: Option<Recordings>,
 }
 
 impl TerminalBytes {
@@ -25,6 +29,7 @@ impl TerminalBytes {
         senders: ThreadSenders,
         debug: bool,
         activity_flag: Arc<AtomicBool>,
+        recordings: Option<Recordings>,
     ) -> Self {
         TerminalBytes {
             terminal_id,
@@ -32,6 +37,7 @@ impl TerminalBytes {
             debug,
             async_reader,
             activity_flag,
+            recordings,
         }
     }
     pub async fn listen(&mut self) -> Result<()> {
@@ -64,6 +70,9 @@ impl TerminalBytes {
                     if self.debug {
                         let _ = debug_to_file(bytes, self.terminal_id as i32);
                     }
+                    if let Some(recordings) = &self.recordings {
+                        recordings.record_output(self.terminal_id, bytes);
+                    }
                     self.async_send_to_screen(ScreenInstruction::PtyBytes(
                         self.terminal_id,
                         bytes.to_vec(),
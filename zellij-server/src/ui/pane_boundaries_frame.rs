@@ -28,7 +28,10 @@ fn foreground_color(characters: &str, color: Option<PaletteColor>) -> Vec<Termin
     colored_string
 }
 
-fn background_color(characters: &str, color: Option<PaletteColor>) -> Vec<TerminalCharacter> {
+pub(crate) fn background_color(
+    characters: &str,
+    color: Option<PaletteColor>,
+) -> Vec<TerminalCharacter> {
     let mut colored_string = Vec::new();
     for character in characters.chars() {
         let mut styles = RcCharacterStyles::reset();
@@ -67,6 +70,8 @@ pub struct FrameParams {
     pub pane_is_selectable: bool,
     pub show_help_text: bool,
     pub highlight_tooltip: Option<String>,
+    pub pane_is_in_sync_group: bool,
+    pub pane_hint_label: Option<String>,
 }
 
 #[derive(Default, PartialEq)]
@@ -82,6 +87,8 @@ pub struct PaneFrame {
     pub other_focused_clients: Vec<ClientId>,
     exit_status: Option<ExitStatus>,
     is_first_run: bool,
+    restart_count: usize,
+    last_exit_status: Option<ExitStatus>,
     pane_is_stacked_over: bool,
     pane_is_stacked_under: bool,
     should_draw_pane_frames: bool,
@@ -92,6 +99,8 @@ pub struct PaneFrame {
     is_selectable: bool,
     show_help_text: bool,
     highlight_tooltip: Option<String>,
+    is_in_sync_group: bool,
+    hint_label: Option<String>,
 }
 
 impl PaneFrame {
@@ -113,6 +122,8 @@ impl PaneFrame {
             other_cursors_exist_in_session: frame_params.other_cursors_exist_in_session,
             exit_status: None,
             is_first_run: false,
+            restart_count: 0,
+            last_exit_status: None,
             pane_is_stacked_over: frame_params.pane_is_stacked_over,
             pane_is_stacked_under: frame_params.pane_is_stacked_under,
             should_draw_pane_frames: frame_params.should_draw_pane_frames,
@@ -123,6 +134,8 @@ impl PaneFrame {
             is_selectable: frame_params.pane_is_selectable,
             show_help_text: frame_params.show_help_text,
             highlight_tooltip: frame_params.highlight_tooltip,
+            is_in_sync_group: frame_params.pane_is_in_sync_group,
+            hint_label: frame_params.pane_hint_label,
         }
     }
     pub fn is_pinned(mut self, is_pinned: bool) -> Self {
@@ -135,6 +148,13 @@ impl PaneFrame {
             None => Some(ExitStatus::Exited),
         };
     }
+    pub fn add_restart_info(&mut self, restart_count: usize, last_exit_status: Option<i32>) {
+        self.restart_count = restart_count;
+        self.last_exit_status = match last_exit_status {
+            Some(exit_status) => Some(ExitStatus::Code(exit_status)),
+            None => Some(ExitStatus::Exited),
+        };
+    }
     pub fn indicate_first_run(&mut self) {
         self.is_first_run = true;
     }
@@ -174,6 +194,74 @@ impl PaneFrame {
         max_length: usize,
     ) -> Option<(Vec<TerminalCharacter>, usize)> {
         // string and length because of color
+        let sync_indication = self.render_sync_indication(max_length);
+        let space_for_other_indications = sync_indication
+            .as_ref()
+            .map(|(_, length)| max_length.saturating_sub(*length + 1))
+            .unwrap_or(max_length);
+        let restart_indication = if self.exit_status.is_none() && self.restart_count > 0 {
+            // once the command exits, the restart count is part of the exit status undertitle
+            self.render_restart_indication(space_for_other_indications)
+        } else {
+            None
+        };
+        let space_for_scroll_and_pin_indications = restart_indication
+            .as_ref()
+            .map(|(_, length)| space_for_other_indications.saturating_sub(*length + 1))
+            .unwrap_or(space_for_other_indications);
+        let scroll_and_pin_indications =
+            self.render_scroll_and_pin_indications(space_for_scroll_and_pin_indications);
+        let other_indications = match (restart_indication, scroll_and_pin_indications) {
+            (
+                Some((mut characters, restart_indication_len)),
+                Some((mut scroll_and_pin_indications, scroll_and_pin_indications_len)),
+            ) => {
+                let mut separator = foreground_color("|", self.color);
+                characters.append(&mut separator);
+                characters.append(&mut scroll_and_pin_indications);
+                Some((
+                    characters,
+                    restart_indication_len + scroll_and_pin_indications_len + 1,
+                ))
+            },
+            (Some(restart_indication), None) => Some(restart_indication),
+            (None, Some(scroll_and_pin_indications)) => Some(scroll_and_pin_indications),
+            _ => None,
+        };
+        match (sync_indication, other_indications) {
+            (
+                Some((mut characters, sync_indication_len)),
+                Some((mut other_indications, other_indications_len)),
+            ) => {
+                let mut separator = foreground_color("|", self.color);
+                characters.append(&mut separator);
+                characters.append(&mut other_indications);
+                Some((characters, sync_indication_len + other_indications_len + 1))
+            },
+            (Some(sync_indication), None) => Some(sync_indication),
+            (None, Some(other_indications)) => Some(other_indications),
+            _ => None,
+        }
+    }
+    fn render_sync_indication(&self, max_length: usize) -> Option<(Vec<TerminalCharacter>, usize)> {
+        if !self.is_in_sync_group {
+            return None;
+        }
+        let full_indication = " SYNC ";
+        let full_indication_len = full_indication.chars().count();
+        if full_indication_len <= max_length {
+            Some((
+                foreground_color(full_indication, self.color),
+                full_indication_len,
+            ))
+        } else {
+            None
+        }
+    }
+    fn render_scroll_and_pin_indications(
+        &self,
+        max_length: usize,
+    ) -> Option<(Vec<TerminalCharacter>, usize)> {
         let has_scroll = self.scroll_position.0 > 0 || self.scroll_position.1 > 0;
         if has_scroll && self.is_selectable {
             // TODO: don't show SCROLL at all for plugins
@@ -237,6 +325,34 @@ impl PaneFrame {
             None
         }
     }
+    fn render_restart_indication(
+        &self,
+        max_length: usize,
+    ) -> Option<(Vec<TerminalCharacter>, usize)> {
+        let full_indication = match self.last_exit_status {
+            Some(ExitStatus::Code(exit_code)) => format!(
+                " RESTARTS: {} (LAST EXIT: {}) ",
+                self.restart_count, exit_code
+            ),
+            _ => format!(" RESTARTS: {} ", self.restart_count),
+        };
+        let short_indication = format!(" RESTARTS: {} ", self.restart_count);
+        let full_indication_len = full_indication.chars().count();
+        let short_indication_len = short_indication.chars().count();
+        if full_indication_len <= max_length {
+            Some((
+                foreground_color(&full_indication, self.color),
+                full_indication_len,
+            ))
+        } else if short_indication_len <= max_length {
+            Some((
+                foreground_color(&short_indication, self.color),
+                short_indication_len,
+            ))
+        } else {
+            None
+        }
+    }
     fn render_pinned_indication(
         &self,
         max_length: usize,
@@ -253,6 +369,28 @@ impl PaneFrame {
             None
         }
     }
+    fn render_hint_label(&self, max_length: usize) -> Option<(Vec<TerminalCharacter>, usize)> {
+        let hint_label = self.hint_label.as_ref()?;
+        let hint_text = format!(" {} ", hint_label);
+        let hint_indication_len = hint_text.width() + 2; // 2 for separators
+        if hint_indication_len > max_length {
+            return None;
+        }
+        let mut hint_indication = vec![];
+        hint_indication.append(&mut foreground_color(
+            boundary_type::VERTICAL_LEFT,
+            self.color,
+        ));
+        hint_indication.append(&mut background_color(
+            &hint_text,
+            Some(self.style.colors.frame_highlight.base),
+        ));
+        hint_indication.append(&mut foreground_color(
+            boundary_type::VERTICAL_RIGHT,
+            self.color,
+        ));
+        Some((hint_indication, hint_indication_len))
+    }
     fn render_my_focus(&self, max_length: usize) -> Option<(Vec<TerminalCharacter>, usize)> {
         let mut left_separator = foreground_color(boundary_type::VERTICAL_LEFT, self.color);
         let mut right_separator = foreground_color(boundary_type::VERTICAL_RIGHT, self.color);
@@ -386,7 +524,9 @@ impl PaneFrame {
     }
     fn render_title_middle(&self, max_length: usize) -> Option<(Vec<TerminalCharacter>, usize)> {
         // string and length because of color
-        if self.is_main_client
+        if self.hint_label.is_some() {
+            self.render_hint_label(max_length)
+        } else if self.is_main_client
             && self.other_focused_clients.is_empty()
             && !self.other_cursors_exist_in_session
         {
@@ -1051,6 +1191,7 @@ impl PaneFrame {
                 } else {
                     self.style.colors.exit_code_error.base
                 };
+                let restarts_text = self.restarts_text();
                 let right_bracket = " ] ";
                 first_part.append(&mut foreground_color(left_bracket, self.color));
                 first_part.append(&mut foreground_color(exited_text, self.color));
@@ -1058,12 +1199,14 @@ impl PaneFrame {
                     &exit_code_text,
                     Some(exit_code_color),
                 ));
+                first_part.append(&mut foreground_color(&restarts_text, self.color));
                 first_part.append(&mut foreground_color(right_bracket, self.color));
                 (
                     first_part,
                     left_bracket.len()
                         + exited_text.len()
                         + exit_code_text.len()
+                        + restarts_text.len()
                         + right_bracket.len(),
                 )
             },
@@ -1071,21 +1214,33 @@ impl PaneFrame {
                 let mut first_part = vec![];
                 let left_bracket = " [ ";
                 let exited_text = "EXITED";
+                let restarts_text = self.restarts_text();
                 let right_bracket = " ] ";
                 first_part.append(&mut foreground_color(left_bracket, self.color));
                 first_part.append(&mut foreground_color(
                     exited_text,
                     Some(self.style.colors.exit_code_error.base),
                 ));
+                first_part.append(&mut foreground_color(&restarts_text, self.color));
                 first_part.append(&mut foreground_color(right_bracket, self.color));
                 (
                     first_part,
-                    left_bracket.len() + exited_text.len() + right_bracket.len(),
+                    left_bracket.len()
+                        + exited_text.len()
+                        + restarts_text.len()
+                        + right_bracket.len(),
                 )
             },
             None => (foreground_color(boundary_type::HORIZONTAL, self.color), 1),
         }
     }
+    fn restarts_text(&self) -> String {
+        if self.restart_count > 0 {
+            format!(", RESTARTS: {}", self.restart_count)
+        } else {
+            String::new()
+        }
+    }
     fn second_held_title_part_full(&self) -> (Vec<TerminalCharacter>, usize) {
         // (title part, length)
         let mut second_part = vec![];
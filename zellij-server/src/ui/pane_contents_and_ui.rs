@@ -1,10 +1,11 @@
-use crate::output::Output;
+use crate::output::{CharacterChunk, Output};
 use crate::panes::PaneId;
-use crate::tab::Pane;
+use crate::tab::{Pane, PaneHints};
 use crate::ui::boundaries::Boundaries;
-use crate::ui::pane_boundaries_frame::FrameParams;
+use crate::ui::pane_boundaries_frame::{background_color, FrameParams};
 use crate::ClientId;
 use std::collections::{HashMap, HashSet};
+use unicode_width::UnicodeWidthStr;
 use zellij_utils::data::{client_id_to_colors, InputMode, PaletteColor, Style};
 use zellij_utils::errors::prelude::*;
 pub struct PaneContentsAndUi<'a> {
@@ -19,10 +20,13 @@ pub struct PaneContentsAndUi<'a> {
     should_draw_pane_frames: bool,
     mouse_is_hovering_over_pane_for_clients: HashSet<ClientId>,
     current_pane_group: HashMap<ClientId, Vec<PaneId>>,
+    pane_is_in_sync_group: bool,
+    pane_hint_labels: HashMap<ClientId, String>,
     show_help_text: bool,
 }
 
 impl<'a> PaneContentsAndUi<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         pane: &'a mut Box<dyn Pane>,
         output: &'a mut Output,
@@ -35,6 +39,8 @@ impl<'a> PaneContentsAndUi<'a> {
         should_draw_pane_frames: bool,
         mouse_hover_pane_id: &HashMap<ClientId, PaneId>,
         current_pane_group: HashMap<ClientId, Vec<PaneId>>,
+        sync_group: &[PaneId],
+        pane_hints: &HashMap<ClientId, PaneHints>,
         show_help_text: bool,
     ) -> Self {
         let mut focused_clients: Vec<ClientId> = active_panes
@@ -53,6 +59,15 @@ impl<'a> PaneContentsAndUi<'a> {
                 }
             })
             .collect();
+        let pane_is_in_sync_group = sync_group.contains(&pane.pid());
+        let pane_hint_labels = pane_hints
+            .iter()
+            .filter_map(|(client_id, pane_hints)| {
+                pane_hints
+                    .label_for(pane.pid())
+                    .map(|label| (*client_id, label.to_owned()))
+            })
+            .collect();
         PaneContentsAndUi {
             pane,
             output,
@@ -65,6 +80,8 @@ impl<'a> PaneContentsAndUi<'a> {
             should_draw_pane_frames,
             mouse_is_hovering_over_pane_for_clients,
             current_pane_group,
+            pane_is_in_sync_group,
+            pane_hint_labels,
             show_help_text,
         }
     }
@@ -258,6 +275,8 @@ impl<'a> PaneContentsAndUi<'a> {
                 pane_is_selectable,
                 show_help_text: self.show_help_text,
                 highlight_tooltip: highlight_tooltip.clone(),
+                pane_is_in_sync_group: self.pane_is_in_sync_group,
+                pane_hint_label: self.pane_hint_labels.get(&client_id).cloned(),
             }
         } else {
             FrameParams {
@@ -278,6 +297,8 @@ impl<'a> PaneContentsAndUi<'a> {
                 pane_is_selectable,
                 show_help_text: self.show_help_text,
                 highlight_tooltip,
+                pane_is_in_sync_group: self.pane_is_in_sync_group,
+                pane_hint_label: self.pane_hint_labels.get(&client_id).cloned(),
             }
         };
 
@@ -297,6 +318,25 @@ impl<'a> PaneContentsAndUi<'a> {
 
         Ok(())
     }
+    /// Panes without a frame show their hint label over the top left corner of their contents,
+    /// so this should be rendered after them
+    pub fn render_pane_hint_label_over_contents(&mut self, client_id: ClientId) -> Result<()> {
+        let Some(hint_label) = self.pane_hint_labels.get(&client_id) else {
+            return Ok(());
+        };
+        let hint_text = format!(" {} ", hint_label);
+        if hint_text.width() > self.pane.get_content_columns() {
+            return Ok(());
+        }
+        let hint_indication = CharacterChunk::new(
+            background_color(&hint_text, Some(self.style.colors.frame_highlight.base)),
+            self.pane.get_content_x(),
+            self.pane.get_content_y(),
+        );
+        self.output
+            .add_character_chunks_to_client(client_id, vec![hint_indication], self.z_index)
+            .with_context(|| format!("failed to render pane hint label for client {client_id}"))
+    }
     pub fn render_pane_boundaries(
         &self,
         client_id: ClientId,
@@ -337,6 +377,8 @@ impl<'a> PaneContentsAndUi<'a> {
             Some((self.style.colors.frame_highlight.emphasis_0, 2))
         } else if pane_is_in_group && pane_focused_for_client_id {
             Some((self.style.colors.frame_highlight.emphasis_1, 3))
+        } else if self.pane_is_in_sync_group && !pane_focused_for_client_id {
+            Some((self.style.colors.frame_highlight.emphasis_2, 2))
         } else if pane_focused_for_client_id {
             match mode {
                 InputMode::Normal | InputMode::Locked => {
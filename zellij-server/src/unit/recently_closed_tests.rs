@@ -0,0 +1,98 @@
+use super::*;
+use std::path::PathBuf;
+use zellij_utils::input::command::RunCommand;
+
+fn closed_pane(title: Option<&str>, run: Option<Run>) -> ClosedItem {
+    ClosedItem::Pane(ClosedPane {
+        run,
+        title: title.map(|t| t.to_owned()),
+        geom: PaneGeom::default(),
+        is_floating: false,
+        is_borderless: false,
+        tab_id: 0,
+    })
+}
+
+fn closed_tab(name: &str) -> ClosedItem {
+    ClosedItem::Tab(ClosedTab {
+        name: name.to_owned(),
+        position: 0,
+        tiled_panes: TiledPaneLayout::default(),
+        floating_panes: vec![],
+    })
+}
+
+#[test]
+fn list_is_most_recent_first() {
+    let mut recently_closed = RecentlyClosed::default();
+    recently_closed.push(closed_pane(Some("first"), None));
+    recently_closed.push(closed_tab("second"));
+
+    let list = recently_closed.list();
+    assert_eq!(
+        list,
+        vec![
+            RecentlyClosedInfo {
+                id: 1,
+                is_tab: true,
+                name: "second".to_owned(),
+            },
+            RecentlyClosedInfo {
+                id: 0,
+                is_tab: false,
+                name: "first".to_owned(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn pane_name_falls_back_to_its_command() {
+    let mut recently_closed = RecentlyClosed::default();
+    let mut run_command = RunCommand::new(PathBuf::from("htop"));
+    run_command.args = vec!["-d".to_owned(), "10".to_owned()];
+    recently_closed.push(closed_pane(None, Some(Run::Command(run_command))));
+    recently_closed.push(closed_pane(None, None));
+
+    let names: Vec<String> = recently_closed.list().into_iter().map(|r| r.name).collect();
+    assert_eq!(names, vec!["Pane".to_owned(), "htop -d 10".to_owned()]);
+}
+
+#[test]
+fn take_last_pane_skips_tabs() {
+    let mut recently_closed = RecentlyClosed::default();
+    recently_closed.push(closed_pane(Some("pane"), None));
+    recently_closed.push(closed_tab("tab"));
+
+    let closed_pane = recently_closed.take_last_pane().unwrap();
+    assert_eq!(closed_pane.title, Some("pane".to_owned()));
+    assert!(recently_closed.take_last_pane().is_none());
+    assert_eq!(recently_closed.take_last_tab().unwrap().name, "tab");
+    assert!(recently_closed.list().is_empty());
+}
+
+#[test]
+fn take_by_id() {
+    let mut recently_closed = RecentlyClosed::default();
+    recently_closed.push(closed_pane(Some("first"), None));
+    recently_closed.push(closed_pane(Some("second"), None));
+
+    assert!(matches!(
+        recently_closed.take(0),
+        Some(ClosedItem::Pane(ClosedPane { title: Some(title), .. })) if title == "first"
+    ));
+    assert!(recently_closed.take(0).is_none());
+    assert_eq!(recently_closed.list().len(), 1);
+}
+
+#[test]
+fn oldest_entries_are_dropped() {
+    let mut recently_closed = RecentlyClosed::default();
+    for i in 0..MAX_RECENTLY_CLOSED + 5 {
+        recently_closed.push(closed_tab(&format!("tab {}", i)));
+    }
+
+    let list = recently_closed.list();
+    assert_eq!(list.len(), MAX_RECENTLY_CLOSED);
+    assert_eq!(list.last().unwrap().name, "tab 5");
+}
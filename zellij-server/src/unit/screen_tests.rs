@@ -11,7 +11,7 @@ use zellij_utils::cli::CliAction;
 use zellij_utils::data::{Event, EventType, Resize, Style, WebSharing};
 use zellij_utils::errors::{prelude::*, ErrorContext};
 use zellij_utils::input::actions::Action;
-use zellij_utils::input::command::{RunCommand, TerminalAction};
+use zellij_utils::input::command::{RestartPolicy, RunCommand, TerminalAction};
 use zellij_utils::input::config::Config;
 use zellij_utils::input::layout::{
     FloatingPaneLayout, Layout, PercentOrFixed, PluginAlias, PluginUserConfiguration, Run,
@@ -26,6 +26,7 @@ use zellij_utils::position::Position;
 use crate::background_jobs::BackgroundJob;
 use crate::os_input_output::AsyncReader;
 use crate::pty_writer::PtyWriteInstruction;
+use crate::recording::Recordings;
 use std::collections::HashSet;
 use std::env::set_var;
 use std::sync::{Arc, Mutex};
@@ -152,6 +153,7 @@ fn send_cli_action_to_server(
 struct FakeInputOutput {
     fake_filesystem: Arc<Mutex<HashMap<String, String>>>,
     server_to_client_messages: Arc<Mutex<HashMap<ClientId, Vec<ServerToClientMsg>>>>,
+    recordings: Recordings,
 }
 
 impl ServerOsApi for FakeInputOutput {
@@ -189,6 +191,9 @@ impl ServerOsApi for FakeInputOutput {
     fn box_clone(&self) -> Box<dyn ServerOsApi> {
         Box::new((*self).clone())
     }
+    fn recordings(&self) -> Option<Recordings> {
+        Some(self.recordings.clone())
+    }
     fn send_to_client(&self, client_id: ClientId, msg: ServerToClientMsg) -> Result<()> {
         self.server_to_client_messages
             .lock()
@@ -2613,6 +2618,142 @@ pub fn send_cli_dump_screen_action() {
     ));
 }
 
+#[test]
+pub fn send_cli_start_and_stop_recording_actions() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let recordings_dir = tempfile::tempdir().unwrap();
+    let output = recordings_dir.path().join("demo.cast");
+    let _ = mock_screen.to_screen.send(ScreenInstruction::PtyBytes(
+        0,
+        "fill pane up with something".as_bytes().to_vec(),
+    ));
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::StartRecording {
+            output: output.clone(),
+            pane_id: Some("terminal_0".to_owned()),
+            tab: false,
+        },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(
+        mock_screen.os_input.recordings.recorded_terminal_ids(),
+        vec![0]
+    );
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::StopRecording {
+            pane_id: None,
+            tab: false,
+        },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    assert!(mock_screen
+        .os_input
+        .recordings
+        .recorded_terminal_ids()
+        .is_empty());
+    let recording = std::fs::read_to_string(&output).unwrap();
+    let mut lines = recording.lines();
+    let header: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert_eq!(header["version"], 2);
+    assert_eq!(header["width"], 38);
+    assert_eq!(header["height"], 18);
+    let initial_screen: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert!(initial_screen[2]
+        .as_str()
+        .unwrap()
+        .contains("fill pane up with something"));
+    let recording_state_changes: Vec<Vec<u32>> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => {
+                updates.iter().find_map(|(_, _, event)| match event {
+                    Event::RecordingStateChanged(ids) => Some(ids.clone()),
+                    _ => None,
+                })
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(recording_state_changes, vec![vec![0], vec![]]);
+}
+
+#[test]
+pub fn send_cli_start_recording_action_for_tab_reports_panes_that_fail_to_record() {
+    let size = Size { cols: 80, rows: 20 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let recordings_dir = tempfile::tempdir().unwrap();
+    let output = recordings_dir.path().join("demo.cast");
+    // the recording of the first pane cannot be created where a directory is in the way
+    std::fs::create_dir(recordings_dir.path().join("demo-terminal_0.cast")).unwrap();
+    send_cli_action_to_server(
+        &session_metadata,
+        CliAction::StartRecording {
+            output: output.clone(),
+            pane_id: None,
+            tab: true,
+        },
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(
+        mock_screen.os_input.recordings.recorded_terminal_ids(),
+        vec![1],
+        "the other pane is recorded"
+    );
+    mock_screen.os_input.recordings.stop(1);
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let reported_errors: Vec<Vec<String>> = received_server_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            ServerInstruction::LogError(lines, ..) => Some(lines.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(reported_errors.len(), 1);
+    assert_eq!(reported_errors[0].len(), 2);
+    assert!(reported_errors[0][0].starts_with("Recording terminal_1 to "));
+    assert!(
+        reported_errors[0][1].starts_with("Failed to start recording: failed to record terminal_0")
+    );
+    assert!(recordings_dir.path().join("demo-terminal_1.cast").exists());
+}
+
 #[test]
 pub fn send_cli_edit_scrollback_action() {
     let size = Size { cols: 80, rows: 20 };
@@ -3153,6 +3294,137 @@ pub fn send_cli_toggle_active_tab_sync_action() {
     assert_snapshot!(format!("{:?}", received_write_instructions));
 }
 
+#[test]
+pub fn send_cli_set_sync_group_action() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+    ];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
+    );
+    let cli_set_sync_group_action = CliAction::SetSyncGroup {
+        pane_ids: vec!["terminal_0".to_owned(), "terminal_2".to_owned()],
+    };
+    let cli_write_action = CliAction::Write {
+        bytes: vec![102, 111, 111],
+        pane_id: None,
+    };
+    send_cli_action_to_server(&session_metadata, cli_set_sync_group_action, client_id);
+    send_cli_action_to_server(&session_metadata, cli_write_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    let written_terminal_ids: Vec<u32> = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|i| match i {
+            PtyWriteInstruction::Write(bytes, terminal_id, _) if bytes == b"foo" => {
+                Some(*terminal_id)
+            },
+            _ => None,
+        })
+        .collect();
+    // the focused pane is in the sync group, so the input goes to the whole group
+    assert_eq!(written_terminal_ids, vec![0, 2]);
+}
+
+#[test]
+pub fn send_cli_set_sync_group_action_without_focused_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_writer_receiver = mock_screen.pty_writer_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+        TiledPaneLayout::default(),
+    ];
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_writer_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyWriteInstruction::Exit,
+        pty_writer_receiver
+    );
+    let cli_set_sync_group_action = CliAction::SetSyncGroup {
+        pane_ids: vec!["terminal_1".to_owned(), "terminal_2".to_owned()],
+    };
+    let cli_write_action = CliAction::Write {
+        bytes: vec![102, 111, 111],
+        pane_id: None,
+    };
+    send_cli_action_to_server(&session_metadata, cli_set_sync_group_action, client_id);
+    send_cli_action_to_server(&session_metadata, cli_write_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
+    mock_screen.teardown(vec![pty_writer_thread, screen_thread]);
+    let written_terminal_ids: Vec<u32> = received_pty_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|i| match i {
+            PtyWriteInstruction::Write(bytes, terminal_id, _) if bytes == b"foo" => {
+                Some(*terminal_id)
+            },
+            _ => None,
+        })
+        .collect();
+    // the focused pane is not in the sync group, so only it gets the input
+    assert_eq!(written_terminal_ids, vec![0]);
+}
+
+#[test]
+pub fn send_cli_set_sync_group_action_indicates_synced_panes() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_server_instructions = Arc::new(Mutex::new(vec![]));
+    let server_receiver = mock_screen.server_receiver.take().unwrap();
+    let server_thread = log_actions_in_thread!(
+        received_server_instructions,
+        ServerInstruction::KillSession,
+        server_receiver
+    );
+    let set_sync_group_action = CliAction::SetSyncGroup {
+        pane_ids: vec!["1".to_owned()],
+    };
+    send_cli_action_to_server(&session_metadata, set_sync_group_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![server_thread, screen_thread]);
+    let snapshots = take_snapshots_and_cursor_coordinates_from_render_events(
+        received_server_instructions.lock().unwrap().iter(),
+        size,
+    );
+    let (_cursor_coordinates, last_snapshot) = snapshots.last().unwrap();
+    assert_snapshot!(format!("{}", last_snapshot));
+}
+
 #[test]
 pub fn send_cli_new_pane_action_with_default_parameters() {
     let size = Size {
@@ -3200,6 +3472,9 @@ pub fn send_cli_new_pane_action_with_default_parameters() {
         near_current_pane: false,
         borderless: Some(false),
         tab_id: None,
+        env: vec![],
+        restart: None,
+        max_restarts: None,
     };
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
@@ -3254,6 +3529,9 @@ pub fn send_cli_new_pane_action_with_split_direction() {
         near_current_pane: false,
         borderless: Some(false),
         tab_id: None,
+        env: vec![],
+        restart: None,
+        max_restarts: None,
     };
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
@@ -3308,6 +3586,9 @@ pub fn send_cli_new_pane_action_with_command_and_cwd() {
         near_current_pane: false,
         borderless: Some(false),
         tab_id: None,
+        env: vec![],
+        restart: None,
+        max_restarts: None,
     };
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
@@ -3373,6 +3654,9 @@ pub fn send_cli_new_pane_action_with_floating_pane_and_coordinates() {
         near_current_pane: false,
         borderless: Some(false),
         tab_id: None,
+        env: vec![],
+        restart: None,
+        max_restarts: None,
     };
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
@@ -3672,6 +3956,190 @@ pub fn send_cli_close_pane_action() {
     assert_snapshot!(format!("{}", snapshot_count));
 }
 
+#[test]
+pub fn send_cli_close_pane_action_remembers_pane_before_closing_it() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    initial_layout.children = vec![TiledPaneLayout::default(), TiledPaneLayout::default()];
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(initial_layout), vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let close_pane_action = CliAction::ClosePane { pane_id: None };
+    send_cli_action_to_server(&session_metadata, close_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+
+    let received_pty_instructions = received_pty_instructions.lock().unwrap();
+    let remembered = received_pty_instructions
+        .iter()
+        .position(|instruction| matches!(instruction, PtyInstruction::RememberClosedPane(..)))
+        .expect("closed pane was remembered");
+    let closed = received_pty_instructions
+        .iter()
+        .position(|instruction| matches!(instruction, PtyInstruction::ClosePane(..)))
+        .expect("pane was closed");
+    assert!(
+        remembered < closed,
+        "pane is remembered while its process is still running"
+    );
+    match (
+        &received_pty_instructions[remembered],
+        &received_pty_instructions[closed],
+    ) {
+        (
+            PtyInstruction::RememberClosedPane(_, remembered_pane_id, _),
+            PtyInstruction::ClosePane(closed_pane_id, _),
+        ) => assert_eq!(remembered_pane_id, closed_pane_id),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+pub fn send_cli_close_pane_action_remembers_only_the_tab_when_closing_its_last_pane() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let close_pane_action = CliAction::ClosePane { pane_id: None };
+    send_cli_action_to_server(&session_metadata, close_pane_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+
+    let received_pty_instructions = received_pty_instructions.lock().unwrap();
+    assert!(received_pty_instructions
+        .iter()
+        .any(|instruction| matches!(instruction, PtyInstruction::RememberClosedTab(..))));
+    assert!(!received_pty_instructions
+        .iter()
+        .any(|instruction| matches!(instruction, PtyInstruction::RememberClosedPane(..))));
+}
+
+#[test]
+pub fn send_cli_toggle_scratchpad_action_spawns_it_the_first_time() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let toggle_scratchpad_action = CliAction::ToggleScratchpad {
+        name: "notes".to_owned(),
+        command: vec!["nvim".to_owned()],
+        cwd: None,
+        close_on_exit: false,
+        x: None,
+        y: None,
+        width: Some("80%".to_owned()),
+        height: None,
+        pinned: None,
+    };
+    send_cli_action_to_server(&session_metadata, toggle_scratchpad_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+
+    let received_pty_instructions = received_pty_instructions.lock().unwrap();
+    let spawn_scratchpad = received_pty_instructions
+        .iter()
+        .find(|instruction| matches!(instruction, PtyInstruction::SpawnScratchpad(..)))
+        .expect("scratchpad was spawned");
+    match spawn_scratchpad {
+        PtyInstruction::SpawnScratchpad(name, terminal_action, coordinates, ..) => {
+            assert_eq!(name, "notes");
+            match terminal_action {
+                Some(TerminalAction::RunCommand(run_command)) => {
+                    assert_eq!(run_command.command, PathBuf::from("nvim"))
+                },
+                _ => panic!("Expected a command"),
+            }
+            assert!(coordinates.is_some());
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+pub fn send_cli_toggle_scratchpad_action_twice_while_spawning_spawns_it_once() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let pty_receiver = mock_screen.pty_receiver.take().unwrap();
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(None, vec![]);
+    let received_pty_instructions = Arc::new(Mutex::new(vec![]));
+    let pty_thread = log_actions_in_thread!(
+        received_pty_instructions,
+        PtyInstruction::Exit,
+        pty_receiver
+    );
+    let toggle_scratchpad_action = CliAction::ToggleScratchpad {
+        name: "notes".to_owned(),
+        command: vec![],
+        cwd: None,
+        close_on_exit: false,
+        x: None,
+        y: None,
+        width: None,
+        height: None,
+        pinned: None,
+    };
+    // the mock pty thread never reports the scratchpad as spawned
+    send_cli_action_to_server(
+        &session_metadata,
+        toggle_scratchpad_action.clone(),
+        client_id,
+    );
+    send_cli_action_to_server(
+        &session_metadata,
+        toggle_scratchpad_action.clone(),
+        client_id,
+    );
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    let spawn_count = |received_pty_instructions: &Mutex<Vec<PtyInstruction>>| {
+        received_pty_instructions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|instruction| matches!(instruction, PtyInstruction::SpawnScratchpad(..)))
+            .count()
+    };
+    assert_eq!(spawn_count(&received_pty_instructions), 1);
+
+    let _ = mock_screen
+        .to_screen
+        .send(ScreenInstruction::ScratchpadNotSpawned("notes".to_owned()));
+    send_cli_action_to_server(&session_metadata, toggle_scratchpad_action, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![pty_thread, screen_thread]);
+    assert_eq!(
+        spawn_count(&received_pty_instructions),
+        2,
+        "a scratchpad that failed to spawn can be spawned again"
+    );
+}
+
 #[test]
 pub fn send_cli_new_tab_action_default_params() {
     let size = Size { cols: 80, rows: 10 };
@@ -3694,6 +4162,7 @@ pub fn send_cli_new_tab_action_default_params() {
         layout: None,
         layout_string: None,
         layout_dir: None,
+        layout_args: vec![],
         cwd: None,
         initial_command: vec![],
         initial_plugin: None,
@@ -3742,6 +4211,7 @@ pub fn send_cli_new_tab_action_with_name_and_layout() {
         ))),
         layout_string: None,
         layout_dir: None,
+        layout_args: vec![],
         cwd: None,
         initial_command: vec![],
         initial_plugin: None,
@@ -5194,6 +5664,9 @@ pub fn send_cli_new_pane_in_place_with_close_replaced_pane() {
         near_current_pane: false,
         borderless: None,
         tab_id: None,
+        env: vec![],
+        restart: None,
+        max_restarts: None,
     };
     send_cli_action_to_server(&session_metadata, cli_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -7786,6 +8259,7 @@ pub fn send_cli_new_tab_action_with_layout_string() {
         layout: None,
         layout_string: Some("layout {\n    pane\n    pane\n    pane\n}\n".into()),
         layout_dir: None,
+        layout_args: vec![],
         cwd: None,
         initial_command: vec![],
         initial_plugin: None,
@@ -7840,6 +8314,7 @@ pub fn send_cli_new_tab_action_with_layout_string_and_name() {
         layout: None,
         layout_string: Some("layout {\n    pane\n    pane\n    pane\n}\n".into()),
         layout_dir: None,
+        layout_args: vec![],
         cwd: None,
         initial_command: vec![],
         initial_plugin: None,
@@ -7919,6 +8394,9 @@ pub fn send_cli_new_pane_action_with_tab_id() {
         near_current_pane: false,
         borderless: Some(false),
         tab_id: Some(0),
+        env: vec![],
+        restart: None,
+        max_restarts: None,
     };
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -7980,6 +8458,9 @@ pub fn send_cli_new_floating_pane_action_with_tab_id() {
         near_current_pane: false,
         borderless: None,
         tab_id: Some(0),
+        env: vec![],
+        restart: None,
+        max_restarts: None,
     };
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -8089,6 +8570,9 @@ pub fn send_cli_new_pane_action_with_tab_id_and_direction() {
         near_current_pane: false,
         borderless: Some(false),
         tab_id: Some(0),
+        env: vec![],
+        restart: None,
+        max_restarts: None,
     };
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -8149,6 +8633,9 @@ pub fn send_cli_new_pane_action_with_tab_id_and_stacked() {
         near_current_pane: false,
         borderless: None,
         tab_id: Some(0),
+        env: vec![],
+        restart: None,
+        max_restarts: None,
     };
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -8253,6 +8740,148 @@ fn cli_rename_focused_pane_single_char_via_rename_active_pane() {
     );
 }
 
+#[test]
+pub fn command_pane_with_restart_policy_is_restarted_until_max_restarts() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size, true, true);
+    let client_id = 1;
+    new_tab(&mut screen, 1, 0);
+
+    let terminal_pane_id = 1;
+    let pane_id = PaneId::Terminal(terminal_pane_id);
+    let run_command = RunCommand {
+        command: PathBuf::from("my-server"),
+        restart_policy: RestartPolicy::OnFailure,
+        max_restarts: Some(1),
+        ..Default::default()
+    };
+    let tab = screen.get_active_tab_mut(client_id).unwrap();
+
+    tab.hold_pane(pane_id, Some(1), false, run_command.clone());
+    assert_eq!(
+        tab.get_pane_with_id(pane_id).unwrap().pending_restart(),
+        Some(1),
+        "failed command is waiting to be restarted"
+    );
+
+    // a stale restart (eg. one scheduled before the user re-ran the pane) is ignored
+    tab.restart_terminal_pane_with_id(terminal_pane_id, 2);
+    assert!(tab.get_pane_with_id(pane_id).unwrap().is_held());
+
+    tab.restart_terminal_pane_with_id(terminal_pane_id, 1);
+    let pane = tab.get_pane_with_id(pane_id).unwrap();
+    assert!(!pane.is_held(), "pane was restarted");
+    assert_eq!(pane.pending_restart(), None);
+
+    tab.hold_pane(pane_id, Some(1), false, run_command.clone());
+    let pane = tab.get_pane_with_id(pane_id).unwrap();
+    assert!(pane.is_held());
+    assert_eq!(
+        pane.pending_restart(),
+        None,
+        "command is not restarted more than max_restarts times"
+    );
+}
+
+#[test]
+pub fn command_pane_that_ran_out_of_restarts_is_held_instead_of_closed() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size, true, true);
+    let client_id = 1;
+    new_tab(&mut screen, 1, 0);
+
+    let pane_id = PaneId::Terminal(1);
+    let run_command = RunCommand {
+        command: PathBuf::from("my-server"),
+        hold_on_close: false,
+        restart_policy: RestartPolicy::OnFailure,
+        max_restarts: Some(0),
+        ..Default::default()
+    };
+    let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> = channels::unbounded();
+    let tab = screen.get_active_tab_mut(client_id).unwrap();
+    tab.senders.to_screen = Some(SenderWithContext::new(to_screen));
+
+    tab.hold_pane(pane_id, Some(1), false, run_command.clone());
+    let pane = tab.get_pane_with_id(pane_id).unwrap();
+    assert!(
+        pane.is_held(),
+        "pane is held so that its last output is kept"
+    );
+    assert_eq!(pane.pending_restart(), None);
+    assert!(
+        screen_receiver.try_recv().is_err(),
+        "pane is not closed after running out of restarts"
+    );
+
+    // a command that did not fail is closed as usual
+    tab.hold_pane(pane_id, Some(0), false, run_command);
+    assert!(matches!(
+        screen_receiver.try_recv(),
+        Ok((ScreenInstruction::ClosePane(closed_pane_id, ..), _)) if closed_pane_id == pane_id
+    ));
+}
+
+#[test]
+pub fn command_pane_with_on_failure_restart_policy_is_not_restarted_after_success() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size, true, true);
+    let client_id = 1;
+    new_tab(&mut screen, 1, 0);
+
+    let pane_id = PaneId::Terminal(1);
+    let run_command = RunCommand {
+        command: PathBuf::from("my-server"),
+        restart_policy: RestartPolicy::OnFailure,
+        ..Default::default()
+    };
+    let tab = screen.get_active_tab_mut(client_id).unwrap();
+    tab.hold_pane(pane_id, Some(0), false, run_command);
+    assert_eq!(
+        tab.get_pane_with_id(pane_id).unwrap().pending_restart(),
+        None
+    );
+}
+
+#[test]
+pub fn command_pane_restart_count_is_reset_after_success() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size, true, true);
+    let client_id = 1;
+    new_tab(&mut screen, 1, 0);
+
+    let terminal_pane_id = 1;
+    let pane_id = PaneId::Terminal(terminal_pane_id);
+    let run_command = RunCommand {
+        command: PathBuf::from("my-server"),
+        restart_policy: RestartPolicy::Always,
+        max_restarts: Some(1),
+        ..Default::default()
+    };
+    let tab = screen.get_active_tab_mut(client_id).unwrap();
+
+    tab.hold_pane(pane_id, Some(1), false, run_command.clone());
+    tab.restart_terminal_pane_with_id(terminal_pane_id, 1);
+    tab.hold_pane(pane_id, Some(0), false, run_command.clone());
+    assert_eq!(
+        tab.get_pane_with_id(pane_id).unwrap().pending_restart(),
+        Some(1),
+        "a successful run starts a fresh series of restarts"
+    );
+}
+
 #[test]
 pub fn pty_bytes_and_hold_pane_buffered_before_new_pane() {
     // Regression test: when a command exits very quickly (e.g. `zellij run -- echo hello`),
@@ -26,21 +26,22 @@ use zellij_utils::plugin_api::plugin_command::{
     ProtobufGetPanePidResponse, ProtobufGetPaneRunningCommandResponse,
     ProtobufGetSessionEnvironmentVariablesResponse, ProtobufGetSessionListResponse,
     ProtobufGetTabInfoResponse, ProtobufHideFloatingPanesResponse, ProtobufKillSessionsResponse,
-    ProtobufNewTabResponse, ProtobufNewTabsResponse, ProtobufOpenCommandPaneBackgroundResponse,
-    ProtobufOpenCommandPaneFloatingNearPluginResponse, ProtobufOpenCommandPaneFloatingResponse,
-    ProtobufOpenCommandPaneInPlaceOfPaneIdResponse, ProtobufOpenCommandPaneInPlaceOfPluginResponse,
-    ProtobufOpenCommandPaneInPlaceResponse, ProtobufOpenCommandPaneNearPluginResponse,
-    ProtobufOpenCommandPaneResponse, ProtobufOpenEditPaneInPlaceOfPaneIdResponse,
-    ProtobufOpenFileFloatingNearPluginResponse, ProtobufOpenFileFloatingResponse,
-    ProtobufOpenFileInPlaceOfPluginResponse, ProtobufOpenFileInPlaceResponse,
-    ProtobufOpenFileNearPluginResponse, ProtobufOpenFileResponse, ProtobufOpenPaneInNewTabResponse,
-    ProtobufOpenPluginPaneFloatingResponse, ProtobufOpenTerminalFloatingNearPluginResponse,
-    ProtobufOpenTerminalFloatingResponse, ProtobufOpenTerminalInPlaceOfPluginResponse,
-    ProtobufOpenTerminalInPlaceResponse, ProtobufOpenTerminalNearPluginResponse,
-    ProtobufOpenTerminalPaneInPlaceOfPaneIdResponse, ProtobufOpenTerminalResponse,
-    ProtobufParseLayoutResponse, ProtobufPluginCommand, ProtobufRenameLayoutResponse,
-    ProtobufSaveLayoutResponse, ProtobufSaveSessionResponse, ProtobufShowFloatingPanesResponse,
-    RenameWebTokenResponse, RevokeAllWebTokensResponse, RevokeTokenResponse,
+    ProtobufListRecentlyClosedResponse, ProtobufNewTabResponse, ProtobufNewTabsResponse,
+    ProtobufOpenCommandPaneBackgroundResponse, ProtobufOpenCommandPaneFloatingNearPluginResponse,
+    ProtobufOpenCommandPaneFloatingResponse, ProtobufOpenCommandPaneInPlaceOfPaneIdResponse,
+    ProtobufOpenCommandPaneInPlaceOfPluginResponse, ProtobufOpenCommandPaneInPlaceResponse,
+    ProtobufOpenCommandPaneNearPluginResponse, ProtobufOpenCommandPaneResponse,
+    ProtobufOpenEditPaneInPlaceOfPaneIdResponse, ProtobufOpenFileFloatingNearPluginResponse,
+    ProtobufOpenFileFloatingResponse, ProtobufOpenFileInPlaceOfPluginResponse,
+    ProtobufOpenFileInPlaceResponse, ProtobufOpenFileNearPluginResponse, ProtobufOpenFileResponse,
+    ProtobufOpenPaneInNewTabResponse, ProtobufOpenPluginPaneFloatingResponse,
+    ProtobufOpenTerminalFloatingNearPluginResponse, ProtobufOpenTerminalFloatingResponse,
+    ProtobufOpenTerminalInPlaceOfPluginResponse, ProtobufOpenTerminalInPlaceResponse,
+    ProtobufOpenTerminalNearPluginResponse, ProtobufOpenTerminalPaneInPlaceOfPaneIdResponse,
+    ProtobufOpenTerminalResponse, ProtobufParseLayoutResponse, ProtobufPluginCommand,
+    ProtobufRenameLayoutResponse, ProtobufSaveLayoutResponse, ProtobufSaveSessionResponse,
+    ProtobufShowFloatingPanesResponse, RenameWebTokenResponse, RevokeAllWebTokensResponse,
+    RevokeTokenResponse,
 };
 use zellij_utils::plugin_api::plugin_ids::{ProtobufPluginIds, ProtobufZellijVersion};
 
@@ -936,7 +937,17 @@ pub fn new_tabs_with_layout(layout: &str) -> Vec<usize> {
 
 /// Provide a LayoutInfo to be applied to the current session in a new tab. If the layout has multiple tabs, they will all be opened.
 pub fn new_tabs_with_layout_info<L: AsRef<LayoutInfo>>(layout_info: L) -> Vec<usize> {
-    let plugin_command = PluginCommand::NewTabsWithLayoutInfo(layout_info.as_ref().clone());
+    new_tabs_with_layout_info_and_args(layout_info, BTreeMap::new())
+}
+
+/// Provide a LayoutInfo to be applied to the current session in a new tab, along with values for
+/// the parameters declared by the layout. If the layout has multiple tabs, they will all be opened.
+pub fn new_tabs_with_layout_info_and_args<L: AsRef<LayoutInfo>>(
+    layout_info: L,
+    layout_args: BTreeMap<String, String>,
+) -> Vec<usize> {
+    let plugin_command =
+        PluginCommand::NewTabsWithLayoutInfo(layout_info.as_ref().clone(), layout_args);
     let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
     object_to_stdout(&protobuf_plugin_command.encode_to_vec());
     unsafe { host_run_plugin_command() };
@@ -2447,6 +2458,28 @@ pub fn rebind_keys(
     let plugin_command = PluginCommand::RebindKeys {
         keys_to_rebind,
         keys_to_unbind,
+        key_sequences_to_rebind: vec![],
+        key_sequences_to_unbind: vec![],
+        write_config_to_disk,
+    };
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Rebind keys and multi-key sequences (eg. `Ctrl a` followed by `g`) for the current user
+pub fn rebind_keys_and_key_sequences(
+    keys_to_unbind: Vec<(InputMode, KeyWithModifier)>,
+    keys_to_rebind: Vec<(InputMode, KeyWithModifier, Vec<Action>)>,
+    key_sequences_to_unbind: Vec<(InputMode, Vec<KeyWithModifier>)>,
+    key_sequences_to_rebind: Vec<(InputMode, Vec<KeyWithModifier>, Vec<Action>)>,
+    write_config_to_disk: bool,
+) {
+    let plugin_command = PluginCommand::RebindKeys {
+        keys_to_rebind,
+        keys_to_unbind,
+        key_sequences_to_rebind,
+        key_sequences_to_unbind,
         write_config_to_disk,
     };
     let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
@@ -2475,6 +2508,41 @@ pub fn stack_panes(pane_ids: Vec<PaneId>) {
     unsafe { host_run_plugin_command() };
 }
 
+/// Send the input typed into any of these panes to all of them, even across tabs. An empty
+/// list stops syncing.
+pub fn set_sync_group(pane_ids: Vec<PaneId>) {
+    let plugin_command = PluginCommand::SetSyncGroup(pane_ids);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// List the panes and tabs that were closed in this session and can still be reopened, most
+/// recently closed first
+pub fn list_recently_closed() -> Vec<RecentlyClosedInfo> {
+    let plugin_command = PluginCommand::ListRecentlyClosed;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+
+    let protobuf_response =
+        ProtobufListRecentlyClosedResponse::decode(bytes_from_stdin().unwrap().as_slice()).unwrap();
+    protobuf_response
+        .recently_closed
+        .into_iter()
+        .map(|recently_closed_info| recently_closed_info.into())
+        .collect()
+}
+
+/// Reopen a closed pane or tab in its original position, by the id returned from
+/// `list_recently_closed`
+pub fn reopen_closed(id: u32) {
+    let plugin_command = PluginCommand::ReopenClosed(id);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
 pub fn change_floating_panes_coordinates(
     pane_ids_and_coordinates: Vec<(PaneId, FloatingPaneCoordinates)>,
 ) {
@@ -22,6 +22,8 @@ pub struct LayoutConstraintWithValue {
     pub constraint_type: i32,
     #[prost(uint32, optional, tag="2")]
     pub value: ::core::option::Option<u32>,
+    #[prost(message, repeated, tag="3")]
+    pub constraints: ::prost::alloc::vec::Vec<LayoutConstraintWithValue>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -876,6 +878,13 @@ pub enum LayoutConstraint {
     MinPanes = 2,
     ExactPanes = 3,
     NoConstraint = 4,
+    MinColumns = 5,
+    MaxColumns = 6,
+    MinRows = 7,
+    MaxRows = 8,
+    MinAspectRatio = 9,
+    MaxAspectRatio = 10,
+    Combined = 11,
 }
 impl LayoutConstraint {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -889,6 +898,13 @@ impl LayoutConstraint {
             LayoutConstraint::MinPanes => "LAYOUT_CONSTRAINT_MIN_PANES",
             LayoutConstraint::ExactPanes => "LAYOUT_CONSTRAINT_EXACT_PANES",
             LayoutConstraint::NoConstraint => "LAYOUT_CONSTRAINT_NO_CONSTRAINT",
+            LayoutConstraint::MinColumns => "LAYOUT_CONSTRAINT_MIN_COLUMNS",
+            LayoutConstraint::MaxColumns => "LAYOUT_CONSTRAINT_MAX_COLUMNS",
+            LayoutConstraint::MinRows => "LAYOUT_CONSTRAINT_MIN_ROWS",
+            LayoutConstraint::MaxRows => "LAYOUT_CONSTRAINT_MAX_ROWS",
+            LayoutConstraint::MinAspectRatio => "LAYOUT_CONSTRAINT_MIN_ASPECT_RATIO",
+            LayoutConstraint::MaxAspectRatio => "LAYOUT_CONSTRAINT_MAX_ASPECT_RATIO",
+            LayoutConstraint::Combined => "LAYOUT_CONSTRAINT_COMBINED",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -899,6 +915,13 @@ impl LayoutConstraint {
             "LAYOUT_CONSTRAINT_MIN_PANES" => Some(Self::MinPanes),
             "LAYOUT_CONSTRAINT_EXACT_PANES" => Some(Self::ExactPanes),
             "LAYOUT_CONSTRAINT_NO_CONSTRAINT" => Some(Self::NoConstraint),
+            "LAYOUT_CONSTRAINT_MIN_COLUMNS" => Some(Self::MinColumns),
+            "LAYOUT_CONSTRAINT_MAX_COLUMNS" => Some(Self::MaxColumns),
+            "LAYOUT_CONSTRAINT_MIN_ROWS" => Some(Self::MinRows),
+            "LAYOUT_CONSTRAINT_MAX_ROWS" => Some(Self::MaxRows),
+            "LAYOUT_CONSTRAINT_MIN_ASPECT_RATIO" => Some(Self::MinAspectRatio),
+            "LAYOUT_CONSTRAINT_MAX_ASPECT_RATIO" => Some(Self::MaxAspectRatio),
+            "LAYOUT_CONSTRAINT_COMBINED" => Some(Self::Combined),
             _ => None,
         }
     }
@@ -967,6 +990,7 @@ pub enum SearchOption {
     CaseSensitivity = 0,
     WholeWord = 1,
     Wrap = 2,
+    Regex = 3,
 }
 impl SearchOption {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -978,6 +1002,7 @@ impl SearchOption {
             SearchOption::CaseSensitivity => "CaseSensitivity",
             SearchOption::WholeWord => "WholeWord",
             SearchOption::Wrap => "Wrap",
+            SearchOption::Regex => "Regex",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -986,6 +1011,7 @@ impl SearchOption {
             "CaseSensitivity" => Some(Self::CaseSensitivity),
             "WholeWord" => Some(Self::WholeWord),
             "Wrap" => Some(Self::Wrap),
+            "Regex" => Some(Self::Regex),
             _ => None,
         }
     }
@@ -1120,6 +1146,14 @@ pub enum ActionName {
     SetDarkTheme = 101,
     SetLightTheme = 102,
     ToggleTheme = 103,
+    ScrollToPreviousPrompt = 104,
+    ScrollToNextPrompt = 105,
+    SelectLastCommandOutput = 106,
+    CopyLastCommandOutput = 107,
+    ToggleSyncGroup = 108,
+    ShowPaneHints = 109,
+    ReopenClosedPane = 110,
+    ReopenClosedTab = 111,
 }
 impl ActionName {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -1229,6 +1263,14 @@ impl ActionName {
             ActionName::SetDarkTheme => "SetDarkTheme",
             ActionName::SetLightTheme => "SetLightTheme",
             ActionName::ToggleTheme => "ToggleTheme",
+            ActionName::ScrollToPreviousPrompt => "ScrollToPreviousPrompt",
+            ActionName::ScrollToNextPrompt => "ScrollToNextPrompt",
+            ActionName::SelectLastCommandOutput => "SelectLastCommandOutput",
+            ActionName::CopyLastCommandOutput => "CopyLastCommandOutput",
+            ActionName::ToggleSyncGroup => "ToggleSyncGroup",
+            ActionName::ShowPaneHints => "ShowPaneHints",
+            ActionName::ReopenClosedPane => "ReopenClosedPane",
+            ActionName::ReopenClosedTab => "ReopenClosedTab",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -1335,6 +1377,14 @@ impl ActionName {
             "SetDarkTheme" => Some(Self::SetDarkTheme),
             "SetLightTheme" => Some(Self::SetLightTheme),
             "ToggleTheme" => Some(Self::ToggleTheme),
+            "ScrollToPreviousPrompt" => Some(Self::ScrollToPreviousPrompt),
+            "ScrollToNextPrompt" => Some(Self::ScrollToNextPrompt),
+            "SelectLastCommandOutput" => Some(Self::SelectLastCommandOutput),
+            "CopyLastCommandOutput" => Some(Self::CopyLastCommandOutput),
+            "ToggleSyncGroup" => Some(Self::ToggleSyncGroup),
+            "ShowPaneHints" => Some(Self::ShowPaneHints),
+            "ReopenClosedPane" => Some(Self::ReopenClosedPane),
+            "ReopenClosedTab" => Some(Self::ReopenClosedTab),
             _ => None,
         }
     }
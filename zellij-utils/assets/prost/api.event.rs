@@ -9,7 +9,7 @@ pub struct EventNameList {
 pub struct Event {
     #[prost(enumeration="EventType", tag="1")]
     pub name: i32,
-    #[prost(oneof="event::Payload", tags="2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40")]
+    #[prost(oneof="event::Payload", tags="2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42")]
     pub payload: ::core::option::Option<event::Payload>,
 }
 /// Nested message and enum types in `Event`.
@@ -95,6 +95,10 @@ pub mod event {
         CommandChangedPayload(super::CommandChangedPayload),
         #[prost(message, tag="40")]
         HostTerminalThemeChangedPayload(super::HostTerminalThemeChangedPayload),
+        #[prost(message, tag="41")]
+        CommandFinishedPayload(super::CommandFinishedPayload),
+        #[prost(message, tag="42")]
+        RecordingStateChangedPayload(super::RecordingStateChangedPayload),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -232,6 +236,20 @@ pub struct CommandPaneExitedPayload {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CommandFinishedPayload {
+    #[prost(uint32, tag="1")]
+    pub terminal_pane_id: u32,
+    #[prost(int32, optional, tag="2")]
+    pub exit_code: ::core::option::Option<i32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordingStateChangedPayload {
+    #[prost(uint32, repeated, tag="1")]
+    pub recorded_terminal_pane_ids: ::prost::alloc::vec::Vec<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EditPaneExitedPayload {
     #[prost(uint32, tag="1")]
     pub terminal_pane_id: u32,
@@ -497,6 +515,8 @@ pub struct LayoutMetadata {
     pub creation_time: ::prost::alloc::string::String,
     #[prost(string, tag="3")]
     pub update_time: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag="4")]
+    pub imports: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -661,6 +681,12 @@ pub struct ModeUpdatePayload {
     pub web_server_port: ::core::option::Option<u32>,
     #[prost(bool, optional, tag="15")]
     pub web_server_capability: ::core::option::Option<bool>,
+    #[prost(message, repeated, tag="16")]
+    pub key_sequences: ::prost::alloc::vec::Vec<InputModeKeySequences>,
+    #[prost(message, repeated, tag="17")]
+    pub pending_key_sequence: ::prost::alloc::vec::Vec<super::key::Key>,
+    #[prost(message, repeated, tag="18")]
+    pub custom_modes: ::prost::alloc::vec::Vec<super::input_mode::CustomInputMode>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -680,6 +706,22 @@ pub struct KeyBind {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InputModeKeySequences {
+    #[prost(enumeration="super::input_mode::InputMode", tag="1")]
+    pub mode: i32,
+    #[prost(message, repeated, tag="2")]
+    pub key_sequence_bind: ::prost::alloc::vec::Vec<KeySequenceBind>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeySequenceBind {
+    #[prost(message, repeated, tag="1")]
+    pub keys: ::prost::alloc::vec::Vec<super::key::Key>,
+    #[prost(message, repeated, tag="2")]
+    pub action: ::prost::alloc::vec::Vec<super::action::Action>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PaneRenderReportPayload {
     #[prost(message, repeated, tag="1")]
     pub pane_contents: ::prost::alloc::vec::Vec<PaneContentsEntry>,
@@ -839,6 +881,8 @@ pub enum EventType {
     InitialKeybinds = 44,
     CommandChanged = 45,
     HostTerminalThemeChanged = 46,
+    CommandFinished = 47,
+    RecordingStateChanged = 48,
 }
 impl EventType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -893,6 +937,8 @@ impl EventType {
             EventType::InitialKeybinds => "InitialKeybinds",
             EventType::CommandChanged => "CommandChanged",
             EventType::HostTerminalThemeChanged => "HostTerminalThemeChanged",
+            EventType::CommandFinished => "CommandFinished",
+            EventType::RecordingStateChanged => "RecordingStateChanged",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -944,6 +990,8 @@ impl EventType {
             "InitialKeybinds" => Some(Self::InitialKeybinds),
             "CommandChanged" => Some(Self::CommandChanged),
             "HostTerminalThemeChanged" => Some(Self::HostTerminalThemeChanged),
+            "CommandFinished" => Some(Self::CommandFinished),
+            "RecordingStateChanged" => Some(Self::RecordingStateChanged),
             _ => None,
         }
     }
@@ -4,6 +4,16 @@ pub struct InputModeMessage {
     #[prost(enumeration="InputMode", tag="1")]
     pub input_mode: i32,
 }
+/// User defined modes (eg. `custom_mode "git" { ... }`) have no value in the InputMode enum, fields
+/// of type InputMode hold their id instead (which is always 1000 or above)
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CustomInputMode {
+    #[prost(uint32, tag="1")]
+    pub id: u32,
+    #[prost(string, tag="2")]
+    pub name: ::prost::alloc::string::String,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum InputMode {
@@ -37,6 +47,8 @@ pub enum InputMode {
     Prompt = 12,
     /// / `Tmux` mode allows for basic tmux keybindings functionality
     Tmux = 13,
+    /// / `Copy` mode allows selecting and copying text with the keyboard
+    Copy = 14,
 }
 impl InputMode {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -59,6 +71,7 @@ impl InputMode {
             InputMode::Move => "Move",
             InputMode::Prompt => "Prompt",
             InputMode::Tmux => "Tmux",
+            InputMode::Copy => "Copy",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -78,6 +91,7 @@ impl InputMode {
             "Move" => Some(Self::Move),
             "Prompt" => Some(Self::Prompt),
             "Tmux" => Some(Self::Tmux),
+            "Copy" => Some(Self::Copy),
             _ => None,
         }
     }
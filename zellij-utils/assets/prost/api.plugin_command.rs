@@ -3,7 +3,7 @@
 pub struct PluginCommand {
     #[prost(enumeration="CommandName", tag="1")]
     pub name: i32,
-    #[prost(oneof="plugin_command::Payload", tags="2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 119, 120, 121, 122, 123, 124, 125, 126, 127, 128, 129, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142, 143, 144, 145, 146, 147, 148, 149, 150, 151, 152, 153, 154, 155, 156, 157, 158, 159, 160, 161, 162, 163")]
+    #[prost(oneof="plugin_command::Payload", tags="2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 119, 120, 121, 122, 123, 124, 125, 126, 127, 128, 129, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142, 143, 144, 145, 146, 147, 148, 149, 150, 151, 152, 153, 154, 155, 156, 157, 158, 159, 160, 161, 162, 163, 164, 165")]
     pub payload: ::core::option::Option<plugin_command::Payload>,
 }
 /// Nested message and enum types in `PluginCommand`.
@@ -309,6 +309,10 @@ pub mod plugin_command {
         KillSessionsAndReplyPayload(super::KillSessionsPayload),
         #[prost(string, tag="163")]
         DeleteDeadSessionAndReplyPayload(::prost::alloc::string::String),
+        #[prost(message, tag="164")]
+        SetSyncGroupPayload(super::SetSyncGroupPayload),
+        #[prost(message, tag="165")]
+        ReopenClosedPayload(super::ReopenClosedPayload),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -511,6 +515,34 @@ pub struct StackPanesPayload {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetSyncGroupPayload {
+    #[prost(message, repeated, tag="1")]
+    pub pane_ids: ::prost::alloc::vec::Vec<PaneId>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReopenClosedPayload {
+    #[prost(uint32, tag="1")]
+    pub id: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecentlyClosedInfo {
+    #[prost(uint32, tag="1")]
+    pub id: u32,
+    #[prost(bool, tag="2")]
+    pub is_tab: bool,
+    #[prost(string, tag="3")]
+    pub name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListRecentlyClosedResponse {
+    #[prost(message, repeated, tag="1")]
+    pub recently_closed: ::prost::alloc::vec::Vec<RecentlyClosedInfo>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SetFloatingPanePinnedPayload {
     #[prost(message, optional, tag="1")]
     pub pane_id: ::core::option::Option<PaneId>,
@@ -546,6 +578,10 @@ pub struct RebindKeysPayload {
     pub keys_to_unbind: ::prost::alloc::vec::Vec<KeyToUnbind>,
     #[prost(bool, tag="3")]
     pub write_config_to_disk: bool,
+    #[prost(message, repeated, tag="4")]
+    pub key_sequences_to_rebind: ::prost::alloc::vec::Vec<KeySequenceToRebind>,
+    #[prost(message, repeated, tag="5")]
+    pub key_sequences_to_unbind: ::prost::alloc::vec::Vec<KeySequenceToUnbind>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -567,6 +603,24 @@ pub struct KeyToUnbind {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeySequenceToRebind {
+    #[prost(enumeration="super::input_mode::InputMode", tag="1")]
+    pub input_mode: i32,
+    #[prost(message, repeated, tag="2")]
+    pub keys: ::prost::alloc::vec::Vec<super::key::Key>,
+    #[prost(message, repeated, tag="3")]
+    pub actions: ::prost::alloc::vec::Vec<super::action::Action>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeySequenceToUnbind {
+    #[prost(enumeration="super::input_mode::InputMode", tag="1")]
+    pub input_mode: i32,
+    #[prost(message, repeated, tag="2")]
+    pub keys: ::prost::alloc::vec::Vec<super::key::Key>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct LoadNewPluginPayload {
     #[prost(string, tag="1")]
     pub plugin_url: ::prost::alloc::string::String,
@@ -742,6 +796,8 @@ pub struct ShowPaneWithIdPayload {
 pub struct NewTabsWithLayoutInfoPayload {
     #[prost(message, optional, tag="1")]
     pub layout_info: ::core::option::Option<super::event::LayoutInfo>,
+    #[prost(map="string, string", tag="2")]
+    pub layout_args: ::std::collections::HashMap<::prost::alloc::string::String, ::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2152,6 +2208,9 @@ pub enum CommandName {
     KillSessionsAndReply = 212,
     DeleteDeadSessionAndReply = 213,
     DeleteAllDeadSessionsAndReply = 214,
+    SetSyncGroup = 215,
+    ListRecentlyClosed = 216,
+    ReopenClosed = 217,
 }
 impl CommandName {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -2354,6 +2413,9 @@ impl CommandName {
             CommandName::KillSessionsAndReply => "KillSessionsAndReply",
             CommandName::DeleteDeadSessionAndReply => "DeleteDeadSessionAndReply",
             CommandName::DeleteAllDeadSessionsAndReply => "DeleteAllDeadSessionsAndReply",
+            CommandName::SetSyncGroup => "SetSyncGroup",
+            CommandName::ListRecentlyClosed => "ListRecentlyClosed",
+            CommandName::ReopenClosed => "ReopenClosed",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -2553,6 +2615,9 @@ impl CommandName {
             "KillSessionsAndReply" => Some(Self::KillSessionsAndReply),
             "DeleteDeadSessionAndReply" => Some(Self::DeleteDeadSessionAndReply),
             "DeleteAllDeadSessionsAndReply" => Some(Self::DeleteAllDeadSessionsAndReply),
+            "SetSyncGroup" => Some(Self::SetSyncGroup),
+            "ListRecentlyClosed" => Some(Self::ListRecentlyClosed),
+            "ReopenClosed" => Some(Self::ReopenClosed),
             _ => None,
         }
     }
@@ -119,7 +119,7 @@ pub struct RgbColor {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Action {
-    #[prost(oneof="action::ActionType", tags="1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124, 125, 126, 127, 128, 129, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139")]
+    #[prost(oneof="action::ActionType", tags="1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124, 125, 126, 127, 128, 129, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142, 143, 144, 145, 146, 147, 148, 149, 150, 151, 152, 153, 154")]
     pub action_type: ::core::option::Option<action::ActionType>,
 }
 /// Nested message and enum types in `Action`.
@@ -407,6 +407,36 @@ pub mod action {
         SetLightTheme(super::SetLightThemeAction),
         #[prost(message, tag="139")]
         ToggleTheme(super::ToggleThemeAction),
+        #[prost(message, tag="140")]
+        ScrollToPreviousPrompt(super::ScrollToPreviousPromptAction),
+        #[prost(message, tag="141")]
+        ScrollToNextPrompt(super::ScrollToNextPromptAction),
+        #[prost(message, tag="142")]
+        SelectLastCommandOutput(super::SelectLastCommandOutputAction),
+        #[prost(message, tag="143")]
+        CopyLastCommandOutput(super::CopyLastCommandOutputAction),
+        #[prost(message, tag="144")]
+        DumpCommandOutput(super::DumpCommandOutputAction),
+        #[prost(message, tag="145")]
+        StartRecording(super::StartRecordingAction),
+        #[prost(message, tag="146")]
+        StopRecording(super::StopRecordingAction),
+        #[prost(message, tag="147")]
+        ToggleSyncGroup(super::ToggleSyncGroupAction),
+        #[prost(message, tag="148")]
+        SetSyncGroup(super::SetSyncGroupAction),
+        #[prost(message, tag="149")]
+        CopyModeMove(super::CopyModeMoveAction),
+        #[prost(message, tag="150")]
+        CopyModeSelect(super::CopyModeSelectAction),
+        #[prost(message, tag="151")]
+        ShowPaneHints(super::ShowPaneHintsAction),
+        #[prost(message, tag="152")]
+        ReopenClosedPane(super::ReopenClosedPaneAction),
+        #[prost(message, tag="153")]
+        ReopenClosedTab(super::ReopenClosedTabAction),
+        #[prost(message, tag="154")]
+        ToggleScratchpad(super::ToggleScratchpadAction),
     }
 }
 // Action message definitions (all 92 variants)
@@ -484,6 +514,22 @@ pub struct HalfPageScrollDownAction {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScrollToPreviousPromptAction {
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScrollToNextPromptAction {
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SelectLastCommandOutputAction {
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CopyLastCommandOutputAction {
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ToggleFocusFullscreenAction {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -690,6 +736,22 @@ pub struct TogglePaneInGroupAction {
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ToggleGroupMarkingAction {
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ToggleSyncGroupAction {
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShowPaneHintsAction {
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReopenClosedPaneAction {
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReopenClosedTabAction {
+}
 /// Complex action types (with data)
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -785,6 +847,34 @@ pub struct DumpScreenAction {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DumpCommandOutputAction {
+    #[prost(string, optional, tag="1")]
+    pub file_path: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(message, optional, tag="2")]
+    pub pane_id: ::core::option::Option<PaneId>,
+    #[prost(uint32, tag="3")]
+    pub index: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StartRecordingAction {
+    #[prost(string, tag="1")]
+    pub output: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="2")]
+    pub pane_id: ::core::option::Option<PaneId>,
+    #[prost(bool, tag="3")]
+    pub whole_tab: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StopRecordingAction {
+    #[prost(message, optional, tag="1")]
+    pub pane_id: ::core::option::Option<PaneId>,
+    #[prost(bool, tag="2")]
+    pub whole_tab: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ScrollUpAtAction {
     #[prost(message, optional, tag="1")]
     pub position: ::core::option::Option<Position>,
@@ -1227,6 +1317,34 @@ pub struct StackPanesAction {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetSyncGroupAction {
+    #[prost(message, repeated, tag="1")]
+    pub pane_ids: ::prost::alloc::vec::Vec<PaneId>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ToggleScratchpadAction {
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="2")]
+    pub command: ::core::option::Option<RunCommandAction>,
+    #[prost(message, optional, tag="3")]
+    pub coordinates: ::core::option::Option<FloatingPaneCoordinates>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CopyModeMoveAction {
+    #[prost(enumeration="CopyModeMotion", tag="1")]
+    pub motion: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CopyModeSelectAction {
+    #[prost(enumeration="CopySelectionKind", tag="1")]
+    pub kind: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ChangeFloatingPaneCoordinatesAction {
     #[prost(message, optional, tag="1")]
     pub pane_id: ::core::option::Option<PaneId>,
@@ -1292,6 +1410,8 @@ pub struct CliAssets {
     pub force_run_layout_commands: bool,
     #[prost(string, optional, tag="11")]
     pub cwd: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(map="string, string", tag="12")]
+    pub layout_args: ::std::collections::HashMap<::prost::alloc::string::String, ::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1325,6 +1445,8 @@ pub struct LayoutMetadata {
     pub creation_time: ::prost::alloc::string::String,
     #[prost(string, tag="3")]
     pub update_time: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag="4")]
+    pub imports: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1513,6 +1635,12 @@ pub struct RunCommandAction {
     /// Added missing use_terminal_title field
     #[prost(bool, tag="8")]
     pub use_terminal_title: bool,
+    #[prost(map="string, string", tag="9")]
+    pub env: ::std::collections::HashMap<::prost::alloc::string::String, ::prost::alloc::string::String>,
+    #[prost(enumeration="RestartPolicy", tag="10")]
+    pub restart_policy: i32,
+    #[prost(uint32, optional, tag="11")]
+    pub max_restarts: ::core::option::Option<u32>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1545,9 +1673,11 @@ pub struct TiledPaneLayout {
     pub pane_initial_contents: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag="14")]
     pub default_fg: ::core::option::Option<::prost::alloc::string::String>,
-    /// NOTE: run_instructions_to_ignore is not represented here because it's a field used only inside the server itself and not part of the server/client contract
     #[prost(string, optional, tag="15")]
     pub default_bg: ::core::option::Option<::prost::alloc::string::String>,
+    /// NOTE: run_instructions_to_ignore is not represented here because it's a field used only inside the server itself and not part of the server/client contract
+    #[prost(string, optional, tag="16")]
+    pub pane_scrollback_file: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1580,6 +1710,10 @@ pub struct FloatingPaneLayout {
     pub default_fg: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag="14")]
     pub default_bg: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="15")]
+    pub pane_scrollback_file: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(string, optional, tag="16")]
+    pub scratchpad: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1767,9 +1901,12 @@ pub mod percent_or_fixed {
 pub struct LayoutConstraintWithValue {
     #[prost(enumeration="LayoutConstraint", tag="1")]
     pub constraint_type: i32,
-    /// Only used for MAX_PANES, MIN_PANES, EXACT_PANES
+    /// Not used for NO_CONSTRAINT and COMBINED
     #[prost(uint32, optional, tag="2")]
     pub value: ::core::option::Option<u32>,
+    /// Only used for COMBINED
+    #[prost(message, repeated, tag="3")]
+    pub constraints: ::prost::alloc::vec::Vec<LayoutConstraintWithValue>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1949,6 +2086,12 @@ pub struct Options {
     pub theme_dark: ::core::option::Option<::prost::alloc::string::String>,
     #[prost(string, optional, tag="47")]
     pub theme_light: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint64, optional, tag="48")]
+    pub key_sequence_timeout: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag="49")]
+    pub disk_scroll_buffer_size: ::core::option::Option<u64>,
+    #[prost(bool, optional, tag="50")]
+    pub serialize_full_scrollback: ::core::option::Option<bool>,
 }
 /// Pane-targeting action messages
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2365,6 +2508,7 @@ pub enum InputMode {
     Move = 12,
     Prompt = 13,
     Tmux = 14,
+    Copy = 15,
 }
 impl InputMode {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -2388,6 +2532,7 @@ impl InputMode {
             InputMode::Move => "INPUT_MODE_MOVE",
             InputMode::Prompt => "INPUT_MODE_PROMPT",
             InputMode::Tmux => "INPUT_MODE_TMUX",
+            InputMode::Copy => "INPUT_MODE_COPY",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -2408,6 +2553,7 @@ impl InputMode {
             "INPUT_MODE_MOVE" => Some(Self::Move),
             "INPUT_MODE_PROMPT" => Some(Self::Prompt),
             "INPUT_MODE_TMUX" => Some(Self::Tmux),
+            "INPUT_MODE_COPY" => Some(Self::Copy),
             _ => None,
         }
     }
@@ -2560,6 +2706,38 @@ impl ExitReason {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum RestartPolicy {
+    Unspecified = 0,
+    Never = 1,
+    OnFailure = 2,
+    Always = 3,
+}
+impl RestartPolicy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            RestartPolicy::Unspecified => "RESTART_POLICY_UNSPECIFIED",
+            RestartPolicy::Never => "RESTART_POLICY_NEVER",
+            RestartPolicy::OnFailure => "RESTART_POLICY_ON_FAILURE",
+            RestartPolicy::Always => "RESTART_POLICY_ALWAYS",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RESTART_POLICY_UNSPECIFIED" => Some(Self::Unspecified),
+            "RESTART_POLICY_NEVER" => Some(Self::Never),
+            "RESTART_POLICY_ON_FAILURE" => Some(Self::OnFailure),
+            "RESTART_POLICY_ALWAYS" => Some(Self::Always),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum MouseEventType {
     Unspecified = 0,
     Press = 1,
@@ -2634,6 +2812,7 @@ pub enum SearchOption {
     WholeWord = 2,
     /// Unchanged - already matches
     Wrap = 3,
+    Regex = 4,
 }
 impl SearchOption {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -2646,6 +2825,7 @@ impl SearchOption {
             SearchOption::CaseSensitivity => "SEARCH_OPTION_CASE_SENSITIVITY",
             SearchOption::WholeWord => "SEARCH_OPTION_WHOLE_WORD",
             SearchOption::Wrap => "SEARCH_OPTION_WRAP",
+            SearchOption::Regex => "SEARCH_OPTION_REGEX",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -2655,6 +2835,95 @@ impl SearchOption {
             "SEARCH_OPTION_CASE_SENSITIVITY" => Some(Self::CaseSensitivity),
             "SEARCH_OPTION_WHOLE_WORD" => Some(Self::WholeWord),
             "SEARCH_OPTION_WRAP" => Some(Self::Wrap),
+            "SEARCH_OPTION_REGEX" => Some(Self::Regex),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CopyModeMotion {
+    Unspecified = 0,
+    Left = 1,
+    Right = 2,
+    Up = 3,
+    Down = 4,
+    WordForward = 5,
+    WordBackward = 6,
+    WordEnd = 7,
+    LineStart = 8,
+    LineEnd = 9,
+    Top = 10,
+    Bottom = 11,
+}
+impl CopyModeMotion {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CopyModeMotion::Unspecified => "COPY_MODE_MOTION_UNSPECIFIED",
+            CopyModeMotion::Left => "COPY_MODE_MOTION_LEFT",
+            CopyModeMotion::Right => "COPY_MODE_MOTION_RIGHT",
+            CopyModeMotion::Up => "COPY_MODE_MOTION_UP",
+            CopyModeMotion::Down => "COPY_MODE_MOTION_DOWN",
+            CopyModeMotion::WordForward => "COPY_MODE_MOTION_WORD_FORWARD",
+            CopyModeMotion::WordBackward => "COPY_MODE_MOTION_WORD_BACKWARD",
+            CopyModeMotion::WordEnd => "COPY_MODE_MOTION_WORD_END",
+            CopyModeMotion::LineStart => "COPY_MODE_MOTION_LINE_START",
+            CopyModeMotion::LineEnd => "COPY_MODE_MOTION_LINE_END",
+            CopyModeMotion::Top => "COPY_MODE_MOTION_TOP",
+            CopyModeMotion::Bottom => "COPY_MODE_MOTION_BOTTOM",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "COPY_MODE_MOTION_UNSPECIFIED" => Some(Self::Unspecified),
+            "COPY_MODE_MOTION_LEFT" => Some(Self::Left),
+            "COPY_MODE_MOTION_RIGHT" => Some(Self::Right),
+            "COPY_MODE_MOTION_UP" => Some(Self::Up),
+            "COPY_MODE_MOTION_DOWN" => Some(Self::Down),
+            "COPY_MODE_MOTION_WORD_FORWARD" => Some(Self::WordForward),
+            "COPY_MODE_MOTION_WORD_BACKWARD" => Some(Self::WordBackward),
+            "COPY_MODE_MOTION_WORD_END" => Some(Self::WordEnd),
+            "COPY_MODE_MOTION_LINE_START" => Some(Self::LineStart),
+            "COPY_MODE_MOTION_LINE_END" => Some(Self::LineEnd),
+            "COPY_MODE_MOTION_TOP" => Some(Self::Top),
+            "COPY_MODE_MOTION_BOTTOM" => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CopySelectionKind {
+    Unspecified = 0,
+    Character = 1,
+    Line = 2,
+    Block = 3,
+}
+impl CopySelectionKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CopySelectionKind::Unspecified => "COPY_SELECTION_KIND_UNSPECIFIED",
+            CopySelectionKind::Character => "COPY_SELECTION_KIND_CHARACTER",
+            CopySelectionKind::Line => "COPY_SELECTION_KIND_LINE",
+            CopySelectionKind::Block => "COPY_SELECTION_KIND_BLOCK",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "COPY_SELECTION_KIND_UNSPECIFIED" => Some(Self::Unspecified),
+            "COPY_SELECTION_KIND_CHARACTER" => Some(Self::Character),
+            "COPY_SELECTION_KIND_LINE" => Some(Self::Line),
+            "COPY_SELECTION_KIND_BLOCK" => Some(Self::Block),
             _ => None,
         }
     }
@@ -2698,6 +2967,13 @@ pub enum LayoutConstraint {
     MinPanes = 2,
     ExactPanes = 3,
     NoConstraint = 4,
+    MinColumns = 5,
+    MaxColumns = 6,
+    MinRows = 7,
+    MaxRows = 8,
+    MinAspectRatio = 9,
+    MaxAspectRatio = 10,
+    Combined = 11,
 }
 impl LayoutConstraint {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -2711,6 +2987,13 @@ impl LayoutConstraint {
             LayoutConstraint::MinPanes => "LAYOUT_CONSTRAINT_MIN_PANES",
             LayoutConstraint::ExactPanes => "LAYOUT_CONSTRAINT_EXACT_PANES",
             LayoutConstraint::NoConstraint => "LAYOUT_CONSTRAINT_NO_CONSTRAINT",
+            LayoutConstraint::MinColumns => "LAYOUT_CONSTRAINT_MIN_COLUMNS",
+            LayoutConstraint::MaxColumns => "LAYOUT_CONSTRAINT_MAX_COLUMNS",
+            LayoutConstraint::MinRows => "LAYOUT_CONSTRAINT_MIN_ROWS",
+            LayoutConstraint::MaxRows => "LAYOUT_CONSTRAINT_MAX_ROWS",
+            LayoutConstraint::MinAspectRatio => "LAYOUT_CONSTRAINT_MIN_ASPECT_RATIO",
+            LayoutConstraint::MaxAspectRatio => "LAYOUT_CONSTRAINT_MAX_ASPECT_RATIO",
+            LayoutConstraint::Combined => "LAYOUT_CONSTRAINT_COMBINED",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -2721,6 +3004,13 @@ impl LayoutConstraint {
             "LAYOUT_CONSTRAINT_MIN_PANES" => Some(Self::MinPanes),
             "LAYOUT_CONSTRAINT_EXACT_PANES" => Some(Self::ExactPanes),
             "LAYOUT_CONSTRAINT_NO_CONSTRAINT" => Some(Self::NoConstraint),
+            "LAYOUT_CONSTRAINT_MIN_COLUMNS" => Some(Self::MinColumns),
+            "LAYOUT_CONSTRAINT_MAX_COLUMNS" => Some(Self::MaxColumns),
+            "LAYOUT_CONSTRAINT_MIN_ROWS" => Some(Self::MinRows),
+            "LAYOUT_CONSTRAINT_MAX_ROWS" => Some(Self::MaxRows),
+            "LAYOUT_CONSTRAINT_MIN_ASPECT_RATIO" => Some(Self::MinAspectRatio),
+            "LAYOUT_CONSTRAINT_MAX_ASPECT_RATIO" => Some(Self::MaxAspectRatio),
+            "LAYOUT_CONSTRAINT_COMBINED" => Some(Self::Combined),
             _ => None,
         }
     }
@@ -2879,7 +3169,7 @@ impl WebSharing {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ClientToServerMsg {
-    #[prost(oneof="client_to_server_msg::Message", tags="1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20")]
+    #[prost(oneof="client_to_server_msg::Message", tags="1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21")]
     pub message: ::core::option::Option<client_to_server_msg::Message>,
 }
 /// Nested message and enum types in `ClientToServerMsg`.
@@ -2927,6 +3217,8 @@ pub mod client_to_server_msg {
         ForwardedReplyFromHost(super::ForwardedReplyFromHostMsg),
         #[prost(message, tag="20")]
         HostTerminalThemeChanged(super::HostTerminalThemeChangedMsg),
+        #[prost(message, tag="21")]
+        KittyGraphicsSupported(super::KittyGraphicsSupportedMsg),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3072,6 +3364,11 @@ pub struct HostTerminalThemeChangedMsg {
     #[prost(enumeration="HostTerminalThemeIndication", tag="1")]
     pub mode: i32,
 }
+/// Empty message
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KittyGraphicsSupportedMsg {
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum HostTerminalThemeIndication {
@@ -1,11 +1,15 @@
-use crate::data::{Direction, InputMode, Resize, UnblockCondition};
+use crate::data::{Direction, InputMode, PermissionType, Resize, UnblockCondition};
 use crate::setup::Setup;
 use crate::{
     consts::{ZELLIJ_CONFIG_DIR_ENV, ZELLIJ_CONFIG_FILE_ENV},
-    input::{layout::PluginUserConfiguration, options::Options},
+    input::{
+        actions::SearchOption, command::RestartPolicy, layout::PluginUserConfiguration,
+        options::Options,
+    },
 };
 use clap::{ArgEnum, Args, Parser, Subcommand};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::net::IpAddr;
 use std::path::PathBuf;
 use url::Url;
@@ -34,6 +38,16 @@ fn validate_session(name: &str) -> Result<String, String> {
     Ok(name.to_owned())
 }
 
+/// Parses a `NAME=VALUE` argument (eg. an environment variable or a layout argument)
+fn parse_name_value(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, value)) if !name.is_empty() && !name.contains(char::is_whitespace) => {
+            Ok((name.to_owned(), value.to_owned()))
+        },
+        _ => Err(format!("invalid argument: '{}', expected NAME=VALUE", arg)),
+    }
+}
+
 #[derive(Parser, Default, Debug, Clone, Serialize, Deserialize)]
 #[clap(version, name = "zellij")]
 pub struct CliArgs {
@@ -65,6 +79,14 @@ pub struct CliArgs {
     #[clap(long, value_parser, conflicts_with_all = &["layout", "new-session-with-layout"])]
     pub layout_string: Option<String>,
 
+    /// Value for a parameter declared by the layout, as NAME=VALUE (can be repeated)
+    #[clap(
+        long = "layout-arg",
+        value_parser = parse_name_value,
+        multiple_occurrences(true)
+    )]
+    pub layout_args: Vec<(String, String)>,
+
     /// Name of a predefined layout inside the layout directory or the path to a layout file
     /// Will always start a new session, even if inside an existing session
     #[clap(short, long, value_parser, overrides_with = "new_session_with_layout")]
@@ -101,6 +123,9 @@ impl CliArgs {
         }
         None
     }
+    pub fn layout_args(&self) -> BTreeMap<String, String> {
+        self.layout_args.iter().cloned().collect()
+    }
 }
 
 #[derive(Debug, Subcommand, Clone, Serialize, Deserialize)]
@@ -205,35 +230,63 @@ pub struct WebCli {
     pub server_startup_timeout: Option<u64>,
     /// Create a login token for the web interface, will only be displayed once and cannot later be
     /// retrieved. Returns the token name and the token.
-    #[clap(long, value_parser, exclusive(true), display_order = 7)]
+    #[clap(
+        long,
+        value_parser,
+        conflicts_with_all(&["start", "stop", "status", "create-read-only-token", "revoke-token", "revoke-all-tokens", "list-tokens"]),
+        display_order = 7
+    )]
     pub create_token: bool,
     /// Optional name for the token
     #[clap(long, value_parser, value_name = "TOKEN_NAME", display_order = 8)]
     pub token_name: Option<String>,
     /// Create a read-only login token (can only attach to existing sessions as watcher)
-    #[clap(long, value_parser, exclusive(true), display_order = 9)]
+    #[clap(
+        long,
+        value_parser,
+        conflicts_with_all(&["start", "stop", "status", "create-token", "revoke-token", "revoke-all-tokens", "list-tokens"]),
+        display_order = 9
+    )]
     pub create_read_only_token: bool,
+    /// Only allow the token being created to attach to sessions with this name or matching this
+    /// glob pattern (eg. 'proj-*'), can be specified multiple times
+    #[clap(
+        long = "session",
+        value_parser,
+        value_name = "SESSION_NAME_OR_GLOB",
+        multiple_occurrences(true),
+        display_order = 10
+    )]
+    pub token_session_scope: Vec<String>,
+    /// Expire the token being created after this long (eg. 12h, 7d)
+    #[clap(
+        long = "expires",
+        value_parser = humantime::parse_duration,
+        value_name = "DURATION",
+        display_order = 11
+    )]
+    pub token_expires_in: Option<std::time::Duration>,
     /// Revoke a login token by its name
     #[clap(
         long,
         value_parser,
         exclusive(true),
         value_name = "TOKEN NAME",
-        display_order = 10
+        display_order = 12
     )]
     pub revoke_token: Option<String>,
     /// Revoke all login tokens
-    #[clap(long, value_parser, exclusive(true), display_order = 11)]
+    #[clap(long, value_parser, exclusive(true), display_order = 13)]
     pub revoke_all_tokens: bool,
-    /// List token names and their creation dates (cannot show actual tokens)
-    #[clap(long, value_parser, exclusive(true), display_order = 12)]
+    /// List token names, their creation dates, session scopes and expiry (cannot show actual tokens)
+    #[clap(long, value_parser, exclusive(true), display_order = 14)]
     pub list_tokens: bool,
     /// The ip address to listen on locally for connections (defaults to 127.0.0.1)
     #[clap(
         long,
         value_parser,
         conflicts_with_all(&["stop", "create-token", "revoke-token", "revoke-all-tokens"]),
-        display_order = 13
+        display_order = 15
     )]
     pub ip: Option<IpAddr>,
     /// The port to listen on locally for connections (defaults to 8082)
@@ -241,7 +294,7 @@ pub struct WebCli {
         long,
         value_parser,
         conflicts_with_all(&["stop", "create-token", "revoke-token", "revoke-all-tokens"]),
-        display_order = 14
+        display_order = 16
     )]
     pub port: Option<u16>,
     /// The path to the SSL certificate (required if not listening on 127.0.0.1)
@@ -249,7 +302,7 @@ pub struct WebCli {
         long,
         value_parser,
         conflicts_with_all(&["stop", "status", "create-token", "revoke-token", "revoke-all-tokens"]),
-        display_order = 15
+        display_order = 17
     )]
     pub cert: Option<PathBuf>,
     /// The path to the SSL key (required if not listening on 127.0.0.1)
@@ -257,7 +310,7 @@ pub struct WebCli {
         long,
         value_parser,
         conflicts_with_all(&["stop", "status", "create-token", "revoke-token", "revoke-all-tokens"]),
-        display_order = 16
+        display_order = 18
     )]
     pub key: Option<PathBuf>,
 }
@@ -282,6 +335,43 @@ pub enum SessionCommand {
     Options(Options),
 }
 
+#[derive(Debug, Subcommand, Clone, Serialize, Deserialize)]
+pub enum PluginSubcommand {
+    /// Audit and manage the permissions granted to plugins
+    #[clap(subcommand)]
+    Permissions(PluginPermissionsCommand),
+}
+
+#[derive(Debug, Subcommand, Clone, Serialize, Deserialize)]
+pub enum PluginPermissionsCommand {
+    /// List the permissions granted to a plugin, or to all plugins if no URL is given
+    #[clap(visible_alias = "ls")]
+    List {
+        /// Plugin URL, can either start with http(s), file: or zellij:
+        #[clap(value_parser)]
+        url: Option<String>,
+    },
+    /// Grant permissions to a plugin, it will not be prompted for them when next loaded
+    Grant {
+        /// Plugin URL, can either start with http(s), file: or zellij:
+        #[clap(value_parser)]
+        url: String,
+        /// The permissions to grant (eg. ReadApplicationState RunCommands)
+        #[clap(value_parser, required(true))]
+        permissions: Vec<PermissionType>,
+    },
+    /// Revoke permissions from a plugin, or all of its permissions if none are given (so that it
+    /// will be prompted again when next loaded)
+    Revoke {
+        /// Plugin URL, can either start with http(s), file: or zellij:
+        #[clap(value_parser)]
+        url: String,
+        /// The permissions to revoke (eg. ReadApplicationState RunCommands)
+        #[clap(value_parser)]
+        permissions: Vec<PermissionType>,
+    },
+}
+
 #[derive(Debug, Subcommand, Clone, Serialize, Deserialize)]
 pub enum Sessions {
     /// List active sessions
@@ -412,6 +502,18 @@ pub enum Sessions {
         #[clap(long, value_parser)]
         cwd: Option<PathBuf>,
 
+        /// Set an environment variable for the command (eg. --env FOO=bar), can be repeated
+        #[clap(long, value_parser = parse_name_value, multiple_occurrences(true))]
+        env: Vec<(String, String)>,
+
+        /// Automatically re-run the command after it exits [never|on-failure|always]
+        #[clap(long, value_parser)]
+        restart: Option<RestartPolicy>,
+
+        /// Maximum number of consecutive automatic restarts (unlimited if not specified)
+        #[clap(long, value_parser, requires("restart"))]
+        max_restarts: Option<usize>,
+
         /// Open the new pane in floating mode
         #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
         floating: bool,
@@ -532,11 +634,14 @@ pub enum Sessions {
     },
     /// Load a plugin
     /// Returns: Created pane ID (format: plugin_<id>)
-    #[clap(visible_alias = "p")]
+    #[clap(visible_alias = "p", subcommand_negates_reqs(true))]
     Plugin {
         /// Plugin URL, can either start with http(s), file: or zellij:
         #[clap(last(true), required(true))]
-        url: String,
+        url: Option<String>,
+
+        #[clap(subcommand)]
+        command: Option<PluginSubcommand>,
 
         /// Plugin configuration
         #[clap(short, long, value_parser)]
@@ -874,6 +979,62 @@ pub enum CliAction {
         #[clap(short, long, value_parser)]
         pane_id: Option<String>,
     },
+    /// Scroll up to the previous shell prompt in the focused pane (requires a shell emitting
+    /// OSC 133 prompt marks)
+    ScrollToPreviousPrompt,
+    /// Scroll down to the next shell prompt in the focused pane (requires a shell emitting
+    /// OSC 133 prompt marks)
+    ScrollToNextPrompt,
+    /// Select the output of the last finished command in the focused pane
+    SelectLastCommandOutput,
+    /// Copy the output of the last finished command in the focused pane to the clipboard
+    CopyLastCommandOutput,
+    /// Dumps the output of a single finished command of a pane to a file or STDOUT (requires a
+    /// shell emitting OSC 133 prompt marks)
+    DumpCommandOutput {
+        /// File path to dump the command output to. If omitted, prints to STDOUT.
+        #[clap(long, value_parser)]
+        path: Option<PathBuf>,
+
+        /// The pane_id of the pane, eg. terminal_1 or 3 (equivalent to terminal_3). If not specified, dumps from the focused pane.
+        #[clap(short, long, value_parser)]
+        pane_id: Option<String>,
+
+        /// Which finished command to dump, counting back from the last one (0)
+        #[clap(short, long, value_parser, default_value("0"))]
+        index: usize,
+    },
+    /// Start recording the output of a terminal pane to an asciicast (asciinema v2) file
+    StartRecording {
+        /// File path to write the recording to. When recording a whole tab, one file is written
+        /// per terminal pane, eg. demo.cast becomes demo-terminal_1.cast, demo-terminal_2.cast
+        #[clap(short, long, value_parser)]
+        output: PathBuf,
+
+        /// The pane_id of the pane, eg. terminal_1 or 3 (equivalent to terminal_3). If not specified, records the focused pane.
+        #[clap(short, long, value_parser, conflicts_with("tab"))]
+        pane_id: Option<String>,
+
+        /// Record every terminal pane in the focused tab
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        tab: bool,
+    },
+    /// Stop recording pane output. If neither a pane nor a tab is specified, stops all recordings
+    StopRecording {
+        /// The pane_id of the pane, eg. terminal_1 or 3 (equivalent to terminal_3)
+        #[clap(short, long, value_parser, conflicts_with("tab"))]
+        pane_id: Option<String>,
+
+        /// Stop recording every pane in the focused tab
+        #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
+        tab: bool,
+    },
+    /// Toggle a scrollback search option of the focused pane
+    /// [CaseSensitivity|WholeWord|Wrap|Regex]
+    SearchToggleOption {
+        #[clap(value_parser)]
+        option: SearchOption,
+    },
     /// Toggle between fullscreen focus pane and normal layout.
     ToggleFullscreen {
         /// Target a specific pane by ID (eg. terminal_1, plugin_2, or 3)
@@ -906,6 +1067,22 @@ pub enum CliAction {
         #[clap(long, value_parser)]
         cwd: Option<PathBuf>,
 
+        /// Set an environment variable for the command (eg. --env FOO=bar), can be repeated
+        #[clap(
+            long,
+            value_parser = parse_name_value, multiple_occurrences(true),
+            requires("command")
+        )]
+        env: Vec<(String, String)>,
+
+        /// Automatically re-run the command after it exits [never|on-failure|always]
+        #[clap(long, value_parser, requires("command"))]
+        restart: Option<RestartPolicy>,
+
+        /// Maximum number of consecutive automatic restarts (unlimited if not specified)
+        #[clap(long, value_parser, requires("restart"))]
+        max_restarts: Option<usize>,
+
         /// Open the new pane in floating mode
         #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
         floating: bool,
@@ -1184,6 +1361,50 @@ pub enum CliAction {
         #[clap(short, long, value_parser)]
         tab_id: Option<usize>,
     },
+    /// Reopen the most recently closed pane where it was.
+    ReopenClosedPane,
+    /// Reopen the most recently closed tab where it was.
+    ReopenClosedTab,
+    /// Show or hide the floating scratchpad pane with this name, opening it if it does not exist
+    /// yet. Hidden scratchpads keep running in the background.
+    ToggleScratchpad {
+        /// The name of the scratchpad
+        name: String,
+
+        /// The command to open the scratchpad with (the default shell if not specified)
+        #[clap(last(true))]
+        command: Vec<String>,
+
+        /// Change the working directory of the scratchpad command
+        #[clap(long, value_parser, requires("command"))]
+        cwd: Option<PathBuf>,
+
+        /// Close the scratchpad immediately when its command exits
+        #[clap(
+            short,
+            long,
+            value_parser,
+            default_value("false"),
+            takes_value(false),
+            requires("command")
+        )]
+        close_on_exit: bool,
+        /// The x coordinates of the scratchpad as a bare integer (eg. 1) or percent (eg. 10%)
+        #[clap(short, long)]
+        x: Option<String>,
+        /// The y coordinates of the scratchpad as a bare integer (eg. 1) or percent (eg. 10%)
+        #[clap(short, long)]
+        y: Option<String>,
+        /// The width of the scratchpad as a bare integer (eg. 1) or percent (eg. 10%)
+        #[clap(long)]
+        width: Option<String>,
+        /// The height of the scratchpad as a bare integer (eg. 1) or percent (eg. 10%)
+        #[clap(long)]
+        height: Option<String>,
+        /// Whether to pin the scratchpad so that it is always on top
+        #[clap(long)]
+        pinned: Option<bool>,
+    },
     /// Go to tab with index [index]
     GoToTab {
         index: u32,
@@ -1239,6 +1460,14 @@ pub enum CliAction {
         #[clap(long, value_parser, requires("layout"))]
         layout_dir: Option<PathBuf>,
 
+        /// Value for a parameter declared by the layout, as NAME=VALUE (can be repeated)
+        #[clap(
+            long = "layout-arg",
+            value_parser = parse_name_value,
+            multiple_occurrences(true)
+        )]
+        layout_args: Vec<(String, String)>,
+
         /// Name of the new tab
         #[clap(short, long, value_parser)]
         name: Option<String>,
@@ -1352,6 +1581,14 @@ pub enum CliAction {
         #[clap(long, value_parser)]
         layout_dir: Option<PathBuf>,
 
+        /// Value for a parameter declared by the layout, as NAME=VALUE (can be repeated)
+        #[clap(
+            long = "layout-arg",
+            value_parser = parse_name_value,
+            multiple_occurrences(true)
+        )]
+        layout_args: Vec<(String, String)>,
+
         /// Retain existing terminal panes that do not fit in the layout (default: false)
         #[clap(long, value_parser, takes_value(false), default_value("false"))]
         retain_existing_terminal_panes: bool,
@@ -1581,6 +1818,15 @@ tail -f /tmp/my-live-logfile | zellij action pipe --name logs --plugin https://e
         #[clap(last(true), required(true))]
         pane_ids: Vec<String>,
     },
+    /// Send the input typed into any of these panes to all of them, even across tabs
+    /// Ids are a space separated list of pane ids, in the same form as for stack-panes.
+    /// Without any ids, stops syncing.
+    ///
+    /// Example: zellij action set-sync-group -- terminal_1 terminal_4 2
+    SetSyncGroup {
+        #[clap(last(true))]
+        pane_ids: Vec<String>,
+    },
     ChangeFloatingPaneCoordinates {
         /// The pane_id of the floating pane, eg.  terminal_1, plugin_2 or 3 (equivalent to
         /// terminal_3)
@@ -1739,4 +1985,98 @@ mod tests {
         let result = CliArgs::try_parse_from(["zellij", "subscribe"]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn run_env_can_be_repeated() {
+        let cli = CliArgs::try_parse_from([
+            "zellij", "run", "--env", "FOO=bar", "--env", "URL=a=b", "--", "cargo", "run",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Sessions(Sessions::Run { env, .. })) => assert_eq!(
+                env,
+                vec![
+                    ("FOO".to_owned(), "bar".to_owned()),
+                    ("URL".to_owned(), "a=b".to_owned())
+                ]
+            ),
+            other => panic!("Expected Run, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn env_must_be_key_value_pair() {
+        let result =
+            CliArgs::try_parse_from(["zellij", "run", "--env", "FOO", "--", "cargo", "run"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn new_pane_env_requires_command() {
+        let result = CliArgs::try_parse_from(["zellij", "action", "new-pane", "--env", "FOO=bar"]);
+        assert!(result.is_err());
+        let result = CliArgs::try_parse_from([
+            "zellij", "action", "new-pane", "--env", "FOO=bar", "--", "htop",
+        ]);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn run_restart_policy_and_max_restarts() {
+        let cli = CliArgs::try_parse_from([
+            "zellij",
+            "run",
+            "--restart",
+            "on-failure",
+            "--max-restarts",
+            "5",
+            "--",
+            "my-server",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Sessions(Sessions::Run {
+                restart,
+                max_restarts,
+                ..
+            })) => {
+                assert_eq!(restart, Some(RestartPolicy::OnFailure));
+                assert_eq!(max_restarts, Some(5));
+            },
+            other => panic!("Expected Run, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_restart_policy_and_bare_max_restarts_are_rejected() {
+        let result =
+            CliArgs::try_parse_from(["zellij", "run", "--restart", "sometimes", "--", "htop"]);
+        assert!(result.is_err());
+        let result =
+            CliArgs::try_parse_from(["zellij", "run", "--max-restarts", "3", "--", "htop"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn layout_args_are_collected() {
+        let cli = CliArgs::try_parse_from([
+            "zellij",
+            "--layout",
+            "dev.kdl",
+            "--layout-arg",
+            "project=api",
+            "--layout-arg",
+            "port=8080",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.layout_args(),
+            BTreeMap::from([
+                ("port".to_owned(), "8080".to_owned()),
+                ("project".to_owned(), "api".to_owned())
+            ])
+        );
+        let result = CliArgs::try_parse_from(["zellij", "--layout-arg", "project"]);
+        assert!(result.is_err());
+    }
 }
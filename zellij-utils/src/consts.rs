@@ -12,7 +12,9 @@ pub const ZELLIJ_CONFIG_DIR_ENV: &str = "ZELLIJ_CONFIG_DIR";
 pub const ZELLIJ_LAYOUT_DIR_ENV: &str = "ZELLIJ_LAYOUT_DIR";
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const DEFAULT_SCROLL_BUFFER_SIZE: usize = 10_000;
+pub const DEFAULT_KEY_SEQUENCE_TIMEOUT_MS: u64 = 1000;
 pub static SCROLL_BUFFER_SIZE: OnceLock<usize> = OnceLock::new();
+pub static DISK_SCROLL_BUFFER_SIZE: OnceLock<usize> = OnceLock::new();
 pub static DEBUG_MODE: OnceLock<bool> = OnceLock::new();
 
 #[cfg(not(windows))]
@@ -24,6 +26,7 @@ pub const SYSTEM_DEFAULT_DATA_DIR_PREFIX: &str = system_default_data_dir();
 pub static ZELLIJ_DEFAULT_THEMES: Dir = include_dir!("$CARGO_MANIFEST_DIR/assets/themes");
 
 pub const CLIENT_SERVER_CONTRACT_VERSION: usize = 1;
+pub const PLUGIN_MODULE_CACHE_DIR_NAME: &str = "plugin_modules";
 
 pub fn session_info_cache_file_name(session_name: &str) -> PathBuf {
     session_info_folder_for_session(session_name).join("session-metadata.kdl")
@@ -107,6 +110,8 @@ lazy_static! {
         .join(CLIENT_SERVER_CONTRACT_DIR.clone())
         .join("session_info");
     pub static ref ZELLIJ_PLUGIN_ARTIFACT_DIR: PathBuf = ZELLIJ_CACHE_DIR.join(VERSION);
+    pub static ref ZELLIJ_PLUGIN_MODULE_CACHE_DIR: PathBuf =
+        ZELLIJ_PLUGIN_ARTIFACT_DIR.join(PLUGIN_MODULE_CACHE_DIR_NAME);
     pub static ref ZELLIJ_SEEN_RELEASE_NOTES_CACHE_FILE: PathBuf =
         ZELLIJ_CACHE_DIR.join(VERSION).join("seen_release_notes");
 }
@@ -18,7 +18,7 @@ use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 use std::str::{self, FromStr};
 use std::time::Duration;
-use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumString};
+use strum_macros::{Display, EnumDiscriminants, EnumIter, EnumString, IntoStaticStr};
 use unicode_width::UnicodeWidthChar;
 
 #[cfg(not(target_family = "wasm"))]
@@ -413,6 +413,14 @@ bitflags::bitflags! {
     }
 }
 
+// the modifiers, without the event type and text that might follow them
+fn modifiers_field(modifier_bytes: &[u8]) -> &[u8] {
+    modifier_bytes
+        .split(|b| *b == b':' || *b == b';')
+        .next()
+        .unwrap_or(&[])
+}
+
 impl KeyModifier {
     pub fn from_bytes(bytes: &[u8]) -> BTreeSet<KeyModifier> {
         let modifier_flags = str::from_utf8(bytes)
@@ -466,11 +474,42 @@ impl KeyWithModifier {
         self
     }
     pub fn from_bytes_with_u(number_bytes: &[u8], modifier_bytes: &[u8]) -> Option<Self> {
-        // CSI number ; modifiers u
-        let bare_key = BareKey::from_bytes_with_u(number_bytes);
+        // CSI number[:shifted-key[:base-layout-key]] ; modifiers[:event-type] [; text] u
+        let mut key_codes = number_bytes.split(|b| *b == b':');
+        let bare_key = key_codes.next().and_then(BareKey::from_bytes_with_u);
+        let shifted_key = key_codes
+            .next()
+            .filter(|k| !k.is_empty())
+            .and_then(BareKey::from_bytes_with_u);
+        let mut fields = modifier_bytes.split(|b| *b == b';');
+        let key_modifiers = KeyModifier::from_bytes(modifiers_field(fields.next().unwrap_or(&[])));
+        let text = fields
+            .next()
+            .and_then(|text| str::from_utf8(text).ok())
+            .map(|text| {
+                text.split(':')
+                    .filter_map(|c| c.parse().ok().and_then(char::from_u32))
+                    .collect::<String>()
+            })
+            .unwrap_or_default();
         match bare_key {
             Some(bare_key) => {
-                let key_modifiers = KeyModifier::from_bytes(modifier_bytes);
+                if key_modifiers.iter().all(|m| *m == KeyModifier::Shift) {
+                    // without other modifiers, this key would have been sent to us as the text
+                    // it produces if we didn't ask for all keys as escape codes
+                    let mut text_chars = text.chars();
+                    match (text_chars.next(), text_chars.next()) {
+                        (Some(character), None) if !character.is_control() => {
+                            return Some(KeyWithModifier::new(BareKey::Char(character)));
+                        },
+                        _ => {},
+                    }
+                    if let Some(BareKey::Char(shifted_key)) = shifted_key {
+                        if key_modifiers.contains(&KeyModifier::Shift) {
+                            return Some(KeyWithModifier::new(BareKey::Char(shifted_key)));
+                        }
+                    }
+                }
                 Some(KeyWithModifier {
                     bare_key,
                     key_modifiers,
@@ -480,11 +519,11 @@ impl KeyWithModifier {
         }
     }
     pub fn from_bytes_with_tilde(number_bytes: &[u8], modifier_bytes: &[u8]) -> Option<Self> {
-        // CSI number ; modifiers ~
+        // CSI number ; modifiers[:event-type] ~
         let bare_key = BareKey::from_bytes_with_tilde(number_bytes);
         match bare_key {
             Some(bare_key) => {
-                let key_modifiers = KeyModifier::from_bytes(modifier_bytes);
+                let key_modifiers = KeyModifier::from_bytes(modifiers_field(modifier_bytes));
                 Some(KeyWithModifier {
                     bare_key,
                     key_modifiers,
@@ -497,11 +536,11 @@ impl KeyWithModifier {
         number_bytes: &[u8],
         modifier_bytes: &[u8],
     ) -> Option<Self> {
-        // CSI 1; modifiers [ABCDEFHPQS]
+        // CSI 1; modifiers[:event-type] [ABCDEFHPQS]
         let bare_key = BareKey::from_bytes_with_no_ending_byte(number_bytes);
         match bare_key {
             Some(bare_key) => {
-                let key_modifiers = KeyModifier::from_bytes(modifier_bytes);
+                let key_modifiers = KeyModifier::from_bytes(modifiers_field(modifier_bytes));
                 Some(KeyWithModifier {
                     bare_key,
                     key_modifiers,
@@ -1028,6 +1067,10 @@ pub enum Event {
     InitialKeybinds(KeybindsVec),
     /// The host terminal indicated its color palette theme mode (CSI 2031 / DSR 997).
     HostTerminalThemeChanged(HostTerminalThemeMode),
+    /// A shell with OSC 133 integration reported that a command finished
+    CommandFinished(u32, Option<i32>), // u32 - terminal_pane_id, Option<i32> - exit code
+    /// The set of terminal panes being recorded to an asciicast file changed
+    RecordingStateChanged(Vec<u32>), // terminal pane ids currently being recorded
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -1130,13 +1173,13 @@ impl PluginPermission {
 
 /// Describes the different input modes, which change the way that keystrokes will be interpreted.
 #[derive(
-    Debug,
     PartialEq,
     Eq,
     Hash,
     Copy,
     Clone,
     EnumIter,
+    IntoStaticStr,
     Serialize,
     Deserialize,
     ArgEnum,
@@ -1188,6 +1231,192 @@ pub enum InputMode {
     /// `Tmux` mode allows for basic tmux keybindings functionality
     #[serde(alias = "tmux")]
     Tmux,
+    /// `Copy` mode allows moving a cursor through a pane with vi-like motions, selecting and
+    /// copying text with the keyboard
+    #[serde(alias = "copy")]
+    Copy,
+    /// A mode defined by the user in the keybindings configuration (eg. `custom_mode "git" {}`)
+    #[strum(disabled)]
+    #[clap(skip)]
+    Custom(CustomInputMode),
+}
+
+// custom modes are displayed by their name, so that they can be rendered and serialized the same
+// way as the built-in ones
+impl fmt::Debug for InputMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputMode::Custom(custom_input_mode) => write!(f, "{}", custom_input_mode),
+            built_in_mode => write!(f, "{}", <&'static str>::from(built_in_mode)),
+        }
+    }
+}
+
+impl InputMode {
+    pub fn is_custom(&self) -> bool {
+        matches!(self, InputMode::Custom(_))
+    }
+    /// Restores the name of a custom mode received by id, see `CustomInputMode::with_name_from`
+    pub fn with_custom_mode_name_from(self, custom_modes: &[CustomInputMode]) -> Self {
+        match self {
+            InputMode::Custom(custom_input_mode) => {
+                InputMode::Custom(custom_input_mode.with_name_from(custom_modes))
+            },
+            built_in_mode => built_in_mode,
+        }
+    }
+}
+
+/// A user defined input mode (`custom_mode "git" { ... }` in the keybindings configuration)
+///
+/// Custom modes are identified by an id derived from their (case insensitive) name, so that the
+/// server, the clients and the plugins agree on it without having to coordinate. The name is
+/// carried along for display, except when the mode is received by id alone (eg. over protobuf),
+/// in which case it can be restored from the custom modes of the `Keybinds` or `ModeInfo`.
+#[derive(Copy, Clone)]
+pub struct CustomInputMode {
+    id: u32,
+    name: [u8; CustomInputMode::MAX_NAME_LEN],
+    name_len: u8,
+}
+
+impl CustomInputMode {
+    /// Ids below this one are reserved for the built-in modes (eg. in the protobuf `InputMode` enum)
+    pub const MIN_ID: u32 = 1000;
+    /// Names are stored inline so that `InputMode` stays `Copy`
+    pub const MAX_NAME_LEN: usize = 32;
+    /// Names longer than `MAX_NAME_LEN` are truncated (the id is derived from the full name)
+    pub fn new(name: &str) -> Self {
+        let name = name.to_lowercase();
+        let mut name_len = name.len().min(CustomInputMode::MAX_NAME_LEN);
+        while !name.is_char_boundary(name_len) {
+            name_len -= 1;
+        }
+        let mut name_bytes = [0; CustomInputMode::MAX_NAME_LEN];
+        name_bytes[..name_len].copy_from_slice(&name.as_bytes()[..name_len]);
+        CustomInputMode {
+            id: CustomInputMode::id_for_name(&name),
+            name: name_bytes,
+            name_len: name_len as u8,
+        }
+    }
+    /// A custom mode whose name is not known (yet), see `with_name_from`
+    pub fn from_id(id: u32) -> Option<Self> {
+        if id >= CustomInputMode::MIN_ID && id <= i32::MAX as u32 {
+            Some(CustomInputMode {
+                id,
+                name: [0; CustomInputMode::MAX_NAME_LEN],
+                name_len: 0,
+            })
+        } else {
+            None
+        }
+    }
+    /// Takes the name of the mode with the same id in `custom_modes`, if there is one
+    pub fn with_name_from(self, custom_modes: &[CustomInputMode]) -> Self {
+        custom_modes
+            .iter()
+            .find(|custom_mode| **custom_mode == self)
+            .copied()
+            .unwrap_or(self)
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn name(&self) -> String {
+        if self.name_len == 0 {
+            format!("custom_{}", self.id)
+        } else {
+            String::from_utf8_lossy(&self.name[..self.name_len as usize]).into_owned()
+        }
+    }
+    // FNV-1a, we need it to be stable across processes and versions so we can't use the std Hasher
+    fn id_for_name(name: &str) -> u32 {
+        let mut hash: u32 = 0x811c9dc5;
+        for byte in name.bytes() {
+            hash ^= byte as u32;
+            hash = hash.wrapping_mul(0x01000193);
+        }
+        CustomInputMode::MIN_ID + hash % (i32::MAX as u32 - CustomInputMode::MIN_ID)
+    }
+}
+
+// the name is only there for display, so modes are compared by id
+impl PartialEq for CustomInputMode {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for CustomInputMode {}
+
+impl Hash for CustomInputMode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl PartialOrd for CustomInputMode {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CustomInputMode {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl fmt::Display for CustomInputMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl fmt::Debug for CustomInputMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("CustomInputMode")
+            .field(&self.name())
+            .finish()
+    }
+}
+
+impl Serialize for CustomInputMode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomInputMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(CustomInputMode::new(&name))
+    }
+}
+
+/// What happens to keys that are not bound in a custom input mode
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CustomModeFallThrough {
+    /// Unbound keys are ignored, as in most of the built-in modes
+    #[default]
+    Swallow,
+    /// Unbound keys are sent to the focused pane, as in the `Normal` mode
+    Pane,
+}
+
+impl FromStr for CustomModeFallThrough {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "swallow" | "Swallow" => Ok(CustomModeFallThrough::Swallow),
+            "pane" | "Pane" => Ok(CustomModeFallThrough::Pane),
+            _ => Err(format!(
+                "Unknown fall_through '{}', expected either \"pane\" or \"swallow\"",
+                s
+            )),
+        }
+    }
 }
 
 impl Default for InputMode {
@@ -1332,6 +1561,7 @@ impl FromStr for InputMode {
             "move" | "Move" => Ok(InputMode::Move),
             "prompt" | "Prompt" => Ok(InputMode::Prompt),
             "tmux" | "Tmux" => Ok(InputMode::Tmux),
+            "copy" | "Copy" => Ok(InputMode::Copy),
             "entersearch" | "Entersearch" | "EnterSearch" => Ok(InputMode::EnterSearch),
             e => Err(ConversionError::UnknownInputMode(e.into())),
         }
@@ -1718,6 +1948,7 @@ impl From<Palette> for Styling {
 
 // FIXME: Poor devs hashtable since HashTable can't derive `Default`...
 pub type KeybindsVec = Vec<(InputMode, Vec<(KeyWithModifier, Vec<Action>)>)>;
+pub type KeySequencesVec = Vec<(InputMode, Vec<(Vec<KeyWithModifier>, Vec<Action>)>)>;
 
 /// Provides information helpful in rendering the Zellij controls for UI bars
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -1738,6 +1969,10 @@ pub struct ModeInfo {
     pub web_server_ip: Option<IpAddr>,
     pub web_server_port: Option<u16>,
     pub web_server_capability: Option<bool>,
+    pub key_sequences: KeySequencesVec,
+    // keys of a multi-key sequence that were pressed so far, waiting for the rest of it
+    pub pending_key_sequence: Vec<KeyWithModifier>,
+    pub custom_modes: Vec<CustomInputMode>,
 }
 
 impl ModeInfo {
@@ -1753,8 +1988,21 @@ impl ModeInfo {
         }
         vec![]
     }
+    pub fn get_key_sequences_for_mode(
+        &self,
+        mode: InputMode,
+    ) -> Vec<(Vec<KeyWithModifier>, Vec<Action>)> {
+        for (vec_mode, sequences) in &self.key_sequences {
+            if mode == *vec_mode {
+                return sequences.to_vec();
+            }
+        }
+        vec![]
+    }
     pub fn update_keybinds(&mut self, keybinds: Keybinds) {
         self.keybinds = keybinds.to_keybinds_vec();
+        self.key_sequences = keybinds.to_key_sequences_vec();
+        self.custom_modes = keybinds.custom_modes();
     }
     pub fn update_default_mode(&mut self, new_default_mode: InputMode) {
         self.base_mode = Some(new_default_mode);
@@ -1846,14 +2094,15 @@ pub struct LayoutMetadata {
     pub tabs: Vec<TabMetadata>,
     pub creation_time: String,
     pub update_time: String,
+    pub imports: Vec<String>, // paths of the layout files this layout imports (directly or not)
 }
 
 impl From<&PathBuf> for LayoutMetadata {
     fn from(path: &PathBuf) -> LayoutMetadata {
         match Layout::stringified_from_path(path) {
             Ok((path_str, stringified_layout, _swap_layouts)) => {
-                match Layout::from_kdl(&stringified_layout, Some(path_str), None, None) {
-                    Ok(layout) => {
+                match Layout::from_kdl_with_imports(&stringified_layout, Some(path_str), None) {
+                    Ok((layout, imported_files)) => {
                         let layout_tabs = layout.tabs();
                         let tabs = if layout_tabs.is_empty() {
                             let (tiled_pane_layout, floating_pane_layout) = layout.new_tab();
@@ -1878,6 +2127,10 @@ impl From<&PathBuf> for LayoutMetadata {
                             tabs,
                             creation_time,
                             update_time,
+                            imports: imported_files
+                                .iter()
+                                .map(|f| f.display().to_string())
+                                .collect(),
                         }
                     },
                     Err(e) => {
@@ -2274,6 +2527,17 @@ pub struct TabInfo {
     pub is_flashing_bell: bool,
 }
 
+/// A pane or tab that was closed in this session and can still be reopened
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct RecentlyClosedInfo {
+    /// The id to reopen it with, unique in this session
+    pub id: u32,
+    /// Whether this was a whole tab rather than a single pane
+    pub is_tab: bool,
+    /// The name of the tab, or the title (or else the command, file or plugin) of the pane
+    pub name: String,
+}
+
 /// The `PaneManifest` contains a dictionary of panes, indexed by the tab position (0 indexed).
 /// Panes include all panes in the relevant tab, including `tiled` panes, `floating` panes and
 /// `suppressed` panes.
@@ -3444,7 +3708,7 @@ pub enum PluginCommand {
         tab_index: Option<usize>,
     },
     CloseSelf,
-    NewTabsWithLayoutInfo(LayoutInfo),
+    NewTabsWithLayoutInfo(LayoutInfo, BTreeMap<String, String>), // BTreeMap -> layout arguments
     Reconfigure(String, bool), // String -> stringified configuration, bool -> save configuration
     // file to disk
     HidePaneWithId(PaneId),
@@ -3504,6 +3768,8 @@ pub enum PluginCommand {
     RebindKeys {
         keys_to_rebind: Vec<(InputMode, KeyWithModifier, Vec<Action>)>,
         keys_to_unbind: Vec<(InputMode, KeyWithModifier)>,
+        key_sequences_to_rebind: Vec<(InputMode, Vec<KeyWithModifier>, Vec<Action>)>,
+        key_sequences_to_unbind: Vec<(InputMode, Vec<KeyWithModifier>)>,
         write_config_to_disk: bool,
     },
     ListClients,
@@ -3610,6 +3876,9 @@ pub enum PluginCommand {
     KillSessionsAndReply(Vec<String>), // one or more session names; sends a response back
     DeleteDeadSessionAndReply(String), // session name; sends a response back
     DeleteAllDeadSessionsAndReply,     // no payload; sends a response back
+    SetSyncGroup(Vec<PaneId>),         // an empty list stops syncing
+    ListRecentlyClosed,
+    ReopenClosed(u32), // the id of the recently closed pane or tab
 }
 
 // Response type for plugin API methods that open a pane in a new tab
@@ -276,6 +276,13 @@ pub enum ScreenContext {
     PageScrollDown,
     HalfPageScrollUp,
     HalfPageScrollDown,
+    ScrollToPreviousPrompt,
+    ScrollToNextPrompt,
+    SelectLastCommandOutput,
+    CopyLastCommandOutput,
+    DumpCommandOutput,
+    StartRecording,
+    StopRecording,
     ClearScroll,
     CloseFocusedPane,
     ToggleActiveSyncTab,
@@ -311,6 +318,7 @@ pub enum ScreenContext {
     TerminalBackgroundColor,
     TerminalForegroundColor,
     TerminalColorRegisters,
+    KittyGraphicsSupported,
     ForwardHostQuery,
     ForwardedReplyFromHost,
     ResumePaneAfterForward,
@@ -320,6 +328,7 @@ pub enum ScreenContext {
     ToggleTheme,
     ChangeMode,
     ChangeModeForAllClients,
+    UpdatePendingKeySequence,
     LeftClick,
     RightClick,
     MiddleClick,
@@ -337,6 +346,9 @@ pub enum ScreenContext {
     SearchToggleCaseSensitivity,
     SearchToggleWholeWord,
     SearchToggleWrap,
+    SearchToggleRegex,
+    CopyModeMove,
+    CopyModeSelect,
     AddRedPaneFrameColorOverride,
     ClearPaneFrameColorOverride,
     SetTabBellFlash,
@@ -382,6 +394,7 @@ pub enum ScreenContext {
     GetCurrentTabInfo,
     Reconfigure,
     RerunCommandPane,
+    RestartCommandPane,
     ResizePaneWithId,
     EditScrollbackForPaneWithId,
     WriteToPaneId,
@@ -417,6 +430,19 @@ pub enum ScreenContext {
     EmbedMultiplePanes,
     TogglePaneInGroup,
     ToggleGroupMarking,
+    ToggleSyncGroup,
+    SetSyncGroup,
+    ShowPaneHints,
+    ReopenClosedPane,
+    ReopenClosedTab,
+    ReopenClosed,
+    RememberClosedPane,
+    AddRecentlyClosed,
+    RestoreClosedPane,
+    ListRecentlyClosed,
+    ToggleScratchpad,
+    AddScratchpad,
+    ScratchpadNotSpawned,
     SessionSharingStatusChange,
     SetMouseSelectionSupport,
     InterceptKeyPresses,
@@ -496,6 +522,10 @@ pub enum PtyContext {
     GetPaneCwd,
     UpdateAndReportCwds,
     NotifyCwdFromOsc7,
+    RememberClosedPane,
+    RememberClosedTab,
+    ReopenClosedPane,
+    SpawnScratchpad,
     Exit,
 }
 
@@ -625,6 +655,7 @@ pub enum BackgroundJobContext {
     StopPluginLoadingAnimation,
     ReportSessionInfo,
     ReportLayoutInfo,
+    PersistPaneScrollbacks,
     RunCommand,
     WebRequest,
     ReportPluginList,
@@ -637,6 +668,7 @@ pub enum BackgroundJobContext {
     StopFlashPaneBell,
     FlashTabBell,
     StopFlashTabBell,
+    RestartCommandPane,
     Exit,
 }
 
@@ -78,6 +78,7 @@ pub enum SearchOption {
     CaseSensitivity,
     WholeWord,
     Wrap,
+    Regex,
 }
 
 impl FromStr for SearchOption {
@@ -89,6 +90,7 @@ impl FromStr for SearchOption {
             },
             "WholeWord" | "wholeword" | "Wholeword" => Ok(SearchOption::WholeWord),
             "Wrap" | "wrap" => Ok(SearchOption::Wrap),
+            "Regex" | "regex" => Ok(SearchOption::Regex),
             _ => Err(format!(
                 "Failed to parse SearchOption. Unknown SearchOption: {}",
                 s
@@ -97,6 +99,81 @@ impl FromStr for SearchOption {
     }
 }
 
+/// A vi-like motion of the copy mode cursor
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CopyModeMotion {
+    Left,
+    Right,
+    Up,
+    #[default]
+    Down,
+    /// The start of the next word (`w`)
+    WordForward,
+    /// The start of the current or previous word (`b`)
+    WordBackward,
+    /// The end of the current or next word (`e`)
+    WordEnd,
+    /// The first column of the line (`0`)
+    LineStart,
+    /// The last character of the line (`$`)
+    LineEnd,
+    /// The first line of the scrollback (`gg`)
+    Top,
+    /// The last line of the pane (`G`)
+    Bottom,
+}
+
+impl FromStr for CopyModeMotion {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Left" | "left" => Ok(CopyModeMotion::Left),
+            "Right" | "right" => Ok(CopyModeMotion::Right),
+            "Up" | "up" => Ok(CopyModeMotion::Up),
+            "Down" | "down" => Ok(CopyModeMotion::Down),
+            "WordForward" | "wordforward" | "Wordforward" => Ok(CopyModeMotion::WordForward),
+            "WordBackward" | "wordbackward" | "Wordbackward" => Ok(CopyModeMotion::WordBackward),
+            "WordEnd" | "wordend" | "Wordend" => Ok(CopyModeMotion::WordEnd),
+            "LineStart" | "linestart" | "Linestart" => Ok(CopyModeMotion::LineStart),
+            "LineEnd" | "lineend" | "Lineend" => Ok(CopyModeMotion::LineEnd),
+            "Top" | "top" => Ok(CopyModeMotion::Top),
+            "Bottom" | "bottom" => Ok(CopyModeMotion::Bottom),
+            _ => Err(format!(
+                "Failed to parse CopyModeMotion. Unknown CopyModeMotion: {}",
+                s
+            )),
+        }
+    }
+}
+
+/// How the text between the copy mode cursor and the point where the selection started is
+/// selected
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CopySelectionKind {
+    /// Every character between the two points (`v`)
+    #[default]
+    Character,
+    /// Whole lines (`V`)
+    Line,
+    /// The rectangle the two points are the corners of (`Ctrl v`)
+    Block,
+}
+
+impl FromStr for CopySelectionKind {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Character" | "character" => Ok(CopySelectionKind::Character),
+            "Line" | "line" => Ok(CopySelectionKind::Line),
+            "Block" | "block" => Ok(CopySelectionKind::Block),
+            _ => Err(format!(
+                "Failed to parse CopySelectionKind. Unknown CopySelectionKind: {}",
+                s
+            )),
+        }
+    }
+}
+
 // As these actions are bound to the default config, please
 // do take care when refactoring - or renaming.
 // They might need to be adjusted in the default config
@@ -212,6 +289,34 @@ pub enum Action {
     HalfPageScrollUp,
     /// Scroll down half page in focus pane.
     HalfPageScrollDown,
+    /// Scroll up to the previous shell prompt in focus pane (requires OSC 133 shell integration).
+    ScrollToPreviousPrompt,
+    /// Scroll down to the next shell prompt in focus pane (requires OSC 133 shell integration).
+    ScrollToNextPrompt,
+    /// Select the output of the last finished command in focus pane.
+    SelectLastCommandOutput,
+    /// Copy the output of the last finished command in focus pane to the clipboard.
+    CopyLastCommandOutput,
+    /// Dumps the output of a single finished command to a file or STDOUT, `index` counts back
+    /// from the last finished command (0)
+    DumpCommandOutput {
+        file_path: Option<String>,
+        pane_id: Option<PaneId>,
+        index: usize,
+    },
+    /// Start recording the output of a terminal pane (the focused one if no `pane_id` is given,
+    /// or every terminal pane of the focused tab if `whole_tab` is set) to an asciicast file
+    StartRecording {
+        output: String,
+        pane_id: Option<PaneId>,
+        whole_tab: bool,
+    },
+    /// Stop recording the given pane (or every pane of the focused tab if `whole_tab` is set),
+    /// stops all recordings in the session if neither is given
+    StopRecording {
+        pane_id: Option<PaneId>,
+        whole_tab: bool,
+    },
     /// Toggle between fullscreen focus pane and normal layout.
     ToggleFocusFullscreen,
     /// Toggle frames around panes in the UI
@@ -411,6 +516,14 @@ pub enum Action {
     SearchToggleOption {
         option: SearchOption,
     },
+    /// Move the copy mode cursor of the focused pane, entering copy mode if needed
+    CopyModeMove {
+        motion: CopyModeMotion,
+    },
+    /// Start selecting text from the copy mode cursor, or stop if already selecting this way
+    CopyModeSelect {
+        kind: CopySelectionKind,
+    },
     ToggleMouseMode,
     PreviousSwapLayout,
     NextSwapLayout,
@@ -561,6 +674,27 @@ pub enum Action {
     },
     TogglePaneInGroup,
     ToggleGroupMarking,
+    /// Turn the panes marked by the client into the sync group, or stop syncing if a sync
+    /// group exists
+    ToggleSyncGroup,
+    /// Send the input typed into any of these panes to all of them, even across tabs. An empty
+    /// list stops syncing
+    SetSyncGroup {
+        pane_ids: Vec<PaneId>,
+    },
+    /// Label every visible pane with a short hint and focus the pane whose hint is typed next
+    ShowPaneHints,
+    /// Reopen the most recently closed pane where it was, with the command, plugin or cwd it had
+    ReopenClosedPane,
+    /// Reopen the most recently closed tab with its panes at its previous position
+    ReopenClosedTab,
+    /// Show or hide the floating scratchpad pane with this name, opening it with this command
+    /// (or the default shell) the first time. A hidden scratchpad keeps running
+    ToggleScratchpad {
+        name: String,
+        command: Option<RunCommandAction>,
+        coordinates: Option<FloatingPaneCoordinates>,
+    },
     // Pane-targeting CLI-only variants
     ScrollUpByPaneId {
         pane_id: PaneId,
@@ -992,6 +1126,64 @@ impl Action {
                 },
                 None => Ok(vec![Action::HalfPageScrollDown]),
             },
+            CliAction::ScrollToPreviousPrompt => Ok(vec![Action::ScrollToPreviousPrompt]),
+            CliAction::ScrollToNextPrompt => Ok(vec![Action::ScrollToNextPrompt]),
+            CliAction::SelectLastCommandOutput => Ok(vec![Action::SelectLastCommandOutput]),
+            CliAction::CopyLastCommandOutput => Ok(vec![Action::CopyLastCommandOutput]),
+            CliAction::DumpCommandOutput {
+                path,
+                pane_id,
+                index,
+            } => {
+                let pane_id = match pane_id {
+                    Some(pane_id_str) => Some(PaneId::from_str(&pane_id_str).map_err(|_| format!(
+                        "Malformed pane id: {pane_id_str}, expecting either a bare integer (eg. 1), a terminal pane id (eg. terminal_1) or a plugin pane id (eg. plugin_1)"
+                    ))?),
+                    None => None,
+                };
+                Ok(vec![Action::DumpCommandOutput {
+                    file_path: path.map(|p| p.as_os_str().to_string_lossy().into()),
+                    pane_id,
+                    index,
+                }])
+            },
+            CliAction::StartRecording {
+                output,
+                pane_id,
+                tab,
+            } => {
+                let pane_id = match pane_id {
+                    Some(pane_id_str) => Some(PaneId::from_str(&pane_id_str).map_err(|_| format!(
+                        "Malformed pane id: {pane_id_str}, expecting either a bare integer (eg. 1) or a terminal pane id (eg. terminal_1)"
+                    ))?),
+                    None => None,
+                };
+                let output = if output.is_relative() {
+                    get_current_dir().join(output)
+                } else {
+                    output
+                };
+                Ok(vec![Action::StartRecording {
+                    output: output.as_os_str().to_string_lossy().into(),
+                    pane_id,
+                    whole_tab: tab,
+                }])
+            },
+            CliAction::StopRecording { pane_id, tab } => {
+                let pane_id = match pane_id {
+                    Some(pane_id_str) => Some(PaneId::from_str(&pane_id_str).map_err(|_| format!(
+                        "Malformed pane id: {pane_id_str}, expecting either a bare integer (eg. 1) or a terminal pane id (eg. terminal_1)"
+                    ))?),
+                    None => None,
+                };
+                Ok(vec![Action::StopRecording {
+                    pane_id,
+                    whole_tab: tab,
+                }])
+            },
+            CliAction::SearchToggleOption { option } => {
+                Ok(vec![Action::SearchToggleOption { option }])
+            },
             CliAction::ToggleFullscreen { pane_id } => match pane_id {
                 Some(pane_id_str) => {
                     let pane_id = PaneId::from_str(&pane_id_str)
@@ -1034,6 +1226,9 @@ impl Action {
                 near_current_pane,
                 borderless,
                 tab_id,
+                env,
+                restart,
+                max_restarts,
             } => {
                 let current_dir = get_current_dir();
                 // cwd should only be specified in a plugin alias if it was explicitly given to us,
@@ -1071,6 +1266,9 @@ impl Action {
                             direction,
                             hold_on_close,
                             hold_on_start,
+                            env: env.into_iter().collect(),
+                            restart_policy: restart.unwrap_or_default(),
+                            max_restarts,
                             ..Default::default()
                         })
                     } else {
@@ -1176,6 +1374,9 @@ impl Action {
                         direction,
                         hold_on_close,
                         hold_on_start,
+                        env: env.into_iter().collect(),
+                        restart_policy: restart.unwrap_or_default(),
+                        max_restarts,
                         ..Default::default()
                     };
                     if floating {
@@ -1359,6 +1560,40 @@ impl Action {
                 Some(id) => Ok(vec![Action::CloseTabById { id: id as u64 }]),
                 None => Ok(vec![Action::CloseTab]),
             },
+            CliAction::ReopenClosedPane => Ok(vec![Action::ReopenClosedPane]),
+            CliAction::ReopenClosedTab => Ok(vec![Action::ReopenClosedTab]),
+            CliAction::ToggleScratchpad {
+                name,
+                command,
+                cwd,
+                close_on_exit,
+                x,
+                y,
+                width,
+                height,
+                pinned,
+            } => {
+                let command = if !command.is_empty() {
+                    let current_dir = get_current_dir();
+                    let cwd = cwd.map(|cwd| current_dir.join(cwd)).or(Some(current_dir));
+                    let mut command = command.clone();
+                    let (command, args) = (PathBuf::from(command.remove(0)), command);
+                    Some(RunCommandAction {
+                        command,
+                        args,
+                        cwd,
+                        hold_on_close: !close_on_exit,
+                        ..Default::default()
+                    })
+                } else {
+                    None
+                };
+                Ok(vec![Action::ToggleScratchpad {
+                    name,
+                    command,
+                    coordinates: FloatingPaneCoordinates::new(x, y, width, height, pinned, None),
+                }])
+            },
             CliAction::GoToTab { index } => Ok(vec![Action::GoToTab { index }]),
             CliAction::GoToTabName { name, create } => {
                 Ok(vec![Action::GoToTabName { name, create }])
@@ -1387,6 +1622,7 @@ impl Action {
                 layout,
                 layout_string,
                 layout_dir,
+                layout_args,
                 cwd,
                 initial_command,
                 initial_plugin,
@@ -1396,6 +1632,7 @@ impl Action {
                 block_until_exit_failure,
                 block_until_exit,
             } => {
+                let layout_args: BTreeMap<String, String> = layout_args.into_iter().collect();
                 let current_dir = get_current_dir();
                 let cwd = cwd
                     .map(|cwd| current_dir.join(cwd))
@@ -1456,7 +1693,10 @@ impl Action {
                     let swap_layouts: Option<(String, String)> = None;
                     let should_start_layout_commands_suspended = false;
                     let raw_layout_for_error = raw_layout.clone();
-                    let mut layout = Layout::from_str(&raw_layout, path_to_raw_layout, swap_layouts.as_ref().map(|(f, p)| (f.as_str(), p.as_str())), cwd).map_err(|e| {
+                    let layout_dir = layout_dir
+                        .or_else(|| config.and_then(|c| c.options.layout_dir))
+                        .or_else(|| get_layout_dir(find_default_config_dir()));
+                    let mut layout = Layout::from_str_with_args(&raw_layout, path_to_raw_layout, swap_layouts.as_ref().map(|(f, p)| (f.as_str(), p.as_str())), cwd, &layout_args, layout_dir).map_err(|e| {
                         let stringified_error = match e {
                             ConfigError::KdlError(kdl_error) => {
                                 let error = kdl_error.add_src(layout_source_name.clone(), raw_layout_for_error);
@@ -1569,10 +1809,13 @@ impl Action {
                             .as_os_str()
                             .to_string_lossy()
                             .to_string();
-                        Layout::stringified_from_path_or_default(Some(&layout_path), layout_dir)
-                            .map_err(|e| format!("Failed to load layout: {}", e))?
+                        Layout::stringified_from_path_or_default(
+                            Some(&layout_path),
+                            layout_dir.clone(),
+                        )
+                        .map_err(|e| format!("Failed to load layout: {}", e))?
                     };
-                    let mut layout = Layout::from_str(&raw_layout, path_to_raw_layout, swap_layouts.as_ref().map(|(f, p)| (f.as_str(), p.as_str())), cwd).map_err(|e| {
+                    let mut layout = Layout::from_str_with_args(&raw_layout, path_to_raw_layout, swap_layouts.as_ref().map(|(f, p)| (f.as_str(), p.as_str())), cwd, &layout_args, layout_dir).map_err(|e| {
                         let stringified_error = match e {
                             ConfigError::KdlError(kdl_error) => {
                                 let error = kdl_error.add_src(layout_source_name.clone(), String::from(raw_layout));
@@ -1683,10 +1926,12 @@ impl Action {
                 layout,
                 layout_string,
                 layout_dir,
+                layout_args,
                 retain_existing_terminal_panes,
                 retain_existing_plugin_panes,
                 apply_only_to_active_tab,
             } => {
+                let layout_args: BTreeMap<String, String> = layout_args.into_iter().collect();
                 // Determine layout_dir: CLI arg > config > default
                 let layout_dir = layout_dir
                     .or_else(|| config.and_then(|c| c.options.layout_dir))
@@ -1720,19 +1965,24 @@ impl Action {
                             .as_os_str()
                             .to_string_lossy()
                             .to_string();
-                        Layout::stringified_from_path_or_default(Some(layout_path), layout_dir)
-                            .map_err(|e| format!("Failed to load layout: {}", e))?
+                        Layout::stringified_from_path_or_default(
+                            Some(layout_path),
+                            layout_dir.clone(),
+                        )
+                        .map_err(|e| format!("Failed to load layout: {}", e))?
                     }
                 } else {
                     return Err("Either layout or layout-string must be provided".to_string());
                 };
 
                 // Parse KDL layout
-                let layout = Layout::from_str(
+                let layout = Layout::from_str_with_args(
                     &raw_layout,
                     path_to_raw_layout,
                     swap_layouts.as_ref().map(|(f, p)| (f.as_str(), p.as_str())),
                     None, // cwd
+                    &layout_args,
+                    layout_dir,
                 )
                 .map_err(|e| {
                     let stringified_error = match e {
@@ -1959,6 +2209,31 @@ impl Action {
                     Ok(vec![Action::StackPanes { pane_ids }])
                 }
             },
+            CliAction::SetSyncGroup { pane_ids } => {
+                let mut malformed_ids = vec![];
+                let pane_ids = pane_ids
+                    .iter()
+                    .filter_map(
+                        |stringified_pane_id| match PaneId::from_str(stringified_pane_id) {
+                            Ok(pane_id) => Some(pane_id),
+                            Err(_e) => {
+                                malformed_ids.push(stringified_pane_id.to_owned());
+                                None
+                            },
+                        },
+                    )
+                    .collect();
+                if !malformed_ids.is_empty() {
+                    Err(
+                        format!(
+                            "Malformed pane ids: {}, expecting a space separated list of either a bare integer (eg. 1), a terminal pane id (eg. terminal_1) or a plugin pane id (eg. plugin_1)",
+                            malformed_ids.join(", ")
+                        )
+                    )
+                } else {
+                    Ok(vec![Action::SetSyncGroup { pane_ids }])
+                }
+            },
             CliAction::ChangeFloatingPaneCoordinates {
                 pane_id,
                 x,
@@ -2273,6 +2548,7 @@ mod tests {
     use super::*;
     use crate::data::BareKey;
     use crate::data::KeyModifier;
+    use crate::input::command::RestartPolicy;
     use std::path::PathBuf;
 
     #[test]
@@ -3447,6 +3723,7 @@ mod tests {
             layout: None,
             layout_string: Some("layout {\n    pane\n    pane\n}\n".into()),
             layout_dir: None,
+            layout_args: vec![],
             cwd: None,
             initial_command: vec![],
             initial_plugin: None,
@@ -3483,6 +3760,59 @@ mod tests {
             layout: None,
             layout_string: Some("invalid { kdl".into()),
             layout_dir: None,
+            layout_args: vec![],
+            cwd: None,
+            initial_command: vec![],
+            initial_plugin: None,
+            close_on_exit: Default::default(),
+            start_suspended: Default::default(),
+            block_until_exit: false,
+            block_until_exit_success: false,
+            block_until_exit_failure: false,
+        };
+        let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_new_tab_with_layout_args() {
+        let cli_action = CliAction::NewTab {
+            name: None,
+            layout: None,
+            layout_string: Some(
+                "layout {\n    parameters {\n        project \"web\"\n    }\n    pane name=\"${project}\"\n}\n"
+                    .into(),
+            ),
+            layout_dir: None,
+            layout_args: vec![("project".into(), "api".into())],
+            cwd: None,
+            initial_command: vec![],
+            initial_plugin: None,
+            close_on_exit: Default::default(),
+            start_suspended: Default::default(),
+            block_until_exit: false,
+            block_until_exit_success: false,
+            block_until_exit_failure: false,
+        };
+        let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
+        let actions = result.unwrap();
+        match &actions[0] {
+            Action::NewTab { tiled_layout, .. } => {
+                let layout = tiled_layout.as_ref().unwrap();
+                assert_eq!(layout.children[0].name, Some("api".to_owned()));
+            },
+            _ => panic!("Expected NewTab action"),
+        }
+    }
+
+    #[test]
+    fn test_new_tab_with_unknown_layout_arg() {
+        let cli_action = CliAction::NewTab {
+            name: None,
+            layout: None,
+            layout_string: Some("layout {\n    pane\n}\n".into()),
+            layout_dir: None,
+            layout_args: vec![("project".into(), "api".into())],
             cwd: None,
             initial_command: vec![],
             initial_plugin: None,
@@ -3502,6 +3832,7 @@ mod tests {
             layout: None,
             layout_string: Some("layout {\n    pane\n    pane\n}\n".into()),
             layout_dir: None,
+            layout_args: vec![],
             retain_existing_terminal_panes: false,
             retain_existing_plugin_panes: false,
             apply_only_to_active_tab: false,
@@ -3590,6 +3921,9 @@ mod tests {
             near_current_pane: false,
             borderless: None,
             tab_id: Some(3),
+            env: vec![],
+            restart: None,
+            max_restarts: None,
         };
         let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
         assert!(result.is_ok());
@@ -3632,6 +3966,9 @@ mod tests {
             near_current_pane: false,
             borderless: None,
             tab_id: None,
+            env: vec![],
+            restart: None,
+            max_restarts: None,
         };
         let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
         assert!(result.is_ok());
@@ -3645,6 +3982,102 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_new_pane_command_with_env() {
+        let cli_action = CliAction::NewPane {
+            direction: None,
+            command: vec!["cargo".to_owned(), "run".to_owned()],
+            plugin: None,
+            cwd: None,
+            floating: false,
+            in_place: false,
+            close_replaced_pane: false,
+            name: None,
+            close_on_exit: false,
+            start_suspended: false,
+            configuration: None,
+            skip_plugin_cache: false,
+            x: None,
+            y: None,
+            width: None,
+            height: None,
+            pinned: None,
+            stacked: false,
+            blocking: false,
+            block_until_exit_success: false,
+            block_until_exit_failure: false,
+            block_until_exit: false,
+            unblock_condition: None,
+            near_current_pane: false,
+            borderless: None,
+            tab_id: None,
+            env: vec![("RUST_LOG".to_owned(), "debug".to_owned())],
+            restart: None,
+            max_restarts: None,
+        };
+        let actions =
+            Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None).unwrap();
+        match &actions[0] {
+            Action::NewTiledPane {
+                command: Some(run_command_action),
+                ..
+            } => {
+                assert_eq!(
+                    run_command_action.env,
+                    BTreeMap::from([("RUST_LOG".to_owned(), "debug".to_owned())])
+                );
+            },
+            _ => panic!("Expected NewTiledPane action with a command"),
+        }
+    }
+
+    #[test]
+    fn test_new_pane_command_with_restart_policy() {
+        let cli_action = CliAction::NewPane {
+            direction: None,
+            command: vec!["my-server".to_owned()],
+            plugin: None,
+            cwd: None,
+            floating: true,
+            in_place: false,
+            close_replaced_pane: false,
+            name: None,
+            close_on_exit: false,
+            start_suspended: false,
+            configuration: None,
+            skip_plugin_cache: false,
+            x: None,
+            y: None,
+            width: None,
+            height: None,
+            pinned: None,
+            stacked: false,
+            blocking: false,
+            block_until_exit_success: false,
+            block_until_exit_failure: false,
+            block_until_exit: false,
+            unblock_condition: None,
+            near_current_pane: false,
+            borderless: None,
+            tab_id: None,
+            env: vec![],
+            restart: Some(RestartPolicy::OnFailure),
+            max_restarts: Some(5),
+        };
+        let actions =
+            Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None).unwrap();
+        match &actions[0] {
+            Action::NewFloatingPane {
+                command: Some(run_command_action),
+                ..
+            } => {
+                assert_eq!(run_command_action.restart_policy, RestartPolicy::OnFailure);
+                assert_eq!(run_command_action.max_restarts, Some(5));
+            },
+            _ => panic!("Expected NewFloatingPane action with a command"),
+        }
+    }
+
     #[test]
     fn test_new_pane_floating_with_tab_id() {
         let cli_action = CliAction::NewPane {
@@ -3674,6 +4107,9 @@ mod tests {
             near_current_pane: false,
             borderless: None,
             tab_id: Some(5),
+            env: vec![],
+            restart: None,
+            max_restarts: None,
         };
         let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
         assert!(result.is_ok());
@@ -3716,6 +4152,9 @@ mod tests {
             near_current_pane: false,
             borderless: None,
             tab_id: Some(1),
+            env: vec![],
+            restart: None,
+            max_restarts: None,
         };
         let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
         assert!(result.is_ok());
@@ -3758,6 +4197,9 @@ mod tests {
             near_current_pane: false,
             borderless: None,
             tab_id: Some(2),
+            env: vec![],
+            restart: None,
+            max_restarts: None,
         };
         let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
         assert!(result.is_ok());
@@ -3862,6 +4304,9 @@ mod tests {
             near_current_pane: false,
             borderless: None,
             tab_id: Some(2),
+            env: vec![],
+            restart: None,
+            max_restarts: None,
         };
         let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
         assert!(result.is_ok());
@@ -3904,6 +4349,9 @@ mod tests {
             near_current_pane: false,
             borderless: None,
             tab_id: Some(1),
+            env: vec![],
+            restart: None,
+            max_restarts: None,
         };
         let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
         assert!(result.is_ok());
@@ -3916,4 +4364,177 @@ mod tests {
             _ => panic!("Expected NewFloatingPluginPane action"),
         }
     }
+
+    #[test]
+    fn test_search_toggle_option_regex() {
+        let cli_action = CliAction::SearchToggleOption {
+            option: SearchOption::Regex,
+        };
+        let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
+        assert!(result.is_ok());
+        let actions = result.unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            actions[0],
+            Action::SearchToggleOption {
+                option: SearchOption::Regex
+            }
+        );
+    }
+
+    #[test]
+    fn test_dump_command_output_with_pane_id_and_index() {
+        let cli_action = CliAction::DumpCommandOutput {
+            path: Some(PathBuf::from("/tmp/output")),
+            pane_id: Some("terminal_3".to_string()),
+            index: 2,
+        };
+        let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
+        assert!(result.is_ok());
+        let actions = result.unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(
+            actions[0],
+            Action::DumpCommandOutput {
+                file_path: Some("/tmp/output".to_string()),
+                pane_id: Some(PaneId::Terminal(3)),
+                index: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_start_recording_resolves_relative_output_path() {
+        let cli_action = CliAction::StartRecording {
+            output: PathBuf::from("recordings/demo.cast"),
+            pane_id: Some("terminal_3".to_string()),
+            tab: false,
+        };
+        let result =
+            Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/home/user")), None);
+        assert_eq!(
+            result.unwrap(),
+            vec![Action::StartRecording {
+                output: "/home/user/recordings/demo.cast".to_string(),
+                pane_id: Some(PaneId::Terminal(3)),
+                whole_tab: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_stop_recording_whole_tab() {
+        let cli_action = CliAction::StopRecording {
+            pane_id: None,
+            tab: true,
+        };
+        let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
+        assert_eq!(
+            result.unwrap(),
+            vec![Action::StopRecording {
+                pane_id: None,
+                whole_tab: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_dump_command_output_with_malformed_pane_id() {
+        let cli_action = CliAction::DumpCommandOutput {
+            path: None,
+            pane_id: Some("not_a_pane".to_string()),
+            index: 0,
+        };
+        let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_set_sync_group() {
+        let cli_action = CliAction::SetSyncGroup {
+            pane_ids: vec![
+                "terminal_1".to_string(),
+                "plugin_2".to_string(),
+                "3".to_string(),
+            ],
+        };
+        let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
+        assert_eq!(
+            result.unwrap(),
+            vec![Action::SetSyncGroup {
+                pane_ids: vec![PaneId::Terminal(1), PaneId::Plugin(2), PaneId::Terminal(3)],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_set_sync_group_with_malformed_pane_id() {
+        let cli_action = CliAction::SetSyncGroup {
+            pane_ids: vec!["terminal_1".to_string(), "not_a_pane".to_string()],
+        };
+        let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_toggle_scratchpad_with_command() {
+        let cli_action = CliAction::ToggleScratchpad {
+            name: "notes".to_string(),
+            command: vec!["nvim".to_string(), "notes.md".to_string()],
+            cwd: Some(PathBuf::from("notes")),
+            close_on_exit: false,
+            x: None,
+            y: None,
+            width: Some("80%".to_string()),
+            height: None,
+            pinned: None,
+        };
+        let result =
+            Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/home/user")), None);
+        assert_eq!(
+            result.unwrap(),
+            vec![Action::ToggleScratchpad {
+                name: "notes".to_string(),
+                command: Some(RunCommandAction {
+                    command: PathBuf::from("nvim"),
+                    args: vec!["notes.md".to_string()],
+                    cwd: Some(PathBuf::from("/home/user/notes")),
+                    hold_on_close: true,
+                    ..Default::default()
+                }),
+                coordinates: FloatingPaneCoordinates::new(
+                    None,
+                    None,
+                    Some("80%".to_string()),
+                    None,
+                    None,
+                    None,
+                ),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_toggle_scratchpad_without_command() {
+        let cli_action = CliAction::ToggleScratchpad {
+            name: "shell".to_string(),
+            command: vec![],
+            cwd: None,
+            close_on_exit: false,
+            x: None,
+            y: None,
+            width: None,
+            height: None,
+            pinned: None,
+        };
+        let result = Action::actions_from_cli(cli_action, Box::new(|| PathBuf::from("/tmp")), None);
+        assert_eq!(
+            result.unwrap(),
+            vec![Action::ToggleScratchpad {
+                name: "shell".to_string(),
+                command: None,
+                coordinates: None,
+            }]
+        );
+    }
 }
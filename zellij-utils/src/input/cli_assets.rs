@@ -7,6 +7,7 @@ use crate::{
     setup::get_default_themes,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
@@ -22,6 +23,7 @@ pub struct CliAssets {
     pub max_panes: Option<usize>,
     pub force_run_layout_commands: bool,
     pub cwd: Option<PathBuf>,
+    pub layout_args: BTreeMap<String, String>,
 }
 
 impl CliAssets {
@@ -52,8 +54,13 @@ impl CliAssets {
                         .map(|dir| dir.join("layouts"))
                 });
             self.layout.as_ref().and_then(|layout_info| {
-                Layout::from_layout_info_with_config(&layout_dir, layout_info, Some(config.clone()))
-                    .ok()
+                Layout::from_layout_info_with_config(
+                    &layout_dir,
+                    layout_info,
+                    Some(config.clone()),
+                    &self.layout_args,
+                )
+                .ok()
             })
         }
         .map(|(layout, config)| (layout, config))
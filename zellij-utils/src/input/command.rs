@@ -1,7 +1,11 @@
 //! Trigger a command
 use crate::data::{Direction, OriginatingPlugin};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub enum TerminalAction {
@@ -72,10 +76,19 @@ pub struct RunCommand {
     pub originating_plugin: Option<OriginatingPlugin>,
     #[serde(default)]
     pub use_terminal_title: bool,
+    /// Environment variables set for the command on top of the ones Zellij was started with
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Whether the command should be automatically re-run after it exits
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    /// How many times in a row the command will be automatically re-run (None is unlimited)
+    #[serde(default)]
+    pub max_restarts: Option<usize>,
 }
 
-impl std::fmt::Display for RunCommand {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl fmt::Display for RunCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut command: String = self
             .command
             .as_path()
@@ -109,6 +122,12 @@ pub struct RunCommandAction {
     pub originating_plugin: Option<OriginatingPlugin>,
     #[serde(default)]
     pub use_terminal_title: bool,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    #[serde(default)]
+    pub max_restarts: Option<usize>,
 }
 
 impl From<RunCommandAction> for RunCommand {
@@ -121,6 +140,9 @@ impl From<RunCommandAction> for RunCommand {
             hold_on_start: action.hold_on_start,
             originating_plugin: action.originating_plugin,
             use_terminal_title: action.use_terminal_title,
+            env: action.env,
+            restart_policy: action.restart_policy,
+            max_restarts: action.max_restarts,
         }
     }
 }
@@ -136,6 +158,9 @@ impl From<RunCommand> for RunCommandAction {
             hold_on_start: run_command.hold_on_start,
             originating_plugin: run_command.originating_plugin,
             use_terminal_title: run_command.use_terminal_title,
+            env: run_command.env,
+            restart_policy: run_command.restart_policy,
+            max_restarts: run_command.max_restarts,
         }
     }
 }
@@ -168,4 +193,84 @@ impl RunCommand {
         self.cwd = Some(cwd);
         self
     }
+    /// Whether the pane of this command should be held open after the command exits (rather
+    /// than closed), either to be re-run by the user or by its restart policy
+    pub fn holds_pane_on_exit(&self) -> bool {
+        self.hold_on_close || self.restart_policy != RestartPolicy::Never
+    }
+    /// The attempt number of the next automatic restart, if the command should be restarted
+    /// after exiting with `exit_status` having already been restarted `restart_count` times
+    pub fn next_restart(&self, exit_status: Option<i32>, restart_count: usize) -> Option<usize> {
+        let within_max_restarts = self
+            .max_restarts
+            .map(|max_restarts| restart_count < max_restarts)
+            .unwrap_or(true);
+        if self.restart_policy.should_restart(exit_status) && within_max_restarts {
+            Some(restart_count + 1)
+        } else {
+            None
+        }
+    }
+}
+
+const INITIAL_RESTART_DELAY: Duration = Duration::from_secs(1);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+// a command that ran at least this long before exiting is considered to have been stable, so its
+// next restart starts a fresh series of retries and backoff
+pub const RESTART_STABILITY_THRESHOLD: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+impl RestartPolicy {
+    pub fn should_restart(&self, exit_status: Option<i32>) -> bool {
+        match self {
+            RestartPolicy::Never => false,
+            // a command killed by a signal has no exit code, we consider it a failure
+            RestartPolicy::OnFailure => exit_status != Some(0),
+            RestartPolicy::Always => true,
+        }
+    }
+    /// Whether a command that exited with `exit_status` after running for `run_time` has
+    /// recovered, in which case its restarts are counted from zero again
+    pub fn resets_restart_count(exit_status: Option<i32>, run_time: Duration) -> bool {
+        exit_status == Some(0) || run_time >= RESTART_STABILITY_THRESHOLD
+    }
+    /// Exponential backoff before each automatic restart (attempts start at 1)
+    pub fn restart_delay(attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16) as u32;
+        INITIAL_RESTART_DELAY
+            .saturating_mul(1 << exponent)
+            .min(MAX_RESTART_DELAY)
+    }
+}
+
+impl FromStr for RestartPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(RestartPolicy::Never),
+            "on-failure" => Ok(RestartPolicy::OnFailure),
+            "always" => Ok(RestartPolicy::Always),
+            _ => Err(format!(
+                "invalid restart policy: '{}', expected one of: never, on-failure, always",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestartPolicy::Never => write!(f, "never"),
+            RestartPolicy::OnFailure => write!(f, "on-failure"),
+            RestartPolicy::Always => write!(f, "always"),
+        }
+    }
 }
@@ -5,7 +5,7 @@ use crate::data::{LayoutInfo, LayoutWithError};
 
 use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::PathBuf;
@@ -20,6 +20,7 @@ use super::plugins::{PluginAliases, PluginsConfigError};
 use super::theme::{Themes, UiConfig};
 use super::web_client::WebClientConfig;
 use crate::cli::{CliArgs, Command};
+use crate::data::PermissionType;
 use crate::envs::EnvironmentVariables;
 use crate::{home, setup};
 
@@ -38,6 +39,8 @@ pub struct Config {
     pub env: EnvironmentVariables,
     pub background_plugins: HashSet<RunPluginOrAlias>,
     pub web_client: WebClientConfig,
+    /// Permissions granted up-front to plugins (by url), without prompting the user
+    pub plugin_permissions: BTreeMap<String, Vec<PermissionType>>,
 }
 
 #[derive(Error, Debug, Serialize, Deserialize)]
@@ -1332,4 +1335,53 @@ mod config_test {
             "Env variables defined in config"
         );
     }
+
+    #[test]
+    fn can_define_plugin_permissions_in_config_file() {
+        let config_contents = r#"
+            plugin_permissions {
+                "file:/path/to/my-plugin.wasm" {
+                    ReadApplicationState
+                    RunCommands
+                }
+                "https://example.com/my-plugin.wasm" {
+                    WebAccess
+                }
+            }
+        "#;
+        let config = Config::from_kdl(config_contents, None).unwrap();
+        let mut expected_plugin_permissions = BTreeMap::new();
+        expected_plugin_permissions.insert(
+            "file:/path/to/my-plugin.wasm".to_owned(),
+            vec![
+                PermissionType::ReadApplicationState,
+                PermissionType::RunCommands,
+            ],
+        );
+        expected_plugin_permissions.insert(
+            "https://example.com/my-plugin.wasm".to_owned(),
+            vec![PermissionType::WebAccess],
+        );
+        assert_eq!(
+            config.plugin_permissions, expected_plugin_permissions,
+            "Plugin permissions defined in config"
+        );
+        let serialized_config = Config::from_kdl(&config.to_string(false), None).unwrap();
+        assert_eq!(
+            serialized_config.plugin_permissions, expected_plugin_permissions,
+            "Plugin permissions survive serialization"
+        );
+    }
+
+    #[test]
+    fn unknown_plugin_permission_in_config_file_errors() {
+        let config_contents = r#"
+            plugin_permissions {
+                "zellij:my-plugin" {
+                    DoAnything
+                }
+            }
+        "#;
+        assert!(Config::from_kdl(config_contents, None).is_err());
+    }
 }
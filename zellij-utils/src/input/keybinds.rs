@@ -1,14 +1,32 @@
 use std::collections::{BTreeMap, HashMap};
 
 use super::actions::Action;
-use crate::data::{BareKey, InputMode, KeyWithModifier, KeybindsVec};
+use crate::data::{
+    BareKey, CustomInputMode, CustomModeFallThrough, InputMode, KeySequencesVec, KeyWithModifier,
+    KeybindsVec,
+};
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+pub type ModeKeybinds = HashMap<KeyWithModifier, Vec<Action>>;
+pub type ModeKeySequences = BTreeMap<Vec<KeyWithModifier>, Vec<Action>>;
+
 /// Used in the config struct
 #[derive(Clone, PartialEq, Deserialize, Serialize, Default)]
-pub struct Keybinds(pub HashMap<InputMode, HashMap<KeyWithModifier, Vec<Action>>>);
+pub struct Keybinds(
+    pub HashMap<InputMode, ModeKeybinds>,
+    pub HashMap<InputMode, ModeKeySequences>, // multi-key sequences
+    pub BTreeMap<CustomInputMode, CustomModeFallThrough>, // user defined modes
+);
+
+/// A key that was pressed as part of a multi-key sequence that has not (yet) been completed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingKey {
+    pub key_with_modifier: KeyWithModifier,
+    pub raw_bytes: Vec<u8>,
+    pub is_kitty_keyboard_protocol: bool,
+}
 
 impl fmt::Debug for Keybinds {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -20,7 +38,19 @@ impl fmt::Debug for Keybinds {
             }
             stable_sorted.insert(mode, stable_sorted_mode_keybinds);
         }
-        write!(f, "{:#?}", stable_sorted)
+        write!(f, "{:#?}", stable_sorted)?;
+        let stable_sorted_sequences: BTreeMap<_, _> = self
+            .1
+            .iter()
+            .filter(|(_mode, key_sequences)| !key_sequences.is_empty())
+            .collect();
+        if !stable_sorted_sequences.is_empty() {
+            write!(f, "\nkey sequences: {:#?}", stable_sorted_sequences)?;
+        }
+        if !self.2.is_empty() {
+            write!(f, "\ncustom modes: {:#?}", self.2)?;
+        }
+        Ok(())
     }
 }
 
@@ -61,12 +91,140 @@ impl Keybinds {
                 )]
             })
     }
+    /// Resolves a key press against the multi-key sequences bound in this mode.
+    ///
+    /// Keys that are a prefix of a longer sequence are accumulated in `pending_keys` and produce
+    /// no actions until the sequence is either completed or broken. When it is broken, the keys
+    /// pressed so far fall through as if they were pressed on their own (eg. are sent to the pane).
+    pub fn get_actions_for_key_sequence_in_mode_or_default_action(
+        &self,
+        mode: &InputMode,
+        pending_keys: &mut Vec<PendingKey>,
+        key_with_modifier: &KeyWithModifier,
+        raw_bytes: Vec<u8>,
+        default_input_mode: InputMode,
+        key_is_kitty_protocol: bool,
+    ) -> Vec<Action> {
+        let key_sequences = match self.1.get(mode).filter(|s| !s.is_empty()) {
+            Some(key_sequences) => key_sequences,
+            None => {
+                // the mode might have changed in the middle of a sequence
+                let mut actions = self.flush_pending_keys(mode, pending_keys, default_input_mode);
+                let mode = mode_after_actions(*mode, &actions);
+                actions.append(&mut self.get_actions_for_key_in_mode_or_default_action(
+                    &mode,
+                    key_with_modifier,
+                    raw_bytes,
+                    default_input_mode,
+                    key_is_kitty_protocol,
+                ));
+                return actions;
+            },
+        };
+        pending_keys.push(PendingKey {
+            key_with_modifier: key_with_modifier.clone(),
+            raw_bytes: raw_bytes.clone(),
+            is_kitty_keyboard_protocol: key_is_kitty_protocol,
+        });
+        let keys: Vec<KeyWithModifier> = pending_keys
+            .iter()
+            .map(|k| k.key_with_modifier.clone())
+            .collect();
+        let is_prefix_of_longer_sequence = key_sequences
+            .keys()
+            .any(|sequence| sequence.len() > keys.len() && sequence.starts_with(&keys));
+        if is_prefix_of_longer_sequence {
+            return vec![];
+        }
+        if let Some(actions) = key_sequences.get(&keys) {
+            pending_keys.clear();
+            return actions.clone();
+        }
+        pending_keys.pop();
+        if pending_keys.is_empty() {
+            self.get_actions_for_key_in_mode_or_default_action(
+                mode,
+                key_with_modifier,
+                raw_bytes,
+                default_input_mode,
+                key_is_kitty_protocol,
+            )
+        } else {
+            // the sequence was broken, the last key might be the start of a new one
+            let mut actions = self.flush_pending_keys(mode, pending_keys, default_input_mode);
+            let mode = mode_after_actions(*mode, &actions);
+            actions.append(
+                &mut self.get_actions_for_key_sequence_in_mode_or_default_action(
+                    &mode,
+                    pending_keys,
+                    key_with_modifier,
+                    raw_bytes,
+                    default_input_mode,
+                    key_is_kitty_protocol,
+                ),
+            );
+            actions
+        }
+    }
+    /// Resolves the keys of an unfinished sequence (eg. after it timed out): if they form a
+    /// complete sequence on their own its actions are returned, otherwise each key is handled as
+    /// if it was pressed separately, in the mode the keys before it left us in
+    pub fn flush_pending_keys(
+        &self,
+        mode: &InputMode,
+        pending_keys: &mut Vec<PendingKey>,
+        default_input_mode: InputMode,
+    ) -> Vec<Action> {
+        if pending_keys.is_empty() {
+            return vec![];
+        }
+        let keys: Vec<KeyWithModifier> = pending_keys
+            .iter()
+            .map(|k| k.key_with_modifier.clone())
+            .collect();
+        let actions = match self.1.get(mode).and_then(|s| s.get(&keys)) {
+            Some(actions) => actions.clone(),
+            None => {
+                let mut mode = *mode;
+                let mut actions = vec![];
+                for pending_key in pending_keys.iter() {
+                    let key_actions = self.get_actions_for_key_in_mode_or_default_action(
+                        &mode,
+                        &pending_key.key_with_modifier,
+                        pending_key.raw_bytes.clone(),
+                        default_input_mode,
+                        pending_key.is_kitty_keyboard_protocol,
+                    );
+                    mode = mode_after_actions(mode, &key_actions);
+                    actions.extend(key_actions);
+                }
+                actions
+            },
+        };
+        pending_keys.clear();
+        actions
+    }
     pub fn get_input_mode_mut(
         &mut self,
         input_mode: &InputMode,
     ) -> &mut HashMap<KeyWithModifier, Vec<Action>> {
         self.0.entry(*input_mode).or_insert_with(HashMap::new)
     }
+    pub fn get_input_mode_and_key_sequences_mut(
+        &mut self,
+        input_mode: &InputMode,
+    ) -> (&mut ModeKeybinds, &mut ModeKeySequences) {
+        (
+            self.0.entry(*input_mode).or_default(),
+            self.1.entry(*input_mode).or_default(),
+        )
+    }
+    pub fn get_input_mode_key_sequences_mut(
+        &mut self,
+        input_mode: &InputMode,
+    ) -> &mut ModeKeySequences {
+        self.1.entry(*input_mode).or_default()
+    }
     pub fn default_action_for_mode(
         &self,
         mode: &InputMode,
@@ -89,6 +247,14 @@ impl Keybinds {
             InputMode::RenameTab => Action::TabNameInput { input: raw_bytes },
             InputMode::RenamePane => Action::PaneNameInput { input: raw_bytes },
             InputMode::EnterSearch => Action::SearchInput { input: raw_bytes },
+            InputMode::Custom(custom_input_mode) => match self.2.get(&custom_input_mode) {
+                Some(CustomModeFallThrough::Pane) => Action::Write {
+                    key_with_modifier: key_with_modifier.cloned(),
+                    bytes: raw_bytes,
+                    is_kitty_keyboard_protocol: key_is_kitty_protocol,
+                },
+                Some(CustomModeFallThrough::Swallow) | None => Action::NoOp,
+            },
             _ => Action::NoOp,
         }
     }
@@ -103,6 +269,23 @@ impl Keybinds {
         }
         ret
     }
+    pub fn to_key_sequences_vec(&self) -> KeySequencesVec {
+        let mut ret = vec![];
+        for (mode, mode_sequences) in &self.1 {
+            if mode_sequences.is_empty() {
+                continue;
+            }
+            let mode_sequences_vec: Vec<(Vec<KeyWithModifier>, Vec<Action>)> = mode_sequences
+                .iter()
+                .map(|(keys, actions)| (keys.clone(), actions.clone()))
+                .collect();
+            ret.push((*mode, mode_sequences_vec))
+        }
+        ret
+    }
+    pub fn custom_modes(&self) -> Vec<CustomInputMode> {
+        self.2.keys().copied().collect()
+    }
     pub fn merge(&mut self, mut other: Keybinds) {
         for (other_input_mode, mut other_input_mode_keybinds) in other.0.drain() {
             let input_mode_keybinds = self
@@ -113,6 +296,13 @@ impl Keybinds {
                 input_mode_keybinds.insert(other_action, other_action_keybinds);
             }
         }
+        for (other_input_mode, other_input_mode_sequences) in other.1.drain() {
+            self.1
+                .entry(other_input_mode)
+                .or_default()
+                .extend(other_input_mode_sequences);
+        }
+        self.2.append(&mut other.2);
     }
 }
 
@@ -136,6 +326,20 @@ fn handle_ctrl_j(
     }
 }
 
+// the mode we are in once these actions are applied, so that the keys that follow them are
+// resolved in it
+fn mode_after_actions(mode: InputMode, actions: &[Action]) -> InputMode {
+    actions
+        .iter()
+        .rev()
+        .find_map(|action| match action {
+            Action::SwitchToMode { input_mode }
+            | Action::SwitchModeForAllClients { input_mode } => Some(*input_mode),
+            _ => None,
+        })
+        .unwrap_or(mode)
+}
+
 // The unit test location.
 #[cfg(test)]
 #[path = "./unit/keybinds_test.rs"]
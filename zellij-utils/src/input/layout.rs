@@ -14,10 +14,10 @@ use crate::{
     data::{Direction, LayoutInfo, LayoutMetadata, LayoutParsingError, LayoutWithError},
     home::{default_layout_dir, find_default_config_dir},
     input::{
-        command::RunCommand,
+        command::{RestartPolicy, RunCommand},
         config::{Config, ConfigError},
     },
-    pane_size::{Constraint, Dimension, PaneGeom},
+    pane_size::{Constraint, Dimension, PaneGeom, Size},
     setup::{self},
 };
 
@@ -277,6 +277,18 @@ impl Run {
                 if merged.args.is_empty() && !base_run_command.args.is_empty() {
                     merged.args = base_run_command.args.clone();
                 }
+                for (key, value) in &base_run_command.env {
+                    merged
+                        .env
+                        .entry(key.clone())
+                        .or_insert_with(|| value.clone());
+                }
+                if merged.restart_policy == RestartPolicy::Never {
+                    merged.restart_policy = base_run_command.restart_policy;
+                }
+                if merged.max_restarts.is_none() {
+                    merged.max_restarts = base_run_command.max_restarts;
+                }
                 Some(Run::Command(merged))
             },
             (Some(Run::Command(base_run_command)), Some(Run::Cwd(other_cwd))) => {
@@ -360,6 +372,29 @@ impl Run {
             }
         }
     }
+    pub fn add_env(&mut self, env: Option<BTreeMap<String, String>>) {
+        // adds to (and overrides) the env of a Run::Command if it is Some
+        if let Some(env) = env {
+            if let Run::Command(run_command) = self {
+                run_command.env.extend(env);
+            }
+        }
+    }
+    pub fn add_restart_policy(
+        &mut self,
+        restart_policy: Option<RestartPolicy>,
+        max_restarts: Option<usize>,
+    ) {
+        // overrides the restart policy and/or max restarts of a Run::Command if they are Some
+        if let Run::Command(run_command) = self {
+            if let Some(restart_policy) = restart_policy {
+                run_command.restart_policy = restart_policy;
+            }
+            if let Some(max_restarts) = max_restarts {
+                run_command.max_restarts = Some(max_restarts);
+            }
+        }
+    }
     pub fn add_close_on_exit(&mut self, close_on_exit: Option<bool>) {
         // overrides the hold_on_close of a Run::Command if it is Some
         // and not empty
@@ -669,15 +704,84 @@ impl fmt::Display for RunPluginLocation {
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum LayoutConstraint {
+    // constraints are attempted in this order, so the more specific ones come first
+    Combined(Vec<LayoutConstraint>), // all of these need to be met
+    MinColumns(usize),
+    MaxColumns(usize),
+    MinRows(usize),
+    MaxRows(usize),
+    MinAspectRatio(usize), // columns divided by rows, in hundredths (eg. 250 is 2.5)
+    MaxAspectRatio(usize), // columns divided by rows, in hundredths (eg. 250 is 2.5)
     MaxPanes(usize),
     MinPanes(usize),
     ExactPanes(usize),
     NoConstraint,
 }
 
+impl LayoutConstraint {
+    /// Whether the size based parts of this constraint are met by the given display area,
+    /// pane count constraints are not considered here
+    pub fn fits_display_area(&self, display_area: &Size) -> bool {
+        let aspect_ratio = display_area.cols * 100 / std::cmp::max(display_area.rows, 1);
+        match self {
+            LayoutConstraint::Combined(constraints) => constraints
+                .iter()
+                .all(|c| c.fits_display_area(display_area)),
+            LayoutConstraint::MinColumns(min_columns) => display_area.cols >= *min_columns,
+            LayoutConstraint::MaxColumns(max_columns) => display_area.cols <= *max_columns,
+            LayoutConstraint::MinRows(min_rows) => display_area.rows >= *min_rows,
+            LayoutConstraint::MaxRows(max_rows) => display_area.rows <= *max_rows,
+            LayoutConstraint::MinAspectRatio(min_aspect_ratio) => aspect_ratio >= *min_aspect_ratio,
+            LayoutConstraint::MaxAspectRatio(max_aspect_ratio) => aspect_ratio <= *max_aspect_ratio,
+            LayoutConstraint::MaxPanes(_)
+            | LayoutConstraint::MinPanes(_)
+            | LayoutConstraint::ExactPanes(_)
+            | LayoutConstraint::NoConstraint => true,
+        }
+    }
+    /// Whether the pane count parts of this constraint are met by the given amount of panes,
+    /// size constraints are not considered here
+    pub fn fits_pane_count(&self, pane_count: usize) -> bool {
+        match self {
+            LayoutConstraint::Combined(constraints) => {
+                constraints.iter().all(|c| c.fits_pane_count(pane_count))
+            },
+            LayoutConstraint::MaxPanes(max_panes) => pane_count <= *max_panes,
+            LayoutConstraint::MinPanes(min_panes) => pane_count >= *min_panes,
+            LayoutConstraint::ExactPanes(exact_panes) => pane_count == *exact_panes,
+            LayoutConstraint::MinColumns(_)
+            | LayoutConstraint::MaxColumns(_)
+            | LayoutConstraint::MinRows(_)
+            | LayoutConstraint::MaxRows(_)
+            | LayoutConstraint::MinAspectRatio(_)
+            | LayoutConstraint::MaxAspectRatio(_)
+            | LayoutConstraint::NoConstraint => true,
+        }
+    }
+}
+
 impl Display for LayoutConstraint {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
+            LayoutConstraint::Combined(constraints) => write!(
+                f,
+                "{}",
+                constraints
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            LayoutConstraint::MinColumns(min_columns) => write!(f, "min_columns={}", min_columns),
+            LayoutConstraint::MaxColumns(max_columns) => write!(f, "max_columns={}", max_columns),
+            LayoutConstraint::MinRows(min_rows) => write!(f, "min_rows={}", min_rows),
+            LayoutConstraint::MaxRows(max_rows) => write!(f, "max_rows={}", max_rows),
+            LayoutConstraint::MinAspectRatio(min_aspect_ratio) => {
+                write!(f, "min_aspect_ratio={}", *min_aspect_ratio as f64 / 100.0)
+            },
+            LayoutConstraint::MaxAspectRatio(max_aspect_ratio) => {
+                write!(f, "max_aspect_ratio={}", *max_aspect_ratio as f64 / 100.0)
+            },
             LayoutConstraint::MaxPanes(max_panes) => write!(f, "max_panes={}", max_panes),
             LayoutConstraint::MinPanes(min_panes) => write!(f, "min_panes={}", min_panes),
             LayoutConstraint::ExactPanes(exact_panes) => write!(f, "exact_panes={}", exact_panes),
@@ -793,9 +897,11 @@ pub struct FloatingPaneLayout {
     pub focus: Option<bool>,
     pub already_running: bool,
     pub pane_initial_contents: Option<String>,
+    pub pane_scrollback_file: Option<PathBuf>,
     pub logical_position: Option<usize>,
     pub default_fg: Option<String>,
     pub default_bg: Option<String>,
+    pub scratchpad: Option<String>, // scratchpads start hidden until they are toggled
 }
 
 impl FloatingPaneLayout {
@@ -812,9 +918,11 @@ impl FloatingPaneLayout {
             focus: None,
             already_running: false,
             pane_initial_contents: None,
+            pane_scrollback_file: None,
             logical_position: None,
             default_fg: None,
             default_bg: None,
+            scratchpad: None,
         }
     }
     pub fn add_cwd_to_layout(&mut self, cwd: &PathBuf) {
@@ -859,6 +967,7 @@ pub struct TiledPaneLayout {
     pub run_instructions_to_ignore: Vec<Option<Run>>,
     pub hide_floating_panes: bool, // only relevant if this is the base layout
     pub pane_initial_contents: Option<String>,
+    pub pane_scrollback_file: Option<PathBuf>,
     pub default_fg: Option<String>,
     pub default_bg: Option<String>,
 }
@@ -1260,8 +1369,16 @@ impl Layout {
                                 .map(|f| f.to_string())
                                 .unwrap_or_default();
 
-                            match Layout::from_path_or_default_without_config(
-                                Some(&file.path()),
+                            // shared template files are imported by other layouts, they are
+                            // not layouts in their own right
+                            if std::fs::read_to_string(file.path())
+                                .map(|raw| Layout::is_template_library(&raw))
+                                .unwrap_or(false)
+                            {
+                                continue;
+                            }
+                            match Layout::from_path_for_listing(
+                                &file.path(),
                                 Some(layout_dir.clone()),
                             ) {
                                 Ok(_layout) => {
@@ -1326,6 +1443,7 @@ impl Layout {
     pub fn from_layout_info(
         layout_dir: &Option<PathBuf>,
         layout_info: LayoutInfo,
+        layout_args: &BTreeMap<String, String>,
     ) -> Result<Layout, ConfigError> {
         let mut should_start_layout_commands_suspended = false;
         let (path_to_raw_layout, raw_layout, raw_swap_layouts) = match layout_info {
@@ -1349,13 +1467,15 @@ impl Layout {
             },
             LayoutInfo::Stringified(stringified_layout) => (None, stringified_layout, None),
         };
-        let mut layout = Layout::from_kdl(
+        let mut layout = Layout::from_kdl_with_args(
             &raw_layout,
             path_to_raw_layout,
             raw_swap_layouts
                 .as_ref()
                 .map(|(r, f)| (r.as_str(), f.as_str())),
             None,
+            layout_args,
+            layout_dir.clone(),
         );
         if should_start_layout_commands_suspended {
             layout
@@ -1369,6 +1489,7 @@ impl Layout {
         layout_dir: &Option<PathBuf>,
         layout_info: &LayoutInfo,
         config: Option<Config>,
+        layout_args: &BTreeMap<String, String>,
     ) -> Result<(Layout, Config), ConfigError> {
         let mut should_start_layout_commands_suspended = false;
         let (path_to_raw_layout, raw_layout, raw_swap_layouts) = match layout_info {
@@ -1392,13 +1513,15 @@ impl Layout {
             },
             LayoutInfo::Stringified(stringified_layout) => (None, stringified_layout.clone(), None),
         };
-        let mut layout = Layout::from_kdl(
+        let mut layout = Layout::from_kdl_with_args(
             &raw_layout,
             path_to_raw_layout,
             raw_swap_layouts
                 .as_ref()
                 .map(|(r, f)| (r.as_str(), f.as_str())),
             None,
+            layout_args,
+            layout_dir.clone(),
         );
         if should_start_layout_commands_suspended {
             layout
@@ -1461,25 +1584,39 @@ impl Layout {
         layout_path: Option<&PathBuf>,
         layout_dir: Option<PathBuf>,
         config: Config,
+        layout_args: &BTreeMap<String, String>,
     ) -> Result<(Layout, Config), ConfigError> {
         let (path_to_raw_layout, raw_layout, raw_swap_layouts) =
-            Layout::stringified_from_path_or_default(layout_path, layout_dir)?;
-        let layout = Layout::from_kdl(
+            Layout::stringified_from_path_or_default(layout_path, layout_dir.clone())?;
+        let layout = Layout::from_kdl_with_args(
             &raw_layout,
             Some(path_to_raw_layout),
             raw_swap_layouts
                 .as_ref()
                 .map(|(r, f)| (r.as_str(), f.as_str())),
             None,
+            layout_args,
+            layout_dir,
         )?;
         let config = Config::from_kdl(&raw_layout, Some(config))?; // this merges the two config, with
         Ok((layout, config))
     }
     #[cfg(not(target_family = "wasm"))]
-    pub fn from_url(url: &str, config: Config) -> Result<(Layout, Config), ConfigError> {
+    pub fn from_url(
+        url: &str,
+        config: Config,
+        layout_args: &BTreeMap<String, String>,
+    ) -> Result<(Layout, Config), ConfigError> {
         let raw_layout = Downloader::download_without_cache_blocking(url)
             .map_err(|e| ConfigError::DownloadError(format!("{}", e)))?;
-        let mut layout = Layout::from_kdl(&raw_layout, Some(url.into()), None, None)?;
+        let mut layout = Layout::from_kdl_with_args(
+            &raw_layout,
+            Some(url.into()),
+            None,
+            None,
+            layout_args,
+            None,
+        )?;
         layout.recursively_add_start_suspended_including_template(Some(true));
         let config = Config::from_kdl(&raw_layout, Some(config))?; // this merges the two config, with
         Ok((layout, config))
@@ -1487,30 +1624,56 @@ impl Layout {
     pub fn from_stringified_layout(
         stringified_layout: &str,
         config: Config,
+        layout_args: &BTreeMap<String, String>,
     ) -> Result<(Layout, Config), ConfigError> {
-        let layout = Layout::from_kdl(&stringified_layout, None, None, None)?;
+        let layout =
+            Layout::from_kdl_with_args(stringified_layout, None, None, None, layout_args, None)?;
         let config = Config::from_kdl(&stringified_layout, Some(config))?; // this merges the two config, with
         Ok((layout, config))
     }
     #[cfg(target_family = "wasm")]
-    pub fn from_url(_url: &str, _config: Config) -> Result<(Layout, Config), ConfigError> {
+    pub fn from_url(
+        _url: &str,
+        _config: Config,
+        _layout_args: &BTreeMap<String, String>,
+    ) -> Result<(Layout, Config), ConfigError> {
         Err(ConfigError::DownloadError(format!(
             "Unsupported platform, cannot download layout from the web"
         )))
     }
+    fn from_path_for_listing(
+        layout_path: &Path,
+        layout_dir: Option<PathBuf>,
+    ) -> Result<Layout, ConfigError> {
+        // layouts with required parameters are valid even though we can't know their arguments
+        let (path_to_raw_layout, raw_layout, raw_swap_layouts) =
+            Layout::stringified_from_path(layout_path)?;
+        Layout::from_kdl_with_args(
+            &raw_layout,
+            Some(path_to_raw_layout),
+            raw_swap_layouts
+                .as_ref()
+                .map(|(r, f)| (r.as_str(), f.as_str())),
+            None,
+            &Layout::placeholder_args(&raw_layout),
+            layout_dir,
+        )
+    }
     pub fn from_path_or_default_without_config(
         layout_path: Option<&PathBuf>,
         layout_dir: Option<PathBuf>,
     ) -> Result<Layout, ConfigError> {
         let (path_to_raw_layout, raw_layout, raw_swap_layouts) =
-            Layout::stringified_from_path_or_default(layout_path, layout_dir)?;
-        let layout = Layout::from_kdl(
+            Layout::stringified_from_path_or_default(layout_path, layout_dir.clone())?;
+        let layout = Layout::from_kdl_with_args(
             &raw_layout,
             Some(path_to_raw_layout),
             raw_swap_layouts
                 .as_ref()
                 .map(|(r, f)| (r.as_str(), f.as_str())),
             None,
+            &BTreeMap::new(),
+            layout_dir,
         )?;
         Ok(layout)
     }
@@ -1554,6 +1717,23 @@ impl Layout {
     ) -> Result<Layout, ConfigError> {
         Layout::from_kdl(raw, Some(path_to_raw_layout), swap_layouts, cwd)
     }
+    pub fn from_str_with_args(
+        raw: &str,
+        path_to_raw_layout: String,
+        swap_layouts: Option<(&str, &str)>, // Option<path_to_swap_layout, stringified_swap_layout>
+        cwd: Option<PathBuf>,
+        layout_args: &BTreeMap<String, String>,
+        layout_dir: Option<PathBuf>,
+    ) -> Result<Layout, ConfigError> {
+        Layout::from_kdl_with_args(
+            raw,
+            Some(path_to_raw_layout),
+            swap_layouts,
+            cwd,
+            layout_args,
+            layout_dir,
+        )
+    }
     pub fn stringified_from_dir(
         layout: &PathBuf,
         layout_dir: Option<&PathBuf>,
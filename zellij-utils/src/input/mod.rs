@@ -35,6 +35,8 @@ mod not_wasm {
         keybinds: &Keybinds,
         base_mode: Option<InputMode>,
     ) -> ModeInfo {
+        let key_sequences = keybinds.to_key_sequences_vec();
+        let custom_modes = keybinds.custom_modes();
         let keybinds = keybinds.to_keybinds_vec();
         let session_name = envs::get_session_name().ok();
 
@@ -54,6 +56,9 @@ mod not_wasm {
             web_server_ip: None,
             web_server_port: None,
             web_server_capability: None,
+            key_sequences,
+            pending_key_sequence: vec![],
+            custom_modes,
         }
     }
 
@@ -96,6 +96,10 @@ pub struct Options {
     pub on_force_close: Option<OnForceClose>,
     #[clap(long, value_parser)]
     pub scroll_buffer_size: Option<usize>,
+    /// Number of additional lines per pane to keep compressed on disk once they fall out of the
+    /// scroll buffer (0 or unset disables this)
+    #[clap(long, value_parser)]
+    pub disk_scroll_buffer_size: Option<usize>,
 
     /// Switch to using a user supplied command for clipboard instead of OSC52
     #[clap(long, value_parser)]
@@ -160,6 +164,13 @@ pub struct Options {
     #[serde(default)]
     pub scrollback_lines_to_serialize: Option<usize>,
 
+    /// Whether the whole scrollback of each pane is stored in a compressed file next to the
+    /// serialized session (keeping its styles and hyperlinks) instead of serializing
+    /// `scrollback_lines_to_serialize` lines into the layout, default is false
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub serialize_full_scrollback: Option<bool>,
+
     /// Whether to use ANSI styled underlines
     #[clap(long, value_parser)]
     #[serde(default)]
@@ -278,6 +289,12 @@ pub struct Options {
     /// NOTE: This only applies to web clients at the moment.
     #[clap(long)]
     pub client_async_worker_tasks: Option<usize>,
+
+    /// How long (in milliseconds) to wait for the next key of a multi-key sequence before the
+    /// keys pressed so far are handled on their own
+    /// default is 1000
+    #[clap(long)]
+    pub key_sequence_timeout: Option<u64>,
 }
 
 #[derive(ArgEnum, Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
@@ -333,6 +350,9 @@ impl Options {
         let theme_light = other.theme_light.or_else(|| self.theme_light.clone());
         let on_force_close = other.on_force_close.or(self.on_force_close);
         let scroll_buffer_size = other.scroll_buffer_size.or(self.scroll_buffer_size);
+        let disk_scroll_buffer_size = other
+            .disk_scroll_buffer_size
+            .or(self.disk_scroll_buffer_size);
         let copy_command = other.copy_command.or_else(|| self.copy_command.clone());
         let copy_clipboard = other.copy_clipboard.or(self.copy_clipboard);
         let copy_on_select = other.copy_on_select.or(self.copy_on_select);
@@ -351,6 +371,9 @@ impl Options {
         let scrollback_lines_to_serialize = other
             .scrollback_lines_to_serialize
             .or(self.scrollback_lines_to_serialize);
+        let serialize_full_scrollback = other
+            .serialize_full_scrollback
+            .or(self.serialize_full_scrollback);
         let styled_underlines = other.styled_underlines.or(self.styled_underlines);
         let serialization_interval = other.serialization_interval.or(self.serialization_interval);
         let disable_session_metadata = other
@@ -384,6 +407,7 @@ impl Options {
         let client_async_worker_tasks = other
             .client_async_worker_tasks
             .or(self.client_async_worker_tasks);
+        let key_sequence_timeout = other.key_sequence_timeout.or(self.key_sequence_timeout);
 
         Options {
             simplified_ui,
@@ -401,6 +425,7 @@ impl Options {
             mirror_session,
             on_force_close,
             scroll_buffer_size,
+            disk_scroll_buffer_size,
             copy_command,
             copy_clipboard,
             copy_on_select,
@@ -412,6 +437,7 @@ impl Options {
             session_serialization,
             serialize_pane_viewport,
             scrollback_lines_to_serialize,
+            serialize_full_scrollback,
             styled_underlines,
             serialization_interval,
             disable_session_metadata,
@@ -433,6 +459,7 @@ impl Options {
             enforce_https_for_localhost,
             post_command_discovery_hook,
             client_async_worker_tasks,
+            key_sequence_timeout,
         }
     }
 
@@ -460,6 +487,10 @@ impl Options {
             merge_bool(other.session_serialization, self.session_serialization);
         let serialize_pane_viewport =
             merge_bool(other.serialize_pane_viewport, self.serialize_pane_viewport);
+        let serialize_full_scrollback = merge_bool(
+            other.serialize_full_scrollback,
+            self.serialize_full_scrollback,
+        );
 
         let default_mode = other.default_mode.or(self.default_mode);
         let default_shell = other.default_shell.or_else(|| self.default_shell.clone());
@@ -472,6 +503,9 @@ impl Options {
         let theme_light = other.theme_light.or_else(|| self.theme_light.clone());
         let on_force_close = other.on_force_close.or(self.on_force_close);
         let scroll_buffer_size = other.scroll_buffer_size.or(self.scroll_buffer_size);
+        let disk_scroll_buffer_size = other
+            .disk_scroll_buffer_size
+            .or(self.disk_scroll_buffer_size);
         let copy_command = other.copy_command.or_else(|| self.copy_command.clone());
         let copy_clipboard = other.copy_clipboard.or(self.copy_clipboard);
         let copy_on_select = other.copy_on_select.or(self.copy_on_select);
@@ -519,6 +553,7 @@ impl Options {
         let client_async_worker_tasks = other
             .client_async_worker_tasks
             .or(self.client_async_worker_tasks);
+        let key_sequence_timeout = other.key_sequence_timeout.or(self.key_sequence_timeout);
 
         Options {
             simplified_ui,
@@ -536,6 +571,7 @@ impl Options {
             mirror_session,
             on_force_close,
             scroll_buffer_size,
+            disk_scroll_buffer_size,
             copy_command,
             copy_clipboard,
             copy_on_select,
@@ -547,6 +583,7 @@ impl Options {
             session_serialization,
             serialize_pane_viewport,
             scrollback_lines_to_serialize,
+            serialize_full_scrollback,
             styled_underlines,
             serialization_interval,
             disable_session_metadata,
@@ -568,6 +605,7 @@ impl Options {
             enforce_https_for_localhost,
             post_command_discovery_hook,
             client_async_worker_tasks,
+            key_sequence_timeout,
         }
     }
 
@@ -1,11 +1,15 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs::{self, File},
     io::Write,
     path::PathBuf,
 };
 
-use crate::{consts::ZELLIJ_PLUGIN_PERMISSIONS_CACHE, data::PermissionType};
+use crate::{
+    consts::ZELLIJ_PLUGIN_PERMISSIONS_CACHE,
+    data::PermissionType,
+    input::{layout::RunPluginLocation, plugins::PluginsConfigError},
+};
 
 pub type GrantedPermission = HashMap<String, Vec<PermissionType>>;
 
@@ -24,6 +28,62 @@ impl PermissionCache {
         self.granted.get(&plugin_name)
     }
 
+    pub fn granted(&self) -> &GrantedPermission {
+        &self.granted
+    }
+
+    /// Adds the permissions to those already granted to the plugin, returns true if anything
+    /// changed
+    pub fn grant(&mut self, plugin_name: String, permissions: Vec<PermissionType>) -> bool {
+        let granted = self.granted.entry(plugin_name).or_default();
+        let mut changed = false;
+        for permission in permissions {
+            if !granted.contains(&permission) {
+                granted.push(permission);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Removes the permissions from those granted to the plugin, or forgets the plugin
+    /// altogether if no permissions are specified (so that it will be prompted again), returns
+    /// true if anything changed
+    pub fn revoke(&mut self, plugin_name: &str, permissions: &[PermissionType]) -> bool {
+        if permissions.is_empty() {
+            return self.granted.remove(plugin_name).is_some();
+        }
+        match self.granted.get_mut(plugin_name) {
+            Some(granted) => {
+                let granted_count = granted.len();
+                granted.retain(|p| !permissions.contains(p));
+                granted.len() != granted_count
+            },
+            None => false,
+        }
+    }
+
+    /// Grants the permissions pre-seeded in the configuration (keyed by plugin url), returns
+    /// true if anything changed
+    pub fn seed(&mut self, plugin_permissions: &BTreeMap<String, Vec<PermissionType>>) -> bool {
+        let mut changed = false;
+        for (plugin_url, permissions) in plugin_permissions {
+            match PermissionCache::plugin_name_from_url(plugin_url, None) {
+                Ok(plugin_name) => changed |= self.grant(plugin_name, permissions.clone()),
+                Err(e) => log::error!("Failed to seed permissions for {}: {}", plugin_url, e),
+            }
+        }
+        changed
+    }
+
+    /// The key under which the permissions of the plugin with this url are cached
+    pub fn plugin_name_from_url(
+        url: &str,
+        cwd: Option<PathBuf>,
+    ) -> Result<String, PluginsConfigError> {
+        RunPluginLocation::parse(url, cwd).map(|location| location.to_string())
+    }
+
     pub fn check_permissions(
         &self,
         plugin_name: String,
@@ -65,3 +125,7 @@ impl PermissionCache {
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/permission_test.rs"]
+mod permission_test;
@@ -1,6 +1,6 @@
 use super::super::actions::*;
 use super::super::keybinds::*;
-use crate::data::{BareKey, Direction, KeyWithModifier};
+use crate::data::{BareKey, CustomInputMode, CustomModeFallThrough, Direction, KeyWithModifier};
 use crate::input::config::Config;
 use insta::assert_snapshot;
 use strum::IntoEnumIterator;
@@ -594,3 +594,513 @@ fn error_received_on_unknown_key_instruction() {
     let config_error = Config::from_kdl(config_contents, None).unwrap_err();
     assert_snapshot!(format!("{:?}", config_error));
 }
+
+fn ctrl_a() -> KeyWithModifier {
+    KeyWithModifier::new(BareKey::Char('a')).with_ctrl_modifier()
+}
+
+fn press_key(
+    keybinds: &Keybinds,
+    pending_keys: &mut Vec<PendingKey>,
+    key: KeyWithModifier,
+    raw_bytes: Vec<u8>,
+) -> Vec<Action> {
+    keybinds.get_actions_for_key_sequence_in_mode_or_default_action(
+        &InputMode::Normal,
+        pending_keys,
+        &key,
+        raw_bytes,
+        InputMode::Normal,
+        false,
+    )
+}
+
+#[test]
+fn can_define_key_sequences_in_configfile() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl a" "g" "s" sequence=true { SwitchToMode "Session"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let sequence = vec![
+        ctrl_a(),
+        KeyWithModifier::new(BareKey::Char('g')),
+        KeyWithModifier::new(BareKey::Char('s')),
+    ];
+    assert_eq!(
+        config
+            .keybinds
+            .1
+            .get(&InputMode::Normal)
+            .and_then(|s| s.get(&sequence)),
+        Some(&vec![Action::SwitchToMode {
+            input_mode: InputMode::Session
+        }]),
+        "Key sequence successfully defined in config"
+    );
+    assert_eq!(
+        config
+            .keybinds
+            .get_actions_for_key_in_mode(&InputMode::Normal, &ctrl_a()),
+        None,
+        "Keys of the sequence were not bound on their own"
+    );
+}
+
+#[test]
+fn key_sequence_actions_are_returned_once_the_sequence_is_complete() {
+    let config_contents = r#"
+        keybinds clear-defaults=true {
+            normal {
+                bind "Ctrl a" "g" "s" sequence=true { SwitchToMode "Session"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let mut pending_keys = vec![];
+    let actions = press_key(&config.keybinds, &mut pending_keys, ctrl_a(), vec![1]);
+    assert_eq!(actions, vec![], "No actions while the sequence is pending");
+    let actions = press_key(
+        &config.keybinds,
+        &mut pending_keys,
+        KeyWithModifier::new(BareKey::Char('g')),
+        vec![b'g'],
+    );
+    assert_eq!(actions, vec![], "No actions while the sequence is pending");
+    assert_eq!(pending_keys.len(), 2, "Both keys are pending");
+    let actions = press_key(
+        &config.keybinds,
+        &mut pending_keys,
+        KeyWithModifier::new(BareKey::Char('s')),
+        vec![b's'],
+    );
+    assert_eq!(
+        actions,
+        vec![Action::SwitchToMode {
+            input_mode: InputMode::Session
+        }],
+        "Sequence actions returned once complete"
+    );
+    assert!(pending_keys.is_empty(), "No more pending keys");
+}
+
+#[test]
+fn unmatched_key_sequence_prefix_falls_through() {
+    let config_contents = r#"
+        keybinds clear-defaults=true {
+            normal {
+                bind "Ctrl a" "g" "s" sequence=true { SwitchToMode "Session"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let mut pending_keys = vec![];
+    press_key(&config.keybinds, &mut pending_keys, ctrl_a(), vec![1]);
+    let actions = press_key(
+        &config.keybinds,
+        &mut pending_keys,
+        KeyWithModifier::new(BareKey::Char('x')),
+        vec![b'x'],
+    );
+    assert_eq!(
+        actions,
+        vec![
+            Action::Write {
+                key_with_modifier: Some(ctrl_a()),
+                bytes: vec![1],
+                is_kitty_keyboard_protocol: false,
+            },
+            Action::Write {
+                key_with_modifier: Some(KeyWithModifier::new(BareKey::Char('x'))),
+                bytes: vec![b'x'],
+                is_kitty_keyboard_protocol: false,
+            },
+        ],
+        "Keys of the broken sequence are written to the pane"
+    );
+    assert!(pending_keys.is_empty(), "No more pending keys");
+}
+
+#[test]
+fn key_breaking_a_sequence_can_start_a_new_one() {
+    let config_contents = r#"
+        keybinds clear-defaults=true {
+            normal {
+                bind "Ctrl a" "n" sequence=true { NewPane; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let mut pending_keys = vec![];
+    press_key(&config.keybinds, &mut pending_keys, ctrl_a(), vec![1]);
+    let actions = press_key(&config.keybinds, &mut pending_keys, ctrl_a(), vec![1]);
+    assert_eq!(
+        actions,
+        vec![Action::Write {
+            key_with_modifier: Some(ctrl_a()),
+            bytes: vec![1],
+            is_kitty_keyboard_protocol: false,
+        }],
+        "First key written to the pane"
+    );
+    assert_eq!(pending_keys.len(), 1, "Second key started a new sequence");
+    let actions = press_key(
+        &config.keybinds,
+        &mut pending_keys,
+        KeyWithModifier::new(BareKey::Char('n')),
+        vec![b'n'],
+    );
+    assert_eq!(
+        actions,
+        vec![Action::NewPane {
+            direction: None,
+            pane_name: None,
+            start_suppressed: false,
+        }],
+        "New sequence completed"
+    );
+}
+
+#[test]
+fn pending_keys_are_flushed_separately_or_as_a_shorter_sequence() {
+    let config_contents = r#"
+        keybinds clear-defaults=true {
+            normal {
+                bind "Ctrl a" "g" sequence=true { SwitchToMode "Locked"; }
+                bind "Ctrl a" "g" "s" sequence=true { SwitchToMode "Session"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let mut pending_keys = vec![];
+    press_key(&config.keybinds, &mut pending_keys, ctrl_a(), vec![1]);
+    let actions = config.keybinds.flush_pending_keys(
+        &InputMode::Normal,
+        &mut pending_keys,
+        InputMode::Normal,
+    );
+    assert_eq!(
+        actions,
+        vec![Action::Write {
+            key_with_modifier: Some(ctrl_a()),
+            bytes: vec![1],
+            is_kitty_keyboard_protocol: false,
+        }],
+        "Incomplete sequence flushed as separate keys"
+    );
+    press_key(&config.keybinds, &mut pending_keys, ctrl_a(), vec![1]);
+    let actions = press_key(
+        &config.keybinds,
+        &mut pending_keys,
+        KeyWithModifier::new(BareKey::Char('g')),
+        vec![b'g'],
+    );
+    assert_eq!(actions, vec![], "Waiting for a possible longer sequence");
+    let actions = config.keybinds.flush_pending_keys(
+        &InputMode::Normal,
+        &mut pending_keys,
+        InputMode::Normal,
+    );
+    assert_eq!(
+        actions,
+        vec![Action::SwitchToMode {
+            input_mode: InputMode::Locked
+        }],
+        "Complete shorter sequence flushed"
+    );
+    assert!(pending_keys.is_empty(), "No more pending keys");
+}
+
+#[test]
+fn can_unbind_key_sequences() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl a" "n" sequence=true { NewPane; }
+                bind "Ctrl a" "x" sequence=true { CloseFocus; }
+                unbind "Ctrl a" "n" sequence=true
+            }
+            unbind "Ctrl a" "x" sequence=true
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    assert_eq!(
+        config.keybinds.1.get(&InputMode::Normal).map(|s| s.len()),
+        Some(0),
+        "Both sequences were unbound"
+    );
+}
+
+#[test]
+fn clear_defaults_clears_key_sequences_in_mode() {
+    let base_config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl a" "n" sequence=true { NewPane; }
+            }
+        }
+    "#;
+    let config_contents = r#"
+        keybinds {
+            normal clear-defaults=true {
+                bind "Ctrl g" { SwitchToMode "Locked"; }
+            }
+        }
+    "#;
+    let base_config = Config::from_kdl(base_config_contents, None).unwrap();
+    let config = Config::from_kdl(config_contents, Some(base_config)).unwrap();
+    assert_eq!(
+        config.keybinds.1.get(&InputMode::Normal).map(|s| s.len()),
+        Some(0),
+        "Sequences of the mode were cleared"
+    );
+}
+
+#[test]
+fn error_received_on_key_sequence_with_a_single_key() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl a" sequence=true { NewPane; }
+            }
+        }
+    "#;
+    let config_error = Config::from_kdl(config_contents, None).unwrap_err();
+    assert_snapshot!(format!("{:?}", config_error));
+}
+
+#[test]
+fn can_define_custom_modes_in_configfile() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl y" { SwitchToMode "git"; }
+            }
+            custom_mode "git" fall_through="pane" {
+                bind "Esc" { SwitchToMode "Normal"; }
+                bind "d" { SwitchToMode "Deploy"; }
+            }
+            custom_mode "Deploy" {
+                bind "Esc" { SwitchToMode "git"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let git_mode = CustomInputMode::new("git");
+    let deploy_mode = CustomInputMode::new("deploy");
+    assert_eq!(
+        config.keybinds.get_actions_for_key_in_mode(
+            &InputMode::Normal,
+            &KeyWithModifier::new(BareKey::Char('y')).with_ctrl_modifier(),
+        ),
+        Some(&vec![Action::SwitchToMode {
+            input_mode: InputMode::Custom(git_mode)
+        }]),
+        "Custom mode can be switched to before it is defined"
+    );
+    assert_eq!(
+        config.keybinds.get_actions_for_key_in_mode(
+            &InputMode::Custom(git_mode),
+            &KeyWithModifier::new(BareKey::Char('d')),
+        ),
+        Some(&vec![Action::SwitchToMode {
+            input_mode: InputMode::Custom(deploy_mode)
+        }]),
+        "Custom mode names are case insensitive"
+    );
+    assert_eq!(
+        config.keybinds.2.get(&git_mode),
+        Some(&CustomModeFallThrough::Pane),
+        "Fall through set from config"
+    );
+    assert_eq!(
+        config.keybinds.2.get(&deploy_mode),
+        Some(&CustomModeFallThrough::Swallow),
+        "Unbound keys are swallowed by default"
+    );
+    assert_eq!(config.keybinds.custom_modes().len(), 2);
+}
+
+#[test]
+fn unbound_keys_in_custom_modes_fall_through() {
+    let config_contents = r#"
+        keybinds {
+            custom_mode "pass-through" fall_through="pane" {
+                bind "Esc" { SwitchToMode "Normal"; }
+            }
+            custom_mode "swallowing" fall_through="swallow" {
+                bind "Esc" { SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let unbound_key = KeyWithModifier::new(BareKey::Char('x'));
+    let actions_in_mode = |mode_name: &str| {
+        config
+            .keybinds
+            .get_actions_for_key_in_mode_or_default_action(
+                &InputMode::Custom(CustomInputMode::new(mode_name)),
+                &unbound_key,
+                vec![b'x'],
+                InputMode::Normal,
+                false,
+            )
+    };
+    assert_eq!(
+        actions_in_mode("pass-through"),
+        vec![Action::Write {
+            key_with_modifier: Some(unbound_key.clone()),
+            bytes: vec![b'x'],
+            is_kitty_keyboard_protocol: false,
+        }],
+        "Unbound key sent to the pane"
+    );
+    assert_eq!(
+        actions_in_mode("swallowing"),
+        vec![Action::NoOp],
+        "Unbound key swallowed"
+    );
+}
+
+#[test]
+fn custom_mode_can_be_the_default_mode() {
+    let config_contents = r#"
+        default_mode "vcs"
+        keybinds {
+            custom_mode "vcs" fall_through="pane" {
+                bind "Ctrl g" { SwitchToMode "Locked"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    assert_eq!(
+        config.options.default_mode,
+        Some(InputMode::Custom(CustomInputMode::new("vcs")))
+    );
+}
+
+#[test]
+fn error_received_on_custom_mode_with_built_in_name() {
+    let config_contents = r#"
+        keybinds {
+            custom_mode "pane" {
+                bind "Esc" { SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let config_error = Config::from_kdl(config_contents, None).unwrap_err();
+    assert_snapshot!(format!("{:?}", config_error));
+}
+
+#[test]
+fn error_received_on_custom_modes_with_the_same_id() {
+    // these two names hash to the same custom mode id
+    let config_contents = r#"
+        keybinds {
+            custom_mode "mode29901" {
+                bind "Esc" { SwitchToMode "Normal"; }
+            }
+            custom_mode "mode868600" {
+                bind "Esc" { SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let config_error = Config::from_kdl(config_contents, None).unwrap_err();
+    assert_snapshot!(format!("{:?}", config_error));
+}
+
+#[test]
+fn error_received_on_switching_to_undefined_custom_mode() {
+    let config_contents = r#"
+        keybinds {
+            normal {
+                bind "Ctrl y" { SwitchToMode "git"; }
+            }
+        }
+    "#;
+    let config_error = Config::from_kdl(config_contents, None).unwrap_err();
+    assert_snapshot!(format!("{:?}", config_error));
+}
+
+#[test]
+fn error_received_on_undefined_custom_default_mode() {
+    let config_contents = r#"
+        default_mode "vcs"
+        keybinds {
+            custom_mode "git" {
+                bind "Esc" { SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let config_error = Config::from_kdl(config_contents, None).unwrap_err();
+    assert_snapshot!(format!("{:?}", config_error));
+}
+
+#[test]
+fn keys_after_a_mode_switch_are_resolved_in_the_new_mode() {
+    let config_contents = r#"
+        keybinds clear-defaults=true {
+            normal {
+                bind "Ctrl a" { SwitchToMode "Tmux"; }
+                bind "Ctrl a" "g" "s" sequence=true { SwitchToMode "Session"; }
+            }
+            tmux {
+                bind "g" { NewPane; }
+                bind "x" { CloseFocus; SwitchToMode "Normal"; }
+            }
+        }
+    "#;
+    let config = Config::from_kdl(config_contents, None).unwrap();
+    let mut pending_keys = vec![];
+    press_key(&config.keybinds, &mut pending_keys, ctrl_a(), vec![1]);
+    let actions = press_key(
+        &config.keybinds,
+        &mut pending_keys,
+        KeyWithModifier::new(BareKey::Char('x')),
+        vec![b'x'],
+    );
+    assert_eq!(
+        actions,
+        vec![
+            Action::SwitchToMode {
+                input_mode: InputMode::Tmux
+            },
+            Action::CloseFocus,
+            Action::SwitchToMode {
+                input_mode: InputMode::Normal
+            },
+        ],
+        "Key breaking the sequence resolved in the mode the prefix switched to"
+    );
+    assert!(pending_keys.is_empty(), "No more pending keys");
+
+    press_key(&config.keybinds, &mut pending_keys, ctrl_a(), vec![1]);
+    press_key(
+        &config.keybinds,
+        &mut pending_keys,
+        KeyWithModifier::new(BareKey::Char('g')),
+        vec![b'g'],
+    );
+    let actions = config.keybinds.flush_pending_keys(
+        &InputMode::Normal,
+        &mut pending_keys,
+        InputMode::Normal,
+    );
+    assert_eq!(
+        actions,
+        vec![
+            Action::SwitchToMode {
+                input_mode: InputMode::Tmux
+            },
+            Action::NewPane {
+                direction: None,
+                pane_name: None,
+                start_suppressed: false,
+            },
+        ],
+        "Flushed keys resolved in the mode the prefix switched to"
+    );
+}
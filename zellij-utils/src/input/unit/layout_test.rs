@@ -216,6 +216,44 @@ fn layout_with_hidden_floating_panes() {
     assert_eq!(layout, expected_layout);
 }
 
+#[test]
+fn layout_with_scratchpad_floating_panes() {
+    let kdl_layout = r#"
+        layout {
+            floating_panes {
+                pane scratchpad="notes" command="nvim" width="80%"
+                pane {
+                    scratchpad "shell"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None).unwrap();
+    let expected_layout = Layout {
+        template: Some((
+            TiledPaneLayout::default(),
+            vec![
+                FloatingPaneLayout {
+                    scratchpad: Some("notes".to_owned()),
+                    width: Some(PercentOrFixed::Percent(80)),
+                    run: Some(Run::Command(RunCommand {
+                        command: PathBuf::from("nvim"),
+                        hold_on_close: true,
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                },
+                FloatingPaneLayout {
+                    scratchpad: Some("shell".to_owned()),
+                    ..Default::default()
+                },
+            ],
+        )),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
 #[test]
 fn layout_with_floating_panes_template() {
     let kdl_layout = r#"
@@ -511,6 +549,92 @@ fn layout_with_command_panes_and_cwd_and_args() {
     assert_eq!(layout, expected_layout);
 }
 
+#[test]
+fn layout_with_command_panes_and_env() {
+    let kdl_layout = r#"
+        layout {
+            pane command="cargo" {
+                args "run"
+                env {
+                    RUST_LOG "debug"
+                    PORT 8080
+                }
+            }
+            floating_panes {
+                pane command="htop" {
+                    env {
+                        TERM "xterm"
+                    }
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None).unwrap();
+    let expected_layout = Layout {
+        template: Some((
+            TiledPaneLayout {
+                children: vec![TiledPaneLayout {
+                    run: Some(Run::Command(RunCommand {
+                        command: PathBuf::from("cargo"),
+                        args: vec![String::from("run")],
+                        hold_on_close: true,
+                        env: BTreeMap::from([
+                            ("RUST_LOG".to_owned(), "debug".to_owned()),
+                            ("PORT".to_owned(), "8080".to_owned()),
+                        ]),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            vec![FloatingPaneLayout {
+                run: Some(Run::Command(RunCommand {
+                    command: PathBuf::from("htop"),
+                    hold_on_close: true,
+                    env: BTreeMap::from([("TERM".to_owned(), "xterm".to_owned())]),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }],
+        )),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
+#[test]
+fn env_added_to_env_in_template() {
+    let kdl_layout = r#"
+        layout {
+            pane_template name="server" {
+                command "cargo"
+                env {
+                    RUST_LOG "info"
+                    PORT "8080"
+                }
+            }
+            server {
+                env {
+                    RUST_LOG "debug"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None).unwrap();
+    let run = &layout.template.unwrap().0.children[0].run;
+    match run {
+        Some(Run::Command(run_command)) => assert_eq!(
+            run_command.env,
+            BTreeMap::from([
+                ("RUST_LOG".to_owned(), "debug".to_owned()),
+                ("PORT".to_owned(), "8080".to_owned()),
+            ])
+        ),
+        _ => panic!("expected a command pane, got: {:?}", run),
+    }
+}
+
 #[test]
 fn layout_with_command_panes_and_close_on_exit() {
     let kdl_layout = r#"
@@ -537,6 +661,285 @@ fn layout_with_command_panes_and_start_suspended() {
     assert_snapshot!(normalize_layout_debug(format!("{:#?}", layout)));
 }
 
+#[test]
+fn layout_with_command_panes_and_restart_policy() {
+    let kdl_layout = r#"
+        layout {
+            pane command="my-server" restart="on-failure" {
+                max_restarts 5
+            }
+            floating_panes {
+                pane command="tail" {
+                    restart "always"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None).unwrap();
+    assert_snapshot!(normalize_layout_debug(format!("{:#?}", layout)));
+}
+
+#[test]
+fn layout_with_parameters() {
+    let kdl_layout = r#"
+        layout cwd="/projects/${project}" {
+            parameters {
+                project "web"
+                port 8080
+                service
+            }
+            pane name="${service} on ${port}" command="cargo" {
+                args "run" "--bin" "${service}" "--" "--port" "${port}"
+                cwd "src/${project}"
+            }
+            pane {
+                plugin location="zellij:strider" {
+                    project_root "/projects/${project}"
+                }
+            }
+        }
+    "#;
+    let layout_args = BTreeMap::from([
+        ("project".to_owned(), "api".to_owned()),
+        ("service".to_owned(), "gateway".to_owned()),
+    ]);
+    let layout = Layout::from_kdl_with_args(
+        kdl_layout,
+        Some("layout_file_name".into()),
+        None,
+        None,
+        &layout_args,
+        None,
+    )
+    .unwrap();
+    assert_snapshot!(normalize_layout_debug(format!("{:#?}", layout)));
+}
+
+#[test]
+fn error_on_missing_layout_parameter() {
+    let kdl_layout = r#"
+        layout {
+            parameters {
+                project
+            }
+            pane cwd="${project}"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn placeholder_args_leave_required_parameters_unsubstituted() {
+    let kdl_layout = r#"
+        layout {
+            parameters {
+                project
+                service "gateway"
+            }
+            pane name="${project}"
+        }
+    "#;
+    let placeholder_args = Layout::placeholder_args(kdl_layout);
+    assert_eq!(
+        placeholder_args,
+        BTreeMap::from([("project".to_owned(), "${project}".to_owned())])
+    );
+    let layout = Layout::from_kdl_with_args(
+        kdl_layout,
+        Some("layout_file_name".into()),
+        None,
+        None,
+        &placeholder_args,
+        None,
+    )
+    .unwrap();
+    let (tiled_layout, _floating_layouts) = layout.new_tab();
+    assert_eq!(tiled_layout.children[0].name, Some("${project}".to_owned()));
+}
+
+#[test]
+fn error_on_unknown_layout_argument() {
+    let kdl_layout = r#"
+        layout {
+            parameters {
+                project "web"
+            }
+            pane cwd="${project}"
+        }
+    "#;
+    let layout_args = BTreeMap::from([("service".to_owned(), "gateway".to_owned())]);
+    let layout = Layout::from_kdl_with_args(
+        kdl_layout,
+        Some("layout_file_name".into()),
+        None,
+        None,
+        &layout_args,
+        None,
+    );
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn layout_parameter_values_are_not_substituted_themselves() {
+    let kdl_layout = r#"
+        layout {
+            parameters {
+                project
+                service
+            }
+            pane name="${project}/${service}"
+        }
+    "#;
+    let layout_args = BTreeMap::from([
+        ("project".to_owned(), "${service}".to_owned()),
+        ("service".to_owned(), "gateway".to_owned()),
+    ]);
+    let layout = Layout::from_kdl_with_args(
+        kdl_layout,
+        Some("layout_file_name".into()),
+        None,
+        None,
+        &layout_args,
+        None,
+    )
+    .unwrap();
+    let (tiled_layout, _floating_layouts) = layout.new_tab();
+    assert_eq!(
+        tiled_layout.children[0].name,
+        Some("${service}/gateway".to_owned())
+    );
+}
+
+#[test]
+fn layout_parameters_are_substituted_in_swap_layout_files() {
+    let kdl_layout = r#"
+        layout {
+            parameters {
+                service "gateway"
+            }
+            pane
+        }
+    "#;
+    let kdl_swap_layout = r#"
+        swap_tiled_layout name="with-service" {
+            tab {
+                pane name="${service}"
+                pane { children; }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(
+        kdl_layout,
+        Some("layout_file_name".into()),
+        Some(("swap_layout_file_name".into(), kdl_swap_layout)),
+        None,
+    )
+    .unwrap();
+    let swap_tiled_layout = layout.swap_tiled_layouts[0].0.values().next().unwrap();
+    assert_eq!(
+        swap_tiled_layout.children[0].name,
+        Some("gateway".to_owned())
+    );
+}
+
+fn layout_fixture_path(file_name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/input/unit/fixtures/layouts")
+        .join(file_name)
+}
+
+fn layout_error_message(error: ConfigError) -> String {
+    match error {
+        ConfigError::KdlError(kdl_error) => kdl_error.error_message,
+        e => e.to_string(),
+    }
+}
+
+#[test]
+fn layout_with_imported_templates() {
+    let layout =
+        Layout::from_path_without_config(&layout_fixture_path("with-imports.kdl")).unwrap();
+    assert_snapshot!(normalize_layout_debug(format!("{:#?}", layout)));
+}
+
+#[test]
+fn local_templates_take_precedence_over_imported_ones() {
+    let layout =
+        Layout::from_path_without_config(&layout_fixture_path("with-imports.kdl")).unwrap();
+    let (_tab_name, tiled_layout, _floating_layouts) = &layout.tabs()[0];
+    let editor_pane = &tiled_layout.children[1].children[0].children[0];
+    match &editor_pane.run {
+        Some(Run::Command(run_command)) => {
+            assert_eq!(run_command.command, std::path::PathBuf::from("nvim"))
+        },
+        run => panic!("expected a command pane, got: {:?}", run),
+    }
+}
+
+#[test]
+fn imported_files_are_reported_transitively() {
+    let layout_path = layout_fixture_path("with-imports.kdl");
+    let raw_layout = std::fs::read_to_string(&layout_path).unwrap();
+    let (_layout, imported_files) =
+        Layout::from_kdl_with_imports(&raw_layout, Some(layout_path.display().to_string()), None)
+            .unwrap();
+    let imported_file_names: Vec<_> = imported_files
+        .iter()
+        .filter_map(|f| f.file_name())
+        .map(|f| f.to_string_lossy().to_string())
+        .collect();
+    assert_eq!(
+        imported_file_names,
+        vec!["common-templates.kdl".to_owned(), "bars.kdl".to_owned()]
+    );
+}
+
+#[test]
+fn error_on_circular_import() {
+    let error =
+        Layout::from_path_without_config(&layout_fixture_path("circular-import.kdl")).unwrap_err();
+    let error_message = layout_error_message(error);
+    assert!(
+        error_message.contains("Circular import"),
+        "unexpected error: {}",
+        error_message
+    );
+}
+
+#[test]
+fn error_on_missing_import() {
+    let error =
+        Layout::from_path_without_config(&layout_fixture_path("missing-import.kdl")).unwrap_err();
+    let error_message = layout_error_message(error);
+    assert!(
+        error_message.contains("no-such-templates.kdl"),
+        "unexpected error: {}",
+        error_message
+    );
+}
+
+#[test]
+fn error_in_imported_file_names_the_imported_file() {
+    let error =
+        Layout::from_path_without_config(&layout_fixture_path("broken-import.kdl")).unwrap_err();
+    let error_message = layout_error_message(error);
+    assert!(
+        error_message.contains("broken-templates.kdl") && error_message.contains("line 5"),
+        "unexpected error: {}",
+        error_message
+    );
+}
+
+#[test]
+fn template_libraries_are_recognized() {
+    let template_library =
+        std::fs::read_to_string(layout_fixture_path("templates/common-templates.kdl")).unwrap();
+    let layout = std::fs::read_to_string(layout_fixture_path("with-imports.kdl")).unwrap();
+    assert!(Layout::is_template_library(&template_library));
+    assert!(!Layout::is_template_library(&layout));
+}
+
 #[test]
 fn layout_with_plugin_panes() {
     let kdl_layout = r#"
@@ -1440,6 +1843,27 @@ fn close_on_exit_added_to_close_on_exit_in_template() {
     assert_snapshot!(normalize_layout_debug(format!("{:#?}", layout)));
 }
 
+#[test]
+fn restart_policy_added_to_restart_policy_in_template() {
+    let kdl_layout = r#"
+        layout {
+            pane_template name="server" {
+                command "my-server"
+                restart "on-failure"
+            }
+            server
+            server {
+                max_restarts 3
+            }
+            server {
+                restart "always"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None).unwrap();
+    assert_snapshot!(normalize_layout_debug(format!("{:#?}", layout)));
+}
+
 #[test]
 fn cwd_override_cwd_in_template() {
     let kdl_layout = r#"
@@ -1515,6 +1939,59 @@ fn error_on_bare_args_without_command() {
     assert!(layout.is_err(), "error provided");
 }
 
+#[test]
+fn error_on_bare_env_without_command() {
+    let kdl_layout = r#"
+        layout {
+            pane {
+                env {
+                    RUST_LOG "debug"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_on_bare_restart_without_command() {
+    let kdl_layout = r#"
+        layout {
+            pane restart="always"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_on_invalid_restart_policy() {
+    let kdl_layout = r#"
+        layout {
+            pane command="htop" restart="sometimes"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_on_bare_env_in_template_without_command() {
+    let kdl_layout = r#"
+        layout {
+            pane_template name="my_template"
+            my_template {
+                env {
+                    RUST_LOG "debug"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
 #[test]
 fn error_on_bare_close_on_exit_without_command() {
     let kdl_layout = r#"
@@ -1951,6 +2428,106 @@ fn can_load_swap_layouts_from_a_different_file() {
     assert_snapshot!(normalize_layout_debug(format!("{:#?}", layout)));
 }
 
+#[test]
+fn swap_layouts_with_size_constraints() {
+    let kdl_layout = r#"
+        layout {
+            swap_tiled_layout name="adaptive" {
+                tab min_columns=200 max_panes=3 {
+                    pane split_direction="vertical" {
+                        pane
+                        pane { children; }
+                    }
+                }
+                tab max_rows=40 min_aspect_ratio=2.5 {
+                    pane
+                    pane { children; }
+                }
+                tab {
+                    pane
+                }
+            }
+            swap_floating_layout {
+                floating_panes max_columns=120 {
+                    pane
+                }
+            }
+            pane
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None).unwrap();
+    let tiled_constraints: Vec<LayoutConstraint> =
+        layout.swap_tiled_layouts[0].0.keys().cloned().collect();
+    assert_eq!(
+        tiled_constraints,
+        vec![
+            LayoutConstraint::Combined(vec![
+                LayoutConstraint::MaxRows(40),
+                LayoutConstraint::MinAspectRatio(250)
+            ]),
+            LayoutConstraint::Combined(vec![
+                LayoutConstraint::MaxPanes(3),
+                LayoutConstraint::MinColumns(200)
+            ]),
+            LayoutConstraint::NoConstraint,
+        ]
+    );
+    let floating_constraints: Vec<LayoutConstraint> =
+        layout.swap_floating_layouts[0].0.keys().cloned().collect();
+    assert_eq!(
+        floating_constraints,
+        vec![LayoutConstraint::MaxColumns(120)]
+    );
+}
+
+#[test]
+fn size_constraints_fit_display_area() {
+    let wide_display_area = Size {
+        rows: 50,
+        cols: 250,
+    };
+    let narrow_display_area = Size { rows: 50, cols: 80 };
+    let constraint = LayoutConstraint::Combined(vec![
+        LayoutConstraint::MaxPanes(3),
+        LayoutConstraint::MinColumns(200),
+        LayoutConstraint::MinAspectRatio(400),
+    ]);
+    assert!(constraint.fits_display_area(&wide_display_area));
+    assert!(!constraint.fits_display_area(&narrow_display_area));
+    assert!(constraint.fits_pane_count(3));
+    assert!(!constraint.fits_pane_count(4));
+}
+
+#[test]
+fn error_on_min_columns_greater_than_max_columns() {
+    let kdl_layout = r#"
+        layout {
+            swap_tiled_layout {
+                tab min_columns=200 max_columns=100 {
+                    pane
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_on_quoted_size_constraint() {
+    let kdl_layout = r#"
+        layout {
+            swap_tiled_layout {
+                tab min_columns="200" {
+                    pane
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, Some("layout_file_name".into()), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
 #[test]
 fn can_define_stacked_children_for_pane_node() {
     let kdl_layout = r#"
@@ -0,0 +1,125 @@
+use super::super::permission::*;
+use crate::data::PermissionType;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn grant_adds_to_existing_permissions_without_duplicates() {
+    let mut permission_cache = PermissionCache::default();
+    assert!(permission_cache.grant(
+        "zellij:my-plugin".to_owned(),
+        vec![PermissionType::ReadApplicationState]
+    ));
+    assert!(permission_cache.grant(
+        "zellij:my-plugin".to_owned(),
+        vec![
+            PermissionType::ReadApplicationState,
+            PermissionType::RunCommands
+        ]
+    ));
+    assert!(!permission_cache.grant(
+        "zellij:my-plugin".to_owned(),
+        vec![PermissionType::RunCommands]
+    ));
+    assert_eq!(
+        permission_cache.get_permissions("zellij:my-plugin".to_owned()),
+        Some(&vec![
+            PermissionType::ReadApplicationState,
+            PermissionType::RunCommands
+        ])
+    );
+}
+
+#[test]
+fn revoke_specific_permissions() {
+    let mut permission_cache = PermissionCache::default();
+    permission_cache.grant(
+        "zellij:my-plugin".to_owned(),
+        vec![
+            PermissionType::ReadApplicationState,
+            PermissionType::RunCommands,
+        ],
+    );
+    assert!(permission_cache.revoke("zellij:my-plugin", &[PermissionType::RunCommands]));
+    assert!(!permission_cache.revoke("zellij:my-plugin", &[PermissionType::RunCommands]));
+    assert!(!permission_cache.revoke("zellij:other-plugin", &[PermissionType::RunCommands]));
+    assert_eq!(
+        permission_cache.get_permissions("zellij:my-plugin".to_owned()),
+        Some(&vec![PermissionType::ReadApplicationState])
+    );
+}
+
+#[test]
+fn revoke_without_permissions_forgets_plugin() {
+    let mut permission_cache = PermissionCache::default();
+    permission_cache.grant(
+        "zellij:my-plugin".to_owned(),
+        vec![PermissionType::ReadApplicationState],
+    );
+    assert!(permission_cache.revoke("zellij:my-plugin", &[]));
+    assert_eq!(
+        permission_cache.get_permissions("zellij:my-plugin".to_owned()),
+        None
+    );
+    assert!(!permission_cache.check_permissions(
+        "zellij:my-plugin".to_owned(),
+        &vec![PermissionType::ReadApplicationState]
+    ));
+}
+
+#[test]
+fn seeded_permissions_are_persisted() {
+    let cache_dir = tempdir().unwrap();
+    let cache_path = cache_dir.path().join("permissions.kdl");
+    let mut plugin_permissions = BTreeMap::new();
+    plugin_permissions.insert(
+        "https://example.com/my-plugin.wasm".to_owned(),
+        vec![
+            PermissionType::ReadApplicationState,
+            PermissionType::WebAccess,
+        ],
+    );
+    plugin_permissions.insert(
+        "file:/path/to/my-plugin.wasm".to_owned(),
+        vec![PermissionType::RunCommands],
+    );
+    let mut permission_cache = PermissionCache::from_path_or_default(Some(cache_path.clone()));
+    assert!(permission_cache.seed(&plugin_permissions));
+    assert!(!permission_cache.seed(&plugin_permissions));
+    permission_cache.write_to_file().unwrap();
+
+    let permission_cache = PermissionCache::from_path_or_default(Some(cache_path));
+    assert!(permission_cache.check_permissions(
+        "https://example.com/my-plugin.wasm".to_owned(),
+        &vec![
+            PermissionType::WebAccess,
+            PermissionType::ReadApplicationState
+        ]
+    ));
+    assert!(permission_cache.check_permissions(
+        "/path/to/my-plugin.wasm".to_owned(),
+        &vec![PermissionType::RunCommands]
+    ));
+}
+
+#[test]
+fn plugin_name_from_url_matches_plugin_location() {
+    assert_eq!(
+        PermissionCache::plugin_name_from_url("file:/path/to/my-plugin.wasm", None).unwrap(),
+        "/path/to/my-plugin.wasm"
+    );
+    assert_eq!(
+        PermissionCache::plugin_name_from_url(
+            "file:my-plugin.wasm",
+            Some(PathBuf::from("/path/to"))
+        )
+        .unwrap(),
+        "/path/to/my-plugin.wasm"
+    );
+    assert_eq!(
+        PermissionCache::plugin_name_from_url("https://example.com/my-plugin.wasm", None).unwrap(),
+        "https://example.com/my-plugin.wasm"
+    );
+    assert!(PermissionCache::plugin_name_from_url("not a url", None).is_err());
+}
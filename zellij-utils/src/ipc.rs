@@ -171,6 +171,9 @@ pub enum ClientToServerMsg {
     HostTerminalThemeChanged {
         mode: HostTerminalThemeMode,
     },
+    /// The host terminal answered our kitty graphics protocol query, so kitty images can be sent
+    /// to it as they are rather than converted to sixel.
+    KittyGraphicsSupported,
 }
 
 // Types of messages sent from the server to the client
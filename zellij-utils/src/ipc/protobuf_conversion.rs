@@ -8,15 +8,15 @@ use crate::{
         ForegroundColorMsg, ForwardQueryToHostMsg, ForwardedReplyFromHostMsg,
         HostTerminalThemeChangedMsg,
         HostTerminalThemeIndication as ProtoHostTerminalThemeIndication,
-        InputMode as ProtoInputMode, KeyMsg, KillSessionMsg, LayoutMetadata as ProtoLayoutMetadata,
-        LogErrorMsg, LogMsg, PaneMetadata as ProtoPaneMetadata, PaneRenderUpdateMsg,
-        QueryTerminalSizeMsg, RenamedSessionMsg, RenderMsg,
-        ServerToClientMsg as ProtoServerToClientMsg, StartWebServerMsg, SubscribeToPaneRendersMsg,
-        SubscribedPaneClosedMsg, SwitchSessionMsg, TabMetadata as ProtoTabMetadata,
-        TerminalPixelDimensionsMsg, TerminalResizeMsg, UnblockCliPipeInputMsg,
-        UnblockInputThreadMsg, WebServerStartedMsg,
+        InputMode as ProtoInputMode, KeyMsg, KillSessionMsg, KittyGraphicsSupportedMsg,
+        LayoutMetadata as ProtoLayoutMetadata, LogErrorMsg, LogMsg,
+        PaneMetadata as ProtoPaneMetadata, PaneRenderUpdateMsg, QueryTerminalSizeMsg,
+        RenamedSessionMsg, RenderMsg, ServerToClientMsg as ProtoServerToClientMsg,
+        StartWebServerMsg, SubscribeToPaneRendersMsg, SubscribedPaneClosedMsg, SwitchSessionMsg,
+        TabMetadata as ProtoTabMetadata, TerminalPixelDimensionsMsg, TerminalResizeMsg,
+        UnblockCliPipeInputMsg, UnblockInputThreadMsg, WebServerStartedMsg,
     },
-    data::{HostTerminalThemeMode, InputMode, PaneId},
+    data::{CustomInputMode, HostTerminalThemeMode, InputMode, PaneId},
     errors::prelude::*,
     ipc::{
         ClientToServerMsg, ColorRegister, ExitReason, PaneReference, PixelDimensions,
@@ -146,6 +146,9 @@ impl From<ClientToServerMsg> for ProtoClientToServerMsg {
                     },
                 )
             },
+            ClientToServerMsg::KittyGraphicsSupported => {
+                client_to_server_msg::Message::KittyGraphicsSupported(KittyGraphicsSupportedMsg {})
+            },
         };
 
         ProtoClientToServerMsg {
@@ -287,6 +290,9 @@ impl TryFrom<ProtoClientToServerMsg> for ClientToServerMsg {
                     mode: proto_mode.into(),
                 })
             },
+            Some(client_to_server_msg::Message::KittyGraphicsSupported(_)) => {
+                Ok(ClientToServerMsg::KittyGraphicsSupported)
+            },
             None => Err(anyhow!("Empty ClientToServerMsg message")),
         }
     }
@@ -622,6 +628,7 @@ impl From<crate::input::cli_assets::CliAssets>
             max_panes: cli_assets.max_panes.map(|m| m as u32),
             force_run_layout_commands: cli_assets.force_run_layout_commands,
             cwd: cli_assets.cwd.map(|p| p.to_string_lossy().to_string()),
+            layout_args: cli_assets.layout_args.into_iter().collect(),
         }
     }
 }
@@ -651,6 +658,7 @@ impl TryFrom<crate::client_server_contract::client_server_contract::CliAssets>
             max_panes: cli_assets.max_panes.map(|m| m as usize),
             force_run_layout_commands: cli_assets.force_run_layout_commands,
             cwd: cli_assets.cwd.map(PathBuf::from),
+            layout_args: cli_assets.layout_args.into_iter().collect(),
         })
     }
 }
@@ -687,6 +695,7 @@ impl From<crate::input::options::Options>
                 crate::input::options::OnForceClose::Detach => ProtoOnForceClose::Detach as i32,
             }),
             scroll_buffer_size: options.scroll_buffer_size.map(|s| s as u32),
+            disk_scroll_buffer_size: options.disk_scroll_buffer_size.map(|s| s as u64),
             copy_command: options.copy_command,
             copy_clipboard: options.copy_clipboard.map(|c| match c {
                 crate::input::options::Clipboard::System => ProtoClipboard::System as i32,
@@ -703,6 +712,7 @@ impl From<crate::input::options::Options>
             session_serialization: options.session_serialization,
             serialize_pane_viewport: options.serialize_pane_viewport,
             scrollback_lines_to_serialize: options.scrollback_lines_to_serialize.map(|s| s as u32),
+            serialize_full_scrollback: options.serialize_full_scrollback,
             styled_underlines: options.styled_underlines,
             serialization_interval: options.serialization_interval,
             disable_session_metadata: options.disable_session_metadata,
@@ -732,6 +742,7 @@ impl From<crate::input::options::Options>
             visual_bell: options.visual_bell,
             focus_follows_mouse: options.focus_follows_mouse,
             mouse_click_through: options.mouse_click_through,
+            key_sequence_timeout: options.key_sequence_timeout,
         }
     }
 }
@@ -776,6 +787,7 @@ impl TryFrom<crate::client_server_contract::client_server_contract::Options>
                 })
                 .transpose()?,
             scroll_buffer_size: options.scroll_buffer_size.map(|s| s as usize),
+            disk_scroll_buffer_size: options.disk_scroll_buffer_size.map(|s| s as usize),
             copy_command: options.copy_command,
             copy_clipboard: options
                 .copy_clipboard
@@ -796,6 +808,7 @@ impl TryFrom<crate::client_server_contract::client_server_contract::Options>
             scrollback_lines_to_serialize: options
                 .scrollback_lines_to_serialize
                 .map(|s| s as usize),
+            serialize_full_scrollback: options.serialize_full_scrollback,
             styled_underlines: options.styled_underlines,
             serialization_interval: options.serialization_interval,
             disable_session_metadata: options.disable_session_metadata,
@@ -829,6 +842,7 @@ impl TryFrom<crate::client_server_contract::client_server_contract::Options>
             visual_bell: options.visual_bell,
             focus_follows_mouse: options.focus_follows_mouse,
             mouse_click_through: options.mouse_click_through,
+            key_sequence_timeout: options.key_sequence_timeout,
         })
     }
 }
@@ -856,9 +870,13 @@ impl From<crate::input::actions::Action>
             CloseTerminalPaneAction,
             ConfirmAction,
             CopyAction,
+            CopyLastCommandOutputAction,
+            CopyModeMoveAction,
+            CopyModeSelectAction,
             CurrentTabInfoAction,
             DenyAction,
             DetachAction,
+            DumpCommandOutputAction,
             DumpLayoutAction,
             DumpScreenAction,
             EditFileAction,
@@ -925,6 +943,8 @@ impl From<crate::input::actions::Action>
             RenameTabAction,
             RenameTabByIdAction,
             RenameTerminalPaneAction,
+            ReopenClosedPaneAction,
+            ReopenClosedTabAction,
             ResizeAction,
             ResizeByPaneIdAction,
             RunAction,
@@ -934,6 +954,8 @@ impl From<crate::input::actions::Action>
             ScrollDownByPaneIdAction,
             ScrollToBottomAction,
             ScrollToBottomByPaneIdAction,
+            ScrollToNextPromptAction,
+            ScrollToPreviousPromptAction,
             ScrollToTopAction,
             ScrollToTopByPaneIdAction,
             ScrollUpAction,
@@ -943,14 +965,19 @@ impl From<crate::input::actions::Action>
             SearchAction,
             SearchInputAction,
             SearchToggleOptionAction,
+            SelectLastCommandOutputAction,
             SetDarkThemeAction,
             SetLightThemeAction,
             SetPaneBorderlessAction,
             SetPaneColorAction,
+            SetSyncGroupAction,
             ShowFloatingPanesAction,
+            ShowPaneHintsAction,
             SkipConfirmAction,
             StackPanesAction,
             StartOrReloadPluginAction,
+            StartRecordingAction,
+            StopRecordingAction,
             SwitchFocusAction,
             SwitchModeForAllClientsAction,
             SwitchSessionAction,
@@ -971,6 +998,8 @@ impl From<crate::input::actions::Action>
             TogglePaneInGroupAction,
             TogglePanePinnedAction,
             TogglePanePinnedByPaneIdAction,
+            ToggleScratchpadAction,
+            ToggleSyncGroupAction,
             ToggleTabAction,
             ToggleThemeAction,
             UndoRenamePaneAction,
@@ -1116,6 +1145,42 @@ impl From<crate::input::actions::Action>
             crate::input::actions::Action::HalfPageScrollDown => {
                 ActionType::HalfPageScrollDown(HalfPageScrollDownAction {})
             },
+            crate::input::actions::Action::ScrollToPreviousPrompt => {
+                ActionType::ScrollToPreviousPrompt(ScrollToPreviousPromptAction {})
+            },
+            crate::input::actions::Action::ScrollToNextPrompt => {
+                ActionType::ScrollToNextPrompt(ScrollToNextPromptAction {})
+            },
+            crate::input::actions::Action::SelectLastCommandOutput => {
+                ActionType::SelectLastCommandOutput(SelectLastCommandOutputAction {})
+            },
+            crate::input::actions::Action::CopyLastCommandOutput => {
+                ActionType::CopyLastCommandOutput(CopyLastCommandOutputAction {})
+            },
+            crate::input::actions::Action::DumpCommandOutput {
+                file_path,
+                pane_id,
+                index,
+            } => ActionType::DumpCommandOutput(DumpCommandOutputAction {
+                file_path,
+                pane_id: pane_id.map(|p| p.into()),
+                index: index as u32,
+            }),
+            crate::input::actions::Action::StartRecording {
+                output,
+                pane_id,
+                whole_tab,
+            } => ActionType::StartRecording(StartRecordingAction {
+                output,
+                pane_id: pane_id.map(|p| p.into()),
+                whole_tab,
+            }),
+            crate::input::actions::Action::StopRecording { pane_id, whole_tab } => {
+                ActionType::StopRecording(StopRecordingAction {
+                    pane_id: pane_id.map(|p| p.into()),
+                    whole_tab,
+                })
+            },
             crate::input::actions::Action::ToggleFocusFullscreen => {
                 ActionType::ToggleFocusFullscreen(ToggleFocusFullscreenAction {})
             },
@@ -1416,6 +1481,16 @@ impl From<crate::input::actions::Action>
                     option: search_option_to_proto_i32(option),
                 })
             },
+            crate::input::actions::Action::CopyModeMove { motion } => {
+                ActionType::CopyModeMove(CopyModeMoveAction {
+                    motion: copy_mode_motion_to_proto_i32(motion),
+                })
+            },
+            crate::input::actions::Action::CopyModeSelect { kind } => {
+                ActionType::CopyModeSelect(CopyModeSelectAction {
+                    kind: copy_selection_kind_to_proto_i32(kind),
+                })
+            },
             crate::input::actions::Action::ToggleMouseMode => {
                 ActionType::ToggleMouseMode(ToggleMouseModeAction {})
             },
@@ -1662,6 +1737,32 @@ impl From<crate::input::actions::Action>
             crate::input::actions::Action::ToggleGroupMarking => {
                 ActionType::ToggleGroupMarking(ToggleGroupMarkingAction {})
             },
+            crate::input::actions::Action::ToggleSyncGroup => {
+                ActionType::ToggleSyncGroup(ToggleSyncGroupAction {})
+            },
+            crate::input::actions::Action::ShowPaneHints => {
+                ActionType::ShowPaneHints(ShowPaneHintsAction {})
+            },
+            crate::input::actions::Action::ReopenClosedPane => {
+                ActionType::ReopenClosedPane(ReopenClosedPaneAction {})
+            },
+            crate::input::actions::Action::ReopenClosedTab => {
+                ActionType::ReopenClosedTab(ReopenClosedTabAction {})
+            },
+            crate::input::actions::Action::ToggleScratchpad {
+                name,
+                command,
+                coordinates,
+            } => ActionType::ToggleScratchpad(ToggleScratchpadAction {
+                name,
+                command: command.map(|c| c.into()),
+                coordinates: coordinates.map(|c| c.into()),
+            }),
+            crate::input::actions::Action::SetSyncGroup { pane_ids } => {
+                ActionType::SetSyncGroup(SetSyncGroupAction {
+                    pane_ids: pane_ids.into_iter().map(|id| id.into()).collect(),
+                })
+            },
             crate::input::actions::Action::SaveSession => {
                 ActionType::SaveSession(SaveSessionAction {})
             },
@@ -1974,6 +2075,44 @@ impl TryFrom<crate::client_server_contract::client_server_contract::Action>
             ActionType::HalfPageScrollDown(_) => {
                 Ok(crate::input::actions::Action::HalfPageScrollDown)
             },
+            ActionType::ScrollToPreviousPrompt(_) => {
+                Ok(crate::input::actions::Action::ScrollToPreviousPrompt)
+            },
+            ActionType::ScrollToNextPrompt(_) => {
+                Ok(crate::input::actions::Action::ScrollToNextPrompt)
+            },
+            ActionType::SelectLastCommandOutput(_) => {
+                Ok(crate::input::actions::Action::SelectLastCommandOutput)
+            },
+            ActionType::CopyLastCommandOutput(_) => {
+                Ok(crate::input::actions::Action::CopyLastCommandOutput)
+            },
+            ActionType::DumpCommandOutput(dump_command_output_action) => {
+                Ok(crate::input::actions::Action::DumpCommandOutput {
+                    file_path: dump_command_output_action.file_path,
+                    pane_id: dump_command_output_action
+                        .pane_id
+                        .and_then(|p| p.try_into().ok()),
+                    index: dump_command_output_action.index as usize,
+                })
+            },
+            ActionType::StartRecording(start_recording_action) => {
+                Ok(crate::input::actions::Action::StartRecording {
+                    output: start_recording_action.output,
+                    pane_id: start_recording_action
+                        .pane_id
+                        .and_then(|p| p.try_into().ok()),
+                    whole_tab: start_recording_action.whole_tab,
+                })
+            },
+            ActionType::StopRecording(stop_recording_action) => {
+                Ok(crate::input::actions::Action::StopRecording {
+                    pane_id: stop_recording_action
+                        .pane_id
+                        .and_then(|p| p.try_into().ok()),
+                    whole_tab: stop_recording_action.whole_tab,
+                })
+            },
             ActionType::ToggleFocusFullscreen(_) => {
                 Ok(crate::input::actions::Action::ToggleFocusFullscreen)
             },
@@ -2283,6 +2422,16 @@ impl TryFrom<crate::client_server_contract::client_server_contract::Action>
                     option: proto_i32_to_search_option(search_toggle_action.option)?,
                 })
             },
+            ActionType::CopyModeMove(copy_mode_move_action) => {
+                Ok(crate::input::actions::Action::CopyModeMove {
+                    motion: proto_i32_to_copy_mode_motion(copy_mode_move_action.motion)?,
+                })
+            },
+            ActionType::CopyModeSelect(copy_mode_select_action) => {
+                Ok(crate::input::actions::Action::CopyModeSelect {
+                    kind: proto_i32_to_copy_selection_kind(copy_mode_select_action.kind)?,
+                })
+            },
             ActionType::ToggleMouseMode(_) => Ok(crate::input::actions::Action::ToggleMouseMode),
             ActionType::PreviousSwapLayout(_) => {
                 Ok(crate::input::actions::Action::PreviousSwapLayout)
@@ -2546,6 +2695,32 @@ impl TryFrom<crate::client_server_contract::client_server_contract::Action>
             ActionType::ToggleGroupMarking(_) => {
                 Ok(crate::input::actions::Action::ToggleGroupMarking)
             },
+            ActionType::ToggleSyncGroup(_) => Ok(crate::input::actions::Action::ToggleSyncGroup),
+            ActionType::ShowPaneHints(_) => Ok(crate::input::actions::Action::ShowPaneHints),
+            ActionType::ReopenClosedPane(_) => Ok(crate::input::actions::Action::ReopenClosedPane),
+            ActionType::ReopenClosedTab(_) => Ok(crate::input::actions::Action::ReopenClosedTab),
+            ActionType::ToggleScratchpad(toggle_scratchpad_action) => {
+                Ok(crate::input::actions::Action::ToggleScratchpad {
+                    name: toggle_scratchpad_action.name,
+                    command: toggle_scratchpad_action
+                        .command
+                        .map(|c| c.try_into())
+                        .transpose()?,
+                    coordinates: toggle_scratchpad_action
+                        .coordinates
+                        .map(|c| c.try_into())
+                        .transpose()?,
+                })
+            },
+            ActionType::SetSyncGroup(set_sync_group_action) => {
+                Ok(crate::input::actions::Action::SetSyncGroup {
+                    pane_ids: set_sync_group_action
+                        .pane_ids
+                        .into_iter()
+                        .map(|id| id.try_into())
+                        .collect::<Result<Vec<_>>>()?,
+                })
+            },
             ActionType::SetPaneColor(set_pane_color_action) => {
                 Ok(crate::input::actions::Action::SetPaneColor {
                     pane_id: set_pane_color_action
@@ -2914,6 +3089,7 @@ impl From<crate::data::LayoutMetadata> for ProtoLayoutMetadata {
             tabs: metadata.tabs.into_iter().map(|t| t.into()).collect(),
             creation_time: metadata.creation_time,
             update_time: metadata.update_time,
+            imports: metadata.imports,
         }
     }
 }
@@ -2930,6 +3106,7 @@ impl TryFrom<ProtoLayoutMetadata> for crate::data::LayoutMetadata {
             tabs,
             creation_time: proto_metadata.creation_time,
             update_time: proto_metadata.update_time,
+            imports: proto_metadata.imports,
         })
     }
 }
@@ -3048,6 +3225,9 @@ fn input_mode_to_proto_i32(mode: InputMode) -> i32 {
         InputMode::Move => ProtoInputMode::Move as i32,
         InputMode::Prompt => ProtoInputMode::Prompt as i32,
         InputMode::Tmux => ProtoInputMode::Tmux as i32,
+        InputMode::Copy => ProtoInputMode::Copy as i32,
+        // custom modes are sent by id, see `CustomInputMode`
+        InputMode::Custom(custom_input_mode) => custom_input_mode.id() as i32,
     }
 }
 
@@ -3067,7 +3247,13 @@ fn proto_i32_to_input_mode(i: i32) -> Result<InputMode> {
         Some(ProtoInputMode::Move) => Ok(InputMode::Move),
         Some(ProtoInputMode::Prompt) => Ok(InputMode::Prompt),
         Some(ProtoInputMode::Tmux) => Ok(InputMode::Tmux),
-        _ => Err(anyhow!("Invalid InputMode value: {}", i)),
+        Some(ProtoInputMode::Copy) => Ok(InputMode::Copy),
+        Some(ProtoInputMode::Unspecified) => Err(anyhow!("Invalid InputMode value: {}", i)),
+        None => u32::try_from(i)
+            .ok()
+            .and_then(CustomInputMode::from_id)
+            .map(InputMode::Custom)
+            .ok_or_else(|| anyhow!("Invalid InputMode value: {}", i)),
     }
 }
 
@@ -3090,6 +3276,15 @@ fn direction_to_proto_i32(direction: crate::data::Direction) -> i32 {
     }
 }
 
+fn restart_policy_to_proto_i32(policy: crate::input::command::RestartPolicy) -> i32 {
+    use crate::client_server_contract::client_server_contract::RestartPolicy as ProtoRestartPolicy;
+    match policy {
+        crate::input::command::RestartPolicy::Never => ProtoRestartPolicy::Never as i32,
+        crate::input::command::RestartPolicy::OnFailure => ProtoRestartPolicy::OnFailure as i32,
+        crate::input::command::RestartPolicy::Always => ProtoRestartPolicy::Always as i32,
+    }
+}
+
 fn search_direction_to_proto_i32(direction: crate::input::actions::SearchDirection) -> i32 {
     use crate::client_server_contract::client_server_contract::SearchDirection as ProtoSearchDirection;
     match direction {
@@ -3106,6 +3301,35 @@ fn search_option_to_proto_i32(option: crate::input::actions::SearchOption) -> i3
         },
         crate::input::actions::SearchOption::Wrap => ProtoSearchOption::Wrap as i32,
         crate::input::actions::SearchOption::WholeWord => ProtoSearchOption::WholeWord as i32,
+        crate::input::actions::SearchOption::Regex => ProtoSearchOption::Regex as i32,
+    }
+}
+
+fn copy_mode_motion_to_proto_i32(motion: crate::input::actions::CopyModeMotion) -> i32 {
+    use crate::client_server_contract::client_server_contract::CopyModeMotion as ProtoCopyModeMotion;
+    use crate::input::actions::CopyModeMotion;
+    match motion {
+        CopyModeMotion::Left => ProtoCopyModeMotion::Left as i32,
+        CopyModeMotion::Right => ProtoCopyModeMotion::Right as i32,
+        CopyModeMotion::Up => ProtoCopyModeMotion::Up as i32,
+        CopyModeMotion::Down => ProtoCopyModeMotion::Down as i32,
+        CopyModeMotion::WordForward => ProtoCopyModeMotion::WordForward as i32,
+        CopyModeMotion::WordBackward => ProtoCopyModeMotion::WordBackward as i32,
+        CopyModeMotion::WordEnd => ProtoCopyModeMotion::WordEnd as i32,
+        CopyModeMotion::LineStart => ProtoCopyModeMotion::LineStart as i32,
+        CopyModeMotion::LineEnd => ProtoCopyModeMotion::LineEnd as i32,
+        CopyModeMotion::Top => ProtoCopyModeMotion::Top as i32,
+        CopyModeMotion::Bottom => ProtoCopyModeMotion::Bottom as i32,
+    }
+}
+
+fn copy_selection_kind_to_proto_i32(kind: crate::input::actions::CopySelectionKind) -> i32 {
+    use crate::client_server_contract::client_server_contract::CopySelectionKind as ProtoCopySelectionKind;
+    use crate::input::actions::CopySelectionKind;
+    match kind {
+        CopySelectionKind::Character => ProtoCopySelectionKind::Character as i32,
+        CopySelectionKind::Line => ProtoCopySelectionKind::Line as i32,
+        CopySelectionKind::Block => ProtoCopySelectionKind::Block as i32,
     }
 }
 
@@ -3172,6 +3396,7 @@ fn proto_i32_to_search_option(option: i32) -> Result<crate::input::actions::Sear
         x if x == ProtoSearchOption::CaseSensitivity as i32 => ProtoSearchOption::CaseSensitivity,
         x if x == ProtoSearchOption::WholeWord as i32 => ProtoSearchOption::WholeWord,
         x if x == ProtoSearchOption::Wrap as i32 => ProtoSearchOption::Wrap,
+        x if x == ProtoSearchOption::Regex as i32 => ProtoSearchOption::Regex,
         _ => return Err(anyhow!("Invalid SearchOption: {}", option)),
     };
     match proto_option {
@@ -3180,10 +3405,58 @@ fn proto_i32_to_search_option(option: i32) -> Result<crate::input::actions::Sear
         },
         ProtoSearchOption::Wrap => Ok(crate::input::actions::SearchOption::Wrap),
         ProtoSearchOption::WholeWord => Ok(crate::input::actions::SearchOption::WholeWord),
+        ProtoSearchOption::Regex => Ok(crate::input::actions::SearchOption::Regex),
         ProtoSearchOption::Unspecified => Err(anyhow!("Unspecified search option")),
     }
 }
 
+fn proto_i32_to_copy_mode_motion(motion: i32) -> Result<crate::input::actions::CopyModeMotion> {
+    use crate::client_server_contract::client_server_contract::CopyModeMotion as ProtoCopyModeMotion;
+    use crate::input::actions::CopyModeMotion;
+    match ProtoCopyModeMotion::from_i32(motion) {
+        Some(ProtoCopyModeMotion::Left) => Ok(CopyModeMotion::Left),
+        Some(ProtoCopyModeMotion::Right) => Ok(CopyModeMotion::Right),
+        Some(ProtoCopyModeMotion::Up) => Ok(CopyModeMotion::Up),
+        Some(ProtoCopyModeMotion::Down) => Ok(CopyModeMotion::Down),
+        Some(ProtoCopyModeMotion::WordForward) => Ok(CopyModeMotion::WordForward),
+        Some(ProtoCopyModeMotion::WordBackward) => Ok(CopyModeMotion::WordBackward),
+        Some(ProtoCopyModeMotion::WordEnd) => Ok(CopyModeMotion::WordEnd),
+        Some(ProtoCopyModeMotion::LineStart) => Ok(CopyModeMotion::LineStart),
+        Some(ProtoCopyModeMotion::LineEnd) => Ok(CopyModeMotion::LineEnd),
+        Some(ProtoCopyModeMotion::Top) => Ok(CopyModeMotion::Top),
+        Some(ProtoCopyModeMotion::Bottom) => Ok(CopyModeMotion::Bottom),
+        Some(ProtoCopyModeMotion::Unspecified) => Err(anyhow!("Unspecified copy mode motion")),
+        None => Err(anyhow!("Invalid CopyModeMotion: {}", motion)),
+    }
+}
+
+fn proto_i32_to_copy_selection_kind(kind: i32) -> Result<crate::input::actions::CopySelectionKind> {
+    use crate::client_server_contract::client_server_contract::CopySelectionKind as ProtoCopySelectionKind;
+    use crate::input::actions::CopySelectionKind;
+    match ProtoCopySelectionKind::from_i32(kind) {
+        Some(ProtoCopySelectionKind::Character) => Ok(CopySelectionKind::Character),
+        Some(ProtoCopySelectionKind::Line) => Ok(CopySelectionKind::Line),
+        Some(ProtoCopySelectionKind::Block) => Ok(CopySelectionKind::Block),
+        Some(ProtoCopySelectionKind::Unspecified) => {
+            Err(anyhow!("Unspecified copy selection kind"))
+        },
+        None => Err(anyhow!("Invalid CopySelectionKind: {}", kind)),
+    }
+}
+
+fn proto_i32_to_restart_policy(policy: i32) -> Result<crate::input::command::RestartPolicy> {
+    use crate::client_server_contract::client_server_contract::RestartPolicy as ProtoRestartPolicy;
+    match ProtoRestartPolicy::from_i32(policy) {
+        // older clients do not send a restart policy at all
+        Some(ProtoRestartPolicy::Unspecified) | Some(ProtoRestartPolicy::Never) => {
+            Ok(crate::input::command::RestartPolicy::Never)
+        },
+        Some(ProtoRestartPolicy::OnFailure) => Ok(crate::input::command::RestartPolicy::OnFailure),
+        Some(ProtoRestartPolicy::Always) => Ok(crate::input::command::RestartPolicy::Always),
+        None => Err(anyhow!("Invalid RestartPolicy: {}", policy)),
+    }
+}
+
 fn proto_i32_to_unblock_condition(condition: i32) -> Result<crate::data::UnblockCondition> {
     use crate::client_server_contract::client_server_contract::UnblockCondition as ProtoUnblockCondition;
     let proto_condition = match condition {
@@ -3602,6 +3875,9 @@ impl From<crate::input::command::RunCommandAction>
             hold_on_start: action.hold_on_start,
             originating_plugin: action.originating_plugin.map(|op| op.into()),
             use_terminal_title: action.use_terminal_title,
+            env: action.env.into_iter().collect(),
+            restart_policy: restart_policy_to_proto_i32(action.restart_policy),
+            max_restarts: action.max_restarts.map(|m| m as u32),
         }
     }
 }
@@ -3704,6 +3980,9 @@ impl From<crate::input::layout::Run>
                         hold_on_start: cmd.hold_on_start,
                         originating_plugin: cmd.originating_plugin.map(|op| op.into()),
                         use_terminal_title: cmd.use_terminal_title,
+                        env: cmd.env.into_iter().collect(),
+                        restart_policy: restart_policy_to_proto_i32(cmd.restart_policy),
+                        max_restarts: cmd.max_restarts.map(|m| m as u32),
                     },
                 )),
             },
@@ -3821,6 +4100,9 @@ impl From<crate::input::layout::TiledPaneLayout>
             is_expanded_in_stack: layout.is_expanded_in_stack,
             hide_floating_panes: layout.hide_floating_panes,
             pane_initial_contents: layout.pane_initial_contents,
+            pane_scrollback_file: layout
+                .pane_scrollback_file
+                .map(|p| p.to_string_lossy().to_string()),
             default_fg: layout.default_fg,
             default_bg: layout.default_bg,
         }
@@ -3842,10 +4124,14 @@ impl From<crate::input::layout::FloatingPaneLayout>
             focus: layout.focus,
             already_running: layout.already_running,
             pane_initial_contents: layout.pane_initial_contents,
+            pane_scrollback_file: layout
+                .pane_scrollback_file
+                .map(|p| p.to_string_lossy().to_string()),
             logical_position: layout.logical_position.map(|l| l as u32),
             borderless: layout.borderless,
             default_fg: layout.default_fg,
             default_bg: layout.default_bg,
+            scratchpad: layout.scratchpad,
         }
     }
 }
@@ -3914,21 +4200,60 @@ impl From<crate::input::layout::LayoutConstraint>
     fn from(constraint: crate::input::layout::LayoutConstraint) -> Self {
         use crate::client_server_contract::client_server_contract::LayoutConstraint as ProtoLayoutConstraint;
         match constraint {
+            crate::input::layout::LayoutConstraint::Combined(constraints) => Self {
+                constraint_type: ProtoLayoutConstraint::Combined as i32,
+                value: None,
+                constraints: constraints.into_iter().map(|c| c.into()).collect(),
+            },
+            crate::input::layout::LayoutConstraint::MinColumns(n) => Self {
+                constraint_type: ProtoLayoutConstraint::MinColumns as i32,
+                value: Some(n as u32),
+                constraints: vec![],
+            },
+            crate::input::layout::LayoutConstraint::MaxColumns(n) => Self {
+                constraint_type: ProtoLayoutConstraint::MaxColumns as i32,
+                value: Some(n as u32),
+                constraints: vec![],
+            },
+            crate::input::layout::LayoutConstraint::MinRows(n) => Self {
+                constraint_type: ProtoLayoutConstraint::MinRows as i32,
+                value: Some(n as u32),
+                constraints: vec![],
+            },
+            crate::input::layout::LayoutConstraint::MaxRows(n) => Self {
+                constraint_type: ProtoLayoutConstraint::MaxRows as i32,
+                value: Some(n as u32),
+                constraints: vec![],
+            },
+            crate::input::layout::LayoutConstraint::MinAspectRatio(n) => Self {
+                constraint_type: ProtoLayoutConstraint::MinAspectRatio as i32,
+                value: Some(n as u32),
+                constraints: vec![],
+            },
+            crate::input::layout::LayoutConstraint::MaxAspectRatio(n) => Self {
+                constraint_type: ProtoLayoutConstraint::MaxAspectRatio as i32,
+                value: Some(n as u32),
+                constraints: vec![],
+            },
             crate::input::layout::LayoutConstraint::MaxPanes(n) => Self {
                 constraint_type: ProtoLayoutConstraint::MaxPanes as i32,
                 value: Some(n as u32),
+                constraints: vec![],
             },
             crate::input::layout::LayoutConstraint::MinPanes(n) => Self {
                 constraint_type: ProtoLayoutConstraint::MinPanes as i32,
                 value: Some(n as u32),
+                constraints: vec![],
             },
             crate::input::layout::LayoutConstraint::ExactPanes(n) => Self {
                 constraint_type: ProtoLayoutConstraint::ExactPanes as i32,
                 value: Some(n as u32),
+                constraints: vec![],
             },
             crate::input::layout::LayoutConstraint::NoConstraint => Self {
                 constraint_type: ProtoLayoutConstraint::NoConstraint as i32,
                 value: None,
+                constraints: vec![],
             },
         }
     }
@@ -4089,6 +4414,9 @@ impl TryFrom<crate::client_server_contract::client_server_contract::Run>
                         .map(|op| op.try_into())
                         .transpose()?,
                     use_terminal_title: cmd.use_terminal_title,
+                    env: cmd.env.into_iter().collect(),
+                    restart_policy: proto_i32_to_restart_policy(cmd.restart_policy)?,
+                    max_restarts: cmd.max_restarts.map(|m| m as usize),
                 },
             )),
             RunType::EditFile(edit) => Ok(crate::input::layout::Run::EditFile(
@@ -4196,6 +4524,9 @@ impl TryFrom<crate::client_server_contract::client_server_contract::RunCommandAc
                 .map(|op| op.try_into())
                 .transpose()?,
             use_terminal_title: action.use_terminal_title,
+            env: action.env.into_iter().collect(),
+            restart_policy: proto_i32_to_restart_policy(action.restart_policy)?,
+            max_restarts: action.max_restarts.map(|m| m as usize),
         })
     }
 }
@@ -4254,6 +4585,7 @@ impl TryFrom<crate::client_server_contract::client_server_contract::TiledPaneLay
             run_instructions_to_ignore: vec![], // not represented in protobuf
             hide_floating_panes: layout.hide_floating_panes,
             pane_initial_contents: layout.pane_initial_contents,
+            pane_scrollback_file: layout.pane_scrollback_file.map(PathBuf::from),
             default_fg: layout.default_fg,
             default_bg: layout.default_bg,
         })
@@ -4286,10 +4618,12 @@ impl TryFrom<crate::client_server_contract::client_server_contract::FloatingPane
             focus: layout.focus,
             already_running: layout.already_running,
             pane_initial_contents: layout.pane_initial_contents,
+            pane_scrollback_file: layout.pane_scrollback_file.map(PathBuf::from),
             logical_position: layout.logical_position.map(|p| p as usize),
             borderless: layout.borderless,
             default_fg: layout.default_fg,
             default_bg: layout.default_bg,
+            scratchpad: layout.scratchpad,
         })
     }
 }
@@ -4400,6 +4734,61 @@ impl TryFrom<crate::client_server_contract::client_server_contract::LayoutConstr
             x if x == ProtoLayoutConstraint::NoConstraint as i32 => {
                 Ok(crate::input::layout::LayoutConstraint::NoConstraint)
             },
+            x if x == ProtoLayoutConstraint::MinColumns as i32 => {
+                let value = constraint
+                    .value
+                    .ok_or_else(|| anyhow!("MinColumns constraint missing value"))?
+                    as usize;
+                Ok(crate::input::layout::LayoutConstraint::MinColumns(value))
+            },
+            x if x == ProtoLayoutConstraint::MaxColumns as i32 => {
+                let value = constraint
+                    .value
+                    .ok_or_else(|| anyhow!("MaxColumns constraint missing value"))?
+                    as usize;
+                Ok(crate::input::layout::LayoutConstraint::MaxColumns(value))
+            },
+            x if x == ProtoLayoutConstraint::MinRows as i32 => {
+                let value = constraint
+                    .value
+                    .ok_or_else(|| anyhow!("MinRows constraint missing value"))?
+                    as usize;
+                Ok(crate::input::layout::LayoutConstraint::MinRows(value))
+            },
+            x if x == ProtoLayoutConstraint::MaxRows as i32 => {
+                let value = constraint
+                    .value
+                    .ok_or_else(|| anyhow!("MaxRows constraint missing value"))?
+                    as usize;
+                Ok(crate::input::layout::LayoutConstraint::MaxRows(value))
+            },
+            x if x == ProtoLayoutConstraint::MinAspectRatio as i32 => {
+                let value = constraint
+                    .value
+                    .ok_or_else(|| anyhow!("MinAspectRatio constraint missing value"))?
+                    as usize;
+                Ok(crate::input::layout::LayoutConstraint::MinAspectRatio(
+                    value,
+                ))
+            },
+            x if x == ProtoLayoutConstraint::MaxAspectRatio as i32 => {
+                let value = constraint
+                    .value
+                    .ok_or_else(|| anyhow!("MaxAspectRatio constraint missing value"))?
+                    as usize;
+                Ok(crate::input::layout::LayoutConstraint::MaxAspectRatio(
+                    value,
+                ))
+            },
+            x if x == ProtoLayoutConstraint::Combined as i32 => {
+                Ok(crate::input::layout::LayoutConstraint::Combined(
+                    constraint
+                        .constraints
+                        .into_iter()
+                        .map(|c| c.try_into())
+                        .collect::<Result<Vec<_>>>()?,
+                ))
+            },
             _ => Err(anyhow!(
                 "Invalid LayoutConstraint type: {}",
                 constraint.constraint_type
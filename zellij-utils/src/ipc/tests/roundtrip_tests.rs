@@ -1,12 +1,15 @@
 use super::test_framework::*;
 use crate::data::{
-    BareKey, CommandOrPlugin, ConnectToSession, Direction, FloatingPaneCoordinates,
-    HostTerminalThemeMode, InputMode, KeyModifier, KeyWithModifier, LayoutInfo, LayoutMetadata,
-    NewPanePlacement, OriginatingPlugin, PaneId, PluginTag, Resize, WebSharing,
+    BareKey, CommandOrPlugin, ConnectToSession, CustomInputMode, Direction,
+    FloatingPaneCoordinates, HostTerminalThemeMode, InputMode, KeyModifier, KeyWithModifier,
+    LayoutInfo, LayoutMetadata, NewPanePlacement, OriginatingPlugin, PaneId, PluginTag, Resize,
+    WebSharing,
+};
+use crate::input::actions::{
+    Action, CopyModeMotion, CopySelectionKind, SearchDirection, SearchOption,
 };
-use crate::input::actions::{Action, SearchDirection, SearchOption};
 use crate::input::cli_assets::CliAssets;
-use crate::input::command::{OpenFilePayload, RunCommand, RunCommandAction};
+use crate::input::command::{OpenFilePayload, RestartPolicy, RunCommand, RunCommandAction};
 use crate::input::layout::{
     FloatingPaneLayout, LayoutConstraint, PercentOrFixed, PluginAlias, PluginUserConfiguration,
     Run, RunPlugin, RunPluginLocation, RunPluginOrAlias, SplitDirection, SplitSize,
@@ -62,6 +65,18 @@ fn test_client_messages() {
             ..Default::default()
         },
     );
+    swap_tiled_layouts_1.insert(
+        LayoutConstraint::Combined(vec![
+            LayoutConstraint::MaxPanes(4),
+            LayoutConstraint::MinColumns(200),
+            LayoutConstraint::MaxRows(40),
+            LayoutConstraint::MinAspectRatio(250),
+        ]),
+        TiledPaneLayout {
+            name: Some("combined".to_owned()),
+            ..Default::default()
+        },
+    );
     swap_tiled_layouts_1.insert(
         LayoutConstraint::NoConstraint,
         TiledPaneLayout {
@@ -406,6 +421,7 @@ fn test_client_messages() {
             max_panes: Some(4),
             force_run_layout_commands: true,
             cwd: Some(PathBuf::from("/path/to/cwd")),
+            layout_args: BTreeMap::from([("project".to_owned(), "api".to_owned())]),
         },
         is_web_client: true,
     });
@@ -422,6 +438,7 @@ fn test_client_messages() {
             max_panes: Some(4),
             force_run_layout_commands: true,
             cwd: Some(PathBuf::from("/path/to/cwd")),
+            layout_args: BTreeMap::new(),
         },
         is_web_client: true,
     });
@@ -446,6 +463,7 @@ fn test_client_messages() {
                 mirror_session: Some(true),
                 on_force_close: Some(OnForceClose::Quit),
                 scroll_buffer_size: Some(100000),
+                disk_scroll_buffer_size: Some(1000000),
                 copy_command: Some("copy_command".to_owned()),
                 copy_clipboard: Some(Clipboard::System),
                 copy_on_select: Some(true),
@@ -457,6 +475,7 @@ fn test_client_messages() {
                 session_serialization: Some(true),
                 serialize_pane_viewport: Some(true),
                 scrollback_lines_to_serialize: Some(10000),
+                serialize_full_scrollback: Some(true),
                 styled_underlines: Some(true),
                 serialization_interval: Some(1),
                 disable_session_metadata: Some(true),
@@ -474,6 +493,7 @@ fn test_client_messages() {
                 enforce_https_for_localhost: Some(true),
                 post_command_discovery_hook: Some("post_command_discovery_hook".to_owned()),
                 client_async_worker_tasks: Some(16),
+                key_sequence_timeout: Some(500),
                 mouse_hover_effects: Some(false),
                 visual_bell: Some(true),
                 focus_follows_mouse: Some(false),
@@ -486,6 +506,7 @@ fn test_client_messages() {
             max_panes: Some(4),
             force_run_layout_commands: true,
             cwd: Some(PathBuf::from("/path/to/cwd")),
+            layout_args: BTreeMap::new(),
         },
         is_web_client: true,
     });
@@ -629,6 +650,16 @@ fn test_client_messages() {
         },
         is_web_client: true,
     });
+    test_client_roundtrip!(ClientToServerMsg::FirstClientConnected {
+        cli_assets: CliAssets {
+            configuration_options: Some(Options {
+                default_mode: Some(InputMode::Custom(CustomInputMode::new("git"))),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        is_web_client: true,
+    });
     test_client_roundtrip!(ClientToServerMsg::FirstClientConnected {
         cli_assets: CliAssets {
             configuration_options: Some(Options {
@@ -979,6 +1010,69 @@ fn test_client_messages() {
         client_id: Some(100),
         is_cli_client: true,
     });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::ScrollToPreviousPrompt,
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::ScrollToNextPrompt,
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::SelectLastCommandOutput,
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::CopyLastCommandOutput,
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::DumpCommandOutput {
+            file_path: Some("/path/to/file".to_owned()),
+            pane_id: Some(PaneId::Terminal(5)),
+            index: 2,
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::DumpCommandOutput {
+            file_path: None,
+            pane_id: None,
+            index: 0,
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::StartRecording {
+            output: "/path/to/demo.cast".to_owned(),
+            pane_id: Some(PaneId::Terminal(5)),
+            whole_tab: false,
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::StopRecording {
+            pane_id: None,
+            whole_tab: true,
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
     test_client_roundtrip!(ClientToServerMsg::Action {
         action: Action::PageScrollUp,
         terminal_id: Some(1),
@@ -1288,6 +1382,9 @@ fn test_client_messages() {
                 hold_on_start: false,
                 originating_plugin: None,
                 use_terminal_title: false,
+                env: BTreeMap::new(),
+                restart_policy: RestartPolicy::Never,
+                max_restarts: None,
             }),
             pane_name: Some("my_pane_name".to_owned()),
             coordinates: FloatingPaneCoordinates::new(
@@ -1320,6 +1417,9 @@ fn test_client_messages() {
                     context: demo_context.clone(),
                 }),
                 use_terminal_title: false,
+                env: demo_context.clone(),
+                restart_policy: RestartPolicy::OnFailure,
+                max_restarts: Some(3),
             }),
             pane_name: Some("my_pane_name".to_owned()),
             coordinates: FloatingPaneCoordinates::new(
@@ -1365,6 +1465,9 @@ fn test_client_messages() {
                     context: demo_context.clone(),
                 }),
                 use_terminal_title: false,
+                env: demo_context.clone(),
+                restart_policy: RestartPolicy::OnFailure,
+                max_restarts: Some(3),
             }),
             direction: Some(Direction::Right),
             pane_name: Some("my_pane_name".to_owned()),
@@ -1391,6 +1494,9 @@ fn test_client_messages() {
                     context: demo_context.clone(),
                 }),
                 use_terminal_title: false,
+                env: demo_context.clone(),
+                restart_policy: RestartPolicy::OnFailure,
+                max_restarts: Some(3),
             }),
             pane_name: Some("my_pane_name".to_owned()),
             near_current_pane: false,
@@ -1430,6 +1536,9 @@ fn test_client_messages() {
                     context: demo_context.clone(),
                 }),
                 use_terminal_title: false,
+                env: demo_context.clone(),
+                restart_policy: RestartPolicy::OnFailure,
+                max_restarts: Some(3),
             }),
             pane_name: Some("my_pane_name".to_owned()),
             near_current_pane: false,
@@ -1912,6 +2021,9 @@ fn test_client_messages() {
                     hold_on_start: false,
                     originating_plugin: None,
                     use_terminal_title: true,
+                    env: BTreeMap::new(),
+                    restart_policy: RestartPolicy::Never,
+                    max_restarts: None,
                 })),
                 ..Default::default()
             }),
@@ -1943,6 +2055,9 @@ fn test_client_messages() {
                         context: empty_context.clone(),
                     }),
                     use_terminal_title: true,
+                    env: demo_context.clone(),
+                    restart_policy: RestartPolicy::OnFailure,
+                    max_restarts: Some(3),
                 })),
                 ..Default::default()
             }),
@@ -2040,9 +2155,11 @@ fn test_client_messages() {
                     focus: Some(true),
                     already_running: true,
                     pane_initial_contents: Some("pane_initial_contents".to_owned()),
+                    pane_scrollback_file: Some(PathBuf::from("/path/to/scrollback_1")),
                     logical_position: Some(15),
                     default_fg: None,
                     default_bg: None,
+                    scratchpad: Some("notes".to_owned()),
                 },
                 FloatingPaneLayout {
                     name: Some("third floating layout".to_owned()),
@@ -2137,6 +2254,9 @@ fn test_client_messages() {
                     hold_on_start: false,
                     originating_plugin: None,
                     use_terminal_title: false,
+                    env: BTreeMap::new(),
+                    restart_policy: RestartPolicy::Never,
+                    max_restarts: None,
                 }),
                 CommandOrPlugin::Plugin(RunPluginOrAlias::RunPlugin(RunPlugin::default())),
             ]),
@@ -2245,6 +2365,9 @@ fn test_client_messages() {
                 hold_on_start: false,
                 originating_plugin: None,
                 use_terminal_title: false,
+                env: BTreeMap::new(),
+                restart_policy: RestartPolicy::Never,
+                max_restarts: None,
             },
             near_current_pane: false,
         },
@@ -2449,6 +2572,30 @@ fn test_client_messages() {
         client_id: Some(100),
         is_cli_client: true,
     });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::SearchToggleOption {
+            option: SearchOption::Regex,
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::CopyModeMove {
+            motion: CopyModeMotion::WordBackward,
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: false,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::CopyModeSelect {
+            kind: CopySelectionKind::Block,
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: false,
+    });
     test_client_roundtrip!(ClientToServerMsg::Action {
         action: Action::ToggleMouseMode,
         terminal_id: Some(1),
@@ -2948,6 +3095,77 @@ fn test_client_messages() {
         client_id: Some(100),
         is_cli_client: true,
     });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::ToggleSyncGroup,
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::ShowPaneHints,
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::ReopenClosedPane,
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::ReopenClosedTab,
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::ToggleScratchpad {
+            name: "notes".to_owned(),
+            command: None,
+            coordinates: None,
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::ToggleScratchpad {
+            name: "notes".to_owned(),
+            command: Some(RunCommandAction {
+                command: PathBuf::from("nvim"),
+                args: vec!["notes.md".to_owned()],
+                cwd: Some(PathBuf::from("/path/to/cwd")),
+                direction: None,
+                hold_on_close: true,
+                hold_on_start: false,
+                originating_plugin: None,
+                use_terminal_title: false,
+                env: BTreeMap::new(),
+                restart_policy: RestartPolicy::Never,
+                max_restarts: None,
+            }),
+            coordinates: FloatingPaneCoordinates::new(
+                None,
+                None,
+                Some("80%".to_owned()),
+                Some("20".to_owned()),
+                Some(true),
+                None,
+            ),
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
+    test_client_roundtrip!(ClientToServerMsg::Action {
+        action: Action::SetSyncGroup {
+            pane_ids: vec![PaneId::Terminal(0), PaneId::Plugin(1)],
+        },
+        terminal_id: Some(1),
+        client_id: Some(100),
+        is_cli_client: true,
+    });
     test_client_roundtrip!(ClientToServerMsg::Action {
         action: Action::SetPaneColor {
             pane_id: PaneId::Terminal(0),
@@ -3569,6 +3787,7 @@ fn test_client_messages() {
     test_client_roundtrip!(ClientToServerMsg::HostTerminalThemeChanged {
         mode: HostTerminalThemeMode::Light,
     });
+    test_client_roundtrip!(ClientToServerMsg::KittyGraphicsSupported);
 }
 
 fn test_server_messages() {
@@ -3654,7 +3873,8 @@ fn test_server_messages() {
                 LayoutMetadata {
                     tabs: vec![],
                     creation_time: "0".to_owned(),
-                    update_time: "0".to_owned()
+                    update_time: "0".to_owned(),
+                    imports: vec!["common-templates.kdl".to_owned()],
                 }
             )),
             cwd: Some(PathBuf::from("/path/to/cwd")),
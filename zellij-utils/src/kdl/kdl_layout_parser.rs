@@ -1,5 +1,7 @@
+use crate::envs::EnvironmentVariables;
+use crate::home::default_layout_dir;
 use crate::input::{
-    command::RunCommand,
+    command::{RestartPolicy, RunCommand},
     config::ConfigError,
     layout::{
         FloatingPaneLayout, Layout, LayoutConstraint, PercentOrFixed, PluginUserConfiguration, Run,
@@ -23,7 +25,7 @@ use crate::{
     kdl_string_arguments,
 };
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::vec::Vec;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -41,6 +43,13 @@ pub struct KdlLayoutParser<'a> {
     default_tab_template: Option<(TiledPaneLayout, Vec<FloatingPaneLayout>, KdlNode)>,
     new_tab_template: Option<(TiledPaneLayout, Vec<FloatingPaneLayout>)>,
     file_name: Option<PathBuf>,
+    layout_args: BTreeMap<String, String>,
+    layout_parameters: BTreeMap<String, String>,
+    layout_dir: Option<PathBuf>,
+    import_stack: Vec<PathBuf>,
+    imported_files: Vec<PathBuf>,
+    imported_swap_tiled_layouts: Vec<SwapTiledLayout>,
+    imported_swap_floating_layouts: Vec<SwapFloatingLayout>,
 }
 
 impl<'a> KdlLayoutParser<'a> {
@@ -48,7 +57,16 @@ impl<'a> KdlLayoutParser<'a> {
         raw_layout: &'a str,
         global_cwd: Option<PathBuf>,
         file_name: Option<String>,
+        layout_args: BTreeMap<String, String>,
+        layout_dir: Option<PathBuf>,
     ) -> Self {
+        let file_name = file_name.map(PathBuf::from);
+        // the file we're parsing is the root of the import chain, so that we can detect cycles
+        let import_stack = file_name
+            .as_ref()
+            .and_then(|f| f.canonicalize().ok())
+            .into_iter()
+            .collect();
         KdlLayoutParser {
             raw_layout,
             tab_templates: HashMap::new(),
@@ -56,12 +74,20 @@ impl<'a> KdlLayoutParser<'a> {
             default_tab_template: None,
             new_tab_template: None,
             global_cwd,
-            file_name: file_name.map(|f| PathBuf::from(f)),
+            file_name,
+            layout_args,
+            layout_parameters: BTreeMap::new(),
+            layout_dir,
+            import_stack,
+            imported_files: vec![],
+            imported_swap_tiled_layouts: vec![],
+            imported_swap_floating_layouts: vec![],
         }
     }
     fn is_a_reserved_word(&self, word: &str) -> bool {
         // note that it's important that none of these words happens to also be a config property,
-        // otherwise they might collide
+        // otherwise they might collide (the exception being "env", which is allowed at the root
+        // of the layout file as a config node, see parse())
         word == "pane"
             || word == "layout"
             || word == "pane_template"
@@ -74,8 +100,11 @@ impl<'a> KdlLayoutParser<'a> {
             || word == "children"
             || word == "tab"
             || word == "args"
+            || word == "env"
             || word == "close_on_exit"
             || word == "start_suspended"
+            || word == "restart"
+            || word == "max_restarts"
             || word == "borderless"
             || word == "focus"
             || word == "name"
@@ -86,6 +115,9 @@ impl<'a> KdlLayoutParser<'a> {
             || word == "swap_floating_layout"
             || word == "hide_floating_panes"
             || word == "contents_file"
+            || word == "scrollback_file"
+            || word == "scratchpad"
+            || word == "parameters"
     }
     fn is_a_valid_pane_property(&self, property_name: &str) -> bool {
         property_name == "borderless"
@@ -97,8 +129,11 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "edit"
             || property_name == "cwd"
             || property_name == "args"
+            || property_name == "env"
             || property_name == "close_on_exit"
             || property_name == "start_suspended"
+            || property_name == "restart"
+            || property_name == "max_restarts"
             || property_name == "split_direction"
             || property_name == "pane"
             || property_name == "children"
@@ -106,6 +141,7 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "expanded"
             || property_name == "exclude_from_sync"
             || property_name == "contents_file"
+            || property_name == "scrollback_file"
             || property_name == "default_fg"
             || property_name == "default_bg"
     }
@@ -118,16 +154,21 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "edit"
             || property_name == "cwd"
             || property_name == "args"
+            || property_name == "env"
             || property_name == "close_on_exit"
             || property_name == "start_suspended"
+            || property_name == "restart"
+            || property_name == "max_restarts"
             || property_name == "x"
             || property_name == "y"
             || property_name == "width"
             || property_name == "height"
             || property_name == "pinned"
             || property_name == "contents_file"
+            || property_name == "scrollback_file"
             || property_name == "default_fg"
             || property_name == "default_bg"
+            || property_name == "scratchpad"
     }
     fn is_a_valid_tab_property(&self, property_name: &str) -> bool {
         property_name == "focus"
@@ -139,6 +180,12 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "max_panes"
             || property_name == "min_panes"
             || property_name == "exact_panes"
+            || property_name == "min_columns"
+            || property_name == "max_columns"
+            || property_name == "min_rows"
+            || property_name == "max_rows"
+            || property_name == "min_aspect_ratio"
+            || property_name == "max_aspect_ratio"
             || property_name == "hide_floating_panes"
     }
     pub fn is_a_reserved_plugin_property(property_name: &str) -> bool {
@@ -407,6 +454,48 @@ impl<'a> KdlLayoutParser<'a> {
             None => Ok(None),
         }
     }
+    fn parse_env(
+        &self,
+        pane_node: &KdlNode,
+    ) -> Result<Option<BTreeMap<String, String>>, ConfigError> {
+        match kdl_get_child!(pane_node, "env") {
+            Some(kdl_env) => Ok(Some(
+                EnvironmentVariables::from_kdl(kdl_env)?
+                    .inner()
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect(),
+            )),
+            None => Ok(None),
+        }
+    }
+    fn parse_restart_policy(
+        &self,
+        pane_node: &KdlNode,
+    ) -> Result<Option<RestartPolicy>, ConfigError> {
+        match kdl_get_string_property_or_child_value_with_error!(pane_node, "restart") {
+            Some(restart_policy) => RestartPolicy::from_str(restart_policy)
+                .map(Some)
+                .map_err(|e| kdl_parsing_error!(e, pane_node)),
+            None => Ok(None),
+        }
+    }
+    fn parse_max_restarts(&self, pane_node: &KdlNode) -> Result<Option<usize>, ConfigError> {
+        match kdl_get_int_property_or_child_value!(pane_node, "max_restarts") {
+            Some(max_restarts) if max_restarts >= 0 => Ok(Some(max_restarts as usize)),
+            Some(_) => Err(kdl_parsing_error!(
+                format!("max_restarts should be a positive number"),
+                pane_node
+            )),
+            None => match kdl_property_or_child_value_node!(pane_node, "max_restarts") {
+                Some(node) => Err(kdl_parsing_error!(
+                    format!("max_restarts should be a number (eg. 5)"),
+                    node
+                )),
+                None => Ok(None),
+            },
+        }
+    }
     fn cwd_prefix(&self, tab_cwd: Option<&PathBuf>) -> Result<Option<PathBuf>, ConfigError> {
         Ok(match (&self.global_cwd, tab_cwd) {
             (Some(global_cwd), Some(tab_cwd)) => Some(global_cwd.join(tab_cwd)),
@@ -437,16 +526,22 @@ impl<'a> KdlLayoutParser<'a> {
         let edit = self.parse_path(pane_node, "edit")?;
         let cwd = self.parse_path(pane_node, "cwd")?;
         let args = self.parse_args(pane_node)?;
+        let env = self.parse_env(pane_node)?;
         let close_on_exit =
             kdl_get_bool_property_or_child_value_with_error!(pane_node, "close_on_exit");
         let start_suspended =
             kdl_get_bool_property_or_child_value_with_error!(pane_node, "start_suspended");
+        let restart_policy = self.parse_restart_policy(pane_node)?;
+        let max_restarts = self.parse_max_restarts(pane_node)?;
         if !is_template {
             self.assert_no_bare_attributes_in_pane_node(
                 &command,
                 &args,
+                &env,
                 &close_on_exit,
                 &start_suspended,
+                &restart_policy,
+                &max_restarts,
                 pane_node,
             )?;
         }
@@ -460,6 +555,9 @@ impl<'a> KdlLayoutParser<'a> {
                 cwd,
                 hold_on_close,
                 hold_on_start,
+                env: env.unwrap_or_default(),
+                restart_policy: restart_policy.unwrap_or_default(),
+                max_restarts,
                 ..Default::default()
             }))),
             (None, Some(edit), Some(cwd)) => {
@@ -538,6 +636,8 @@ impl<'a> KdlLayoutParser<'a> {
             kdl_get_bool_property_or_child_value_with_error!(kdl_node, "exclude_from_sync");
         let contents_file =
             kdl_get_string_property_or_child_value_with_error!(kdl_node, "contents_file");
+        let scrollback_file =
+            kdl_get_string_property_or_child_value_with_error!(kdl_node, "scrollback_file");
         let split_size = self.parse_split_size(kdl_node)?;
         let run = self.parse_command_plugin_or_edit_block(kdl_node)?;
         let children_split_direction = self.parse_split_direction(kdl_node)?;
@@ -580,6 +680,12 @@ impl<'a> KdlLayoutParser<'a> {
                     std::fs::read_to_string(parent_folder.join(contents_file)).ok()
                 })
         });
+        let pane_scrollback_file = scrollback_file.and_then(|scrollback_file| {
+            self.file_name
+                .as_ref()
+                .and_then(|f| f.parent())
+                .map(|parent_folder| parent_folder.join(scrollback_file))
+        });
         Ok(TiledPaneLayout {
             borderless,
             focus,
@@ -593,6 +699,7 @@ impl<'a> KdlLayoutParser<'a> {
             children_are_stacked,
             is_expanded_in_stack,
             pane_initial_contents,
+            pane_scrollback_file,
             default_fg,
             default_bg,
             ..Default::default()
@@ -615,10 +722,14 @@ impl<'a> KdlLayoutParser<'a> {
             .map(|name| name.to_string());
         let contents_file =
             kdl_get_string_property_or_child_value_with_error!(kdl_node, "contents_file");
+        let scrollback_file =
+            kdl_get_string_property_or_child_value_with_error!(kdl_node, "scrollback_file");
         let default_fg = kdl_get_string_property_or_child_value_with_error!(kdl_node, "default_fg")
             .map(|s| s.to_string());
         let default_bg = kdl_get_string_property_or_child_value_with_error!(kdl_node, "default_bg")
             .map(|s| s.to_string());
+        let scratchpad = kdl_get_string_property_or_child_value_with_error!(kdl_node, "scratchpad")
+            .map(|s| s.to_string());
         self.assert_no_mixed_children_and_properties(kdl_node)?;
         let pane_initial_contents = contents_file.and_then(|contents_file| {
             self.file_name
@@ -628,6 +739,12 @@ impl<'a> KdlLayoutParser<'a> {
                     std::fs::read_to_string(parent_folder.join(contents_file)).ok()
                 })
         });
+        let pane_scrollback_file = scrollback_file.and_then(|scrollback_file| {
+            self.file_name
+                .as_ref()
+                .and_then(|f| f.parent())
+                .map(|parent_folder| parent_folder.join(scrollback_file))
+        });
         Ok(FloatingPaneLayout {
             name,
             height,
@@ -639,8 +756,10 @@ impl<'a> KdlLayoutParser<'a> {
             pinned,
             borderless,
             pane_initial_contents,
+            pane_scrollback_file,
             default_fg,
             default_bg,
+            scratchpad,
             ..Default::default()
         })
     }
@@ -729,10 +848,13 @@ impl<'a> KdlLayoutParser<'a> {
                 let is_expanded_in_stack =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "expanded");
                 let args = self.parse_args(kdl_node)?;
+                let env = self.parse_env(kdl_node)?;
                 let close_on_exit =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
                 let start_suspended =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "start_suspended");
+                let restart_policy = self.parse_restart_policy(kdl_node)?;
+                let max_restarts = self.parse_max_restarts(kdl_node)?;
                 let split_size = self.parse_split_size(kdl_node)?;
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
                 let exclude_from_sync =
@@ -747,8 +869,11 @@ impl<'a> KdlLayoutParser<'a> {
                     &run,
                     &pane_template.run,
                     &args,
+                    &env,
                     &close_on_exit,
                     &start_suspended,
+                    &restart_policy,
+                    &max_restarts,
                     kdl_node,
                 )?;
                 self.insert_children_to_pane_template(
@@ -761,8 +886,10 @@ impl<'a> KdlLayoutParser<'a> {
                     // we need to do this because panes consuming a pane_template
                     // can have bare args without a command
                     pane_template_run_command.add_args(args);
+                    pane_template_run_command.add_env(env);
                     pane_template_run_command.add_close_on_exit(close_on_exit);
                     pane_template_run_command.add_start_suspended(start_suspended);
+                    pane_template_run_command.add_restart_policy(restart_policy, max_restarts);
                 };
                 if let Some(borderless) = borderless {
                     pane_template.borderless = Some(borderless);
@@ -835,17 +962,23 @@ impl<'a> KdlLayoutParser<'a> {
                 let name = kdl_get_string_property_or_child_value_with_error!(kdl_node, "name")
                     .map(|name| name.to_string());
                 let args = self.parse_args(kdl_node)?;
+                let env = self.parse_env(kdl_node)?;
                 let close_on_exit =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
                 let start_suspended =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "start_suspended");
+                let restart_policy = self.parse_restart_policy(kdl_node)?;
+                let max_restarts = self.parse_max_restarts(kdl_node)?;
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
                 self.assert_no_bare_attributes_in_pane_node_with_template(
                     &run,
                     &pane_template.run,
                     &args,
+                    &env,
                     &close_on_exit,
                     &start_suspended,
+                    &restart_policy,
+                    &max_restarts,
                     kdl_node,
                 )?;
                 pane_template.run = Run::merge(&pane_template.run, &run);
@@ -853,8 +986,10 @@ impl<'a> KdlLayoutParser<'a> {
                     // we need to do this because panes consuming a pane_template
                     // can have bare args without a command
                     pane_template_run_command.add_args(args);
+                    pane_template_run_command.add_env(env);
                     pane_template_run_command.add_close_on_exit(close_on_exit);
                     pane_template_run_command.add_start_suspended(start_suspended);
+                    pane_template_run_command.add_restart_policy(restart_policy, max_restarts);
                 };
                 if let Some(focus) = focus {
                     pane_template.focus = Some(focus);
@@ -889,17 +1024,23 @@ impl<'a> KdlLayoutParser<'a> {
                 let name = kdl_get_string_property_or_child_value_with_error!(kdl_node, "name")
                     .map(|name| name.to_string());
                 let args = self.parse_args(kdl_node)?;
+                let env = self.parse_env(kdl_node)?;
                 let close_on_exit =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
                 let start_suspended =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "start_suspended");
+                let restart_policy = self.parse_restart_policy(kdl_node)?;
+                let max_restarts = self.parse_max_restarts(kdl_node)?;
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
                 self.assert_no_bare_attributes_in_pane_node_with_template(
                     &run,
                     &pane_template.run,
                     &args,
+                    &env,
                     &close_on_exit,
                     &start_suspended,
+                    &restart_policy,
+                    &max_restarts,
                     kdl_node,
                 )?;
                 pane_template.run = Run::merge(&pane_template.run, &run);
@@ -907,8 +1048,10 @@ impl<'a> KdlLayoutParser<'a> {
                     // we need to do this because panes consuming a pane_template
                     // can have bare args without a command
                     pane_template_run_command.add_args(args);
+                    pane_template_run_command.add_env(env);
                     pane_template_run_command.add_close_on_exit(close_on_exit);
                     pane_template_run_command.add_start_suspended(start_suspended);
+                    pane_template_run_command.add_restart_policy(restart_policy, max_restarts);
                 };
                 if let Some(focus) = focus {
                     pane_template.focus = Some(focus);
@@ -1338,13 +1481,17 @@ impl<'a> KdlLayoutParser<'a> {
         }
         false
     }
+    #[allow(clippy::too_many_arguments)]
     fn assert_no_bare_attributes_in_pane_node_with_template(
         &self,
         pane_run: &Option<Run>,
         pane_template_run: &Option<Run>,
         args: &Option<Vec<String>>,
+        env: &Option<BTreeMap<String, String>>,
         close_on_exit: &Option<bool>,
         start_suspended: &Option<bool>,
+        restart_policy: &Option<RestartPolicy>,
+        max_restarts: &Option<usize>,
         pane_node: &KdlNode,
     ) -> Result<(), ConfigError> {
         if let (None, None, true) = (pane_run, pane_template_run, args.is_some()) {
@@ -1353,6 +1500,12 @@ impl<'a> KdlLayoutParser<'a> {
                 pane_node
             ));
         }
+        if let (None, None, true) = (pane_run, pane_template_run, env.is_some()) {
+            return Err(kdl_parsing_error!(
+                format!("env can only be specified if a command was specified either in the pane_template or in the pane"),
+                pane_node
+            ));
+        }
         if let (None, None, true) = (pane_run, pane_template_run, close_on_exit.is_some()) {
             return Err(kdl_parsing_error!(
                 format!("close_on_exit can only be specified if a command was specified either in the pane_template or in the pane"),
@@ -1365,14 +1518,28 @@ impl<'a> KdlLayoutParser<'a> {
                 pane_node
             ));
         }
+        if let (None, None, true) = (
+            pane_run,
+            pane_template_run,
+            restart_policy.is_some() || max_restarts.is_some(),
+        ) {
+            return Err(kdl_parsing_error!(
+                format!("restart and max_restarts can only be specified if a command was specified either in the pane_template or in the pane"),
+                pane_node
+            ));
+        }
         Ok(())
     }
+    #[allow(clippy::too_many_arguments)]
     fn assert_no_bare_attributes_in_pane_node(
         &self,
         command: &Option<PathBuf>,
         args: &Option<Vec<String>>,
+        env: &Option<BTreeMap<String, String>>,
         close_on_exit: &Option<bool>,
         start_suspended: &Option<bool>,
+        restart_policy: &Option<RestartPolicy>,
+        max_restarts: &Option<usize>,
         pane_node: &KdlNode,
     ) -> Result<(), ConfigError> {
         if command.is_none() {
@@ -1397,6 +1564,20 @@ impl<'a> KdlLayoutParser<'a> {
                     pane_node.span().len(),
                 ));
             }
+            if env.is_some() {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "env can only be set if a command was specified".into(),
+                    pane_node.span().offset(),
+                    pane_node.span().len(),
+                ));
+            }
+            if restart_policy.is_some() || max_restarts.is_some() {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "restart and max_restarts can only be set if a command was specified".into(),
+                    pane_node.span().offset(),
+                    pane_node.span().len(),
+                ));
+            }
         }
         Ok(())
     }
@@ -2049,7 +2230,103 @@ impl<'a> KdlLayoutParser<'a> {
                 layout_node
             ));
         }
-        Ok(constraint.unwrap_or(LayoutConstraint::NoConstraint))
+        let mut constraints: Vec<LayoutConstraint> = constraint.into_iter().collect();
+        constraints.append(&mut self.parse_size_constraints(layout_node)?);
+        match constraints.len() {
+            0 => Ok(LayoutConstraint::NoConstraint),
+            1 => Ok(constraints.remove(0)),
+            _ => Ok(LayoutConstraint::Combined(constraints)),
+        }
+    }
+    fn parse_size_constraints(
+        &self,
+        layout_node: &KdlNode,
+    ) -> Result<Vec<LayoutConstraint>, ConfigError> {
+        let mut constraints = vec![];
+        let min_columns = self.parse_size_constraint_value(layout_node, "min_columns")?;
+        let max_columns = self.parse_size_constraint_value(layout_node, "max_columns")?;
+        let min_rows = self.parse_size_constraint_value(layout_node, "min_rows")?;
+        let max_rows = self.parse_size_constraint_value(layout_node, "max_rows")?;
+        let min_aspect_ratio =
+            self.parse_aspect_ratio_constraint_value(layout_node, "min_aspect_ratio")?;
+        let max_aspect_ratio =
+            self.parse_aspect_ratio_constraint_value(layout_node, "max_aspect_ratio")?;
+        for (min, max, min_name, max_name) in [
+            (min_columns, max_columns, "min_columns", "max_columns"),
+            (min_rows, max_rows, "min_rows", "max_rows"),
+            (
+                min_aspect_ratio,
+                max_aspect_ratio,
+                "min_aspect_ratio",
+                "max_aspect_ratio",
+            ),
+        ] {
+            if let (Some(min), Some(max)) = (min, max) {
+                if min > max {
+                    return Err(kdl_parsing_error!(
+                        format!("{} cannot be greater than {}", min_name, max_name),
+                        layout_node
+                    ));
+                }
+            }
+        }
+        constraints.extend(min_columns.map(LayoutConstraint::MinColumns));
+        constraints.extend(max_columns.map(LayoutConstraint::MaxColumns));
+        constraints.extend(min_rows.map(LayoutConstraint::MinRows));
+        constraints.extend(max_rows.map(LayoutConstraint::MaxRows));
+        constraints.extend(min_aspect_ratio.map(LayoutConstraint::MinAspectRatio));
+        constraints.extend(max_aspect_ratio.map(LayoutConstraint::MaxAspectRatio));
+        Ok(constraints)
+    }
+    fn parse_size_constraint_value(
+        &self,
+        layout_node: &KdlNode,
+        constraint_name: &str,
+    ) -> Result<Option<usize>, ConfigError> {
+        match kdl_get_property_or_child!(layout_node, constraint_name) {
+            Some(entry) => match entry.value().as_i64() {
+                Some(value) if value >= 0 => Ok(Some(value as usize)),
+                _ => Err(kdl_parsing_error!(
+                    format!(
+                        "{} should be a fixed positive number (eg. 100), found: {}",
+                        constraint_name,
+                        entry.value()
+                    ),
+                    layout_node
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+    fn parse_aspect_ratio_constraint_value(
+        &self,
+        layout_node: &KdlNode,
+        constraint_name: &str,
+    ) -> Result<Option<usize>, ConfigError> {
+        // the aspect ratio is the amount of columns divided by the amount of rows, kept in
+        // hundredths so that the constraint can be compared and ordered
+        match kdl_get_property_or_child!(layout_node, constraint_name) {
+            Some(entry) => {
+                let aspect_ratio = entry
+                    .value()
+                    .as_f64()
+                    .or_else(|| entry.value().as_i64().map(|v| v as f64));
+                match aspect_ratio {
+                    Some(aspect_ratio) if aspect_ratio > 0.0 => {
+                        Ok(Some((aspect_ratio * 100.0).round() as usize))
+                    },
+                    _ => Err(kdl_parsing_error!(
+                        format!(
+                            "{} should be a positive number (eg. 2.5), found: {}",
+                            constraint_name,
+                            entry.value()
+                        ),
+                        layout_node
+                    )),
+                }
+            },
+            None => Ok(None),
+        }
     }
     fn populate_one_swap_tiled_layout(
         &self,
@@ -2397,7 +2674,13 @@ impl<'a> KdlLayoutParser<'a> {
         raw_swap_layouts: &str,
         mut existing_layout: Layout,
     ) -> Result<Layout, ConfigError> {
-        let kdl_swap_layout: KdlDocument = raw_swap_layouts.parse()?;
+        let mut kdl_swap_layout: KdlDocument = raw_swap_layouts.parse()?;
+        if !self.layout_parameters.is_empty() {
+            let layout_parameters = self.layout_parameters.clone();
+            for node in kdl_swap_layout.nodes_mut() {
+                self.substitute_layout_parameters(node, &layout_parameters, false);
+            }
+        }
         let mut swap_tiled_layouts = vec![];
         let mut swap_floating_layouts = vec![];
 
@@ -2440,8 +2723,308 @@ impl<'a> KdlLayoutParser<'a> {
             .append(&mut swap_floating_layouts);
         Ok(existing_layout)
     }
+    fn parse_layout_parameters(
+        &self,
+        layout_node: &KdlNode,
+    ) -> Result<BTreeMap<String, String>, ConfigError> {
+        // a parameter without a value is required, and must be supplied as a layout argument
+        let parameters_node = kdl_children_nodes!(layout_node)
+            .and_then(|children| children.iter().find(|c| kdl_name!(c) == "parameters"));
+        let mut parameters = BTreeMap::new();
+        for parameter in parameters_node
+            .and_then(|p| kdl_children_nodes!(p))
+            .unwrap_or(&[])
+        {
+            let parameter_name = kdl_name!(parameter);
+            let default_value = match parameter.entries().iter().next().map(|e| e.value()) {
+                Some(KdlValue::String(s) | KdlValue::RawString(s)) => Some(s.clone()),
+                Some(KdlValue::Base10(i)) => Some(i.to_string()),
+                Some(KdlValue::Bool(b)) => Some(b.to_string()),
+                Some(_) => {
+                    return Err(ConfigError::new_layout_kdl_error(
+                        format!(
+                            "Layout parameter '{}' must have a string, integer or boolean default",
+                            parameter_name
+                        ),
+                        parameter.span().offset(),
+                        parameter.span().len(),
+                    ));
+                },
+                None => None,
+            };
+            match self
+                .layout_args
+                .get(parameter_name)
+                .cloned()
+                .or(default_value)
+            {
+                Some(value) => {
+                    parameters.insert(parameter_name.to_owned(), value);
+                },
+                None => {
+                    return Err(ConfigError::new_layout_kdl_error(
+                        format!(
+                            "Missing value for layout parameter '{}', supply it with --layout-arg {}=<VALUE>",
+                            parameter_name, parameter_name
+                        ),
+                        parameter.span().offset(),
+                        parameter.span().len(),
+                    ));
+                },
+            }
+        }
+        let declared_node = parameters_node.unwrap_or(layout_node);
+        if let Some(unknown_arg) = self
+            .layout_args
+            .keys()
+            .find(|arg| !parameters.contains_key(arg.as_str()))
+        {
+            return Err(ConfigError::new_layout_kdl_error(
+                format!("Unknown layout parameter: '{}'", unknown_arg),
+                declared_node.span().offset(),
+                declared_node.span().len(),
+            ));
+        }
+        Ok(parameters)
+    }
+    fn substitute_layout_parameters(
+        &self,
+        kdl_node: &mut KdlNode,
+        parameters: &BTreeMap<String, String>,
+        is_inside_plugin: bool,
+    ) {
+        // we substitute in cwd, args, name and plugin configuration, leaving any ${VAR} that is
+        // not a declared parameter for environment variable expansion further down the line
+        let node_name = kdl_name!(kdl_node).to_owned();
+        let is_inside_plugin = is_inside_plugin || node_name == "plugin";
+        let substitutes_arguments =
+            is_inside_plugin || node_name == "cwd" || node_name == "args" || node_name == "name";
+        for entry in kdl_node.entries_mut() {
+            let should_substitute = match entry.name().map(|n| n.value()) {
+                Some(property_name) => {
+                    is_inside_plugin || property_name == "cwd" || property_name == "name"
+                },
+                None => substitutes_arguments,
+            };
+            if !should_substitute {
+                continue;
+            }
+            if let Some(value) = entry.value().as_string() {
+                let substituted = substitute_parameters_in(value, parameters);
+                if substituted != value {
+                    entry.set_value(KdlValue::String(substituted));
+                }
+            }
+        }
+        if let Some(children) = kdl_node.children_mut() {
+            for child in children.nodes_mut() {
+                if kdl_name!(child) != "parameters" {
+                    self.substitute_layout_parameters(child, parameters, is_inside_plugin);
+                }
+            }
+        }
+    }
+    fn apply_layout_parameters(&mut self, kdl_layout: &mut KdlDocument) -> Result<(), ConfigError> {
+        if let Some(layout_node) = kdl_layout
+            .nodes_mut()
+            .iter_mut()
+            .find(|n| kdl_name!(n) == "layout")
+        {
+            let parameters = self.parse_layout_parameters(layout_node)?;
+            if !parameters.is_empty() {
+                self.substitute_layout_parameters(layout_node, &parameters, false);
+            }
+            // kept so that they can also be substituted in imported files
+            self.layout_parameters = parameters;
+        }
+        Ok(())
+    }
+    fn resolve_import_path(&self, import_path: &str) -> Option<PathBuf> {
+        // imports are resolved relative to the importing file, and then to the layout dir
+        let import_path = PathBuf::from(import_path);
+        let candidates: Vec<PathBuf> = if import_path.is_absolute() {
+            vec![import_path]
+        } else {
+            self.file_name
+                .as_ref()
+                .and_then(|f| f.parent())
+                .filter(|d| !d.as_os_str().is_empty())
+                .map(|d| d.to_path_buf())
+                .into_iter()
+                .chain(self.layout_dir.clone().or_else(default_layout_dir))
+                .map(|d| d.join(&import_path))
+                .collect()
+        };
+        candidates.into_iter().find_map(|candidate| {
+            if candidate.is_file() {
+                Some(candidate)
+            } else if candidate.with_extension("kdl").is_file() {
+                Some(candidate.with_extension("kdl"))
+            } else {
+                None
+            }
+        })
+    }
+    fn import_error(
+        &self,
+        error: ConfigError,
+        import_node: &KdlNode,
+        import_path: &Path,
+        raw_import: &str,
+    ) -> ConfigError {
+        // errors are reported on the import node, since their spans belong to the imported file
+        let (error_message, offset) = match error {
+            ConfigError::KdlError(kdl_error) => (kdl_error.error_message, kdl_error.offset),
+            ConfigError::KdlDeserializationError(kdl_error) => (
+                format!(
+                    "Failed to deserialize KDL node: {}",
+                    kdl_error
+                        .help
+                        .or(kdl_error.label)
+                        .unwrap_or("invalid syntax")
+                ),
+                Some(kdl_error.span.offset()),
+            ),
+            e => (e.to_string(), None),
+        };
+        let location = offset
+            .map(|offset| {
+                let preceding = &raw_import[..offset.min(raw_import.len())];
+                let line = preceding.matches('\n').count() + 1;
+                let column = preceding.chars().rev().take_while(|c| *c != '\n').count() + 1;
+                format!(" (line {}, column {})", line, column)
+            })
+            .unwrap_or_default();
+        ConfigError::new_layout_kdl_error(
+            format!(
+                "Failed to import \"{}\"{}: {}",
+                import_path.display(),
+                location,
+                error_message
+            ),
+            import_node.span().offset(),
+            import_node.span().len(),
+        )
+    }
+    fn populate_imports(&mut self, kdl_document: &KdlDocument) -> Result<(), ConfigError> {
+        for import_node in kdl_document
+            .nodes()
+            .iter()
+            .filter(|n| kdl_name!(n) == "import")
+        {
+            let import_path = kdl_first_entry_as_string!(import_node).ok_or(
+                ConfigError::new_layout_kdl_error(
+                    "Imports must specify a layout file, eg. import \"common-templates.kdl\""
+                        .into(),
+                    import_node.span().offset(),
+                    import_node.span().len(),
+                ),
+            )?;
+            let resolved_import_path =
+                self.resolve_import_path(import_path)
+                    .ok_or(ConfigError::new_layout_kdl_error(
+                        format!("Could not find imported layout file \"{}\"", import_path),
+                        import_node.span().offset(),
+                        import_node.span().len(),
+                    ))?;
+            let canonical_import_path = resolved_import_path
+                .canonicalize()
+                .unwrap_or_else(|_| resolved_import_path.clone());
+            if self.import_stack.contains(&canonical_import_path) {
+                return Err(ConfigError::new_layout_kdl_error(
+                    format!(
+                        "Circular import: \"{}\" is already being imported",
+                        resolved_import_path.display()
+                    ),
+                    import_node.span().offset(),
+                    import_node.span().len(),
+                ));
+            }
+            let raw_import = std::fs::read_to_string(&resolved_import_path).map_err(|e| {
+                ConfigError::new_layout_kdl_error(
+                    format!(
+                        "Failed to read imported layout file \"{}\": {}",
+                        resolved_import_path.display(),
+                        e
+                    ),
+                    import_node.span().offset(),
+                    import_node.span().len(),
+                )
+            })?;
+            let mut import_parser = KdlLayoutParser::new(
+                &raw_import,
+                None,
+                Some(resolved_import_path.display().to_string()),
+                BTreeMap::new(),
+                self.layout_dir.clone(),
+            );
+            import_parser.layout_parameters = self.layout_parameters.clone();
+            import_parser.import_stack = self.import_stack.clone();
+            import_parser.import_stack.push(canonical_import_path);
+            import_parser.parse_imported_file().map_err(|e| {
+                self.import_error(e, import_node, &resolved_import_path, &raw_import)
+            })?;
+            // definitions in the importing file take precedence over imported ones
+            for (name, pane_template) in import_parser.pane_templates.drain() {
+                self.pane_templates.entry(name).or_insert(pane_template);
+            }
+            for (name, tab_template) in import_parser.tab_templates.drain() {
+                self.tab_templates.entry(name).or_insert(tab_template);
+            }
+            self.imported_swap_tiled_layouts
+                .append(&mut import_parser.imported_swap_tiled_layouts);
+            self.imported_swap_floating_layouts
+                .append(&mut import_parser.imported_swap_floating_layouts);
+            self.imported_files.push(resolved_import_path);
+            self.imported_files
+                .append(&mut import_parser.imported_files);
+        }
+        Ok(())
+    }
+    fn parse_imported_file(&mut self) -> Result<(), ConfigError> {
+        let mut kdl_document: KdlDocument = self.raw_layout.parse()?;
+        if !self.layout_parameters.is_empty() {
+            let layout_parameters = self.layout_parameters.clone();
+            for node in kdl_document.nodes_mut() {
+                self.substitute_layout_parameters(node, &layout_parameters, false);
+            }
+        }
+        for node in kdl_document.nodes() {
+            let node_name = kdl_name!(node);
+            if node_name != "import"
+                && node_name != "pane_template"
+                && node_name != "tab_template"
+                && node_name != "swap_tiled_layout"
+                && node_name != "swap_floating_layout"
+            {
+                return Err(ConfigError::new_layout_kdl_error(
+                    format!("Imported layout files can only contain import, pane_template, tab_template, swap_tiled_layout and swap_floating_layout nodes, found: '{}'", node_name),
+                    node.span().offset(),
+                    node.span().len(),
+                ));
+            }
+        }
+        self.populate_imports(&kdl_document)?;
+        let nodes = kdl_document.nodes();
+        self.populate_pane_templates(nodes, &kdl_document)?;
+        self.populate_tab_templates(nodes)?;
+        let mut swap_tiled_layouts = vec![];
+        let mut swap_floating_layouts = vec![];
+        self.populate_swap_tiled_layouts(nodes, &mut swap_tiled_layouts)?;
+        self.populate_swap_floating_layouts(nodes, &mut swap_floating_layouts)?;
+        self.imported_swap_tiled_layouts
+            .append(&mut swap_tiled_layouts);
+        self.imported_swap_floating_layouts
+            .append(&mut swap_floating_layouts);
+        Ok(())
+    }
+    pub fn imported_files(&self) -> &[PathBuf] {
+        &self.imported_files
+    }
     pub fn parse(&mut self) -> Result<Layout, ConfigError> {
-        let kdl_layout: KdlDocument = self.raw_layout.parse()?;
+        let mut kdl_layout: KdlDocument = self.raw_layout.parse()?;
+        self.apply_layout_parameters(&mut kdl_layout)?;
+        self.populate_imports(&kdl_layout)?;
         let layout_node = kdl_layout
             .nodes()
             .iter()
@@ -2457,10 +3040,11 @@ impl<'a> KdlLayoutParser<'a> {
             .filter(|n| kdl_name!(n) == "layout")
             .count()
             > 1;
-        let mut non_layout_nodes_in_root = kdl_layout
-            .nodes()
-            .iter()
-            .filter(|n| kdl_name!(n) != "layout" && self.is_a_reserved_word(kdl_name!(n)));
+        let mut non_layout_nodes_in_root = kdl_layout.nodes().iter().filter(|n| {
+            kdl_name!(n) != "layout"
+                && kdl_name!(n) != "env"
+                && self.is_a_reserved_word(kdl_name!(n))
+        });
         if let Some(first_non_layout_node) = non_layout_nodes_in_root.next() {
             return Err(ConfigError::new_layout_kdl_error(
                 "This node should be inside the main \"layout\" node".into(),
@@ -2478,8 +3062,9 @@ impl<'a> KdlLayoutParser<'a> {
         let mut child_tabs = vec![];
         let mut child_panes = vec![];
         let mut child_floating_panes = vec![];
-        let mut swap_tiled_layouts = vec![];
-        let mut swap_floating_layouts = vec![];
+        // imported swap layouts come first, as though the imported files were inlined at the top
+        let mut swap_tiled_layouts = std::mem::take(&mut self.imported_swap_tiled_layouts);
+        let mut swap_floating_layouts = std::mem::take(&mut self.imported_swap_floating_layouts);
         if let Some(children) = kdl_children_nodes!(layout_node) {
             self.populate_global_cwd(layout_node)?;
             self.populate_pane_templates(children, &kdl_layout)?;
@@ -2599,3 +3184,29 @@ impl<'a> KdlLayoutParser<'a> {
         }
     }
 }
+
+/// Replaces every `${NAME}` of a declared parameter in a single pass over `value`, so that
+/// parameter values are inserted verbatim and never substituted themselves
+fn substitute_parameters_in(value: &str, parameters: &BTreeMap<String, String>) -> String {
+    let mut substituted = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        substituted.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        match placeholder.find('}') {
+            Some(end) => {
+                match parameters.get(&placeholder[2..end]) {
+                    Some(parameter_value) => substituted.push_str(parameter_value),
+                    None => substituted.push_str(&placeholder[..=end]),
+                }
+                rest = &placeholder[end + 1..];
+            },
+            None => {
+                substituted.push_str(placeholder);
+                rest = "";
+            },
+        }
+    }
+    substituted.push_str(rest);
+    substituted
+}
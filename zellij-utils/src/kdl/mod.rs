@@ -1,14 +1,14 @@
 mod kdl_layout_parser;
 use crate::data::{
-    BareKey, Direction, FloatingPaneCoordinates, InputMode, KeyWithModifier, LayoutInfo,
-    LayoutMetadata, MultiplayerColors, Palette, PaletteColor, PaneId, PaneInfo, PaneManifest,
-    PermissionType, Resize, SessionInfo, StyleDeclaration, Styling, TabInfo, WebSharing,
-    DEFAULT_STYLES,
+    BareKey, CustomInputMode, CustomModeFallThrough, Direction, FloatingPaneCoordinates, InputMode,
+    KeyWithModifier, LayoutInfo, LayoutMetadata, MultiplayerColors, Palette, PaletteColor, PaneId,
+    PaneInfo, PaneManifest, PermissionType, Resize, SessionInfo, StyleDeclaration, Styling,
+    TabInfo, WebSharing, DEFAULT_STYLES,
 };
 use crate::envs::EnvironmentVariables;
 use crate::home::{find_default_config_dir, get_layout_dir};
 use crate::input::config::{Config, ConfigError, KdlError};
-use crate::input::keybinds::Keybinds;
+use crate::input::keybinds::{Keybinds, ModeKeySequences, ModeKeybinds};
 use crate::input::layout::{
     Layout, PercentOrFixed, PluginUserConfiguration, RunPlugin, RunPluginOrAlias, TabLayoutInfo,
 };
@@ -31,7 +31,9 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 
-use crate::input::actions::{Action, SearchDirection, SearchOption};
+use crate::input::actions::{
+    Action, CopyModeMotion, CopySelectionKind, SearchDirection, SearchOption,
+};
 use crate::input::command::RunCommandAction;
 
 #[macro_export]
@@ -58,6 +60,14 @@ macro_rules! parse_kdl_action_arguments {
                 "PageScrollDown" => Ok(Action::PageScrollDown),
                 "HalfPageScrollUp" => Ok(Action::HalfPageScrollUp),
                 "HalfPageScrollDown" => Ok(Action::HalfPageScrollDown),
+                "ScrollToPreviousPrompt" => Ok(Action::ScrollToPreviousPrompt),
+                "ScrollToNextPrompt" => Ok(Action::ScrollToNextPrompt),
+                "SelectLastCommandOutput" => Ok(Action::SelectLastCommandOutput),
+                "CopyLastCommandOutput" => Ok(Action::CopyLastCommandOutput),
+                "StopRecording" => Ok(Action::StopRecording {
+                    pane_id: None,
+                    whole_tab: false,
+                }),
                 "ToggleFocusFullscreen" => Ok(Action::ToggleFocusFullscreen),
                 "TogglePaneFrames" => Ok(Action::TogglePaneFrames),
                 "ToggleActiveSyncTab" => Ok(Action::ToggleActiveSyncTab),
@@ -329,18 +339,23 @@ macro_rules! kdl_document_name {
 #[macro_export]
 macro_rules! keys_from_kdl {
     ( $kdl_node:expr ) => {
-        kdl_string_arguments!($kdl_node)
-            .iter()
-            .map(|k| {
-                KeyWithModifier::from_str(k).map_err(|_| {
-                    ConfigError::new_kdl_error(
-                        format!("Invalid key: '{}'", k),
-                        $kdl_node.span().offset(),
-                        $kdl_node.span().len(),
-                    )
-                })
+        $crate::kdl::kdl_arguments_that_are_strings(
+            $kdl_node
+                .entries()
+                .iter()
+                .filter(|e| e.name().map(|n| n.value()) != Some("sequence")),
+        )?
+        .iter()
+        .map(|k| {
+            KeyWithModifier::from_str(k).map_err(|_| {
+                ConfigError::new_kdl_error(
+                    format!("Invalid key: '{}'", k),
+                    $kdl_node.span().offset(),
+                    $kdl_node.span().len(),
+                )
             })
-            .collect::<Result<_, _>>()?
+        })
+        .collect::<Result<_, _>>()?
     };
 }
 
@@ -414,6 +429,13 @@ pub fn kdl_child_bool_value_for_entry<'a>(
         .and_then(|cwd_value| cwd_value.value().as_bool())
 }
 
+// names that are not built-in modes are taken to be custom modes, whether these are defined is
+// only known once all of the keybinds are parsed (see `Keybinds::check_custom_modes_are_defined`)
+fn input_mode_or_custom_mode_from_str(mode_name: &str) -> InputMode {
+    InputMode::from_str(mode_name)
+        .unwrap_or_else(|_| InputMode::Custom(CustomInputMode::new(mode_name)))
+}
+
 impl Action {
     pub fn new_from_bytes(
         action_name: &str,
@@ -453,16 +475,9 @@ impl Action {
     ) -> Result<Self, ConfigError> {
         match action_name {
             "WriteChars" => Ok(Action::WriteChars { chars: string }),
-            "SwitchToMode" => match InputMode::from_str(string.as_str()) {
-                Ok(input_mode) => Ok(Action::SwitchToMode { input_mode }),
-                Err(_e) => {
-                    return Err(ConfigError::new_kdl_error(
-                        format!("Unknown InputMode '{}'", string),
-                        action_node.span().offset(),
-                        action_node.span().len(),
-                    ))
-                },
-            },
+            "SwitchToMode" => Ok(Action::SwitchToMode {
+                input_mode: input_mode_or_custom_mode_from_str(&string),
+            }),
             "Resize" => {
                 let mut resize: Option<Resize> = None;
                 let mut direction: Option<Direction> = None;
@@ -548,6 +563,16 @@ impl Action {
                 pane_id: None,
                 ansi: false,
             }),
+            "DumpCommandOutput" => Ok(Action::DumpCommandOutput {
+                file_path: Some(string),
+                pane_id: None,
+                index: 0,
+            }),
+            "StartRecording" => Ok(Action::StartRecording {
+                output: string,
+                pane_id: None,
+                whole_tab: false,
+            }),
             "DumpLayout" => Ok(Action::DumpLayout),
             "NewPane" => {
                 if string.is_empty() {
@@ -603,6 +628,26 @@ impl Action {
                     direction: search_direction,
                 })
             },
+            "CopyModeMove" => {
+                let motion = CopyModeMotion::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!("Invalid copy mode motion: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::CopyModeMove { motion })
+            },
+            "CopyModeSelect" => {
+                let kind = CopySelectionKind::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!("Invalid copy selection kind: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::CopyModeSelect { kind })
+            },
             "RenameSession" => Ok(Action::RenameSession { name: string }),
             _ => Err(ConfigError::new_kdl_error(
                 format!("Unsupported action: {}", action_name),
@@ -709,6 +754,32 @@ impl Action {
             Action::DumpScreen {
                 file_path: None, ..
             } => None,
+            Action::DumpCommandOutput {
+                file_path: Some(file),
+                ..
+            } => {
+                let mut node = KdlNode::new("DumpCommandOutput");
+                node.push(file.clone());
+                Some(node)
+            },
+            Action::DumpCommandOutput {
+                file_path: None, ..
+            } => None,
+            Action::StartRecording {
+                output,
+                pane_id: None,
+                whole_tab: false,
+            } => {
+                let mut node = KdlNode::new("StartRecording");
+                node.push(output.clone());
+                Some(node)
+            },
+            Action::StartRecording { .. } => None,
+            Action::StopRecording {
+                pane_id: None,
+                whole_tab: false,
+            } => Some(KdlNode::new("StopRecording")),
+            Action::StopRecording { .. } => None,
             Action::DumpLayout => Some(KdlNode::new("DumpLayout")),
             Action::EditScrollback { ansi } => {
                 let mut node = KdlNode::new("EditScrollback");
@@ -729,6 +800,10 @@ impl Action {
             Action::PageScrollDown => Some(KdlNode::new("PageScrollDown")),
             Action::HalfPageScrollUp => Some(KdlNode::new("HalfPageScrollUp")),
             Action::HalfPageScrollDown => Some(KdlNode::new("HalfPageScrollDown")),
+            Action::ScrollToPreviousPrompt => Some(KdlNode::new("ScrollToPreviousPrompt")),
+            Action::ScrollToNextPrompt => Some(KdlNode::new("ScrollToNextPrompt")),
+            Action::SelectLastCommandOutput => Some(KdlNode::new("SelectLastCommandOutput")),
+            Action::CopyLastCommandOutput => Some(KdlNode::new("CopyLastCommandOutput")),
             Action::ToggleFocusFullscreen => Some(KdlNode::new("ToggleFocusFullscreen")),
             Action::TogglePaneFrames => Some(KdlNode::new("TogglePaneFrames")),
             Action::ToggleActiveSyncTab => Some(KdlNode::new("ToggleActiveSyncTab")),
@@ -1239,6 +1314,16 @@ impl Action {
                 node.push(format!("{:?}", search_toggle_option));
                 Some(node)
             },
+            Action::CopyModeMove { motion } => {
+                let mut node = KdlNode::new("CopyModeMove");
+                node.push(format!("{:?}", motion));
+                Some(node)
+            },
+            Action::CopyModeSelect { kind } => {
+                let mut node = KdlNode::new("CopyModeSelect");
+                node.push(format!("{:?}", kind));
+                Some(node)
+            },
             Action::ToggleMouseMode => Some(KdlNode::new("ToggleMouseMode")),
             Action::PreviousSwapLayout => Some(KdlNode::new("PreviousSwapLayout")),
             Action::NextSwapLayout => Some(KdlNode::new("NextSwapLayout")),
@@ -1322,6 +1407,68 @@ impl Action {
             Action::TogglePanePinned => Some(KdlNode::new("TogglePanePinned")),
             Action::TogglePaneInGroup => Some(KdlNode::new("TogglePaneInGroup")),
             Action::ToggleGroupMarking => Some(KdlNode::new("ToggleGroupMarking")),
+            Action::ToggleSyncGroup => Some(KdlNode::new("ToggleSyncGroup")),
+            Action::ShowPaneHints => Some(KdlNode::new("ShowPaneHints")),
+            Action::ReopenClosedPane => Some(KdlNode::new("ReopenClosedPane")),
+            Action::ReopenClosedTab => Some(KdlNode::new("ReopenClosedTab")),
+            Action::ToggleScratchpad {
+                name,
+                command,
+                coordinates,
+            } => {
+                let mut node = KdlNode::new("ToggleScratchpad");
+                node.push(name.clone());
+                let mut node_children = KdlDocument::new();
+                if let Some(command) = command {
+                    let mut command_node = KdlNode::new("command");
+                    command_node.push(command.command.display().to_string());
+                    node_children.nodes_mut().push(command_node);
+                    if !command.args.is_empty() {
+                        let mut args_node = KdlNode::new("args");
+                        for arg in &command.args {
+                            args_node.push(arg.clone());
+                        }
+                        node_children.nodes_mut().push(args_node);
+                    }
+                    if let Some(cwd) = &command.cwd {
+                        let mut cwd_node = KdlNode::new("cwd");
+                        cwd_node.push(cwd.display().to_string());
+                        node_children.nodes_mut().push(cwd_node);
+                    }
+                    if !command.hold_on_close {
+                        let mut close_on_exit_node = KdlNode::new("close_on_exit");
+                        close_on_exit_node.push(KdlValue::Bool(true));
+                        node_children.nodes_mut().push(close_on_exit_node);
+                    }
+                }
+                if let Some(coordinates) = coordinates {
+                    let coordinates_to_serialize = [
+                        ("x", &coordinates.x),
+                        ("y", &coordinates.y),
+                        ("width", &coordinates.width),
+                        ("height", &coordinates.height),
+                    ];
+                    for (entry_name, coordinate) in coordinates_to_serialize {
+                        if let Some(coordinate) = coordinate {
+                            let mut coordinate_node = KdlNode::new(entry_name);
+                            coordinate_node.push(match coordinate {
+                                PercentOrFixed::Percent(percent) => format!("{}%", percent),
+                                PercentOrFixed::Fixed(fixed) => fixed.to_string(),
+                            });
+                            node_children.nodes_mut().push(coordinate_node);
+                        }
+                    }
+                    if let Some(pinned) = coordinates.pinned {
+                        let mut pinned_node = KdlNode::new("pinned");
+                        pinned_node.push(KdlValue::Bool(pinned));
+                        node_children.nodes_mut().push(pinned_node);
+                    }
+                }
+                if !node_children.nodes().is_empty() {
+                    node.set_children(node_children);
+                }
+                Some(node)
+            },
             _ => None,
         }
     }
@@ -1536,6 +1683,18 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
             "HalfPageScrollDown" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
+            "ScrollToPreviousPrompt" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "ScrollToNextPrompt" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "SelectLastCommandOutput" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "CopyLastCommandOutput" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "ToggleFocusFullscreen" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
@@ -1687,6 +1846,19 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                 action_arguments,
                 kdl_action
             ),
+            "DumpCommandOutput" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "StartRecording" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "StopRecording" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "DumpLayout" => parse_kdl_action_char_or_string_arguments!(
                 action_name,
                 action_arguments,
@@ -1930,6 +2102,16 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                 action_arguments,
                 kdl_action
             ),
+            "CopyModeMove" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "CopyModeSelect" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
             "Run" => {
                 let arguments = action_arguments.iter().copied();
                 let mut args = kdl_arguments_that_are_strings(arguments)?;
@@ -2250,6 +2432,72 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
             "TogglePanePinned" => Ok(Action::TogglePanePinned),
             "TogglePaneInGroup" => Ok(Action::TogglePaneInGroup),
             "ToggleGroupMarking" => Ok(Action::ToggleGroupMarking),
+            "ToggleSyncGroup" => Ok(Action::ToggleSyncGroup),
+            "ShowPaneHints" => Ok(Action::ShowPaneHints),
+            "ReopenClosedPane" => Ok(Action::ReopenClosedPane),
+            "ReopenClosedTab" => Ok(Action::ReopenClosedTab),
+            "ToggleScratchpad" => {
+                let arguments = action_arguments.iter().copied();
+                let mut args = kdl_arguments_that_are_strings(arguments)?;
+                if args.is_empty() {
+                    return Err(ConfigError::new_kdl_error(
+                        "No scratchpad name found in ToggleScratchpad".into(),
+                        kdl_action.span().offset(),
+                        kdl_action.span().len(),
+                    ));
+                }
+                let name = args.remove(0);
+                let command_metadata = action_children.first();
+                // commands are not run through a shell, so we expand ~ in their paths ourselves
+                let expand_home = |s: &str| shellexpand::tilde(s).into_owned();
+                let command = match command_metadata
+                    .and_then(|c_m| kdl_child_string_value_for_entry(c_m, "command"))
+                {
+                    Some(command) => {
+                        let args = match command_metadata.and_then(|c_m| c_m.get("args")) {
+                            Some(args) => kdl_arguments_that_are_strings(args.entries().iter())?
+                                .iter()
+                                .map(|arg| expand_home(arg))
+                                .collect(),
+                            None => vec![],
+                        };
+                        let cwd = command_metadata
+                            .and_then(|c_m| kdl_child_string_value_for_entry(c_m, "cwd"))
+                            .map(|cwd| PathBuf::from(expand_home(cwd)));
+                        let hold_on_close = command_metadata
+                            .and_then(|c_m| kdl_child_bool_value_for_entry(c_m, "close_on_exit"))
+                            .map(|close_on_exit| !close_on_exit)
+                            .unwrap_or(true);
+                        Some(RunCommandAction {
+                            command: PathBuf::from(expand_home(command)),
+                            args,
+                            cwd,
+                            hold_on_close,
+                            ..Default::default()
+                        })
+                    },
+                    None => None,
+                };
+                let coordinate = |entry_name| {
+                    command_metadata
+                        .and_then(|c_m| kdl_child_string_value_for_entry(c_m, entry_name))
+                        .map(|s| s.to_owned())
+                };
+                let pinned =
+                    command_metadata.and_then(|c_m| kdl_child_bool_value_for_entry(c_m, "pinned"));
+                Ok(Action::ToggleScratchpad {
+                    name,
+                    command,
+                    coordinates: FloatingPaneCoordinates::new(
+                        coordinate("x"),
+                        coordinate("y"),
+                        coordinate("width"),
+                        coordinate("height"),
+                        pinned,
+                        None,
+                    ),
+                })
+            },
             _ => Err(ConfigError::new_kdl_error(
                 format!("Unsupported action: {}", action_name).into(),
                 kdl_action.span().offset(),
@@ -2681,13 +2929,8 @@ impl Options {
             .map(|(theme, _entry)| theme.to_string());
         let theme_light = kdl_property_first_arg_as_string_or_error!(kdl_options, "theme_light")
             .map(|(theme, _entry)| theme.to_string());
-        let default_mode =
-            match kdl_property_first_arg_as_string_or_error!(kdl_options, "default_mode") {
-                Some((string, entry)) => Some(InputMode::from_str(string).map_err(|_| {
-                    kdl_parsing_error!(format!("Invalid input mode: '{}'", string), entry)
-                })?),
-                None => None,
-            };
+        let default_mode = kdl_property_first_arg_as_string_or_error!(kdl_options, "default_mode")
+            .map(|(string, _entry)| input_mode_or_custom_mode_from_str(string));
         let default_layout =
             kdl_property_first_arg_as_string_or_error!(kdl_options, "default_layout")
                 .map(|(string, _entry)| PathBuf::from(string));
@@ -2700,6 +2943,9 @@ impl Options {
         let scroll_buffer_size =
             kdl_property_first_arg_as_i64_or_error!(kdl_options, "scroll_buffer_size")
                 .map(|(scroll_buffer_size, _entry)| scroll_buffer_size as usize);
+        let disk_scroll_buffer_size =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "disk_scroll_buffer_size")
+                .map(|(disk_scroll_buffer_size, _entry)| disk_scroll_buffer_size as usize);
         let copy_command = kdl_property_first_arg_as_string_or_error!(kdl_options, "copy_command")
             .map(|(copy_command, _entry)| copy_command.to_string());
         let copy_clipboard =
@@ -2736,6 +2982,9 @@ impl Options {
         let scrollback_lines_to_serialize =
             kdl_property_first_arg_as_i64_or_error!(kdl_options, "scrollback_lines_to_serialize")
                 .map(|(v, _)| v as usize);
+        let serialize_full_scrollback =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "serialize_full_scrollback")
+                .map(|(v, _)| v);
         let styled_underlines =
             kdl_property_first_arg_as_bool_or_error!(kdl_options, "styled_underlines")
                 .map(|(v, _)| v);
@@ -2816,6 +3065,9 @@ impl Options {
                 },
                 None => None,
             };
+        let key_sequence_timeout =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "key_sequence_timeout")
+                .map(|(v, _)| v as u64);
         let visual_bell =
             kdl_property_first_arg_as_bool_or_error!(kdl_options, "visual_bell").map(|(v, _)| v);
         let focus_follows_mouse =
@@ -2841,6 +3093,7 @@ impl Options {
             mirror_session,
             on_force_close,
             scroll_buffer_size,
+            disk_scroll_buffer_size,
             copy_command,
             copy_clipboard,
             copy_on_select,
@@ -2852,6 +3105,7 @@ impl Options {
             session_serialization,
             serialize_pane_viewport,
             scrollback_lines_to_serialize,
+            serialize_full_scrollback,
             styled_underlines,
             serialization_interval,
             disable_session_metadata,
@@ -2873,6 +3127,7 @@ impl Options {
             enforce_https_for_localhost,
             post_command_discovery_hook,
             client_async_worker_tasks,
+            key_sequence_timeout,
         })
     }
     pub fn from_string(stringified_keybindings: &String) -> Result<Self, ConfigError> {
@@ -3347,6 +3602,38 @@ impl Options {
             None
         }
     }
+    fn disk_scroll_buffer_size_to_kdl(&self, add_comments: bool) -> Option<KdlNode> {
+        let comment_text = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            " ",
+            "// Keep lines that fall out of the scroll back buffer in a compressed file in the",
+            "// cache dir instead of discarding them. This is the number of additional lines",
+            "// stored this way for each pane. Search, edit and dump of the scrollback include them.",
+            "// (Requires restart)",
+            "// Valid values: positive integers, 0 to disable",
+            "// Default value: 0",
+            "// ",
+        );
+
+        let create_node = |node_value: usize| -> KdlNode {
+            let mut node = KdlNode::new("disk_scroll_buffer_size");
+            node.push(KdlValue::Base10(node_value as i64));
+            node
+        };
+        if let Some(disk_scroll_buffer_size) = self.disk_scroll_buffer_size {
+            let mut node = create_node(disk_scroll_buffer_size);
+            if add_comments {
+                node.set_leading(format!("{}\n", comment_text));
+            }
+            Some(node)
+        } else if add_comments {
+            let mut node = create_node(1000000);
+            node.set_leading(format!("{}\n// ", comment_text));
+            Some(node)
+        } else {
+            None
+        }
+    }
     fn copy_command_to_kdl(&self, add_comments: bool) -> Option<KdlNode> {
         let comment_text = format!(
             "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
@@ -3646,6 +3933,39 @@ impl Options {
             None
         }
     }
+    fn serialize_full_scrollback_to_kdl(&self, add_comments: bool) -> Option<KdlNode> {
+        let comment_text = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            " ",
+            "// Whether the whole scrollback of each pane is stored in a compressed file next to the",
+            "// serialized session, keeping its styles and hyperlinks, instead of serializing",
+            "// `scrollback_lines_to_serialize` lines into the layout. The file is appended to on",
+            "// every `serialization_interval`. This does nothing if `serialize_pane_viewport` is not true.",
+            "// Options:",
+            "//   - true",
+            "//   - false (default)",
+            "// ",
+        );
+
+        let create_node = |node_value: bool| -> KdlNode {
+            let mut node = KdlNode::new("serialize_full_scrollback");
+            node.push(KdlValue::Bool(node_value));
+            node
+        };
+        if let Some(serialize_full_scrollback) = self.serialize_full_scrollback {
+            let mut node = create_node(serialize_full_scrollback);
+            if add_comments {
+                node.set_leading(format!("{}\n", comment_text));
+            }
+            Some(node)
+        } else if add_comments {
+            let mut node = create_node(false);
+            node.set_leading(format!("{}\n// ", comment_text));
+            Some(node)
+        } else {
+            None
+        }
+    }
     fn styled_underlines_to_kdl(&self, add_comments: bool) -> Option<KdlNode> {
         let comment_text = format!(
             "{}\n{}\n{}\n{}\n{}\n{}",
@@ -4218,6 +4538,35 @@ impl Options {
             None
         }
     }
+    fn key_sequence_timeout_to_kdl(&self, add_comments: bool) -> Option<KdlNode> {
+        let comment_text = format!(
+            "{}\n{}\n{}\n{}\n{}",
+            " ",
+            "// How long (in milliseconds) to wait for the next key of a multi-key sequence",
+            "// before the keys pressed so far are handled on their own",
+            "// Default: 1000",
+            "// ",
+        );
+
+        let create_node = |node_value: u64| -> KdlNode {
+            let mut node = KdlNode::new("key_sequence_timeout");
+            node.push(KdlValue::Base10(node_value as i64));
+            node
+        };
+        if let Some(key_sequence_timeout) = self.key_sequence_timeout {
+            let mut node = create_node(key_sequence_timeout);
+            if add_comments {
+                node.set_leading(format!("{}\n", comment_text));
+            }
+            Some(node)
+        } else if add_comments {
+            let mut node = create_node(1000);
+            node.set_leading(format!("{}\n// ", comment_text));
+            Some(node)
+        } else {
+            None
+        }
+    }
     fn client_async_worker_tasks_to_kdl(&self, add_comments: bool) -> Option<KdlNode> {
         let comment_text = r#"
 // Number of async worker tasks to spawn per active client.
@@ -4294,6 +4643,9 @@ impl Options {
         if let Some(scroll_buffer_size) = self.scroll_buffer_size_to_kdl(add_comments) {
             nodes.push(scroll_buffer_size);
         }
+        if let Some(disk_scroll_buffer_size) = self.disk_scroll_buffer_size_to_kdl(add_comments) {
+            nodes.push(disk_scroll_buffer_size);
+        }
         if let Some(copy_command) = self.copy_command_to_kdl(add_comments) {
             nodes.push(copy_command);
         }
@@ -4326,6 +4678,10 @@ impl Options {
         {
             nodes.push(scrollback_lines_to_serialize);
         }
+        if let Some(serialize_full_scrollback) = self.serialize_full_scrollback_to_kdl(add_comments)
+        {
+            nodes.push(serialize_full_scrollback);
+        }
         if let Some(styled_underlines) = self.styled_underlines_to_kdl(add_comments) {
             nodes.push(styled_underlines);
         }
@@ -4396,6 +4752,9 @@ impl Options {
         {
             nodes.push(client_async_worker_tasks);
         }
+        if let Some(key_sequence_timeout) = self.key_sequence_timeout_to_kdl(add_comments) {
+            nodes.push(key_sequence_timeout);
+        }
         nodes
     }
 }
@@ -4407,7 +4766,87 @@ impl Layout {
         raw_swap_layouts: Option<(&str, &str)>, // raw_swap_layouts swap_layouts_file_name
         cwd: Option<PathBuf>,
     ) -> Result<Self, ConfigError> {
-        let mut kdl_layout_parser = KdlLayoutParser::new(raw_layout, cwd, file_name.clone());
+        Layout::from_kdl_with_args(
+            raw_layout,
+            file_name,
+            raw_swap_layouts,
+            cwd,
+            &BTreeMap::new(),
+            None,
+        )
+    }
+    /// Arguments that leave the required parameters of a layout unsubstituted, so that it can be
+    /// inspected (eg. listed with its metadata) before the actual arguments are known
+    pub fn placeholder_args(raw_layout: &str) -> BTreeMap<String, String> {
+        let mut placeholder_args = BTreeMap::new();
+        let Ok(kdl_layout) = raw_layout.parse::<KdlDocument>() else {
+            return placeholder_args;
+        };
+        let required_parameters = kdl_layout
+            .get("layout")
+            .and_then(|l| l.children())
+            .and_then(|c| c.get("parameters"))
+            .and_then(|p| p.children())
+            .map(|p| p.nodes())
+            .unwrap_or(&[])
+            .iter()
+            .filter(|p| p.entries().is_empty());
+        for parameter in required_parameters {
+            let parameter_name = parameter.name().value();
+            placeholder_args.insert(
+                parameter_name.to_owned(),
+                format!("${{{}}}", parameter_name),
+            );
+        }
+        placeholder_args
+    }
+    /// Parses a layout with its required parameters left unsubstituted (see `placeholder_args`),
+    /// also returning the layout files it imports
+    pub fn from_kdl_with_imports(
+        raw_layout: &str,
+        file_name: Option<String>,
+        layout_dir: Option<PathBuf>,
+    ) -> Result<(Self, Vec<PathBuf>), ConfigError> {
+        let mut kdl_layout_parser = KdlLayoutParser::new(
+            raw_layout,
+            None,
+            file_name,
+            Layout::placeholder_args(raw_layout),
+            layout_dir,
+        );
+        let layout = kdl_layout_parser.parse()?;
+        Ok((layout, kdl_layout_parser.imported_files().to_vec()))
+    }
+    /// Files without a layout node that only hold definitions to be imported by other layouts
+    pub fn is_template_library(raw_layout: &str) -> bool {
+        let Ok(kdl_document) = raw_layout.parse::<KdlDocument>() else {
+            return false;
+        };
+        !kdl_document.nodes().is_empty()
+            && kdl_document.nodes().iter().all(|n| {
+                let node_name = n.name().value();
+                node_name == "import"
+                    || node_name == "pane_template"
+                    || node_name == "tab_template"
+                    || node_name == "swap_tiled_layout"
+                    || node_name == "swap_floating_layout"
+            })
+    }
+    pub fn from_kdl_with_args(
+        raw_layout: &str,
+        file_name: Option<String>,
+        raw_swap_layouts: Option<(&str, &str)>, // raw_swap_layouts swap_layouts_file_name
+        cwd: Option<PathBuf>,
+        layout_args: &BTreeMap<String, String>,
+        layout_dir: Option<PathBuf>, // used to resolve imports not found next to the layout file
+    ) -> Result<Self, ConfigError> {
+        let mut kdl_layout_parser = KdlLayoutParser::new(
+            raw_layout,
+            cwd,
+            file_name.clone(),
+            layout_args.clone(),
+            layout_dir,
+        );
         let layout = kdl_layout_parser.parse().map_err(|e| match e {
             ConfigError::KdlError(kdl_error) => ConfigError::KdlError(kdl_error.add_src(
                 file_name.unwrap_or_else(|| "N/A".to_owned()),
@@ -4517,17 +4956,38 @@ impl Keybinds {
     fn bind_keys_in_block(
         block: &KdlNode,
         input_mode_keybinds: &mut HashMap<KeyWithModifier, Vec<Action>>,
+        input_mode_key_sequences: &mut ModeKeySequences,
+        custom_modes: &BTreeMap<CustomInputMode, CustomModeFallThrough>,
         config_options: &Options,
     ) -> Result<(), ConfigError> {
         let all_nodes = kdl_children_nodes_or_error!(block, "no keybinding block for mode");
         let bind_nodes = all_nodes.iter().filter(|n| kdl_name!(n) == "bind");
         let unbind_nodes = all_nodes.iter().filter(|n| kdl_name!(n) == "unbind");
         for key_block in bind_nodes {
-            Keybinds::bind_actions_for_each_key(key_block, input_mode_keybinds, config_options)?;
+            if Keybinds::is_key_sequence(key_block)? {
+                Keybinds::bind_actions_for_key_sequence(
+                    key_block,
+                    input_mode_key_sequences,
+                    custom_modes,
+                    config_options,
+                )?;
+            } else {
+                Keybinds::bind_actions_for_each_key(
+                    key_block,
+                    input_mode_keybinds,
+                    custom_modes,
+                    config_options,
+                )?;
+            }
         }
         // we loop a second time so that the unbinds always happen after the binds
         for key_block in unbind_nodes {
-            Keybinds::unbind_keys(key_block, input_mode_keybinds)?;
+            if Keybinds::is_key_sequence(key_block)? {
+                let keys: Vec<KeyWithModifier> = keys_from_kdl!(key_block);
+                input_mode_key_sequences.remove(&keys);
+            } else {
+                Keybinds::unbind_keys(key_block, input_mode_keybinds)?;
+            }
         }
         for key_block in all_nodes {
             if kdl_name!(key_block) != "bind" && kdl_name!(key_block) != "unbind" {
@@ -4551,6 +5011,9 @@ impl Keybinds {
         } else {
             base_keybinds
         };
+        keybinds_from_config.add_custom_modes_from_kdl(kdl_keybinds)?;
+        // custom modes can be switched to before they are defined, so they are all known up front
+        let custom_modes = keybinds_from_config.2.clone();
         for block in kdl_children_nodes_or_error!(kdl_keybinds, "keybindings with no children") {
             if kdl_name!(block) == "shared_except" || kdl_name!(block) == "shared" {
                 let mut modes_to_exclude = vec![];
@@ -4567,8 +5030,15 @@ impl Keybinds {
                     if modes_to_exclude.contains(&mode) {
                         continue;
                     }
-                    let mut input_mode_keybinds = keybinds_from_config.get_input_mode_mut(&mode);
-                    Keybinds::bind_keys_in_block(block, &mut input_mode_keybinds, config_options)?;
+                    let (input_mode_keybinds, input_mode_key_sequences) =
+                        keybinds_from_config.get_input_mode_and_key_sequences_mut(&mode);
+                    Keybinds::bind_keys_in_block(
+                        block,
+                        input_mode_keybinds,
+                        input_mode_key_sequences,
+                        &custom_modes,
+                        config_options,
+                    )?;
                 }
             }
             if kdl_name!(block) == "shared_among" {
@@ -4580,8 +5050,15 @@ impl Keybinds {
                     if !modes_to_include.contains(&mode) {
                         continue;
                     }
-                    let mut input_mode_keybinds = keybinds_from_config.get_input_mode_mut(&mode);
-                    Keybinds::bind_keys_in_block(block, &mut input_mode_keybinds, config_options)?;
+                    let (input_mode_keybinds, input_mode_key_sequences) =
+                        keybinds_from_config.get_input_mode_and_key_sequences_mut(&mode);
+                    Keybinds::bind_keys_in_block(
+                        block,
+                        input_mode_keybinds,
+                        input_mode_key_sequences,
+                        &custom_modes,
+                        config_options,
+                    )?;
                 }
             }
         }
@@ -4593,9 +5070,15 @@ impl Keybinds {
             {
                 continue;
             }
-            let mut input_mode_keybinds =
+            let (input_mode_keybinds, input_mode_key_sequences) =
                 Keybinds::input_mode_keybindings(mode, &mut keybinds_from_config)?;
-            Keybinds::bind_keys_in_block(mode, &mut input_mode_keybinds, config_options)?;
+            Keybinds::bind_keys_in_block(
+                mode,
+                input_mode_keybinds,
+                input_mode_key_sequences,
+                &custom_modes,
+                config_options,
+            )?;
         }
         if let Some(global_unbind) = kdl_keybinds.children().and_then(|c| c.get("unbind")) {
             Keybinds::unbind_keys_in_all_modes(global_unbind, &mut keybinds_from_config)?;
@@ -4605,15 +5088,50 @@ impl Keybinds {
     fn bind_actions_for_each_key(
         key_block: &KdlNode,
         input_mode_keybinds: &mut HashMap<KeyWithModifier, Vec<Action>>,
+        custom_modes: &BTreeMap<CustomInputMode, CustomModeFallThrough>,
         config_options: &Options,
     ) -> Result<(), ConfigError> {
         let keys: Vec<KeyWithModifier> = keys_from_kdl!(key_block);
         let actions: Vec<Action> = actions_from_kdl!(key_block, config_options);
+        Keybinds::check_custom_modes_are_defined(&actions, custom_modes, key_block)?;
         for key in keys {
             input_mode_keybinds.insert(key, actions.clone());
         }
         Ok(())
     }
+    fn is_key_sequence(key_block: &KdlNode) -> Result<bool, ConfigError> {
+        match key_block.get("sequence") {
+            Some(entry) => entry.value().as_bool().ok_or_else(|| {
+                kdl_parsing_error!(
+                    format!(
+                        "sequence should be either true or false, found {}",
+                        entry.value()
+                    ),
+                    entry
+                )
+            }),
+            None => Ok(false),
+        }
+    }
+    fn bind_actions_for_key_sequence(
+        key_block: &KdlNode,
+        input_mode_key_sequences: &mut ModeKeySequences,
+        custom_modes: &BTreeMap<CustomInputMode, CustomModeFallThrough>,
+        config_options: &Options,
+    ) -> Result<(), ConfigError> {
+        let keys: Vec<KeyWithModifier> = keys_from_kdl!(key_block);
+        if keys.len() < 2 {
+            return Err(ConfigError::new_kdl_error(
+                "A key sequence must have at least two keys".into(),
+                key_block.span().offset(),
+                key_block.span().len(),
+            ));
+        }
+        let actions: Vec<Action> = actions_from_kdl!(key_block, config_options);
+        Keybinds::check_custom_modes_are_defined(&actions, custom_modes, key_block)?;
+        input_mode_key_sequences.insert(keys, actions);
+        Ok(())
+    }
     fn unbind_keys(
         key_block: &KdlNode,
         input_mode_keybinds: &mut HashMap<KeyWithModifier, Vec<Action>>,
@@ -4629,6 +5147,12 @@ impl Keybinds {
         keybinds_from_config: &mut Keybinds,
     ) -> Result<(), ConfigError> {
         let keys: Vec<KeyWithModifier> = keys_from_kdl!(global_unbind);
+        if Keybinds::is_key_sequence(global_unbind)? {
+            for mode in keybinds_from_config.1.values_mut() {
+                mode.remove(&keys);
+            }
+            return Ok(());
+        }
         for mode in keybinds_from_config.0.values_mut() {
             for key in &keys {
                 mode.remove(&key);
@@ -4636,24 +5160,120 @@ impl Keybinds {
         }
         Ok(())
     }
+    fn add_custom_modes_from_kdl(&mut self, kdl_keybinds: &KdlNode) -> Result<(), ConfigError> {
+        if let Some(children) = kdl_keybinds.children() {
+            for block in children.nodes() {
+                if kdl_name!(block) != "custom_mode" {
+                    continue;
+                }
+                let (custom_input_mode, fall_through) = Keybinds::custom_mode_from_kdl(block)?;
+                if let Some((existing, _)) = self.2.get_key_value(&custom_input_mode) {
+                    if existing.name() != custom_input_mode.name() {
+                        return Err(ConfigError::new_kdl_error(
+                            format!(
+                                "Custom modes '{}' and '{}' cannot be told apart, please rename one of them",
+                                existing, custom_input_mode
+                            ),
+                            block.span().offset(),
+                            block.span().len(),
+                        ));
+                    }
+                }
+                self.2.insert(custom_input_mode, fall_through);
+            }
+        }
+        Ok(())
+    }
+    fn check_custom_modes_are_defined(
+        actions: &[Action],
+        custom_modes: &BTreeMap<CustomInputMode, CustomModeFallThrough>,
+        key_block: &KdlNode,
+    ) -> Result<(), ConfigError> {
+        for action in actions {
+            if let Action::SwitchToMode {
+                input_mode: InputMode::Custom(custom_input_mode),
+            } = action
+            {
+                if !custom_modes.contains_key(custom_input_mode) {
+                    return Err(ConfigError::new_kdl_error(
+                        format!("Unknown InputMode '{}'", custom_input_mode),
+                        key_block.span().offset(),
+                        key_block.span().len(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+    fn custom_mode_from_kdl(
+        block: &KdlNode,
+    ) -> Result<(CustomInputMode, CustomModeFallThrough), ConfigError> {
+        let mode_name = block
+            .entries()
+            .iter()
+            .find(|e| e.name().is_none())
+            .and_then(|e| e.value().as_string())
+            .ok_or_else(|| {
+                ConfigError::new_kdl_error(
+                    "custom_mode must have a name (eg. custom_mode \"git\" { ... })".into(),
+                    block.span().offset(),
+                    block.span().len(),
+                )
+            })?;
+        if InputMode::from_str(mode_name).is_ok() {
+            return Err(ConfigError::new_kdl_error(
+                format!("'{}' is a built-in mode", mode_name),
+                block.span().offset(),
+                block.span().len(),
+            ));
+        }
+        if mode_name.len() > CustomInputMode::MAX_NAME_LEN {
+            return Err(ConfigError::new_kdl_error(
+                format!(
+                    "custom mode names can be at most {} bytes long",
+                    CustomInputMode::MAX_NAME_LEN
+                ),
+                block.span().offset(),
+                block.span().len(),
+            ));
+        }
+        let fall_through = match block.get("fall_through") {
+            Some(entry) => entry
+                .value()
+                .as_string()
+                .ok_or_else(|| "fall_through should be a string".to_owned())
+                .and_then(CustomModeFallThrough::from_str)
+                .map_err(|e| kdl_parsing_error!(e, entry))?,
+            None => CustomModeFallThrough::default(),
+        };
+        Ok((CustomInputMode::new(mode_name), fall_through))
+    }
     fn input_mode_keybindings<'a>(
         mode: &KdlNode,
         keybinds_from_config: &'a mut Keybinds,
-    ) -> Result<&'a mut HashMap<KeyWithModifier, Vec<Action>>, ConfigError> {
+    ) -> Result<(&'a mut ModeKeybinds, &'a mut ModeKeySequences), ConfigError> {
         let mode_name = kdl_name!(mode);
-        let input_mode = InputMode::from_str(mode_name).map_err(|_| {
-            ConfigError::new_kdl_error(
-                format!("Invalid mode: '{}'", mode_name),
-                mode.name().span().offset(),
-                mode.name().span().len(),
-            )
-        })?;
-        let input_mode_keybinds = keybinds_from_config.get_input_mode_mut(&input_mode);
+        let input_mode = if mode_name == "custom_mode" {
+            // already added to the custom modes of the keybinds in `add_custom_modes_from_kdl`
+            let (custom_input_mode, _fall_through) = Keybinds::custom_mode_from_kdl(mode)?;
+            InputMode::Custom(custom_input_mode)
+        } else {
+            InputMode::from_str(mode_name).map_err(|_| {
+                ConfigError::new_kdl_error(
+                    format!("Invalid mode: '{}'", mode_name),
+                    mode.name().span().offset(),
+                    mode.name().span().len(),
+                )
+            })?
+        };
+        let (input_mode_keybinds, input_mode_key_sequences) =
+            keybinds_from_config.get_input_mode_and_key_sequences_mut(&input_mode);
         let clear_defaults_for_mode = kdl_arg_is_truthy!(mode, "clear-defaults");
         if clear_defaults_for_mode {
             input_mode_keybinds.clear();
+            input_mode_key_sequences.clear();
         }
-        Ok(input_mode_keybinds)
+        Ok((input_mode_keybinds, input_mode_key_sequences))
     }
     pub fn from_string(
         stringified_keybindings: String,
@@ -4678,15 +5298,19 @@ impl Keybinds {
     ) -> BTreeMap<BTreeSet<InputMode>, BTreeMap<KeyWithModifier, Vec<Action>>> {
         let mut minimized: BTreeMap<BTreeSet<InputMode>, BTreeMap<KeyWithModifier, Vec<Action>>> =
             BTreeMap::new();
-        let mut flattened: Vec<BTreeMap<KeyWithModifier, Vec<Action>>> = self
-            .0
-            .iter()
+        // custom modes are serialized separately, since they need to be declared
+        let built_in_modes = || {
+            self.0
+                .iter()
+                .filter(|(input_mode, _keybinds)| !input_mode.is_custom())
+        };
+        let mut flattened: Vec<BTreeMap<KeyWithModifier, Vec<Action>>> = built_in_modes()
             .map(|(_input_mode, keybind)| keybind.clone().into_iter().collect())
             .collect();
         for keybind in flattened.drain(..) {
             for (key, actions) in keybind.into_iter() {
                 let mut appears_in_modes: BTreeSet<InputMode> = BTreeSet::new();
-                for (input_mode, keybinds) in self.0.iter() {
+                for (input_mode, keybinds) in built_in_modes() {
                     if keybinds.get(&key) == Some(&actions) {
                         appears_in_modes.insert(*input_mode);
                     }
@@ -4734,28 +5358,40 @@ impl Keybinds {
         for keybind in keybinds {
             let mut keybind_node = KdlNode::new("bind");
             keybind_node.push(keybind.0.to_kdl());
-            let mut actions = KdlDocument::new();
-            let mut actions_have_children = false;
-            for action in keybind.1 {
-                if let Some(kdl_action) = action.to_kdl() {
-                    if kdl_action.children().is_some() {
-                        actions_have_children = true;
-                    }
-                    actions.nodes_mut().push(kdl_action);
+            keybind_node.set_children(self.serialize_keybind_actions(keybind.1));
+            mode_keybinds.nodes_mut().push(keybind_node);
+        }
+        mode_keybinds
+    }
+    fn serialize_key_sequence(&self, keys: &[KeyWithModifier], actions: &[Action]) -> KdlNode {
+        let mut keybind_node = KdlNode::new("bind");
+        for key in keys {
+            keybind_node.push(key.to_kdl());
+        }
+        keybind_node.push(("sequence", true));
+        keybind_node.set_children(self.serialize_keybind_actions(actions));
+        keybind_node
+    }
+    fn serialize_keybind_actions(&self, keybind_actions: &[Action]) -> KdlDocument {
+        let mut actions = KdlDocument::new();
+        let mut actions_have_children = false;
+        for action in keybind_actions {
+            if let Some(kdl_action) = action.to_kdl() {
+                if kdl_action.children().is_some() {
+                    actions_have_children = true;
                 }
+                actions.nodes_mut().push(kdl_action);
             }
-            if !actions_have_children {
-                for action in actions.nodes_mut() {
-                    action.set_leading("");
-                    action.set_trailing("; ");
-                }
-                actions.set_leading(" ");
-                actions.set_trailing("");
+        }
+        if !actions_have_children {
+            for action in actions.nodes_mut() {
+                action.set_leading("");
+                action.set_trailing("; ");
             }
-            keybind_node.set_children(actions);
-            mode_keybinds.nodes_mut().push(keybind_node);
+            actions.set_leading(" ");
+            actions.set_trailing("");
         }
-        mode_keybinds
+        actions
     }
     pub fn to_kdl(&self, should_clear_defaults: bool) -> KdlNode {
         let mut keybinds_node = KdlNode::new("keybinds");
@@ -4798,6 +5434,56 @@ impl Keybinds {
             mode_node.set_children(mode_keybinds);
             keybinds_children.nodes_mut().push(mode_node);
         }
+
+        // key sequences are not minimized, they are placed in the node of their own mode
+        for input_mode in InputMode::iter() {
+            let Some(key_sequences) = self.1.get(&input_mode).filter(|s| !s.is_empty()) else {
+                continue;
+            };
+            let mode_name = format!("{:?}", input_mode).to_lowercase();
+            let mode_node_position = keybinds_children
+                .nodes()
+                .iter()
+                .position(|n| n.name().value() == mode_name && n.entries().is_empty());
+            let mode_node_position = match mode_node_position {
+                Some(position) => position,
+                None => {
+                    let mut mode_node = KdlNode::new(mode_name);
+                    mode_node.set_children(KdlDocument::new());
+                    keybinds_children.nodes_mut().push(mode_node);
+                    keybinds_children.nodes().len() - 1
+                },
+            };
+            let mode_node = &mut keybinds_children.nodes_mut()[mode_node_position];
+            for (keys, actions) in key_sequences {
+                let key_sequence_node = self.serialize_key_sequence(keys, actions);
+                mode_node
+                    .ensure_children()
+                    .nodes_mut()
+                    .push(key_sequence_node);
+            }
+        }
+
+        for (custom_input_mode, fall_through) in &self.2 {
+            let input_mode = InputMode::Custom(*custom_input_mode);
+            let mut mode_node = KdlNode::new("custom_mode");
+            mode_node.push(custom_input_mode.name());
+            if *fall_through != CustomModeFallThrough::default() {
+                mode_node.push(("fall_through", format!("{:?}", fall_through).to_lowercase()));
+            }
+            let keybinds: BTreeMap<KeyWithModifier, Vec<Action>> = self
+                .0
+                .get(&input_mode)
+                .map(|keybinds| keybinds.clone().into_iter().collect())
+                .unwrap_or_default();
+            let mut mode_keybinds = self.serialize_mode_keybinds(&keybinds);
+            for (keys, actions) in self.1.get(&input_mode).into_iter().flatten() {
+                let key_sequence_node = self.serialize_key_sequence(keys, actions);
+                mode_keybinds.nodes_mut().push(key_sequence_node);
+            }
+            mode_node.set_children(mode_keybinds);
+            keybinds_children.nodes_mut().push(mode_node);
+        }
         keybinds_node.set_children(keybinds_children);
         keybinds_node
     }
@@ -4864,6 +5550,16 @@ impl Config {
         if let Some(kdl_keybinds) = kdl_config.get("keybinds") {
             config.keybinds = Keybinds::from_kdl(&kdl_keybinds, config.keybinds, &config.options)?;
         }
+        // the default mode can be a custom mode, which is only known once the keybinds are parsed
+        if let Some(InputMode::Custom(custom_input_mode)) = config.options.default_mode {
+            if !config.keybinds.2.contains_key(&custom_input_mode) {
+                let message = format!("Invalid input mode: '{}'", custom_input_mode);
+                return Err(match kdl_config.get("default_mode") {
+                    Some(default_mode) => kdl_parsing_error!(message, default_mode),
+                    None => ConfigError::new_kdl_error(message, 0, 0),
+                });
+            }
+        }
         if let Some(kdl_themes) = kdl_config.get("themes") {
             let sourced_from_external_file = false;
             let config_themes = Themes::from_kdl(kdl_themes, sourced_from_external_file)?;
@@ -4877,6 +5573,10 @@ impl Config {
             let load_plugins = load_plugins_from_kdl(kdl_load_plugins)?;
             config.background_plugins = load_plugins;
         }
+        if let Some(kdl_plugin_permissions) = kdl_config.get("plugin_permissions") {
+            let plugin_permissions = plugin_permissions_from_kdl(kdl_plugin_permissions)?;
+            config.plugin_permissions.extend(plugin_permissions);
+        }
         if let Some(kdl_ui_config) = kdl_config.get("ui") {
             let config_ui = UiConfig::from_kdl(&kdl_ui_config)?;
             config.ui = config.ui.merge(config_ui);
@@ -4908,6 +5608,10 @@ impl Config {
         let load_plugins = load_plugins_to_kdl(&self.background_plugins, add_comments);
         document.nodes_mut().push(load_plugins);
 
+        if let Some(plugin_permissions) = plugin_permissions_to_kdl(&self.plugin_permissions) {
+            document.nodes_mut().push(plugin_permissions);
+        }
+
         if let Some(ui_config) = self.ui.to_kdl() {
             document.nodes_mut().push(ui_config);
         }
@@ -5096,6 +5800,62 @@ fn load_plugins_from_kdl(
     Ok(load_plugins)
 }
 
+fn plugin_permissions_from_kdl(
+    kdl_plugin_permissions: &KdlNode,
+) -> Result<BTreeMap<String, Vec<PermissionType>>, ConfigError> {
+    let mut plugin_permissions = BTreeMap::new();
+    if let Some(kdl_plugin_permissions) = kdl_children_nodes!(kdl_plugin_permissions) {
+        for plugin_block in kdl_plugin_permissions {
+            let url_node = plugin_block.name();
+            let plugin_url = url_node.value();
+            PermissionCache::plugin_name_from_url(plugin_url, None).map_err(|e| {
+                ConfigError::new_kdl_error(
+                    format!("Failed to parse plugin url: {}", e),
+                    url_node.span().offset(),
+                    url_node.span().len(),
+                )
+            })?;
+            let mut permissions = vec![];
+            for permission_node in kdl_children_nodes!(plugin_block).unwrap_or(&[]) {
+                let permission =
+                    PermissionType::from_str(kdl_name!(permission_node)).map_err(|_| {
+                        ConfigError::new_kdl_error(
+                            format!("Unknown permission: {}", kdl_name!(permission_node)),
+                            permission_node.span().offset(),
+                            permission_node.span().len(),
+                        )
+                    })?;
+                permissions.push(permission);
+            }
+            plugin_permissions.insert(plugin_url.to_owned(), permissions);
+        }
+    }
+    Ok(plugin_permissions)
+}
+
+fn plugin_permissions_to_kdl(
+    plugin_permissions: &BTreeMap<String, Vec<PermissionType>>,
+) -> Option<KdlNode> {
+    if plugin_permissions.is_empty() {
+        return None;
+    }
+    let mut plugin_permissions_node = KdlNode::new("plugin_permissions");
+    let mut plugin_permissions_children = KdlDocument::new();
+    for (plugin_url, permissions) in plugin_permissions {
+        let mut plugin_node = KdlNode::new(plugin_url.as_str());
+        let mut permission_nodes = KdlDocument::new();
+        for permission in permissions {
+            permission_nodes
+                .nodes_mut()
+                .push(KdlNode::new(permission.to_string().as_str()));
+        }
+        plugin_node.set_children(permission_nodes);
+        plugin_permissions_children.nodes_mut().push(plugin_node);
+    }
+    plugin_permissions_node.set_children(plugin_permissions_children);
+    Some(plugin_permissions_node)
+}
+
 impl UiConfig {
     pub fn from_kdl(kdl_ui_config: &KdlNode) -> Result<UiConfig, ConfigError> {
         let mut ui_config = UiConfig::default();
@@ -6591,6 +7351,17 @@ fn keybinds_to_string_with_all_actions() {
                         config_key_2 "config_value_2";
                     };
                 }
+                bind "Ctrl Alt k" { ToggleScratchpad "shell"; }
+                bind "Ctrl Alt l" {
+                    ToggleScratchpad "notes" {
+                        command "nvim";
+                        args "/tmp/notes.md";
+                        cwd "/tmp";
+                        width "80%";
+                        height "20";
+                        pinned true;
+                    };
+                }
             }
         }"#;
     let document: KdlDocument = fake_config.parse().unwrap();
@@ -6632,6 +7403,63 @@ fn keybinds_to_string_with_all_actions() {
     insta::assert_snapshot!(serialized.to_string());
 }
 
+#[test]
+fn keybinds_to_string_with_shell_integration_actions() {
+    let fake_config = r#"
+        keybinds {
+            normal {
+                bind "Ctrl a" { ScrollToPreviousPrompt; }
+                bind "Ctrl b" { ScrollToNextPrompt; }
+                bind "Ctrl c" { SelectLastCommandOutput; }
+                bind "Ctrl d" { CopyLastCommandOutput; }
+                bind "Ctrl e" { DumpCommandOutput "/tmp/command-output"; }
+                bind "Ctrl f" { StartRecording "/tmp/pane.cast"; }
+                bind "Ctrl g" { StopRecording; }
+            }
+        }"#;
+    let document: KdlDocument = fake_config.parse().unwrap();
+    let deserialized = Keybinds::from_kdl(
+        document.get("keybinds").unwrap(),
+        Default::default(),
+        &Default::default(),
+    )
+    .unwrap();
+    let clear_defaults = true;
+    let serialized = Keybinds::to_kdl(&deserialized, clear_defaults);
+    let deserialized_from_serialized = Keybinds::from_kdl(
+        serialized
+            .to_string()
+            .parse::<KdlDocument>()
+            .unwrap()
+            .get("keybinds")
+            .unwrap(),
+        Default::default(),
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        deserialized, deserialized_from_serialized,
+        "Deserialized serialized config equals original config"
+    );
+    let serialized = serialized.to_string();
+    for action_name in [
+        "ScrollToPreviousPrompt",
+        "ScrollToNextPrompt",
+        "SelectLastCommandOutput",
+        "CopyLastCommandOutput",
+        "DumpCommandOutput \"/tmp/command-output\"",
+        "StartRecording \"/tmp/pane.cast\"",
+        "StopRecording",
+    ] {
+        assert!(
+            serialized.contains(action_name),
+            "{} missing from serialized keybinds: {}",
+            action_name,
+            serialized
+        );
+    }
+}
+
 #[test]
 fn keybinds_to_string_with_shared_modes() {
     let fake_config = r#"
@@ -6676,6 +7504,89 @@ fn keybinds_to_string_with_shared_modes() {
     insta::assert_snapshot!(serialized.to_string());
 }
 
+#[test]
+fn keybinds_to_string_with_key_sequences() {
+    let fake_config = r#"
+        keybinds {
+            normal {
+                bind "Ctrl g" { SwitchToMode "Locked"; }
+                bind "Ctrl a" "g" "s" sequence=true { SwitchToMode "Session"; }
+            }
+            shared_among "locked" "pane" {
+                bind "Ctrl a" "n" sequence=true { NewPane; }
+            }
+            scroll {
+                bind "g" "g" sequence=true { ScrollToTop; }
+            }
+        }"#;
+    let document: KdlDocument = fake_config.parse().unwrap();
+    let deserialized = Keybinds::from_kdl(
+        document.get("keybinds").unwrap(),
+        Default::default(),
+        &Default::default(),
+    )
+    .unwrap();
+    let clear_defaults = true;
+    let serialized = Keybinds::to_kdl(&deserialized, clear_defaults);
+    let deserialized_from_serialized = Keybinds::from_kdl(
+        serialized
+            .to_string()
+            .parse::<KdlDocument>()
+            .unwrap()
+            .get("keybinds")
+            .unwrap(),
+        Default::default(),
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        deserialized, deserialized_from_serialized,
+        "Deserialized serialized config equals original config"
+    );
+    insta::assert_snapshot!(serialized.to_string());
+}
+
+#[test]
+fn keybinds_to_string_with_custom_modes() {
+    let fake_config = r#"
+        keybinds {
+            normal {
+                bind "Ctrl g" { SwitchToMode "Locked"; }
+                bind "Ctrl y" { SwitchToMode "git"; }
+            }
+            custom_mode "git" fall_through="pane" {
+                bind "Esc" { SwitchToMode "Normal"; }
+                bind "Ctrl g" { SwitchToMode "Locked"; }
+                bind "g" "s" sequence=true { NewPane; }
+            }
+        }"#;
+    let document: KdlDocument = fake_config.parse().unwrap();
+    let deserialized = Keybinds::from_kdl(
+        document.get("keybinds").unwrap(),
+        Default::default(),
+        &Default::default(),
+    )
+    .unwrap();
+    let clear_defaults = true;
+    let serialized = Keybinds::to_kdl(&deserialized, clear_defaults);
+    let deserialized_from_serialized = Keybinds::from_kdl(
+        serialized
+            .to_string()
+            .parse::<KdlDocument>()
+            .unwrap()
+            .get("keybinds")
+            .unwrap(),
+        Default::default(),
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        deserialized, deserialized_from_serialized,
+        "Deserialized serialized config equals original config"
+    );
+    insta::assert_snapshot!(serialized.to_string());
+}
+
 #[test]
 fn keybinds_to_string_with_multiple_multiline_actions() {
     let fake_config = r#"
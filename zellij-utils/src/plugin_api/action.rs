@@ -73,9 +73,9 @@ pub use super::generated_api::api::{
         WriteCharsPayload,
         WritePayload,
     },
-    input_mode::InputMode as ProtobufInputMode,
     resize::{Resize as ProtobufResize, ResizeDirection as ProtobufResizeDirection},
 };
+use super::input_mode::{input_mode_from_i32, input_mode_to_i32};
 use crate::data::{
     CommandOrPlugin, Direction, FloatingPaneCoordinates, InputMode, KeyWithModifier,
     NewPanePlacement, PaneId, PluginTag, ResizeStrategy, UnblockCondition,
@@ -129,9 +129,8 @@ impl TryFrom<ProtobufAction> for Action {
             Some(ProtobufActionName::SwitchToMode) => match protobuf_action.optional_payload {
                 Some(OptionalPayload::SwitchToModePayload(switch_to_mode_payload)) => {
                     let input_mode: InputMode =
-                        ProtobufInputMode::from_i32(switch_to_mode_payload.input_mode)
-                            .ok_or("Malformed input mode for SwitchToMode Action")?
-                            .try_into()?;
+                        input_mode_from_i32(switch_to_mode_payload.input_mode)
+                            .ok_or("Malformed input mode for SwitchToMode Action")?;
                     Ok(Action::SwitchToMode { input_mode })
                 },
                 _ => Err("Wrong payload for Action::SwitchToModePayload"),
@@ -142,9 +141,8 @@ impl TryFrom<ProtobufAction> for Action {
                         switch_to_mode_payload,
                     )) => {
                         let input_mode: InputMode =
-                            ProtobufInputMode::from_i32(switch_to_mode_payload.input_mode)
-                                .ok_or("Malformed input mode for SwitchToMode Action")?
-                                .try_into()?;
+                            input_mode_from_i32(switch_to_mode_payload.input_mode)
+                                .ok_or("Malformed input mode for SwitchToMode Action")?;
                         Ok(Action::SwitchModeForAllClients { input_mode })
                     },
                     _ => Err("Wrong payload for Action::SwitchModeForAllClients"),
@@ -286,6 +284,46 @@ impl TryFrom<ProtobufAction> for Action {
                     None => Ok(Action::HalfPageScrollDown),
                 }
             },
+            Some(ProtobufActionName::ScrollToPreviousPrompt) => {
+                match protobuf_action.optional_payload {
+                    Some(_) => Err("ScrollToPreviousPrompt should not have a payload"),
+                    None => Ok(Action::ScrollToPreviousPrompt),
+                }
+            },
+            Some(ProtobufActionName::ScrollToNextPrompt) => {
+                match protobuf_action.optional_payload {
+                    Some(_) => Err("ScrollToNextPrompt should not have a payload"),
+                    None => Ok(Action::ScrollToNextPrompt),
+                }
+            },
+            Some(ProtobufActionName::SelectLastCommandOutput) => {
+                match protobuf_action.optional_payload {
+                    Some(_) => Err("SelectLastCommandOutput should not have a payload"),
+                    None => Ok(Action::SelectLastCommandOutput),
+                }
+            },
+            Some(ProtobufActionName::CopyLastCommandOutput) => {
+                match protobuf_action.optional_payload {
+                    Some(_) => Err("CopyLastCommandOutput should not have a payload"),
+                    None => Ok(Action::CopyLastCommandOutput),
+                }
+            },
+            Some(ProtobufActionName::ToggleSyncGroup) => match protobuf_action.optional_payload {
+                Some(_) => Err("ToggleSyncGroup should not have a payload"),
+                None => Ok(Action::ToggleSyncGroup),
+            },
+            Some(ProtobufActionName::ShowPaneHints) => match protobuf_action.optional_payload {
+                Some(_) => Err("ShowPaneHints should not have a payload"),
+                None => Ok(Action::ShowPaneHints),
+            },
+            Some(ProtobufActionName::ReopenClosedPane) => match protobuf_action.optional_payload {
+                Some(_) => Err("ReopenClosedPane should not have a payload"),
+                None => Ok(Action::ReopenClosedPane),
+            },
+            Some(ProtobufActionName::ReopenClosedTab) => match protobuf_action.optional_payload {
+                Some(_) => Err("ReopenClosedTab should not have a payload"),
+                None => Ok(Action::ReopenClosedTab),
+            },
             Some(ProtobufActionName::ToggleFocusFullscreen) => {
                 match protobuf_action.optional_payload {
                     Some(_) => Err("ToggleFocusFullscreen should not have a payload"),
@@ -1132,28 +1170,20 @@ impl TryFrom<Action> for ProtobufAction {
             | Action::MoveTabByTabId { .. } => {
                 Err("These are CLI-only actions, not available in keybindings")
             },
-            Action::SwitchToMode { input_mode } => {
-                let input_mode: ProtobufInputMode = input_mode.try_into()?;
-                Ok(ProtobufAction {
-                    name: ProtobufActionName::SwitchToMode as i32,
-                    optional_payload: Some(OptionalPayload::SwitchToModePayload(
-                        SwitchToModePayload {
-                            input_mode: input_mode as i32,
-                        },
-                    )),
-                })
-            },
-            Action::SwitchModeForAllClients { input_mode } => {
-                let input_mode: ProtobufInputMode = input_mode.try_into()?;
-                Ok(ProtobufAction {
-                    name: ProtobufActionName::SwitchModeForAllClients as i32,
-                    optional_payload: Some(OptionalPayload::SwitchModeForAllClientsPayload(
-                        SwitchToModePayload {
-                            input_mode: input_mode as i32,
-                        },
-                    )),
-                })
-            },
+            Action::SwitchToMode { input_mode } => Ok(ProtobufAction {
+                name: ProtobufActionName::SwitchToMode as i32,
+                optional_payload: Some(OptionalPayload::SwitchToModePayload(SwitchToModePayload {
+                    input_mode: input_mode_to_i32(input_mode),
+                })),
+            }),
+            Action::SwitchModeForAllClients { input_mode } => Ok(ProtobufAction {
+                name: ProtobufActionName::SwitchModeForAllClients as i32,
+                optional_payload: Some(OptionalPayload::SwitchModeForAllClientsPayload(
+                    SwitchToModePayload {
+                        input_mode: input_mode_to_i32(input_mode),
+                    },
+                )),
+            }),
             Action::Resize { resize, direction } => {
                 let mut resize: ProtobufResize = resize.try_into()?;
                 resize.direction = direction.and_then(|d| {
@@ -1285,6 +1315,38 @@ impl TryFrom<Action> for ProtobufAction {
                 name: ProtobufActionName::HalfPageScrollDown as i32,
                 optional_payload: None,
             }),
+            Action::ScrollToPreviousPrompt => Ok(ProtobufAction {
+                name: ProtobufActionName::ScrollToPreviousPrompt as i32,
+                optional_payload: None,
+            }),
+            Action::ScrollToNextPrompt => Ok(ProtobufAction {
+                name: ProtobufActionName::ScrollToNextPrompt as i32,
+                optional_payload: None,
+            }),
+            Action::SelectLastCommandOutput => Ok(ProtobufAction {
+                name: ProtobufActionName::SelectLastCommandOutput as i32,
+                optional_payload: None,
+            }),
+            Action::CopyLastCommandOutput => Ok(ProtobufAction {
+                name: ProtobufActionName::CopyLastCommandOutput as i32,
+                optional_payload: None,
+            }),
+            Action::ToggleSyncGroup => Ok(ProtobufAction {
+                name: ProtobufActionName::ToggleSyncGroup as i32,
+                optional_payload: None,
+            }),
+            Action::ShowPaneHints => Ok(ProtobufAction {
+                name: ProtobufActionName::ShowPaneHints as i32,
+                optional_payload: None,
+            }),
+            Action::ReopenClosedPane => Ok(ProtobufAction {
+                name: ProtobufActionName::ReopenClosedPane as i32,
+                optional_payload: None,
+            }),
+            Action::ReopenClosedTab => Ok(ProtobufAction {
+                name: ProtobufActionName::ReopenClosedTab as i32,
+                optional_payload: None,
+            }),
             Action::ToggleFocusFullscreen => Ok(ProtobufAction {
                 name: ProtobufActionName::ToggleFocusFullscreen as i32,
                 optional_payload: None,
@@ -1927,11 +1989,15 @@ impl TryFrom<Action> for ProtobufAction {
             }
             | Action::Deny
             | Action::Copy
+            | Action::CopyModeMove { .. }
+            | Action::CopyModeSelect { .. }
             | Action::DumpLayout
             | Action::CliPipe { .. }
             | Action::ListClients
             | Action::ListPanes { .. }
             | Action::StackPanes { pane_ids: _ }
+            | Action::SetSyncGroup { .. }
+            | Action::ToggleScratchpad { .. }
             | Action::ChangeFloatingPaneCoordinates {
                 pane_id: _,
                 coordinates: _,
@@ -1943,7 +2009,10 @@ impl TryFrom<Action> for ProtobufAction {
             | Action::SaveSession
             | Action::ListTabs { .. }
             | Action::CurrentTabInfo { .. }
-            | Action::SetPaneColor { .. } => Err("Unsupported action"),
+            | Action::SetPaneColor { .. }
+            | Action::DumpCommandOutput { .. }
+            | Action::StartRecording { .. }
+            | Action::StopRecording { .. } => Err("Unsupported action"),
         }
     }
 }
@@ -1955,6 +2024,7 @@ impl TryFrom<ProtobufSearchOption> for SearchOption {
             ProtobufSearchOption::CaseSensitivity => Ok(SearchOption::CaseSensitivity),
             ProtobufSearchOption::WholeWord => Ok(SearchOption::WholeWord),
             ProtobufSearchOption::Wrap => Ok(SearchOption::Wrap),
+            ProtobufSearchOption::Regex => Ok(SearchOption::Regex),
         }
     }
 }
@@ -1966,6 +2036,7 @@ impl TryFrom<SearchOption> for ProtobufSearchOption {
             SearchOption::CaseSensitivity => Ok(ProtobufSearchOption::CaseSensitivity),
             SearchOption::WholeWord => Ok(ProtobufSearchOption::WholeWord),
             SearchOption::Wrap => Ok(ProtobufSearchOption::Wrap),
+            SearchOption::Regex => Ok(ProtobufSearchOption::Regex),
         }
     }
 }
@@ -2599,6 +2670,13 @@ impl TryFrom<ProtobufLayoutConstraint> for LayoutConstraint {
             ProtobufLayoutConstraint::MinPanes => Ok(LayoutConstraint::MinPanes(0)),
             ProtobufLayoutConstraint::ExactPanes => Ok(LayoutConstraint::ExactPanes(0)),
             ProtobufLayoutConstraint::NoConstraint => Ok(LayoutConstraint::NoConstraint),
+            ProtobufLayoutConstraint::MinColumns => Ok(LayoutConstraint::MinColumns(0)),
+            ProtobufLayoutConstraint::MaxColumns => Ok(LayoutConstraint::MaxColumns(0)),
+            ProtobufLayoutConstraint::MinRows => Ok(LayoutConstraint::MinRows(0)),
+            ProtobufLayoutConstraint::MaxRows => Ok(LayoutConstraint::MaxRows(0)),
+            ProtobufLayoutConstraint::MinAspectRatio => Ok(LayoutConstraint::MinAspectRatio(0)),
+            ProtobufLayoutConstraint::MaxAspectRatio => Ok(LayoutConstraint::MaxAspectRatio(0)),
+            ProtobufLayoutConstraint::Combined => Ok(LayoutConstraint::Combined(vec![])),
             ProtobufLayoutConstraint::Unspecified => Err("LayoutConstraint cannot be unspecified"),
         }
     }
@@ -2612,6 +2690,13 @@ impl TryFrom<LayoutConstraint> for ProtobufLayoutConstraint {
             LayoutConstraint::MinPanes(_) => ProtobufLayoutConstraint::MinPanes,
             LayoutConstraint::ExactPanes(_) => ProtobufLayoutConstraint::ExactPanes,
             LayoutConstraint::NoConstraint => ProtobufLayoutConstraint::NoConstraint,
+            LayoutConstraint::MinColumns(_) => ProtobufLayoutConstraint::MinColumns,
+            LayoutConstraint::MaxColumns(_) => ProtobufLayoutConstraint::MaxColumns,
+            LayoutConstraint::MinRows(_) => ProtobufLayoutConstraint::MinRows,
+            LayoutConstraint::MaxRows(_) => ProtobufLayoutConstraint::MaxRows,
+            LayoutConstraint::MinAspectRatio(_) => ProtobufLayoutConstraint::MinAspectRatio,
+            LayoutConstraint::MaxAspectRatio(_) => ProtobufLayoutConstraint::MaxAspectRatio,
+            LayoutConstraint::Combined(_) => ProtobufLayoutConstraint::Combined,
         })
     }
 }
@@ -2632,6 +2717,31 @@ impl TryFrom<ProtobufLayoutConstraintWithValue> for LayoutConstraint {
                 protobuf.value.unwrap_or(0) as usize,
             )),
             ProtobufLayoutConstraint::NoConstraint => Ok(LayoutConstraint::NoConstraint),
+            ProtobufLayoutConstraint::MinColumns => Ok(LayoutConstraint::MinColumns(
+                protobuf.value.unwrap_or(0) as usize,
+            )),
+            ProtobufLayoutConstraint::MaxColumns => Ok(LayoutConstraint::MaxColumns(
+                protobuf.value.unwrap_or(0) as usize,
+            )),
+            ProtobufLayoutConstraint::MinRows => Ok(LayoutConstraint::MinRows(
+                protobuf.value.unwrap_or(0) as usize,
+            )),
+            ProtobufLayoutConstraint::MaxRows => Ok(LayoutConstraint::MaxRows(
+                protobuf.value.unwrap_or(0) as usize,
+            )),
+            ProtobufLayoutConstraint::MinAspectRatio => Ok(LayoutConstraint::MinAspectRatio(
+                protobuf.value.unwrap_or(0) as usize,
+            )),
+            ProtobufLayoutConstraint::MaxAspectRatio => Ok(LayoutConstraint::MaxAspectRatio(
+                protobuf.value.unwrap_or(0) as usize,
+            )),
+            ProtobufLayoutConstraint::Combined => Ok(LayoutConstraint::Combined(
+                protobuf
+                    .constraints
+                    .into_iter()
+                    .map(LayoutConstraint::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
             ProtobufLayoutConstraint::Unspecified => Err("LayoutConstraint cannot be unspecified"),
         }
     }
@@ -2640,7 +2750,35 @@ impl TryFrom<ProtobufLayoutConstraintWithValue> for LayoutConstraint {
 impl TryFrom<LayoutConstraint> for ProtobufLayoutConstraintWithValue {
     type Error = &'static str;
     fn try_from(internal: LayoutConstraint) -> Result<Self, Self::Error> {
+        let mut constraints = vec![];
         let (constraint_type, value) = match internal {
+            LayoutConstraint::Combined(combined_constraints) => {
+                constraints = combined_constraints
+                    .into_iter()
+                    .map(ProtobufLayoutConstraintWithValue::try_from)
+                    .collect::<Result<Vec<_>, _>>()?;
+                (ProtobufLayoutConstraint::Combined as i32, None)
+            },
+            LayoutConstraint::MinColumns(v) => {
+                (ProtobufLayoutConstraint::MinColumns as i32, Some(v as u32))
+            },
+            LayoutConstraint::MaxColumns(v) => {
+                (ProtobufLayoutConstraint::MaxColumns as i32, Some(v as u32))
+            },
+            LayoutConstraint::MinRows(v) => {
+                (ProtobufLayoutConstraint::MinRows as i32, Some(v as u32))
+            },
+            LayoutConstraint::MaxRows(v) => {
+                (ProtobufLayoutConstraint::MaxRows as i32, Some(v as u32))
+            },
+            LayoutConstraint::MinAspectRatio(v) => (
+                ProtobufLayoutConstraint::MinAspectRatio as i32,
+                Some(v as u32),
+            ),
+            LayoutConstraint::MaxAspectRatio(v) => (
+                ProtobufLayoutConstraint::MaxAspectRatio as i32,
+                Some(v as u32),
+            ),
             LayoutConstraint::MaxPanes(v) => {
                 (ProtobufLayoutConstraint::MaxPanes as i32, Some(v as u32))
             },
@@ -2655,6 +2793,7 @@ impl TryFrom<LayoutConstraint> for ProtobufLayoutConstraintWithValue {
         Ok(ProtobufLayoutConstraintWithValue {
             constraint_type,
             value,
+            constraints,
         })
     }
 }
@@ -3023,6 +3162,7 @@ impl TryFrom<ProtobufTiledPaneLayout> for TiledPaneLayout {
             run_instructions_to_ignore,
             hide_floating_panes: protobuf.hide_floating_panes,
             pane_initial_contents: protobuf.pane_initial_contents,
+            pane_scrollback_file: None,
             default_fg: None,
             default_bg: None,
         })
@@ -3079,10 +3219,12 @@ impl TryFrom<ProtobufFloatingPaneLayout> for FloatingPaneLayout {
             focus: protobuf.focus,
             already_running: protobuf.already_running,
             pane_initial_contents: protobuf.pane_initial_contents,
+            pane_scrollback_file: None,
             logical_position: protobuf.logical_position.map(|p| p as usize),
             borderless: protobuf.borderless,
             default_fg: None,
             default_bg: None,
+            scratchpad: None,
         })
     }
 }
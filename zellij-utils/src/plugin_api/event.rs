@@ -13,10 +13,11 @@ pub use super::generated_api::api::{
         EventType as ProtobufEventType, FileMetadata as ProtobufFileMetadata,
         HostTerminalThemeChangedPayload as ProtobufHostTerminalThemeChangedPayload,
         HostTerminalThemeIndication as ProtobufHostTerminalThemeIndication,
+        InputModeKeySequences as ProtobufInputModeKeySequences,
         InputModeKeybinds as ProtobufInputModeKeybinds, KdlError as ProtobufKdlError,
         KdlErrorVariant as ProtobufKdlErrorVariant, KeyBind as ProtobufKeyBind,
-        LayoutInfo as ProtobufLayoutInfo, LayoutMetadata as ProtobufLayoutMetadata,
-        LayoutParsingError as ProtobufLayoutParsingError,
+        KeySequenceBind as ProtobufKeySequenceBind, LayoutInfo as ProtobufLayoutInfo,
+        LayoutMetadata as ProtobufLayoutMetadata, LayoutParsingError as ProtobufLayoutParsingError,
         LayoutWithError as ProtobufLayoutWithError, ModeUpdatePayload as ProtobufModeUpdatePayload,
         PaneContents as ProtobufPaneContents, PaneContentsEntry as ProtobufPaneContentsEntry,
         PaneId as ProtobufPaneId, PaneInfo as ProtobufPaneInfo,
@@ -31,19 +32,20 @@ pub use super::generated_api::api::{
         WebServerStatusPayload as ProtobufWebServerStatusPayload, WebSharing as ProtobufWebSharing,
         *,
     },
-    input_mode::InputMode as ProtobufInputMode,
+    input_mode::CustomInputMode as ProtobufCustomInputMode,
     key::Key as ProtobufKey,
     style::Style as ProtobufStyle,
 };
 #[allow(hidden_glob_reexports)]
 use crate::data::{
-    ClientId, ClientInfo, CopyDestination, Event, EventType, FileMetadata, HostTerminalThemeMode,
-    InputMode, KeyWithModifier, LayoutInfo, LayoutMetadata, ModeInfo, Mouse, PaneContents, PaneId,
-    PaneInfo, PaneManifest, PaneMetadata, PaneScrollbackResponse, PermissionStatus,
-    PluginCapabilities, PluginInfo, SelectedText, SessionInfo, Style, TabInfo, TabMetadata,
-    WebServerStatus, WebSharing,
+    ClientId, ClientInfo, CopyDestination, CustomInputMode, Event, EventType, FileMetadata,
+    HostTerminalThemeMode, InputMode, KeySequencesVec, KeyWithModifier, LayoutInfo, LayoutMetadata,
+    ModeInfo, Mouse, PaneContents, PaneId, PaneInfo, PaneManifest, PaneMetadata,
+    PaneScrollbackResponse, PermissionStatus, PluginCapabilities, PluginInfo, SelectedText,
+    SessionInfo, Style, TabInfo, TabMetadata, WebServerStatus, WebSharing,
 };
 
+use super::input_mode::{input_mode_from_i32, input_mode_to_i32};
 use crate::errors::prelude::*;
 use crate::input::actions::Action;
 
@@ -558,8 +560,7 @@ impl TryFrom<ProtobufEvent> for Event {
                         .keybinds
                         .into_iter()
                         .filter_map(|imk| {
-                            let mode: InputMode =
-                                ProtobufInputMode::from_i32(imk.mode)?.try_into().ok()?;
+                            let mode: InputMode = input_mode_from_i32(imk.mode)?;
                             let key_binds: Vec<(KeyWithModifier, Vec<Action>)> = imk
                                 .key_bind
                                 .into_iter()
@@ -588,6 +589,23 @@ impl TryFrom<ProtobufEvent> for Event {
                 },
                 _ => Err("Malformed payload for HostTerminalThemeChanged Event"),
             },
+            Some(ProtobufEventType::CommandFinished) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::CommandFinishedPayload(command_finished_payload)) => {
+                    Ok(Event::CommandFinished(
+                        command_finished_payload.terminal_pane_id,
+                        command_finished_payload.exit_code,
+                    ))
+                },
+                _ => Err("Malformed payload for the CommandFinished Event"),
+            },
+            Some(ProtobufEventType::RecordingStateChanged) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::RecordingStateChangedPayload(
+                    recording_state_changed_payload,
+                )) => Ok(Event::RecordingStateChanged(
+                    recording_state_changed_payload.recorded_terminal_pane_ids,
+                )),
+                _ => Err("Malformed payload for the RecordingStateChanged Event"),
+            },
             None => Err("Unknown Protobuf Event"),
         }
     }
@@ -1129,10 +1147,27 @@ impl TryFrom<Event> for ProtobufEvent {
                     payload: Some(event::Payload::HostTerminalThemeChangedPayload(payload)),
                 })
             },
+            Event::CommandFinished(terminal_pane_id, exit_code) => Ok(ProtobufEvent {
+                name: ProtobufEventType::CommandFinished as i32,
+                payload: Some(event::Payload::CommandFinishedPayload(
+                    CommandFinishedPayload {
+                        terminal_pane_id,
+                        exit_code,
+                    },
+                )),
+            }),
+            Event::RecordingStateChanged(recorded_terminal_pane_ids) => Ok(ProtobufEvent {
+                name: ProtobufEventType::RecordingStateChanged as i32,
+                payload: Some(event::Payload::RecordingStateChangedPayload(
+                    RecordingStateChangedPayload {
+                        recorded_terminal_pane_ids,
+                    },
+                )),
+            }),
             Event::InitialKeybinds(keybinds) => {
                 let mut protobuf_keybinds: Vec<ProtobufInputModeKeybinds> = vec![];
                 for (input_mode, input_mode_keybinds) in keybinds {
-                    let mode: ProtobufInputMode = input_mode.try_into()?;
+                    let mode = input_mode_to_i32(input_mode);
                     let mut key_binds: Vec<ProtobufKeyBind> = vec![];
                     for (key, actions) in input_mode_keybinds {
                         let protobuf_key: ProtobufKey = key.try_into()?;
@@ -1148,7 +1183,7 @@ impl TryFrom<Event> for ProtobufEvent {
                         });
                     }
                     protobuf_keybinds.push(ProtobufInputModeKeybinds {
-                        mode: mode as i32,
+                        mode,
                         key_bind: key_binds,
                     });
                 }
@@ -1387,6 +1422,7 @@ impl TryFrom<ProtobufLayoutMetadata> for LayoutMetadata {
             tabs,
             creation_time: protobuf_metadata.creation_time,
             update_time: protobuf_metadata.update_time,
+            imports: protobuf_metadata.imports,
         })
     }
 }
@@ -1403,6 +1439,7 @@ impl TryFrom<LayoutMetadata> for ProtobufLayoutMetadata {
             tabs,
             creation_time: metadata.creation_time,
             update_time: metadata.update_time,
+            imports: metadata.imports,
         })
     }
 }
@@ -1846,35 +1883,51 @@ impl TryFrom<TabInfo> for ProtobufTabInfo {
     }
 }
 
+fn with_custom_mode_names(action: Action, custom_modes: &[CustomInputMode]) -> Action {
+    match action {
+        Action::SwitchToMode { input_mode } => Action::SwitchToMode {
+            input_mode: input_mode.with_custom_mode_name_from(custom_modes),
+        },
+        Action::SwitchModeForAllClients { input_mode } => Action::SwitchModeForAllClients {
+            input_mode: input_mode.with_custom_mode_name_from(custom_modes),
+        },
+        action => action,
+    }
+}
+
 impl TryFrom<ProtobufModeUpdatePayload> for ModeInfo {
     type Error = &'static str;
     fn try_from(
         mut protobuf_mode_update_payload: ProtobufModeUpdatePayload,
     ) -> Result<Self, &'static str> {
+        // custom modes are sent by id, their names are restored from this list
+        let custom_modes: Vec<CustomInputMode> = protobuf_mode_update_payload
+            .custom_modes
+            .drain(..)
+            .filter_map(|c| c.try_into().ok())
+            .collect();
+        let named = |input_mode: InputMode| input_mode.with_custom_mode_name_from(&custom_modes);
         let current_mode: InputMode =
-            ProtobufInputMode::from_i32(protobuf_mode_update_payload.current_mode)
-                .ok_or("Malformed InputMode in the ModeUpdate Event")?
-                .try_into()?;
+            input_mode_from_i32(protobuf_mode_update_payload.current_mode)
+                .map(named)
+                .ok_or("Malformed InputMode in the ModeUpdate Event")?;
         let base_mode: Option<InputMode> = protobuf_mode_update_payload
             .base_mode
-            .and_then(|b_m| ProtobufInputMode::from_i32(b_m)?.try_into().ok());
+            .and_then(input_mode_from_i32)
+            .map(named);
         let keybinds: Vec<(InputMode, Vec<(KeyWithModifier, Vec<Action>)>)> =
             protobuf_mode_update_payload
                 .keybinds
                 .iter_mut()
                 .filter_map(|k| {
-                    let input_mode: InputMode = ProtobufInputMode::from_i32(k.mode)
-                        .ok_or("Malformed InputMode in the ModeUpdate Event")
-                        .ok()?
-                        .try_into()
-                        .ok()?;
+                    let input_mode: InputMode = named(input_mode_from_i32(k.mode)?);
                     let mut keybinds: Vec<(KeyWithModifier, Vec<Action>)> = vec![];
                     for mut protobuf_keybind in k.key_bind.drain(..) {
                         let key: KeyWithModifier = protobuf_keybind.key.unwrap().try_into().ok()?;
                         let mut actions: Vec<Action> = vec![];
                         for action in protobuf_keybind.action.drain(..) {
                             if let Ok(action) = action.try_into() {
-                                actions.push(action);
+                                actions.push(with_custom_mode_names(action, &custom_modes));
                             }
                         }
                         keybinds.push((key, actions));
@@ -1882,6 +1935,35 @@ impl TryFrom<ProtobufModeUpdatePayload> for ModeInfo {
                     Some((input_mode, keybinds))
                 })
                 .collect();
+        let key_sequences: KeySequencesVec = protobuf_mode_update_payload
+            .key_sequences
+            .iter_mut()
+            .filter_map(|k| {
+                let input_mode: InputMode = named(input_mode_from_i32(k.mode)?);
+                let mut key_sequences: Vec<(Vec<KeyWithModifier>, Vec<Action>)> = vec![];
+                for protobuf_key_sequence_bind in k.key_sequence_bind.drain(..) {
+                    let keys: Vec<KeyWithModifier> = protobuf_key_sequence_bind
+                        .keys
+                        .into_iter()
+                        .map(|k| k.try_into())
+                        .collect::<Result<_, _>>()
+                        .ok()?;
+                    let actions: Vec<Action> = protobuf_key_sequence_bind
+                        .action
+                        .into_iter()
+                        .filter_map(|a| a.try_into().ok())
+                        .map(|a| with_custom_mode_names(a, &custom_modes))
+                        .collect();
+                    key_sequences.push((keys, actions));
+                }
+                Some((input_mode, key_sequences))
+            })
+            .collect();
+        let pending_key_sequence: Vec<KeyWithModifier> = protobuf_mode_update_payload
+            .pending_key_sequence
+            .into_iter()
+            .filter_map(|k| k.try_into().ok())
+            .collect();
         let style: Style = protobuf_mode_update_payload
             .style
             .and_then(|m| m.try_into().ok())
@@ -1930,6 +2012,9 @@ impl TryFrom<ProtobufModeUpdatePayload> for ModeInfo {
             web_server_ip,
             web_server_port,
             web_server_capability,
+            key_sequences,
+            pending_key_sequence,
+            custom_modes,
         };
         Ok(mode_info)
     }
@@ -1938,10 +2023,8 @@ impl TryFrom<ProtobufModeUpdatePayload> for ModeInfo {
 impl TryFrom<ModeInfo> for ProtobufModeUpdatePayload {
     type Error = &'static str;
     fn try_from(mode_info: ModeInfo) -> Result<Self, &'static str> {
-        let current_mode: ProtobufInputMode = mode_info.mode.try_into()?;
-        let base_mode: Option<ProtobufInputMode> = mode_info
-            .base_mode
-            .and_then(|mode| ProtobufInputMode::try_from(mode).ok());
+        let current_mode = input_mode_to_i32(mode_info.mode);
+        let base_mode = mode_info.base_mode.map(input_mode_to_i32);
         let style: ProtobufStyle = mode_info.style.try_into()?;
         let arrow_fonts_support: bool = mode_info.capabilities.arrow_fonts;
         let session_name = mode_info.session_name;
@@ -1956,7 +2039,7 @@ impl TryFrom<ModeInfo> for ProtobufModeUpdatePayload {
         let web_server_capability = mode_info.web_server_capability;
         let mut protobuf_input_mode_keybinds: Vec<ProtobufInputModeKeybinds> = vec![];
         for (input_mode, input_mode_keybinds) in mode_info.keybinds {
-            let mode: ProtobufInputMode = input_mode.try_into()?;
+            let mode = input_mode_to_i32(input_mode);
             let mut keybinds: Vec<ProtobufKeyBind> = vec![];
             for (key, actions) in input_mode_keybinds {
                 let protobuf_key: ProtobufKey = key.try_into()?;
@@ -1973,18 +2056,51 @@ impl TryFrom<ModeInfo> for ProtobufModeUpdatePayload {
                 keybinds.push(key_bind);
             }
             let input_mode_keybind = ProtobufInputModeKeybinds {
-                mode: mode as i32,
+                mode,
                 key_bind: keybinds,
             };
             protobuf_input_mode_keybinds.push(input_mode_keybind);
         }
+        let mut protobuf_input_mode_key_sequences: Vec<ProtobufInputModeKeySequences> = vec![];
+        for (input_mode, input_mode_key_sequences) in mode_info.key_sequences {
+            let mode = input_mode_to_i32(input_mode);
+            let mut key_sequence_binds: Vec<ProtobufKeySequenceBind> = vec![];
+            for (keys, actions) in input_mode_key_sequences {
+                let protobuf_keys: Vec<ProtobufKey> = keys
+                    .into_iter()
+                    .map(|k| k.try_into())
+                    .collect::<Result<_, _>>()?;
+                let protobuf_actions: Vec<ProtobufAction> = actions
+                    .into_iter()
+                    .filter_map(|a| a.try_into().ok())
+                    .collect();
+                key_sequence_binds.push(ProtobufKeySequenceBind {
+                    keys: protobuf_keys,
+                    action: protobuf_actions,
+                });
+            }
+            protobuf_input_mode_key_sequences.push(ProtobufInputModeKeySequences {
+                mode,
+                key_sequence_bind: key_sequence_binds,
+            });
+        }
+        let pending_key_sequence: Vec<ProtobufKey> = mode_info
+            .pending_key_sequence
+            .into_iter()
+            .map(|k| k.try_into())
+            .collect::<Result<_, _>>()?;
+        let custom_modes: Vec<ProtobufCustomInputMode> = mode_info
+            .custom_modes
+            .into_iter()
+            .map(|c| c.into())
+            .collect();
         Ok(ProtobufModeUpdatePayload {
-            current_mode: current_mode as i32,
+            current_mode,
             style: Some(style),
             keybinds: protobuf_input_mode_keybinds,
             arrow_fonts_support,
             session_name,
-            base_mode: base_mode.map(|b_m| b_m as i32),
+            base_mode,
             editor,
             shell,
             web_clients_allowed,
@@ -1994,6 +2110,9 @@ impl TryFrom<ModeInfo> for ProtobufModeUpdatePayload {
             web_server_ip,
             web_server_port,
             web_server_capability,
+            key_sequences: protobuf_input_mode_key_sequences,
+            pending_key_sequence,
+            custom_modes,
         })
     }
 }
@@ -2078,6 +2197,8 @@ impl TryFrom<ProtobufEventType> for EventType {
             ProtobufEventType::HighlightClicked => EventType::HighlightClicked,
             ProtobufEventType::InitialKeybinds => EventType::InitialKeybinds,
             ProtobufEventType::HostTerminalThemeChanged => EventType::HostTerminalThemeChanged,
+            ProtobufEventType::CommandFinished => EventType::CommandFinished,
+            ProtobufEventType::RecordingStateChanged => EventType::RecordingStateChanged,
         })
     }
 }
@@ -2132,6 +2253,8 @@ impl TryFrom<EventType> for ProtobufEventType {
             EventType::HighlightClicked => ProtobufEventType::HighlightClicked,
             EventType::InitialKeybinds => ProtobufEventType::InitialKeybinds,
             EventType::HostTerminalThemeChanged => ProtobufEventType::HostTerminalThemeChanged,
+            EventType::CommandFinished => ProtobufEventType::CommandFinished,
+            EventType::RecordingStateChanged => ProtobufEventType::RecordingStateChanged,
         })
     }
 }
@@ -2309,6 +2432,21 @@ fn serialize_mode_update_event_with_non_default_values() {
         web_server_ip: IpAddr::from_str("127.0.0.1").ok(),
         web_server_port: Some(8082),
         web_server_capability: Some(true),
+        key_sequences: vec![(
+            InputMode::Normal,
+            vec![(
+                vec![
+                    KeyWithModifier::new(BareKey::Char('a')).with_ctrl_modifier(),
+                    KeyWithModifier::new(BareKey::Char('g')),
+                    KeyWithModifier::new(BareKey::Char('s')),
+                ],
+                vec![Action::SwitchToMode {
+                    input_mode: InputMode::Session,
+                }],
+            )],
+        )],
+        pending_key_sequence: vec![KeyWithModifier::new(BareKey::Char('a')).with_ctrl_modifier()],
+        custom_modes: vec![CustomInputMode::new("deploy")],
     });
     let protobuf_event: ProtobufEvent = mode_update_event.clone().try_into().unwrap();
     let serialized_protobuf_event = protobuf_event.encode_to_vec();
@@ -2321,6 +2459,53 @@ fn serialize_mode_update_event_with_non_default_values() {
     );
 }
 
+#[test]
+fn serialize_mode_update_event_with_custom_mode() {
+    use crate::data::BareKey;
+    use prost::Message;
+    let git_mode = InputMode::Custom(CustomInputMode::new("git"));
+    let mode_update_event = Event::ModeUpdate(ModeInfo {
+        mode: git_mode,
+        base_mode: Some(InputMode::Normal),
+        keybinds: vec![
+            (
+                InputMode::Normal,
+                vec![(
+                    KeyWithModifier::new(BareKey::Char('y')).with_ctrl_modifier(),
+                    vec![Action::SwitchToMode {
+                        input_mode: git_mode,
+                    }],
+                )],
+            ),
+            (
+                git_mode,
+                vec![(
+                    KeyWithModifier::new(BareKey::Esc),
+                    vec![Action::SwitchToMode {
+                        input_mode: InputMode::Normal,
+                    }],
+                )],
+            ),
+        ],
+        custom_modes: vec![CustomInputMode::new("git")],
+        ..Default::default()
+    });
+    let protobuf_event: ProtobufEvent = mode_update_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        mode_update_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+    assert_eq!(
+        format!("{:?}", mode_update_event),
+        format!("{:?}", deserialized_event),
+        "Names of the custom modes restored"
+    );
+}
+
 #[test]
 fn serialize_tab_update_event() {
     use prost::Message;
@@ -2773,6 +2958,7 @@ fn serialize_session_update_event_with_non_default_values() {
                     tabs: vec![],
                     creation_time: "0".to_owned(),
                     update_time: "0".to_owned(),
+                    imports: vec!["common-templates.kdl".to_owned()],
                 },
             ),
             LayoutInfo::BuiltIn("layout2".to_owned()),
@@ -2782,6 +2968,7 @@ fn serialize_session_update_event_with_non_default_values() {
                     tabs: vec![],
                     creation_time: "0".to_owned(),
                     update_time: "0".to_owned(),
+                    imports: vec![],
                 },
             ),
         ],
@@ -2807,6 +2994,7 @@ fn serialize_session_update_event_with_non_default_values() {
                     tabs: vec![],
                     creation_time: "0".to_owned(),
                     update_time: "0".to_owned(),
+                    imports: vec![],
                 },
             ),
             LayoutInfo::BuiltIn("layout2".to_owned()),
@@ -2816,6 +3004,7 @@ fn serialize_session_update_event_with_non_default_values() {
                     tabs: vec![],
                     creation_time: "0".to_owned(),
                     update_time: "0".to_owned(),
+                    imports: vec![],
                 },
             ),
         ],
@@ -3117,3 +3306,39 @@ fn serialize_pane_render_report_with_ansi_event_with_data() {
         "PaneRenderReportWithAnsi event with ANSI data properly serialized/deserialized"
     );
 }
+
+#[test]
+fn serialize_command_finished_event() {
+    use prost::Message;
+    for exit_code in [Some(0), Some(127), None] {
+        let command_finished_event = Event::CommandFinished(3, exit_code);
+        let protobuf_event: ProtobufEvent = command_finished_event.clone().try_into().unwrap();
+        let serialized_protobuf_event = protobuf_event.encode_to_vec();
+        let deserialized_protobuf_event: ProtobufEvent =
+            Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+        let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+        assert_eq!(
+            command_finished_event, deserialized_event,
+            "Event properly serialized/deserialized without change"
+        );
+    }
+}
+
+#[test]
+fn serialize_recording_state_changed_event() {
+    use prost::Message;
+    for recorded_terminal_pane_ids in [vec![], vec![1, 3]] {
+        let recording_state_changed_event =
+            Event::RecordingStateChanged(recorded_terminal_pane_ids);
+        let protobuf_event: ProtobufEvent =
+            recording_state_changed_event.clone().try_into().unwrap();
+        let serialized_protobuf_event = protobuf_event.encode_to_vec();
+        let deserialized_protobuf_event: ProtobufEvent =
+            Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+        let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+        assert_eq!(
+            recording_state_changed_event, deserialized_event,
+            "Event properly serialized/deserialized without change"
+        );
+    }
+}
@@ -1,7 +1,8 @@
 pub use super::generated_api::api::input_mode::{
-    InputMode as ProtobufInputMode, InputModeMessage as ProtobufInputModeMessage,
+    CustomInputMode as ProtobufCustomInputMode, InputMode as ProtobufInputMode,
+    InputModeMessage as ProtobufInputModeMessage,
 };
-use crate::data::InputMode;
+use crate::data::{CustomInputMode, InputMode};
 
 use std::convert::TryFrom;
 
@@ -23,6 +24,7 @@ impl TryFrom<ProtobufInputMode> for InputMode {
             ProtobufInputMode::Move => Ok(InputMode::Move),
             ProtobufInputMode::Prompt => Ok(InputMode::Prompt),
             ProtobufInputMode::Tmux => Ok(InputMode::Tmux),
+            ProtobufInputMode::Copy => Ok(InputMode::Copy),
         }
     }
 }
@@ -45,25 +47,67 @@ impl TryFrom<InputMode> for ProtobufInputMode {
             InputMode::Move => ProtobufInputMode::Move,
             InputMode::Prompt => ProtobufInputMode::Prompt,
             InputMode::Tmux => ProtobufInputMode::Tmux,
+            InputMode::Copy => ProtobufInputMode::Copy,
+            InputMode::Custom(_) => {
+                return Err("Custom input modes have no protobuf enum value, use input_mode_to_i32")
+            },
         })
     }
 }
 
+/// Converts the value of a protobuf `InputMode` field, which is either one of the built-in modes
+/// or the id of a custom mode
+pub fn input_mode_from_i32(input_mode: i32) -> Option<InputMode> {
+    match ProtobufInputMode::from_i32(input_mode) {
+        Some(protobuf_input_mode) => protobuf_input_mode.try_into().ok(),
+        None => u32::try_from(input_mode)
+            .ok()
+            .and_then(CustomInputMode::from_id)
+            .map(InputMode::Custom),
+    }
+}
+
+pub fn input_mode_to_i32(input_mode: InputMode) -> i32 {
+    match input_mode {
+        InputMode::Custom(custom_input_mode) => custom_input_mode.id() as i32,
+        built_in_mode => ProtobufInputMode::try_from(built_in_mode)
+            .map(|m| m as i32)
+            .unwrap_or_default(),
+    }
+}
+
+impl TryFrom<ProtobufCustomInputMode> for CustomInputMode {
+    type Error = &'static str;
+    fn try_from(protobuf_custom_input_mode: ProtobufCustomInputMode) -> Result<Self, &'static str> {
+        let custom_input_mode = CustomInputMode::new(&protobuf_custom_input_mode.name);
+        if custom_input_mode.id() != protobuf_custom_input_mode.id {
+            return Err("Invalid custom input mode id");
+        }
+        Ok(custom_input_mode)
+    }
+}
+
+impl From<CustomInputMode> for ProtobufCustomInputMode {
+    fn from(custom_input_mode: CustomInputMode) -> Self {
+        ProtobufCustomInputMode {
+            id: custom_input_mode.id(),
+            name: custom_input_mode.name(),
+        }
+    }
+}
+
 impl TryFrom<ProtobufInputModeMessage> for InputMode {
     type Error = &'static str;
     fn try_from(protobuf_input_mode: ProtobufInputModeMessage) -> Result<Self, &'static str> {
-        ProtobufInputMode::from_i32(protobuf_input_mode.input_mode)
-            .and_then(|p| p.try_into().ok())
-            .ok_or("Invalid input mode")
+        input_mode_from_i32(protobuf_input_mode.input_mode).ok_or("Invalid input mode")
     }
 }
 
 impl TryFrom<InputMode> for ProtobufInputModeMessage {
     type Error = &'static str;
     fn try_from(input_mode: InputMode) -> Result<Self, &'static str> {
-        let protobuf_input_mode: ProtobufInputMode = input_mode.try_into()?;
         Ok(ProtobufInputModeMessage {
-            input_mode: protobuf_input_mode as i32,
+            input_mode: input_mode_to_i32(input_mode),
         })
     }
 }
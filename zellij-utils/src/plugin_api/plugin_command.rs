@@ -61,8 +61,9 @@ pub use super::generated_api::api::{
         HideFloatingPanesResponse as ProtobufHideFloatingPanesResponse, HidePaneWithIdPayload,
         HighlightAndUnhighlightPanesPayload, HighlightLayer as ProtobufHighlightLayer,
         HighlightStyle as ProtobufHighlightStyle, HttpVerb as ProtobufHttpVerb, IdAndNewName,
-        KeyToRebind, KeyToUnbind, KillSessionsPayload,
-        KillSessionsResponse as ProtobufKillSessionsResponse, ListTokensResponse,
+        KeySequenceToRebind, KeySequenceToUnbind, KeyToRebind, KeyToUnbind, KillSessionsPayload,
+        KillSessionsResponse as ProtobufKillSessionsResponse,
+        ListRecentlyClosedResponse as ProtobufListRecentlyClosedResponse, ListTokensResponse,
         LoadNewPluginPayload, MessageToPluginPayload, MovePaneWithPaneIdInDirectionPayload,
         MovePaneWithPaneIdPayload, MovePayload, NewPluginArgs as ProtobufNewPluginArgs,
         NewTabPayload, NewTabResponse as ProtobufNewTabResponse,
@@ -107,20 +108,21 @@ pub use super::generated_api::api::{
         PageScrollDownInPaneIdPayload, PageScrollUpInPaneIdPayload, PaneId as ProtobufPaneId,
         PaneIdAndFloatingPaneCoordinates, PaneType as ProtobufPaneType, ParseLayoutPayload,
         ParseLayoutResponse as ProtobufParseLayoutResponse, PluginCommand as ProtobufPluginCommand,
-        PluginMessagePayload, RebindKeysPayload, ReconfigurePayload,
-        RegexHighlight as ProtobufRegexHighlight, ReloadPluginPayload, RenameLayoutPayload,
-        RenameLayoutResponse as ProtobufRenameLayoutResponse, RenameTabWithIdPayload,
-        RenameWebLoginTokenPayload, RenameWebTokenResponse, ReplacePaneWithExistingPanePayload,
-        RequestPluginPermissionPayload, RerunCommandPanePayload, ResizePaneIdWithDirectionPayload,
-        ResizePayload, RevokeAllWebTokensResponse, RevokeTokenResponse, RevokeWebLoginTokenPayload,
+        PluginMessagePayload, RebindKeysPayload, RecentlyClosedInfo as ProtobufRecentlyClosedInfo,
+        ReconfigurePayload, RegexHighlight as ProtobufRegexHighlight, ReloadPluginPayload,
+        RenameLayoutPayload, RenameLayoutResponse as ProtobufRenameLayoutResponse,
+        RenameTabWithIdPayload, RenameWebLoginTokenPayload, RenameWebTokenResponse,
+        ReopenClosedPayload, ReplacePaneWithExistingPanePayload, RequestPluginPermissionPayload,
+        RerunCommandPanePayload, ResizePaneIdWithDirectionPayload, ResizePayload,
+        RevokeAllWebTokensResponse, RevokeTokenResponse, RevokeWebLoginTokenPayload,
         RunActionPayload, RunCommandPayload, RunningCommand as ProtobufRunningCommand,
         SaveLayoutPayload, SaveLayoutResponse as ProtobufSaveLayoutResponse, SaveSessionPayload,
         SaveSessionResponse as ProtobufSaveSessionResponse, ScrollDownInPaneIdPayload,
         ScrollToBottomInPaneIdPayload, ScrollToTopInPaneIdPayload, ScrollUpInPaneIdPayload,
         SessionListSnapshot as ProtobufSessionListSnapshot, SetFloatingPanePinnedPayload,
         SetPaneBorderlessPayload, SetPaneColorPayload, SetPaneRegexHighlightsPayload,
-        SetSelfMouseSelectionSupportPayload, SetTimeoutPayload, ShowCursorPayload,
-        ShowFloatingPanesPayload as ProtobufShowFloatingPanesPayload,
+        SetSelfMouseSelectionSupportPayload, SetSyncGroupPayload, SetTimeoutPayload,
+        ShowCursorPayload, ShowFloatingPanesPayload as ProtobufShowFloatingPanesPayload,
         ShowFloatingPanesResponse as ProtobufShowFloatingPanesResponse, ShowPaneWithIdPayload,
         StackPanesPayload, SubscribePayload, SwitchSessionPayload, SwitchTabToIdPayload,
         SwitchTabToPayload, TogglePaneBorderlessPayload, TogglePaneEmbedOrEjectForPaneIdPayload,
@@ -131,13 +133,14 @@ pub use super::generated_api::api::{
     resize::ResizeAction as ProtobufResizeAction,
 };
 
+use super::input_mode::{input_mode_from_i32, input_mode_to_i32};
 use crate::data::{
     ConnectToSession, DeleteAllDeadSessionsResponse, DeleteDeadSessionResponse,
     DeleteLayoutResponse, EditLayoutResponse, FloatingPaneCoordinates, GetFocusedPaneInfoResponse,
     GetPaneCwdResponse, GetPanePidResponse, GetPaneRunningCommandResponse, GetSessionListResponse,
     HighlightLayer, HighlightStyle, HttpVerb, InputMode, KeyWithModifier, KillSessionsResponse,
-    MessageToPlugin, NewPluginArgs, PaneId, PermissionType, PluginCommand, RegexHighlight,
-    RenameLayoutResponse, SaveLayoutResponse, SessionInfo, SessionListSnapshot,
+    MessageToPlugin, NewPluginArgs, PaneId, PermissionType, PluginCommand, RecentlyClosedInfo,
+    RegexHighlight, RenameLayoutResponse, SaveLayoutResponse, SessionInfo, SessionListSnapshot,
 };
 use crate::input::actions::Action;
 use crate::input::layout::PercentOrFixed;
@@ -611,7 +614,7 @@ impl TryFrom<(InputMode, KeyWithModifier, Vec<Action>)> for KeyToRebind {
         key_to_rebind: (InputMode, KeyWithModifier, Vec<Action>),
     ) -> Result<Self, &'static str> {
         Ok(KeyToRebind {
-            input_mode: key_to_rebind.0 as i32,
+            input_mode: input_mode_to_i32(key_to_rebind.0),
             key: Some(key_to_rebind.1.try_into()?),
             actions: key_to_rebind
                 .2
@@ -626,19 +629,54 @@ impl TryFrom<(InputMode, KeyWithModifier)> for KeyToUnbind {
     type Error = &'static str;
     fn try_from(key_to_unbind: (InputMode, KeyWithModifier)) -> Result<Self, &'static str> {
         Ok(KeyToUnbind {
-            input_mode: key_to_unbind.0 as i32,
+            input_mode: input_mode_to_i32(key_to_unbind.0),
             key: Some(key_to_unbind.1.try_into()?),
         })
     }
 }
 
+impl TryFrom<(InputMode, Vec<KeyWithModifier>, Vec<Action>)> for KeySequenceToRebind {
+    type Error = &'static str;
+    fn try_from(
+        key_sequence_to_rebind: (InputMode, Vec<KeyWithModifier>, Vec<Action>),
+    ) -> Result<Self, &'static str> {
+        Ok(KeySequenceToRebind {
+            input_mode: input_mode_to_i32(key_sequence_to_rebind.0),
+            keys: key_sequence_to_rebind
+                .1
+                .into_iter()
+                .map(|k| k.try_into())
+                .collect::<Result<_, _>>()?,
+            actions: key_sequence_to_rebind
+                .2
+                .into_iter()
+                .filter_map(|a| a.try_into().ok())
+                .collect(),
+        })
+    }
+}
+
+impl TryFrom<(InputMode, Vec<KeyWithModifier>)> for KeySequenceToUnbind {
+    type Error = &'static str;
+    fn try_from(
+        key_sequence_to_unbind: (InputMode, Vec<KeyWithModifier>),
+    ) -> Result<Self, &'static str> {
+        Ok(KeySequenceToUnbind {
+            input_mode: input_mode_to_i32(key_sequence_to_unbind.0),
+            keys: key_sequence_to_unbind
+                .1
+                .into_iter()
+                .map(|k| k.try_into())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
 fn key_to_rebind_to_plugin_command_assets(
     key_to_rebind: KeyToRebind,
 ) -> Option<(InputMode, KeyWithModifier, Vec<Action>)> {
     Some((
-        ProtobufInputMode::from_i32(key_to_rebind.input_mode)?
-            .try_into()
-            .ok()?,
+        input_mode_from_i32(key_to_rebind.input_mode)?,
         key_to_rebind.key?.try_into().ok()?,
         key_to_rebind
             .actions
@@ -648,13 +686,42 @@ fn key_to_rebind_to_plugin_command_assets(
     ))
 }
 
+fn key_sequence_to_rebind_to_plugin_command_assets(
+    key_sequence_to_rebind: KeySequenceToRebind,
+) -> Option<(InputMode, Vec<KeyWithModifier>, Vec<Action>)> {
+    Some((
+        input_mode_from_i32(key_sequence_to_rebind.input_mode)?,
+        key_sequence_to_rebind
+            .keys
+            .into_iter()
+            .map(|k| k.try_into().ok())
+            .collect::<Option<_>>()?,
+        key_sequence_to_rebind
+            .actions
+            .into_iter()
+            .filter_map(|a| a.try_into().ok())
+            .collect(),
+    ))
+}
+
+fn key_sequence_to_unbind_to_plugin_command_assets(
+    key_sequence_to_unbind: KeySequenceToUnbind,
+) -> Option<(InputMode, Vec<KeyWithModifier>)> {
+    Some((
+        input_mode_from_i32(key_sequence_to_unbind.input_mode)?,
+        key_sequence_to_unbind
+            .keys
+            .into_iter()
+            .map(|k| k.try_into().ok())
+            .collect::<Option<_>>()?,
+    ))
+}
+
 fn key_to_unbind_to_plugin_command_assets(
     key_to_unbind: KeyToUnbind,
 ) -> Option<(InputMode, KeyWithModifier)> {
     Some((
-        ProtobufInputMode::from_i32(key_to_unbind.input_mode)?
-            .try_into()
-            .ok()?,
+        input_mode_from_i32(key_to_unbind.input_mode)?,
         key_to_unbind.key?.try_into().ok()?,
     ))
 }
@@ -872,10 +939,8 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
             },
             Some(CommandName::SwitchToMode) => match protobuf_plugin_command.payload {
                 Some(Payload::SwitchToModePayload(switch_to_mode_payload)) => {
-                    match ProtobufInputMode::from_i32(switch_to_mode_payload.input_mode) {
-                        Some(protobuf_input_mode) => {
-                            Ok(PluginCommand::SwitchToMode(protobuf_input_mode.try_into()?))
-                        },
+                    match input_mode_from_i32(switch_to_mode_payload.input_mode) {
+                        Some(input_mode) => Ok(PluginCommand::SwitchToMode(input_mode)),
                         None => Err("Malformed switch to mode payload"),
                     }
                 },
@@ -1448,6 +1513,28 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
             Some(CommandName::DeleteAllDeadSessionsAndReply) => {
                 Ok(PluginCommand::DeleteAllDeadSessionsAndReply)
             },
+            Some(CommandName::SetSyncGroup) => match protobuf_plugin_command.payload {
+                Some(Payload::SetSyncGroupPayload(set_sync_group_payload)) => {
+                    Ok(PluginCommand::SetSyncGroup(
+                        set_sync_group_payload
+                            .pane_ids
+                            .into_iter()
+                            .filter_map(|p_id| p_id.try_into().ok())
+                            .collect(),
+                    ))
+                },
+                _ => Err("Mismatched payload for SetSyncGroup"),
+            },
+            Some(CommandName::ListRecentlyClosed) => match protobuf_plugin_command.payload {
+                Some(_) => Err("ListRecentlyClosed should not have a payload"),
+                None => Ok(PluginCommand::ListRecentlyClosed),
+            },
+            Some(CommandName::ReopenClosed) => match protobuf_plugin_command.payload {
+                Some(Payload::ReopenClosedPayload(reopen_closed_payload)) => {
+                    Ok(PluginCommand::ReopenClosed(reopen_closed_payload.id))
+                },
+                _ => Err("Mismatched payload for ReopenClosed"),
+            },
             Some(CommandName::DumpSessionLayout) => match protobuf_plugin_command.payload {
                 Some(Payload::DumpSessionLayoutPayload(payload)) => {
                     Ok(PluginCommand::DumpSessionLayout {
@@ -1463,11 +1550,16 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
             },
             Some(CommandName::NewTabsWithLayoutInfo) => match protobuf_plugin_command.payload {
                 Some(Payload::NewTabsWithLayoutInfoPayload(new_tabs_with_layout_info_payload)) => {
+                    let layout_args = new_tabs_with_layout_info_payload
+                        .layout_args
+                        .into_iter()
+                        .collect();
                     new_tabs_with_layout_info_payload
                         .layout_info
                         .and_then(|layout_info| {
                             Some(PluginCommand::NewTabsWithLayoutInfo(
                                 layout_info.try_into().ok()?,
+                                layout_args,
                             ))
                         })
                         .ok_or("Failed to parse NewTabsWithLayoutInfo command")
@@ -1931,6 +2023,16 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
                             .into_iter()
                             .filter_map(|k| key_to_unbind_to_plugin_command_assets(k))
                             .collect(),
+                        key_sequences_to_rebind: rebind_keys_payload
+                            .key_sequences_to_rebind
+                            .into_iter()
+                            .filter_map(key_sequence_to_rebind_to_plugin_command_assets)
+                            .collect(),
+                        key_sequences_to_unbind: rebind_keys_payload
+                            .key_sequences_to_unbind
+                            .into_iter()
+                            .filter_map(key_sequence_to_unbind_to_plugin_command_assets)
+                            .collect(),
                         write_config_to_disk: rebind_keys_payload.write_config_to_disk,
                     })
                 },
@@ -2879,7 +2981,7 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
             PluginCommand::SwitchToMode(input_mode) => Ok(ProtobufPluginCommand {
                 name: CommandName::SwitchToMode as i32,
                 payload: Some(Payload::SwitchToModePayload(SwitchToModePayload {
-                    input_mode: ProtobufInputMode::try_from(input_mode)? as i32,
+                    input_mode: input_mode_to_i32(input_mode),
                 })),
             }),
             PluginCommand::NewTabsWithLayout(raw_layout) => Ok(ProtobufPluginCommand {
@@ -3315,6 +3417,23 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
                 name: CommandName::DeleteAllDeadSessionsAndReply as i32,
                 payload: None,
             }),
+            PluginCommand::SetSyncGroup(pane_ids) => Ok(ProtobufPluginCommand {
+                name: CommandName::SetSyncGroup as i32,
+                payload: Some(Payload::SetSyncGroupPayload(SetSyncGroupPayload {
+                    pane_ids: pane_ids
+                        .into_iter()
+                        .filter_map(|p_id| p_id.try_into().ok())
+                        .collect(),
+                })),
+            }),
+            PluginCommand::ListRecentlyClosed => Ok(ProtobufPluginCommand {
+                name: CommandName::ListRecentlyClosed as i32,
+                payload: None,
+            }),
+            PluginCommand::ReopenClosed(id) => Ok(ProtobufPluginCommand {
+                name: CommandName::ReopenClosed as i32,
+                payload: Some(Payload::ReopenClosedPayload(ReopenClosedPayload { id })),
+            }),
             PluginCommand::DumpSessionLayout { tab_index } => Ok(ProtobufPluginCommand {
                 name: CommandName::DumpSessionLayout as i32,
                 payload: tab_index.map(|idx| {
@@ -3327,12 +3446,13 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
                 name: CommandName::CloseSelf as i32,
                 payload: None,
             }),
-            PluginCommand::NewTabsWithLayoutInfo(new_tabs_with_layout_info_payload) => {
+            PluginCommand::NewTabsWithLayoutInfo(layout_info, layout_args) => {
                 Ok(ProtobufPluginCommand {
                     name: CommandName::NewTabsWithLayoutInfo as i32,
                     payload: Some(Payload::NewTabsWithLayoutInfoPayload(
                         NewTabsWithLayoutInfoPayload {
-                            layout_info: new_tabs_with_layout_info_payload.try_into().ok(),
+                            layout_info: layout_info.try_into().ok(),
+                            layout_args: layout_args.into_iter().collect(),
                         },
                     )),
                 })
@@ -3723,6 +3843,8 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
             PluginCommand::RebindKeys {
                 keys_to_rebind,
                 keys_to_unbind,
+                key_sequences_to_rebind,
+                key_sequences_to_unbind,
                 write_config_to_disk,
             } => Ok(ProtobufPluginCommand {
                 name: CommandName::RebindKeys as i32,
@@ -3735,6 +3857,14 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
                         .into_iter()
                         .filter_map(|k| k.try_into().ok())
                         .collect(),
+                    key_sequences_to_rebind: key_sequences_to_rebind
+                        .into_iter()
+                        .filter_map(|k| k.try_into().ok())
+                        .collect(),
+                    key_sequences_to_unbind: key_sequences_to_unbind
+                        .into_iter()
+                        .filter_map(|k| k.try_into().ok())
+                        .collect(),
                     write_config_to_disk,
                 })),
             }),
@@ -5053,3 +5183,23 @@ impl From<OpenPluginPaneFloatingResponse> for ProtobufOpenPluginPaneFloatingResp
         }
     }
 }
+
+impl From<RecentlyClosedInfo> for ProtobufRecentlyClosedInfo {
+    fn from(recently_closed_info: RecentlyClosedInfo) -> Self {
+        ProtobufRecentlyClosedInfo {
+            id: recently_closed_info.id,
+            is_tab: recently_closed_info.is_tab,
+            name: recently_closed_info.name,
+        }
+    }
+}
+
+impl From<ProtobufRecentlyClosedInfo> for RecentlyClosedInfo {
+    fn from(protobuf_recently_closed_info: ProtobufRecentlyClosedInfo) -> Self {
+        RecentlyClosedInfo {
+            id: protobuf_recently_closed_info.id,
+            is_tab: protobuf_recently_closed_info.is_tab,
+            name: protobuf_recently_closed_info.name,
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 
 use crate::{
+    input::command::RestartPolicy,
     input::layout::PluginUserConfiguration,
     input::layout::{
         FloatingPaneLayout, Layout, LayoutConstraint, PercentOrFixed, Run, RunPluginOrAlias,
@@ -36,8 +37,10 @@ pub struct PaneLayoutManifest {
     pub title: Option<String>,
     pub is_focused: bool,
     pub pane_contents: Option<String>,
+    pub scrollback_file: Option<PathBuf>,
     pub default_fg: Option<String>,
     pub default_bg: Option<String>,
+    pub scratchpad: Option<String>,
 }
 
 pub fn serialize_session_layout(
@@ -165,6 +168,7 @@ fn serialize_tiled_pane(
     pane_contents: &mut BTreeMap<String, String>,
 ) -> KdlNode {
     let (command, args) = extract_command_and_args(&layout.run);
+    let env = extract_env(&layout.run);
     let (plugin, plugin_config) = extract_plugin_and_config(&layout.run);
     let (edit, _line_number) = extract_edit_and_line_number(&layout.run);
     let cwd = layout.run.as_ref().and_then(|r| r.get_cwd());
@@ -182,6 +186,12 @@ fn serialize_tiled_pane(
         has_children,
         &mut tiled_pane_node,
     );
+    serialize_scrollback_file(
+        &command,
+        &edit,
+        &layout.pane_scrollback_file,
+        &mut tiled_pane_node,
+    );
 
     serialize_tiled_layout_attributes(&layout, ignore_size, &mut tiled_pane_node);
     if let Some(ref fg) = layout.default_fg {
@@ -197,11 +207,14 @@ fn serialize_tiled_pane(
     let has_child_attributes = !layout.children.is_empty()
         || layout.external_children_index.is_some()
         || !args.is_empty()
+        || !env.is_empty()
         || plugin.is_some()
         || command.is_some();
     if has_child_attributes {
         let mut tiled_pane_node_children = KdlDocument::new();
         serialize_args(args, &mut tiled_pane_node_children);
+        serialize_env(env, &mut tiled_pane_node_children);
+        serialize_restart_policy(&layout.run, &mut tiled_pane_node_children);
         serialize_start_suspended(&command, &mut tiled_pane_node_children);
         serialize_plugin(plugin, plugin_config, &mut tiled_pane_node_children);
         if layout.children.is_empty() && layout.external_children_index.is_some() {
@@ -234,6 +247,13 @@ pub fn extract_command_and_args(layout_run: &Option<Run>) -> (Option<String>, Ve
         _ => (None, vec![]),
     }
 }
+pub fn extract_env(layout_run: &Option<Run>) -> BTreeMap<String, String> {
+    match layout_run {
+        Some(Run::Command(run_command)) => run_command.env.clone(),
+        _ => BTreeMap::new(),
+    }
+}
+
 pub fn extract_plugin_and_config(
     layout_run: &Option<Run>,
 ) -> (Option<String>, Option<PluginUserConfiguration>) {
@@ -322,6 +342,24 @@ fn serialize_pane_title_and_attributes(
     }
 }
 
+fn serialize_scrollback_file(
+    command: &Option<String>,
+    edit: &Option<String>,
+    scrollback_file: &Option<PathBuf>,
+    kdl_node: &mut KdlNode,
+) {
+    // like the initial contents, the scrollback of command and edit panes is not restored
+    // because they are started anew
+    if let Some(scrollback_file) = scrollback_file.as_ref() {
+        if command.is_none() && edit.is_none() {
+            kdl_node.entries_mut().push(KdlEntry::new_prop(
+                "scrollback_file",
+                scrollback_file.display().to_string(),
+            ));
+        }
+    }
+}
+
 fn serialize_args(args: Vec<String>, pane_node_children: &mut KdlDocument) {
     if !args.is_empty() {
         let mut args_node = KdlNode::new("args");
@@ -332,6 +370,39 @@ fn serialize_args(args: Vec<String>, pane_node_children: &mut KdlDocument) {
     }
 }
 
+fn serialize_env(env: BTreeMap<String, String>, pane_node_children: &mut KdlDocument) {
+    if !env.is_empty() {
+        let mut env_node = KdlNode::new("env");
+        let mut env_node_children = KdlDocument::new();
+        for (key, value) in env {
+            let mut env_var_node = KdlNode::new(key);
+            env_var_node.entries_mut().push(KdlEntry::new(value));
+            env_node_children.nodes_mut().push(env_var_node);
+        }
+        env_node.set_children(env_node_children);
+        pane_node_children.nodes_mut().push(env_node);
+    }
+}
+
+fn serialize_restart_policy(layout_run: &Option<Run>, pane_node_children: &mut KdlDocument) {
+    if let Some(Run::Command(run_command)) = layout_run {
+        if run_command.restart_policy != RestartPolicy::Never {
+            let mut restart_node = KdlNode::new("restart");
+            restart_node
+                .entries_mut()
+                .push(KdlEntry::new(run_command.restart_policy.to_string()));
+            pane_node_children.nodes_mut().push(restart_node);
+            if let Some(max_restarts) = run_command.max_restarts {
+                let mut max_restarts_node = KdlNode::new("max_restarts");
+                max_restarts_node
+                    .entries_mut()
+                    .push(KdlEntry::new(max_restarts as i64));
+                pane_node_children.nodes_mut().push(max_restarts_node);
+            }
+        }
+    }
+}
+
 fn serialize_plugin(
     plugin: Option<String>,
     plugin_config: Option<PluginUserConfiguration>,
@@ -542,9 +613,9 @@ fn serialize_swap_tiled_layouts(
                 };
             let mut layout_step_node = KdlNode::new("tab");
             let mut layout_step_node_children = KdlDocument::new();
-            if let Some(layout_constraint_entry) = serialize_layout_constraint(layout_constraint) {
-                layout_step_node.entries_mut().push(layout_constraint_entry);
-            }
+            layout_step_node
+                .entries_mut()
+                .append(&mut serialize_layout_constraint(layout_constraint));
 
             serialize_tiled_and_floating_panes(
                 &tiled_panes_layout,
@@ -564,21 +635,49 @@ fn serialize_swap_tiled_layouts(
     }
 }
 
-fn serialize_layout_constraint(layout_constraint: LayoutConstraint) -> Option<KdlEntry> {
+fn serialize_layout_constraint(layout_constraint: LayoutConstraint) -> Vec<KdlEntry> {
     match layout_constraint {
-        LayoutConstraint::MaxPanes(max_panes) => Some(KdlEntry::new_prop(
+        LayoutConstraint::Combined(layout_constraints) => layout_constraints
+            .into_iter()
+            .flat_map(serialize_layout_constraint)
+            .collect(),
+        LayoutConstraint::MinColumns(min_columns) => vec![KdlEntry::new_prop(
+            "min_columns",
+            KdlValue::Base10(min_columns as i64),
+        )],
+        LayoutConstraint::MaxColumns(max_columns) => vec![KdlEntry::new_prop(
+            "max_columns",
+            KdlValue::Base10(max_columns as i64),
+        )],
+        LayoutConstraint::MinRows(min_rows) => vec![KdlEntry::new_prop(
+            "min_rows",
+            KdlValue::Base10(min_rows as i64),
+        )],
+        LayoutConstraint::MaxRows(max_rows) => vec![KdlEntry::new_prop(
+            "max_rows",
+            KdlValue::Base10(max_rows as i64),
+        )],
+        LayoutConstraint::MinAspectRatio(min_aspect_ratio) => vec![KdlEntry::new_prop(
+            "min_aspect_ratio",
+            KdlValue::Base10Float(min_aspect_ratio as f64 / 100.0),
+        )],
+        LayoutConstraint::MaxAspectRatio(max_aspect_ratio) => vec![KdlEntry::new_prop(
+            "max_aspect_ratio",
+            KdlValue::Base10Float(max_aspect_ratio as f64 / 100.0),
+        )],
+        LayoutConstraint::MaxPanes(max_panes) => vec![KdlEntry::new_prop(
             "max_panes",
             KdlValue::Base10(max_panes as i64),
-        )),
-        LayoutConstraint::MinPanes(min_panes) => Some(KdlEntry::new_prop(
+        )],
+        LayoutConstraint::MinPanes(min_panes) => vec![KdlEntry::new_prop(
             "min_panes",
             KdlValue::Base10(min_panes as i64),
-        )),
-        LayoutConstraint::ExactPanes(exact_panes) => Some(KdlEntry::new_prop(
+        )],
+        LayoutConstraint::ExactPanes(exact_panes) => vec![KdlEntry::new_prop(
             "exact_panes",
             KdlValue::Base10(exact_panes as i64),
-        )),
-        LayoutConstraint::NoConstraint => None,
+        )],
+        LayoutConstraint::NoConstraint => vec![],
     }
 }
 
@@ -600,9 +699,9 @@ fn serialize_swap_floating_layouts(
         for (layout_constraint, floating_panes_layout) in swap_floating_layout.0 {
             let mut layout_step_node = KdlNode::new("floating_panes");
             let mut layout_step_node_children = KdlDocument::new();
-            if let Some(layout_constraint_entry) = serialize_layout_constraint(layout_constraint) {
-                layout_step_node.entries_mut().push(layout_constraint_entry);
-            }
+            layout_step_node
+                .entries_mut()
+                .append(&mut serialize_layout_constraint(layout_constraint));
 
             for floating_pane_layout in floating_panes_layout {
                 let floating_pane_node =
@@ -656,6 +755,7 @@ fn serialize_floating_pane(
     let mut floating_pane_node = KdlNode::new("pane");
     let mut floating_pane_node_children = KdlDocument::new();
     let (command, args) = extract_command_and_args(&layout.run);
+    let env = extract_env(&layout.run);
     let (plugin, plugin_config) = extract_plugin_and_config(&layout.run);
     let (edit, _line_number) = extract_edit_and_line_number(&layout.run);
     let cwd = layout.run.as_ref().and_then(|r| r.get_cwd());
@@ -671,6 +771,12 @@ fn serialize_floating_pane(
         has_children,
         &mut floating_pane_node,
     );
+    serialize_scrollback_file(
+        &command,
+        &edit,
+        &layout.pane_scrollback_file,
+        &mut floating_pane_node,
+    );
     if let Some(ref fg) = layout.default_fg {
         floating_pane_node
             .entries_mut()
@@ -681,9 +787,16 @@ fn serialize_floating_pane(
             .entries_mut()
             .push(KdlEntry::new_prop("default_bg", bg.to_owned()));
     }
+    if let Some(ref scratchpad) = layout.scratchpad {
+        floating_pane_node
+            .entries_mut()
+            .push(KdlEntry::new_prop("scratchpad", scratchpad.to_owned()));
+    }
     serialize_start_suspended(&command, &mut floating_pane_node_children);
     serialize_floating_layout_attributes(&layout, &mut floating_pane_node_children);
     serialize_args(args, &mut floating_pane_node_children);
+    serialize_env(env, &mut floating_pane_node_children);
+    serialize_restart_policy(&layout.run, &mut floating_pane_node_children);
     serialize_plugin(plugin, plugin_config, &mut floating_pane_node_children);
     floating_pane_node.set_children(floating_pane_node_children);
     floating_pane_node
@@ -738,6 +851,7 @@ fn tiled_pane_layout_from_manifest(
         name,
         focus,
         pane_initial_contents,
+        pane_scrollback_file,
         default_fg,
         default_bg,
     ) = manifest
@@ -757,11 +871,12 @@ fn tiled_pane_layout_from_manifest(
                 g.title.clone(),
                 Some(g.is_focused),
                 g.pane_contents.clone(),
+                g.scrollback_file.clone(),
                 g.default_fg.clone(),
                 g.default_bg.clone(),
             )
         })
-        .unwrap_or((None, None, false, None, None, None, None, None));
+        .unwrap_or((None, None, false, None, None, None, None, None, None));
     TiledPaneLayout {
         split_size,
         run,
@@ -770,6 +885,7 @@ fn tiled_pane_layout_from_manifest(
         name,
         focus,
         pane_initial_contents,
+        pane_scrollback_file,
         default_fg,
         default_bg,
         ..Default::default()
@@ -878,10 +994,12 @@ fn get_floating_panes_layout_from_panegeoms(
                 focus: Some(m.is_focused),
                 already_running: false,
                 pane_initial_contents: m.pane_contents.clone(),
+                pane_scrollback_file: m.scrollback_file.clone(),
                 logical_position: None,
                 borderless: Some(m.is_borderless),
                 default_fg: m.default_fg.clone(),
                 default_bg: m.default_bg.clone(),
+                scratchpad: m.scratchpad.clone(),
             }
         })
         .collect()
@@ -1714,6 +1832,183 @@ mod tests {
         assert_snapshot!(kdl.0);
     }
     #[test]
+    fn can_serialize_command_panes_with_env() {
+        use crate::input::command::RunCommand;
+        let mut env = BTreeMap::new();
+        env.insert("RUST_LOG".to_owned(), "debug".to_owned());
+        env.insert("GREETING".to_owned(), "hello \"world\"".to_owned());
+        let run = Some(Run::Command(RunCommand {
+            command: PathBuf::from("cargo"),
+            args: vec!["run".to_owned()],
+            env,
+            ..Default::default()
+        }));
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes: vec![
+                PaneLayoutManifest {
+                    run: run.clone(),
+                    geom: PaneGeom {
+                        x: 0,
+                        y: 0,
+                        rows: Dimension::fixed(10),
+                        cols: Dimension::fixed(10),
+                        stacked: None,
+                        is_pinned: false,
+                        logical_position: None,
+                    },
+                    ..Default::default()
+                },
+                PaneLayoutManifest {
+                    geom: PaneGeom {
+                        x: 0,
+                        y: 10,
+                        rows: Dimension::fixed(10),
+                        cols: Dimension::fixed(10),
+                        stacked: None,
+                        is_pinned: false,
+                        logical_position: None,
+                    },
+                    ..Default::default()
+                },
+            ],
+            floating_panes: vec![PaneLayoutManifest {
+                run: run.clone(),
+                geom: PaneGeom {
+                    x: 0,
+                    y: 0,
+                    rows: Dimension::fixed(10),
+                    cols: Dimension::fixed(10),
+                    stacked: None,
+                    is_pinned: false,
+                    logical_position: None,
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        assert_snapshot!(kdl.0);
+        let layout = Layout::from_kdl(&kdl.0, Some("layout.kdl".into()), None, None).unwrap();
+        let (_tab_name, tiled_panes, floating_panes) = &layout.tabs[0];
+        let deserialized_envs: Vec<BTreeMap<String, String>> = tiled_panes
+            .children
+            .iter()
+            .map(|p| &p.run)
+            .chain(floating_panes.iter().map(|p| &p.run))
+            .map(extract_env)
+            .collect();
+        assert_eq!(
+            deserialized_envs,
+            vec![extract_env(&run), BTreeMap::new(), extract_env(&run)]
+        );
+    }
+    #[test]
+    fn can_serialize_command_panes_with_restart_policy() {
+        use crate::input::command::RunCommand;
+        let run = Some(Run::Command(RunCommand {
+            command: PathBuf::from("my-server"),
+            restart_policy: RestartPolicy::OnFailure,
+            max_restarts: Some(5),
+            ..Default::default()
+        }));
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes: vec![
+                PaneLayoutManifest {
+                    run: run.clone(),
+                    geom: PaneGeom {
+                        x: 0,
+                        y: 0,
+                        rows: Dimension::fixed(10),
+                        cols: Dimension::fixed(10),
+                        stacked: None,
+                        is_pinned: false,
+                        logical_position: None,
+                    },
+                    ..Default::default()
+                },
+                PaneLayoutManifest {
+                    geom: PaneGeom {
+                        x: 0,
+                        y: 10,
+                        rows: Dimension::fixed(10),
+                        cols: Dimension::fixed(10),
+                        stacked: None,
+                        is_pinned: false,
+                        logical_position: None,
+                    },
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        assert_snapshot!(kdl.0);
+        let layout = Layout::from_kdl(&kdl.0, Some("layout.kdl".into()), None, None).unwrap();
+        let (_tab_name, tiled_panes, _floating_panes) = &layout.tabs[0];
+        match &tiled_panes.children[0].run {
+            Some(Run::Command(run_command)) => {
+                assert_eq!(run_command.restart_policy, RestartPolicy::OnFailure);
+                assert_eq!(run_command.max_restarts, Some(5));
+            },
+            _ => panic!("Expected a command pane"),
+        }
+    }
+    #[test]
+    fn can_serialize_scratchpads() {
+        use crate::input::command::RunCommand;
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes: vec![PaneLayoutManifest {
+                geom: PaneGeom {
+                    x: 0,
+                    y: 0,
+                    rows: Dimension::fixed(20),
+                    cols: Dimension::fixed(20),
+                    stacked: None,
+                    is_pinned: false,
+                    logical_position: None,
+                },
+                ..Default::default()
+            }],
+            floating_panes: vec![PaneLayoutManifest {
+                run: Some(Run::Command(RunCommand {
+                    command: PathBuf::from("nvim"),
+                    args: vec!["notes.md".to_owned()],
+                    ..Default::default()
+                })),
+                scratchpad: Some("notes".to_owned()),
+                geom: PaneGeom {
+                    x: 2,
+                    y: 2,
+                    rows: Dimension::fixed(10),
+                    cols: Dimension::fixed(16),
+                    stacked: None,
+                    is_pinned: false,
+                    logical_position: None,
+                },
+                ..Default::default()
+            }],
+            hide_floating_panes: true,
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        assert_snapshot!(kdl.0);
+        let layout = Layout::from_kdl(&kdl.0, Some("layout.kdl".into()), None, None).unwrap();
+        let (_tab_name, _tiled_panes, floating_panes) = &layout.tabs[0];
+        assert_eq!(floating_panes[0].scratchpad, Some("notes".to_owned()));
+    }
+    #[test]
     fn can_serialize_tab_with_stacked_panes() {
         let tab_layout_manifest = TabLayoutManifest {
             tiled_panes: vec![
@@ -2153,6 +2448,32 @@ mod tests {
         assert_snapshot!(kdl.0);
     }
     #[test]
+    fn can_serialize_swap_tiled_panes_with_size_constraints() {
+        let tiled_panes_layout = TiledPaneLayout {
+            children: vec![TiledPaneLayout::default(), TiledPaneLayout::default()],
+            ..Default::default()
+        };
+        let mut default_layout = Layout::default();
+        let mut swap_tiled_layout = BTreeMap::new();
+        swap_tiled_layout.insert(
+            LayoutConstraint::Combined(vec![
+                LayoutConstraint::MaxPanes(2),
+                LayoutConstraint::MinColumns(200),
+                LayoutConstraint::MaxAspectRatio(350),
+            ]),
+            tiled_panes_layout.clone(),
+        );
+        swap_tiled_layout.insert(LayoutConstraint::MaxRows(40), tiled_panes_layout.clone());
+        default_layout.swap_tiled_layouts = vec![(swap_tiled_layout, None)];
+        let default_layout = Box::new(default_layout);
+        let global_layout_manifest = GlobalLayoutManifest {
+            default_layout,
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        assert_snapshot!(kdl.0);
+    }
+    #[test]
     fn can_serialize_swap_floating_panes() {
         let floating_panes_layout = vec![
             FloatingPaneLayout::default(),
@@ -2199,6 +2520,67 @@ mod tests {
         let kdl = serialize_session_layout(global_layout_manifest).unwrap();
         assert_snapshot!(kdl.0);
     }
+    #[test]
+    fn can_serialize_pane_scrollback_files() {
+        use crate::input::command::RunCommand;
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes: vec![
+                PaneLayoutManifest {
+                    pane_contents: Some("last line\nviewport".to_owned()),
+                    scrollback_file: Some(PathBuf::from("scrollback_0")),
+                    geom: PaneGeom {
+                        x: 0,
+                        y: 0,
+                        rows: Dimension::fixed(10),
+                        cols: Dimension::fixed(10),
+                        stacked: None,
+                        is_pinned: false,
+                        logical_position: None,
+                    },
+                    ..Default::default()
+                },
+                PaneLayoutManifest {
+                    // command panes are started anew, so their scrollback is not restored
+                    run: Some(Run::Command(RunCommand {
+                        command: PathBuf::from("htop"),
+                        ..Default::default()
+                    })),
+                    pane_contents: Some("htop output".to_owned()),
+                    scrollback_file: Some(PathBuf::from("scrollback_1")),
+                    geom: PaneGeom {
+                        x: 10,
+                        y: 0,
+                        rows: Dimension::fixed(10),
+                        cols: Dimension::fixed(10),
+                        stacked: None,
+                        is_pinned: false,
+                        logical_position: None,
+                    },
+                    ..Default::default()
+                },
+            ],
+            floating_panes: vec![PaneLayoutManifest {
+                scrollback_file: Some(PathBuf::from("scrollback_2")),
+                geom: PaneGeom {
+                    x: 0,
+                    y: 0,
+                    rows: Dimension::fixed(10),
+                    cols: Dimension::fixed(10),
+                    stacked: None,
+                    is_pinned: false,
+                    logical_position: None,
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab with scrollback".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        assert_snapshot!(kdl.0);
+    }
 
     // utility functions
     fn parse_panegeom_from_json(data_str: &str) -> PaneGeom {
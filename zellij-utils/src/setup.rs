@@ -4,7 +4,9 @@ use crate::input::theme::Themes;
 #[allow(unused_imports)]
 use crate::{
     cli::{CliArgs, Command, SessionCommand, Sessions},
-    consts::{FEATURES, VERSION, ZELLIJ_CACHE_DIR, ZELLIJ_DEFAULT_THEMES},
+    consts::{
+        FEATURES, PLUGIN_MODULE_CACHE_DIR_NAME, VERSION, ZELLIJ_CACHE_DIR, ZELLIJ_DEFAULT_THEMES,
+    },
     data::LayoutInfo,
     errors::prelude::*,
     home::*,
@@ -241,6 +243,31 @@ pub fn dump_builtin_plugins(_path: &PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// Removes the plugin module caches of all Zellij versions (each version keeps its own cache
+/// under its versioned folder in the cache dir)
+pub fn clear_plugin_module_cache() -> Result<()> {
+    let mut cleared = 0;
+    if let Ok(entries) = fs::read_dir(ZELLIJ_CACHE_DIR.as_path()) {
+        for entry in entries.flatten() {
+            let module_cache_dir = entry.path().join(PLUGIN_MODULE_CACHE_DIR_NAME);
+            if module_cache_dir.is_dir() {
+                fs::remove_dir_all(&module_cache_dir).with_context(|| {
+                    format!(
+                        "failed to remove plugin module cache '{}'",
+                        module_cache_dir.display()
+                    )
+                })?;
+                println!("Removed '{}'", module_cache_dir.display());
+                cleared += 1;
+            }
+        }
+    }
+    if cleared == 0 {
+        println!("Plugin module cache is already empty");
+    }
+    Ok(())
+}
+
 #[derive(Debug, Default, Clone, Args, Serialize, Deserialize)]
 pub struct Setup {
     /// Dump the default configuration file to stdout
@@ -276,6 +303,10 @@ pub struct Setup {
     )]
     pub dump_plugins: Option<Option<PathBuf>>,
 
+    /// Clear the on-disk cache of validated plugin modules
+    #[clap(long, value_parser)]
+    pub clear_plugin_cache: bool,
+
     /// Generates completion for the specified shell
     #[clap(long, value_name = "SHELL", value_parser)]
     pub generate_completion: Option<String>,
@@ -392,6 +423,11 @@ impl Setup {
             std::process::exit(0);
         }
 
+        if self.clear_plugin_cache {
+            clear_plugin_module_cache()?;
+            std::process::exit(0);
+        }
+
         Ok(())
     }
 
@@ -629,16 +665,23 @@ impl Setup {
             let layout_info = LayoutInfo::from_config(&layout_dir, &chosen_layout);
             (layout_info, chosen_layout)
         };
+        let layout_args = cli_args.layout_args();
         match layout_info {
             Some(LayoutInfo::Url(ref layout_url)) => {
-                Layout::from_url(layout_url, config).map(|(_layout, config)| (layout_info, config))
+                Layout::from_url(layout_url, config, &layout_args)
+                    .map(|(_layout, config)| (layout_info, config))
             },
             Some(LayoutInfo::Stringified(ref raw_layout)) => {
-                Layout::from_stringified_layout(raw_layout, config)
+                Layout::from_stringified_layout(raw_layout, config, &layout_args)
                     .map(|(_layout, config)| (layout_info, config))
             },
-            _ => Layout::from_path_or_default(chosen_layout.as_ref(), layout_dir.clone(), config)
-                .map(|(_layout, config)| (layout_info, config)),
+            _ => Layout::from_path_or_default(
+                chosen_layout.as_ref(),
+                layout_dir.clone(),
+                config,
+                &layout_args,
+            )
+            .map(|(_layout, config)| (layout_info, config)),
         }
     }
     fn handle_setup_commands(cli_args: &CliArgs) {
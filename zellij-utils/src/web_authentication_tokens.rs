@@ -3,7 +3,7 @@ use crate::consts::ZELLIJ_PROJ_DIR;
 use rusqlite::Connection;
 use sha2::{Digest, Sha256};
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 #[derive(Debug)]
@@ -11,6 +11,9 @@ pub struct TokenInfo {
     pub name: String,
     pub created_at: String,
     pub read_only: bool,
+    /// Session names or glob patterns this token may attach to, empty means any session
+    pub session_scope: Vec<String>,
+    pub expires_at: Option<String>,
 }
 
 #[derive(Debug)]
@@ -115,24 +118,35 @@ fn init_db(conn: &Connection) -> Result<()> {
         [],
     )?;
 
-    // Migration: Add read_only column if it doesn't exist
-    match conn.execute(
+    // Migrations: Add columns if they don't exist
+    add_column_if_missing(
+        conn,
         "ALTER TABLE tokens ADD COLUMN read_only BOOLEAN NOT NULL DEFAULT 0",
-        [],
-    ) {
-        Ok(_) => {},
+    )?;
+    // newline separated session names or glob patterns, NULL means any session
+    add_column_if_missing(conn, "ALTER TABLE tokens ADD COLUMN session_scope TEXT")?;
+    // NULL means the token never expires
+    add_column_if_missing(conn, "ALTER TABLE tokens ADD COLUMN expires_at DATETIME")?;
+
+    Ok(())
+}
+
+fn add_column_if_missing(conn: &Connection, alter_table_statement: &str) -> Result<()> {
+    match conn.execute(alter_table_statement, []) {
+        Ok(_) => Ok(()),
         Err(e) => {
             let err_msg = e.to_string();
             // "duplicate column name" is the expected error when the column already exists
             if !err_msg.contains("duplicate column name") {
                 return Err(TokenError::Database(e));
             }
+            Ok(())
         },
     }
-
-    Ok(())
 }
 
+const TOKEN_NOT_EXPIRED: &str = "(expires_at IS NULL OR expires_at > datetime('now'))";
+
 pub fn hash_token(token: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(token.as_bytes());
@@ -140,6 +154,17 @@ pub fn hash_token(token: &str) -> String {
 }
 
 pub fn create_token(name: Option<String>, read_only: bool) -> Result<(String, String)> {
+    create_scoped_token(name, read_only, vec![], None)
+}
+
+/// Create a token that can only attach to sessions matching one of `session_scope` (any session
+/// if it is empty) and that stops working once `expires_in` has passed (never if `None`)
+pub fn create_scoped_token(
+    name: Option<String>,
+    read_only: bool,
+    session_scope: Vec<String>,
+    expires_in: Option<Duration>,
+) -> Result<(String, String)> {
     let conn = open_db()?;
 
     let token = Uuid::new_v4().to_string();
@@ -152,9 +177,28 @@ pub fn create_token(name: Option<String>, read_only: bool) -> Result<(String, St
         format!("token_{}", count + 1)
     };
 
+    let session_scope = if session_scope.is_empty() {
+        None
+    } else {
+        Some(session_scope.join("\n"))
+    };
+    let expires_at = expires_in.map(|expires_in| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        now.saturating_add(expires_in.as_secs()) as i64
+    });
+
     match conn.execute(
-        "INSERT INTO tokens (token_hash, name, read_only) VALUES (?1, ?2, ?3)",
-        [&token_hash, &token_name, &(read_only as i64).to_string()],
+        "INSERT INTO tokens (token_hash, name, read_only, session_scope, expires_at) VALUES (?1, ?2, ?3, ?4, datetime(?5, 'unixepoch'))",
+        rusqlite::params![
+            &token_hash,
+            &token_name,
+            read_only as i64,
+            session_scope,
+            expires_at
+        ],
     ) {
         Err(rusqlite::Error::SqliteFailure(ffi_error, _))
             if ffi_error.code == rusqlite::ErrorCode::ConstraintViolation =>
@@ -174,7 +218,10 @@ pub fn create_session_token(auth_token: &str, remember_me: bool) -> Result<Strin
     let auth_token_hash = hash_token(auth_token);
 
     let count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM tokens WHERE token_hash = ?1",
+        &format!(
+            "SELECT COUNT(*) FROM tokens WHERE token_hash = ?1 AND {}",
+            TOKEN_NOT_EXPIRED
+        ),
         [&auth_token_hash],
         |row| row.get(0),
     )?;
@@ -218,8 +265,12 @@ pub fn validate_session_token(session_token: &str) -> Result<bool> {
 
     let session_token_hash = hash_token(session_token);
 
+    // the session token stops working when the token it was created with expires
     let count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM session_tokens WHERE session_token_hash = ?1 AND expires_at > datetime('now')",
+        "SELECT COUNT(*) FROM session_tokens st
+         JOIN tokens t ON st.auth_token_hash = t.token_hash
+         WHERE st.session_token_hash = ?1 AND st.expires_at > datetime('now')
+         AND (t.expires_at IS NULL OR t.expires_at > datetime('now'))",
         [&session_token_hash],
         |row| row.get(0),
     )?;
@@ -248,6 +299,67 @@ pub fn is_session_token_read_only(session_token: &str) -> Result<bool> {
     Ok(read_only != 0)
 }
 
+pub fn get_session_token_scope(session_token: &str) -> Result<Vec<String>> {
+    let conn = open_db()?;
+
+    let session_token_hash = hash_token(session_token);
+
+    let session_scope: Option<String> = match conn.query_row(
+        "SELECT t.session_scope FROM tokens t
+         JOIN session_tokens st ON st.auth_token_hash = t.token_hash
+         WHERE st.session_token_hash = ?1 AND st.expires_at > datetime('now')
+         AND (t.expires_at IS NULL OR t.expires_at > datetime('now'))",
+        [&session_token_hash],
+        |row| row.get(0),
+    ) {
+        Ok(val) => val,
+        Err(rusqlite::Error::QueryReturnedNoRows) => return Err(TokenError::InvalidToken),
+        Err(e) => return Err(TokenError::Database(e)),
+    };
+
+    Ok(parse_session_scope(session_scope))
+}
+
+/// Whether a token with this session scope (as returned by `get_session_token_scope`) may attach
+/// to `session_name`
+pub fn session_scope_allows(session_scope: &[String], session_name: &str) -> bool {
+    session_scope.is_empty()
+        || session_scope
+            .iter()
+            .any(|pattern| glob_matches(pattern, session_name))
+}
+
+fn parse_session_scope(session_scope: Option<String>) -> Vec<String> {
+    session_scope
+        .map(|s| s.lines().map(|l| l.to_owned()).collect())
+        .unwrap_or_default()
+}
+
+// supports * (any number of characters) and ? (exactly one character)
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // let the last * swallow one more character and try again
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
 pub fn cleanup_expired_sessions() -> Result<usize> {
     let conn = open_db()?;
 
@@ -350,13 +462,16 @@ pub fn rename_token(old_name: &str, new_name: &str) -> Result<()> {
 pub fn list_tokens() -> Result<Vec<TokenInfo>> {
     let conn = open_db()?;
 
-    let mut stmt =
-        conn.prepare("SELECT name, created_at, read_only FROM tokens ORDER BY created_at")?;
+    let mut stmt = conn.prepare(
+        "SELECT name, created_at, read_only, session_scope, expires_at FROM tokens ORDER BY created_at",
+    )?;
     let rows = stmt.query_map([], |row| {
         Ok(TokenInfo {
             name: row.get::<_, String>(0)?,
             created_at: row.get::<_, String>(1)?,
             read_only: row.get::<_, i64>(2)? != 0,
+            session_scope: parse_session_scope(row.get::<_, Option<String>>(3)?),
+            expires_at: row.get::<_, Option<String>>(4)?,
         })
     })?;
 
@@ -381,9 +496,44 @@ pub fn validate_token(token: &str) -> Result<bool> {
     let token_hash = hash_token(token);
 
     let count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM tokens WHERE token_hash = ?1",
+        &format!(
+            "SELECT COUNT(*) FROM tokens WHERE token_hash = ?1 AND {}",
+            TOKEN_NOT_EXPIRED
+        ),
         [&token_hash],
         |row| row.get(0),
     )?;
     Ok(count > 0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_session_scope_allows_any_session() {
+        assert!(session_scope_allows(&[], "any-session"));
+    }
+
+    #[test]
+    fn session_scope_matches_names_and_globs() {
+        let session_scope = vec!["proj-*".to_owned(), "exact".to_owned(), "t?st".to_owned()];
+        assert!(session_scope_allows(&session_scope, "proj-"));
+        assert!(session_scope_allows(&session_scope, "proj-frontend"));
+        assert!(session_scope_allows(&session_scope, "exact"));
+        assert!(session_scope_allows(&session_scope, "test"));
+        assert!(!session_scope_allows(&session_scope, "my-proj-frontend"));
+        assert!(!session_scope_allows(&session_scope, "exactly"));
+        assert!(!session_scope_allows(&session_scope, "toast"));
+        assert!(!session_scope_allows(&session_scope, "other"));
+    }
+
+    #[test]
+    fn glob_backtracks_over_multiple_wildcards() {
+        assert!(glob_matches("*-api-*", "team-api-v2"));
+        assert!(glob_matches("a*b*c", "aXbYbZc"));
+        assert!(glob_matches("**", ""));
+        assert!(!glob_matches("a*b*c", "aXbYbZ"));
+        assert!(!glob_matches("?", ""));
+    }
+}